@@ -678,6 +678,7 @@ where
             ticket_price,
             win_probability,
             peer_discovery_tx,
+            Some(transport_api.relay_safes()),
         )
         .inspect(|_| {
             tracing::warn!(
@@ -11,7 +11,7 @@ use hopr_api::{
         primitive::prelude::{Address, UnitaryFloatOps},
     },
 };
-use hopr_transport::{NeighborTelemetry, PathTelemetry};
+use hopr_transport::{NeighborTelemetry, PathTelemetry, RelaySafes};
 use parking_lot::RwLock;
 use tracing::Instrument;
 
@@ -30,6 +30,7 @@ lazy_static::lazy_static! {
 /// `ChainEvent`s into [`NetworkGraphUpdate`] calls so the routing graph stays current.
 /// When `peer_discovery_tx` is `Some`, each [`ChainEvent::Announcement`] is also forwarded
 /// to the p2p network layer so it can initiate connections to newly discovered peers.
+/// When `relay_safes` is `Some`, the Safe of each announced account is recorded for the relay policy.
 /// Runs until the supplied `events` stream terminates.
 #[allow(clippy::too_many_arguments)]
 pub(super) async fn process_chain_events<C, G>(
@@ -41,6 +42,7 @@ pub(super) async fn process_chain_events<C, G>(
     ticket_price: Arc<RwLock<HoprBalance>>,
     win_probability: Arc<RwLock<WinningProbability>>,
     mut peer_discovery_tx: Option<hopr_utils::network_types::crossfire_sink::CrossfireSink<(PeerId, Vec<Multiaddr>)>>,
+    relay_safes: Option<RelaySafes>,
 ) where
     C: ChainKeyOperations + Clone + Send + Sync + 'static,
     G: NetworkGraphUpdate + Send + Sync + 'static,
@@ -64,6 +66,9 @@ pub(super) async fn process_chain_events<C, G>(
                     "recording graph node for announced account"
                 );
                 graph_updater.record_node(account.public_key);
+                if let Some(ref safes) = relay_safes {
+                    safes.update(account.public_key, account.safe_address);
+                }
                 if let Some(ref mut tx) = peer_discovery_tx {
                     let peer_id: PeerId = account.public_key.into();
                     let multiaddrs = account.get_multiaddrs();
@@ -373,6 +378,7 @@ mod tests {
             Arc::new(RwLock::new(ticket_price)),
             Arc::new(RwLock::new(win_probability)),
             Some(tx),
+            None,
         )
         .await;
         rx.collect().await
@@ -718,6 +724,7 @@ mod tests {
             Arc::new(RwLock::new(HoprBalance::from(10u64))),
             Arc::new(RwLock::new(WinningProbability::ALWAYS)),
             Some(tx),
+            None,
         )
         .await;
     }
@@ -66,16 +66,25 @@ use hopr_api::{
     tickets::TicketManagement,
     types::{crypto::prelude::OffchainKeypair, internal::routing::DestinationRouting},
 };
+pub use hopr_transport::RelayPolicy;
 /// Maximum user-data payload per HOPR session frame (bytes).
 ///
 /// Use this when sizing buffers or computing how many session frames a given
 /// wxHOPR balance can fund (together with the on-chain ticket price).
 pub use hopr_transport::SESSION_MTU;
 use hopr_transport::{ApplicationDataIn, ApplicationDataOut, HoprTransport, HoprTransportProcess, OffchainPublicKey};
+#[cfg(feature = "capture")]
+pub use hopr_transport::{
+    CaptureFilter, CaptureReadError, CaptureReader, CapturedPacket, CapturedPacketContent, DecodedPacket,
+    DecodedPayload, NullWriter, PacketDirection, PacketKind, PacketWriter, PcapPacketWriter, RotatingPcapWriter,
+    RotationPolicy,
+};
+#[cfg(feature = "session-server")]
+pub use hopr_transport::{EchoService, IncomingSession, ServiceId, SessionService};
 #[cfg(feature = "session-client")]
 pub use hopr_transport::{
-    FlowControlConfig, HoprSession, HoprSessionConfigurator, SessionCapabilities, SessionCapability, SessionTarget,
-    SurbBalancerConfig,
+    FlowControlConfig, HoprSession, HoprSessionConfigurator, MultipathConfig, PathStats, SessionCapabilities,
+    SessionCapability, SessionTarget, SurbBalancerConfig, SurbBalancerControllerKind,
 };
 use hopr_utils::runtime::prelude::spawn;
 pub use hopr_utils::runtime::{Abortable, AbortableList};
@@ -159,6 +168,11 @@ pub struct HoprSessionClientConfig {
     /// the tail-tolerance bundle. Only meaningful on a reliable (`RetransmissionAck`) session.
     #[default(None)]
     pub flow_control: Option<FlowControlConfig>,
+    /// Opt-in striping of the session over multiple independently selected paths
+    /// (`None` = single path, the default). The paths are selected according to the
+    /// `forward_path` and `return_path` routing.
+    #[default(None)]
+    pub multipath: Option<MultipathConfig>,
 }
 
 /// Session client configuration for explicit intermediate-path routing.
@@ -208,10 +222,13 @@ impl From<HoprSessionClientConfig> for hopr_transport::SessionClientConfig {
             forward_path_options: value.forward_path.into(),
             return_path_options: value.return_path.into(),
             capabilities: value.capabilities,
+            optional_capabilities: Default::default(),
             pseudonym: value.pseudonym,
             surb_management: value.surb_management,
             always_max_out_surbs: value.always_max_out_surbs,
             flow_control: value.flow_control,
+            multipath: value.multipath,
+            destination_key: None,
         }
     }
 }
@@ -230,10 +247,13 @@ impl TryFrom<HoprSessionClientExplicitPathConfig> for hopr_transport::SessionCli
             forward_path_options: forward,
             return_path_options: ret,
             capabilities: value.capabilities,
+            optional_capabilities: Default::default(),
             pseudonym: value.pseudonym,
             surb_management: value.surb_management,
             always_max_out_surbs: value.always_max_out_surbs,
             flow_control: value.flow_control,
+            multipath: None,
+            destination_key: None,
         })
     }
 }
@@ -343,6 +363,15 @@ impl<Chain, Graph, Net, TMgr> std::fmt::Debug for Hopr<Chain, Graph, Net, TMgr>
     }
 }
 
+impl<Chain, Graph, Net, TMgr> Drop for Hopr<Chain, Graph, Net, TMgr> {
+    fn drop(&mut self) {
+        // Persist the packet replay filter, so that it is restored when the node starts again
+        if let Err(error) = self.transport_api.save_replay_filter() {
+            tracing::error!(%error, "failed to save the packet replay filter");
+        }
+    }
+}
+
 impl<Chain, Graph, Net, TMgr> Hopr<Chain, Graph, Net, TMgr>
 where
     Chain: HoprChainApi + Clone + Send + Sync + 'static,
@@ -361,6 +390,53 @@ where
         self.transport_api.graph()
     }
 
+    /// Returns the relay policy currently applied to path selection.
+    pub fn relay_policy(&self) -> RelayPolicy {
+        self.transport_api.relay_policy()
+    }
+
+    /// Replaces the relay policy applied to path selection.
+    ///
+    /// See [`HoprTransport::set_relay_policy`].
+    pub async fn set_relay_policy(&self, policy: RelayPolicy) -> errors::Result<()> {
+        Ok(self.transport_api.set_relay_policy(policy).await?)
+    }
+
+    /// Registers the `service` serving incoming Sessions targeting the Exit service with the given `id`.
+    ///
+    /// Such Sessions are not passed to the session server of the node, and Sessions targeting
+    /// services that are not registered are rejected.
+    #[cfg(feature = "session-server")]
+    pub fn register_service(&self, id: ServiceId, service: impl SessionService + 'static) -> errors::Result<()> {
+        Ok(self.transport_api.register_service(id, service)?)
+    }
+
+    /// Unregisters the Exit service with the given `id`, returning `true` if it was registered.
+    #[cfg(feature = "session-server")]
+    pub fn unregister_service(&self, id: ServiceId) -> bool {
+        self.transport_api.unregister_service(id)
+    }
+
+    /// Starts capturing packets passing the `filter` into the `writer`.
+    ///
+    /// See [`HoprTransport::start_packet_capture`].
+    #[cfg(feature = "capture")]
+    pub fn start_packet_capture(&self, writer: impl PacketWriter + Send + 'static, filter: CaptureFilter) {
+        self.transport_api.start_packet_capture(writer, filter)
+    }
+
+    /// Stops the running packet capture, returning `false` if no capture was running.
+    #[cfg(feature = "capture")]
+    pub fn stop_packet_capture(&self) -> bool {
+        self.transport_api.stop_packet_capture()
+    }
+
+    /// Returns the filter of the running packet capture, or `None` if no capture is running.
+    #[cfg(feature = "capture")]
+    pub fn packet_capture_filter(&self) -> Option<CaptureFilter> {
+        self.transport_api.packet_capture_filter()
+    }
+
     #[cfg(feature = "session-client")]
     fn error_if_not_in_state(&self, state: HoprState, error: String) -> errors::Result<()> {
         if HoprNodeOperations::status(self) == state {
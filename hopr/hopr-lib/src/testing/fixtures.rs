@@ -203,6 +203,7 @@ impl ClusterGuard {
                     surb_management,
                     always_max_out_surbs: false,
                     flow_control: None,
+                    multipath: None,
                 },
             )
             .timeout(futures_time::time::Duration::from(timeout))
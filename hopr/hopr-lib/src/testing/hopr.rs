@@ -69,7 +69,13 @@ pub fn create_hopr_instance_config(
             transit_latency,
             stream: Default::default(),
             path_planner: Default::default(),
+            replay_filter_state: None,
+            replay_filter_save_interval: Default::default(),
             counter_flush_interval: Default::default(),
+            #[cfg(feature = "surb-store-redb")]
+            surb_store_db: None,
+            #[cfg(feature = "surb-store-redb")]
+            surb_store_db_durable_commit_interval: std::num::NonZeroU64::MIN,
         },
         publish: true,
         ..Default::default()
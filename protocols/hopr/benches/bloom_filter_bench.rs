@@ -2,13 +2,11 @@ use std::hint::black_box;
 
 use criterion::{Criterion, Throughput, criterion_group, criterion_main};
 use hopr_api::types::crypto::types::PACKET_TAG_LENGTH;
-
-#[path = "../src/tbf.rs"]
-mod tbf;
+use hopr_protocol_hopr::TagBloomFilter;
 
 fn tag_bloom_filter_bench(c: &mut Criterion) {
     // Fill up the Bloom filter first
-    let mut bloom = tbf::TagBloomFilter::default();
+    let mut bloom = TagBloomFilter::default();
     for _ in 1..bloom.capacity() {
         let mut tag = hopr_api::types::crypto_random::random_bytes();
         tag[0] = 0xaa;
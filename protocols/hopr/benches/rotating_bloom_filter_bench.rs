@@ -0,0 +1,64 @@
+use std::hint::black_box;
+
+use criterion::{BatchSize, Criterion, Throughput, criterion_group, criterion_main};
+use hopr_api::types::crypto::types::PACKET_TAG_LENGTH;
+use hopr_protocol_hopr::RotatingTagBloomFilter;
+
+fn rotating_tag_bloom_filter_bench(c: &mut Criterion) {
+    // Fill up the filter first, so that all generations are present
+    let mut bloom = RotatingTagBloomFilter::default();
+    for _ in 1..bloom.capacity() {
+        let mut tag = hopr_api::types::crypto_random::random_bytes();
+        tag[0] = 0xaa;
+        bloom.set(&tag);
+    }
+
+    let mut existing_tag = hopr_api::types::crypto_random::random_bytes();
+    existing_tag[0] = 0xaa;
+    bloom.set(&existing_tag);
+
+    let mut group = c.benchmark_group("rotating_tag_bloom_filter");
+    group.sample_size(100_000);
+    group.throughput(Throughput::Elements(1));
+    group.measurement_time(std::time::Duration::from_secs(10));
+
+    group.bench_function("check_existing", |b| {
+        b.iter(|| {
+            bloom.check(black_box(&existing_tag));
+        })
+    });
+
+    let non_existent_tag = [0u8; PACKET_TAG_LENGTH];
+    group.bench_function("check_non_existent", |b| {
+        b.iter(|| {
+            bloom.check(black_box(&non_existent_tag));
+        })
+    });
+
+    group.bench_function("check_and_set_rotating", |b| {
+        b.iter_batched(
+            || {
+                let mut tag = hopr_api::types::crypto_random::random_bytes();
+                tag[0] = 0xbb;
+                tag
+            },
+            |tag| bloom.check_and_set(black_box(&tag)),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("rotating_tag_bloom_filter_state");
+    group.sample_size(10);
+
+    group.bench_function("save", |b| b.iter(|| black_box(bloom.to_bytes())));
+
+    let state = bloom.to_bytes();
+    group.throughput(Throughput::Bytes(state.len() as u64));
+    group.bench_function("load", |b| {
+        b.iter(|| RotatingTagBloomFilter::from_bytes(black_box(&state)))
+    });
+}
+
+criterion_group!(benches, rotating_tag_bloom_filter_bench);
+criterion_main!(benches);
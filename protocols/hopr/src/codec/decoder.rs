@@ -10,7 +10,8 @@ use hopr_utils::trace_timed;
 
 use crate::{
     AuxiliaryPacketInfo, HoprCodecConfig, IncomingAcknowledgementPacket, IncomingFinalPacket, IncomingForwardedPacket,
-    IncomingPacket, IncomingPacketError, PacketDecoder, SurbStore, errors::HoprProtocolError, tbf::TagBloomFilter,
+    IncomingPacket, IncomingPacketError, PacketDecoder, SurbStore, errors::HoprProtocolError,
+    tbf::RotatingTagBloomFilter,
 };
 
 /// Default [decoder](PacketDecoder) implementation for HOPR packets.
@@ -22,7 +23,7 @@ pub struct HoprDecoder<Chain, S, T> {
     chain_key: ChainKeypair,
     channels_dst: Hash,
     cfg: HoprCodecConfig,
-    tbf: parking_lot::Mutex<TagBloomFilter>,
+    tbf: std::sync::Arc<parking_lot::Mutex<RotatingTagBloomFilter>>,
     peer_id_cache: moka::sync::Cache<PeerId, OffchainPublicKey>,
 }
 
@@ -32,14 +33,37 @@ where
     S: SurbStore + Send + Sync,
     T: hopr_api::tickets::TicketFactory + Send + Sync,
 {
-    /// Creates a new instance of the decoder.
+    /// Creates a new instance of the decoder with an empty packet replay filter.
     pub fn new(
+        keys: (OffchainKeypair, ChainKeypair),
+        chain_api: Chain,
+        surb_store: S,
+        ticket_factory: T,
+        channels_dst: Hash,
+        cfg: HoprCodecConfig,
+    ) -> Self {
+        Self::new_with_replay_filter(
+            keys,
+            chain_api,
+            surb_store,
+            ticket_factory,
+            channels_dst,
+            cfg,
+            Default::default(),
+        )
+    }
+
+    /// Creates a new instance of the decoder using the given packet replay filter.
+    ///
+    /// The filter can be shared with its owner, e.g., to persist its state while the decoder is running.
+    pub fn new_with_replay_filter(
         (packet_key, chain_key): (OffchainKeypair, ChainKeypair),
         chain_api: Chain,
         surb_store: S,
         ticket_factory: T,
         channels_dst: Hash,
         cfg: HoprCodecConfig,
+        replay_filter: std::sync::Arc<parking_lot::Mutex<RotatingTagBloomFilter>>,
     ) -> Self {
         Self {
             chain_api,
@@ -49,7 +73,7 @@ where
             channels_dst,
             cfg,
             ticket_factory,
-            tbf: parking_lot::Mutex::new(Default::default()),
+            tbf: replay_filter,
             peer_id_cache: moka::sync::Cache::builder()
                 .time_to_idle(Duration::from_secs(600))
                 .max_capacity(100_000)
@@ -218,9 +242,20 @@ where
         // This is checked on both Final and Forwarded packets,
         // Outgoing packets are not allowed to pass and are later reported as invalid state.
         if let Some(tag) = packet.packet_tag() {
-            // This operation has run-time of ~10 nanoseconds,
-            // and therefore does not need to be invoked via spawn_blocking
-            if self.tbf.lock().check_and_set(tag) {
+            // Checking the tag takes only a few hash lookups per generation of the filter,
+            // so it is cheap enough to be done inline while holding the lock
+            let (is_replay, spare_allocator) = {
+                let mut tbf = self.tbf.lock();
+                (tbf.check_and_set(tag), tbf.request_spare())
+            };
+
+            // Allocating the next generation of the filter is expensive, so it must not hold the lock
+            if let Some(allocator) = spare_allocator {
+                let spare = allocator.allocate();
+                self.tbf.lock().install_spare(spare);
+            }
+
+            if is_replay {
                 return Err(IncomingPacketError::ProcessingError(
                     previous_hop.into(),
                     HoprProtocolError::Replay,
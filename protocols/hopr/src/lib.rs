@@ -10,8 +10,10 @@ pub(crate) mod utils;
 
 pub use codec::{HoprCodecConfig, HoprDecoder, HoprEncoder, MAX_ACKNOWLEDGEMENTS_BATCH_SIZE};
 pub use errors::*;
-pub use surb_store::{MemorySurbStore, SurbStoreConfig};
-pub use tbf::TagBloomFilter;
+pub use surb_store::{MemorySurbStore, SurbConsumptionPolicy, SurbStoreConfig};
+#[cfg(feature = "redb")]
+pub use surb_store::{RedbSurbStore, RedbSurbStoreError};
+pub use tbf::{RotatingTagBloomFilter, SpareAllocator, SpareGeneration, TagBloomFilter};
 pub use ticket_processing::{HoprUnacknowledgedTicketProcessor, HoprUnacknowledgedTicketProcessorConfig};
 pub use traits::*;
 pub use types::*;
@@ -0,0 +1,1064 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use hopr_api::types::internal::{prelude::HoprPseudonym, routing::SurbMatcher};
+use hopr_crypto_packet::prelude::*;
+use moka::notification::RemovalCause;
+use validator::ValidationError;
+
+use crate::{FoundSurb, traits::SurbStore};
+
+#[cfg(feature = "redb")]
+mod redb;
+
+#[cfg(feature = "redb")]
+pub use self::redb::{RedbSurbStore, RedbSurbStoreError};
+
+const MINIMUM_SURB_LIFETIME: Duration = Duration::from_secs(30);
+const MINIMUM_OPENER_PSEUDONYMS: usize = 1000;
+const MINIMUM_OPENERS_PER_PSEUDONYM: usize = 1000;
+const MINIMUM_SURBS_PER_PSEUDONYM: usize = 1000;
+const MINIMUM_OPENER_LIFETIME: Duration = Duration::from_secs(60);
+const MIN_SURB_RB_CAPACITY: usize = 1024;
+
+fn validate_pseudonyms_lifetime(lifetime: &Duration) -> Result<(), ValidationError> {
+    if lifetime < &MINIMUM_SURB_LIFETIME {
+        Err(ValidationError::new("pseudonyms_lifetime is too low"))
+    } else {
+        Ok(())
+    }
+}
+
+fn validate_reply_opener_lifetime(lifetime: &Duration) -> Result<(), ValidationError> {
+    if lifetime < &MINIMUM_OPENER_LIFETIME {
+        Err(ValidationError::new("reply_opener_lifetime is too low"))
+    } else {
+        Ok(())
+    }
+}
+
+fn validate_surb_store_config(cfg: &SurbStoreConfig) -> Result<(), ValidationError> {
+    if cfg.reserved_surbs_per_pseudonym > cfg.rb_capacity {
+        return Err(ValidationError::new(
+            "reserved_surbs_per_pseudonym must not be greater than rb_capacity",
+        ));
+    }
+
+    if let SurbConsumptionPolicy::ExpiryAware { margin } = cfg.consumption_policy
+        && margin >= cfg.reply_opener_lifetime
+    {
+        return Err(ValidationError::new(
+            "expiry-aware consumption policy margin must be less than reply_opener_lifetime",
+        ));
+    }
+
+    Ok(())
+}
+
+fn default_rb_capacity() -> usize {
+    15_000
+}
+
+fn default_distress_threshold() -> usize {
+    500
+}
+
+fn default_max_openers_per_pseudonym() -> usize {
+    100_000
+}
+
+fn default_max_pseudonyms() -> usize {
+    10_000
+}
+
+fn default_pseudonyms_lifetime() -> Duration {
+    Duration::from_secs(600)
+}
+
+fn default_reply_opener_lifetime() -> Duration {
+    Duration::from_secs(3600)
+}
+
+fn default_expiry_margin() -> Duration {
+    Duration::from_secs(60)
+}
+
+/// Determines which SURB is used next when the replying side sends a reply.
+///
+/// The policy affects how quickly the number of remaining SURBs of a pseudonym drops
+/// and therefore also when the SURB distress is signalled to the sending side.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum SurbConsumptionPolicy {
+    /// The oldest SURB is used first.
+    #[default]
+    Fifo,
+    /// The freshest SURB is used first.
+    ///
+    /// The freshest SURBs are the least likely to be unusable because the sending side has already
+    /// dropped their reply openers. On the other hand, the old SURBs are used only when
+    /// the newer ones are exhausted or pushed out of the ring buffer.
+    Lifo,
+    /// The oldest SURB is used first, but SURBs whose age is within `margin` of the
+    /// `reply_opener_lifetime` are dropped instead of being used.
+    ///
+    /// Such SURBs would very likely produce replies that cannot be decrypted by the sending side
+    /// anymore. Dropping them makes the number of remaining SURBs reflect only the usable ones,
+    /// so the SURB distress is signalled earlier.
+    ExpiryAware {
+        /// How long before the end of its lifetime a SURB is considered expired.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default = "default_expiry_margin", with = "humantime_serde")
+        )]
+        margin: Duration,
+    },
+}
+
+impl SurbConsumptionPolicy {
+    /// Creates the [`SurbConsumptionPolicy::ExpiryAware`] policy with the default margin of 60 seconds.
+    pub fn expiry_aware() -> Self {
+        Self::ExpiryAware {
+            margin: default_expiry_margin(),
+        }
+    }
+}
+
+/// Configuration for the SURB cache.
+///
+/// The configuration options affect both the sending side (SURB creator) and the
+/// replying side (SURB consumer).
+///
+/// In the classical scenario (`Entry - Relay 1 -... - Exit`), the sending side is
+/// the `Entry` and the replying side is the `Exit`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, smart_default::SmartDefault, validator::Validate)]
+#[validate(schema(function = "validate_surb_store_config"))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(deny_unknown_fields)
+)]
+pub struct SurbStoreConfig {
+    /// Size of the SURB ring buffer per pseudonym.
+    ///
+    /// Affects only the replying side.
+    ///
+    /// This indicates how many SURBs can be at most held to be used to send a reply
+    /// back to the sending side.
+    ///
+    /// Default is 15 000.
+    #[default(default_rb_capacity())]
+    #[validate(range(min = 1024, message = "rb_capacity must be at least 1024"))]
+    #[cfg_attr(feature = "serde", serde(default = "default_rb_capacity"))]
+    pub rb_capacity: usize,
+    /// Threshold for the number of SURBs in the ring buffer, below which it is
+    /// considered low ("SURB distress").
+    ///
+    /// Default is 500.
+    #[default(default_distress_threshold())]
+    #[validate(range(min = 10, message = "distress_threshold must be at least 10"))]
+    #[cfg_attr(feature = "serde", serde(default = "default_distress_threshold"))]
+    pub distress_threshold: usize,
+    /// Maximum number of reply openers (SURB counterparts) per pseudonym.
+    ///
+    /// Affects only the sending side when decrypting a received reply.
+    ///
+    /// This mostly affects Sessions, as they use a fixed pseudonym.
+    /// It reflects how many reply openers the initiator-side of a Session can hold,
+    /// until the oldest ones are dropped. If the other party uses a SURB corresponding
+    /// to a dropped reply opener, the reply message will be undecryptable by the initiator-side.
+    ///
+    /// Default is 100 000.
+    #[default(default_max_openers_per_pseudonym())]
+    #[validate(range(min = 100, message = "max_openers_per_pseudonym must be at least 100"))]
+    #[cfg_attr(feature = "serde", serde(default = "default_max_openers_per_pseudonym"))]
+    pub max_openers_per_pseudonym: usize,
+    /// The maximum number of distinct pseudonyms for which we hold a SURB ringbuffer.
+    ///
+    /// Affects only the replying side.
+    ///
+    /// For each pseudonym, there is a ring-buffer with capacity `rb_capacity`.
+    ///
+    /// Default is 10 000.
+    #[default(default_max_pseudonyms())]
+    #[validate(range(min = 100, message = "max_pseudonyms must be at least 100"))]
+    #[cfg_attr(feature = "serde", serde(default = "default_max_pseudonyms"))]
+    pub max_pseudonyms: usize,
+    /// Maximum lifetime of ring-buffer for each pseudonym.
+    ///
+    /// # Effects on sending side
+    /// This is the period for which we hold all reply openers for a pseudonym.
+    /// If no more messages carrying SURBs are sent during this period, the entire stash of
+    /// reply openers is dropped. Preventing receiving any more replies for that pseudonym.
+    ///
+    /// # Effects on replying side
+    /// If a pseudonym has not received any SURBs for this period,
+    /// the entire ring buffer with `rb_capacity` (= all SURBs for this pseudonym) is dropped.
+    /// Preventing from sending any more replies for that pseudonym.
+    ///
+    /// Default is 600 seconds.
+    #[default(default_pseudonyms_lifetime())]
+    #[validate(custom(function = "validate_pseudonyms_lifetime"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default = "default_pseudonyms_lifetime", with = "humantime_serde")
+    )]
+    pub pseudonyms_lifetime: Duration,
+    /// Maximum lifetime of a reply opener.
+    ///
+    /// Affects only the sending side.
+    ///
+    /// A reply opener is distinguished using [`HoprSurbId`] and a pseudonym it belongs to.
+    /// If a reply opener is not used to decrypt the received packet within this period,
+    /// it is dropped. If the replying side uses the corresponding SURB to send a reply,
+    /// it won't be possible to decrypt it when received.
+    ///
+    /// Default is 3600 seconds.
+    #[default(default_reply_opener_lifetime())]
+    #[validate(custom(function = "validate_reply_opener_lifetime"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default = "default_reply_opener_lifetime", with = "humantime_serde")
+    )]
+    pub reply_opener_lifetime: Duration,
+    /// Policy determining which SURB of a pseudonym is used next.
+    ///
+    /// Affects only the replying side.
+    ///
+    /// Default is [`SurbConsumptionPolicy::Fifo`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub consumption_policy: SurbConsumptionPolicy,
+    /// Maximum total number of SURBs held across all pseudonyms.
+    ///
+    /// Affects only the replying side.
+    ///
+    /// Once the limit is reached, new SURBs of a pseudonym only replace its own oldest SURBs,
+    /// unless the pseudonym holds fewer than `reserved_surbs_per_pseudonym` SURBs. This prevents
+    /// a single heavy pseudonym from starving the others.
+    ///
+    /// Because the reserved quotas are always honored, and every pseudonym can always hold
+    /// at least its newest SURB, the actual total can exceed this limit by at most
+    /// `max(reserved_surbs_per_pseudonym, 1)` SURBs per pseudonym.
+    ///
+    /// Default is `None` (only `rb_capacity` per pseudonym applies).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub total_surb_capacity: Option<usize>,
+    /// Number of SURBs each pseudonym can always hold, regardless of the `total_surb_capacity`.
+    ///
+    /// Must not be greater than `rb_capacity`.
+    ///
+    /// Default is 0.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub reserved_surbs_per_pseudonym: usize,
+}
+
+impl SurbStoreConfig {
+    /// Returns the signal for the sending side when the replying side has `remaining` SURBs left
+    /// after using one.
+    ///
+    /// Returns `None` if the number of remaining SURBs is above the `distress_threshold`.
+    pub fn surb_signal(&self, remaining: usize) -> Option<PacketSignal> {
+        match remaining {
+            0 => Some(PacketSignal::OutOfSurbs),
+            remaining if remaining < self.distress_threshold.max(2) => Some(PacketSignal::SurbDistress),
+            _ => None,
+        }
+    }
+}
+
+/// Entry of the [`MemorySurbStore`] that was dropped without being explicitly consumed or discarded,
+/// due to its lifetime or the store capacity.
+#[cfg_attr(not(feature = "redb"), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SurbStoreEviction {
+    /// All SURBs of the pseudonym.
+    Surbs(HoprPseudonym),
+    /// All reply openers of the pseudonym.
+    ReplyOpeners(HoprPseudonym),
+    /// A single reply opener.
+    ReplyOpener(HoprSenderId),
+}
+
+type EvictionListener = Arc<dyn Fn(SurbStoreEviction) + Send + Sync>;
+
+/// Expires each reply opener once its remaining lifetime (stored along with it) has elapsed.
+struct ReplyOpenerExpiry;
+
+impl moka::Expiry<HoprSurbId, (ReplyOpener, Duration)> for ReplyOpenerExpiry {
+    fn expire_after_create(&self, _: &HoprSurbId, value: &(ReplyOpener, Duration), _: Instant) -> Option<Duration> {
+        Some(value.1)
+    }
+
+    fn expire_after_update(
+        &self,
+        _: &HoprSurbId,
+        value: &(ReplyOpener, Duration),
+        _: Instant,
+        _: Option<Duration>,
+    ) -> Option<Duration> {
+        Some(value.1)
+    }
+}
+
+/// Basic [`SurbStore`] implementation based on an in-memory cache.
+///
+/// This SURB store offers no persistence, and all SURBs and Reply Openers are lost once dropped.
+///
+/// The instance can be cheaply cloned.
+#[derive(Clone)]
+pub struct MemorySurbStore {
+    pseudonym_openers: moka::sync::Cache<HoprPseudonym, moka::sync::Cache<HoprSurbId, (ReplyOpener, Duration)>>,
+    surbs_per_pseudonym: moka::sync::Cache<HoprPseudonym, SurbRingBuffer<HoprSurb>>,
+    total_surbs: Arc<AtomicUsize>,
+    cfg: Arc<SurbStoreConfig>,
+    on_eviction: EvictionListener,
+}
+
+impl MemorySurbStore {
+    /// Creates a new instance with the given configuration.
+    pub fn new(cfg: SurbStoreConfig) -> Self {
+        Self::new_with_eviction_listener(cfg, |_| {})
+    }
+
+    /// Same as [`MemorySurbStore::new`], but `on_eviction` is called for every entry the store drops
+    /// on its own, i.e. not via [`SurbStore::find_surb`], [`SurbStore::find_reply_opener`] or
+    /// [`SurbStore::discard_surbs`].
+    pub(crate) fn new_with_eviction_listener<F>(cfg: SurbStoreConfig, on_eviction: F) -> Self
+    where
+        F: Fn(SurbStoreEviction) + Send + Sync + 'static,
+    {
+        let on_eviction: EvictionListener = Arc::new(on_eviction);
+        let on_openers_eviction = on_eviction.clone();
+        let on_surbs_eviction = on_eviction.clone();
+        Self {
+            // Reply openers are indexed by entire Sender IDs (Pseudonym + SURB ID)
+            // in a cascade fashion, allowing the entire batches (by Pseudonym) to be evicted
+            // if not used.
+            pseudonym_openers: moka::sync::Cache::builder()
+                .time_to_idle(cfg.pseudonyms_lifetime.max(MINIMUM_SURB_LIFETIME))
+                .eviction_policy(moka::policy::EvictionPolicy::lru())
+                .eviction_listener(move |pseudonym: Arc<HoprPseudonym>, _reply_opener, cause| {
+                    tracing::warn!(%pseudonym, ?cause, "evicting reply opener for pseudonym");
+                    if cause.was_evicted() {
+                        on_openers_eviction(SurbStoreEviction::ReplyOpeners(*pseudonym));
+                    }
+                })
+                .max_capacity(cfg.max_openers_per_pseudonym.max(MINIMUM_OPENER_PSEUDONYMS) as u64)
+                .build(),
+            // SURBs are indexed only by Pseudonyms, which have longer lifetimes.
+            // For each Pseudonym, there's an RB of SURBs and their IDs.
+            surbs_per_pseudonym: moka::sync::Cache::builder()
+                .time_to_idle(cfg.pseudonyms_lifetime.max(MINIMUM_SURB_LIFETIME))
+                .eviction_policy(moka::policy::EvictionPolicy::lru())
+                .eviction_listener(
+                    move |pseudonym: Arc<HoprPseudonym>, surbs: SurbRingBuffer<HoprSurb>, cause| {
+                        tracing::warn!(%pseudonym, ?cause, "evicting surb for pseudonym");
+                        surbs.evict();
+                        if cause.was_evicted() {
+                            on_surbs_eviction(SurbStoreEviction::Surbs(*pseudonym));
+                        }
+                    },
+                )
+                .max_capacity(cfg.max_pseudonyms.max(MINIMUM_SURBS_PER_PSEUDONYM) as u64)
+                .build(),
+            total_surbs: Arc::new(AtomicUsize::new(0)),
+            cfg: cfg.into(),
+            on_eviction,
+        }
+    }
+
+    /// Inserts a reply opener that was originally inserted at the given time.
+    ///
+    /// The opener expires once the rest of its lifetime elapses. If nothing is left, it is not inserted at all.
+    pub(crate) fn insert_reply_opener_at(&self, sender_id: HoprSenderId, opener: ReplyOpener, inserted_at: Instant) {
+        let opener_lifetime = self.cfg.reply_opener_lifetime.max(MINIMUM_OPENER_LIFETIME);
+        let remaining_lifetime = opener_lifetime.saturating_sub(inserted_at.elapsed());
+        if remaining_lifetime.is_zero() {
+            tracing::debug!(?sender_id, "not inserting expired reply opener");
+            return;
+        }
+
+        let max_openers_per_pseudonym = self.cfg.max_openers_per_pseudonym.max(MINIMUM_OPENERS_PER_PSEUDONYM);
+        let on_eviction = self.on_eviction.clone();
+        self.pseudonym_openers
+            .get_with(sender_id.pseudonym(), move || {
+                moka::sync::Cache::builder()
+                    .expire_after(ReplyOpenerExpiry)
+                    .eviction_listener(move |id: Arc<HoprSurbId>, _, cause| {
+                        if cause != RemovalCause::Explicit {
+                            tracing::warn!(
+                                pseudonym = %sender_id.pseudonym(),
+                                surb_id = const_hex::encode(id.as_slice()),
+                                ?cause,
+                                "evicting reply opener for sender id"
+                            );
+                        }
+                        if cause.was_evicted() {
+                            on_eviction(SurbStoreEviction::ReplyOpener(HoprSenderId::from_pseudonym_and_id(
+                                &sender_id.pseudonym(),
+                                *id,
+                            )));
+                        }
+                    })
+                    .max_capacity(max_openers_per_pseudonym as u64)
+                    .build()
+            })
+            .insert(sender_id.surb_id(), (opener, remaining_lifetime));
+    }
+
+    /// Inserts SURBs together with the times they were originally inserted at.
+    ///
+    /// Returns the number of SURBs of the `pseudonym` after the insertion.
+    pub(crate) fn insert_surbs_at<I>(&self, pseudonym: HoprPseudonym, surbs: I) -> usize
+    where
+        I: IntoIterator<Item = (HoprSurbId, HoprSurb, Instant)>,
+    {
+        let rb = self
+            .surbs_per_pseudonym
+            .entry_by_ref(&pseudonym)
+            .or_insert_with(|| {
+                SurbRingBuffer::with_total_counter(
+                    self.cfg.rb_capacity.max(MIN_SURB_RB_CAPACITY),
+                    self.total_surbs.clone(),
+                )
+            })
+            .into_value();
+
+        // Beyond its reserved quota, the pseudonym can grow only into the free part of the total capacity
+        let max_len = self.cfg.total_surb_capacity.map_or(usize::MAX, |total_capacity| {
+            let free = total_capacity.saturating_sub(self.total_surbs.load(Ordering::Relaxed));
+            rb.len().saturating_add(free).max(self.cfg.reserved_surbs_per_pseudonym)
+        });
+
+        rb.push_bounded_at(surbs, max_len).1
+    }
+}
+
+impl Default for MemorySurbStore {
+    fn default() -> Self {
+        Self::new(SurbStoreConfig::default())
+    }
+}
+
+#[async_trait::async_trait]
+impl SurbStore for MemorySurbStore {
+    #[tracing::instrument(skip_all, level = "trace", fields(?matcher), ret)]
+    fn find_surb(&self, matcher: SurbMatcher) -> Option<FoundSurb> {
+        let pseudonym = matcher.pseudonym();
+        let surbs_for_pseudonym = self.surbs_per_pseudonym.get(&pseudonym)?;
+
+        // The exact match intentionally only checks the SURB selected by the consumption policy
+        // and does not search the entire RB.
+        // This is because the exact match use-case is suited only for situations
+        // when there is a single SURB in the RB.
+        let expected_id = match &matcher {
+            SurbMatcher::Pseudonym(_) => None,
+            SurbMatcher::Exact(id) => Some(id.surb_id()),
+        };
+
+        let (popped, num_dropped) = surbs_for_pseudonym.pop_one_by_policy(
+            self.cfg.consumption_policy,
+            self.cfg.reply_opener_lifetime.max(MINIMUM_OPENER_LIFETIME),
+            expected_id.as_ref(),
+        );
+
+        if num_dropped > 0 {
+            tracing::debug!(%pseudonym, num_dropped, "dropped surbs close to their expiration");
+        }
+
+        popped.map(|popped_surb| FoundSurb {
+            sender_id: HoprSenderId::from_pseudonym_and_id(&pseudonym, popped_surb.id),
+            surb: popped_surb.surb,
+            remaining: popped_surb.remaining,
+        })
+    }
+
+    #[tracing::instrument(skip_all, level = "trace", fields(%pseudonym, num_surbs = surbs.len()))]
+    fn insert_surbs(&self, pseudonym: HoprPseudonym, surbs: Vec<(HoprSurbId, HoprSurb)>) -> usize {
+        let now = Instant::now();
+        self.insert_surbs_at(pseudonym, surbs.into_iter().map(|(id, surb)| (id, surb, now)))
+    }
+
+    #[tracing::instrument(skip_all, level = "trace", fields(%pseudonym, keep_freshest), ret)]
+    fn discard_surbs(&self, pseudonym: &HoprPseudonym, keep_freshest: usize) -> usize {
+        self.surbs_per_pseudonym
+            .get(pseudonym)
+            .map_or(0, |surbs| surbs.retain_freshest(keep_freshest))
+    }
+
+    #[tracing::instrument(skip_all, level = "trace", fields(?sender_id))]
+    fn insert_reply_opener(&self, sender_id: HoprSenderId, opener: ReplyOpener) {
+        self.insert_reply_opener_at(sender_id, opener, Instant::now());
+    }
+
+    #[tracing::instrument(skip_all, level = "trace", fields(?sender_id), ret)]
+    fn find_reply_opener(&self, sender_id: &HoprSenderId) -> Option<ReplyOpener> {
+        self.pseudonym_openers
+            .get(&sender_id.pseudonym())
+            // Unlike the lookup, the removal does not check whether the opener has expired
+            .filter(|cache| cache.contains_key(&sender_id.surb_id()))
+            .and_then(|cache| cache.remove(&sender_id.surb_id()))
+            .map(|(opener, _)| opener)
+    }
+}
+
+/// Represents a single SURB along with its ID popped from the [`SurbRingBuffer`].
+#[derive(Debug, Clone)]
+pub struct PoppedSurb<S> {
+    /// Complete SURB sender ID.
+    pub id: HoprSurbId,
+    /// The popped SURB.
+    pub surb: S,
+    /// Number of SURBs left in the RB after the pop.
+    pub remaining: usize,
+}
+
+#[derive(Debug)]
+struct SurbRingBufferInner<S> {
+    entries: VecDeque<(HoprSurbId, S, Instant)>,
+    capacity: usize,
+    /// Number of SURBs in all RBs sharing this counter, updated together with the `entries`.
+    total: Arc<AtomicUsize>,
+    /// Set once the RB has been evicted from the store and its SURBs no longer count into the `total`.
+    evicted: bool,
+}
+
+impl<S> SurbRingBufferInner<S> {
+    fn pop(&mut self, from_back: bool, id: Option<&HoprSurbId>) -> Option<PoppedSurb<S>> {
+        let next = if from_back {
+            self.entries.back()
+        } else {
+            self.entries.front()
+        };
+
+        if id.is_some_and(|id| next.is_none_or(|(surb_id, ..)| surb_id != id)) {
+            return None;
+        }
+
+        let (id, surb, _) = if from_back {
+            self.entries.pop_back()?
+        } else {
+            self.entries.pop_front()?
+        };
+        self.update_total(1, 0);
+
+        Some(PoppedSurb {
+            id,
+            surb,
+            remaining: self.entries.len(),
+        })
+    }
+
+    fn update_total(&self, removed: usize, added: usize) {
+        if self.evicted {
+            return;
+        }
+        if added > removed {
+            self.total.fetch_add(added - removed, Ordering::Relaxed);
+        } else if removed > added {
+            self.total.fetch_sub(removed - added, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Ring buffer containing SURBs along with their IDs.
+///
+/// All these SURBs usually belong to the same pseudonym and are therefore identified
+/// only by the [`HoprSurbId`].
+#[derive(Clone, Debug)]
+pub struct SurbRingBuffer<S>(Arc<parking_lot::Mutex<SurbRingBufferInner<S>>>);
+
+impl<S> SurbRingBuffer<S> {
+    #[cfg(test)]
+    pub fn new(capacity: usize) -> Self {
+        Self::with_total_counter(capacity, Default::default())
+    }
+
+    /// Creates a new RB whose number of SURBs is also counted in the given `total` counter.
+    pub fn with_total_counter(capacity: usize, total: Arc<AtomicUsize>) -> Self {
+        Self(Arc::new(parking_lot::Mutex::new(SurbRingBufferInner {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+            total,
+            evicted: false,
+        })))
+    }
+
+    /// Drops all SURBs and stops counting the RB in its total counter.
+    ///
+    /// The SURBs pushed into an evicted RB are not counted anymore.
+    pub fn evict(&self) {
+        let mut rb = self.0.lock();
+        let len = rb.entries.len();
+        rb.update_total(len, 0);
+        rb.evicted = true;
+        rb.entries.clear();
+    }
+
+    /// Drops all SURBs except the `count` most recently pushed ones.
+    ///
+    /// Returns the number of dropped SURBs.
+    pub fn retain_freshest(&self, count: usize) -> usize {
+        let mut rb = self.0.lock();
+        let removed = rb.entries.len().saturating_sub(count);
+        rb.entries.drain(..removed);
+        rb.update_total(removed, 0);
+        removed
+    }
+
+    /// Number of SURBs currently in the RB.
+    pub fn len(&self) -> usize {
+        self.0.lock().entries.len()
+    }
+
+    /// Push all SURBs with their IDs into the RB.
+    ///
+    /// Returns the total number of elements in the RB after the push.
+    #[cfg(test)]
+    pub fn push<I: IntoIterator<Item = (HoprSurbId, S)>>(&self, surbs: I) -> usize {
+        self.push_bounded(surbs, usize::MAX).1
+    }
+
+    /// Push all SURBs with their IDs into the RB, while holding at most `max_len` SURBs.
+    ///
+    /// If the RB already holds `max_len` (or its capacity) SURBs, the oldest ones are dropped.
+    ///
+    /// Returns the number of elements in the RB before and after the push.
+    #[cfg(test)]
+    pub fn push_bounded<I: IntoIterator<Item = (HoprSurbId, S)>>(&self, surbs: I, max_len: usize) -> (usize, usize) {
+        let now = Instant::now();
+        self.push_bounded_at(surbs.into_iter().map(|(id, surb)| (id, surb, now)), max_len)
+    }
+
+    /// Push all SURBs with their IDs and insertion times into the RB, while holding at most `max_len` SURBs.
+    ///
+    /// If the RB already holds `max_len` (or its capacity) SURBs, the oldest ones are dropped.
+    /// The SURBs must be ordered by their insertion times.
+    ///
+    /// Returns the number of elements in the RB before and after the push.
+    pub fn push_bounded_at<I: IntoIterator<Item = (HoprSurbId, S, Instant)>>(
+        &self,
+        surbs: I,
+        max_len: usize,
+    ) -> (usize, usize) {
+        let mut rb = self.0.lock();
+        let len_before = rb.entries.len();
+        let max_len = max_len.clamp(1, rb.capacity);
+        let (mut removed, mut added) = (0, 0);
+        for entry in surbs {
+            while rb.entries.len() >= max_len {
+                rb.entries.pop_front();
+                removed += 1;
+            }
+            rb.entries.push_back(entry);
+            added += 1;
+        }
+        rb.update_total(removed, added);
+        (len_before, rb.entries.len())
+    }
+
+    /// Pop the oldest SURB and its IDs from the RB.
+    #[cfg(test)]
+    pub fn pop_one(&self) -> Option<PoppedSurb<S>> {
+        self.0.lock().pop(false, None)
+    }
+
+    /// Check if the next SURB has the given ID and pop it from the RB.
+    #[cfg(test)]
+    pub fn pop_one_if_has_id(&self, id: &HoprSurbId) -> Option<PoppedSurb<S>> {
+        self.0.lock().pop(false, Some(id))
+    }
+
+    /// Pops a SURB selected by the given `policy`.
+    ///
+    /// The `surb_lifetime` is used by the [`SurbConsumptionPolicy::ExpiryAware`] policy only.
+    /// If `id` is given, the selected SURB is popped only if it has this ID.
+    ///
+    /// Returns the popped SURB (if any) and the number of SURBs dropped due to their expiration.
+    pub fn pop_one_by_policy(
+        &self,
+        policy: SurbConsumptionPolicy,
+        surb_lifetime: Duration,
+        id: Option<&HoprSurbId>,
+    ) -> (Option<PoppedSurb<S>>, usize) {
+        let mut rb = self.0.lock();
+        match policy {
+            SurbConsumptionPolicy::Fifo => (rb.pop(false, id), 0),
+            SurbConsumptionPolicy::Lifo => (rb.pop(true, id), 0),
+            SurbConsumptionPolicy::ExpiryAware { margin } => {
+                let max_age = surb_lifetime.saturating_sub(margin);
+                let len_before = rb.entries.len();
+                // SURBs are ordered by their insertion time, so the expired ones are always at the front
+                while rb
+                    .entries
+                    .front()
+                    .is_some_and(|(_, _, inserted)| inserted.elapsed() >= max_age)
+                {
+                    rb.entries.pop_front();
+                }
+                let num_dropped = len_before - rb.entries.len();
+                rb.update_total(num_dropped, 0);
+                (rb.pop(false, id), num_dropped)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hopr_api::types::crypto_random::Randomizable;
+
+    use super::*;
+
+    fn random_surb() -> anyhow::Result<HoprSurb> {
+        let mut bytes = vec![0u8; HoprSurb::SIZE];
+        hopr_api::types::crypto_random::random_fill(&mut bytes);
+        Ok(HoprSurb::try_from(bytes.as_slice())?)
+    }
+
+    #[test]
+    fn surb_ring_buffer_must_drop_items_when_capacity_is_reached() -> anyhow::Result<()> {
+        let rb = SurbRingBuffer::new(3);
+        rb.push([([1u8; 8], 0)]);
+        rb.push([([2u8; 8], 0)]);
+        rb.push([([3u8; 8], 0)]);
+        rb.push([([4u8; 8], 0)]);
+
+        let popped = rb.pop_one().ok_or(anyhow::anyhow!("expected pop"))?;
+        assert_eq!([2u8; 8], popped.id);
+        assert_eq!(2, popped.remaining);
+
+        let popped = rb.pop_one().ok_or(anyhow::anyhow!("expected pop"))?;
+        assert_eq!([3u8; 8], popped.id);
+        assert_eq!(1, popped.remaining);
+
+        let popped = rb.pop_one().ok_or(anyhow::anyhow!("expected pop"))?;
+        assert_eq!([4u8; 8], popped.id);
+        assert_eq!(0, popped.remaining);
+
+        assert!(rb.pop_one().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn surb_ring_buffer_must_be_fifo() -> anyhow::Result<()> {
+        let rb = SurbRingBuffer::new(5);
+
+        let len = rb.push([([1u8; 8], 0)]);
+        assert_eq!(1, len);
+
+        let len = rb.push([([2u8; 8], 0)]);
+        assert_eq!(2, len);
+
+        let popped = rb.pop_one().ok_or(anyhow::anyhow!("expected pop"))?;
+        assert_eq!([1u8; 8], popped.id);
+        assert_eq!(1, popped.remaining);
+
+        let popped = rb.pop_one().ok_or(anyhow::anyhow!("expected pop"))?;
+        assert_eq!([2u8; 8], popped.id);
+        assert_eq!(0, popped.remaining);
+
+        let len = rb.push([([1u8; 8], 0), ([2u8; 8], 0)]);
+        assert_eq!(2, len);
+
+        assert_eq!([1u8; 8], rb.pop_one().ok_or(anyhow::anyhow!("expected pop"))?.id);
+        assert_eq!([2u8; 8], rb.pop_one().ok_or(anyhow::anyhow!("expected pop"))?.id);
+
+        Ok(())
+    }
+
+    #[test]
+    fn surb_ring_buffer_must_not_pop_if_id_does_not_match() -> anyhow::Result<()> {
+        let rb = SurbRingBuffer::new(5);
+
+        rb.push([([1u8; 8], 0)]);
+
+        assert!(rb.pop_one_if_has_id(&[2u8; 8]).is_none());
+        assert_eq!(
+            [1u8; 8],
+            rb.pop_one_if_has_id(&[1u8; 8])
+                .ok_or(anyhow::anyhow!("expected pop"))?
+                .id
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn surb_ring_buffer_lifo_policy_must_pop_freshest_first() -> anyhow::Result<()> {
+        let rb = SurbRingBuffer::new(5);
+        rb.push([([1u8; 8], 0), ([2u8; 8], 0), ([3u8; 8], 0)]);
+
+        let (popped, dropped) = rb.pop_one_by_policy(SurbConsumptionPolicy::Lifo, Duration::from_secs(60), None);
+        let popped = popped.ok_or(anyhow::anyhow!("expected pop"))?;
+        assert_eq!([3u8; 8], popped.id);
+        assert_eq!(2, popped.remaining);
+        assert_eq!(0, dropped);
+
+        let (popped, _) = rb.pop_one_by_policy(SurbConsumptionPolicy::Lifo, Duration::from_secs(60), Some(&[1u8; 8]));
+        assert!(popped.is_none(), "exact match must only check the freshest surb");
+
+        let (popped, _) = rb.pop_one_by_policy(SurbConsumptionPolicy::Lifo, Duration::from_secs(60), Some(&[2u8; 8]));
+        assert_eq!([2u8; 8], popped.ok_or(anyhow::anyhow!("expected pop"))?.id);
+
+        Ok(())
+    }
+
+    #[test]
+    fn surb_ring_buffer_expiry_aware_policy_must_drop_surbs_close_to_expiration() -> anyhow::Result<()> {
+        let lifetime = Duration::from_secs(60);
+        let policy = SurbConsumptionPolicy::ExpiryAware {
+            margin: Duration::from_secs(20),
+        };
+        let now = Instant::now();
+        let old = now
+            .checked_sub(Duration::from_secs(50))
+            .ok_or(anyhow::anyhow!("clock too early"))?;
+
+        let fifo_rb = SurbRingBuffer::new(10);
+        let expiry_rb = SurbRingBuffer::new(10);
+        for rb in [&fifo_rb, &expiry_rb] {
+            rb.push_bounded_at(
+                [
+                    ([1u8; 8], 0, old),
+                    ([2u8; 8], 0, old),
+                    ([3u8; 8], 0, old),
+                    ([4u8; 8], 0, now),
+                    ([5u8; 8], 0, now),
+                ],
+                usize::MAX,
+            );
+        }
+
+        let (popped, dropped) = fifo_rb.pop_one_by_policy(SurbConsumptionPolicy::Fifo, lifetime, None);
+        let popped = popped.ok_or(anyhow::anyhow!("expected pop"))?;
+        assert_eq!([1u8; 8], popped.id);
+        assert_eq!(4, popped.remaining);
+        assert_eq!(0, dropped);
+
+        let (popped, dropped) = expiry_rb.pop_one_by_policy(policy, lifetime, None);
+        let popped = popped.ok_or(anyhow::anyhow!("expected pop"))?;
+        assert_eq!([4u8; 8], popped.id);
+        assert_eq!(1, popped.remaining);
+        assert_eq!(3, dropped);
+
+        Ok(())
+    }
+
+    #[test]
+    fn memory_surb_store_expiry_aware_policy_must_signal_distress_earlier() -> anyhow::Result<()> {
+        let now = Instant::now();
+        let old = now
+            .checked_sub(Duration::from_secs(45))
+            .ok_or(anyhow::anyhow!("clock too early"))?;
+
+        let mut signals = Vec::new();
+        for policy in [
+            SurbConsumptionPolicy::Fifo,
+            SurbConsumptionPolicy::ExpiryAware {
+                margin: Duration::from_secs(30),
+            },
+        ] {
+            let cfg = SurbStoreConfig {
+                reply_opener_lifetime: MINIMUM_OPENER_LIFETIME,
+                distress_threshold: 10,
+                consumption_policy: policy,
+                ..Default::default()
+            };
+            let store = MemorySurbStore::new(cfg);
+            let pseudonym = HoprPseudonym::random();
+
+            // 20 SURBs are past the expiry margin, 5 are fresh
+            let surbs = (0..25u64)
+                .map(|i| Ok((i.to_be_bytes(), random_surb()?, if i < 20 { old } else { now })))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            assert_eq!(25, store.insert_surbs_at(pseudonym, surbs));
+
+            let found = store
+                .find_surb(SurbMatcher::Pseudonym(pseudonym))
+                .ok_or(anyhow::anyhow!("expected surb"))?;
+            signals.push(cfg.surb_signal(found.remaining));
+        }
+
+        assert_eq!(vec![None, Some(PacketSignal::SurbDistress)], signals);
+        Ok(())
+    }
+
+    #[test]
+    fn surb_store_config_must_signal_out_of_surbs_and_distress() {
+        let cfg = SurbStoreConfig {
+            distress_threshold: 10,
+            ..Default::default()
+        };
+        assert_eq!(Some(PacketSignal::OutOfSurbs), cfg.surb_signal(0));
+        assert_eq!(Some(PacketSignal::SurbDistress), cfg.surb_signal(1));
+        assert_eq!(Some(PacketSignal::SurbDistress), cfg.surb_signal(9));
+        assert_eq!(None, cfg.surb_signal(10));
+    }
+
+    #[test]
+    fn surb_ring_buffer_must_keep_total_count_exact_across_eviction() {
+        let total = Arc::new(AtomicUsize::new(0));
+        let rb1 = SurbRingBuffer::with_total_counter(3, total.clone());
+        let rb2 = SurbRingBuffer::with_total_counter(3, total.clone());
+
+        rb1.push([([1u8; 8], 0), ([2u8; 8], 0), ([3u8; 8], 0), ([4u8; 8], 0)]);
+        rb2.push([([5u8; 8], 0)]);
+        assert_eq!(4, total.load(Ordering::Relaxed));
+
+        rb1.pop_one();
+        assert_eq!(3, total.load(Ordering::Relaxed));
+
+        rb1.evict();
+        assert_eq!(1, total.load(Ordering::Relaxed));
+
+        // A late push into the evicted RB must not be counted
+        rb1.push([([6u8; 8], 0)]);
+        assert_eq!(1, total.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn memory_surb_store_policies_must_affect_remaining_surbs() -> anyhow::Result<()> {
+        let surbs = (0..10u8)
+            .map(|i| Ok(([i; 8], random_surb()?)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        for (policy, expected_id) in [
+            (SurbConsumptionPolicy::Fifo, [0u8; 8]),
+            (SurbConsumptionPolicy::Lifo, [9u8; 8]),
+            (SurbConsumptionPolicy::expiry_aware(), [0u8; 8]),
+        ] {
+            let store = MemorySurbStore::new(SurbStoreConfig {
+                consumption_policy: policy,
+                ..Default::default()
+            });
+            let pseudonym = HoprPseudonym::random();
+            assert_eq!(10, store.insert_surbs(pseudonym, surbs.clone()));
+
+            let found = store
+                .find_surb(SurbMatcher::Pseudonym(pseudonym))
+                .ok_or(anyhow::anyhow!("expected surb"))?;
+            assert_eq!(
+                HoprSenderId::from_pseudonym_and_id(&pseudonym, expected_id),
+                found.sender_id
+            );
+            assert_eq!(9, found.remaining, "{policy:?}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn memory_surb_store_must_honor_reserved_quota_per_pseudonym() -> anyhow::Result<()> {
+        let store = MemorySurbStore::new(SurbStoreConfig {
+            rb_capacity: 2000,
+            total_surb_capacity: Some(1500),
+            reserved_surbs_per_pseudonym: 100,
+            distress_threshold: 50,
+            ..Default::default()
+        });
+
+        let surb = random_surb()?;
+        let heavy = HoprPseudonym::random();
+        let light = HoprPseudonym::random();
+
+        // The heavy pseudonym takes up the entire total capacity
+        let heavy_surbs = (0..2000u64).map(|i| (i.to_be_bytes(), surb.clone())).collect();
+        assert_eq!(1500, store.insert_surbs(heavy, heavy_surbs));
+
+        // The light pseudonym can still fill its reserved quota
+        let light_surbs = (0..150u64).map(|i| (i.to_be_bytes(), surb.clone())).collect::<Vec<_>>();
+        assert_eq!(100, store.insert_surbs(light, light_surbs));
+
+        // The heavy pseudonym's new SURBs only replace its own oldest SURBs
+        let heavy_surbs = (2000..2100u64).map(|i| (i.to_be_bytes(), surb.clone())).collect();
+        assert_eq!(1500, store.insert_surbs(heavy, heavy_surbs));
+
+        let found = store
+            .find_surb(SurbMatcher::Pseudonym(light))
+            .ok_or(anyhow::anyhow!("expected surb"))?;
+        assert_eq!(99, found.remaining);
+        assert!(found.remaining >= store.cfg.distress_threshold);
+
+        let found = store
+            .find_surb(SurbMatcher::Pseudonym(heavy))
+            .ok_or(anyhow::anyhow!("expected surb"))?;
+        assert_eq!(
+            HoprSenderId::from_pseudonym_and_id(&heavy, 600u64.to_be_bytes()),
+            found.sender_id
+        );
+        assert_eq!(1499, found.remaining);
+
+        Ok(())
+    }
+
+    #[test]
+    fn memory_surb_store_must_discard_surbs_of_pseudonym() -> anyhow::Result<()> {
+        let store = MemorySurbStore::new(SurbStoreConfig {
+            total_surb_capacity: Some(10),
+            reserved_surbs_per_pseudonym: 0,
+            ..Default::default()
+        });
+
+        let surb = random_surb()?;
+        let discarded = HoprPseudonym::random();
+        let kept = HoprPseudonym::random();
+
+        let surbs = (0..10u64).map(|i| (i.to_be_bytes(), surb.clone())).collect();
+        assert_eq!(10, store.insert_surbs(discarded, surbs));
+        let surbs = (0..2u64).map(|i| (i.to_be_bytes(), surb.clone())).collect();
+        assert_eq!(1, store.insert_surbs(kept, surbs));
+
+        assert_eq!(8, store.discard_surbs(&discarded, 2));
+        assert_eq!(0, store.discard_surbs(&discarded, 2));
+
+        // Only the freshest SURBs are kept
+        let found = store
+            .find_surb(SurbMatcher::Pseudonym(discarded))
+            .ok_or(anyhow::anyhow!("expected surb"))?;
+        assert_eq!(
+            HoprSenderId::from_pseudonym_and_id(&discarded, 8u64.to_be_bytes()),
+            found.sender_id
+        );
+        assert_eq!(1, found.remaining);
+
+        // The discarded SURBs no longer count into the total capacity
+        let surbs = (2..4u64).map(|i| (i.to_be_bytes(), surb.clone())).collect();
+        assert_eq!(3, store.insert_surbs(kept, surbs));
+        assert_eq!(0, store.discard_surbs(&HoprPseudonym::random(), 0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn surb_store_config_must_reject_invalid_policy_settings() {
+        use validator::Validate;
+
+        assert!(SurbStoreConfig::default().validate().is_ok());
+
+        let cfg = SurbStoreConfig {
+            reserved_surbs_per_pseudonym: SurbStoreConfig::default().rb_capacity + 1,
+            ..Default::default()
+        };
+        assert!(cfg.validate().is_err());
+
+        let cfg = SurbStoreConfig {
+            consumption_policy: SurbConsumptionPolicy::ExpiryAware {
+                margin: SurbStoreConfig::default().reply_opener_lifetime,
+            },
+            ..Default::default()
+        };
+        assert!(cfg.validate().is_err());
+    }
+}
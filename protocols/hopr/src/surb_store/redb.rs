@@ -0,0 +1,1387 @@
+use std::{
+    num::NonZeroU64,
+    ops::RangeInclusive,
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+        mpsc,
+    },
+    time::{Duration, Instant},
+};
+
+use hopr_api::types::{
+    internal::{
+        prelude::HoprPseudonym,
+        routing::{SURB_ID_SIZE, SurbMatcher},
+    },
+    primitive::prelude::*,
+};
+use hopr_crypto_packet::prelude::*;
+use hopr_utils::platform::time::native::current_time;
+use redb::{
+    Durability, ReadableDatabase, ReadableTable, ReadableTableMetadata, Table, TableDefinition, WriteTransaction,
+};
+
+use super::{
+    MIN_SURB_RB_CAPACITY, MINIMUM_OPENER_LIFETIME, MINIMUM_OPENER_PSEUDONYMS, MINIMUM_OPENERS_PER_PSEUDONYM,
+    MINIMUM_SURB_LIFETIME, MINIMUM_SURBS_PER_PSEUDONYM, MemorySurbStore, SurbConsumptionPolicy, SurbStoreConfig,
+    SurbStoreEviction,
+};
+use crate::{FoundSurb, traits::SurbStore};
+
+type PseudonymKey = [u8; HoprPseudonym::SIZE];
+type SenderIdKey = [u8; HoprSenderId::SIZE];
+
+/// SURBs keyed by pseudonym and insertion sequence number.
+///
+/// The value holds the insertion timestamp (in milliseconds) and the SURB prefixed with its [`HoprSurbId`].
+const SURBS_TABLE: TableDefinition<(PseudonymKey, u64), (u64, Vec<u8>)> = TableDefinition::new("surbs");
+
+/// Ring buffer metadata of each pseudonym, see [`RingMeta`].
+const SURB_RINGS_TABLE: TableDefinition<PseudonymKey, (u64, u64, u64)> = TableDefinition::new("surb_rings");
+
+/// Reply openers keyed by the complete sender ID.
+///
+/// The value holds the insertion sequence number, the insertion timestamp (in milliseconds)
+/// and the `postcard`-serialized [`ReplyOpener`].
+const OPENERS_TABLE: TableDefinition<SenderIdKey, (u64, u64, Vec<u8>)> = TableDefinition::new("reply_openers");
+
+/// Insertion order of reply openers for each pseudonym.
+const OPENER_ORDER_TABLE: TableDefinition<(PseudonymKey, u64), HoprSurbId> = TableDefinition::new("reply_opener_order");
+
+/// Reply opener set metadata of each pseudonym, see [`RingMeta`].
+const OPENER_SETS_TABLE: TableDefinition<PseudonymKey, (u64, u64, u64)> = TableDefinition::new("reply_opener_sets");
+
+/// Errors returned by the [`RedbSurbStore`].
+#[derive(Debug, thiserror::Error)]
+pub enum RedbSurbStoreError {
+    #[error("database error: {0}")]
+    Database(#[from] redb::Error),
+    #[error("serialization error: {0}")]
+    Serialization(#[from] postcard::Error),
+    #[error("corrupted SURB entry: {0}")]
+    Corrupted(#[from] GeneralError),
+    #[error("failed to start the writer thread: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("writer thread is not running")]
+    WriterStopped,
+}
+
+macro_rules! impl_from_redb_error {
+    ($($t:ty),+) => {
+        $(impl From<$t> for RedbSurbStoreError {
+            fn from(error: $t) -> Self {
+                Self::Database(error.into())
+            }
+        })+
+    };
+}
+
+impl_from_redb_error!(
+    redb::DatabaseError,
+    redb::TransactionError,
+    redb::TableError,
+    redb::StorageError,
+    redb::CommitError,
+    redb::SetDurabilityError
+);
+
+/// Bookkeeping of a per-pseudonym collection (SURB ring buffer or reply opener set).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct RingMeta {
+    /// Number of live entries.
+    len: u64,
+    /// Sequence number assigned to the next inserted entry.
+    next_seq: u64,
+    /// Timestamp (in milliseconds) of the last access to the collection.
+    last_access: u64,
+}
+
+impl RingMeta {
+    fn new(now: u64) -> Self {
+        Self {
+            len: 0,
+            next_seq: 0,
+            last_access: now,
+        }
+    }
+
+    fn is_expired(&self, now: u64, lifetime: u64) -> bool {
+        self.last_access.saturating_add(lifetime) < now
+    }
+}
+
+impl From<(u64, u64, u64)> for RingMeta {
+    fn from((len, next_seq, last_access): (u64, u64, u64)) -> Self {
+        Self {
+            len,
+            next_seq,
+            last_access,
+        }
+    }
+}
+
+impl From<RingMeta> for (u64, u64, u64) {
+    fn from(value: RingMeta) -> Self {
+        (value.len, value.next_seq, value.last_access)
+    }
+}
+
+/// Effective limits derived from the [`SurbStoreConfig`].
+///
+/// The same lower bounds as in the [`MemorySurbStore`](super::MemorySurbStore) are applied.
+#[derive(Clone, Copy, Debug)]
+struct Limits {
+    rb_capacity: u64,
+    max_pseudonyms: u64,
+    pseudonyms_lifetime: u64,
+    max_opener_pseudonyms: u64,
+    max_openers_per_pseudonym: u64,
+    reply_opener_lifetime: u64,
+}
+
+impl From<&SurbStoreConfig> for Limits {
+    fn from(cfg: &SurbStoreConfig) -> Self {
+        Self {
+            rb_capacity: cfg.rb_capacity.max(MIN_SURB_RB_CAPACITY) as u64,
+            max_pseudonyms: cfg.max_pseudonyms.max(MINIMUM_SURBS_PER_PSEUDONYM) as u64,
+            pseudonyms_lifetime: cfg.pseudonyms_lifetime.max(MINIMUM_SURB_LIFETIME).as_millis() as u64,
+            max_opener_pseudonyms: cfg.max_openers_per_pseudonym.max(MINIMUM_OPENER_PSEUDONYMS) as u64,
+            max_openers_per_pseudonym: cfg.max_openers_per_pseudonym.max(MINIMUM_OPENERS_PER_PSEUDONYM) as u64,
+            reply_opener_lifetime: cfg.reply_opener_lifetime.max(MINIMUM_OPENER_LIFETIME).as_millis() as u64,
+        }
+    }
+}
+
+#[inline]
+fn pseudonym_range(pseudonym: &PseudonymKey) -> RangeInclusive<(PseudonymKey, u64)> {
+    (*pseudonym, 0)..=(*pseudonym, u64::MAX)
+}
+
+#[inline]
+fn pseudonym_key(pseudonym: &HoprPseudonym) -> PseudonymKey {
+    let mut ret = [0u8; HoprPseudonym::SIZE];
+    ret.copy_from_slice(pseudonym.as_ref());
+    ret
+}
+
+#[inline]
+fn sender_id_key(pseudonym: &PseudonymKey, surb_id: &HoprSurbId) -> SenderIdKey {
+    let mut ret = [0u8; HoprSenderId::SIZE];
+    ret[..HoprPseudonym::SIZE].copy_from_slice(pseudonym);
+    ret[HoprPseudonym::SIZE..].copy_from_slice(surb_id);
+    ret
+}
+
+/// Returns the sequence number of the oldest entry of the given `pseudonym`.
+fn first_seq<V: redb::Value + 'static>(
+    table: &Table<(PseudonymKey, u64), V>,
+    pseudonym: &PseudonymKey,
+) -> Result<Option<u64>, RedbSurbStoreError> {
+    Ok(table
+        .range(pseudonym_range(pseudonym))?
+        .next()
+        .transpose()?
+        .map(|(k, _)| k.value().1))
+}
+
+/// Maximum number of operations written to the database in a single transaction.
+const MAX_WRITE_BATCH_SIZE: usize = 1024;
+
+/// Mutation of the store that is mirrored into the database by the [`Writer`].
+///
+/// Each operation carries the timestamp (in milliseconds) of the moment it was applied to the in-memory state.
+enum WriteOp {
+    InsertSurbs {
+        pseudonym: PseudonymKey,
+        surbs: Vec<(HoprSurbId, HoprSurb)>,
+        /// Number of SURBs held by the pseudonym after the insertion.
+        len: u64,
+        now: u64,
+    },
+    ConsumeSurb {
+        pseudonym: PseudonymKey,
+        surb_id: HoprSurbId,
+        /// Whether the SURB was taken from the back (newest end) of the ring buffer.
+        from_back: bool,
+        now: u64,
+    },
+    InsertReplyOpener {
+        sender_id: HoprSenderId,
+        opener: ReplyOpener,
+        now: u64,
+    },
+    RemoveReplyOpener {
+        sender_id: HoprSenderId,
+        now: u64,
+    },
+    DiscardSurbs {
+        pseudonym: PseudonymKey,
+        /// Number of the freshest SURBs to keep.
+        keep: u64,
+        now: u64,
+    },
+    /// All SURBs of the pseudonym were evicted from the in-memory store.
+    EvictSurbs {
+        pseudonym: PseudonymKey,
+    },
+    /// All reply openers of the pseudonym were evicted from the in-memory store.
+    EvictReplyOpeners {
+        pseudonym: PseudonymKey,
+    },
+}
+
+/// Reply to the [`WriterRequest::Close`], sent once the database has been released.
+type CloseReply = futures::channel::oneshot::Sender<Result<(), RedbSurbStoreError>>;
+
+/// Request sent to the [`Writer`] thread.
+enum WriterRequest {
+    Write(WriteOp),
+    /// Durably commits all preceding operations.
+    Flush(mpsc::SyncSender<Result<(), RedbSurbStoreError>>),
+    /// Durably commits all preceding operations and [prunes](RedbSurbStore::prune) the database.
+    Prune(mpsc::SyncSender<Result<(), RedbSurbStoreError>>),
+    /// Durably commits all preceding operations and stops the writer, releasing the database.
+    Close(CloseReply),
+}
+
+/// Owns the write side of the database and applies [`WriteOps`](WriteOp) in batches on a dedicated thread.
+struct Writer {
+    db: Arc<redb::Database>,
+    limits: Limits,
+    durable_commit_interval: Arc<AtomicU64>,
+    commits: u64,
+    clock: Arc<dyn Fn() -> Duration + Send + Sync>,
+}
+
+impl Writer {
+    /// Processes the `requests` until all their senders are gone or a [`WriterRequest::Close`] is received.
+    ///
+    /// Returns the sender of the close request together with the result of the final commit, so it can be
+    /// replied to once the writer and its database are dropped.
+    fn run(mut self, requests: mpsc::Receiver<WriterRequest>) -> Option<(CloseReply, Result<(), RedbSurbStoreError>)> {
+        let mut pending = Vec::with_capacity(MAX_WRITE_BATCH_SIZE);
+        while let Ok(first) = requests.recv() {
+            for request in std::iter::once(first).chain(requests.try_iter().take(MAX_WRITE_BATCH_SIZE - 1)) {
+                match request {
+                    WriterRequest::Write(op) => pending.push(op),
+                    WriterRequest::Flush(reply) => {
+                        let _ = reply.send(self.commit(pending.drain(..), true));
+                    }
+                    WriterRequest::Prune(reply) => {
+                        let _ = reply.send(self.commit(pending.drain(..), true).and_then(|_| self.prune()));
+                    }
+                    WriterRequest::Close(reply) => {
+                        let res = self.commit(pending.drain(..), true);
+                        return Some((reply, res));
+                    }
+                }
+            }
+
+            if !pending.is_empty() {
+                self.commits += 1;
+                let durable = self
+                    .commits
+                    .is_multiple_of(self.durable_commit_interval.load(Ordering::Relaxed));
+                if let Err(error) = self.commit(pending.drain(..), durable) {
+                    tracing::error!(%error, "failed to write to the redb surb store");
+                }
+            }
+        }
+
+        // All store instances are gone, so make everything written so far durable
+        if let Err(error) = self.commit(std::iter::empty(), true) {
+            tracing::error!(%error, "failed to flush the redb surb store");
+        }
+        None
+    }
+
+    fn commit(&self, ops: impl Iterator<Item = WriteOp>, durable: bool) -> Result<(), RedbSurbStoreError> {
+        let mut tx = self.db.begin_write()?;
+        if !durable {
+            tx.set_durability(Durability::None)?;
+        }
+        for op in ops {
+            match op {
+                WriteOp::InsertSurbs {
+                    pseudonym,
+                    surbs,
+                    len,
+                    now,
+                } => self.insert_surbs(&tx, pseudonym, surbs, len, now)?,
+                WriteOp::ConsumeSurb {
+                    pseudonym,
+                    surb_id,
+                    from_back,
+                    now,
+                } => Self::consume_surb(&tx, pseudonym, surb_id, from_back, now)?,
+                WriteOp::InsertReplyOpener { sender_id, opener, now } => {
+                    self.insert_reply_opener(&tx, sender_id, opener, now)?
+                }
+                WriteOp::RemoveReplyOpener { sender_id, now } => Self::remove_reply_opener(&tx, sender_id, now)?,
+                WriteOp::DiscardSurbs { pseudonym, keep, now } => Self::discard_surbs(&tx, pseudonym, keep, now)?,
+                WriteOp::EvictSurbs { pseudonym } => Self::evict_surbs(&tx, pseudonym)?,
+                WriteOp::EvictReplyOpeners { pseudonym } => Self::evict_reply_openers(&tx, pseudonym)?,
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn now(&self) -> u64 {
+        (self.clock)().as_millis() as u64
+    }
+
+    /// Drops all entries that violate the lifetimes or capacities of the [`SurbStoreConfig`].
+    fn prune(&self) -> Result<(), RedbSurbStoreError> {
+        let now = self.now();
+        let tx = self.db.begin_write()?;
+        {
+            let mut rings = tx.open_table(SURB_RINGS_TABLE)?;
+            let mut surbs = tx.open_table(SURBS_TABLE)?;
+
+            let all_rings = rings
+                .iter()?
+                .map(|r| r.map(|(k, v)| (k.value(), RingMeta::from(v.value()))))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            for (pseudonym, mut meta) in all_rings {
+                if meta.is_expired(now, self.limits.pseudonyms_lifetime) {
+                    surbs.retain_in(pseudonym_range(&pseudonym), |_, _| false)?;
+                    rings.remove(pseudonym)?;
+                } else if meta.len > self.limits.rb_capacity {
+                    while meta.len > self.limits.rb_capacity {
+                        let Some(seq) = first_seq(&surbs, &pseudonym)? else {
+                            break;
+                        };
+                        surbs.remove((pseudonym, seq))?;
+                        meta.len -= 1;
+                    }
+                    rings.insert(pseudonym, <(u64, u64, u64)>::from(meta))?;
+                }
+            }
+
+            while rings.len()? > self.limits.max_pseudonyms {
+                Self::evict_lru_ring(&mut rings, &mut surbs)?;
+            }
+        }
+        {
+            let mut sets = tx.open_table(OPENER_SETS_TABLE)?;
+            let mut order = tx.open_table(OPENER_ORDER_TABLE)?;
+            let mut openers = tx.open_table(OPENERS_TABLE)?;
+
+            let all_sets = sets
+                .iter()?
+                .map(|r| r.map(|(k, v)| (k.value(), RingMeta::from(v.value()))))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            for (pseudonym, mut meta) in all_sets {
+                if meta.is_expired(now, self.limits.pseudonyms_lifetime) {
+                    Self::clear_opener_set(&mut order, &mut openers, &pseudonym)?;
+                    sets.remove(pseudonym)?;
+                    continue;
+                }
+
+                // Openers are ordered by their insertion time, so the expired ones are always at the front
+                let initial_len = meta.len;
+                loop {
+                    let oldest = order
+                        .range(pseudonym_range(&pseudonym))?
+                        .next()
+                        .transpose()?
+                        .map(|(k, v)| (k.value().1, v.value()));
+                    let Some((seq, surb_id)) = oldest else {
+                        break;
+                    };
+
+                    let key = sender_id_key(&pseudonym, &surb_id);
+                    let is_expired = openers
+                        .get(key)?
+                        .is_none_or(|v| v.value().1.saturating_add(self.limits.reply_opener_lifetime) < now);
+
+                    if is_expired || meta.len > self.limits.max_openers_per_pseudonym {
+                        order.remove((pseudonym, seq))?;
+                        openers.remove(key)?;
+                        meta.len = meta.len.saturating_sub(1);
+                    } else {
+                        break;
+                    }
+                }
+
+                if meta.len != initial_len {
+                    sets.insert(pseudonym, <(u64, u64, u64)>::from(meta))?;
+                }
+            }
+
+            while sets.len()? > self.limits.max_opener_pseudonyms {
+                Self::evict_lru_opener_set(&mut sets, &mut order, &mut openers)?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn find_lru(table: &Table<PseudonymKey, (u64, u64, u64)>) -> Result<Option<PseudonymKey>, RedbSurbStoreError> {
+        let mut lru: Option<(u64, PseudonymKey)> = None;
+        for entry in table.iter()? {
+            let (k, v) = entry?;
+            let last_access = RingMeta::from(v.value()).last_access;
+            if lru.is_none_or(|(oldest, _)| last_access < oldest) {
+                lru = Some((last_access, k.value()));
+            }
+        }
+        Ok(lru.map(|(_, k)| k))
+    }
+
+    // NOTE: the LRU search is linear in the number of pseudonyms, but it happens only
+    // when a new pseudonym is about to be inserted into a full store.
+    fn evict_lru_ring(
+        rings: &mut Table<PseudonymKey, (u64, u64, u64)>,
+        surbs: &mut Table<(PseudonymKey, u64), (u64, Vec<u8>)>,
+    ) -> Result<(), RedbSurbStoreError> {
+        if let Some(pseudonym) = Self::find_lru(rings)? {
+            tracing::warn!(pseudonym = const_hex::encode(pseudonym), "evicting surbs for pseudonym");
+            surbs.retain_in(pseudonym_range(&pseudonym), |_, _| false)?;
+            rings.remove(pseudonym)?;
+        }
+        Ok(())
+    }
+
+    fn clear_opener_set(
+        order: &mut Table<(PseudonymKey, u64), HoprSurbId>,
+        openers: &mut Table<SenderIdKey, (u64, u64, Vec<u8>)>,
+        pseudonym: &PseudonymKey,
+    ) -> Result<(), RedbSurbStoreError> {
+        for entry in order.extract_from_if(pseudonym_range(pseudonym), |_, _| true)? {
+            let (_, surb_id) = entry?;
+            openers.remove(sender_id_key(pseudonym, &surb_id.value()))?;
+        }
+        Ok(())
+    }
+
+    fn evict_lru_opener_set(
+        sets: &mut Table<PseudonymKey, (u64, u64, u64)>,
+        order: &mut Table<(PseudonymKey, u64), HoprSurbId>,
+        openers: &mut Table<SenderIdKey, (u64, u64, Vec<u8>)>,
+    ) -> Result<(), RedbSurbStoreError> {
+        if let Some(pseudonym) = Self::find_lru(sets)? {
+            tracing::warn!(
+                pseudonym = const_hex::encode(pseudonym),
+                "evicting reply openers for pseudonym"
+            );
+            Self::clear_opener_set(order, openers, &pseudonym)?;
+            sets.remove(pseudonym)?;
+        }
+        Ok(())
+    }
+
+    fn insert_surbs(
+        &self,
+        tx: &WriteTransaction,
+        key: PseudonymKey,
+        new_surbs: Vec<(HoprSurbId, HoprSurb)>,
+        len: u64,
+        now: u64,
+    ) -> Result<(), RedbSurbStoreError> {
+        let mut rings = tx.open_table(SURB_RINGS_TABLE)?;
+        let mut surbs = tx.open_table(SURBS_TABLE)?;
+
+        let existing = rings.get(key)?.map(|v| RingMeta::from(v.value()));
+        let mut meta = match existing {
+            Some(meta) if !meta.is_expired(now, self.limits.pseudonyms_lifetime) => meta,
+            Some(_) => {
+                surbs.retain_in(pseudonym_range(&key), |_, _| false)?;
+                RingMeta::new(now)
+            }
+            None => {
+                while rings.len()? >= self.limits.max_pseudonyms {
+                    Self::evict_lru_ring(&mut rings, &mut surbs)?;
+                }
+                RingMeta::new(now)
+            }
+        };
+
+        for (surb_id, surb) in new_surbs {
+            let mut entry = Vec::with_capacity(SURB_ID_SIZE + HoprSurb::SIZE);
+            entry.extend_from_slice(&surb_id);
+            entry.extend_from_slice(&surb.into_boxed());
+            surbs.insert((key, meta.next_seq), (now, entry))?;
+            meta.next_seq += 1;
+            meta.len += 1;
+        }
+
+        // Drop the oldest SURBs the same way the in-memory ring buffer did
+        while meta.len > len.min(self.limits.rb_capacity) {
+            let Some(seq) = first_seq(&surbs, &key)? else {
+                break;
+            };
+            surbs.remove((key, seq))?;
+            meta.len -= 1;
+        }
+
+        meta.last_access = now;
+        rings.insert(key, <(u64, u64, u64)>::from(meta))?;
+        Ok(())
+    }
+
+    fn consume_surb(
+        tx: &WriteTransaction,
+        key: PseudonymKey,
+        surb_id: HoprSurbId,
+        from_back: bool,
+        now: u64,
+    ) -> Result<(), RedbSurbStoreError> {
+        let mut rings = tx.open_table(SURB_RINGS_TABLE)?;
+        let mut surbs = tx.open_table(SURBS_TABLE)?;
+
+        let Some(mut meta) = rings.get(key)?.map(|v| RingMeta::from(v.value())) else {
+            return Ok(());
+        };
+
+        let mut consumed = Vec::new();
+        if from_back {
+            let newest = surbs
+                .range(pseudonym_range(&key))?
+                .next_back()
+                .transpose()?
+                .map(|(k, v)| (k.value().1, v.value().1));
+            if let Some((seq, _)) = newest.filter(|(_, entry)| entry.starts_with(&surb_id)) {
+                consumed.push(seq);
+            }
+        } else {
+            // SURBs in front of the consumed one were dropped by the consumption policy
+            let mut found = false;
+            for entry in surbs.range(pseudonym_range(&key))? {
+                let (k, v) = entry?;
+                consumed.push(k.value().1);
+                if v.value().1.starts_with(&surb_id) {
+                    found = true;
+                    break;
+                }
+            }
+            if !found {
+                consumed.clear();
+            }
+        }
+
+        for seq in &consumed {
+            surbs.remove((key, *seq))?;
+        }
+        meta.len = meta.len.saturating_sub(consumed.len() as u64);
+        meta.last_access = now;
+        rings.insert(key, <(u64, u64, u64)>::from(meta))?;
+        Ok(())
+    }
+
+    fn discard_surbs(tx: &WriteTransaction, key: PseudonymKey, keep: u64, now: u64) -> Result<(), RedbSurbStoreError> {
+        let mut rings = tx.open_table(SURB_RINGS_TABLE)?;
+        let mut surbs = tx.open_table(SURBS_TABLE)?;
+
+        let Some(mut meta) = rings.get(key)?.map(|v| RingMeta::from(v.value())) else {
+            return Ok(());
+        };
+
+        while meta.len > keep {
+            let Some(seq) = first_seq(&surbs, &key)? else {
+                break;
+            };
+            surbs.remove((key, seq))?;
+            meta.len -= 1;
+        }
+
+        meta.last_access = now;
+        rings.insert(key, <(u64, u64, u64)>::from(meta))?;
+        Ok(())
+    }
+
+    fn evict_surbs(tx: &WriteTransaction, key: PseudonymKey) -> Result<(), RedbSurbStoreError> {
+        let mut rings = tx.open_table(SURB_RINGS_TABLE)?;
+        let mut surbs = tx.open_table(SURBS_TABLE)?;
+        surbs.retain_in(pseudonym_range(&key), |_, _| false)?;
+        rings.remove(key)?;
+        Ok(())
+    }
+
+    fn evict_reply_openers(tx: &WriteTransaction, key: PseudonymKey) -> Result<(), RedbSurbStoreError> {
+        let mut sets = tx.open_table(OPENER_SETS_TABLE)?;
+        let mut order = tx.open_table(OPENER_ORDER_TABLE)?;
+        let mut openers = tx.open_table(OPENERS_TABLE)?;
+        Self::clear_opener_set(&mut order, &mut openers, &key)?;
+        sets.remove(key)?;
+        Ok(())
+    }
+
+    fn insert_reply_opener(
+        &self,
+        tx: &WriteTransaction,
+        sender_id: HoprSenderId,
+        opener: ReplyOpener,
+        now: u64,
+    ) -> Result<(), RedbSurbStoreError> {
+        let pseudonym = pseudonym_key(&sender_id.pseudonym());
+        let surb_id = sender_id.surb_id();
+        let opener = postcard::to_allocvec(&opener)?;
+
+        let mut sets = tx.open_table(OPENER_SETS_TABLE)?;
+        let mut order = tx.open_table(OPENER_ORDER_TABLE)?;
+        let mut openers = tx.open_table(OPENERS_TABLE)?;
+
+        let existing = sets.get(pseudonym)?.map(|v| RingMeta::from(v.value()));
+        let mut meta = match existing {
+            Some(meta) if !meta.is_expired(now, self.limits.pseudonyms_lifetime) => meta,
+            Some(_) => {
+                Self::clear_opener_set(&mut order, &mut openers, &pseudonym)?;
+                RingMeta::new(now)
+            }
+            None => {
+                while sets.len()? >= self.limits.max_opener_pseudonyms {
+                    Self::evict_lru_opener_set(&mut sets, &mut order, &mut openers)?;
+                }
+                RingMeta::new(now)
+            }
+        };
+
+        let key = sender_id_key(&pseudonym, &surb_id);
+        if let Some(replaced_seq) = openers.insert(key, (meta.next_seq, now, opener))?.map(|v| v.value().0) {
+            order.remove((pseudonym, replaced_seq))?;
+        } else {
+            meta.len += 1;
+        }
+        order.insert((pseudonym, meta.next_seq), surb_id)?;
+        meta.next_seq += 1;
+
+        while meta.len > self.limits.max_openers_per_pseudonym {
+            let Some((seq, oldest_id)) = order
+                .range(pseudonym_range(&pseudonym))?
+                .next()
+                .transpose()?
+                .map(|(k, v)| (k.value().1, v.value()))
+            else {
+                break;
+            };
+            order.remove((pseudonym, seq))?;
+            openers.remove(sender_id_key(&pseudonym, &oldest_id))?;
+            meta.len -= 1;
+        }
+
+        meta.last_access = now;
+        sets.insert(pseudonym, <(u64, u64, u64)>::from(meta))?;
+        Ok(())
+    }
+
+    fn remove_reply_opener(tx: &WriteTransaction, sender_id: HoprSenderId, now: u64) -> Result<(), RedbSurbStoreError> {
+        let pseudonym = pseudonym_key(&sender_id.pseudonym());
+
+        let mut sets = tx.open_table(OPENER_SETS_TABLE)?;
+        let mut order = tx.open_table(OPENER_ORDER_TABLE)?;
+        let mut openers = tx.open_table(OPENERS_TABLE)?;
+
+        let Some(mut meta) = sets.get(pseudonym)?.map(|v| RingMeta::from(v.value())) else {
+            return Ok(());
+        };
+
+        let removed = openers
+            .remove(sender_id_key(&pseudonym, &sender_id.surb_id()))?
+            .map(|v| v.value().0);
+        if let Some(seq) = removed {
+            order.remove((pseudonym, seq))?;
+            meta.len = meta.len.saturating_sub(1);
+        }
+
+        meta.last_access = now;
+        sets.insert(pseudonym, <(u64, u64, u64)>::from(meta))?;
+        Ok(())
+    }
+}
+
+/// Handle of the [`Writer`] thread shared by all clones of the [`RedbSurbStore`].
+///
+/// Dropping the last handle lets the writer finish all queued operations in the background,
+/// without waiting for it. Use [`RedbSurbStore::close`] to wait until the writer is done.
+struct WriterHandle {
+    requests: mpsc::Sender<WriterRequest>,
+    durable_commit_interval: Arc<AtomicU64>,
+}
+
+impl WriterHandle {
+    fn send(&self, request: WriterRequest) -> Result<(), RedbSurbStoreError> {
+        self.requests
+            .send(request)
+            .map_err(|_| RedbSurbStoreError::WriterStopped)
+    }
+
+    fn call(
+        &self,
+        request: impl FnOnce(mpsc::SyncSender<Result<(), RedbSurbStoreError>>) -> WriterRequest,
+    ) -> Result<(), RedbSurbStoreError> {
+        let (reply_tx, reply_rx) = mpsc::sync_channel(1);
+        self.send(request(reply_tx))?;
+        reply_rx.recv().map_err(|_| RedbSurbStoreError::WriterStopped)?
+    }
+}
+
+/// Persistent [`SurbStore`] implementation backed by an embedded `redb` database.
+///
+/// The store serves all lookups and insertions from a [`MemorySurbStore`], so it applies the same
+/// [consumption policy](SurbConsumptionPolicy) and quotas, and never blocks the packet processing on disk I/O.
+/// Every mutation is additionally queued to a dedicated writer thread, which mirrors it into the database
+/// in batched ACID write transactions. The database therefore always reopens in a consistent state,
+/// even after a crash, and its content is loaded back into memory when the store is opened.
+///
+/// The capacities and lifetimes of the [`SurbStoreConfig`] are enforced on every operation and
+/// [once again](RedbSurbStore::prune) when the database is reopened, so that SURBs and reply openers that
+/// outlived their lifetime while the node was down are never used.
+///
+/// By default, every write transaction is durable. When the node crashes, only the operations
+/// still queued for the writer (typically the last few milliseconds) are lost. Use
+/// [`RedbSurbStore::with_durable_commit_interval`] to trade more of them for throughput,
+/// and [`RedbSurbStore::flush`] to wait until everything is on disk. Dropping the store does not wait
+/// for the writer, use [`RedbSurbStore::close`] to shut it down gracefully.
+///
+/// The instance can be cheaply cloned.
+#[derive(Clone)]
+pub struct RedbSurbStore {
+    memory: MemorySurbStore,
+    writer: Arc<WriterHandle>,
+    consumption_policy: SurbConsumptionPolicy,
+    clock: Arc<dyn Fn() -> Duration + Send + Sync>,
+}
+
+impl std::fmt::Debug for RedbSurbStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedbSurbStore")
+            .field("consumption_policy", &self.consumption_policy)
+            .field(
+                "durable_commit_interval",
+                &self.writer.durable_commit_interval.load(Ordering::Relaxed),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+impl RedbSurbStore {
+    /// Default number of write transactions after which a transaction is made durable.
+    pub const DEFAULT_DURABLE_COMMIT_INTERVAL: NonZeroU64 = NonZeroU64::MIN;
+
+    /// Opens (or creates) the store at the given `path` using the given configuration.
+    ///
+    /// If the database already exists, all entries violating the `cfg` are [pruned](RedbSurbStore::prune)
+    /// and the rest is loaded into memory.
+    pub fn new(path: impl AsRef<Path>, cfg: SurbStoreConfig) -> Result<Self, RedbSurbStoreError> {
+        Self::new_with_clock(path, cfg, || current_time().as_unix_timestamp())
+    }
+
+    fn new_with_clock<C>(path: impl AsRef<Path>, cfg: SurbStoreConfig, clock: C) -> Result<Self, RedbSurbStoreError>
+    where
+        C: Fn() -> Duration + Send + Sync + 'static,
+    {
+        let clock: Arc<dyn Fn() -> Duration + Send + Sync> = Arc::new(clock);
+        let db = Arc::new(redb::Database::create(path)?);
+
+        let tx = db.begin_write()?;
+        tx.open_table(SURBS_TABLE)?;
+        tx.open_table(SURB_RINGS_TABLE)?;
+        tx.open_table(OPENERS_TABLE)?;
+        tx.open_table(OPENER_ORDER_TABLE)?;
+        tx.open_table(OPENER_SETS_TABLE)?;
+        tx.commit()?;
+
+        let durable_commit_interval = Arc::new(AtomicU64::new(Self::DEFAULT_DURABLE_COMMIT_INTERVAL.get()));
+        let writer = Writer {
+            db: db.clone(),
+            limits: (&cfg).into(),
+            durable_commit_interval: durable_commit_interval.clone(),
+            commits: 0,
+            clock: clock.clone(),
+        };
+        writer.prune()?;
+
+        // Entries dropped by the in-memory store on its own are deleted from the database too
+        let (requests_tx, requests_rx) = mpsc::channel();
+        let evictions_tx = requests_tx.clone();
+        let eviction_clock = clock.clone();
+        let memory = MemorySurbStore::new_with_eviction_listener(cfg, move |eviction| {
+            let op = match eviction {
+                SurbStoreEviction::Surbs(pseudonym) => WriteOp::EvictSurbs {
+                    pseudonym: pseudonym_key(&pseudonym),
+                },
+                SurbStoreEviction::ReplyOpeners(pseudonym) => WriteOp::EvictReplyOpeners {
+                    pseudonym: pseudonym_key(&pseudonym),
+                },
+                SurbStoreEviction::ReplyOpener(sender_id) => WriteOp::RemoveReplyOpener {
+                    sender_id,
+                    now: eviction_clock().as_millis() as u64,
+                },
+            };
+            if evictions_tx.send(WriterRequest::Write(op)).is_err() {
+                tracing::error!("failed to queue eviction to the redb surb store");
+            }
+        });
+        Self::load(&db, &memory, writer.now())?;
+        drop(db);
+
+        std::thread::Builder::new()
+            .name("redb-surb-store".into())
+            .spawn(move || {
+                // Reply only once the writer has released the database
+                if let Some((reply, res)) = writer.run(requests_rx) {
+                    let _ = reply.send(res);
+                }
+            })?;
+
+        Ok(Self {
+            memory,
+            writer: Arc::new(WriterHandle {
+                requests: requests_tx,
+                durable_commit_interval,
+            }),
+            consumption_policy: cfg.consumption_policy,
+            clock,
+        })
+    }
+
+    /// Loads all SURBs and reply openers from the database into the `memory` store.
+    ///
+    /// SURBs and reply openers keep their original age, so that reply openers expire at the same
+    /// time as if the store had not been reopened.
+    fn load(db: &redb::Database, memory: &MemorySurbStore, now: u64) -> Result<(), RedbSurbStoreError> {
+        let loaded_at = Instant::now();
+        let tx = db.begin_read()?;
+
+        let rings = tx.open_table(SURB_RINGS_TABLE)?;
+        let surbs = tx.open_table(SURBS_TABLE)?;
+        for ring in rings.iter()? {
+            let key = ring?.0.value();
+            let entries = surbs
+                .range(pseudonym_range(&key))?
+                .map(|entry| {
+                    let (inserted_at, entry) = entry?.1.value();
+                    let (surb_id, surb) = entry.split_at(SURB_ID_SIZE);
+                    let surb_id: HoprSurbId = surb_id
+                        .try_into()
+                        .map_err(|_| GeneralError::ParseError("SURB ID".into()))?;
+                    let age = Duration::from_millis(now.saturating_sub(inserted_at));
+                    Ok((
+                        surb_id,
+                        HoprSurb::try_from(surb)?,
+                        loaded_at.checked_sub(age).unwrap_or(loaded_at),
+                    ))
+                })
+                .collect::<Result<Vec<_>, RedbSurbStoreError>>()?;
+            memory.insert_surbs_at(HoprPseudonym::try_from(key.as_slice())?, entries);
+        }
+
+        let order = tx.open_table(OPENER_ORDER_TABLE)?;
+        let openers = tx.open_table(OPENERS_TABLE)?;
+        for entry in order.iter()? {
+            let (k, surb_id) = entry?;
+            let (pseudonym, surb_id) = (k.value().0, surb_id.value());
+            if let Some(opener) = openers.get(sender_id_key(&pseudonym, &surb_id))? {
+                let (_, inserted_at, opener) = opener.value();
+                let sender_id =
+                    HoprSenderId::from_pseudonym_and_id(&HoprPseudonym::try_from(pseudonym.as_slice())?, surb_id);
+                let age = Duration::from_millis(now.saturating_sub(inserted_at));
+                memory.insert_reply_opener_at(
+                    sender_id,
+                    postcard::from_bytes(&opener)?,
+                    loaded_at.checked_sub(age).unwrap_or(loaded_at),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Makes only every `interval`-th write transaction durable.
+    ///
+    /// The database always stays consistent, but in case of a crash, the operations
+    /// of up to `interval - 1` last transactions can be lost.
+    ///
+    /// Default is [`RedbSurbStore::DEFAULT_DURABLE_COMMIT_INTERVAL`] (every transaction is durable).
+    pub fn with_durable_commit_interval(self, interval: NonZeroU64) -> Self {
+        self.writer
+            .durable_commit_interval
+            .store(interval.get(), Ordering::Relaxed);
+        self
+    }
+
+    /// Waits until all previously performed operations are durably written to the database.
+    ///
+    /// This blocks the calling thread and should not be called from an async context.
+    pub fn flush(&self) -> Result<(), RedbSurbStoreError> {
+        self.writer.call(WriterRequest::Flush)
+    }
+
+    /// Drops all entries that violate the lifetimes or capacities of the [`SurbStoreConfig`] from the database.
+    ///
+    /// This is done automatically when the store is opened, but it can be also called periodically
+    /// to reclaim disk space taken by pseudonyms that are no longer active.
+    ///
+    /// This blocks the calling thread and should not be called from an async context.
+    pub fn prune(&self) -> Result<(), RedbSurbStoreError> {
+        self.writer.call(WriterRequest::Prune)
+    }
+
+    /// Durably writes all previously performed operations and stops the writer, releasing the database.
+    ///
+    /// Unlike dropping the last instance of the store, this waits until the database can be opened again.
+    /// Operations performed on any instance of the store after this call are no longer persisted.
+    pub async fn close(&self) -> Result<(), RedbSurbStoreError> {
+        let (reply_tx, reply_rx) = futures::channel::oneshot::channel();
+        self.writer.send(WriterRequest::Close(reply_tx))?;
+        reply_rx.await.map_err(|_| RedbSurbStoreError::WriterStopped)?
+    }
+
+    fn now(&self) -> u64 {
+        (self.clock)().as_millis() as u64
+    }
+
+    fn write(&self, op: WriteOp) {
+        if let Err(error) = self.writer.send(WriterRequest::Write(op)) {
+            tracing::error!(%error, "failed to queue write to the redb surb store");
+        }
+    }
+}
+
+impl SurbStore for RedbSurbStore {
+    #[tracing::instrument(skip_all, level = "trace", fields(?matcher), ret)]
+    fn find_surb(&self, matcher: SurbMatcher) -> Option<FoundSurb> {
+        let found = self.memory.find_surb(matcher)?;
+        self.write(WriteOp::ConsumeSurb {
+            pseudonym: pseudonym_key(&found.sender_id.pseudonym()),
+            surb_id: found.sender_id.surb_id(),
+            from_back: self.consumption_policy == SurbConsumptionPolicy::Lifo,
+            now: self.now(),
+        });
+        Some(found)
+    }
+
+    #[tracing::instrument(skip_all, level = "trace", fields(%pseudonym, num_surbs = surbs.len()))]
+    fn insert_surbs(&self, pseudonym: HoprPseudonym, surbs: Vec<(HoprSurbId, HoprSurb)>) -> usize {
+        let len = self.memory.insert_surbs(pseudonym, surbs.clone());
+        self.write(WriteOp::InsertSurbs {
+            pseudonym: pseudonym_key(&pseudonym),
+            surbs,
+            len: len as u64,
+            now: self.now(),
+        });
+        len
+    }
+
+    #[tracing::instrument(skip_all, level = "trace", fields(%pseudonym, keep_freshest), ret)]
+    fn discard_surbs(&self, pseudonym: &HoprPseudonym, keep_freshest: usize) -> usize {
+        let discarded = self.memory.discard_surbs(pseudonym, keep_freshest);
+        self.write(WriteOp::DiscardSurbs {
+            pseudonym: pseudonym_key(pseudonym),
+            keep: keep_freshest as u64,
+            now: self.now(),
+        });
+        discarded
+    }
+
+    #[tracing::instrument(skip_all, level = "trace", fields(?sender_id))]
+    fn insert_reply_opener(&self, sender_id: HoprSenderId, opener: ReplyOpener) {
+        self.memory.insert_reply_opener(sender_id, opener.clone());
+        self.write(WriteOp::InsertReplyOpener {
+            sender_id,
+            opener,
+            now: self.now(),
+        });
+    }
+
+    #[tracing::instrument(skip_all, level = "trace", fields(?sender_id), ret)]
+    fn find_reply_opener(&self, sender_id: &HoprSenderId) -> Option<ReplyOpener> {
+        let opener = self.memory.find_reply_opener(sender_id)?;
+        self.write(WriteOp::RemoveReplyOpener {
+            sender_id: *sender_id,
+            now: self.now(),
+        });
+        Some(opener)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicU64;
+
+    use hopr_api::types::{crypto::prelude::*, crypto_random::Randomizable};
+    use hopr_crypto_packet::sphinx::prelude::SharedSecret;
+
+    use super::*;
+
+    fn random_surb() -> anyhow::Result<HoprSurb> {
+        let mut bytes = vec![0u8; HoprSurb::SIZE];
+        hopr_api::types::crypto_random::random_fill(&mut bytes);
+        Ok(HoprSurb::try_from(bytes.as_slice())?)
+    }
+
+    fn random_opener() -> ReplyOpener {
+        ReplyOpener {
+            sender_key: SecretKey16::random(),
+            shared_secrets: (0..3).map(|_| SharedSecret::random()).collect(),
+        }
+    }
+
+    /// Clock that can be moved forward by the test.
+    fn test_clock() -> (Arc<AtomicU64>, impl Fn() -> Duration + Send + Sync + Clone + 'static) {
+        let now = Arc::new(AtomicU64::new(1_000_000));
+        let now_clone = now.clone();
+        (now, move || Duration::from_millis(now_clone.load(Ordering::Relaxed)))
+    }
+
+    #[tokio::test]
+    async fn redb_surb_store_must_keep_surbs_across_restarts() -> anyhow::Result<()> {
+        let file = tempfile::NamedTempFile::new()?;
+        let pseudonym = HoprPseudonym::random();
+        let surbs = (0..3u8)
+            .map(|i| Ok(([i; 8], random_surb()?)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        {
+            let store = RedbSurbStore::new(file.path(), SurbStoreConfig::default())?;
+            assert_eq!(3, store.insert_surbs(pseudonym, surbs.clone()));
+            store.close().await?;
+        }
+
+        let store = RedbSurbStore::new(file.path(), SurbStoreConfig::default())?;
+        for (i, (id, surb)) in surbs.into_iter().enumerate() {
+            let found = store
+                .find_surb(SurbMatcher::Pseudonym(pseudonym))
+                .ok_or(anyhow::anyhow!("expected surb"))?;
+            assert_eq!(HoprSenderId::from_pseudonym_and_id(&pseudonym, id), found.sender_id);
+            assert_eq!(surb, found.surb);
+            assert_eq!(2 - i, found.remaining);
+        }
+        assert!(store.find_surb(SurbMatcher::Pseudonym(pseudonym)).is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn redb_surb_store_exact_match_must_only_check_the_oldest_surb() -> anyhow::Result<()> {
+        let file = tempfile::NamedTempFile::new()?;
+        let store = RedbSurbStore::new(file.path(), SurbStoreConfig::default())?;
+        let pseudonym = HoprPseudonym::random();
+
+        store.insert_surbs(pseudonym, vec![([1u8; 8], random_surb()?), ([2u8; 8], random_surb()?)]);
+
+        let second = HoprSenderId::from_pseudonym_and_id(&pseudonym, [2u8; 8]);
+        assert!(store.find_surb(SurbMatcher::Exact(second)).is_none());
+
+        let first = HoprSenderId::from_pseudonym_and_id(&pseudonym, [1u8; 8]);
+        let found = store
+            .find_surb(SurbMatcher::Exact(first))
+            .ok_or(anyhow::anyhow!("expected surb"))?;
+        assert_eq!(first, found.sender_id);
+        assert_eq!(1, found.remaining);
+
+        Ok(())
+    }
+
+    #[test]
+    fn redb_surb_store_must_overwrite_oldest_surbs_when_full() -> anyhow::Result<()> {
+        let file = tempfile::NamedTempFile::new()?;
+        let store = RedbSurbStore::new(file.path(), SurbStoreConfig::default())?;
+        let pseudonym = HoprPseudonym::random();
+        let capacity = SurbStoreConfig::default().rb_capacity;
+
+        let surb = random_surb()?;
+        let surbs = (0..capacity as u64 + 5)
+            .map(|i| (i.to_be_bytes(), surb.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(capacity, store.insert_surbs(pseudonym, surbs));
+
+        let found = store
+            .find_surb(SurbMatcher::Pseudonym(pseudonym))
+            .ok_or(anyhow::anyhow!("expected surb"))?;
+        assert_eq!(5u64.to_be_bytes(), found.sender_id.surb_id());
+        assert_eq!(capacity - 1, found.remaining);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn redb_surb_store_must_enforce_capacity_on_reload() -> anyhow::Result<()> {
+        let file = tempfile::NamedTempFile::new()?;
+        let pseudonym = HoprPseudonym::random();
+        let surb = random_surb()?;
+
+        {
+            let cfg = SurbStoreConfig {
+                rb_capacity: 2000,
+                ..Default::default()
+            };
+            let store = RedbSurbStore::new(file.path(), cfg)?;
+            let surbs = (0..2000u64).map(|i| (i.to_be_bytes(), surb.clone())).collect();
+            assert_eq!(2000, store.insert_surbs(pseudonym, surbs));
+            store.close().await?;
+        }
+
+        let cfg = SurbStoreConfig {
+            rb_capacity: 1024,
+            ..Default::default()
+        };
+        let store = RedbSurbStore::new(file.path(), cfg)?;
+        let found = store
+            .find_surb(SurbMatcher::Pseudonym(pseudonym))
+            .ok_or(anyhow::anyhow!("expected surb"))?;
+        assert_eq!((2000u64 - 1024).to_be_bytes(), found.sender_id.surb_id());
+        assert_eq!(1023, found.remaining);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn redb_surb_store_must_drop_expired_pseudonyms_on_reload() -> anyhow::Result<()> {
+        let file = tempfile::NamedTempFile::new()?;
+        let (now, clock) = test_clock();
+        let cfg = SurbStoreConfig::default();
+        let pseudonym = HoprPseudonym::random();
+        let sender_id = HoprSenderId::new(&pseudonym);
+
+        {
+            let store = RedbSurbStore::new_with_clock(file.path(), cfg, clock.clone())?;
+            store.insert_surbs(pseudonym, vec![([1u8; 8], random_surb()?)]);
+            store.insert_reply_opener(sender_id, random_opener());
+            store.close().await?;
+        }
+
+        now.fetch_add(cfg.pseudonyms_lifetime.as_millis() as u64 + 1, Ordering::Relaxed);
+
+        let store = RedbSurbStore::new_with_clock(file.path(), cfg, clock)?;
+        assert!(store.find_surb(SurbMatcher::Pseudonym(pseudonym)).is_none());
+        assert!(store.find_reply_opener(&sender_id).is_none());
+
+        store.close().await?;
+        let db = redb::Database::open(file.path())?;
+        let tx = db.begin_read()?;
+        assert!(tx.open_table(SURBS_TABLE)?.is_empty()?);
+        assert!(tx.open_table(SURB_RINGS_TABLE)?.is_empty()?);
+        assert!(tx.open_table(OPENERS_TABLE)?.is_empty()?);
+        assert!(tx.open_table(OPENER_ORDER_TABLE)?.is_empty()?);
+        assert!(tx.open_table(OPENER_SETS_TABLE)?.is_empty()?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn redb_surb_store_must_keep_reply_openers_across_restarts() -> anyhow::Result<()> {
+        let file = tempfile::NamedTempFile::new()?;
+        let pseudonym = HoprPseudonym::random();
+        let sender_ids = HoprSenderId::new(&pseudonym)
+            .into_sequence()
+            .take(3)
+            .collect::<Vec<_>>();
+        let openers = sender_ids.iter().map(|_| random_opener()).collect::<Vec<_>>();
+
+        {
+            let store = RedbSurbStore::new(file.path(), SurbStoreConfig::default())?;
+            for (id, opener) in sender_ids.iter().zip(openers.iter()) {
+                store.insert_reply_opener(*id, opener.clone());
+            }
+            store.close().await?;
+        }
+
+        let store = RedbSurbStore::new(file.path(), SurbStoreConfig::default())?;
+        for (id, opener) in sender_ids.iter().zip(openers.iter()) {
+            let found = store.find_reply_opener(id).ok_or(anyhow::anyhow!("expected opener"))?;
+            assert_eq!(opener.sender_key.as_ref(), found.sender_key.as_ref());
+            assert_eq!(opener.shared_secrets.len(), found.shared_secrets.len());
+
+            // Reply openers are single-use
+            assert!(store.find_reply_opener(id).is_none());
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn redb_surb_store_must_drop_expired_reply_openers_on_reload() -> anyhow::Result<()> {
+        let file = tempfile::NamedTempFile::new()?;
+        let (now, clock) = test_clock();
+        let cfg = SurbStoreConfig {
+            pseudonyms_lifetime: Duration::from_secs(7200),
+            reply_opener_lifetime: Duration::from_secs(60),
+            ..Default::default()
+        };
+
+        let pseudonym = HoprPseudonym::random();
+        let mut ids = HoprSenderId::new(&pseudonym).into_sequence();
+        let old_id = ids.next().ok_or(anyhow::anyhow!("expected id"))?;
+        let new_id = ids.next().ok_or(anyhow::anyhow!("expected id"))?;
+
+        {
+            let store = RedbSurbStore::new_with_clock(file.path(), cfg, clock.clone())?;
+            store.insert_reply_opener(old_id, random_opener());
+            now.fetch_add(30_000, Ordering::Relaxed);
+            store.insert_reply_opener(new_id, random_opener());
+            store.close().await?;
+        }
+        now.fetch_add(31_000, Ordering::Relaxed);
+
+        let store = RedbSurbStore::new_with_clock(file.path(), cfg, clock)?;
+        assert!(store.find_reply_opener(&old_id).is_none());
+        assert!(store.find_reply_opener(&new_id).is_some());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn redb_surb_store_must_keep_remaining_lifetime_of_reply_openers_on_reload() -> anyhow::Result<()> {
+        let file = tempfile::NamedTempFile::new()?;
+        let (now, clock) = test_clock();
+        let cfg = SurbStoreConfig {
+            reply_opener_lifetime: Duration::from_secs(60),
+            ..Default::default()
+        };
+        let pseudonym = HoprPseudonym::random();
+        let sender_id = HoprSenderId::new(&pseudonym);
+
+        {
+            let store = RedbSurbStore::new_with_clock(file.path(), cfg, clock.clone())?;
+            store.insert_reply_opener(sender_id, random_opener());
+            store.close().await?;
+        }
+        now.fetch_add(59_500, Ordering::Relaxed);
+
+        // Only the rest of the lifetime is left after the reload
+        let store = RedbSurbStore::new_with_clock(file.path(), cfg, clock)?;
+        // Expired entries are evicted by a timer wheel with ~1 second granularity
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert!(store.find_reply_opener(&sender_id).is_none());
+
+        // The expired opener is deleted from the database as well
+        if let Some(openers) = store.memory.pseudonym_openers.get(&pseudonym) {
+            openers.run_pending_tasks();
+        }
+        store.close().await?;
+        let db = redb::Database::open(file.path())?;
+        let tx = db.begin_read()?;
+        assert!(tx.open_table(OPENERS_TABLE)?.is_empty()?);
+        assert!(tx.open_table(OPENER_ORDER_TABLE)?.is_empty()?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn redb_surb_store_must_evict_oldest_reply_openers_when_full() -> anyhow::Result<()> {
+        let file = tempfile::NamedTempFile::new()?;
+        let cfg = SurbStoreConfig {
+            max_openers_per_pseudonym: 1000,
+            ..Default::default()
+        };
+
+        let pseudonym = HoprPseudonym::random();
+        let ids = HoprSenderId::new(&pseudonym)
+            .into_sequence()
+            .take(1001)
+            .collect::<Vec<_>>();
+        let opener = random_opener();
+
+        {
+            let store = RedbSurbStore::new(file.path(), cfg)?;
+            for id in &ids {
+                store.insert_reply_opener(*id, opener.clone());
+            }
+            store.close().await?;
+        }
+
+        let store = RedbSurbStore::new(file.path(), cfg)?;
+        assert!(store.find_reply_opener(&ids[0]).is_none());
+        assert!(store.find_reply_opener(&ids[1]).is_some());
+        assert!(store.find_reply_opener(&ids[1000]).is_some());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn redb_surb_store_must_persist_surbs_consumed_by_policy() -> anyhow::Result<()> {
+        let file = tempfile::NamedTempFile::new()?;
+        let cfg = SurbStoreConfig {
+            consumption_policy: SurbConsumptionPolicy::Lifo,
+            ..Default::default()
+        };
+        let pseudonym = HoprPseudonym::random();
+        let surbs = (0..3u8)
+            .map(|i| Ok(([i; 8], random_surb()?)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        {
+            let store = RedbSurbStore::new(file.path(), cfg)?;
+            store.insert_surbs(pseudonym, surbs);
+
+            let found = store
+                .find_surb(SurbMatcher::Pseudonym(pseudonym))
+                .ok_or(anyhow::anyhow!("expected surb"))?;
+            assert_eq!([2u8; 8], found.sender_id.surb_id());
+            store.close().await?;
+        }
+
+        let store = RedbSurbStore::new(file.path(), cfg)?;
+        let found = store
+            .find_surb(SurbMatcher::Pseudonym(pseudonym))
+            .ok_or(anyhow::anyhow!("expected surb"))?;
+        assert_eq!([1u8; 8], found.sender_id.surb_id());
+        assert_eq!(1, found.remaining);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn redb_surb_store_must_persist_discarded_surbs() -> anyhow::Result<()> {
+        let file = tempfile::NamedTempFile::new()?;
+        let pseudonym = HoprPseudonym::random();
+
+        {
+            let store = RedbSurbStore::new(file.path(), SurbStoreConfig::default())?;
+            let surbs = (0..3u8)
+                .map(|i| Ok(([i; 8], random_surb()?)))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            store.insert_surbs(pseudonym, surbs);
+            assert_eq!(2, store.discard_surbs(&pseudonym, 1));
+            store.close().await?;
+        }
+
+        let store = RedbSurbStore::new(file.path(), SurbStoreConfig::default())?;
+        let found = store
+            .find_surb(SurbMatcher::Pseudonym(pseudonym))
+            .ok_or(anyhow::anyhow!("expected surb"))?;
+        assert_eq!([2u8; 8], found.sender_id.surb_id());
+        assert_eq!(0, found.remaining);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn redb_surb_store_flush_must_write_all_queued_operations() -> anyhow::Result<()> {
+        let file = tempfile::NamedTempFile::new()?;
+        let store = RedbSurbStore::new(file.path(), SurbStoreConfig::default())?
+            .with_durable_commit_interval(NonZeroU64::new(1000).ok_or(anyhow::anyhow!("invalid interval"))?);
+        let pseudonym = HoprPseudonym::random();
+        let sender_id = HoprSenderId::new(&pseudonym);
+
+        let surbs = (0..10u64)
+            .map(|i| Ok((i.to_be_bytes(), random_surb()?)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        store.insert_surbs(pseudonym, surbs);
+        store.find_surb(SurbMatcher::Pseudonym(pseudonym));
+        store.insert_reply_opener(sender_id, random_opener());
+        store.flush()?;
+
+        store.close().await?;
+        let db = redb::Database::open(file.path())?;
+        let tx = db.begin_read()?;
+        assert_eq!(9, tx.open_table(SURBS_TABLE)?.len()?);
+        assert_eq!(
+            Some((9, 10)),
+            tx.open_table(SURB_RINGS_TABLE)?
+                .get(pseudonym_key(&pseudonym))?
+                .map(|v| RingMeta::from(v.value()))
+                .map(|meta| (meta.len, meta.next_seq))
+        );
+        assert_eq!(1, tx.open_table(OPENERS_TABLE)?.len()?);
+
+        Ok(())
+    }
+}
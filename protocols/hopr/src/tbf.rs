@@ -1,4 +1,6 @@
-use hopr_api::types::{crypto::types::PacketTag, crypto_random::random_bytes};
+use std::collections::VecDeque;
+
+use hopr_api::types::{crypto::types::PacketTag, crypto_random::random_bytes, primitive::errors::GeneralError};
 
 /// Bloom filter for packet tags to detect packet replays.
 ///
@@ -92,6 +94,356 @@ impl Default for TagBloomFilter {
     }
 }
 
+/// Single generation of the [`RotatingTagBloomFilter`].
+#[derive(Debug, Clone)]
+struct BloomGeneration {
+    bloom: bloomfilter::Bloom<PacketTag>,
+    count: usize,
+}
+
+impl BloomGeneration {
+    fn new(capacity: usize, fp_rate: f64) -> Self {
+        Self {
+            bloom: bloomfilter::Bloom::new_for_fp_rate_with_seed(capacity, fp_rate, &random_bytes())
+                .expect("bloom filter with the specified capacity is constructible"),
+            count: 0,
+        }
+    }
+}
+
+/// Empty generation of a [`RotatingTagBloomFilter`] allocated ahead of time.
+///
+/// See [`RotatingTagBloomFilter::request_spare`].
+#[derive(Debug)]
+pub struct SpareGeneration {
+    generation: BloomGeneration,
+    generation_capacity: usize,
+    max_generations: usize,
+}
+
+/// Allocates a [`SpareGeneration`] for a [`RotatingTagBloomFilter`] without access to the filter.
+#[derive(Clone, Copy, Debug)]
+pub struct SpareAllocator {
+    generation_capacity: usize,
+    max_generations: usize,
+}
+
+impl SpareAllocator {
+    /// Allocates the empty generation.
+    pub fn allocate(self) -> SpareGeneration {
+        SpareGeneration {
+            generation: BloomGeneration::new(
+                self.generation_capacity,
+                RotatingTagBloomFilter::FALSE_POSITIVE_RATE / self.max_generations as f64,
+            ),
+            generation_capacity: self.generation_capacity,
+            max_generations: self.max_generations,
+        }
+    }
+}
+
+/// Multi-generation Bloom filter for packet tags to detect packet replays.
+///
+/// Unlike the [`TagBloomFilter`], this filter is never cleared as a whole.
+/// The tags are inserted into the newest generation, and once it becomes full, a new empty
+/// generation is started. When the maximum number of generations is exceeded, the oldest one is dropped.
+///
+/// This way, the filter always remembers at least `(generations - 1) * generation_capacity`
+/// most recent tags, so that packet replays of any of these are always detected.
+///
+/// Allocating a new generation is expensive, so the filter can hold a [spare](RotatingTagBloomFilter::request_spare)
+/// generation allocated ahead of time, which is then used by the next rotation.
+/// The spare is only requested once the newest generation is almost full.
+///
+/// By default, the filter has 4 generations of 2 000 000 tags each, so that together with the spare
+/// it never holds more than the 10 000 000 tags of the [`TagBloomFilter`]. This amounts to about 34 MB
+/// (slightly more than the 30 MB of the [`TagBloomFilter`], because each generation must have a lower
+/// false positive rate), while the 6 000 000 most recent tags are always remembered.
+///
+/// The state of the filter can be persisted using [`RotatingTagBloomFilter::to_bytes`]
+/// and restored using [`RotatingTagBloomFilter::from_bytes`].
+#[derive(Debug)]
+pub struct RotatingTagBloomFilter {
+    generations: VecDeque<BloomGeneration>,
+    generation_capacity: usize,
+    max_generations: usize,
+    spare: Option<BloomGeneration>,
+    spare_requested: bool,
+}
+
+impl RotatingTagBloomFilter {
+    // The default number of generations.
+    // With the default generation capacity, at least 6 000 000 most recent packet tags are always remembered.
+    const DEFAULT_GENERATIONS: usize = 4;
+    // The default number of packet tags a single generation can hold.
+    // Together with the spare generation, the default filter holds at most 10 000 000 packet tags.
+    const DEFAULT_GENERATION_CAPACITY: usize = 2_000_000;
+    // Allowed false positive rate of the entire filter. This amounts to 0.001% chance
+    const FALSE_POSITIVE_RATE: f64 = 0.00001_f64;
+    // Maximum number of generations.
+    const MAX_GENERATIONS: usize = 64;
+    // Maximum number of packet tags a single generation can hold (about 400 MB per generation).
+    const MAX_GENERATION_CAPACITY: usize = 100_000_000;
+    // Minimum number of generations, so that there's always at least one full generation of history.
+    const MIN_GENERATIONS: usize = 2;
+    // Fill level (in percent) of the newest generation from which the spare generation is requested.
+    const SPARE_THRESHOLD_PERCENT: usize = 90;
+    // Identifies the serialized state.
+    const STATE_MAGIC: &'static [u8; 4] = b"RTBF";
+    const STATE_VERSION: u8 = 1;
+
+    /// Creates a new filter with the given capacity of a single generation and the maximum
+    /// number of generations.
+    ///
+    /// The number of generations is always between 2 and 64, and the generation capacity is between 1
+    /// and 100 000 000.
+    pub fn new(generation_capacity: usize, generations: usize) -> Self {
+        let max_generations = generations.clamp(Self::MIN_GENERATIONS, Self::MAX_GENERATIONS);
+        let mut ret = Self {
+            generations: VecDeque::with_capacity(max_generations + 1),
+            generation_capacity: generation_capacity.clamp(1, Self::MAX_GENERATION_CAPACITY),
+            max_generations,
+            spare: None,
+            spare_requested: false,
+        };
+        ret.rotate();
+        ret
+    }
+
+    /// Returns the current number of items in all generations of this filter.
+    pub fn count(&self) -> usize {
+        self.generations.iter().map(|g| g.count).sum()
+    }
+
+    /// Returns the maximum number of items this filter can hold at once.
+    pub fn capacity(&self) -> usize {
+        self.generation_capacity * self.max_generations
+    }
+
+    /// Returns the number of most recent items that are always guaranteed to be remembered.
+    pub fn guaranteed_history(&self) -> usize {
+        self.generation_capacity * (self.max_generations - 1)
+    }
+
+    /// Returns the current number of generations.
+    pub fn generations(&self) -> usize {
+        self.generations.len()
+    }
+
+    /// Puts a packet tag into the filter.
+    pub fn set(&mut self, tag: &PacketTag) {
+        self.current_generation().bloom.set(tag);
+        self.current_generation().count += 1;
+    }
+
+    /// Check if the packet tag is in the filter.
+    ///
+    /// Returns `true` if the given `tag` was already present in any of the generations.
+    /// False positives are possible.
+    pub fn check(&self, tag: &PacketTag) -> bool {
+        // Most replays are of recent packets, so check the newest generations first
+        self.generations.iter().rev().any(|g| g.bloom.check(tag))
+    }
+
+    /// Checks and sets a packet tag (if not present) in a single operation.
+    ///
+    /// Returns `true` if the given `tag` was already present in the filter.
+    /// False positives are possible.
+    pub fn check_and_set(&mut self, tag: &PacketTag) -> bool {
+        let is_present = self.check(tag);
+        if !is_present {
+            self.set(tag);
+        }
+        is_present
+    }
+
+    /// Returns an allocator of the next generation if the newest generation is almost full
+    /// and the filter holds no spare generation yet.
+    ///
+    /// Only a single allocator is returned until its generation is [installed](RotatingTagBloomFilter::install_spare).
+    /// This allows allocating the generation outside a lock guarding the filter.
+    pub fn request_spare(&mut self) -> Option<SpareAllocator> {
+        let almost_full = self.generations.back().is_none_or(|g| {
+            g.count.saturating_mul(100) >= self.generation_capacity.saturating_mul(Self::SPARE_THRESHOLD_PERCENT)
+        });
+        (almost_full && self.spare.is_none() && !self.spare_requested).then(|| {
+            self.spare_requested = true;
+            SpareAllocator {
+                generation_capacity: self.generation_capacity,
+                max_generations: self.max_generations,
+            }
+        })
+    }
+
+    /// Installs the spare generation to be used by the next rotation.
+    ///
+    /// The generation is discarded if the filter already holds one or if it was allocated for a filter
+    /// with different parameters.
+    pub fn install_spare(&mut self, spare: SpareGeneration) {
+        self.spare_requested = false;
+        if self.spare.is_none()
+            && spare.generation_capacity == self.generation_capacity
+            && spare.max_generations == self.max_generations
+        {
+            self.spare = Some(spare.generation);
+        }
+    }
+
+    /// Copies the state of the filter that is serialized by [`RotatingTagBloomFilter::to_bytes`].
+    ///
+    /// This allows serializing the state outside a lock guarding the filter. The spare generation is not copied.
+    pub fn snapshot(&self) -> Self {
+        Self {
+            generations: self.generations.clone(),
+            generation_capacity: self.generation_capacity,
+            max_generations: self.max_generations,
+            spare: None,
+            spare_requested: false,
+        }
+    }
+
+    /// Serializes the entire state of the filter, so it can be restored via [`RotatingTagBloomFilter::from_bytes`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut ret = Vec::with_capacity(
+            Self::STATE_MAGIC.len()
+                + 21
+                + self
+                    .generations
+                    .iter()
+                    .map(|g| 16 + g.bloom.as_slice().len())
+                    .sum::<usize>(),
+        );
+        ret.extend_from_slice(Self::STATE_MAGIC);
+        ret.push(Self::STATE_VERSION);
+        ret.extend_from_slice(&(self.generation_capacity as u64).to_be_bytes());
+        ret.extend_from_slice(&(self.max_generations as u64).to_be_bytes());
+        ret.extend_from_slice(&(self.generations.len() as u32).to_be_bytes());
+        for generation in &self.generations {
+            let bloom = generation.bloom.as_slice();
+            ret.extend_from_slice(&(generation.count as u64).to_be_bytes());
+            ret.extend_from_slice(&(bloom.len() as u64).to_be_bytes());
+            ret.extend_from_slice(bloom);
+        }
+        ret
+    }
+
+    /// Restores the filter from the state previously produced by [`RotatingTagBloomFilter::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, GeneralError> {
+        let mut reader = StateReader(bytes);
+
+        if reader.take(Self::STATE_MAGIC.len())? != Self::STATE_MAGIC {
+            return Err(GeneralError::ParseError("invalid replay filter state header".into()));
+        }
+        if reader.take(1)?[0] != Self::STATE_VERSION {
+            return Err(GeneralError::ParseError(
+                "unsupported replay filter state version".into(),
+            ));
+        }
+
+        let generation_capacity = reader.take_u64()?;
+        let max_generations = reader.take_u64()?;
+        let num_generations = u32::from_be_bytes(reader.take_array()?) as usize;
+        if !(1..=Self::MAX_GENERATION_CAPACITY as u64).contains(&generation_capacity)
+            || !(Self::MIN_GENERATIONS as u64..=Self::MAX_GENERATIONS as u64).contains(&max_generations)
+            || num_generations as u64 > max_generations
+        {
+            return Err(GeneralError::ParseError("invalid replay filter parameters".into()));
+        }
+        let (generation_capacity, max_generations) = (generation_capacity as usize, max_generations as usize);
+
+        // Each generation takes at least 16 bytes for its count and length
+        if reader.0.len() < num_generations * 16 {
+            return Err(GeneralError::ParseError("replay filter state is truncated".into()));
+        }
+
+        let mut generations = VecDeque::with_capacity(max_generations + 1);
+        for _ in 0..num_generations {
+            let count = reader.take_u64()? as usize;
+            let len = reader.take_u64()? as usize;
+            let bloom = bloomfilter::Bloom::from_slice(reader.take(len)?)
+                .map_err(|e| GeneralError::ParseError(format!("invalid replay filter generation: {e}")))?;
+            if count > generation_capacity {
+                return Err(GeneralError::ParseError(
+                    "replay filter generation over capacity".into(),
+                ));
+            }
+            generations.push_back(BloomGeneration { bloom, count });
+        }
+
+        if !reader.0.is_empty() {
+            return Err(GeneralError::ParseError("trailing bytes in replay filter state".into()));
+        }
+
+        let mut ret = Self {
+            generations,
+            generation_capacity,
+            max_generations,
+            spare: None,
+            spare_requested: false,
+        };
+        if ret.generations.is_empty() {
+            ret.rotate();
+        }
+        Ok(ret)
+    }
+
+    fn current_generation(&mut self) -> &mut BloomGeneration {
+        if self
+            .generations
+            .back()
+            .is_none_or(|g| g.count >= self.generation_capacity)
+        {
+            self.rotate();
+        }
+        self.generations
+            .back_mut()
+            .expect("there is always at least one generation after rotation")
+    }
+
+    fn rotate(&mut self) {
+        let next = self.spare.take().unwrap_or_else(|| {
+            BloomGeneration::new(
+                self.generation_capacity,
+                Self::FALSE_POSITIVE_RATE / self.max_generations as f64,
+            )
+        });
+        self.generations.push_back(next);
+        if self.generations.len() > self.max_generations {
+            tracing::debug!("dropping the oldest generation of the replay filter");
+            self.generations.pop_front();
+        }
+    }
+}
+
+impl Default for RotatingTagBloomFilter {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_GENERATION_CAPACITY, Self::DEFAULT_GENERATIONS)
+    }
+}
+
+struct StateReader<'a>(&'a [u8]);
+
+impl<'a> StateReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], GeneralError> {
+        if self.0.len() < len {
+            return Err(GeneralError::ParseError("replay filter state is truncated".into()));
+        }
+        let (ret, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(ret)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], GeneralError> {
+        self.take(N)?
+            .try_into()
+            .map_err(|_| GeneralError::ParseError("replay filter state is truncated".into()))
+    }
+
+    fn take_u64(&mut self) -> Result<u64, GeneralError> {
+        Ok(u64::from_be_bytes(self.take_array()?))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use hopr_api::types::crypto::types::PACKET_TAG_LENGTH;
@@ -168,4 +520,155 @@ mod tests {
         // but it was cleared, so for a small capacity they should likely be gone
         // (unless there is a collision).
     }
+
+    #[test]
+    fn rotating_tag_bloom_filter_must_remember_at_least_one_full_generation() {
+        let mut filter = RotatingTagBloomFilter::new(100, 2);
+        assert!(!filter.check_and_set(&ZEROS_TAG));
+
+        // Fill up the rest of the first generation and the entire second generation
+        for i in 1..filter.capacity() as u64 {
+            let mut tag = [0xaa; PACKET_TAG_LENGTH];
+            tag[..8].copy_from_slice(&i.to_be_bytes());
+            assert!(!filter.check_and_set(&tag));
+
+            // Unlike the TagBloomFilter, the entry does not disappear when a generation fills up
+            assert!(filter.check(&ZEROS_TAG));
+        }
+        assert_eq!(2, filter.generations());
+        assert_eq!(filter.capacity(), filter.count());
+
+        // Next insertion drops the oldest generation containing the first entry
+        assert!(!filter.check_and_set(&ONES_TAG));
+        assert_eq!(2, filter.generations());
+        assert_eq!(filter.generation_capacity + 1, filter.count());
+        assert!(filter.check(&ONES_TAG));
+        assert!(!filter.check(&ZEROS_TAG));
+    }
+
+    #[test]
+    fn rotating_tag_bloom_filter_must_never_forget_recent_tags() {
+        let mut filter = RotatingTagBloomFilter::new(50, 3);
+        let tags = (0..1000u64)
+            .map(|i| {
+                let mut tag = [0x55; PACKET_TAG_LENGTH];
+                tag[..8].copy_from_slice(&i.to_be_bytes());
+                tag
+            })
+            .collect::<Vec<_>>();
+
+        for (i, tag) in tags.iter().enumerate() {
+            assert!(!filter.check_and_set(tag));
+            assert!(filter.generations() <= 3);
+
+            // All the tags within the guaranteed history must be detected as replays
+            let history_start = (i + 1).saturating_sub(filter.guaranteed_history());
+            assert!(tags[history_start..=i].iter().all(|t| filter.check(t)));
+        }
+    }
+
+    #[test]
+    fn rotating_tag_bloom_filter_must_restore_from_saved_state() -> anyhow::Result<()> {
+        let mut filter = RotatingTagBloomFilter::new(10, 3);
+        for i in 0..25u8 {
+            filter.set(&[i; PACKET_TAG_LENGTH]);
+        }
+
+        let mut restored = RotatingTagBloomFilter::from_bytes(&filter.to_bytes())?;
+        assert_eq!(filter.count(), restored.count());
+        assert_eq!(filter.capacity(), restored.capacity());
+        assert_eq!(filter.generations(), restored.generations());
+        assert!((0..25u8).all(|i| restored.check(&[i; PACKET_TAG_LENGTH])));
+
+        // The restored filter continues rotating where the original one stopped
+        for i in 25..35u8 {
+            assert!(!restored.check_and_set(&[i; PACKET_TAG_LENGTH]));
+        }
+        assert_eq!(3, restored.generations());
+        assert_eq!(25, restored.count());
+        assert!((10..35u8).all(|i| restored.check(&[i; PACKET_TAG_LENGTH])));
+
+        Ok(())
+    }
+
+    #[test]
+    fn rotating_tag_bloom_filter_must_reject_invalid_state() {
+        let state = RotatingTagBloomFilter::new(10, 2).to_bytes();
+
+        assert!(RotatingTagBloomFilter::from_bytes(&[]).is_err());
+        assert!(RotatingTagBloomFilter::from_bytes(&state[..state.len() - 1]).is_err());
+
+        let mut invalid_magic = state.clone();
+        invalid_magic[0] ^= 0xff;
+        assert!(RotatingTagBloomFilter::from_bytes(&invalid_magic).is_err());
+
+        let mut trailing = state;
+        trailing.push(0);
+        assert!(RotatingTagBloomFilter::from_bytes(&trailing).is_err());
+    }
+
+    #[test]
+    fn rotating_tag_bloom_filter_must_reject_oversized_parameters() {
+        let state = RotatingTagBloomFilter::new(10, 2).to_bytes();
+
+        let mut huge_capacity = state.clone();
+        huge_capacity[5..13].copy_from_slice(&u64::MAX.to_be_bytes());
+        assert!(RotatingTagBloomFilter::from_bytes(&huge_capacity).is_err());
+
+        let mut huge_generations = state.clone();
+        huge_generations[13..21].copy_from_slice(&u64::MAX.to_be_bytes());
+        assert!(RotatingTagBloomFilter::from_bytes(&huge_generations).is_err());
+
+        let mut many_generations = state;
+        many_generations[13..21].copy_from_slice(&64u64.to_be_bytes());
+        many_generations[21..25].copy_from_slice(&64u32.to_be_bytes());
+        assert!(RotatingTagBloomFilter::from_bytes(&many_generations).is_err());
+    }
+
+    #[test]
+    fn rotating_tag_bloom_filter_must_rotate_into_installed_spare() -> anyhow::Result<()> {
+        let mut filter = RotatingTagBloomFilter::new(10, 2);
+
+        // The spare is not needed until the newest generation is almost full
+        for i in 0..8u8 {
+            filter.set(&[i; PACKET_TAG_LENGTH]);
+            assert!(filter.request_spare().is_none());
+        }
+        filter.set(&[8; PACKET_TAG_LENGTH]);
+
+        let allocator = filter.request_spare().ok_or(anyhow::anyhow!("must request spare"))?;
+        assert!(
+            filter.request_spare().is_none(),
+            "only one spare must be requested at a time"
+        );
+
+        // A spare allocated for a filter with different parameters must be discarded
+        let mut other_filter = RotatingTagBloomFilter::new(20, 2);
+        for i in 0..18u8 {
+            other_filter.set(&[i; PACKET_TAG_LENGTH]);
+        }
+        let other_allocator = other_filter
+            .request_spare()
+            .ok_or(anyhow::anyhow!("must request spare"))?;
+        filter.install_spare(other_allocator.allocate());
+        assert!(filter.spare.is_none());
+
+        filter.install_spare(allocator.allocate());
+        assert!(filter.spare.is_some());
+        assert!(filter.request_spare().is_none());
+
+        for i in 9..11u8 {
+            filter.set(&[i; PACKET_TAG_LENGTH]);
+        }
+        assert_eq!(2, filter.generations());
+        assert!(filter.spare.is_none());
+        assert!(filter.request_spare().is_none());
+
+        for i in 11..20u8 {
+            filter.set(&[i; PACKET_TAG_LENGTH]);
+        }
+        assert!(filter.request_spare().is_some());
+
+        Ok(())
+    }
 }
@@ -29,6 +29,15 @@ pub trait SurbStore {
     /// ones.
     fn insert_surbs(&self, pseudonym: HoprPseudonym, surbs: Vec<(HoprSurbId, HoprSurb)>) -> usize;
 
+    /// Drops the SURBs associated with the given [`pseudonym`](HoprPseudonym), except for
+    /// the `keep_freshest` most recently inserted ones.
+    ///
+    /// This is used by the replying side when the return path the older SURBs were created for
+    /// is no longer valid, e.g. when a Session has been resumed over a different path.
+    ///
+    /// Returns the number of SURBs that were dropped.
+    fn discard_surbs(&self, pseudonym: &HoprPseudonym, keep_freshest: usize) -> usize;
+
     /// Stores the given [`opener`](ReplyOpener) for the given [`sender_id`](HoprSenderId).
     ///
     /// This is done by the sending side, when it creates a packet containing a SURB to be delivered
@@ -27,13 +27,27 @@
 //!    and re-grows on delivery).
 //! 5. **The anti-grief ⇄ throughput trade is the client's dial** (`FlowControlConfig`), set explicitly — SURB supply is
 //!    never silently widened for speed.
+//!
+//! # Congestion controllers
+//!
+//! The window algorithm is pluggable via the [`CongestionController`] trait and selected by
+//! [`FlowControlConfig::algorithm`]:
+//!
+//! * [`WindowController`] — loss-based AIMD (the default).
+//! * [`DelayBasedController`] — Vegas/BBR-style: estimates the base RTT and the delivery rate from the honest clock and
+//!   holds the window where the path queues stay short, instead of probing until loss. Better suited to long multi-hop
+//!   paths with mixer delay, where AIMD keeps overshooting and collapsing.
+//!
+//! Every controller obeys the invariants above: only acknowledged bytes may grow the window, while
+//! loss, queueing delay and SURB backoff may only shrink it.
 
 use std::{
+    collections::VecDeque,
     sync::{
         Arc,
         atomic::{AtomicU64, Ordering},
     },
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 /// How the send window learns that data was delivered (its "honest clock").
@@ -51,6 +65,16 @@ pub enum FlowControlMode {
     Segmentation,
 }
 
+/// Congestion-control algorithm driving the send window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CongestionControlAlgorithm {
+    /// Loss-based additive-increase/multiplicative-decrease, see [`WindowController`].
+    #[default]
+    Aimd,
+    /// Delay-based (Vegas/BBR-style) control, see [`DelayBasedController`].
+    DelayBased,
+}
+
 /// Client-tunable flow-control parameters. Defaults are deliberately conservative
 /// (anti-grief-preserving): the window starts at the floor and only grows on proven delivery, and
 /// the opt-in robustness knobs are off. This is the **clean** profile.
@@ -63,6 +87,9 @@ pub struct FlowControlConfig {
     /// Honest-clock mode. Default [`FlowControlMode::Reliable`].
     pub mode: FlowControlMode,
 
+    /// Congestion controller driving the window. Default [`CongestionControlAlgorithm::Aimd`].
+    pub algorithm: CongestionControlAlgorithm,
+
     /// Minimum in-flight bytes that are always admitted, regardless of delivery feedback or SURB
     /// supply. Guarantees the duplex socket never deadlocks (acks/keep-alives can always flow) and
     /// is the hard floor a malicious peer can never push the window below — but also never above
@@ -107,6 +134,12 @@ pub struct FlowControlConfig {
     /// frame leaves a gap → stream corruption).
     #[default(2)]
     pub frame_retries: u32,
+
+    /// Bytes the [`DelayBasedController`] tolerates queued along the path. The window grows while
+    /// the estimated queue is below half of this and shrinks once it exceeds it. Ignored by the
+    /// AIMD controller. Default 16 KiB.
+    #[default(16 * 1024)]
+    pub queue_target_size: usize,
 }
 
 impl FlowControlConfig {
@@ -117,6 +150,7 @@ impl FlowControlConfig {
         let min_window_size = self.min_window_size.max(1);
         FlowControlConfig {
             mode: self.mode,
+            algorithm: self.algorithm,
             min_window_size,
             max_window_size: self.max_window_size.max(min_window_size),
             ai_step: self.ai_step.max(1),
@@ -130,6 +164,15 @@ impl FlowControlConfig {
             // At least one retry: under reliable-mode flow control an abandoned frame leaves a gap
             // (stream corruption), so `frame_retries` must never clamp the retry budget to 0.
             frame_retries: self.frame_retries.max(1),
+            queue_target_size: self.queue_target_size.max(1),
+        }
+    }
+
+    /// Creates the congestion controller selected by [`algorithm`](Self::algorithm).
+    pub fn controller(self) -> Box<dyn CongestionController> {
+        match self.algorithm {
+            CongestionControlAlgorithm::Aimd => Box::new(WindowController::new(self)),
+            CongestionControlAlgorithm::DelayBased => Box::new(DelayBasedController::new(self)),
         }
     }
 
@@ -211,6 +254,43 @@ pub trait SupplyConstraint {
     fn backoff_hint(&self) -> Option<Backoff>;
 }
 
+/// A send-window congestion controller.
+///
+/// Implementations hold no I/O and take the current time as an argument, so they are deterministic
+/// under test. They must uphold the trust-model invariants: only [`apply_delivery`](Self::apply_delivery)
+/// with non-zero `acked_bytes` may grow the window; losses and backoffs may only shrink it, and the
+/// window always stays within `[min_window_size, max_window_size]`.
+pub trait CongestionController: Send + std::fmt::Debug {
+    /// Current window size in bytes.
+    fn window(&self) -> usize;
+
+    /// Current in-flight bytes (sent, not yet retired).
+    fn inflight(&self) -> usize;
+
+    /// The configured minimum window (duplex floor / persist-probe size).
+    fn min_window_size(&self) -> usize;
+
+    /// Records `bytes` admitted onto the wire at `now`. Never grows the window.
+    fn on_sent(&mut self, bytes: usize, now: Instant);
+
+    /// Applies a batched [`Delivered`] observation from a [`DeliverySignal`] made at `now`.
+    fn apply_delivery(&mut self, delivered: Delivered, now: Instant);
+
+    /// Applies a SURB-supply backoff. Down-only.
+    fn apply_backoff(&mut self, backoff: Backoff);
+
+    /// Effective window against a raw ceiling value (bytes): `min(window, ceiling)`, but never below
+    /// `min_window_size`. The ceiling does not mutate the window.
+    fn effective_window_for(&self, ceiling: usize) -> usize {
+        self.window().min(ceiling).max(self.min_window_size())
+    }
+
+    /// Bytes admissible now against a raw ceiling value: `effective_window − inflight` (saturating).
+    fn admissible_for(&self, ceiling: usize) -> usize {
+        self.effective_window_for(ceiling).saturating_sub(self.inflight())
+    }
+}
+
 /// Pure AIMD send-window controller. Holds no I/O; every state transition is a plain method so the
 /// invariants are exhaustively unit-testable. Byte-based (not frame-based) so it is agnostic to
 /// frame sizing.
@@ -348,6 +428,234 @@ impl WindowController {
     }
 }
 
+impl CongestionController for WindowController {
+    fn window(&self) -> usize {
+        self.cwnd
+    }
+
+    fn inflight(&self) -> usize {
+        self.inflight
+    }
+
+    fn min_window_size(&self) -> usize {
+        self.cfg.min_window_size
+    }
+
+    fn on_sent(&mut self, bytes: usize, _now: Instant) {
+        WindowController::on_sent(self, bytes)
+    }
+
+    fn apply_delivery(&mut self, delivered: Delivered, _now: Instant) {
+        WindowController::apply_delivery(self, delivered)
+    }
+
+    fn apply_backoff(&mut self, backoff: Backoff) {
+        WindowController::apply_backoff(self, backoff)
+    }
+}
+
+/// Delay-based (Vegas/BBR-style) send-window controller.
+///
+/// The honest clock retires bytes in the order they were sent, so each acknowledgement is matched
+/// against the send time of the bytes it retires to obtain an RTT sample. Once per round (a window's
+/// worth of acknowledged bytes) the controller computes:
+///
+/// * the **base RTT**: the minimum of the per-round mean RTTs over the last 10 seconds. Averaging over a round filters
+///   out the random per-packet mixer delay, which a plain minimum would mistake for queueing.
+/// * the **delivery rate**: the maximum rate of acknowledged bytes over the last rounds.
+/// * the **queue estimate**: `window × (mean_rtt − base_rtt) / mean_rtt`, the bytes sitting in path queues.
+///
+/// The window grows by `ai_step` while the queue estimate is below half of `queue_target_size`,
+/// shrinks by `ai_step` when it exceeds `queue_target_size`, and holds otherwise. On loss, it
+/// decreases multiplicatively, but not below the bandwidth-delay product proven by the delivery
+/// rate, so a single loss does not collapse a window the path has been shown to carry.
+#[derive(Clone, Debug)]
+pub struct DelayBasedController {
+    cfg: FlowControlConfig,
+    cwnd: usize,
+    inflight: usize,
+    /// Total bytes ever sent.
+    sent_total: u64,
+    /// Total bytes ever retired (delivered or lost).
+    retired_total: u64,
+    /// Send times of in-flight bytes as `(sent_total after the send, time)`, oldest first.
+    send_times: VecDeque<(u64, Instant)>,
+    /// Start of the current round: the first send, then the end of the previous round.
+    round_start: Option<Instant>,
+    /// Bytes acknowledged in the current round.
+    round_acked: usize,
+    /// Sum and count of RTT samples taken in the current round.
+    round_rtt_sum: Duration,
+    round_rtt_samples: u32,
+    /// Lowest per-round mean RTT and when it was measured.
+    base_rtt: Option<(Duration, Instant)>,
+    /// Delivery rates (bytes/s) of the most recent rounds.
+    delivery_rates: VecDeque<f64>,
+}
+
+impl DelayBasedController {
+    /// How long a base RTT measurement stays valid before a higher one may replace it.
+    const BASE_RTT_WINDOW: Duration = Duration::from_secs(10);
+    /// Number of recent rounds the delivery-rate maximum is taken over.
+    const RATE_ROUNDS: usize = 10;
+
+    /// Creates a controller with the window at the floor (`min_window_size`).
+    pub fn new(cfg: FlowControlConfig) -> Self {
+        let cfg = cfg.normalized();
+        Self {
+            cwnd: cfg.min_window_size,
+            inflight: 0,
+            sent_total: 0,
+            retired_total: 0,
+            send_times: VecDeque::new(),
+            round_start: None,
+            round_acked: 0,
+            round_rtt_sum: Duration::ZERO,
+            round_rtt_samples: 0,
+            base_rtt: None,
+            delivery_rates: VecDeque::with_capacity(Self::RATE_ROUNDS),
+            cfg,
+        }
+    }
+
+    /// The current base RTT estimate, if any round has completed.
+    pub fn base_rtt(&self) -> Option<Duration> {
+        self.base_rtt.map(|(rtt, _)| rtt)
+    }
+
+    /// The highest delivery rate (bytes/s) over the recent rounds, if any round has completed.
+    pub fn delivery_rate(&self) -> Option<f64> {
+        self.delivery_rates.iter().copied().reduce(f64::max)
+    }
+
+    /// Bandwidth-delay product proven by the honest clock: `delivery_rate × base_rtt`.
+    fn proven_bdp(&self) -> usize {
+        match (self.delivery_rate(), self.base_rtt()) {
+            (Some(rate), Some(rtt)) => (rate * rtt.as_secs_f64()) as usize,
+            _ => 0,
+        }
+    }
+
+    /// Retires `bytes` from the send-time queue, returning the send time of the newest retired byte.
+    fn retire(&mut self, bytes: usize) -> Option<Instant> {
+        self.inflight = self.inflight.saturating_sub(bytes);
+        self.retired_total = self.retired_total.saturating_add(bytes as u64).min(self.sent_total);
+        let mut sent_at = None;
+        while let Some(&(end, at)) = self.send_times.front() {
+            if end > self.retired_total {
+                break;
+            }
+            sent_at = Some(at);
+            self.send_times.pop_front();
+        }
+        sent_at
+    }
+
+    /// Clears the statistics of the current round, keeping its start.
+    fn reset_round(&mut self) {
+        self.round_acked = 0;
+        self.round_rtt_sum = Duration::ZERO;
+        self.round_rtt_samples = 0;
+    }
+
+    /// Closes the current round and moves the window according to the queue estimate.
+    fn end_round(&mut self, now: Instant) {
+        if let Some(start) = self.round_start {
+            let elapsed = now.saturating_duration_since(start).as_secs_f64();
+            if elapsed > 0.0 {
+                if self.delivery_rates.len() == Self::RATE_ROUNDS {
+                    self.delivery_rates.pop_front();
+                }
+                self.delivery_rates.push_back(self.round_acked as f64 / elapsed);
+            }
+        }
+
+        let mut queued = 0;
+        if self.round_rtt_samples > 0 {
+            let mean_rtt = self.round_rtt_sum / self.round_rtt_samples;
+            let base_rtt = match self.base_rtt {
+                Some((base, at)) if base < mean_rtt && now.saturating_duration_since(at) < Self::BASE_RTT_WINDOW => {
+                    base
+                }
+                _ => {
+                    self.base_rtt = Some((mean_rtt, now));
+                    mean_rtt
+                }
+            };
+            if !mean_rtt.is_zero() {
+                let queued_frac = (mean_rtt - base_rtt).as_secs_f64() / mean_rtt.as_secs_f64();
+                queued = (self.cwnd as f64 * queued_frac) as usize;
+            }
+        }
+
+        if queued < self.cfg.queue_target_size / 2 {
+            self.cwnd = self.cwnd.saturating_add(self.cfg.ai_step).min(self.cfg.max_window_size);
+        } else if queued > self.cfg.queue_target_size {
+            self.cwnd = self.cwnd.saturating_sub(self.cfg.ai_step).max(self.cfg.min_window_size);
+        }
+        self.reset_round();
+        self.round_start = Some(now);
+    }
+
+    /// Multiplicative decrease helper, flooring at `floor` (clamped to `[min_window_size, cwnd]`).
+    fn decrease(&mut self, floor: usize) {
+        let reduced = (self.cwnd as f64 * self.cfg.md_factor) as usize;
+        self.cwnd = reduced.max(floor.min(self.cwnd)).max(self.cfg.min_window_size);
+        self.reset_round();
+    }
+}
+
+impl CongestionController for DelayBasedController {
+    fn window(&self) -> usize {
+        self.cwnd
+    }
+
+    fn inflight(&self) -> usize {
+        self.inflight
+    }
+
+    fn min_window_size(&self) -> usize {
+        self.cfg.min_window_size
+    }
+
+    fn on_sent(&mut self, bytes: usize, now: Instant) {
+        if bytes == 0 {
+            return;
+        }
+        self.inflight = self.inflight.saturating_add(bytes);
+        self.sent_total = self.sent_total.saturating_add(bytes as u64);
+        self.send_times.push_back((self.sent_total, now));
+        self.round_start.get_or_insert(now);
+    }
+
+    fn apply_delivery(&mut self, delivered: Delivered, now: Instant) {
+        if delivered.acked_bytes > 0 {
+            if let Some(sent_at) = self.retire(delivered.acked_bytes) {
+                self.round_rtt_sum += now.saturating_duration_since(sent_at);
+                self.round_rtt_samples += 1;
+            }
+            self.round_acked = self.round_acked.saturating_add(delivered.acked_bytes);
+            if self.round_acked >= self.cwnd {
+                self.end_round(now);
+            }
+        }
+        if delivered.lost_bytes > 0 {
+            self.retire(delivered.lost_bytes);
+            self.decrease(self.proven_bdp());
+        }
+    }
+
+    fn apply_backoff(&mut self, backoff: Backoff) {
+        match backoff {
+            Backoff::Soft => self.decrease(self.cfg.min_window_size),
+            Backoff::Hard => {
+                self.cwnd = self.cfg.min_window_size;
+                self.reset_round();
+            }
+        }
+    }
+}
+
 /// Shared, lock-free honest-clock meter. Producers bump it **in place** with a single atomic add —
 /// the reliable ack machinery on ack / retransmission-exhaustion (impl A), or an
 /// application-return-byte reader (impl B). The window driver reads byte deltas via [`DeliveryClock`].
@@ -782,4 +1090,139 @@ mod tests {
         let cfg = FlowControlConfig::default().with_bdp(rate, Duration::from_millis(100));
         assert_eq!(cfg.max_window_size, (rate as f64 * 0.1) as usize);
     }
+
+    // ---- Delay-based controller ----
+
+    fn delay_cfg() -> FlowControlConfig {
+        FlowControlConfig {
+            algorithm: CongestionControlAlgorithm::DelayBased,
+            queue_target_size: 4_000,
+            ..cfg()
+        }
+    }
+
+    /// Sends one full window at `now` and acknowledges it frame by frame after `rtt`.
+    fn run_round(c: &mut dyn CongestionController, now: Instant, rtt: Duration) -> Instant {
+        let win = c.window();
+        for _ in 0..win.div_ceil(1_000) {
+            c.on_sent(1_000, now);
+        }
+        let acked_at = now + rtt;
+        for _ in 0..win.div_ceil(1_000) {
+            c.apply_delivery(
+                Delivered {
+                    acked_bytes: 1_000,
+                    lost_bytes: 0,
+                },
+                acked_at,
+            );
+        }
+        acked_at
+    }
+
+    #[test]
+    fn config_selects_congestion_controller() {
+        let aimd = cfg().controller();
+        assert!(format!("{aimd:?}").starts_with("WindowController"));
+        let delay = delay_cfg().controller();
+        assert!(format!("{delay:?}").starts_with("DelayBasedController"));
+        assert_eq!(delay.window(), 1_000, "window must start at min_window_size");
+    }
+
+    #[test]
+    fn delay_based_adversarial_healthy_supply_no_delivery_cannot_open_window() {
+        let mut c = DelayBasedController::new(delay_cfg());
+        let now = Instant::now();
+        for i in 0..1_000 {
+            let can = c.admissible_for(usize::MAX);
+            c.on_sent(can, now + Duration::from_millis(i));
+        }
+        assert_eq!(c.window(), 1_000);
+        assert_eq!(c.admissible_for(usize::MAX), 0);
+    }
+
+    #[test]
+    fn delay_based_grows_while_rtt_stays_at_base() {
+        let mut c = DelayBasedController::new(delay_cfg());
+        let mut now = Instant::now();
+        for _ in 0..20 {
+            now = run_round(&mut c, now, Duration::from_millis(300));
+        }
+        assert_eq!(c.window(), 21_000, "one ai_step per round without queueing");
+        assert_eq!(c.base_rtt(), Some(Duration::from_millis(300)));
+        assert!(c.delivery_rate().is_some_and(|r| r > 0.0));
+        assert_eq!(c.inflight(), 0);
+    }
+
+    #[test]
+    fn delay_based_backs_off_when_queue_builds() {
+        let mut c = DelayBasedController::new(delay_cfg());
+        let mut now = Instant::now();
+        for _ in 0..20 {
+            now = run_round(&mut c, now, Duration::from_millis(300));
+        }
+        let grown = c.window();
+
+        // Doubling the RTT puts half of the window into queues, far above the target.
+        for _ in 0..5 {
+            now = run_round(&mut c, now, Duration::from_millis(600));
+        }
+        assert!(c.window() < grown, "queueing delay must shrink the window");
+
+        // A queue estimate between half the target and the target holds the window.
+        let held = c.window();
+        let rtt = Duration::from_secs_f64(0.3 / (1.0 - 3_000.0 / held as f64));
+        run_round(&mut c, now, rtt);
+        assert_eq!(c.window(), held);
+    }
+
+    #[test]
+    fn delay_based_loss_does_not_collapse_below_proven_bdp() {
+        let mut c = DelayBasedController::new(delay_cfg());
+        let mut now = Instant::now();
+        for _ in 0..20 {
+            now = run_round(&mut c, now, Duration::from_millis(300));
+        }
+        let grown = c.window();
+        c.on_sent(1_000, now);
+        c.apply_delivery(
+            Delivered {
+                acked_bytes: 0,
+                lost_bytes: 1_000,
+            },
+            now,
+        );
+        assert!(c.window() > grown / 2, "proven BDP must floor the loss decrease");
+        assert!(c.window() <= grown, "loss must never grow the window");
+    }
+
+    #[test]
+    fn delay_based_backoff_is_down_only() {
+        let mut c = DelayBasedController::new(delay_cfg());
+        let mut now = Instant::now();
+        for _ in 0..10 {
+            now = run_round(&mut c, now, Duration::from_millis(300));
+        }
+        let grown = c.window();
+
+        let mut soft = c.clone();
+        soft.apply_backoff(Backoff::Soft);
+        assert_eq!(soft.window(), grown / 2, "SURB backoff ignores the proven BDP");
+
+        c.apply_backoff(Backoff::Hard);
+        assert_eq!(c.window(), 1_000, "hard backoff collapses to floor");
+    }
+
+    #[test]
+    fn delay_based_window_capped_at_max_win() {
+        let mut c = DelayBasedController::new(FlowControlConfig {
+            max_window_size: 5_000,
+            ..delay_cfg()
+        });
+        let mut now = Instant::now();
+        for _ in 0..20 {
+            now = run_round(&mut c, now, Duration::from_millis(300));
+        }
+        assert_eq!(c.window(), 5_000);
+    }
 }
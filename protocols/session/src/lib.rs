@@ -41,7 +41,7 @@ mod protocol;
 mod socket;
 pub(crate) mod utils;
 
-pub use processing::types::FrameInspector;
+pub use processing::{fec::FecConfig, types::FrameInspector};
 pub use protocol::{FrameAcknowledgements, FrameId, Segment, SegmentId, SegmentRequest, SeqIndicator};
 #[cfg(feature = "telemetry")]
 pub use socket::telemetry::{NoopTracker, SessionMessageDiscriminants, SessionTelemetryTracker};
@@ -0,0 +1,432 @@
+//! Contains the forward error correction (FEC) of frames.
+//!
+//! Frames are protected by a systematic Reed-Solomon erasure code over GF(2^8) with a Cauchy
+//! generator matrix: the data segments of a frame are sent unmodified and are followed by
+//! *repair* segments. A frame of `n` data segments can be reconstructed from any `n`
+//! of its data and repair segments, without a retransmission.
+//!
+//! Repair segments carry the same [`SeqIndicator`](crate::protocol::SeqIndicator) as the data segments of their frame,
+//! but their `seq_idx` starts at the sequence length (see [`Segment::is_repair`]).
+//! The payload of a repair segment consists of the length of the last data segment
+//! (big-endian `u16`), followed by the repair data, which are as long as the longest data segment.
+//!
+//! The number of data and repair segments of a frame is limited by [`Segment::MAX_CODED_SEGMENTS`].
+use std::sync::{
+    Arc,
+    atomic::{AtomicU32, Ordering},
+};
+
+use crate::{
+    errors::SessionError,
+    protocol::{FrameId, Segment, SeqNum},
+};
+
+/// Size of the header of the repair segment payload.
+pub const REPAIR_HEADER_SIZE: usize = size_of::<u16>();
+
+/// Configuration of the forward error correction in a [`SessionSocket`](crate::SessionSocket).
+///
+/// The redundancy is given as the number of repair segments per 100 data segments of a frame.
+/// The number of repair segments of each frame is rounded up, therefore every frame
+/// carries at least one repair segment, unless the redundancy is 0.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, smart_default::SmartDefault)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), derive(serde::Deserialize))]
+pub struct FecConfig {
+    /// Initial redundancy in percent.
+    ///
+    /// Default is 25.
+    #[default(25)]
+    pub redundancy: u16,
+    /// Lower bound of the redundancy in percent when adapting to the observed loss.
+    ///
+    /// Default is 10.
+    #[default(10)]
+    pub min_redundancy: u16,
+    /// Upper bound of the redundancy in percent when adapting to the observed loss.
+    ///
+    /// Default is 100.
+    #[default(100)]
+    pub max_redundancy: u16,
+    /// Adapt the redundancy to the observed loss.
+    ///
+    /// The loss can be observed only by stateful sockets: every retransmitted segment increases
+    /// the redundancy, while every acknowledged frame slowly decreases it.
+    ///
+    /// Default is true.
+    #[default(true)]
+    pub adaptive: bool,
+}
+
+/// Redundancy of the forward error correction shared between the
+/// [`Segmenter`](super::segmenter::Segmenter) and the loss observers of a socket.
+#[derive(Clone, Debug)]
+pub(crate) struct RedundancyController {
+    // Redundancy in per-mille
+    current: Arc<AtomicU32>,
+    min: u32,
+    max: u32,
+    adaptive: bool,
+}
+
+impl RedundancyController {
+    /// Per-mille decrease of the redundancy per acknowledged frame.
+    const DECREASE_PER_DELIVERED_FRAME: u32 = 1;
+    /// Per-mille increase of the redundancy per retransmitted segment.
+    const INCREASE_PER_LOST_SEGMENT: u32 = 50;
+
+    pub fn new(cfg: &FecConfig) -> Self {
+        let min = cfg.min_redundancy as u32 * 10;
+        let max = (cfg.max_redundancy as u32 * 10).max(min);
+        Self {
+            current: Arc::new(AtomicU32::new((cfg.redundancy as u32 * 10).clamp(min, max))),
+            min,
+            max,
+            adaptive: cfg.adaptive,
+        }
+    }
+
+    /// Current redundancy in per-mille.
+    pub fn redundancy(&self) -> u32 {
+        self.current.load(Ordering::Relaxed)
+    }
+
+    /// Number of repair segments for a frame of `num_data` data segments.
+    pub fn repair_count(&self, num_data: usize) -> usize {
+        (num_data * self.redundancy() as usize)
+            .div_ceil(1000)
+            .min((Segment::MAX_CODED_SEGMENTS as usize).saturating_sub(num_data))
+    }
+
+    /// Increases the redundancy, because `count` segments had to be retransmitted.
+    pub fn segments_lost(&self, count: usize) {
+        if self.adaptive && count > 0 {
+            let increase = (count as u32).saturating_mul(Self::INCREASE_PER_LOST_SEGMENT);
+            let _ = self.current.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |r| {
+                Some(r.saturating_add(increase).min(self.max))
+            });
+        }
+    }
+
+    /// Decreases the redundancy, because `count` frames were acknowledged.
+    pub fn frames_delivered(&self, count: usize) {
+        if self.adaptive && count > 0 {
+            let decrease = (count as u32).saturating_mul(Self::DECREASE_PER_DELIVERED_FRAME);
+            let _ = self.current.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |r| {
+                Some(r.saturating_sub(decrease).max(self.min))
+            });
+        }
+    }
+}
+
+// Arithmetic in GF(2^8) with the 0x11d reduction polynomial
+const GF_POLY: u16 = 0x11d;
+
+const fn gf_tables() -> ([u8; 512], [u8; 256]) {
+    let mut exp = [0u8; 512];
+    let mut log = [0u8; 256];
+    let mut x: u16 = 1;
+    let mut i = 0;
+    while i < 255 {
+        exp[i] = x as u8;
+        exp[i + 255] = x as u8;
+        log[x as usize] = i as u8;
+        x <<= 1;
+        if x & 0x100 != 0 {
+            x ^= GF_POLY;
+        }
+        i += 1;
+    }
+    (exp, log)
+}
+
+const GF_TABLES: ([u8; 512], [u8; 256]) = gf_tables();
+static GF_EXP: [u8; 512] = GF_TABLES.0;
+static GF_LOG: [u8; 256] = GF_TABLES.1;
+
+#[inline]
+fn gf_mul(a: u8, b: u8) -> u8 {
+    if a == 0 || b == 0 {
+        0
+    } else {
+        GF_EXP[GF_LOG[a as usize] as usize + GF_LOG[b as usize] as usize]
+    }
+}
+
+#[inline]
+fn gf_inv(a: u8) -> u8 {
+    debug_assert_ne!(a, 0, "zero has no inverse");
+    GF_EXP[255 - GF_LOG[a as usize] as usize]
+}
+
+/// Computes `dst += c * src` over GF(2^8), treating `src` as zero-padded to the length of `dst`.
+fn gf_mul_add(dst: &mut [u8], src: &[u8], c: u8) {
+    if c == 0 {
+        return;
+    }
+    let log_c = GF_LOG[c as usize] as usize;
+    dst.iter_mut().zip(src).filter(|(_, s)| **s != 0).for_each(|(d, s)| {
+        *d ^= GF_EXP[log_c + GF_LOG[*s as usize] as usize];
+    });
+}
+
+/// Coefficient of the data segment `data_idx` in the repair segment `repair_idx` of a frame
+/// with `num_data` data segments.
+///
+/// Any square sub-matrix of a Cauchy matrix is invertible, which makes the code MDS.
+#[inline]
+fn cauchy(num_data: usize, repair_idx: usize, data_idx: usize) -> u8 {
+    gf_inv(((num_data + repair_idx) as u8) ^ data_idx as u8)
+}
+
+/// Inverts the square `matrix` over GF(2^8) using Gauss-Jordan elimination.
+fn gf_invert(mut matrix: Vec<Vec<u8>>) -> Option<Vec<Vec<u8>>> {
+    let n = matrix.len();
+    let mut inverse = (0..n)
+        .map(|i| (0..n).map(|j| (i == j) as u8).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    for col in 0..n {
+        let pivot = (col..n).find(|&row| matrix[row][col] != 0)?;
+        matrix.swap(col, pivot);
+        inverse.swap(col, pivot);
+
+        let inv_pivot = gf_inv(matrix[col][col]);
+        matrix[col].iter_mut().for_each(|v| *v = gf_mul(*v, inv_pivot));
+        inverse[col].iter_mut().for_each(|v| *v = gf_mul(*v, inv_pivot));
+
+        for row in (0..n).filter(|&row| row != col) {
+            let factor = matrix[row][col];
+            if factor != 0 {
+                let (pivot_row, pivot_inv) = (matrix[col].clone(), inverse[col].clone());
+                gf_mul_add(&mut matrix[row], &pivot_row, factor);
+                gf_mul_add(&mut inverse[row], &pivot_inv, factor);
+            }
+        }
+    }
+
+    Some(inverse)
+}
+
+/// Creates `count` repair segments for the given data segments of a single frame.
+///
+/// The `data` must be all the data segments of the frame in order, and their number plus `count`
+/// must not exceed [`Segment::MAX_CODED_SEGMENTS`].
+pub(crate) fn repair_segments(data: &[Segment], count: usize) -> Vec<Segment> {
+    let Some(last) = data.last() else {
+        return Vec::new();
+    };
+
+    let repair_len = data.iter().map(|s| s.data.len()).max().unwrap_or_default();
+    if repair_len == 0 || data.len() + count > Segment::MAX_CODED_SEGMENTS as usize {
+        return Vec::new();
+    }
+
+    (0..count)
+        .map(|repair_idx| {
+            let mut payload = vec![0u8; REPAIR_HEADER_SIZE + repair_len];
+            payload[..REPAIR_HEADER_SIZE].copy_from_slice(&(last.data.len() as u16).to_be_bytes());
+            data.iter().enumerate().for_each(|(data_idx, segment)| {
+                gf_mul_add(
+                    &mut payload[REPAIR_HEADER_SIZE..],
+                    &segment.data,
+                    cauchy(data.len(), repair_idx, data_idx),
+                )
+            });
+
+            Segment {
+                frame_id: last.frame_id,
+                seq_idx: (data.len() + repair_idx) as SeqNum,
+                seq_flags: last.seq_flags,
+                data: payload.into_boxed_slice(),
+            }
+        })
+        .collect()
+}
+
+/// Reconstructs the missing data `segments` of the frame `frame_id` using the `repair` segments.
+///
+/// The `segments` must be indexed by their `seq_idx`.
+/// Fails with [`SessionError::IncompleteFrame`] if there are not enough repair segments
+/// and with [`SessionError::InvalidSegment`] if the repair segments are malformed.
+pub(crate) fn recover(
+    frame_id: FrameId,
+    segments: &mut [Option<Segment>],
+    repair: &[Segment],
+) -> Result<(), SessionError> {
+    let num_data = segments.len();
+    let missing = segments
+        .iter()
+        .enumerate()
+        .filter_map(|(i, s)| s.is_none().then_some(i))
+        .collect::<Vec<_>>();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    if repair.len() < missing.len() {
+        return Err(SessionError::IncompleteFrame(frame_id));
+    }
+
+    // Only as many repair segments as there are missing data segments are needed
+    let repair = &repair[..missing.len()];
+    let payload_len = repair[0].data.len();
+    if payload_len <= REPAIR_HEADER_SIZE
+        || repair.iter().any(|r| {
+            r.data.len() != payload_len
+                || r.data[..REPAIR_HEADER_SIZE] != repair[0].data[..REPAIR_HEADER_SIZE]
+                || !r.is_repair()
+                || r.seq_idx as usize >= Segment::MAX_CODED_SEGMENTS as usize
+        })
+    {
+        return Err(SessionError::InvalidSegment);
+    }
+
+    let repair_len = payload_len - REPAIR_HEADER_SIZE;
+    let last_len = u16::from_be_bytes([repair[0].data[0], repair[0].data[1]]) as usize;
+    if last_len > repair_len || segments.iter().flatten().any(|s| s.data.len() > repair_len) {
+        return Err(SessionError::InvalidSegment);
+    }
+
+    // Subtract the contribution of the received data segments from the repair data
+    let residuals = repair
+        .iter()
+        .map(|r| {
+            let repair_idx = r.seq_idx as usize - num_data;
+            let mut residual = r.data[REPAIR_HEADER_SIZE..].to_vec();
+            segments.iter().enumerate().for_each(|(data_idx, s)| {
+                if let Some(s) = s {
+                    gf_mul_add(&mut residual, &s.data, cauchy(num_data, repair_idx, data_idx));
+                }
+            });
+            residual
+        })
+        .collect::<Vec<_>>();
+
+    // Solve the system given by the Cauchy sub-matrix for the missing data segments
+    let matrix = repair
+        .iter()
+        .map(|r| {
+            missing
+                .iter()
+                .map(|&data_idx| cauchy(num_data, r.seq_idx as usize - num_data, data_idx))
+                .collect()
+        })
+        .collect();
+    let inverse = gf_invert(matrix).ok_or(SessionError::InvalidSegment)?;
+
+    for (row, &data_idx) in inverse.iter().zip(&missing) {
+        let mut data = vec![0u8; repair_len];
+        row.iter()
+            .zip(&residuals)
+            .for_each(|(c, residual)| gf_mul_add(&mut data, residual, *c));
+        data.truncate(if data_idx == num_data - 1 { last_len } else { repair_len });
+
+        segments[data_idx] = Some(Segment {
+            frame_id,
+            seq_idx: data_idx as SeqNum,
+            seq_flags: repair[0].seq_flags,
+            data: data.into_boxed_slice(),
+        });
+    }
+
+    tracing::trace!(frame_id, recovered = missing.len(), "recovered missing segments");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::segment;
+
+    #[test]
+    fn gf_inverse_should_be_multiplicative_inverse() {
+        for a in 1..=255u8 {
+            assert_eq!(1, gf_mul(a, gf_inv(a)), "inverse of {a}");
+        }
+    }
+
+    #[test]
+    fn fec_should_recover_any_combination_of_missing_segments() -> anyhow::Result<()> {
+        const NUM_REPAIR: usize = 3;
+
+        let data = hopr_types::crypto_random::random_bytes::<1000>();
+        let segments = segment(data, 110, 1)?;
+        let repair = repair_segments(&segments, NUM_REPAIR);
+        assert_eq!(NUM_REPAIR, repair.len());
+        assert!(repair.iter().all(|r| r.is_repair()));
+
+        let num_data = segments.len();
+        for lost_1 in 0..num_data {
+            for lost_2 in lost_1..num_data {
+                for lost_3 in lost_2..num_data {
+                    let mut received = segments.iter().cloned().map(Some).collect::<Vec<_>>();
+                    [lost_1, lost_2, lost_3].into_iter().for_each(|i| received[i] = None);
+
+                    // Use the repair segments that follow the lost ones
+                    let mut available_repair = repair.clone();
+                    available_repair.rotate_left(lost_1 % NUM_REPAIR);
+
+                    recover(1, &mut received, &available_repair)?;
+                    assert_eq!(segments, received.into_iter().flatten().collect::<Vec<_>>());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn fec_should_not_recover_with_insufficient_repair_segments() -> anyhow::Result<()> {
+        let segments = segment(hopr_types::crypto_random::random_bytes::<500>(), 100, 1)?;
+        let repair = repair_segments(&segments, 1);
+
+        let mut received = segments.iter().cloned().map(Some).collect::<Vec<_>>();
+        received[0] = None;
+        received[3] = None;
+
+        assert!(matches!(
+            recover(1, &mut received, &repair),
+            Err(SessionError::IncompleteFrame(1))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn redundancy_controller_should_adapt_within_bounds() {
+        let cfg = FecConfig {
+            redundancy: 20,
+            min_redundancy: 10,
+            max_redundancy: 30,
+            adaptive: true,
+        };
+        let controller = RedundancyController::new(&cfg);
+        assert_eq!(200, controller.redundancy());
+        assert_eq!(1, controller.repair_count(2));
+        assert_eq!(2, controller.repair_count(10));
+
+        controller.segments_lost(1);
+        assert_eq!(250, controller.redundancy());
+        controller.segments_lost(10);
+        assert_eq!(300, controller.redundancy());
+
+        controller.frames_delivered(150);
+        assert_eq!(150, controller.redundancy());
+        controller.frames_delivered(1000);
+        assert_eq!(100, controller.redundancy());
+
+        let fixed = RedundancyController::new(&FecConfig { adaptive: false, ..cfg });
+        fixed.segments_lost(10);
+        assert_eq!(200, fixed.redundancy());
+    }
+
+    #[test]
+    fn redundancy_controller_should_respect_coded_segment_limit() {
+        let controller = RedundancyController::new(&FecConfig {
+            redundancy: 100,
+            ..Default::default()
+        });
+
+        assert_eq!(6, controller.repair_count(250));
+        assert_eq!(0, controller.repair_count(300));
+    }
+}
@@ -8,7 +8,9 @@
 //! 3. Sequencer
 //!
 //! Reassembler followed by a Sequencer is commonly called frame Reconstructor.
+//! The Segmenter and the Reassembler optionally apply forward error correction to frames.
 
+pub(crate) mod fec;
 mod reassembly;
 mod segmenter;
 mod sequencer;
@@ -148,7 +150,7 @@ mod tests {
 
         let mut data_in = reassm_tx
             .sink_map_err(|_| SessionError::InvalidSegment)
-            .segmenter::<MTU>(FRAME_SIZE);
+            .segmenter::<MTU>(FRAME_SIZE, 1);
 
         let data_written = hopr_types::crypto_random::random_bytes::<DATA_SIZE>();
 
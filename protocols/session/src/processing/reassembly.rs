@@ -54,6 +54,12 @@ lazy_static::lazy_static! {
 /// The reassemblers internal buffer is stored in a [`FrameMap`] and can be constructed using
 /// different implementations of it, suitable for different use-cases.
 ///
+/// [Repair](Segment::is_repair) segments are dropped unless forward error correction is enabled
+/// via [`Reassembler::with_fec`].
+/// Frames protected by forward error correction are complete before all their segments arrive.
+/// The remaining repair segments of such frames are dropped for up to `max_age`
+/// after the frame completion.
+///
 /// Use [`ReassemblerExt`] methods to turn a ` Segment ` stream into a fallible `Frame` stream using the `Reassembler`.
 #[must_use = "streams do nothing unless polled"]
 #[pin_project::pin_project]
@@ -64,9 +70,11 @@ pub struct Reassembler<S, M> {
     timer: futures_time::task::Sleep,
     incomplete_frames: M,
     expired_frames: Vec<FrameId>,
+    completed_frames: std::collections::HashMap<FrameId, Instant>,
     max_age: Duration,
     capacity: usize,
     last_expiration: Option<Instant>,
+    fec: bool,
 }
 
 impl<S: futures::Stream<Item = Segment>, M: FrameMap> Reassembler<S, M> {
@@ -80,13 +88,27 @@ impl<S: futures::Stream<Item = Segment>, M: FrameMap> Reassembler<S, M> {
             ),
             incomplete_frames,
             expired_frames: Vec::with_capacity(capacity),
+            completed_frames: std::collections::HashMap::new(),
             last_expiration: None,
             max_age,
             capacity,
+            fec: false,
         }
     }
 
-    fn expire_frames(incomplete_frames: &mut M, expired_frames: &mut Vec<FrameId>, max_age: Duration) {
+    /// Accepts [repair](Segment::is_repair) segments if forward error correction is `enabled`.
+    pub(crate) fn with_fec(mut self, enabled: bool) -> Self {
+        self.fec = enabled;
+        self
+    }
+
+    fn expire_frames(
+        incomplete_frames: &mut M,
+        expired_frames: &mut Vec<FrameId>,
+        completed_frames: &mut std::collections::HashMap<FrameId, Instant>,
+        max_age: Duration,
+    ) {
+        completed_frames.retain(|_, completed| completed.elapsed() < max_age);
         incomplete_frames.retain(|id, builder| {
             if builder.last_recv.elapsed() >= max_age {
                 expired_frames.push(*id);
@@ -140,6 +162,16 @@ impl<S: futures::Stream<Item = Segment>, M: FrameMap> futures::Stream for Reasse
                         "received segment"
                     );
 
+                    if item.is_repair() && !*this.fec {
+                        tracing::warn!(seg_id = %item.id(), "dropped repair segment without forward error correction");
+                        continue;
+                    }
+
+                    if item.is_repair() && this.completed_frames.contains_key(&item.frame_id) {
+                        tracing::trace!(seg_id = %item.id(), "dropped repair segment of completed frame");
+                        continue;
+                    }
+
                     match this.incomplete_frames.entry(item.frame_id) {
                         FrameMapEntry::Occupied(mut e) => {
                             let builder = e.get_builder_mut();
@@ -153,6 +185,7 @@ impl<S: futures::Stream<Item = Segment>, M: FrameMap> futures::Stream for Reasse
                                             .observe(builder.created.elapsed().as_millis() as f64);
 
                                         tracing::trace!(frame_id = builder.frame_id(), "frame is complete");
+                                        this.completed_frames.insert(builder.frame_id(), Instant::now());
                                         return Poll::Ready(Some(e.finalize().try_into()));
                                     }
                                 }
@@ -168,6 +201,7 @@ impl<S: futures::Stream<Item = Segment>, M: FrameMap> futures::Stream for Reasse
                                 METRIC_TIME_TO_FRAME_FINISH.observe(builder.created.elapsed().as_millis() as f64);
 
                                 tracing::trace!(frame_id = builder.frame_id(), "segment frame is complete");
+                                this.completed_frames.insert(builder.frame_id(), Instant::now());
                                 return Poll::Ready(Some(builder.try_into()));
                             } else {
                                 tracing::trace!(frame_id = builder.frame_id(), "added segment for new frame");
@@ -179,7 +213,12 @@ impl<S: futures::Stream<Item = Segment>, M: FrameMap> futures::Stream for Reasse
                     // Since the retaining operation is potentially expensive,
                     // we do it actually only if there's a real chance that a frame is expired
                     if this.last_expiration.is_none_or(|e| e.elapsed() >= *this.max_age) {
-                        Self::expire_frames(this.incomplete_frames, this.expired_frames, *this.max_age);
+                        Self::expire_frames(
+                            this.incomplete_frames,
+                            this.expired_frames,
+                            this.completed_frames,
+                            *this.max_age,
+                        );
                         *this.last_expiration = Some(Instant::now());
                     }
                 }
@@ -198,7 +237,12 @@ impl<S: futures::Stream<Item = Segment>, M: FrameMap> futures::Stream for Reasse
                 }
                 (Poll::Pending, Poll::Ready(_)) => {
                     // Check if some frames are expired
-                    Self::expire_frames(this.incomplete_frames, this.expired_frames, *this.max_age);
+                    Self::expire_frames(
+                        this.incomplete_frames,
+                        this.expired_frames,
+                        this.completed_frames,
+                        *this.max_age,
+                    );
                     *this.last_expiration = Some(Instant::now());
                     this.timer.as_mut().reset_timer();
                 }
@@ -308,6 +352,41 @@ mod tests {
         Ok(())
     }
 
+    #[test_log::test(tokio::test)]
+    pub async fn reassembler_should_accept_repair_segments_only_with_fec() -> anyhow::Result<()> {
+        let data = hopr_types::crypto_random::random_bytes::<470>();
+        let segments = segment(data, 100, 1)?;
+        let repair = crate::processing::fec::repair_segments(&segments, 1);
+
+        // The last data segment is lost, so the frame can be completed only via the repair segment
+        let received = segments[..segments.len() - 1]
+            .iter()
+            .chain(repair.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for fec in [false, true] {
+            let (r_sink, r_stream) = futures::channel::mpsc::unbounded();
+            let r_stream = r_stream.reassembler(Duration::from_secs(5), 1024).with_fec(fec);
+
+            futures::stream::iter(received.clone()).map(Ok).forward(r_sink).await?;
+
+            let actual = r_stream
+                .collect::<Vec<_>>()
+                .timeout(futures_time::time::Duration::from_secs(5))
+                .await?;
+
+            assert_eq!(1, actual.len());
+            match &actual[0] {
+                Ok(frame) if fec => assert_eq!(&data, frame.data.as_ref()),
+                Err(SessionError::FrameDiscarded(1)) if !fec => {}
+                result => anyhow::bail!("unexpected result with fec = {fec}: {result:?}"),
+            }
+        }
+
+        Ok(())
+    }
+
     #[test_log::test(tokio::test)]
     pub async fn reassembler_should_discard_incomplete_frames_on_expiration() -> anyhow::Result<()> {
         let expected = (1u32..=10)
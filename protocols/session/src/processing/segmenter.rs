@@ -8,6 +8,7 @@ use std::{
 use tracing::instrument;
 
 use crate::{
+    processing::fec::{self, RedundancyController},
     protocol::{FrameId, Segment, SeqIndicator, SessionMessage},
     utils::segment_into,
 };
@@ -16,7 +17,8 @@ use crate::{
 /// that turns it into [`futures::io::AsyncWrite`].
 ///
 /// Bytes written to the Segmenter are buffered up and/or chopped into [`Segment`]
-/// of at most `C` in payload size (the `data` member).
+/// of at most [`SessionMessage::max_segment_payload`] in payload size (the `data` member),
+/// given the MTU `C` and the protocol version.
 ///
 /// The bytes are written to the
 /// underlying Sink once more than `frame_size` is written (or unless flushed),
@@ -30,6 +32,10 @@ use crate::{
 /// Segmenter can optionally send a [terminating](Segment::terminating) when `poll_close`
 /// is called.
 ///
+/// If forward error correction is enabled, the data segments of each frame are followed
+/// by [repair](Segment::is_repair) segments and the segment payload size is reduced
+/// by the size of the repair segment header.
+///
 /// Segmenter is essentially inverse of [`Reassembler`](super::reassembly::Reassembler).
 ///
 /// Use [`SegmenterExt`] to turn a `Segment` sink into an `AsyncWrite` object using the `Segmenter`.
@@ -42,6 +48,9 @@ pub struct Segmenter<const C: usize, S> {
     frame: Vec<u8>,
     ready_segments: VecDeque<Segment>,
     frame_size: usize,
+    segment_size: usize,
+    max_segments: usize,
+    fec: Option<RedundancyController>,
     frame_id: FrameId,
     is_closed: bool,
     send_terminating_segment: bool,
@@ -57,21 +66,23 @@ where
     S: futures::Sink<Segment>,
     S::Error: std::error::Error + Send + Sync + 'static,
 {
-    fn new(inner: S, frame_size: usize, send_terminating_segment: bool) -> Self {
-        // Clamp frame_size to [SESSION_MTU, SESSION_MTU * (SeqIndicator::MAX + 1)].
-        // Minimum is SESSION_MTU (= C - SEGMENT_OVERHEAD) so that a single frame fits in one
-        // HOPR packet (1 segment). Maximum is bounded by SeqIndicator capacity.
-        let frame_size = frame_size.clamp(
-            C - SessionMessage::<C>::SEGMENT_OVERHEAD,
-            (C - SessionMessage::<C>::SEGMENT_OVERHEAD) * (SeqIndicator::MAX + 1) as usize,
-        );
+    fn new(inner: S, frame_size: usize, version: u8, send_terminating_segment: bool) -> Self {
+        // Clamp frame_size to [SESSION_MTU, SESSION_MTU * SeqIndicator::max_len(version)].
+        // Minimum is SESSION_MTU (= C - segment overhead) so that a single frame fits in one
+        // HOPR packet (1 segment). Maximum is bounded by SeqIndicator capacity in the given version.
+        let segment_size = SessionMessage::<C>::max_segment_payload(version);
+        let max_segments = SeqIndicator::max_len(version) as usize;
+        let frame_size = frame_size.clamp(segment_size, segment_size * max_segments);
 
         Self {
             inner,
             state: State::BufferingFrame,
             frame: Vec::with_capacity(frame_size),
-            ready_segments: VecDeque::with_capacity(frame_size.div_ceil(C - SessionMessage::<C>::SEGMENT_OVERHEAD)),
+            ready_segments: VecDeque::with_capacity(frame_size.div_ceil(segment_size)),
             frame_size,
+            segment_size,
+            max_segments,
+            fec: None,
             frame_id: 1,
             is_closed: false,
             send_terminating_segment,
@@ -79,6 +90,25 @@ where
     }
 }
 
+impl<const C: usize, S> Segmenter<C, S> {
+    /// Enables forward error correction with the redundancy given by the `controller`.
+    pub(crate) fn with_fec(mut self, controller: RedundancyController) -> Self {
+        self.segment_size -= fec::REPAIR_HEADER_SIZE;
+        self.frame_size = self
+            .frame_size
+            .clamp(self.segment_size, self.segment_size * self.max_segments);
+        self.fec = Some(controller);
+        self
+    }
+}
+
+/// Appends repair segments to the data segments of a single frame.
+fn append_repair_segments(controller: &RedundancyController, segments: &mut VecDeque<Segment>) {
+    let count = controller.repair_count(segments.len());
+    let repair = fec::repair_segments(segments.make_contiguous(), count);
+    segments.extend(repair);
+}
+
 impl<const C: usize, S> futures::io::AsyncWrite for Segmenter<C, S>
 where
     S: futures::Sink<Segment>,
@@ -108,11 +138,14 @@ where
                         // and write segments to the downstream
                         segment_into(
                             this.frame.as_slice(),
-                            C - SessionMessage::<C>::SEGMENT_OVERHEAD,
+                            *this.segment_size,
                             *this.frame_id,
                             this.ready_segments,
                         )
                         .map_err(std::io::Error::other)?;
+                        if let Some(controller) = this.fec.as_ref() {
+                            append_repair_segments(controller, this.ready_segments);
+                        }
 
                         tracing::trace!(num_segments = this.ready_segments.len(), "frame ready");
 
@@ -160,11 +193,14 @@ where
                 // because poll_write always makes sure it is before returning Ready
                 segment_into(
                     this.frame.as_slice(),
-                    C - SessionMessage::<C>::SEGMENT_OVERHEAD,
+                    *this.segment_size,
                     *this.frame_id,
                     this.ready_segments,
                 )
                 .map_err(std::io::Error::other)?;
+                if let Some(controller) = this.fec.as_ref() {
+                    append_repair_segments(controller, this.ready_segments);
+                }
 
                 tracing::trace!(num_segments = this.ready_segments.len(), "flushed frame ready");
 
@@ -205,23 +241,25 @@ where
 
 /// Sink extension methods for segmenting binary data into a sink.
 pub trait SegmenterExt: futures::Sink<Segment> {
-    /// Attaches a [`Segmenter`] to the underlying sink.
-    fn segmenter<const C: usize>(self, frame_size: usize) -> Segmenter<C, Self>
+    /// Attaches a [`Segmenter`] to the underlying sink, producing segments for the
+    /// given protocol `version`.
+    fn segmenter<const C: usize>(self, frame_size: usize, version: u8) -> Segmenter<C, Self>
     where
         Self: Sized,
         Self::Error: std::error::Error + Send + Sync + 'static,
     {
-        Segmenter::new(self, frame_size, false)
+        Segmenter::new(self, frame_size, version, false)
     }
 
-    /// Attaches a [`Segmenter`] to the underlying sink.
+    /// Attaches a [`Segmenter`] to the underlying sink, producing segments for the
+    /// given protocol `version`.
     /// The `Segmenter` also sends a [terminating](Segment::terminating) when closed.
-    fn segmenter_with_terminating_segment<const C: usize>(self, frame_size: usize) -> Segmenter<C, Self>
+    fn segmenter_with_terminating_segment<const C: usize>(self, frame_size: usize, version: u8) -> Segmenter<C, Self>
     where
         Self: Sized,
         Self::Error: std::error::Error + Send + Sync + 'static,
     {
-        Segmenter::new(self, frame_size, true)
+        Segmenter::new(self, frame_size, version, true)
     }
 }
 
@@ -286,7 +324,7 @@ mod tests {
     #[tokio::test]
     async fn segmenter_should_not_segment_small_data_unless_flushed() -> anyhow::Result<()> {
         let (segments_tx, segments) = futures::channel::mpsc::unbounded();
-        let mut writer = segments_tx.segmenter::<MTU>(FRAME_SIZE);
+        let mut writer = segments_tx.segmenter::<MTU>(FRAME_SIZE, 1);
 
         writer.write_all(b"test").await?;
 
@@ -308,11 +346,40 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn segmenter_with_fec_should_append_repair_segments() -> anyhow::Result<()> {
+        let (segments_tx, segments) = futures::channel::mpsc::unbounded();
+        let controller = RedundancyController::new(&fec::FecConfig {
+            redundancy: 50,
+            ..Default::default()
+        });
+        let mut writer = segments_tx.segmenter::<MTU>(FRAME_SIZE, 1).with_fec(controller);
+
+        let data = hopr_types::crypto_random::random_bytes::<FRAME_SIZE>();
+        writer.write_all(&data).await?;
+        writer.flush().await?;
+        writer.close().await?;
+
+        let segments = segments.collect::<Vec<_>>().await;
+        assert_eq!(3, segments.len());
+        assert!(segments.iter().all(|s| s.frame_id == 1 && s.seq_flags.seq_len() == 2));
+        assert_eq!(SMTU - fec::REPAIR_HEADER_SIZE, segments[0].data.len());
+        assert!(!segments[1].is_repair());
+        assert!(segments[2].is_repair());
+        assert_eq!(SMTU, segments[2].data.len());
+
+        let mut received = vec![None, Some(segments[1].clone())];
+        fec::recover(1, &mut received, &segments[2..])?;
+        assert_eq!(Some(&segments[0]), received[0].as_ref());
+
+        Ok(())
+    }
+
     #[parameterized::parameterized(num_frames = { 1, 3, 5, 11 })]
     #[parameterized_macro(tokio::test)]
     async fn segmenter_should_segment_complete_frames(num_frames: usize) -> anyhow::Result<()> {
         let (segments_tx, segments) = futures::channel::mpsc::unbounded();
-        let mut writer = segments_tx.segmenter::<MTU>(FRAME_SIZE);
+        let mut writer = segments_tx.segmenter::<MTU>(FRAME_SIZE, 1);
 
         let mut all_data = Vec::new();
         for _ in 0..num_frames {
@@ -335,7 +402,7 @@ mod tests {
     #[tokio::test]
     async fn segmenter_full_frame_segmentation_must_be_consistent_with_segment_function() -> anyhow::Result<()> {
         let (segments_tx, segments) = futures::channel::mpsc::unbounded();
-        let mut writer = segments_tx.segmenter::<MTU>(FRAME_SIZE);
+        let mut writer = segments_tx.segmenter::<MTU>(FRAME_SIZE, 1);
 
         let data = hopr_types::crypto_random::random_bytes::<FRAME_SIZE>();
 
@@ -355,7 +422,7 @@ mod tests {
     #[test_log::test(tokio::test)]
     async fn segmenter_full_frame_segmentation_must_also_include_terminating_segment() -> anyhow::Result<()> {
         let (segments_tx, segments) = futures::channel::mpsc::unbounded();
-        let mut writer = segments_tx.segmenter_with_terminating_segment::<MTU>(FRAME_SIZE);
+        let mut writer = segments_tx.segmenter_with_terminating_segment::<MTU>(FRAME_SIZE, 1);
 
         let data = hopr_types::crypto_random::random_bytes::<FRAME_SIZE>();
 
@@ -376,7 +443,7 @@ mod tests {
     #[test_log::test(tokio::test)]
     async fn segmenter_should_segment_complete_frame_with_misaligned_mtu() -> anyhow::Result<()> {
         let (segments_tx, segments) = futures::channel::mpsc::unbounded();
-        let mut writer = segments_tx.segmenter::<MTU>(FRAME_SIZE);
+        let mut writer = segments_tx.segmenter::<MTU>(FRAME_SIZE, 1);
 
         // Make sure the FRAME_SIZE is not a multiple of MTU
         assert_ne!(0, FRAME_SIZE % MTU);
@@ -411,7 +478,7 @@ mod tests {
     #[test_log::test(tokio::test)]
     async fn segmenter_should_segment_multiple_complete_frames_and_incomplete_frame_on_flush() -> anyhow::Result<()> {
         let (segments_tx, segments) = futures::channel::mpsc::unbounded();
-        let mut writer = segments_tx.segmenter::<MTU>(FRAME_SIZE);
+        let mut writer = segments_tx.segmenter::<MTU>(FRAME_SIZE, 1);
 
         let data = hopr_types::crypto_random::random_bytes::<{ FRAME_SIZE + 4 }>();
         writer.write_all(&data).await?;
@@ -455,7 +522,7 @@ mod tests {
     #[test_log::test(tokio::test)]
     async fn segmenter_should_work_with_buffering_backend() -> anyhow::Result<()> {
         let (tx, rx) = futures::channel::mpsc::channel(5);
-        let mut writer = tx.segmenter::<MTU>(FRAME_SIZE);
+        let mut writer = tx.segmenter::<MTU>(FRAME_SIZE, 1);
 
         let data = hopr_types::crypto_random::random_bytes::<{ 10 * FRAME_SIZE }>();
 
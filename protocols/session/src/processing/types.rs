@@ -2,13 +2,19 @@ use std::time::Instant;
 
 use crate::{
     errors::SessionError,
-    protocol::{Frame, FrameId, MissingSegmentsBitmap, Segment, SeqNum},
+    processing::fec,
+    protocol::{Frame, FrameId, MissingSegmentsBitmap, Segment, SegmentId, SeqNum},
 };
 
 /// A helper object that reassembles segments into frames.
+///
+/// Missing data segments can be reconstructed from the [repair](Segment::is_repair) segments
+/// of the frame, therefore a frame is complete once it has as many segments as the frame
+/// has data segments.
 #[derive(Debug)]
 pub struct FrameBuilder {
     segments: Vec<Option<Segment>>,
+    repair: Vec<Segment>,
     frame_id: FrameId,
     seg_remaining: SeqNum,
     recv_bytes: usize,
@@ -22,6 +28,7 @@ impl From<Segment> for FrameBuilder {
         let idx = value.seq_idx;
         let mut ret = Self {
             segments: vec![None; value.seq_flags.seq_len() as usize],
+            repair: Vec::new(),
             frame_id: value.frame_id,
             seg_remaining: value.seq_flags.seq_len() - 1,
             recv_bytes: value.data.len(),
@@ -30,45 +37,84 @@ impl From<Segment> for FrameBuilder {
             created: Instant::now(),
         };
 
-        ret.segments[idx as usize] = Some(value);
+        if value.is_repair() {
+            ret.repair.push(value);
+        } else {
+            ret.segments[idx as usize] = Some(value);
+        }
 
         ret
     }
 }
 
 impl FrameBuilder {
-    /// Adds a segment to this frame.
+    /// Adds a data or repair segment to this frame.
     ///
     /// Fails if the segment is invalid for this frame.
     pub fn add_segment(&mut self, segment: Segment) -> Result<(), SessionError> {
         let idx = segment.seq_idx;
         if segment.frame_id != self.frame_id
-            || idx as usize >= self.segments.len()
             || segment.seq_flags.seq_len() as usize != self.segments.len()
             || self.seg_remaining == 0
-            || self.segments[idx as usize].is_some()
+            || self.segments.get(idx as usize).is_some_and(Option::is_some)
+            || self.repair.iter().any(|r| r.seq_idx == idx)
+            || !self.is_coding_consistent(&segment)
         {
             return Err(SessionError::InvalidSegment);
         }
 
         self.recv_bytes += segment.data.len();
         self.seg_remaining -= 1;
-        self.segments[idx as usize] = Some(segment);
+        if segment.is_repair() {
+            self.repair.push(segment);
+        } else {
+            self.segments[idx as usize] = Some(segment);
+        }
         self.last_recv = Instant::now();
         Ok(())
     }
 
+    /// Checks that the length of the `segment` is consistent with the repair segments of this frame.
+    ///
+    /// The repair data are as long as the longest data segment of the frame.
+    fn is_coding_consistent(&self, segment: &Segment) -> bool {
+        let repair_len = self.repair.first().map(|r| r.data.len());
+        if segment.is_repair() {
+            segment.seq_idx < Segment::MAX_CODED_SEGMENTS
+                && segment.data.len() > fec::REPAIR_HEADER_SIZE
+                && repair_len.is_none_or(|len| len == segment.data.len())
+                && self
+                    .segments
+                    .iter()
+                    .flatten()
+                    .all(|s| s.data.len() + fec::REPAIR_HEADER_SIZE <= segment.data.len())
+        } else {
+            repair_len.is_none_or(|len| segment.data.len() + fec::REPAIR_HEADER_SIZE <= len)
+        }
+    }
+
     /// Retrieves the bitmap of missing segments in this frame.
+    ///
+    /// Only the first [`MissingSegmentsBitmap`] length segments are covered.
     pub fn as_missing(&self) -> MissingSegmentsBitmap {
         let mut ret = MissingSegmentsBitmap::ZERO;
         self.segments
             .iter()
-            .take(SeqNum::BITS as usize)
+            .take(ret.len())
             .enumerate()
             .for_each(|(i, v)| ret.set(i, v.is_none()));
         ret
     }
 
+    /// Retrieves IDs of all the missing segments in this frame.
+    pub fn missing_ids(&self) -> impl Iterator<Item = SegmentId> + '_ {
+        self.segments
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| v.is_none())
+            .map(|(i, _)| SegmentId(self.frame_id, i as SeqNum))
+    }
+
     /// Retrieves the frame's ID.
     #[inline]
     pub fn frame_id(&self) -> FrameId {
@@ -85,7 +131,12 @@ impl FrameBuilder {
 impl TryFrom<FrameBuilder> for Frame {
     type Error = SessionError;
 
-    fn try_from(value: FrameBuilder) -> Result<Self, Self::Error> {
+    fn try_from(mut value: FrameBuilder) -> Result<Self, Self::Error> {
+        // Reconstruct the missing data segments using the repair segments
+        if !value.repair.is_empty() {
+            fec::recover(value.frame_id, &mut value.segments, &value.repair)?;
+        }
+
         // The Frame has the terminating flag set
         // if any of its segments had the terminating indicator set
         let mut is_terminating = false;
@@ -126,6 +177,13 @@ impl FrameInspector {
         self.0.0.get(frame_id).map(|f| f.as_missing())
     }
 
+    /// Returns IDs of all the missing segments in a frame.
+    ///
+    /// Unlike [`FrameInspector::missing_segments`], this is not limited by the bitmap size.
+    pub fn missing_segment_ids(&self, frame_id: &FrameId) -> Option<Vec<SegmentId>> {
+        self.0.0.get(frame_id).map(|f| f.missing_ids().collect())
+    }
+
     /// Number of incomplete frames.
     pub fn len(&self) -> usize {
         self.0.len()
@@ -545,4 +603,76 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn frame_builder_should_report_missing_segments_beyond_bitmap() -> anyhow::Result<()> {
+        let mut fb = FrameBuilder::from(Segment {
+            frame_id: 1,
+            seq_idx: 0,
+            seq_flags: SeqIndicator::new(12),
+            data: (*b"hello").into(),
+        });
+        for seq_idx in (1..12).filter(|i| *i != 3 && *i != 10) {
+            fb.add_segment(Segment {
+                frame_id: 1,
+                seq_idx,
+                seq_flags: SeqIndicator::new(12),
+                data: (*b"hello").into(),
+            })?;
+        }
+
+        assert_eq!(
+            vec![SegmentId(1, 3), SegmentId(1, 10)],
+            fb.missing_ids().collect::<Vec<_>>()
+        );
+
+        let mut expected = MissingSegmentsBitmap::ZERO;
+        expected.set(3, true);
+        assert_eq!(expected, fb.as_missing());
+
+        Ok(())
+    }
+
+    #[test]
+    fn frame_builder_should_recover_missing_segments_from_repair_segments() -> anyhow::Result<()> {
+        let data = hopr_types::crypto_random::random_bytes::<470>();
+        let segments = crate::utils::segment(data, 100, 1)?;
+        let repair = fec::repair_segments(&segments, 2);
+
+        let mut fb = FrameBuilder::from(repair[1].clone());
+        for segment in segments.iter().filter(|s| s.seq_idx != 1 && s.seq_idx != 4) {
+            fb.add_segment(segment.clone())?;
+        }
+        assert!(!fb.is_complete());
+        assert_eq!(
+            vec![SegmentId(1, 1), SegmentId(1, 4)],
+            fb.missing_ids().collect::<Vec<_>>()
+        );
+
+        fb.add_segment(repair[0].clone())?;
+        assert!(fb.is_complete());
+
+        let reassembled: Frame = fb.try_into()?;
+        assert_eq!(&data, reassembled.data.as_ref());
+
+        Ok(())
+    }
+
+    #[test]
+    fn frame_builder_should_not_accept_inconsistent_repair_segments() -> anyhow::Result<()> {
+        let segments = crate::utils::segment(hopr_types::crypto_random::random_bytes::<500>(), 100, 1)?;
+        let repair = fec::repair_segments(&segments, 2);
+
+        let mut fb = FrameBuilder::from(segments[0].clone());
+        fb.add_segment(repair[0].clone())?;
+        fb.add_segment(repair[0].clone())
+            .expect_err("should not accept duplicate repair segment");
+
+        let mut short_repair = repair[1].clone();
+        short_repair.data = short_repair.data[..50].into();
+        fb.add_segment(short_repair)
+            .expect_err("should not accept repair segment shorter than data segments");
+
+        Ok(())
+    }
 }
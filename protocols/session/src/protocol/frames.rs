@@ -13,7 +13,10 @@ use crate::errors::SessionError;
 pub type FrameId = u32;
 
 /// Type representing the sequence numbers in a `Frame`.
-pub type SeqNum = u8;
+///
+/// Protocol version 1 encodes sequence numbers as a single byte and therefore supports only
+/// up to [`SeqIndicator::MAX_V1`] segments per frame.
+pub type SeqNum = u16;
 
 /// Convenience type that identifies a segment within a frame.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
@@ -114,11 +117,14 @@ pub struct SeqIndicator(SeqNum);
 
 impl SeqIndicator {
     /// Maximum length of a segment sequence.
-    pub const MAX: SeqNum = 0b0011_1111;
+    pub const MAX: SeqNum = 0b0011_1111_1111_1111;
+    /// Maximum length of a segment sequence in the protocol version 1.
+    pub const MAX_V1: SeqNum = 0b0011_1111;
+    const TERMINATING_BIT: SeqNum = 1 << (SeqNum::BITS - 1);
 
     #[inline]
     pub const fn new_with_flags(seq_len: SeqNum, is_terminating: bool) -> Self {
-        let flags = ((is_terminating as u8) << 7) | (seq_len & Self::MAX);
+        let flags = (is_terminating as SeqNum * Self::TERMINATING_BIT) | (seq_len & Self::MAX);
         Self(flags)
     }
 
@@ -132,6 +138,12 @@ impl SeqIndicator {
         Self(seq_ind)
     }
 
+    /// Maximum length of a segment sequence in the given protocol `version`.
+    #[inline]
+    pub const fn max_len(version: u8) -> SeqNum {
+        if version > 1 { Self::MAX } else { Self::MAX_V1 }
+    }
+
     #[inline]
     pub fn with_terminating_bit(self, is_terminating: bool) -> Self {
         Self::new_with_flags(self.0, is_terminating)
@@ -139,7 +151,7 @@ impl SeqIndicator {
 
     #[inline]
     pub const fn is_terminating(&self) -> bool {
-        self.0 & 0b1000_0000 != 0
+        self.0 & Self::TERMINATING_BIT != 0
     }
 
     #[inline]
@@ -151,6 +163,20 @@ impl SeqIndicator {
     pub const fn value(&self) -> SeqNum {
         self.0
     }
+
+    /// Single byte encoding of the indicator in the protocol version 1.
+    ///
+    /// Fails if the sequence length exceeds [`SeqIndicator::MAX_V1`].
+    fn to_v1(self) -> Result<u8, SessionError> {
+        (self.seq_len() <= Self::MAX_V1)
+            .then(|| ((self.is_terminating() as u8) << 7) | self.seq_len() as u8)
+            .ok_or(SessionError::DataTooLong)
+    }
+
+    /// Decodes the single byte indicator of the protocol version 1.
+    const fn from_v1(value: u8) -> Self {
+        Self::new_with_flags((value & Self::MAX_V1 as u8) as SeqNum, value & 0b1000_0000 != 0)
+    }
 }
 
 impl Debug for SeqIndicator {
@@ -200,8 +226,22 @@ pub struct Segment {
 }
 
 impl Segment {
-    /// Size of the segment header.
-    pub const HEADER_SIZE: usize = size_of::<FrameId>() + 2 * size_of::<SeqNum>();
+    /// Size of the segment header in the protocol version 1.
+    pub const HEADER_SIZE: usize = size_of::<FrameId>() + 2 * size_of::<u8>();
+    /// Size of the segment header in the protocol version 2.
+    pub const HEADER_SIZE_V2: usize = size_of::<FrameId>() + 2 * size_of::<SeqNum>();
+    /// Maximum number of data and repair segments of a frame protected by forward error correction.
+    pub const MAX_CODED_SEGMENTS: SeqNum = 256;
+
+    /// Size of the segment header in the given protocol `version`.
+    #[inline]
+    pub const fn header_size(version: u8) -> usize {
+        if version > 1 {
+            Self::HEADER_SIZE_V2
+        } else {
+            Self::HEADER_SIZE
+        }
+    }
 
     /// Returns the [SegmentId] for this segment.
     pub fn id(&self) -> SegmentId {
@@ -220,6 +260,14 @@ impl Segment {
         self.seq_idx == self.seq_flags.seq_len() - 1
     }
 
+    /// Indicates whether this segment is a forward error correction repair segment.
+    ///
+    /// Repair segments follow the data segments of a frame, so their index is beyond the sequence length.
+    #[inline]
+    pub fn is_repair(&self) -> bool {
+        self.seq_idx >= self.seq_flags.seq_len()
+    }
+
     /// Short-cut to check if this segment is a terminating segment.
     #[inline]
     pub fn is_terminating(&self) -> bool {
@@ -269,35 +317,68 @@ impl From<&Segment> for SegmentId {
     }
 }
 
-impl From<Segment> for Vec<u8> {
-    fn from(value: Segment) -> Self {
-        let mut ret = Vec::with_capacity(Segment::HEADER_SIZE + value.data.len());
-        ret.extend_from_slice(value.frame_id.to_be_bytes().as_ref());
-        ret.extend_from_slice(value.seq_idx.to_be_bytes().as_ref());
-        ret.push(value.seq_flags.value());
-        ret.extend_from_slice(value.data.as_ref());
-        ret
+impl Segment {
+    /// Encodes the segment using the given protocol `version`.
+    ///
+    /// Fails if the segment's sequence does not fit into the encoding of the given version.
+    pub fn encode(&self, version: u8) -> Result<Vec<u8>, SessionError> {
+        let mut ret = Vec::with_capacity(Self::header_size(version) + self.data.len());
+        ret.extend_from_slice(self.frame_id.to_be_bytes().as_ref());
+        if version > 1 {
+            ret.extend_from_slice(self.seq_idx.to_be_bytes().as_ref());
+            ret.extend_from_slice(self.seq_flags.value().to_be_bytes().as_ref());
+        } else {
+            ret.push(u8::try_from(self.seq_idx).map_err(|_| SessionError::DataTooLong)?);
+            ret.push(self.seq_flags.to_v1()?);
+        }
+        ret.extend_from_slice(self.data.as_ref());
+        Ok(ret)
     }
-}
 
-impl TryFrom<&[u8]> for Segment {
-    type Error = SessionError;
+    /// Reads the [`SegmentId`] of a segment encoded using the given protocol `version`,
+    /// without decoding the rest of the segment.
+    pub fn peek_id(value: &[u8], version: u8) -> Option<SegmentId> {
+        let header = value.get(..Self::header_size(version))?;
+        let frame_id = FrameId::from_be_bytes(header[0..4].try_into().ok()?);
+        let seq_idx = if version > 1 {
+            SeqNum::from_be_bytes(header[4..6].try_into().ok()?)
+        } else {
+            header[4] as SeqNum
+        };
+        Some(SegmentId(frame_id, seq_idx))
+    }
 
-    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
-        if value.len() < Self::HEADER_SIZE {
+    /// Decodes the segment encoded using the given protocol `version`.
+    pub fn decode(value: &[u8], version: u8) -> Result<Self, SessionError> {
+        if value.len() < Self::header_size(version) {
             return Err(SessionError::InvalidSegment);
         }
 
-        let (header, data) = value.split_at(Self::HEADER_SIZE);
+        let (header, data) = value.split_at(Self::header_size(version));
+        let frame_id = FrameId::from_be_bytes(header[0..4].try_into().map_err(|_| SessionError::InvalidSegment)?);
+        let (seq_idx, seq_flags) = if version > 1 {
+            (
+                SeqNum::from_be_bytes(header[4..6].try_into().map_err(|_| SessionError::InvalidSegment)?),
+                SeqIndicator::new_unchecked(SeqNum::from_be_bytes(
+                    header[6..8].try_into().map_err(|_| SessionError::InvalidSegment)?,
+                )),
+            )
+        } else {
+            (header[4] as SeqNum, SeqIndicator::from_v1(header[5]))
+        };
+
         let segment = Segment {
-            frame_id: FrameId::from_be_bytes(header[0..4].try_into().map_err(|_| SessionError::InvalidSegment)?),
-            seq_idx: SeqNum::from_be_bytes(header[4..5].try_into().map_err(|_| SessionError::InvalidSegment)?),
-            seq_flags: SeqIndicator::new_unchecked(header[5]),
+            frame_id,
+            seq_idx,
+            seq_flags,
             data: data.into(),
         };
-        (segment.frame_id > 0 && segment.seq_idx < segment.seq_flags.seq_len())
-            .then_some(segment)
-            .ok_or(SessionError::InvalidSegment)
+        // Repair segments have indices beyond the sequence length
+        (segment.frame_id > 0
+            && segment.seq_flags.seq_len() > 0
+            && segment.seq_idx < segment.seq_flags.seq_len().max(Self::MAX_CODED_SEGMENTS))
+        .then_some(segment)
+        .ok_or(SessionError::InvalidSegment)
     }
 }
 
@@ -323,8 +404,59 @@ mod tests {
             data: Box::new([123u8]),
         };
 
-        let seg_2 = Segment::try_from(Vec::from(seg_1.clone()).as_slice())?;
+        let seg_2 = Segment::decode(&seg_1.encode(1)?, 1)?;
+        assert_eq!(seg_1, seg_2);
+
+        let seg_3 = Segment::decode(&seg_1.encode(2)?, 2)?;
+        assert_eq!(seg_1, seg_3);
+        Ok(())
+    }
+
+    #[test]
+    fn segment_with_wide_sequence_should_only_serialize_in_v2() -> anyhow::Result<()> {
+        let seg_1 = Segment {
+            frame_id: 10,
+            seq_idx: 300,
+            seq_flags: SeqIndicator::new_with_flags(1000, true),
+            data: Box::new([123u8]),
+        };
+
+        assert!(seg_1.encode(1).is_err());
+
+        let data = seg_1.encode(2)?;
+        assert_eq!(Segment::HEADER_SIZE_V2 + 1, data.len());
+
+        let seg_2 = Segment::decode(&data, 2)?;
         assert_eq!(seg_1, seg_2);
+        assert!(seg_2.is_terminating());
+        assert_eq!(1000, seg_2.seq_flags.seq_len());
+        Ok(())
+    }
+
+    #[test]
+    fn v1_sequence_indicator_should_keep_terminating_flag() -> anyhow::Result<()> {
+        let ind = SeqIndicator::new_with_flags(SeqIndicator::MAX_V1, true);
+        assert_eq!(0b1011_1111, ind.to_v1()?);
+        assert_eq!(ind, SeqIndicator::from_v1(ind.to_v1()?));
+        Ok(())
+    }
+
+    #[test]
+    fn repair_segment_should_deserialize_only_within_coded_segment_limit() -> anyhow::Result<()> {
+        let mut seg_1 = Segment {
+            frame_id: 10,
+            seq_idx: 4,
+            seq_flags: 2.try_into()?,
+            data: Box::new([1u8, 2, 3]),
+        };
+        assert!(seg_1.is_repair());
+
+        for version in [1, 2] {
+            assert_eq!(seg_1, Segment::decode(&seg_1.encode(version)?, version)?);
+        }
+
+        seg_1.seq_idx = Segment::MAX_CODED_SEGMENTS;
+        assert!(Segment::decode(&seg_1.encode(2)?, 2).is_err());
         Ok(())
     }
 }
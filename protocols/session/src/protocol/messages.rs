@@ -1,34 +1,40 @@
 //! Contains definitions of Session protocol messages.
 
-use std::collections::{BTreeMap, BTreeSet};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::RangeInclusive,
+};
 
 use bitvec::{BitArr, field::BitField, prelude::Msb0};
 
 use crate::{
     errors::SessionError,
-    protocol::{FrameId, SegmentId, SeqNum, SessionMessage},
+    protocol::{FrameId, SegmentId, SeqIndicator, SeqNum, SessionMessage},
 };
 
 /// Holds the Segment Retransmission Request message.
 ///
 /// That is an ordered map of frame IDs and a bitmap of missing segments in each frame.
 /// The bitmap can cover up a request for up to [`SegmentRequest::MAX_ENTRIES`] segments.
+///
+/// This message can only request the first [`SegmentRequest::MAX_MISSING_SEGMENTS_PER_FRAME`]
+/// segments of a frame, see [`SegmentRangeRequest`] for its version 2 counterpart.
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
-pub struct SegmentRequest<const C: usize>(pub(super) BTreeMap<FrameId, SeqNum>);
+pub struct SegmentRequest<const C: usize>(pub(super) BTreeMap<FrameId, u8>);
 
 /// Bitmap of segments missing in a frame.
 ///
 /// Represented by `u8`, it can cover up to 8 segments per frame.
 /// If a bit is set, the segment is *missing* from the frame.
-pub type MissingSegmentsBitmap = BitArr!(for 1, in SeqNum, Msb0);
+pub type MissingSegmentsBitmap = BitArr!(for 1, in u8, Msb0);
 
 impl<const C: usize> SegmentRequest<C> {
     /// Size of a single segment retransmission request entry.
-    pub const ENTRY_SIZE: usize = size_of::<FrameId>() + size_of::<SeqNum>();
+    pub const ENTRY_SIZE: usize = size_of::<FrameId>() + size_of::<u8>();
     /// Maximum number of segment retransmission entries.
     pub const MAX_ENTRIES: usize = Self::SIZE / Self::ENTRY_SIZE;
     /// Maximum number of missing segments per frame.
-    pub const MAX_MISSING_SEGMENTS_PER_FRAME: usize = SeqNum::BITS as usize;
+    pub const MAX_MISSING_SEGMENTS_PER_FRAME: usize = u8::BITS as usize;
     /// Size of the message.
     pub const SIZE: usize = C - SessionMessage::<C>::HEADER_SIZE;
 
@@ -53,8 +59,7 @@ impl<const C: usize> IntoIterator for SegmentRequest<C> {
 
     // An ordered iterator of missing segments in the form of SegmentId tuples.
     fn into_iter(self) -> Self::IntoIter {
-        let seq_size = SeqNum::BITS as usize;
-        let mut ret = Vec::with_capacity(seq_size * self.0.len());
+        let mut ret = Vec::with_capacity(Self::MAX_MISSING_SEGMENTS_PER_FRAME * self.0.len());
         for (frame_id, missing) in self.0 {
             ret.extend(
                 MissingSegmentsBitmap::from([missing])
@@ -91,7 +96,7 @@ impl<const C: usize> TryFrom<&[u8]> for SegmentRequest<C> {
                 if frame_id > 0 {
                     ret.0.insert(
                         frame_id,
-                        SeqNum::from_be_bytes(missing.try_into().map_err(|_| SessionError::ParseError)?),
+                        u8::from_be_bytes(missing.try_into().map_err(|_| SessionError::ParseError)?),
                     );
                 }
             }
@@ -106,12 +111,12 @@ impl<const C: usize> From<SegmentRequest<C>> for Vec<u8> {
     fn from(value: SegmentRequest<C>) -> Self {
         let mut ret = vec![0u8; SegmentRequest::<C>::SIZE];
         let mut offset = 0;
-        for (frame_id, seq_num) in value.0 {
-            if offset + size_of::<FrameId>() + size_of::<SeqNum>() <= SegmentRequest::<C>::SIZE {
+        for (frame_id, missing) in value.0 {
+            if offset + SegmentRequest::<C>::ENTRY_SIZE <= SegmentRequest::<C>::SIZE {
                 ret[offset..offset + size_of::<FrameId>()].copy_from_slice(&frame_id.to_be_bytes());
                 offset += size_of::<FrameId>();
-                ret[offset..offset + size_of::<SeqNum>()].copy_from_slice(&seq_num.to_be_bytes());
-                offset += size_of::<SeqNum>();
+                ret[offset] = missing;
+                offset += size_of::<u8>();
             } else {
                 break;
             }
@@ -120,6 +125,176 @@ impl<const C: usize> From<SegmentRequest<C>> for Vec<u8> {
     }
 }
 
+/// Holds the Segment Range Retransmission Request message (since protocol version 2).
+///
+/// That is an ordered map of the first missing [segment](SegmentId) of each range of
+/// consecutive missing segments, and the number of segments in that range.
+/// Unlike [`SegmentRequest`], it can request any segment of a frame, and a single entry
+/// covers a run of missing segments up to the end of the longest possible frame
+/// (see [`SeqIndicator::MAX`]).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SegmentRangeRequest<const C: usize>(pub(super) BTreeMap<SegmentId, SeqNum>);
+
+impl<const C: usize> SegmentRangeRequest<C> {
+    /// Size of a single range entry: frame ID, index of the first segment and the number of segments.
+    pub const ENTRY_SIZE: usize = size_of::<FrameId>() + 2 * size_of::<SeqNum>();
+    /// Maximum number of range entries.
+    pub const MAX_ENTRIES: usize = Self::SIZE / Self::ENTRY_SIZE;
+    /// Size of the message.
+    pub const SIZE: usize = C - SessionMessage::<C>::HEADER_SIZE;
+
+    /// Number of range entries in this request.
+    pub fn num_ranges(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns the total number of segments to retransmit for all frames in this request.
+    pub fn len(&self) -> usize {
+        self.0
+            .values()
+            .take(Self::MAX_ENTRIES)
+            .map(|count| *count as usize)
+            .sum()
+    }
+
+    /// Returns true if there are no segments to retransmit in this request.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Ordered inclusive ranges of the requested segments, one per range entry.
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<SegmentId>> + '_ {
+        self.0
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .map(|(first, count)| *first..=SegmentId(first.0, first.1.saturating_add(*count - 1)))
+    }
+
+    /// Creates a vector of [`SegmentRangeRequests`](SegmentRangeRequest) from the given iterator
+    /// of missing [segments](SegmentId), so that each fits into a single message.
+    pub fn new_multiple<T: IntoIterator<Item = SegmentId>>(items: T) -> Vec<Self> {
+        let all = Self::from_iter(items);
+        let mut out = Vec::with_capacity(all.0.len().div_ceil(Self::MAX_ENTRIES.max(1)));
+        let mut req = Self::default();
+        for (first, count) in all.0 {
+            if req.0.len() == Self::MAX_ENTRIES {
+                out.push(std::mem::take(&mut req));
+            }
+            req.0.insert(first, count);
+        }
+        out.push(req);
+        out
+    }
+}
+
+impl<const C: usize> IntoIterator for SegmentRangeRequest<C> {
+    type IntoIter = SegmentRangeIter;
+    type Item = SegmentId;
+
+    // An ordered iterator of missing segments in the form of SegmentId tuples.
+    fn into_iter(self) -> Self::IntoIter {
+        SegmentRangeIter {
+            ranges: self.0.into_iter(),
+            current: None,
+        }
+    }
+}
+
+/// Ordered iterator of the [segments](SegmentId) requested by a [`SegmentRangeRequest`].
+///
+/// The segments are produced lazily, so that the consumer can bound how many of them it processes.
+#[derive(Debug)]
+pub struct SegmentRangeIter {
+    ranges: std::collections::btree_map::IntoIter<SegmentId, SeqNum>,
+    current: Option<(FrameId, std::ops::Range<SeqNum>)>,
+}
+
+impl Iterator for SegmentRangeIter {
+    type Item = SegmentId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((frame_id, range)) = &mut self.current
+                && let Some(seq_idx) = range.next()
+            {
+                return Some(SegmentId(*frame_id, seq_idx));
+            }
+            let (SegmentId(frame_id, first), count) = self.ranges.next()?;
+            self.current = Some((frame_id, first..first.saturating_add(count)));
+        }
+    }
+}
+
+// From missing segments, consecutive segments within a frame are merged into ranges
+impl<const C: usize> FromIterator<SegmentId> for SegmentRangeRequest<C> {
+    fn from_iter<T: IntoIterator<Item = SegmentId>>(iter: T) -> Self {
+        let mut ret = Self::default();
+        let mut last: Option<(SegmentId, SeqNum)> = None;
+        for seg_id in iter.into_iter().collect::<BTreeSet<_>>() {
+            match &mut last {
+                Some((first, count))
+                    if first.0 == seg_id.0 && first.1.checked_add(*count) == Some(seg_id.1) && *count < SeqNum::MAX =>
+                {
+                    *count += 1
+                }
+                _ => {
+                    if let Some((first, count)) = last.replace((seg_id, 1)) {
+                        ret.0.insert(first, count);
+                    }
+                }
+            }
+        }
+        if let Some((first, count)) = last {
+            ret.0.insert(first, count);
+        }
+        ret
+    }
+}
+
+impl<const C: usize> TryFrom<&[u8]> for SegmentRangeRequest<C> {
+    type Error = SessionError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        if value.len() != Self::SIZE {
+            return Err(SessionError::ParseError);
+        }
+
+        let mut ret = Self::default();
+        for entry in value.chunks_exact(Self::ENTRY_SIZE) {
+            let frame_id = FrameId::from_be_bytes(entry[0..4].try_into().map_err(|_| SessionError::ParseError)?);
+            let first = SeqNum::from_be_bytes(entry[4..6].try_into().map_err(|_| SessionError::ParseError)?);
+            let count = SeqNum::from_be_bytes(entry[6..8].try_into().map_err(|_| SessionError::ParseError)?);
+            // No frame can have segments beyond the maximum sequence length
+            if first as usize + count as usize > SeqIndicator::MAX as usize {
+                return Err(SessionError::ParseError);
+            }
+            if frame_id > 0 && count > 0 {
+                ret.0.insert(SegmentId(frame_id, first), count);
+            }
+        }
+        Ok(ret)
+    }
+}
+
+impl<const C: usize> From<SegmentRangeRequest<C>> for Vec<u8> {
+    fn from(value: SegmentRangeRequest<C>) -> Self {
+        value
+            .0
+            .into_iter()
+            .flat_map(|(SegmentId(frame_id, first), count)| {
+                frame_id
+                    .to_be_bytes()
+                    .into_iter()
+                    .chain(first.to_be_bytes())
+                    .chain(count.to_be_bytes())
+            })
+            .take(SegmentRangeRequest::<C>::MAX_ENTRIES * SegmentRangeRequest::<C>::ENTRY_SIZE)
+            .chain(std::iter::repeat(0_u8))
+            .take(SegmentRangeRequest::<C>::SIZE)
+            .collect()
+    }
+}
+
 /// Holds the Frame Acknowledgement message.
 /// This carries an ordered set of up to [`FrameAcknowledgements::MAX_ACK_FRAMES`] [frame IDs](FrameId)
 /// that has been acknowledged by the counterparty.
@@ -238,6 +413,151 @@ impl<const C: usize> From<FrameAcknowledgements<C>> for Vec<u8> {
     }
 }
 
+/// Holds the Selective Frame Acknowledgement message (since protocol version 2).
+///
+/// The message carries up to [`SelectiveAcknowledgement::MAX_RANGES`] inclusive ranges of
+/// [frame IDs](FrameId) that have been completely received by the counterparty. A single range
+/// can acknowledge up to [`SelectiveAcknowledgement::MAX_RANGE_LEN`] consecutive frames,
+/// longer runs are split into multiple ranges.
+///
+/// In addition, the `cumulative` frame ID indicates that all frames up to and including it
+/// were resolved by the counterparty: each of them was either received or discarded, and no more
+/// segments of them will be accepted.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SelectiveAcknowledgement<const C: usize> {
+    pub(super) cumulative: FrameId,
+    pub(super) ranges: BTreeMap<FrameId, FrameId>,
+}
+
+impl<const C: usize> SelectiveAcknowledgement<C> {
+    /// Size of a single range entry: the first and the last frame ID of the range.
+    pub const ENTRY_SIZE: usize = 2 * size_of::<FrameId>();
+    /// Maximum number of ranges that can be accommodated.
+    pub const MAX_RANGES: usize = (Self::SIZE - size_of::<FrameId>()) / Self::ENTRY_SIZE;
+    /// Maximum number of frames in a single range.
+    pub const MAX_RANGE_LEN: FrameId = 4096;
+    /// Size of the message.
+    pub const SIZE: usize = C - SessionMessage::<C>::HEADER_SIZE;
+
+    /// All frames up to and including this frame ID were resolved by the counterparty.
+    ///
+    /// Zero if no cumulative acknowledgement is given.
+    #[inline]
+    pub fn cumulative(&self) -> FrameId {
+        self.cumulative
+    }
+
+    /// Ordered inclusive ranges of completely received frames.
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<FrameId>> + '_ {
+        self.ranges.iter().map(|(first, last)| *first..=*last)
+    }
+
+    /// Ordered completely received frames.
+    pub fn selective_frames(&self) -> impl Iterator<Item = FrameId> + '_ {
+        self.ranges().flatten()
+    }
+
+    /// Ordered completely received frames that fall within the given `window`.
+    ///
+    /// Each range is clamped to the `window` before it is expanded, so the number of
+    /// yielded frames never exceeds the size of the `window`.
+    pub fn selective_frames_within(&self, window: RangeInclusive<FrameId>) -> impl Iterator<Item = FrameId> + '_ {
+        self.ranges
+            .range(..=*window.end())
+            .flat_map(move |(first, last)| *first.max(window.start())..=*last.min(window.end()))
+    }
+
+    /// Number of completely received frames.
+    pub fn len(&self) -> usize {
+        self.ranges
+            .iter()
+            .map(|(first, last)| (last - first) as usize + 1)
+            .sum()
+    }
+
+    /// Returns true if this message acknowledges no frames.
+    pub fn is_empty(&self) -> bool {
+        self.cumulative == 0 && self.ranges.is_empty()
+    }
+
+    /// Creates a vector of [`SelectiveAcknowledgements`](SelectiveAcknowledgement) carrying the
+    /// given `cumulative` acknowledgement and all the received frames from the iterator, merged into ranges.
+    pub fn new_multiple<T: IntoIterator<Item = FrameId>>(cumulative: FrameId, items: T) -> Vec<Self> {
+        let mut ranges: Vec<(FrameId, FrameId)> = Vec::new();
+        for frame_id in items.into_iter().filter(|f| *f > 0).collect::<BTreeSet<_>>() {
+            match ranges.last_mut() {
+                Some((first, last))
+                    if last.checked_add(1) == Some(frame_id) && *last - *first + 1 < Self::MAX_RANGE_LEN =>
+                {
+                    *last = frame_id
+                }
+                _ => ranges.push((frame_id, frame_id)),
+            }
+        }
+
+        if ranges.is_empty() {
+            return vec![Self {
+                cumulative,
+                ..Default::default()
+            }];
+        }
+
+        ranges
+            .chunks(Self::MAX_RANGES.max(1))
+            .map(|chunk| Self {
+                cumulative,
+                ranges: chunk.iter().copied().collect(),
+            })
+            .collect()
+    }
+}
+
+impl<const C: usize> TryFrom<&[u8]> for SelectiveAcknowledgement<C> {
+    type Error = SessionError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        if value.len() != Self::SIZE {
+            return Err(SessionError::ParseError);
+        }
+
+        let (cumulative, ranges) = value.split_at(size_of::<FrameId>());
+        let mut ret = Self {
+            cumulative: FrameId::from_be_bytes(cumulative.try_into().map_err(|_| SessionError::ParseError)?),
+            ..Default::default()
+        };
+        for entry in ranges.chunks_exact(Self::ENTRY_SIZE) {
+            let first = FrameId::from_be_bytes(entry[0..4].try_into().map_err(|_| SessionError::ParseError)?);
+            let last = FrameId::from_be_bytes(entry[4..8].try_into().map_err(|_| SessionError::ParseError)?);
+            if first > 0 {
+                if first > last || last - first >= Self::MAX_RANGE_LEN {
+                    return Err(SessionError::ParseError);
+                }
+                ret.ranges.insert(first, last);
+            }
+        }
+        Ok(ret)
+    }
+}
+
+impl<const C: usize> From<SelectiveAcknowledgement<C>> for Vec<u8> {
+    fn from(value: SelectiveAcknowledgement<C>) -> Self {
+        value
+            .cumulative
+            .to_be_bytes()
+            .into_iter()
+            .chain(
+                value
+                    .ranges
+                    .into_iter()
+                    .take(SelectiveAcknowledgement::<C>::MAX_RANGES)
+                    .flat_map(|(first, last)| first.to_be_bytes().into_iter().chain(last.to_be_bytes())),
+            )
+            .chain(std::iter::repeat(0_u8))
+            .take(SelectiveAcknowledgement::<C>::SIZE)
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -298,4 +618,134 @@ mod tests {
         ];
         assert_eq!(missing, expected);
     }
+
+    #[test]
+    fn segment_range_request_should_merge_consecutive_segments() -> anyhow::Result<()> {
+        let missing = [
+            SegmentId(1, 0),
+            SegmentId(1, 1),
+            SegmentId(1, 2),
+            SegmentId(1, 300),
+            SegmentId(2, 3),
+            SegmentId(2, 4),
+        ];
+        let req = SegmentRangeRequest::<1000>::from_iter(missing.into_iter().rev());
+
+        assert_eq!(3, req.num_ranges());
+        assert_eq!(missing.len(), req.len());
+
+        let decoded = SegmentRangeRequest::<1000>::try_from(Vec::from(req.clone()).as_slice())?;
+        assert_eq!(req, decoded);
+        assert_eq!(missing.to_vec(), decoded.into_iter().collect::<Vec<_>>());
+        Ok(())
+    }
+
+    #[test]
+    fn segment_range_request_multiple_should_chunk_ranges() {
+        const MAX: usize = SegmentRangeRequest::<100>::MAX_ENTRIES;
+
+        // Every other segment is missing, so no ranges can be merged
+        let missing = (0..(2 * MAX + 1) as SeqNum)
+            .map(|i| SegmentId(1, 2 * i))
+            .collect::<Vec<_>>();
+        let reqs = SegmentRangeRequest::<100>::new_multiple(missing.clone());
+
+        let chunk_lengths: Vec<_> = reqs.iter().map(|r| r.num_ranges()).collect();
+        assert_eq!(chunk_lengths, [MAX, MAX, 1]);
+        assert_eq!(missing, reqs.into_iter().flatten().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn selective_ack_should_merge_frames_into_ranges() -> anyhow::Result<()> {
+        let mut sacks = SelectiveAcknowledgement::<1000>::new_multiple(3, [1, 2, 5, 6, 7, 8, 10, 1000, 1001]);
+        assert_eq!(1, sacks.len());
+
+        let sack = sacks.remove(0);
+        assert_eq!(3, sack.cumulative());
+        assert_eq!(
+            vec![1..=2, 5..=8, 10..=10, 1000..=1001],
+            sack.ranges().collect::<Vec<_>>()
+        );
+        assert_eq!(9, sack.len());
+
+        let decoded = SelectiveAcknowledgement::<1000>::try_from(Vec::from(sack.clone()).as_slice())?;
+        assert_eq!(sack, decoded);
+        Ok(())
+    }
+
+    #[test]
+    fn selective_ack_multiple_should_chunk_ranges() {
+        const MAX: usize = SelectiveAcknowledgement::<100>::MAX_RANGES;
+
+        let frames = (1..=(2 * MAX + 1) as FrameId).map(|i| 2 * i).collect::<Vec<_>>();
+        let sacks = SelectiveAcknowledgement::<100>::new_multiple(1, frames.clone());
+
+        let chunk_lengths: Vec<_> = sacks.iter().map(|s| s.ranges().count()).collect();
+        assert_eq!(chunk_lengths, [MAX, MAX, 1]);
+        assert!(sacks.iter().all(|s| s.cumulative() == 1));
+        assert_eq!(
+            frames,
+            sacks.iter().flat_map(|s| s.selective_frames()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn selective_ack_with_reversed_range_should_not_parse() {
+        let mut data = vec![0u8; SelectiveAcknowledgement::<100>::SIZE];
+        data[0..4].copy_from_slice(&10_u32.to_be_bytes());
+        data[4..8].copy_from_slice(&6_u32.to_be_bytes());
+        data[8..12].copy_from_slice(&5_u32.to_be_bytes());
+
+        assert!(SelectiveAcknowledgement::<100>::try_from(data.as_slice()).is_err());
+    }
+
+    #[test]
+    fn selective_ack_with_oversized_range_should_not_parse() {
+        let mut data = vec![0u8; SelectiveAcknowledgement::<100>::SIZE];
+        data[4..8].copy_from_slice(&1_u32.to_be_bytes());
+        data[8..12].copy_from_slice(&u32::MAX.to_be_bytes());
+
+        assert!(SelectiveAcknowledgement::<100>::try_from(data.as_slice()).is_err());
+    }
+
+    #[test]
+    fn selective_ack_should_split_long_runs_into_bounded_ranges() -> anyhow::Result<()> {
+        const MAX_LEN: FrameId = SelectiveAcknowledgement::<1000>::MAX_RANGE_LEN;
+
+        let mut sacks = SelectiveAcknowledgement::<1000>::new_multiple(0, 1..=MAX_LEN + 1);
+        assert_eq!(1, sacks.len());
+
+        let sack = sacks.remove(0);
+        assert_eq!(
+            vec![1..=MAX_LEN, MAX_LEN + 1..=MAX_LEN + 1],
+            sack.ranges().collect::<Vec<_>>()
+        );
+
+        let decoded = SelectiveAcknowledgement::<1000>::try_from(Vec::from(sack.clone()).as_slice())?;
+        assert_eq!(sack, decoded);
+        Ok(())
+    }
+
+    #[test]
+    fn selective_ack_frames_within_window_should_be_clamped() {
+        let sack = SelectiveAcknowledgement::<1000>::new_multiple(0, [1, 2, 3, 5, 6, 7, 8, 20]).remove(0);
+
+        assert_eq!(
+            vec![2, 3, 5, 6],
+            sack.selective_frames_within(2..=6).collect::<Vec<_>>()
+        );
+        assert_eq!(0, sack.selective_frames_within(9..=19).count());
+        let (start, end) = (1, 0);
+        assert_eq!(0, sack.selective_frames_within(start..=end).count());
+    }
+
+    #[test]
+    fn segment_range_request_beyond_max_frame_length_should_not_parse() {
+        let mut data = vec![0u8; SegmentRangeRequest::<100>::SIZE];
+        data[0..4].copy_from_slice(&1_u32.to_be_bytes());
+        data[4..6].copy_from_slice(&1_u16.to_be_bytes());
+        data[6..8].copy_from_slice(&SeqIndicator::MAX.to_be_bytes());
+
+        assert!(SegmentRangeRequest::<100>::try_from(data.as_slice()).is_err());
+    }
 }
@@ -2,17 +2,27 @@
 //!
 //! The protocol components are built via low-level types of the `frame` module, such as
 //! [`Segment`] and [`Frame`].
-//! Most importantly, the `Session` protocol fixes the maximum number of segments per frame
-//! to 8 (see `SessionMessage::MAX_SEGMENTS_PER_FRAME`).
+//! Most importantly, the version 1 of the `Session` protocol fixes the maximum number of segments
+//! per frame to 64 (see [`SeqIndicator::MAX_V1`]), and only the first 8 segments of a frame
+//! can be requested for retransmission (see [`SegmentRequest::MAX_MISSING_SEGMENTS_PER_FRAME`]).
 //! Since each segment must fit within a maximum transmission unit (MTU),
-//! a frame can be at most *eight* times the size of the MTU.
+//! a frame of a reliable socket can be at most *eight* times the size of the MTU.
 //!
-//! The [current version](SessionMessage::VERSION) of the protocol consists of three
+//! The version 1 of the protocol consists of three
 //! messages that are sent and received via the underlying transport:
 //! - [`Segment message`](Segment)
 //! - [`Retransmission request`](SegmentRequest)
 //! - [`Frame acknowledgement`](FrameAcknowledgements)
 //!
+//! The [current version](SessionMessage::VERSION) 2 of the protocol widens the sequence numbers of
+//! segments to 16 bits (see [`SeqIndicator::MAX`]) and adds two more messages:
+//! - [`Segment range retransmission request`](SegmentRangeRequest)
+//! - [`Selective frame acknowledgement`](SelectiveAcknowledgement)
+//!
+//! The version is carried by every message, so a peer always decodes messages of all
+//! [supported versions](SessionMessage::MIN_VERSION). The version used for outgoing messages
+//! is chosen by the socket.
+//!
 //! All of these messages are bundled within the [`SessionMessage`] enum,
 //! which is then encoded as a byte array of a maximum
 //! MTU size `C` (which is a generic const argument of the `SessionMessage` type).
@@ -49,6 +59,21 @@
 //! frames. There can be at most [`MAX_ACK_FRAMES`](FrameAcknowledgements::MAX_ACK_FRAMES)
 //! per message. If more frames need to be acknowledged, more messages need to be sent.
 //! If the message contains fewer entries, it is padded with zeros (0 is not a valid frame ID).
+//!
+//! ## Segment range retransmission request message ([`RangeRequest`](SessionMessage::RangeRequest))
+//!
+//! Version 2 counterpart of the Retransmission request message. Its entries are triplets of
+//! [frame ID](FrameId), index of the first missing segment and the number of consecutive missing
+//! segments, each [`ENTRY_SIZE`](SegmentRangeRequest::ENTRY_SIZE) bytes-long.
+//! Unused entries are padded with zeros.
+//!
+//! ## Selective frame acknowledgement message ([`SelectiveAcknowledge`](SessionMessage::SelectiveAcknowledge))
+//!
+//! Version 2 counterpart of the Frame acknowledgement message. It starts with a cumulative
+//! [frame ID](FrameId), indicating that all frames up to and including it were either received or
+//! discarded by the recipient, followed by up to [`MAX_RANGES`](SelectiveAcknowledgement::MAX_RANGES)
+//! pairs of the first and last frame ID of inclusive ranges of completely received frames.
+//! Unused pairs are padded with zeros.
 
 mod frames;
 mod messages;
@@ -56,7 +81,10 @@ mod messages;
 use asynchronous_codec::{Decoder, Encoder};
 use bytes::{Buf, BufMut, BytesMut};
 pub use frames::{Frame, FrameId, OrderedFrame, Segment, SegmentId, SeqIndicator, SeqNum};
-pub use messages::{FrameAcknowledgements, MissingSegmentsBitmap, SegmentRequest};
+pub use messages::{
+    FrameAcknowledgements, MissingSegmentsBitmap, SegmentRangeIter, SegmentRangeRequest, SegmentRequest,
+    SelectiveAcknowledgement,
+};
 
 use crate::errors::SessionError;
 
@@ -72,6 +100,14 @@ pub enum SessionMessage<const C: usize> {
     Request(SegmentRequest<C>),
     /// Represents a message containing [frame acknowledgements](FrameAcknowledgements).
     Acknowledge(FrameAcknowledgements<C>),
+    /// Represents a message containing a [request](SegmentRangeRequest) for segment ranges.
+    ///
+    /// Since protocol version 2.
+    RangeRequest(SegmentRangeRequest<C>),
+    /// Represents a message containing [selective frame acknowledgements](SelectiveAcknowledgement).
+    ///
+    /// Since protocol version 2.
+    SelectiveAcknowledge(SelectiveAcknowledgement<C>),
 }
 
 impl<const C: usize> std::fmt::Display for SessionMessage<C> {
@@ -80,6 +116,13 @@ impl<const C: usize> std::fmt::Display for SessionMessage<C> {
             SessionMessage::Segment(s) => write!(f, "segment {}", s.id()),
             SessionMessage::Request(r) => write!(f, "retransmission request of {:?}", r.0),
             SessionMessage::Acknowledge(a) => write!(f, "acknowledgement of {:?}", a.0),
+            SessionMessage::RangeRequest(r) => write!(f, "retransmission request of ranges {:?}", r.0),
+            SessionMessage::SelectiveAcknowledge(a) => write!(
+                f,
+                "acknowledgement up to {} and of {:?}",
+                a.cumulative,
+                a.ranges().collect::<Vec<_>>()
+            ),
         }
     }
 }
@@ -89,14 +132,36 @@ impl<const C: usize> SessionMessage<C> {
     /// This is currently the version byte, the size of [`SessionMessageDiscriminants`] representation
     /// and two bytes for the message length.
     pub const HEADER_SIZE: usize = 1 + size_of::<SessionMessageDiscriminants>() + size_of::<u16>();
-    /// Maximum size of the message in v1.
+    /// Maximum size of the message.
     pub const MAX_MESSAGE_LENGTH: usize = C.saturating_sub(Self::HEADER_SIZE);
-    /// Size of the overhead that's added to the raw payload of each [`Segment`].
+    /// Oldest supported version of the protocol.
+    pub const MIN_VERSION: u8 = 1;
+    /// Size of the overhead that's added to the raw payload of each [`Segment`] in the protocol version 1.
     ///
     /// This amounts to [`SessionMessage::HEADER_SIZE`] + [`Segment::HEADER_SIZE`].
+    /// See [`SessionMessage::segment_overhead`] for other versions.
     pub const SEGMENT_OVERHEAD: usize = Self::HEADER_SIZE + Segment::HEADER_SIZE;
     /// Current version of the protocol.
-    pub const VERSION: u8 = 1;
+    pub const VERSION: u8 = 2;
+
+    /// Checks if the given protocol `version` is supported.
+    #[inline]
+    pub const fn is_supported_version(version: u8) -> bool {
+        version >= Self::MIN_VERSION && version <= Self::VERSION
+    }
+
+    /// Size of the overhead that's added to the raw payload of each [`Segment`] in the given
+    /// protocol `version`.
+    #[inline]
+    pub const fn segment_overhead(version: u8) -> usize {
+        Self::HEADER_SIZE + Segment::header_size(version)
+    }
+
+    /// Maximum size of the payload of a single [`Segment`] in the given protocol `version`.
+    #[inline]
+    pub const fn max_segment_payload(version: u8) -> usize {
+        C - Self::segment_overhead(version)
+    }
 
     /// Returns the minimum size of a [`SessionMessage`].
     pub fn minimum_message_size() -> usize {
@@ -105,12 +170,49 @@ impl<const C: usize> SessionMessage<C> {
             + Segment::HEADER_SIZE
                 .min(SegmentRequest::<C>::SIZE)
                 .min(FrameAcknowledgements::<C>::SIZE)
+                .min(SegmentRangeRequest::<C>::SIZE)
+                .min(SelectiveAcknowledgement::<C>::SIZE)
+    }
+
+    /// Returns the oldest protocol version that is able to encode this message.
+    pub fn min_version(&self) -> u8 {
+        match self {
+            SessionMessage::Segment(s)
+                if s.seq_idx > SeqIndicator::MAX_V1 || s.seq_flags.seq_len() > SeqIndicator::MAX_V1 =>
+            {
+                2
+            }
+            SessionMessage::Segment(_) | SessionMessage::Request(_) | SessionMessage::Acknowledge(_) => 1,
+            SessionMessage::RangeRequest(_) | SessionMessage::SelectiveAcknowledge(_) => 2,
+        }
     }
 
     /// Convenience method to encode the session message.
     pub fn into_encoded(self) -> Box<[u8]> {
         Vec::from(self).into_boxed_slice()
     }
+
+    /// Reads the [IDs](SegmentId) of all [`Segment`] messages in the given encoded messages,
+    /// without decoding them.
+    ///
+    /// Reading stops at the first incomplete message or a message of an unsupported version.
+    pub fn segment_ids(data: &[u8]) -> impl Iterator<Item = SegmentId> + '_ {
+        let mut rest = data;
+        std::iter::from_fn(move || {
+            loop {
+                let (header, tail) = rest.split_at_checked(Self::HEADER_SIZE)?;
+                let version = header[0];
+                if !Self::is_supported_version(version) {
+                    return None;
+                }
+                let (payload, tail) = tail.split_at_checked(u16::from_be_bytes([header[2], header[3]]) as usize)?;
+                rest = tail;
+                if header[1] == SessionMessageDiscriminants::Segment as u8 {
+                    return Segment::peek_id(payload, version);
+                }
+            }
+        })
+    }
 }
 
 impl<const C: usize> From<SessionMessage<C>> for Vec<u8> {
@@ -120,7 +222,7 @@ impl<const C: usize> From<SessionMessage<C>> for Vec<u8> {
         );
 
         let mut result = BytesMut::new();
-        SessionCodec::<C>
+        SessionCodec::<C>::new(message.min_version())
             .encode(message, &mut result)
             .expect("encoding never fails");
 
@@ -132,14 +234,36 @@ impl<const C: usize> TryFrom<&[u8]> for SessionMessage<C> {
     type Error = SessionError;
 
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
-        SessionCodec
+        SessionCodec::default()
             .decode(&mut BytesMut::from(value))?
             .ok_or(SessionError::IncorrectMessageLength)
     }
 }
 
-#[derive(Clone, Copy, Default)]
-pub struct SessionCodec<const C: usize>;
+/// Encoder and decoder of [`SessionMessages`](SessionMessage).
+///
+/// Messages of all supported protocol versions are decoded, while the messages are always
+/// encoded using the version the codec was created with.
+#[derive(Clone, Copy, Debug)]
+pub struct SessionCodec<const C: usize> {
+    version: u8,
+}
+
+impl<const C: usize> SessionCodec<C> {
+    /// Creates a codec that encodes messages using the given protocol `version`.
+    ///
+    /// The `version` must be [supported](SessionMessage::is_supported_version).
+    pub fn new(version: u8) -> Self {
+        debug_assert!(SessionMessage::<C>::is_supported_version(version));
+        Self { version }
+    }
+}
+
+impl<const C: usize> Default for SessionCodec<C> {
+    fn default() -> Self {
+        Self::new(SessionMessage::<C>::MIN_VERSION)
+    }
+}
 
 impl<const C: usize> Encoder for SessionCodec<C> {
     type Error = SessionError;
@@ -153,9 +277,14 @@ impl<const C: usize> Encoder for SessionCodec<C> {
         let disc = SessionMessageDiscriminants::from(&item) as u8;
 
         let msg = match item {
-            SessionMessage::Segment(s) => Vec::from(s),
+            SessionMessage::Segment(s) => s.encode(self.version)?,
             SessionMessage::Request(r) => Vec::from(r),
             SessionMessage::Acknowledge(a) => Vec::from(a),
+            SessionMessage::RangeRequest(_) | SessionMessage::SelectiveAcknowledge(_) if self.version < 2 => {
+                return Err(SessionError::WrongVersion);
+            }
+            SessionMessage::RangeRequest(r) => Vec::from(r),
+            SessionMessage::SelectiveAcknowledge(a) => Vec::from(a),
         };
 
         if msg.len() > SessionMessage::<C>::MAX_MESSAGE_LENGTH {
@@ -163,7 +292,7 @@ impl<const C: usize> Encoder for SessionCodec<C> {
         }
 
         let msg_len = msg.len() as u16;
-        dst.put_u8(SessionMessage::<C>::VERSION);
+        dst.put_u8(self.version);
         dst.put_u8(disc);
         dst.put_u16(msg_len);
         dst.extend_from_slice(&msg);
@@ -186,7 +315,8 @@ impl<const C: usize> Decoder for SessionCodec<C> {
         }
 
         // Protocol version
-        if src[0] != SessionMessage::<C>::VERSION {
+        let version = src[0];
+        if !SessionMessage::<C>::is_supported_version(version) {
             return Err(SessionError::WrongVersion);
         }
 
@@ -196,7 +326,7 @@ impl<const C: usize> Decoder for SessionCodec<C> {
         // Message length
         let payload_len = u16::from_be_bytes([src[2], src[3]]) as usize;
 
-        // Check the maximum message length for version 1
+        // Check the maximum message length
         if payload_len > SessionMessage::<C>::MAX_MESSAGE_LENGTH {
             return Err(SessionError::IncorrectMessageLength);
         }
@@ -207,16 +337,20 @@ impl<const C: usize> Decoder for SessionCodec<C> {
         }
 
         // Read the message
+        let payload = &src[SessionMessage::<C>::HEADER_SIZE..SessionMessage::<C>::HEADER_SIZE + payload_len];
         let res = match SessionMessageDiscriminants::from_repr(disc).ok_or(SessionError::UnknownMessageTag)? {
-            SessionMessageDiscriminants::Segment => SessionMessage::Segment(
-                src[SessionMessage::<C>::HEADER_SIZE..SessionMessage::<C>::HEADER_SIZE + payload_len].try_into()?,
-            ),
-            SessionMessageDiscriminants::Request => SessionMessage::Request(
-                src[SessionMessage::<C>::HEADER_SIZE..SessionMessage::<C>::HEADER_SIZE + payload_len].try_into()?,
-            ),
-            SessionMessageDiscriminants::Acknowledge => SessionMessage::Acknowledge(
-                src[SessionMessage::<C>::HEADER_SIZE..SessionMessage::<C>::HEADER_SIZE + payload_len].try_into()?,
-            ),
+            SessionMessageDiscriminants::Segment => SessionMessage::Segment(Segment::decode(payload, version)?),
+            SessionMessageDiscriminants::Request => SessionMessage::Request(payload.try_into()?),
+            SessionMessageDiscriminants::Acknowledge => SessionMessage::Acknowledge(payload.try_into()?),
+            SessionMessageDiscriminants::RangeRequest | SessionMessageDiscriminants::SelectiveAcknowledge
+                if version < 2 =>
+            {
+                return Err(SessionError::UnknownMessageTag);
+            }
+            SessionMessageDiscriminants::RangeRequest => SessionMessage::RangeRequest(payload.try_into()?),
+            SessionMessageDiscriminants::SelectiveAcknowledge => {
+                SessionMessage::SelectiveAcknowledge(payload.try_into()?)
+            }
         };
 
         src.advance(SessionMessage::<C>::HEADER_SIZE + payload_len);
@@ -237,15 +371,33 @@ mod tests {
         utils::segment,
     };
 
+    #[test]
+    fn ensure_session_protocol_version_2_values() {
+        assert_eq!(2, SessionMessage::<{ ApplicationData::PAYLOAD_SIZE }>::VERSION);
+        assert_eq!(
+            12,
+            SessionMessage::<{ ApplicationData::PAYLOAD_SIZE }>::segment_overhead(2)
+        );
+        assert_eq!(8, SegmentRangeRequest::<{ ApplicationData::PAYLOAD_SIZE }>::ENTRY_SIZE);
+        assert_eq!(
+            8,
+            SelectiveAcknowledgement::<{ ApplicationData::PAYLOAD_SIZE }>::ENTRY_SIZE
+        );
+    }
+
     #[test]
     fn ensure_session_protocol_version_1_values() {
         // All of these values are independent of C, so we can set C = 0
-        assert_eq!(1, SessionMessage::<{ ApplicationData::PAYLOAD_SIZE }>::VERSION);
+        assert_eq!(1, SessionMessage::<{ ApplicationData::PAYLOAD_SIZE }>::MIN_VERSION);
         assert_eq!(4, SessionMessage::<{ ApplicationData::PAYLOAD_SIZE }>::HEADER_SIZE);
         assert_eq!(
             10,
             SessionMessage::<{ ApplicationData::PAYLOAD_SIZE }>::SEGMENT_OVERHEAD
         );
+        assert_eq!(
+            SessionMessage::<{ ApplicationData::PAYLOAD_SIZE }>::SEGMENT_OVERHEAD,
+            SessionMessage::<{ ApplicationData::PAYLOAD_SIZE }>::segment_overhead(1)
+        );
         assert_eq!(
             session_socket_mtu::<{ ApplicationData::PAYLOAD_SIZE }>()
                 + SessionMessage::<{ ApplicationData::PAYLOAD_SIZE }>::SEGMENT_OVERHEAD
@@ -326,4 +478,122 @@ mod tests {
         assert_eq!(iter.next(), Some(SegmentId(10, 4)));
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn session_message_v2_should_serialize_and_deserialize() -> anyhow::Result<()> {
+        let msgs = [
+            SessionMessage::<466>::Segment(Segment {
+                frame_id: 10,
+                seq_idx: 1000,
+                seq_flags: SeqIndicator::new(1001),
+                data: Box::new([1, 2, 3]),
+            }),
+            SessionMessage::<466>::RangeRequest(SegmentRangeRequest::from_iter([
+                SegmentId(2, 10),
+                SegmentId(2, 11),
+                SegmentId(3, 500),
+            ])),
+            SessionMessage::<466>::SelectiveAcknowledge(
+                SelectiveAcknowledgement::new_multiple(5, [7, 8, 9, 100]).remove(0),
+            ),
+        ];
+
+        for msg_1 in msgs {
+            assert_eq!(2, msg_1.min_version());
+            let data = Vec::from(msg_1.clone());
+            assert_eq!(2, data[0]);
+
+            let msg_2 = SessionMessage::try_from(&data[..])?;
+            assert_eq!(msg_1, msg_2);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn session_message_segment_ids_should_be_read_without_decoding() -> anyhow::Result<()> {
+        let segments = segment(hex!("deadbeefcafebabe"), 4, 10)?;
+
+        let mut data = BytesMut::new();
+        SessionCodec::<466>::new(1).encode(SessionMessage::Segment(segments[0].clone()), &mut data)?;
+        SessionCodec::<466>::new(2).encode(
+            SessionMessage::Acknowledge(FrameAcknowledgements::try_from(vec![1, 2])?),
+            &mut data,
+        )?;
+        SessionCodec::<466>::new(2).encode(SessionMessage::Segment(segments[1].clone()), &mut data)?;
+
+        assert_eq!(
+            vec![segments[0].id(), segments[1].id()],
+            SessionMessage::<466>::segment_ids(&data).collect::<Vec<_>>()
+        );
+
+        // Truncated messages are not read
+        assert_eq!(
+            vec![segments[0].id()],
+            SessionMessage::<466>::segment_ids(&data[..data.len() - 1]).collect::<Vec<_>>()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn session_codec_should_decode_all_supported_versions() -> anyhow::Result<()> {
+        let segment = segment(hex!("deadbeefcafebabe"), 8, 10)?.remove(0);
+
+        let mut v1 = BytesMut::new();
+        SessionCodec::<466>::new(1).encode(SessionMessage::Segment(segment.clone()), &mut v1)?;
+        let mut v2 = BytesMut::new();
+        SessionCodec::<466>::new(2).encode(SessionMessage::Segment(segment.clone()), &mut v2)?;
+
+        assert_eq!(1, v1[0]);
+        assert_eq!(2, v2[0]);
+        assert_eq!(v1.len() + 2, v2.len());
+
+        let mut codec = SessionCodec::<466>::new(2);
+        assert_eq!(Some(SessionMessage::Segment(segment.clone())), codec.decode(&mut v1)?);
+        assert_eq!(Some(SessionMessage::Segment(segment)), codec.decode(&mut v2)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn session_codec_v1_should_not_encode_v2_messages() {
+        let mut buf = BytesMut::new();
+        let ack = SelectiveAcknowledgement::<466>::new_multiple(1, [3]).remove(0);
+        assert!(matches!(
+            SessionCodec::<466>::new(1).encode(SessionMessage::SelectiveAcknowledge(ack), &mut buf),
+            Err(SessionError::WrongVersion)
+        ));
+
+        let wide_segment = Segment {
+            frame_id: 1,
+            seq_idx: 100,
+            seq_flags: SeqIndicator::new(101),
+            data: Box::new([]),
+        };
+        assert!(
+            SessionCodec::<466>::new(1)
+                .encode(SessionMessage::Segment(wide_segment), &mut buf)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn session_codec_should_reject_v2_messages_with_v1_version() {
+        let mut data = Vec::from(SessionMessage::<466>::RangeRequest(SegmentRangeRequest::from_iter([
+            SegmentId(1, 1),
+        ])));
+        data[0] = 1;
+
+        assert!(matches!(
+            SessionMessage::<466>::try_from(&data[..]),
+            Err(SessionError::UnknownMessageTag)
+        ));
+
+        data[0] = SessionMessage::<466>::VERSION + 1;
+        assert!(matches!(
+            SessionMessage::<466>::try_from(&data[..]),
+            Err(SessionError::WrongVersion)
+        ));
+    }
 }
@@ -2,7 +2,8 @@
 //! a reliable socket, with segment/frame retransmission and frame acknowledgements.
 
 use std::{
-    sync::atomic::AtomicBool,
+    collections::BTreeMap,
+    sync::atomic::{AtomicBool, AtomicU32},
     time::{Duration, Instant},
 };
 
@@ -15,7 +16,10 @@ use crate::{
     errors::SessionError,
     flow_control::DeliveryTap,
     processing::types::FrameInspector,
-    protocol::{FrameAcknowledgements, FrameId, Segment, SegmentId, SegmentRequest, SeqIndicator, SessionMessage},
+    protocol::{
+        FrameAcknowledgements, FrameId, MissingSegmentsBitmap, Segment, SegmentId, SegmentRangeRequest, SegmentRequest,
+        SelectiveAcknowledgement, SeqIndicator, SessionMessage,
+    },
     socket::{SocketState, state::SocketComponents},
     utils::{
         RetriedFrameId, RingBufferProducer, RingBufferView, next_deadline_with_backoff, searchable_ringbuffer,
@@ -128,6 +132,64 @@ struct AcknowledgementStateContext<const C: usize> {
     ctl_tx: CrossfireSink<SessionMessage<C>>,
 }
 
+/// Creates retransmission requests for the `missing` segments, using the messages of the given protocol `version`.
+///
+/// Protocol version 1 can only request the first [`SegmentRequest::MAX_MISSING_SEGMENTS_PER_FRAME`]
+/// segments of each frame.
+fn retransmission_requests<const C: usize>(
+    version: u8,
+    missing: impl IntoIterator<Item = SegmentId>,
+) -> Vec<SessionMessage<C>> {
+    if version > 1 {
+        SegmentRangeRequest::<C>::new_multiple(missing)
+            .into_iter()
+            .filter(|req| !req.is_empty())
+            .map(SessionMessage::RangeRequest)
+            .collect()
+    } else {
+        let mut bitmaps = BTreeMap::<FrameId, MissingSegmentsBitmap>::new();
+        missing
+            .into_iter()
+            .filter(|SegmentId(_, seq_idx)| (*seq_idx as usize) < SegmentRequest::<C>::MAX_MISSING_SEGMENTS_PER_FRAME)
+            .for_each(|SegmentId(frame_id, seq_idx)| {
+                bitmaps
+                    .entry(frame_id)
+                    .or_insert(MissingSegmentsBitmap::ZERO)
+                    .set(seq_idx as usize, true)
+            });
+
+        bitmaps
+            .into_iter()
+            .collect::<Vec<_>>()
+            .chunks(SegmentRequest::<C>::MAX_ENTRIES.max(1))
+            .map(|chunk| SessionMessage::Request(chunk.iter().copied().collect()))
+            .collect()
+    }
+}
+
+/// Creates acknowledgements of the `completed` frames, using the messages of the given protocol `version`.
+///
+/// Protocol version 2 additionally acknowledges all frames up to `cumulative` in each message.
+fn acknowledgements<const C: usize>(
+    version: u8,
+    cumulative: FrameId,
+    completed: impl IntoIterator<Item = FrameId>,
+) -> Vec<SessionMessage<C>> {
+    if version > 1 {
+        SelectiveAcknowledgement::<C>::new_multiple(cumulative, completed)
+            .into_iter()
+            .filter(|acks| !acks.is_empty())
+            .map(SessionMessage::SelectiveAcknowledge)
+            .collect()
+    } else {
+        FrameAcknowledgements::<C>::new_multiple(completed)
+            .into_iter()
+            .filter(|acks| !acks.is_empty())
+            .map(SessionMessage::Acknowledge)
+            .collect()
+    }
+}
+
 #[cfg_attr(doc, aquamarine::aquamarine)]
 /// Represents a Session socket state is able to process acknowledgements.
 ///
@@ -227,6 +289,16 @@ pub struct AcknowledgementState<const C: usize> {
     /// and retransmission-exhausted frames bump the tap's atomic meter, but acknowledgement and
     /// retransmission behaviour is unchanged whether or not it is installed.
     delivery_tap: Option<DeliveryTap>,
+    /// The highest frame ID up to which all incoming frames were emitted or discarded.
+    ///
+    /// Sent as the cumulative acknowledgement in protocol version 2.
+    incoming_resolved: std::sync::Arc<AtomicU32>,
+    /// The highest cumulatively acknowledged outgoing frame ID received.
+    outgoing_acknowledged: std::sync::Arc<AtomicU32>,
+    /// The highest outgoing frame ID of which a segment has been sent.
+    ///
+    /// Incoming acknowledgements are clamped to the frames sent so far.
+    outgoing_sent: std::sync::Arc<AtomicU32>,
 }
 
 impl<const C: usize> AcknowledgementState<C> {
@@ -237,6 +309,9 @@ impl<const C: usize> AcknowledgementState<C> {
             context: Default::default(),
             started: std::sync::Arc::new(AtomicBool::new(false)),
             delivery_tap: None,
+            incoming_resolved: std::sync::Arc::new(AtomicU32::new(0)),
+            outgoing_acknowledged: std::sync::Arc::new(AtomicU32::new(0)),
+            outgoing_sent: std::sync::Arc::new(AtomicU32::new(0)),
         }
     }
 
@@ -248,6 +323,104 @@ impl<const C: usize> AcknowledgementState<C> {
         self.delivery_tap = Some(tap);
         self
     }
+
+    /// Retransmits the `missing` segments (sorted by their IDs) via the Control stream.
+    fn retransmit_segments(&mut self, missing: Vec<SegmentId>) -> Result<(), SessionError> {
+        let ctx = self
+            .started
+            .load(std::sync::atomic::Ordering::Relaxed)
+            .then_some(self.context.as_mut())
+            .flatten()
+            .ok_or(SessionError::StateNotRunning)?;
+
+        let (mut missing_seg_ids, mut missing_frame_ids): (Vec<_>, Vec<_>) =
+            missing.into_iter().map(|s| (s, s.0)).unzip();
+
+        // Perform a single find to lock the RB only once
+        let segments = ctx.rb_rx.find(|s| {
+            // SegmentIds are guaranteed to be sorted, so we can use binary search
+            if let Ok(i) = missing_seg_ids.binary_search(&s.id()) {
+                missing_seg_ids.remove(i);
+                true
+            } else {
+                false
+            }
+        });
+
+        tracing::trace!(
+            found = segments.len(),
+            requested = missing_frame_ids.len(),
+            "found matching segments to be retransmitted"
+        );
+
+        // Partially acknowledged frames will not need to be fully resent in the future.
+        // Cancel all partially acknowledged frame resends.
+        if self.cfg.mode.is_full_ack_enabled() {
+            // Since the FrameIds are guaranteed to be sorted, we can simply dedup them.
+            missing_frame_ids.dedup();
+
+            if let Err(error) = ctx.outgoing_frame_retries_tx.send_many(
+                missing_frame_ids
+                    .into_iter()
+                    .map(|frame_id| (RetriedFrameId::no_retries(frame_id), Skip).into()),
+            ) {
+                tracing::error!(%error, "failed to cancel frame resend of partially acknowledged frames");
+            }
+        }
+
+        // Resend the segments via the Control Stream
+        segments
+            .into_iter()
+            .try_for_each(|s| {
+                tracing::trace!(seg_id = %s.id(), "retransmit segment on request");
+                ctx.ctl_tx.try_send(SessionMessage::Segment(s))
+            })
+            .map_err(|e| SessionError::ProcessingError(e.to_string()))
+    }
+
+    /// Processes the `acked` frames and frames `resolved` by the counterparty, which were not
+    /// acknowledged explicitly.
+    fn acknowledge_frames(
+        &mut self,
+        acked: Vec<FrameId>,
+        resolved: impl IntoIterator<Item = FrameId>,
+    ) -> Result<(), SessionError> {
+        // Cloned out before the mutable `ctx` borrow so the honest-clock tap can run inside the
+        // acknowledgement iteration without conflicting with the retry-cancel borrow.
+        let tap = self.delivery_tap.clone();
+        let full_ack = self.cfg.mode.is_full_ack_enabled();
+
+        let ctx = self
+            .started
+            .load(std::sync::atomic::Ordering::Relaxed)
+            .then_some(self.context.as_mut())
+            .flatten()
+            .ok_or(SessionError::StateNotRunning)?;
+
+        // Honest-clock tap: every received frame acknowledgement is proof of delivery, and must feed
+        // the flow-control window regardless of the ack mode. `full_ack` only governs whether we
+        // *also* cancel the outgoing full-frame retransmission below (in `Partial` mode the receiver
+        // drives retransmission, so there is nothing to cancel here — but the acks still arrive).
+        // Frames resolved only cumulatively are not reported, as they might have been discarded.
+        if let Some(tap) = &tap {
+            (0..acked.len()).for_each(|_| tap.on_acked_frame());
+        }
+
+        // Frame acknowledged, we will not need to resend it (full-ack mode only).
+        if full_ack
+            && let Err(error) = ctx.outgoing_frame_retries_tx.send_many(
+                acked
+                    .into_iter()
+                    .inspect(|frame_id| tracing::trace!(frame_id, "frame acknowledged"))
+                    .chain(resolved)
+                    .map(|frame_id| (RetriedFrameId::no_retries(frame_id), Skip).into()),
+            )
+        {
+            tracing::error!(%error, "failed to cancel frame resend");
+        }
+
+        Ok(())
+    }
 }
 
 impl<const C: usize> SocketState<C> for AcknowledgementState<C> {
@@ -261,6 +434,7 @@ impl<const C: usize> SocketState<C> for AcknowledgementState<C> {
             return Err(SessionError::InvalidState("state is already running".into()));
         }
 
+        let version = socket_components.version;
         let (incoming_frame_retries_tx, incoming_frame_retries_rx) = skip_delay_channel();
         let (outgoing_frame_retries_tx, outgoing_frame_retries_rx) = skip_delay_channel();
         let (rb_tx, rb_rx) = searchable_ringbuffer(self.cfg.lookbehind_segments);
@@ -290,7 +464,7 @@ impl<const C: usize> SocketState<C> for AcknowledgementState<C> {
             hopr_utils::runtime::prelude::spawn(incoming_frame_retries_rx
                 .filter_map(move |rf| {
                     let frame_id = rf.frame_id;
-                    let missing_segments = frame_inspector_clone.missing_segments(&frame_id).unwrap_or_default();
+                    let missing_segments = frame_inspector_clone.missing_segment_ids(&frame_id).unwrap_or_default();
                     if !missing_segments.is_empty() {
                         // Find out if we need to subscribe for further retries of this Frame
                         if let Some(next) = rf.next() {
@@ -305,15 +479,16 @@ impl<const C: usize> SocketState<C> for AcknowledgementState<C> {
                             tracing::debug!(frame_id, "last request of incoming frame segments");
                         }
 
-                        futures::future::ready(Some((frame_id, missing_segments)))
+                        futures::future::ready(Some(missing_segments))
                     } else {
                         tracing::debug!(frame_id, "no more missing segments in frame");
                         futures::future::ready(None)
                     }
                 })
                 .ready_chunks(SegmentRequest::<C>::MAX_ENTRIES)
-                .inspect(|r| tracing::trace!(req = ?r, "requesting segments resend"))
-                .map(|a| Ok(SessionMessage::<C>::Request(a.into_iter().collect())))
+                .flat_map(move |missing| futures::stream::iter(retransmission_requests::<C>(version, missing.into_iter().flatten())))
+                .inspect(|r| tracing::trace!(req = %r, "requesting segments resend"))
+                .map(Ok)
                 .forward(ctl_tx_clone)
                 .map(move |res| match res {
                     Ok(_) => tracing::debug!("incoming frame resends processing done"),
@@ -326,13 +501,24 @@ impl<const C: usize> SocketState<C> for AcknowledgementState<C> {
         // Send out Frame Acknowledgements chunked as Control messages
         let ctl_tx_clone = context.ctl_tx.clone();
         let ack_delay = self.cfg.acknowledgement_delay;
+        let incoming_resolved = self.incoming_resolved.clone();
+        let mut sent_cumulative = 0;
         hopr_utils::runtime::prelude::spawn(
             ack_rx
                 .buffer(futures_time::time::Duration::from(ack_delay))
-                .flat_map(|acks| futures::stream::iter(FrameAcknowledgements::<C>::new_multiple(acks)))
-                .filter(|acks| futures::future::ready(!acks.is_empty()))
-                .inspect(|acks| tracing::trace!(?acks, "acknowledgements sent"))
-                .map(|acks| Ok(SessionMessage::<C>::Acknowledge(acks)))
+                .flat_map(move |acks| {
+                    // Do not repeat the cumulative acknowledgement if it did not advance
+                    let cumulative = incoming_resolved.load(std::sync::atomic::Ordering::Relaxed);
+                    let msgs = if !acks.is_empty() || cumulative > sent_cumulative {
+                        sent_cumulative = cumulative;
+                        acknowledgements::<C>(version, cumulative, acks)
+                    } else {
+                        Vec::new()
+                    };
+                    futures::stream::iter(msgs)
+                })
+                .inspect(|acks| tracing::trace!(%acks, "acknowledgements sent"))
+                .map(Ok)
                 .forward(ctl_tx_clone)
                 .map(move |res| match res {
                     Ok(_) => tracing::debug!("acknowledgement forwarding done"),
@@ -444,95 +630,49 @@ impl<const C: usize> SocketState<C> for AcknowledgementState<C> {
         // The state will respond to segment retransmission requests even
         // if it has this feature disabled in the config.
         tracing::trace!(count = request.len(), "segment retransmission requested");
-
-        let ctx = self
-            .started
-            .load(std::sync::atomic::Ordering::Relaxed)
-            .then_some(self.context.as_mut())
-            .flatten()
-            .ok_or(SessionError::StateNotRunning)?;
-
-        let (mut missing_seg_ids, mut missing_frame_ids): (Vec<_>, Vec<_>) =
-            request.into_iter().map(|s| (s, s.0)).unzip();
-
-        // Perform a single find to lock the RB only once
-        let segments = ctx.rb_rx.find(|s| {
-            // SegmentIds are guaranteed to be sorted, so we can use binary search
-            if let Ok(i) = missing_seg_ids.binary_search(&s.id()) {
-                missing_seg_ids.remove(i);
-                true
-            } else {
-                false
-            }
-        });
-
-        tracing::trace!(
-            found = segments.len(),
-            requested = missing_frame_ids.len(),
-            "found matching segments to be retransmitted"
-        );
-
-        // Partially acknowledged frames will not need to be fully resent in the future.
-        // Cancel all partially acknowledged frame resends.
-        if self.cfg.mode.is_full_ack_enabled() {
-            // Since the FrameIds are guaranteed to be sorted, we can simply dedup them.
-            missing_frame_ids.dedup();
-
-            if let Err(error) = ctx.outgoing_frame_retries_tx.send_many(
-                missing_frame_ids
-                    .into_iter()
-                    .map(|frame_id| (RetriedFrameId::no_retries(frame_id), Skip).into()),
-            ) {
-                tracing::error!(%error, "failed to cancel frame resend of partially acknowledged frames");
-            }
-        }
-
-        // Resend the segments via the Control Stream
-        segments
-            .into_iter()
-            .try_for_each(|s| {
-                tracing::trace!(seg_id = %s.id(), "retransmit segment on request");
-                ctx.ctl_tx.try_send(SessionMessage::Segment(s))
-            })
-            .map_err(|e| SessionError::ProcessingError(e.to_string()))
+        self.retransmit_segments(request.into_iter().collect())
     }
 
     #[tracing::instrument(name = "AcknowledgementState::incoming_acknowledged_frames", skip(self), fields(session_id = self.id))]
     fn incoming_acknowledged_frames(&mut self, ack: FrameAcknowledgements<C>) -> Result<(), SessionError> {
         tracing::trace!(count = ack.len(), "frame acknowledgements received");
+        self.acknowledge_frames(ack.into_iter().collect(), std::iter::empty())
+    }
 
-        // Cloned out before the mutable `ctx` borrow so the honest-clock tap can run inside the
-        // acknowledgement iteration without conflicting with the retry-cancel borrow.
-        let tap = self.delivery_tap.clone();
-        let full_ack = self.cfg.mode.is_full_ack_enabled();
-
-        let ctx = self
-            .started
-            .load(std::sync::atomic::Ordering::Relaxed)
-            .then_some(self.context.as_mut())
-            .flatten()
-            .ok_or(SessionError::StateNotRunning)?;
-
-        // Honest-clock tap: every received frame acknowledgement is proof of delivery, and must feed
-        // the flow-control window regardless of the ack mode. `full_ack` only governs whether we
-        // *also* cancel the outgoing full-frame retransmission below (in `Partial` mode the receiver
-        // drives retransmission, so there is nothing to cancel here — but the acks still arrive).
-        if let Some(tap) = &tap {
-            (0..ack.len()).for_each(|_| tap.on_acked_frame());
-        }
+    #[tracing::instrument(name = "AcknowledgementState::incoming_range_retransmission_request", skip(self, request), fields(session_id = self.id))]
+    fn incoming_range_retransmission_request(&mut self, request: SegmentRangeRequest<C>) -> Result<(), SessionError> {
+        // The state will respond to segment retransmission requests even
+        // if it has this feature disabled in the config.
+        tracing::trace!(count = request.len(), "segment range retransmission requested");
+        // No more segments than the ring buffer can hold could ever be retransmitted
+        self.retransmit_segments(request.into_iter().take(self.cfg.lookbehind_segments).collect())
+    }
 
-        // Frame acknowledged, we will not need to resend it (full-ack mode only).
-        if full_ack
-            && let Err(error) = ctx.outgoing_frame_retries_tx.send_many(
-                ack.into_iter()
-                    .inspect(|frame_id| tracing::trace!(frame_id, "frame acknowledged"))
-                    .map(|frame_id| (RetriedFrameId::no_retries(frame_id), Skip).into()),
-            )
-        {
-            tracing::error!(%error, "failed to cancel frame resend");
-        }
+    #[tracing::instrument(name = "AcknowledgementState::incoming_selective_acknowledgement", skip(self), fields(session_id = self.id))]
+    fn incoming_selective_acknowledgement(&mut self, ack: SelectiveAcknowledgement<C>) -> Result<(), SessionError> {
+        tracing::trace!(
+            cumulative = ack.cumulative(),
+            count = ack.len(),
+            "selective frame acknowledgements received"
+        );
 
-        Ok(())
+        // The counterparty cannot acknowledge more frames than we have sent, and frames
+        // older than the lookbehind cannot be retransmitted anyway.
+        // Both the cumulative and selective acknowledgements are therefore clamped to this window
+        // before they are expanded, regardless of the ranges the counterparty claims.
+        let sent = self.outgoing_sent.load(std::sync::atomic::Ordering::Relaxed);
+        let oldest = sent.saturating_sub(self.cfg.lookbehind_segments as FrameId);
+        let cumulative = ack.cumulative().min(sent);
+
+        // Frames up to the cumulative acknowledgement were resolved by the counterparty,
+        // only those not seen in any previous cumulative acknowledgement need to be cancelled.
+        let previous = self
+            .outgoing_acknowledged
+            .fetch_max(cumulative, std::sync::atomic::Ordering::Relaxed);
+        let resolved = previous.max(oldest).saturating_add(1)..=cumulative;
+
+        let acked = ack.selective_frames_within(oldest.saturating_add(1)..=sent).collect();
+        self.acknowledge_frames(acked, resolved)
     }
 
     #[tracing::instrument(name = "AcknowledgementState::frame_complete", skip(self), fields(session_id = self.id))]
@@ -573,6 +713,10 @@ impl<const C: usize> SocketState<C> for AcknowledgementState<C> {
             .then_some(self.context.as_mut())
             .flatten()
             .ok_or(SessionError::StateNotRunning)?;
+
+        // Frames are emitted or discarded in sequence, so all frames up to this one are resolved
+        self.incoming_resolved
+            .fetch_max(id, std::sync::atomic::Ordering::Relaxed);
         Ok(())
     }
 
@@ -587,6 +731,10 @@ impl<const C: usize> SocketState<C> for AcknowledgementState<C> {
             .flatten()
             .ok_or(SessionError::StateNotRunning)?;
 
+        // Frames are emitted or discarded in sequence, so all frames up to this one are resolved
+        self.incoming_resolved
+            .fetch_max(frame_id, std::sync::atomic::Ordering::Relaxed);
+
         if self.cfg.mode.is_partial_ack_enabled() {
             // No more requesting of segment retransmissions from frames that were discarded
             if let Err(error) = ctx
@@ -611,6 +759,9 @@ impl<const C: usize> SocketState<C> for AcknowledgementState<C> {
             .flatten()
             .ok_or(SessionError::StateNotRunning)?;
 
+        self.outgoing_sent
+            .fetch_max(segment.frame_id, std::sync::atomic::Ordering::Relaxed);
+
         // Since segments are re-sent via Control stream, they are not later fed again
         // into the ring buffer.
         if !ctx.rb_tx.push(segment.clone()) {
@@ -672,6 +823,7 @@ mod tests {
         state.run(SocketComponents {
             inspector: inspector.into(),
             ctl_tx,
+            version: 1,
         })?;
 
         let acked_frame_ids = [1, 2, 3];
@@ -717,6 +869,7 @@ mod tests {
         state.run(SocketComponents {
             inspector: inspector.into(),
             ctl_tx,
+            version: 1,
         })?;
 
         let mut expected_frame_segments = Vec::new();
@@ -781,6 +934,7 @@ mod tests {
         state.run(SocketComponents {
             inspector: inspector.into(),
             ctl_tx,
+            version: 1,
         })?;
 
         let expected_segments = segment(hopr_types::crypto_random::random_bytes::<FRAME_SIZE>(), MTU, 1)?;
@@ -814,6 +968,7 @@ mod tests {
         state.run(SocketComponents {
             inspector: inspector.into(),
             ctl_tx,
+            version: 1,
         })?;
 
         let expected_segments = segment(hopr_types::crypto_random::random_bytes::<{ FRAME_SIZE * 2 }>(), MTU, 1)?;
@@ -850,6 +1005,7 @@ mod tests {
         state.run(SocketComponents {
             inspector: inspector.into(),
             ctl_tx,
+            version: 1,
         })?;
 
         let expected_segments = segment(hopr_types::crypto_random::random_bytes::<{ FRAME_SIZE * 2 }>(), MTU, 1)?;
@@ -890,6 +1046,7 @@ mod tests {
         state.run(SocketComponents {
             inspector: inspector.into(),
             ctl_tx,
+            version: 1,
         })?;
 
         let expected_segments_1 = segment(hopr_types::crypto_random::random_bytes::<{ FRAME_SIZE * 2 }>(), MTU, 1)?;
@@ -968,6 +1125,7 @@ mod tests {
         state.run(SocketComponents {
             inspector: inspector.into(),
             ctl_tx,
+            version: 1,
         })?;
 
         state.incoming_segment(&segments[0].id(), (segments.len() as SeqNum).try_into()?)?;
@@ -1014,6 +1172,7 @@ mod tests {
         state.run(SocketComponents {
             inspector: inspector.into(),
             ctl_tx,
+            version: 1,
         })?;
 
         state.incoming_segment(&segments[0].id(), (segments.len() as SeqNum).try_into()?)?;
@@ -1057,6 +1216,7 @@ mod tests {
         state.run(SocketComponents {
             inspector: inspector.clone().into(),
             ctl_tx,
+            version: 1,
         })?;
 
         state.incoming_segment(&segments[0].id(), (segments.len() as SeqNum).try_into()?)?;
@@ -1123,6 +1283,7 @@ mod tests {
         state.run(SocketComponents {
             inspector: inspector.clone().into(),
             ctl_tx,
+            version: 1,
         })?;
 
         // Segment 2
@@ -1177,4 +1338,226 @@ mod tests {
 
         Ok(())
     }
+
+    #[test_log::test(tokio::test)]
+    async fn ack_state_v2_must_selectively_acknowledge_completed_frames() -> anyhow::Result<()> {
+        let cfg = AcknowledgementStateConfig {
+            expected_packet_latency: Duration::from_millis(10),
+            acknowledgement_delay: Duration::from_millis(2),
+            ..Default::default()
+        };
+
+        let inspector = FrameInspector(FrameDashMap::with_capacity(10));
+        let (ctl_tx, ctl_rx) = bounded_sink_channel::<SessionMessage<MTU>>(1024);
+
+        let mut state = AcknowledgementState::<MTU>::new("test", cfg);
+        state.run(SocketComponents {
+            inspector: inspector.into(),
+            ctl_tx,
+            version: 2,
+        })?;
+
+        state.frame_emitted(1)?;
+        state.frame_discarded(2)?;
+        for frame_id in [1, 3, 4, 5, 7] {
+            state.frame_complete(frame_id)?;
+        }
+
+        tokio::time::sleep(cfg.acknowledgement_delay * 2).await;
+
+        state.stop()?;
+
+        let ctl_msgs = tokio::time::timeout(Duration::from_millis(100), ctl_rx.collect::<Vec<_>>())
+            .await
+            .context("timeout receiving Control messages")?;
+
+        // The cumulative acknowledgement might be sent before the selective ones
+        let sacks = ctl_msgs
+            .into_iter()
+            .map(|m| {
+                m.try_as_selective_acknowledge()
+                    .ok_or(anyhow::anyhow!("expected selective acknowledgement"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(Some(2), sacks.iter().map(|sack| sack.cumulative()).max());
+        assert_eq!(
+            std::collections::BTreeSet::from([1, 3, 4, 5, 7]),
+            sacks.iter().flat_map(|sack| sack.selective_frames()).collect()
+        );
+
+        Ok(())
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn ack_state_v2_must_not_resend_cumulatively_acknowledged_frames() -> anyhow::Result<()> {
+        let cfg = AcknowledgementStateConfig {
+            mode: AcknowledgementMode::Full,
+            expected_packet_latency: Duration::from_millis(5),
+            max_outgoing_frame_retries: 2,
+            ..Default::default()
+        };
+
+        let inspector = FrameInspector(FrameDashMap::with_capacity(10));
+        let (ctl_tx, ctl_rx) = bounded_sink_channel::<SessionMessage<MTU>>(1024);
+
+        let mut state = AcknowledgementState::<MTU>::new("test", cfg);
+        state.run(SocketComponents {
+            inspector: inspector.into(),
+            ctl_tx,
+            version: 2,
+        })?;
+
+        let mut segments = Vec::new();
+        for frame_id in 1..=4 {
+            for segment in segment(hopr_types::crypto_random::random_bytes::<FRAME_SIZE>(), MTU, frame_id)? {
+                state.segment_sent(&segment)?;
+                segments.push(segment);
+            }
+        }
+
+        // Frames 1 and 2 are resolved cumulatively, frame 4 is acknowledged selectively
+        state.incoming_selective_acknowledgement(SelectiveAcknowledgement::new_multiple(2, [4]).remove(0))?;
+
+        tokio::time::sleep(cfg.expected_packet_latency * 10).await;
+        state.stop()?;
+
+        let ctl_msgs = tokio::time::timeout(Duration::from_millis(100), ctl_rx.collect::<Vec<_>>())
+            .await
+            .context("timeout receiving Control messages")?;
+
+        let retransmitted_frames = ctl_msgs
+            .into_iter()
+            .map(|m| m.try_as_segment().ok_or(anyhow::anyhow!("must be segment")))
+            .map(|s| s.map(|s| s.frame_id))
+            .collect::<Result<std::collections::BTreeSet<_>, _>>()?;
+
+        assert_eq!(std::collections::BTreeSet::from([3]), retransmitted_frames);
+
+        Ok(())
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn ack_state_v2_must_clamp_selective_acknowledgement_to_sent_frames() -> anyhow::Result<()> {
+        const MAX_LEN: FrameId = SelectiveAcknowledgement::<MTU>::MAX_RANGE_LEN;
+
+        let cfg = AcknowledgementStateConfig {
+            mode: AcknowledgementMode::Full,
+            expected_packet_latency: Duration::from_millis(5),
+            max_outgoing_frame_retries: 1,
+            ..Default::default()
+        };
+
+        // A single range covering the entire frame ID space must not be accepted
+        let mut data = vec![0u8; SelectiveAcknowledgement::<MTU>::SIZE];
+        data[4..8].copy_from_slice(&1_u32.to_be_bytes());
+        data[8..12].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(SelectiveAcknowledgement::<MTU>::try_from(data.as_slice()).is_err());
+
+        // Fill the message with the largest possible ranges, only frame 2 was actually sent
+        let mut data = vec![0u8; SelectiveAcknowledgement::<MTU>::SIZE];
+        data[4..8].copy_from_slice(&2_u32.to_be_bytes());
+        data[8..12].copy_from_slice(&2_u32.to_be_bytes());
+        for (i, entry) in data[12..]
+            .chunks_exact_mut(SelectiveAcknowledgement::<MTU>::ENTRY_SIZE)
+            .enumerate()
+        {
+            let last = u32::MAX - i as FrameId * MAX_LEN;
+            entry[0..4].copy_from_slice(&(last - MAX_LEN + 1).to_be_bytes());
+            entry[4..8].copy_from_slice(&last.to_be_bytes());
+        }
+        let sack = SelectiveAcknowledgement::<MTU>::try_from(data.as_slice())?;
+        assert_eq!(SelectiveAcknowledgement::<MTU>::MAX_RANGES, sack.ranges().count());
+
+        let inspector = FrameInspector(FrameDashMap::with_capacity(10));
+        let (ctl_tx, ctl_rx) = bounded_sink_channel::<SessionMessage<MTU>>(1024);
+
+        let mut state = AcknowledgementState::<MTU>::new("test", cfg);
+        state.run(SocketComponents {
+            inspector: inspector.into(),
+            ctl_tx,
+            version: 2,
+        })?;
+
+        for frame_id in 1..=3 {
+            for segment in segment(hopr_types::crypto_random::random_bytes::<FRAME_SIZE>(), MTU, frame_id)? {
+                state.segment_sent(&segment)?;
+            }
+        }
+
+        state.incoming_selective_acknowledgement(sack)?;
+
+        tokio::time::sleep(cfg.expected_packet_latency * 10).await;
+        state.stop()?;
+
+        let ctl_msgs = tokio::time::timeout(Duration::from_millis(100), ctl_rx.collect::<Vec<_>>())
+            .await
+            .context("timeout receiving Control messages")?;
+
+        let retransmitted_frames = ctl_msgs
+            .into_iter()
+            .map(|m| m.try_as_segment().ok_or(anyhow::anyhow!("must be segment")))
+            .map(|s| s.map(|s| s.frame_id))
+            .collect::<Result<std::collections::BTreeSet<_>, _>>()?;
+
+        assert_eq!(std::collections::BTreeSet::from([1, 3]), retransmitted_frames);
+
+        Ok(())
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn ack_state_v2_receiver_must_request_segment_ranges_beyond_bitmap() -> anyhow::Result<()> {
+        let cfg = AcknowledgementStateConfig {
+            mode: AcknowledgementMode::Partial,
+            expected_packet_latency: Duration::from_millis(2),
+            max_incoming_frame_retries: 1,
+            ..Default::default()
+        };
+
+        let mut inspector = FrameInspector(FrameDashMap::with_capacity(10));
+        let (ctl_tx, ctl_rx) = bounded_sink_channel::<SessionMessage<MTU>>(1024);
+
+        // 12 segments per frame, which cannot be covered by the v1 bitmap
+        let segments = segment(hopr_types::crypto_random::random_bytes::<1200>(), 100, 1)?;
+        let mut builder = FrameBuilder::from(segments[0].clone());
+        for segment in segments.iter().skip(1).filter(|s| ![3, 9, 10].contains(&s.seq_idx)) {
+            builder.add_segment(segment.clone())?;
+        }
+        inspector
+            .0
+            .entry(1)
+            .try_as_vacant()
+            .ok_or(anyhow::anyhow!("frame 1 must be vacant"))?
+            .insert(builder);
+
+        let mut state = AcknowledgementState::<MTU>::new("test", cfg);
+        state.run(SocketComponents {
+            inspector: inspector.clone().into(),
+            ctl_tx,
+            version: 2,
+        })?;
+
+        state.incoming_segment(&segments[0].id(), (segments.len() as SeqNum).try_into()?)?;
+
+        tokio::time::sleep(cfg.expected_packet_latency * 4).await;
+        state.stop()?;
+
+        let ctl_msgs = tokio::time::timeout(Duration::from_millis(100), ctl_rx.collect::<Vec<_>>())
+            .await
+            .context("timeout receiving Control messages")?;
+
+        assert_eq!(1, ctl_msgs.len());
+        let request = ctl_msgs[0]
+            .clone()
+            .try_as_range_request()
+            .ok_or(anyhow::anyhow!("expected range request"))?;
+
+        assert_eq!(2, request.num_ranges());
+        assert_eq!(
+            vec![SegmentId(1, 3), SegmentId(1, 9), SegmentId(1, 10)],
+            request.into_iter().collect::<Vec<_>>()
+        );
+
+        Ok(())
+    }
 }
@@ -26,7 +26,11 @@ use {
 
 use crate::{
     errors::SessionError,
-    processing::{ReassemblerExt, SegmenterExt, SequencerExt, types::FrameInspector},
+    processing::{
+        ReassemblerExt, SegmenterExt, SequencerExt,
+        fec::{self, FecConfig, RedundancyController},
+        types::FrameInspector,
+    },
     protocol::{OrderedFrame, SegmentRequest, SeqIndicator, SessionCodec, SessionMessage},
 };
 
@@ -35,11 +39,11 @@ use crate::{
 pub struct SessionSocketConfig {
     /// The maximum size of a frame on the read/write interface of the [`SessionSocket`].
     ///
-    /// The size is always greater or equal to the MTU `C` of the underlying transport, and
-    /// less or equal to:
-    /// - (`C` - `SessionMessage::SEGMENT_OVERHEAD`) * (`SeqIndicator::MAX` + 1) for stateless sockets, or
-    /// - (`C` - `SessionMessage::SEGMENT_OVERHEAD`) * min(`SeqIndicator::MAX` + 1,
-    ///   `SegmentRequest::MAX_MISSING_SEGMENTS_PER_FRAME`) for stateful sockets
+    /// The size is always greater or equal to the segment payload size
+    /// `SessionMessage::max_segment_payload` of the configured `protocol_version`, and
+    /// less or equal to that size multiplied by:
+    /// - `SeqIndicator::max_len` for stateless sockets and stateful sockets using protocol version 2, or
+    /// - `SegmentRequest::MAX_MISSING_SEGMENTS_PER_FRAME` for stateful sockets using protocol version 1
     ///
     /// Default is 1500 bytes.
     #[default(1500)]
@@ -73,6 +77,40 @@ pub struct SessionSocketConfig {
     /// Default is 2048.
     #[default(2048)]
     pub control_channel_capacity: usize,
+    /// Version of the Session protocol used for the outgoing messages.
+    ///
+    /// Incoming messages of all [supported](SessionMessage::is_supported_version) versions are always accepted.
+    /// The counterparty must support this version.
+    ///
+    /// Default is 1.
+    #[default(1)]
+    pub protocol_version: u8,
+    /// Forward error correction of the outgoing frames.
+    ///
+    /// When enabled, the segment payload size is reduced by the size of the repair segment header.
+    /// The counterparty must be able to process the repair segments.
+    /// Incoming repair segments are always used to reconstruct frames.
+    ///
+    /// Default is `None` (disabled).
+    #[default(None)]
+    pub fec: Option<FecConfig>,
+}
+
+impl SessionSocketConfig {
+    fn segment_size<const C: usize>(&self, version: u8) -> usize {
+        let size = SessionMessage::<C>::max_segment_payload(version);
+        if self.fec.is_some() {
+            size - fec::REPAIR_HEADER_SIZE
+        } else {
+            size
+        }
+    }
+
+    fn checked_version<const C: usize>(&self) -> Result<u8, SessionError> {
+        SessionMessage::<C>::is_supported_version(self.protocol_version)
+            .then_some(self.protocol_version)
+            .ok_or(SessionError::WrongVersion)
+    }
 }
 
 enum WriteState {
@@ -128,15 +166,17 @@ impl<const C: usize> SessionSocket<C, Stateless<C>> {
         T: futures::io::AsyncRead + futures::io::AsyncWrite + Send + Unpin + 'static,
         I: std::fmt::Display + Clone,
     {
-        // The minimum frame size is SESSION_MTU (= C - SEGMENT_OVERHEAD) to allow 1-segment frames.
+        let version = cfg.checked_version::<C>()?;
+
+        // The minimum frame size is SESSION_MTU (= C - segment overhead) to allow 1-segment frames.
         // The maximum is bounded by the SeqIndicator capacity.
-        let frame_size = cfg.frame_size.clamp(
-            C - SessionMessage::<C>::SEGMENT_OVERHEAD,
-            (C - SessionMessage::<C>::SEGMENT_OVERHEAD) * (SeqIndicator::MAX + 1) as usize,
-        );
+        let segment_size = cfg.segment_size::<C>(version);
+        let frame_size = cfg
+            .frame_size
+            .clamp(segment_size, segment_size * SeqIndicator::max_len(version) as usize);
 
         // Segment data incoming/outgoing using underlying transport
-        let mut framed = asynchronous_codec::Framed::new(transport, SessionCodec::<C>);
+        let mut framed = asynchronous_codec::Framed::new(transport, SessionCodec::<C>::new(version));
 
         // Check if we allow sending multiple segments to downstream in a single write
         // The HWM cannot be 0 bytes
@@ -150,14 +190,20 @@ impl<const C: usize> SessionSocket<C, Stateless<C>> {
         let (s0, s1, s2, s3) = { (stats.clone(), stats.clone(), stats.clone(), stats.clone()) };
 
         // Pipeline IN: Data incoming from Upstream
-        let upstream_frames_in = packets_out
+        let segmenter = packets_out
             .with(move |segment| {
                 #[cfg(feature = "telemetry")]
                 s0.outgoing_message(SessionMessageDiscriminants::Segment);
 
                 future::ok::<_, SessionError>(SessionMessage::<C>::Segment(segment))
             })
-            .segmenter_with_terminating_segment::<C>(frame_size);
+            .segmenter_with_terminating_segment::<C>(frame_size, version);
+
+        // The redundancy of a stateless socket is fixed, because it cannot observe the loss
+        let upstream_frames_in = match cfg.fec.as_ref().map(RedundancyController::new) {
+            Some(controller) => segmenter.with_fec(controller),
+            None => segmenter,
+        };
 
         let last_emitted_frame = Arc::new(AtomicU32::new(0));
         let last_emitted_frame_clone = last_emitted_frame.clone();
@@ -202,6 +248,7 @@ impl<const C: usize> SessionSocket<C, Stateless<C>> {
             })
             // Reassemble the segments into frames
             .reassembler(cfg.frame_timeout, cfg.capacity)
+            .with_fec(cfg.fec.is_some())
             // Discard frames that we could not reassemble
             .filter_map(move |maybe_frame| {
                 let _span = stage2_span.enter();
@@ -276,16 +323,20 @@ impl<const C: usize, S: SocketState<C> + Clone + 'static> SessionSocket<C, S> {
     where
         T: futures::io::AsyncRead + futures::io::AsyncWrite + Send + Unpin + 'static,
     {
-        // The minimum frame size is SESSION_MTU (= C - SEGMENT_OVERHEAD) to allow 1-segment frames.
-        // The maximum is reduced due to the size of the missing segment bitmap in SegmentRequests.
-        let frame_size = cfg.frame_size.clamp(
-            C - SessionMessage::<C>::SEGMENT_OVERHEAD,
-            (C - SessionMessage::<C>::SEGMENT_OVERHEAD)
-                * SegmentRequest::<C>::MAX_MISSING_SEGMENTS_PER_FRAME.min((SeqIndicator::MAX + 1) as usize),
-        );
+        let version = cfg.checked_version::<C>()?;
+
+        // The minimum frame size is SESSION_MTU (= C - segment overhead) to allow 1-segment frames.
+        // In version 1, the maximum is reduced due to the size of the missing segment bitmap in SegmentRequests.
+        let segment_size = cfg.segment_size::<C>(version);
+        let max_segments = if version > 1 {
+            SeqIndicator::max_len(version) as usize
+        } else {
+            SegmentRequest::<C>::MAX_MISSING_SEGMENTS_PER_FRAME.min(SeqIndicator::max_len(version) as usize)
+        };
+        let frame_size = cfg.frame_size.clamp(segment_size, segment_size * max_segments);
 
         // Segment data incoming/outgoing using underlying transport
-        let mut framed = asynchronous_codec::Framed::new(transport, SessionCodec::<C>);
+        let mut framed = asynchronous_codec::Framed::new(transport, SessionCodec::<C>::new(version));
 
         // Check if we allow sending multiple segments to downstream in a single write
         // The HWM cannot be 0 bytes
@@ -310,8 +361,11 @@ impl<const C: usize, S: SocketState<C> + Clone + 'static> SessionSocket<C, S> {
         state.run(SocketComponents {
             inspector: Some(inspector.clone()),
             ctl_tx,
+            version,
         })?;
 
+        let fec = cfg.fec.as_ref().map(RedundancyController::new);
+
         // Pipeline IN: Data incoming from Upstream
         let (segments_tx, segments_rx) = futures::channel::mpsc::channel(cfg.capacity);
         let mut st_1 = state.clone();
@@ -327,7 +381,19 @@ impl<const C: usize, S: SocketState<C> + Clone + 'static> SessionSocket<C, S> {
                 }
                 future::ok::<_, futures::channel::mpsc::SendError>(SessionMessage::<C>::Segment(segment))
             })
-            .segmenter_with_terminating_segment::<C>(frame_size);
+            .segmenter_with_terminating_segment::<C>(frame_size, version);
+        let upstream_frames_in = match fec.clone() {
+            Some(controller) => upstream_frames_in.with_fec(controller),
+            None => upstream_frames_in,
+        };
+
+        // Segments from the Control stream are retransmissions, which indicate loss
+        let fec_clone = fec.clone();
+        let ctl_rx = ctl_rx.inspect(move |msg| {
+            if let (Some(controller), SessionMessage::Segment(_)) = (&fec_clone, msg) {
+                controller.segments_lost(1);
+            }
+        });
 
         // We have to merge the streams here and spawn a special task for it
         // Since the control messages from the State can come independent of Upstream writes.
@@ -374,6 +440,17 @@ impl<const C: usize, S: SocketState<C> + Clone + 'static> SessionSocket<C, S> {
                 .entered();
                 futures::future::ready(match packet {
                     Ok(packet) => {
+                        // Acknowledged frames indicate that the redundancy can be lowered
+                        match (&fec, &packet) {
+                            (Some(controller), SessionMessage::Acknowledge(acks)) => {
+                                controller.frames_delivered(acks.len())
+                            }
+                            (Some(controller), SessionMessage::SelectiveAcknowledge(acks)) => {
+                                controller.frames_delivered(acks.len())
+                            }
+                            _ => {}
+                        }
+
                         if let Err(error) = st_1.incoming_message(&packet) {
                             tracing::debug!(%error, "incoming message state update failed");
                         }
@@ -401,6 +478,7 @@ impl<const C: usize, S: SocketState<C> + Clone + 'static> SessionSocket<C, S> {
             })
             // Reassemble segments into frames
             .reassembler_with_inspector(cfg.frame_timeout, cfg.capacity, inspector)
+            .with_fec(cfg.fec.is_some())
             // Notify State once a frame has been reassembled, discard frames that we could not reassemble
             .filter_map(move |maybe_frame| {
                 let _span = tracing::debug_span!(
@@ -807,6 +885,65 @@ mod tests {
         Ok(())
     }
 
+    #[parameterized::parameterized(drop_every = { 20, 10, 4 })]
+    #[parameterized_macro(test_log::test(tokio::test))]
+    async fn stateless_socket_with_fec_should_recover_frames_without_retransmission(
+        drop_every: usize,
+    ) -> anyhow::Result<()> {
+        // Dropping every n-th packet loses fewer segments of each frame than the frame has repair
+        // segments, so that every frame is recoverable regardless of the timing.
+        let network_cfg = FaultyNetworkConfig {
+            ids_to_drop: (0..1000).filter(|id| id % drop_every == drop_every - 1).collect(),
+            ..Default::default()
+        };
+
+        let (alice, bob) = setup_alice_bob::<MTU>(network_cfg, None, None);
+
+        // Stateless sockets never retransmit, so the frames can be recovered only via FEC
+        let sock_cfg = SessionSocketConfig {
+            frame_size: 16 * FRAME_SIZE,
+            fec: Some(FecConfig {
+                redundancy: 75,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let mut alice_socket = SessionSocket::<MTU, _>::new_stateless(
+            "alice",
+            alice,
+            sock_cfg,
+            #[cfg(feature = "telemetry")]
+            NoopTracker,
+        )?;
+        let mut bob_socket = SessionSocket::<MTU, _>::new_stateless(
+            "bob",
+            bob,
+            sock_cfg,
+            #[cfg(feature = "telemetry")]
+            NoopTracker,
+        )?;
+
+        let data = hopr_types::crypto_random::random_bytes::<{ 4 * DATA_SIZE }>();
+        alice_socket
+            .write_all(&data)
+            .timeout(futures_time::time::Duration::from_secs(2))
+            .await??;
+        alice_socket.flush().await?;
+
+        let mut bob_recv_data = [0u8; 4 * DATA_SIZE];
+        bob_socket
+            .read_exact(&mut bob_recv_data)
+            .timeout(futures_time::time::Duration::from_secs(2))
+            .await??;
+        assert_eq!(data, bob_recv_data);
+
+        alice_socket.close().await?;
+        bob_socket.close().await?;
+
+        Ok(())
+    }
+
     #[test_log::test(tokio::test)]
     async fn stateless_socket_unidirectional_should_work_with_mixing() -> anyhow::Result<()> {
         let network_cfg = FaultyNetworkConfig {
@@ -3,7 +3,10 @@ use hopr_utils::network_types::crossfire_sink::CrossfireSink;
 use crate::{
     errors::SessionError,
     processing::types::FrameInspector,
-    protocol::{FrameAcknowledgements, FrameId, Segment, SegmentId, SegmentRequest, SeqIndicator, SessionMessage},
+    protocol::{
+        FrameAcknowledgements, FrameId, Segment, SegmentId, SegmentRangeRequest, SegmentRequest,
+        SelectiveAcknowledgement, SeqIndicator, SessionMessage,
+    },
 };
 
 /// Components the `SessionSocket` exposes to a [`SocketState`].
@@ -20,6 +23,10 @@ pub struct SocketComponents<const C: usize> {
     /// It is a regular `SessionMessage` injected into the downstream.
     /// Strict-capacity bounded channel — capacity never inflates regardless of clone count.
     pub ctl_tx: CrossfireSink<SessionMessage<C>>,
+    /// Version of the Session protocol the socket uses for outgoing messages.
+    ///
+    /// Control messages sent via `ctl_tx` must be encodable in this version.
+    pub version: u8,
 }
 
 /// Abstraction of the `SessionSocket` state.
@@ -44,6 +51,12 @@ pub trait SocketState<const C: usize>: Send {
     /// Called when an [acknowledgement of frames](FrameAcknowledgements) is received from Downstream.
     fn incoming_acknowledged_frames(&mut self, ack: FrameAcknowledgements<C>) -> Result<(), SessionError>;
 
+    /// Called when [segment range retransmission request](SegmentRangeRequest) is received from Downstream.
+    fn incoming_range_retransmission_request(&mut self, request: SegmentRangeRequest<C>) -> Result<(), SessionError>;
+
+    /// Called when a [selective acknowledgement of frames](SelectiveAcknowledgement) is received from Downstream.
+    fn incoming_selective_acknowledgement(&mut self, ack: SelectiveAcknowledgement<C>) -> Result<(), SessionError>;
+
     /// Called when a complete Frame has been finalized from segments received from Downstream.
     fn frame_complete(&mut self, id: FrameId) -> Result<(), SessionError>;
 
@@ -62,6 +75,8 @@ pub trait SocketState<const C: usize>: Send {
             SessionMessage::Segment(s) => self.incoming_segment(&s.id(), s.seq_flags),
             SessionMessage::Request(r) => self.incoming_retransmission_request(r.clone()),
             SessionMessage::Acknowledge(a) => self.incoming_acknowledged_frames(a.clone()),
+            SessionMessage::RangeRequest(r) => self.incoming_range_retransmission_request(r.clone()),
+            SessionMessage::SelectiveAcknowledge(a) => self.incoming_selective_acknowledgement(a.clone()),
         }
     }
 }
@@ -103,6 +118,14 @@ impl<const C: usize> SocketState<C> for Stateless<C> {
         Ok(())
     }
 
+    fn incoming_range_retransmission_request(&mut self, _: SegmentRangeRequest<C>) -> Result<(), SessionError> {
+        Ok(())
+    }
+
+    fn incoming_selective_acknowledgement(&mut self, _: SelectiveAcknowledgement<C>) -> Result<(), SessionError> {
+        Ok(())
+    }
+
     fn frame_complete(&mut self, _: FrameId) -> Result<(), SessionError> {
         Ok(())
     }
@@ -149,6 +172,8 @@ mod tests {
             fn incoming_segment(&mut self, id: &SegmentId, ind: SeqIndicator) -> Result<(), SessionError>;
             fn incoming_retransmission_request(&mut self, request: SegmentRequest<MTU>) -> Result<(), SessionError>;
             fn incoming_acknowledged_frames(&mut self, ack: FrameAcknowledgements<MTU>) -> Result<(), SessionError>;
+            fn incoming_range_retransmission_request(&mut self, request: SegmentRangeRequest<MTU>) -> Result<(), SessionError>;
+            fn incoming_selective_acknowledgement(&mut self, ack: SelectiveAcknowledgement<MTU>) -> Result<(), SessionError>;
             fn frame_complete(&mut self, id: FrameId) -> Result<(), SessionError>;
             fn frame_emitted(&mut self, id: FrameId) -> Result<(), SessionError>;
             fn frame_discarded(&mut self, id: FrameId) -> Result<(), SessionError>;
@@ -196,6 +221,22 @@ mod tests {
             self.0.lock().unwrap().incoming_acknowledged_frames(ack)
         }
 
+        fn incoming_range_retransmission_request(
+            &mut self,
+            request: SegmentRangeRequest<MTU>,
+        ) -> Result<(), SessionError> {
+            tracing::debug!(id = self.1, "incoming_range_retransmission_request called");
+            self.0.lock().unwrap().incoming_range_retransmission_request(request)
+        }
+
+        fn incoming_selective_acknowledgement(
+            &mut self,
+            ack: SelectiveAcknowledgement<MTU>,
+        ) -> Result<(), SessionError> {
+            tracing::debug!(id = self.1, "incoming_selective_acknowledgement called");
+            self.0.lock().unwrap().incoming_selective_acknowledgement(ack)
+        }
+
         fn frame_complete(&mut self, id: FrameId) -> Result<(), SessionError> {
             tracing::debug!(id = self.1, "frame_complete called");
             self.0.lock().unwrap().frame_complete(id)
@@ -137,7 +137,7 @@ pub fn segment_into<T: AsRef<[u8]>, E: Extend<Segment>>(
     let data = data.as_ref();
 
     let num_chunks = data.len().div_ceil(max_segment_size);
-    if num_chunks > SeqNum::MAX as usize {
+    if num_chunks > SeqIndicator::MAX as usize {
         return Err(SessionError::DataTooLong);
     }
 
@@ -147,7 +147,7 @@ pub fn segment_into<T: AsRef<[u8]>, E: Extend<Segment>>(
     segments.extend(chunks.enumerate().map(|(idx, data)| Segment {
         frame_id,
         seq_flags: seq_len,
-        seq_idx: idx as u8,
+        seq_idx: idx as SeqNum,
         data: data.into(),
     }));
 
@@ -13,6 +13,16 @@ pub enum StartProtocolError {
     UnknownMessage,
     #[error("message parse error: {0}")]
     ParseError(String),
+    #[error("counterparty did not send a key share")]
+    MissingKeyShare,
+    #[error("key share is not a valid public key")]
+    InvalidKeyShare,
+    #[error("counterparty did not confirm the key exchange")]
+    MissingKeyConfirmation,
+    #[error("key confirmation does not match the handshake transcript")]
+    InvalidKeyConfirmation,
+    #[error("message cannot be authenticated")]
+    InvalidCiphertext,
     #[error("cbor error: {0}")]
     CborError(#[from] serde_cbor_2::Error),
     #[error(transparent)]
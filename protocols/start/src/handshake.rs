@@ -0,0 +1,654 @@
+//! Optional authenticated key exchange of the Start protocol.
+//!
+//! When the Session initiator (Entry) knows the packet key of the Session recipient (Exit),
+//! both parties can run a key exchange inside the Start handshake. The exchange follows the `NK`
+//! pattern of the [Noise protocol framework](https://noiseprotocol.org/noise.html), where the
+//! recipient's static key is known in advance and the initiator stays anonymous:
+//!
+//! ```text
+//!   <- s
+//!   ...
+//!   -> e, es    (StartInitiation::key_share)
+//!   <- e, ee    (StartEstablished::key_confirmation)
+//! ```
+//!
+//! The transcript is bound to the challenge, the required capabilities and the offered Session protocol version
+//! of the [`StartInitiation`], and to the Session ID, the agreed capabilities and the agreed Session protocol
+//! version in [`StartEstablished`]. The recipient proves knowledge of the derived key with
+//! a confirmation tag, so the initiator learns that the Session was established by the holder of the
+//! recipient's packet key, and that nobody changed the negotiated parameters on the way in either direction.
+//! The resulting [`SessionKeys`] are forward-secret, because they depend on ephemeral keys only known
+//! to both parties during the handshake.
+//!
+//! The keys are meant to be used with the [`SessionCipher`] to add an AEAD layer over Session segments.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use chacha20poly1305::{
+    ChaCha20Poly1305, KeyInit, Nonce,
+    aead::{Aead, AeadInPlace},
+};
+use hopr_types::{
+    crypto::{
+        keypairs::{Keypair, OffchainKeypair},
+        primitives::{Blake3, Curve25519MontgomeryPoint, Curve25519Scalar, SecretKey, blake3_hash},
+        types::OffchainPublicKey,
+    },
+    crypto_random::random_bytes,
+};
+
+use crate::{
+    StartEstablished, StartInitiation,
+    errors::{Result, StartProtocolError},
+};
+
+/// Name of the handshake protocol, used to initialize the transcript.
+const PROTOCOL_NAME: &[u8] = b"HOPR_Start_NK_25519_ChaChaPoly_Blake3";
+
+/// Size of a [`KeyShare`].
+pub const KEY_SHARE_SIZE: usize = 32;
+
+/// Size of the tag in the [`KeyConfirmation`].
+pub const CONFIRMATION_TAG_SIZE: usize = 16;
+
+/// Ephemeral X25519 public key of a handshake party.
+pub type KeyShare = [u8; KEY_SHARE_SIZE];
+
+/// Recipient's part of the key exchange, sent in [`StartEstablished`](crate::StartEstablished).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyConfirmation {
+    /// Ephemeral public key of the recipient.
+    pub key_share: KeyShare,
+    /// Proves that the recipient derived the same keys from the same transcript.
+    pub tag: [u8; CONFIRMATION_TAG_SIZE],
+}
+
+impl KeyConfirmation {
+    /// Size of the encoded key confirmation.
+    pub const SIZE: usize = KEY_SHARE_SIZE + CONFIRMATION_TAG_SIZE;
+
+    pub(crate) fn to_bytes(self) -> [u8; Self::SIZE] {
+        let mut out = [0u8; Self::SIZE];
+        out[..KEY_SHARE_SIZE].copy_from_slice(&self.key_share);
+        out[KEY_SHARE_SIZE..].copy_from_slice(&self.tag);
+        out
+    }
+
+    pub(crate) fn from_bytes(data: &[u8]) -> Result<Self> {
+        if data.len() != Self::SIZE {
+            return Err(StartProtocolError::InvalidLength);
+        }
+        let mut ret = Self {
+            key_share: [0u8; KEY_SHARE_SIZE],
+            tag: [0u8; CONFIRMATION_TAG_SIZE],
+        };
+        ret.key_share.copy_from_slice(&data[..KEY_SHARE_SIZE]);
+        ret.tag.copy_from_slice(&data[KEY_SHARE_SIZE..]);
+        Ok(ret)
+    }
+}
+
+/// Directional keys derived by the key exchange.
+///
+/// Each party encrypts with its `sending` key, which is the `receiving` key of the counterparty.
+pub struct SessionKeys {
+    /// Key for the data sent by this party.
+    pub sending: SecretKey,
+    /// Key for the data received by this party.
+    pub receiving: SecretKey,
+}
+
+impl std::fmt::Debug for SessionKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionKeys").finish_non_exhaustive()
+    }
+}
+
+/// Chaining key and transcript hash of the handshake.
+struct SymmetricState {
+    chaining_key: [u8; 32],
+    hash: [u8; 32],
+    key: Option<[u8; 32]>,
+}
+
+impl SymmetricState {
+    fn new<T, C: Copy + Into<u16>>(responder_key: &Curve25519MontgomeryPoint, init: &StartInitiation<T, C>) -> Self {
+        let hash = *blake3_hash(PROTOCOL_NAME).as_bytes();
+        let mut ret = Self {
+            chaining_key: hash,
+            hash,
+            key: None,
+        };
+        // Prologue binds the handshake to the negotiation offered in the initiation message
+        ret.mix_hash(&init.challenge.to_be_bytes());
+        // The optional capabilities are bound only via the required ones, because the recipient
+        // ignores the optional capabilities it does not know
+        let optional: u16 = init.optional_capabilities.map(Into::into).unwrap_or_default();
+        ret.mix_hash(&(init.capabilities.into() & !optional).to_be_bytes());
+        ret.mix_optional(init.session_protocol_version.map(|v| [v]));
+        // Pre-message: the responder's static key is known to the initiator
+        ret.mix_hash(responder_key.as_bytes());
+        ret
+    }
+
+    fn mix_hash(&mut self, data: &[u8]) {
+        let mut hasher = Blake3::new();
+        hasher.update(&self.hash);
+        hasher.update(data);
+        self.hash = *hasher.finalize().as_bytes();
+    }
+
+    /// Mixes an optional value, so that an absent value cannot be confused with any present one.
+    fn mix_optional<D: AsRef<[u8]>>(&mut self, data: Option<D>) {
+        match data {
+            Some(data) => {
+                self.mix_hash(&[1]);
+                self.mix_hash(data.as_ref());
+            }
+            None => self.mix_hash(&[0]),
+        }
+    }
+
+    /// Binds the handshake to the Session agreed in the establishment message.
+    fn mix_established<I: serde::Serialize, C: Copy + Into<u16>>(
+        &mut self,
+        est: &StartEstablished<I, C>,
+    ) -> Result<()> {
+        self.mix_hash(&session_id_bytes(&est.session_id)?);
+        self.mix_optional(est.capabilities.map(|c| c.into().to_be_bytes()));
+        self.mix_optional(est.session_protocol_version.map(|v| [v]));
+        Ok(())
+    }
+
+    fn mix_key(&mut self, shared_secret: &Curve25519MontgomeryPoint) -> Result<()> {
+        // Low-order points result in an all-zero shared secret
+        if shared_secret.as_bytes().iter().all(|b| *b == 0) {
+            return Err(StartProtocolError::InvalidKeyShare);
+        }
+
+        let mut output = [0u8; 64];
+        Blake3::new_keyed(&self.chaining_key)
+            .update(shared_secret.as_bytes())
+            .finalize_xof()
+            .fill(&mut output);
+        self.chaining_key.copy_from_slice(&output[..32]);
+        let mut key = [0u8; 32];
+        key.copy_from_slice(&output[32..]);
+        self.key = Some(key);
+        Ok(())
+    }
+
+    fn confirmation_tag(&self) -> [u8; CONFIRMATION_TAG_SIZE] {
+        let mut tag = [0u8; CONFIRMATION_TAG_SIZE];
+        let key = self.key.unwrap_or_default();
+        tag.copy_from_slice(&Blake3::new_keyed(&key).update(&self.hash).finalize().as_bytes()[..CONFIRMATION_TAG_SIZE]);
+        tag
+    }
+
+    /// Returns the initiator-to-responder and responder-to-initiator keys.
+    fn split(&self) -> (SecretKey, SecretKey) {
+        let mut output = [0u8; 64];
+        Blake3::new_keyed(&self.chaining_key)
+            .update(&self.hash)
+            .finalize_xof()
+            .fill(&mut output);
+        let keys = (
+            SecretKey::try_from(&output[..32]).expect("slice has the size of a secret key"),
+            SecretKey::try_from(&output[32..]).expect("slice has the size of a secret key"),
+        );
+        output.fill(0);
+        keys
+    }
+}
+
+fn session_id_bytes<I: serde::Serialize>(session_id: &I) -> Result<Vec<u8>> {
+    Ok(serde_cbor_2::to_vec(session_id)?)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Initiator's state of the key exchange, kept until [`StartEstablished`](crate::StartEstablished) arrives.
+pub struct HandshakeInitiator {
+    ephemeral: [u8; 32],
+    state: SymmetricState,
+}
+
+impl HandshakeInitiator {
+    /// Starts the key exchange with the recipient identified by its packet key.
+    ///
+    /// The `init` is the [`StartInitiation`] to be sent, which must carry the returned [`KeyShare`].
+    /// Its `key_share` is not looked at.
+    pub fn new<T, C: Copy + Into<u16>>(
+        responder_key: &OffchainPublicKey,
+        init: &StartInitiation<T, C>,
+    ) -> Result<(Self, KeyShare)> {
+        let responder_key = Curve25519MontgomeryPoint::from(responder_key);
+        let mut state = SymmetricState::new(&responder_key, init);
+
+        let ephemeral = random_bytes::<32>();
+        let key_share = Curve25519MontgomeryPoint::mul_base_clamped(ephemeral).to_bytes();
+        state.mix_hash(&key_share);
+        state.mix_key(&responder_key.mul_clamped(ephemeral))?;
+
+        Ok((Self { ephemeral, state }, key_share))
+    }
+
+    /// Completes the key exchange using the recipient's [`KeyConfirmation`] in the received [`StartEstablished`].
+    ///
+    /// Fails with [`StartProtocolError::MissingKeyConfirmation`] if the recipient did not take part
+    /// in the key exchange, so that the Session cannot be silently downgraded to an unencrypted one.
+    pub fn finish<I: serde::Serialize, C: Copy + Into<u16>>(
+        mut self,
+        est: &StartEstablished<I, C>,
+    ) -> Result<SessionKeys> {
+        let confirmation = est
+            .key_confirmation
+            .as_ref()
+            .ok_or(StartProtocolError::MissingKeyConfirmation)?;
+
+        let responder_ephemeral = Curve25519MontgomeryPoint(confirmation.key_share);
+        self.state.mix_hash(&confirmation.key_share);
+        self.state.mix_key(&responder_ephemeral.mul_clamped(self.ephemeral))?;
+        self.state.mix_established(est)?;
+
+        if !constant_time_eq(&self.state.confirmation_tag(), &confirmation.tag) {
+            return Err(StartProtocolError::InvalidKeyConfirmation);
+        }
+
+        let (sending, receiving) = self.state.split();
+        Ok(SessionKeys { sending, receiving })
+    }
+}
+
+impl Drop for HandshakeInitiator {
+    fn drop(&mut self) {
+        self.ephemeral.fill(0);
+    }
+}
+
+impl Drop for SymmetricState {
+    fn drop(&mut self) {
+        self.chaining_key.fill(0);
+        if let Some(key) = self.key.as_mut() {
+            key.fill(0);
+        }
+    }
+}
+
+/// Recipient's side of the key exchange.
+pub struct HandshakeResponder {
+    secret: Curve25519Scalar,
+    public: Curve25519MontgomeryPoint,
+}
+
+impl HandshakeResponder {
+    /// Creates the responder from the recipient's packet key.
+    pub fn new(keypair: &OffchainKeypair) -> Self {
+        Self {
+            secret: Curve25519Scalar::from(keypair),
+            public: Curve25519MontgomeryPoint::from(keypair.public()),
+        }
+    }
+
+    /// Answers the initiator's [`KeyShare`] in the received [`StartInitiation`].
+    ///
+    /// The `est` is the [`StartEstablished`] to be sent back, which must carry the returned [`KeyConfirmation`].
+    /// Its `key_confirmation` is not looked at.
+    pub fn respond<T, I: serde::Serialize, C: Copy + Into<u16>>(
+        &self,
+        init: &StartInitiation<T, C>,
+        est: &StartEstablished<I, C>,
+    ) -> Result<(SessionKeys, KeyConfirmation)> {
+        let key_share = init.key_share.as_ref().ok_or(StartProtocolError::MissingKeyShare)?;
+        let mut state = SymmetricState::new(&self.public, init);
+
+        let initiator_ephemeral = Curve25519MontgomeryPoint(*key_share);
+        state.mix_hash(key_share);
+        state.mix_key(&(initiator_ephemeral * self.secret))?;
+
+        let mut ephemeral = random_bytes::<32>();
+        let response_share = Curve25519MontgomeryPoint::mul_base_clamped(ephemeral).to_bytes();
+        state.mix_hash(&response_share);
+        let shared_secret = initiator_ephemeral.mul_clamped(ephemeral);
+        ephemeral.fill(0);
+        state.mix_key(&shared_secret)?;
+        state.mix_established(est)?;
+
+        let confirmation = KeyConfirmation {
+            key_share: response_share,
+            tag: state.confirmation_tag(),
+        };
+
+        let (receiving, sending) = state.split();
+        Ok((SessionKeys { sending, receiving }, confirmation))
+    }
+}
+
+/// Size of the explicit nonce prepended to each sealed message.
+const NONCE_SIZE: usize = size_of::<u64>();
+
+/// Size of the Poly1305 authentication tag appended to each sealed message.
+const TAG_SIZE: usize = 16;
+
+/// ChaCha20-Poly1305 AEAD (RFC 8439) keyed with [`SessionKeys`].
+///
+/// Each sealed message is prefixed by an explicit 64-bit nonce taken from a counter,
+/// so messages can be opened independently of each other and in any order.
+/// Duplicate messages are not rejected; they are expected to be handled by the layer above.
+pub struct SessionCipher {
+    sending: ChaCha20Poly1305,
+    receiving: ChaCha20Poly1305,
+    nonce: AtomicU64,
+}
+
+impl SessionCipher {
+    /// Number of bytes a sealed message is longer than the plaintext.
+    pub const OVERHEAD: usize = NONCE_SIZE + TAG_SIZE;
+
+    /// Creates a cipher from the keys derived by the key exchange.
+    pub fn new(keys: SessionKeys) -> Self {
+        Self {
+            sending: ChaCha20Poly1305::new_from_slice(keys.sending.as_ref()).expect("secret key has valid size"),
+            receiving: ChaCha20Poly1305::new_from_slice(keys.receiving.as_ref()).expect("secret key has valid size"),
+            nonce: AtomicU64::new(0),
+        }
+    }
+
+    /// The 96-bit AEAD nonce is the explicit 64-bit nonce prefixed with zeroes.
+    fn aead_nonce(nonce: &[u8]) -> Nonce {
+        let mut ret = Nonce::default();
+        ret[size_of::<u32>()..].copy_from_slice(nonce);
+        ret
+    }
+
+    /// Encrypts and authenticates the `plaintext` with the sending key.
+    pub fn seal(&self, plaintext: &[u8]) -> Box<[u8]> {
+        let nonce = self.nonce.fetch_add(1, Ordering::Relaxed).to_be_bytes();
+
+        let mut out = Vec::with_capacity(plaintext.len() + Self::OVERHEAD);
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(plaintext);
+        let tag = self
+            .sending
+            .encrypt_in_place_detached(&Self::aead_nonce(&nonce), &[], &mut out[NONCE_SIZE..])
+            .expect("plaintext does not exceed the maximum length of a chacha20 stream");
+        out.extend_from_slice(&tag);
+        out.into_boxed_slice()
+    }
+
+    /// Authenticates and decrypts the `data` previously [sealed](Self::seal) by the counterparty.
+    pub fn open(&self, data: &[u8]) -> Result<Box<[u8]>> {
+        if data.len() < Self::OVERHEAD {
+            return Err(StartProtocolError::InvalidCiphertext);
+        }
+
+        let (nonce, ciphertext) = data.split_at(NONCE_SIZE);
+        self.receiving
+            .decrypt(&Self::aead_nonce(nonce), ciphertext)
+            .map(Vec::into_boxed_slice)
+            .map_err(|_| StartProtocolError::InvalidCiphertext)
+    }
+}
+
+impl std::fmt::Debug for SessionCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionCipher")
+            .field("nonce", &self.nonce.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{StartChallenge, StartProtocol};
+
+    const CHALLENGE: StartChallenge = 0x0123_4567_89ab_cdef;
+    const CAPABILITIES: u16 = 0b1_0000_1011;
+    const OPTIONAL_CAPABILITIES: u16 = 0b0000_0011;
+    const SESSION_ID: &str = "session-id";
+
+    type TestStartProtocol = StartProtocol<String, String, u16>;
+
+    fn initiation() -> StartInitiation<String, u16> {
+        StartInitiation {
+            challenge: CHALLENGE,
+            target: "127.0.0.1:1234".into(),
+            capabilities: CAPABILITIES,
+            optional_capabilities: Some(OPTIONAL_CAPABILITIES),
+            session_protocol_version: Some(2),
+            additional_data: 0,
+            key_share: None,
+        }
+    }
+
+    fn establishment(init: &StartInitiation<String, u16>) -> StartEstablished<String, u16> {
+        StartEstablished {
+            orig_challenge: init.challenge,
+            session_id: SESSION_ID.to_string(),
+            capabilities: Some(init.capabilities & !0b0000_0001),
+            session_protocol_version: init.session_protocol_version,
+            resume_ticket: None,
+            key_confirmation: None,
+        }
+    }
+
+    /// Encodes and decodes the message, as if it was sent over the network.
+    fn transfer(msg: TestStartProtocol) -> anyhow::Result<TestStartProtocol> {
+        let (tag, data) = msg.encode()?;
+        Ok(TestStartProtocol::decode(tag, &data)?)
+    }
+
+    /// Runs the Entry side of the handshake and sends out the initiation message.
+    fn entry_initiates(exit: &OffchainKeypair) -> anyhow::Result<(HandshakeInitiator, TestStartProtocol)> {
+        let mut init = initiation();
+        let (initiator, key_share) = HandshakeInitiator::new(exit.public(), &init)?;
+        init.key_share = Some(key_share);
+        Ok((initiator, transfer(StartProtocol::StartSession(init))?))
+    }
+
+    /// Runs the Exit side of the handshake on the received initiation message.
+    fn exit_responds(
+        exit: &OffchainKeypair,
+        msg: TestStartProtocol,
+    ) -> anyhow::Result<(SessionKeys, TestStartProtocol)> {
+        let StartProtocol::StartSession(init) = msg else {
+            anyhow::bail!("not an initiation");
+        };
+        let mut est = establishment(&init);
+        let (keys, confirmation) = HandshakeResponder::new(exit).respond(&init, &est)?;
+        est.key_confirmation = Some(confirmation);
+        Ok((keys, transfer(StartProtocol::SessionEstablished(est))?))
+    }
+
+    fn initiation_of(msg: TestStartProtocol) -> anyhow::Result<StartInitiation<String, u16>> {
+        match msg {
+            StartProtocol::StartSession(init) => Ok(init),
+            _ => anyhow::bail!("not an initiation"),
+        }
+    }
+
+    fn established(msg: TestStartProtocol) -> anyhow::Result<StartEstablished<String, u16>> {
+        match msg {
+            StartProtocol::SessionEstablished(est) => Ok(est),
+            _ => anyhow::bail!("not an establishment"),
+        }
+    }
+
+    /// Runs the whole handshake and returns the keys of the Entry and the Exit.
+    fn handshake(exit: &OffchainKeypair) -> anyhow::Result<(SessionKeys, SessionKeys)> {
+        let (initiator, init) = entry_initiates(exit)?;
+        let (exit_keys, est) = exit_responds(exit, init)?;
+        Ok((initiator.finish(&established(est)?)?, exit_keys))
+    }
+
+    #[test]
+    fn handshake_should_derive_matching_directional_keys() -> anyhow::Result<()> {
+        let (entry_keys, exit_keys) = handshake(&OffchainKeypair::random())?;
+
+        assert_eq!(entry_keys.sending.as_ref(), exit_keys.receiving.as_ref());
+        assert_eq!(entry_keys.receiving.as_ref(), exit_keys.sending.as_ref());
+        assert_ne!(entry_keys.sending.as_ref(), entry_keys.receiving.as_ref());
+        Ok(())
+    }
+
+    #[test]
+    fn handshake_should_derive_fresh_keys_for_each_session() -> anyhow::Result<()> {
+        let exit = OffchainKeypair::random();
+        let (_, init_1) = entry_initiates(&exit)?;
+        let (_, init_2) = entry_initiates(&exit)?;
+        assert_ne!(init_1, init_2);
+
+        let (keys_1, _) = exit_responds(&exit, init_1.clone())?;
+        let (keys_2, _) = exit_responds(&exit, init_1)?;
+        assert_ne!(keys_1.sending.as_ref(), keys_2.sending.as_ref());
+        Ok(())
+    }
+
+    #[test]
+    fn handshake_should_reject_establishment_without_key_confirmation() -> anyhow::Result<()> {
+        let exit = OffchainKeypair::random();
+        let (initiator, init) = entry_initiates(&exit)?;
+
+        // An attacker strips the key share, so the Exit never takes part in the key exchange
+        let mut init = initiation_of(init)?;
+        init.key_share = None;
+        let init = initiation_of(transfer(StartProtocol::StartSession(init))?)?;
+        assert!(matches!(
+            HandshakeResponder::new(&exit).respond(&init, &establishment(&init)),
+            Err(StartProtocolError::MissingKeyShare)
+        ));
+
+        let est = established(transfer(StartProtocol::SessionEstablished(establishment(&init)))?)?;
+        assert!(matches!(
+            initiator.finish(&est),
+            Err(StartProtocolError::MissingKeyConfirmation)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn handshake_should_reject_tampered_initiation() -> anyhow::Result<()> {
+        let exit = OffchainKeypair::random();
+
+        // An attacker changes the negotiation offered by the Entry on the way to the Exit
+        let tamperings: [fn(&mut StartInitiation<String, u16>); 5] = [
+            |init| init.challenge += 1,
+            |init| init.capabilities &= 0b0111_1111,
+            |init| init.optional_capabilities = Some(CAPABILITIES),
+            |init| init.optional_capabilities = None,
+            |init| init.session_protocol_version = None,
+        ];
+        for tamper in tamperings {
+            let (initiator, init) = entry_initiates(&exit)?;
+            let mut init = initiation_of(init)?;
+            tamper(&mut init);
+
+            let (_, est) = exit_responds(&exit, transfer(StartProtocol::StartSession(init))?)?;
+            assert!(matches!(
+                initiator.finish(&established(est)?),
+                Err(StartProtocolError::InvalidKeyConfirmation)
+            ));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn handshake_should_reject_tampered_establishment() -> anyhow::Result<()> {
+        let exit = OffchainKeypair::random();
+
+        // An attacker changes the Session agreed by the Exit on the way to the Entry
+        let tamperings: [fn(&mut StartEstablished<String, u16>); 4] = [
+            |est| est.session_id = "other-session-id".into(),
+            |est| est.capabilities = est.capabilities.map(|caps| caps & 0b0111_1111),
+            |est| est.capabilities = Some(CAPABILITIES),
+            |est| est.session_protocol_version = Some(1),
+        ];
+        for tamper in tamperings {
+            let (initiator, init) = entry_initiates(&exit)?;
+            let (_, est) = exit_responds(&exit, init)?;
+            let mut est = established(est)?;
+            tamper(&mut est);
+
+            let est = established(transfer(StartProtocol::SessionEstablished(est))?)?;
+            assert!(matches!(
+                initiator.finish(&est),
+                Err(StartProtocolError::InvalidKeyConfirmation)
+            ));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn handshake_should_reject_impersonated_exit() -> anyhow::Result<()> {
+        let exit = OffchainKeypair::random();
+        let impostor = OffchainKeypair::random();
+        let (initiator, init) = entry_initiates(&exit)?;
+
+        let (_, est) = exit_responds(&impostor, init)?;
+
+        assert!(matches!(
+            initiator.finish(&established(est)?),
+            Err(StartProtocolError::InvalidKeyConfirmation)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn handshake_should_reject_low_order_key_share() -> anyhow::Result<()> {
+        let exit = OffchainKeypair::random();
+        let init = StartInitiation {
+            key_share: Some([0u8; KEY_SHARE_SIZE]),
+            ..initiation()
+        };
+
+        assert!(matches!(
+            HandshakeResponder::new(&exit).respond(&init, &establishment(&init)),
+            Err(StartProtocolError::InvalidKeyShare)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn session_cipher_should_seal_and_open_in_both_directions() -> anyhow::Result<()> {
+        let (entry_keys, exit_keys) = handshake(&OffchainKeypair::random())?;
+        let entry = SessionCipher::new(entry_keys);
+        let exit = SessionCipher::new(exit_keys);
+
+        let msg_1 = entry.seal(b"hello exit");
+        let msg_2 = entry.seal(b"hello exit");
+        assert_eq!(msg_1.len(), b"hello exit".len() + SessionCipher::OVERHEAD);
+        assert_ne!(msg_1, msg_2, "each message must use a fresh nonce");
+
+        // Messages can be opened out of order
+        assert_eq!(b"hello exit", exit.open(&msg_2)?.as_ref());
+        assert_eq!(b"hello exit", exit.open(&msg_1)?.as_ref());
+        assert_eq!(b"hello entry", entry.open(&exit.seal(b"hello entry"))?.as_ref());
+
+        // A party cannot open its own messages
+        assert!(entry.open(&msg_1).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn session_cipher_should_reject_tampered_messages() -> anyhow::Result<()> {
+        let (entry_keys, exit_keys) = handshake(&OffchainKeypair::random())?;
+        let entry = SessionCipher::new(entry_keys);
+        let exit = SessionCipher::new(exit_keys);
+
+        let sealed = entry.seal(b"hello exit");
+        for i in 0..sealed.len() {
+            let mut tampered = sealed.to_vec();
+            tampered[i] ^= 0x01;
+            assert!(matches!(
+                exit.open(&tampered),
+                Err(StartProtocolError::InvalidCiphertext)
+            ));
+        }
+        assert!(exit.open(&sealed[..sealed.len() - 1]).is_err());
+        assert!(exit.open(&sealed[..SessionCipher::OVERHEAD - 1]).is_err());
+        Ok(())
+    }
+}
@@ -10,21 +10,86 @@
 //! Per `RFC-0012`, the types `I` and `T` are serialized/deserialized to the CBOR binary format
 //! (see [`RFC7049`](https://datatracker.ietf.org/doc/html/rfc7049)) and therefore must implement
 //! `serde::Serialize + serde::Deserialize`.
-//! The capability type `C` must be expressible as a single unsigned byte.
+//! The capability type `C` must be expressible as an unsigned 16-bit integer (see [`StartCapabilities`]).
+//! Capabilities are encoded using 7 bits per byte, and the most significant bit of each byte signals
+//! that another byte follows. Capabilities within the lowest 7 bits are therefore encoded in a single byte,
+//! as in the older versions of the protocol.
 //!
 //! See [`StartProtocol`] docs for the protocol diagram.
 
 /// Contains errors raised by the Start protocol.
 pub mod errors;
+/// Optional authenticated key exchange performed during the Start handshake.
+pub mod handshake;
 
 use hopr_crypto_packet::prelude::HoprPacket;
 use hopr_protocol_app::prelude::{ApplicationData, ReservedTag, Tag};
 
-use crate::errors::StartProtocolError;
+use crate::{
+    errors::StartProtocolError,
+    handshake::{KEY_SHARE_SIZE, KeyConfirmation, KeyShare},
+};
 
 /// Challenge that identifies a Start initiation protocol message.
 pub type StartChallenge = u64;
 
+/// Secret issued by the Session recipient that allows the initiator to resume the Session.
+pub type ResumeTicket = u128;
+
+/// Session capabilities carried by the Start protocol messages.
+///
+/// Capabilities the recipient may leave out are decoded via [`StartCapabilities::from_truncated`],
+/// so that optional capabilities unknown to the recipient are ignored instead of rejected.
+/// All other capabilities are decoded strictly via `TryFrom<u16>`.
+pub trait StartCapabilities: Into<u16> + TryFrom<u16> {
+    /// Converts the `bits`, leaving out those that do not stand for a known capability.
+    fn from_truncated(bits: u16) -> Self;
+}
+
+impl StartCapabilities for u8 {
+    fn from_truncated(bits: u16) -> Self {
+        (bits & 0xff) as u8
+    }
+}
+
+impl StartCapabilities for u16 {
+    fn from_truncated(bits: u16) -> Self {
+        bits
+    }
+}
+
+/// Maximum number of bytes of the encoded capabilities.
+const MAX_CAPABILITIES_SIZE: usize = 3;
+
+/// Appends the `capabilities` encoded using 7 bits per byte, starting with the least significant bits.
+///
+/// The most significant bit of each byte is reserved to signal that another byte follows.
+fn encode_capabilities(capabilities: u16, out: &mut Vec<u8>) {
+    let mut remaining = capabilities;
+    while remaining >= 0x80 {
+        out.push((remaining & 0x7f) as u8 | 0x80);
+        remaining >>= 7;
+    }
+    out.push(remaining as u8);
+}
+
+/// Decodes the capabilities at the beginning of `data`, returning them with the number of bytes read.
+///
+/// Fails if the encoding does not terminate, overflows 16 bits or ends with a redundant zero byte.
+fn decode_capabilities(data: &[u8]) -> Option<(u16, usize)> {
+    let mut capabilities = 0u32;
+    for (i, byte) in data.iter().take(MAX_CAPABILITIES_SIZE).enumerate() {
+        capabilities |= u32::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return (i == 0 || *byte != 0)
+                .then(|| u16::try_from(capabilities).ok())
+                .flatten()
+                .map(|capabilities| (capabilities, i + 1));
+        }
+    }
+    None
+}
+
 /// Lists all Start protocol error reasons.
 #[repr(u8)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, strum::Display, strum::FromRepr)]
@@ -35,6 +100,28 @@ pub enum StartErrorReason {
     NoSlotsAvailable = 1,
     /// Recipient is busy.
     Busy = 2,
+    /// The Session to be resumed does not exist or the resume ticket does not match.
+    UnknownSession = 3,
+    /// The recipient could not perform the requested [key exchange](handshake).
+    HandshakeFailed = 4,
+    /// The recipient does not support some of the capabilities required by the initiator.
+    UnsupportedCapability = 5,
+    /// The recipient refuses to forward the Session to the requested target.
+    TargetRejected = 6,
+    /// The initiator opens Sessions at the recipient too frequently.
+    RateLimited = 7,
+    /// The recipient does not run the service requested as the Session target.
+    UnknownService = 8,
+}
+
+impl StartErrorReason {
+    /// Lowest version of the Start protocol that knows this reason.
+    fn min_version(&self) -> u8 {
+        match self {
+            Self::UnsupportedCapability | Self::TargetRejected | Self::RateLimited | Self::UnknownService => 0x03,
+            _ => 0x02,
+        }
+    }
 }
 
 /// Error message in the Start protocol.
@@ -54,6 +141,17 @@ pub struct StartErrorType {
 ///
 /// The `additional_data` are set dependent on the `capabilities`
 /// or set to `0x00000000` to be ignored.
+///
+/// ## Capability negotiation
+/// All the `capabilities` are required by the initiator, unless also listed in `optional_capabilities`.
+/// The recipient leaves out the optional capabilities it does not support and returns the agreed
+/// capabilities in [`StartEstablished`]. If it does not support any of the required capabilities,
+/// it responds with [`StartErrorReason::UnsupportedCapability`].
+///
+/// ## Session protocol version
+/// The initiator offers the highest version of the Session protocol it supports in
+/// `session_protocol_version`, and the recipient answers with the version both of them will use
+/// in [`StartEstablished`]. Without the offer, the Session uses version 1 of the Session protocol.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct StartInitiation<T, C> {
     /// Random challenge for this initiation.
@@ -62,20 +160,68 @@ pub struct StartInitiation<T, C> {
     pub target: T,
     /// Capabilities of the session.
     pub capabilities: C,
+    /// Subset of `capabilities` the recipient may leave out, if the initiator negotiates them.
+    ///
+    /// Recipients on protocol versions without negotiation treat all capabilities as required.
+    pub optional_capabilities: Option<C>,
+    /// Highest version of the Session protocol supported by the initiator, if it offers any.
+    pub session_protocol_version: Option<u8>,
     /// Additional options (might be `capabilities` dependent), ignored if `0x00000000`.
     pub additional_data: u32,
+    /// Initiator's part of the [key exchange](handshake), if requested.
+    pub key_share: Option<KeyShare>,
 }
 
 /// Message of the Start protocol that confirms the establishment of a session.
 ///
 /// ## Generic parameters
-/// `I` is for session identifier.
+/// - `I` is for session identifier.
+/// - `C` are session capabilities.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct StartEstablished<I> {
+pub struct StartEstablished<I, C> {
     /// Challenge that was used in the [initiation message](StartInitiation) to establish correspondence.
     pub orig_challenge: StartChallenge,
     /// Session ID that was selected by the recipient.
     pub session_id: I,
+    /// Capabilities agreed by the recipient, if the initiator [negotiated](StartInitiation) them.
+    ///
+    /// If not present, all the capabilities requested by the initiator were accepted.
+    /// It must be present whenever the `session_protocol_version` is.
+    pub capabilities: Option<C>,
+    /// Version of the Session protocol agreed by the recipient, if the initiator offered one.
+    ///
+    /// It never exceeds the version [offered](StartInitiation::session_protocol_version) by the initiator.
+    /// If not present, the Session uses version 1 of the Session protocol.
+    pub session_protocol_version: Option<u8>,
+    /// Ticket allowing the initiator to [resume](StartResumption) the Session later.
+    ///
+    /// Issued only if the initiator asked for it.
+    pub resume_ticket: Option<ResumeTicket>,
+    /// Recipient's part of the [key exchange](handshake), if the initiator started one.
+    pub key_confirmation: Option<KeyConfirmation>,
+}
+
+/// The session resumption message of the Start protocol.
+///
+/// Re-attaches the initiator to an existing Session at the recipient, for example
+/// after the initiator's path to the recipient has changed.
+/// The recipient answers with [`StartEstablished`] or with [`StartErrorReason::UnknownSession`].
+///
+/// Sessions are not persisted by the recipient, so a Session cannot be resumed once the recipient
+/// has restarted: such a request is always answered with [`StartErrorReason::UnknownSession`].
+///
+/// ## Generic parameters
+/// `I` is for session identifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StartResumption<I> {
+    /// Random challenge for this resumption.
+    pub challenge: StartChallenge,
+    /// Session ID of the Session to resume.
+    pub session_id: I,
+    /// Ticket received in the [`StartEstablished`] message of the Session.
+    pub ticket: ResumeTicket,
+    /// Additional options, ignored if `0x00000000`.
+    pub additional_data: u32,
 }
 
 #[cfg_attr(doc, aquamarine::aquamarine)]
@@ -88,10 +234,10 @@ pub struct StartEstablished<I> {
 /// # Diagram of the protocol
 /// ```mermaid
 /// sequenceDiagram
-///     Entry->>Exit: SessionInitiation (Challenge)
+///     Entry->>Exit: SessionInitiation (Challenge, Capabilities, [SessionVersion], [KeyShare])
 ///     alt If Exit can accept a new session
 ///     Note right of Exit: SessionID [Pseudonym, Tag]
-///     Exit->>Entry: SessionEstablished (Challenge, SessionID_Entry)
+///     Exit->>Entry: SessionEstablished (Challenge, SessionID_Entry, [Capabilities], [SessionVersion], [KeyConfirmation])
 ///     Note left of Entry: SessionID [Pseudonym, Tag]
 ///     Entry->>Exit: KeepAlive (SessionID)
 ///     Note over Entry,Exit: Data
@@ -101,6 +247,10 @@ pub struct StartEstablished<I> {
 ///     opt If initiation attempt times out
 ///     Note left of Entry: Failure
 ///     end
+///     opt If Entry path changes
+///     Entry->>Exit: ResumeSession (Challenge, SessionID, Ticket)
+///     Exit->>Entry: SessionEstablished (Challenge, SessionID, Ticket)
+///     end
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, strum::EnumDiscriminants)]
 #[strum_discriminants(vis(pub))]
@@ -109,11 +259,13 @@ pub enum StartProtocol<I, T, C> {
     /// Request to initiate a new session.
     StartSession(StartInitiation<T, C>),
     /// Confirmation that a new session has been established by the counterparty.
-    SessionEstablished(StartEstablished<I>),
+    SessionEstablished(StartEstablished<I, C>),
     /// Counterparty could not establish a new session due to an error.
     SessionError(StartErrorType),
     /// A ping message to keep the session alive.
     KeepAlive(KeepAliveMessage<I>),
+    /// Request to resume an existing session.
+    ResumeSession(StartResumption<I>),
 }
 
 /// Keep-alive message for a Session with the identifier `T`.
@@ -168,37 +320,88 @@ impl<I> From<I> for KeepAliveMessage<I> {
 }
 
 impl<I, T, C> StartProtocol<I, T, C> {
+    /// Oldest version of the Start protocol that can still be decoded.
+    pub const MIN_START_PROTOCOL_VERSION: u8 = 0x02;
     /// Fixed [`Tag`] of every protocol message.
     pub const START_PROTOCOL_MESSAGE_TAG: Tag = Tag::Reserved(ReservedTag::SessionStart as u64);
     /// Current version of the Start protocol.
-    pub const START_PROTOCOL_VERSION: u8 = 0x02;
+    pub const START_PROTOCOL_VERSION: u8 = 0x04;
+
+    /// Lowest version of the Start protocol that can carry this message.
+    ///
+    /// Messages are always encoded using this version, so that counterparties on older versions
+    /// can still decode them, unless the message uses features of a newer version.
+    /// Version `0x03` added the capability negotiation and the error reasons related to it.
+    /// Version `0x04` added the negotiation of the Session protocol version.
+    pub fn version(&self) -> u8 {
+        match self {
+            StartProtocol::StartSession(init) if init.session_protocol_version.is_some() => 0x04,
+            StartProtocol::SessionEstablished(est) if est.session_protocol_version.is_some() => 0x04,
+            StartProtocol::StartSession(init) if init.optional_capabilities.is_some() => 0x03,
+            StartProtocol::SessionEstablished(est) if est.capabilities.is_some() => 0x03,
+            StartProtocol::SessionError(err) => err.reason.min_version(),
+            _ => Self::MIN_START_PROTOCOL_VERSION,
+        }
+    }
 }
 
 impl<I, T, C> StartProtocol<I, T, C>
 where
     I: serde::Serialize + for<'de> serde::Deserialize<'de>,
     T: serde::Serialize + for<'de> serde::Deserialize<'de>,
-    C: Into<u8> + TryFrom<u8>,
+    C: StartCapabilities,
 {
     /// Tries to encode the message into binary format and [`Tag`]
     pub fn encode(self) -> errors::Result<(Tag, Box<[u8]>)> {
+        let version = self.version();
         let mut out = Vec::with_capacity(ApplicationData::PAYLOAD_SIZE);
-        out.push(Self::START_PROTOCOL_VERSION);
+        out.push(version);
         out.push(StartProtocolDiscriminants::from(&self) as u8);
 
         let mut data = Vec::with_capacity(ApplicationData::PAYLOAD_SIZE - 2);
         match self {
             StartProtocol::StartSession(init) => {
                 data.extend_from_slice(&init.challenge.to_be_bytes());
-                data.push(init.capabilities.into());
+                encode_capabilities(init.capabilities.into(), &mut data);
+                // Since version 0x04, no optional capabilities are encoded as an empty set
+                match init.optional_capabilities {
+                    Some(optional_capabilities) => encode_capabilities(optional_capabilities.into(), &mut data),
+                    None if version >= 0x04 => encode_capabilities(0, &mut data),
+                    None => {}
+                }
+                if let Some(session_protocol_version) = init.session_protocol_version {
+                    data.push(session_protocol_version);
+                }
                 data.extend_from_slice(&init.additional_data.to_be_bytes());
                 let target = serde_cbor_2::to_vec(&init.target)?;
                 data.extend_from_slice(&target);
+                if let Some(key_share) = init.key_share {
+                    data.extend_from_slice(&key_share);
+                }
             }
             StartProtocol::SessionEstablished(est) => {
                 data.extend_from_slice(&est.orig_challenge.to_be_bytes());
+                match est.capabilities {
+                    Some(capabilities) => encode_capabilities(capabilities.into(), &mut data),
+                    // The agreed capabilities cannot be left out once the message carries the version
+                    None if version >= 0x04 => {
+                        return Err(StartProtocolError::ParseError("est.capabilities".into()));
+                    }
+                    None => {}
+                }
+                if let Some(session_protocol_version) = est.session_protocol_version {
+                    data.push(session_protocol_version);
+                }
                 let session_id = serde_cbor_2::to_vec(&est.session_id)?;
                 data.extend(session_id);
+                // The ticket is appended only when present, keeping the message
+                // compatible with recipients that do not know about resumption.
+                if let Some(ticket) = est.resume_ticket {
+                    data.extend_from_slice(&ticket.to_be_bytes());
+                }
+                if let Some(confirmation) = est.key_confirmation {
+                    data.extend_from_slice(&confirmation.to_bytes());
+                }
             }
             StartProtocol::SessionError(err) => {
                 data.extend_from_slice(&err.challenge.to_be_bytes());
@@ -210,6 +413,13 @@ where
                 let session_id = serde_cbor_2::to_vec(&ping.session_id)?;
                 data.extend(session_id);
             }
+            StartProtocol::ResumeSession(res) => {
+                data.extend_from_slice(&res.challenge.to_be_bytes());
+                data.extend_from_slice(&res.ticket.to_be_bytes());
+                data.extend_from_slice(&res.additional_data.to_be_bytes());
+                let session_id = serde_cbor_2::to_vec(&res.session_id)?;
+                data.extend(session_id);
+            }
         }
 
         out.extend_from_slice(&(data.len() as u16).to_be_bytes());
@@ -221,7 +431,8 @@ where
     /// Tries to decode the message from the binary representation and [`Tag`].
     ///
     /// The `tag` must be currently [`START_PROTOCOL_MESSAGE_TAG`](Self::START_PROTOCOL_MESSAGE_TAG)
-    /// and version [`START_PROTOCOL_VERSION`](Self::START_PROTOCOL_VERSION).
+    /// and version between [`MIN_START_PROTOCOL_VERSION`](Self::MIN_START_PROTOCOL_VERSION)
+    /// and [`START_PROTOCOL_VERSION`](Self::START_PROTOCOL_VERSION).
     pub fn decode(tag: Tag, data: &[u8]) -> errors::Result<Self> {
         if tag != Self::START_PROTOCOL_MESSAGE_TAG {
             return Err(StartProtocolError::UnknownTag);
@@ -231,9 +442,14 @@ where
             return Err(StartProtocolError::InvalidLength);
         }
 
-        if data[0] != Self::START_PROTOCOL_VERSION {
+        let version = data[0];
+        if !(Self::MIN_START_PROTOCOL_VERSION..=Self::START_PROTOCOL_VERSION).contains(&version) {
             return Err(StartProtocolError::InvalidVersion);
         }
+        // Version 0x03 added the negotiated capabilities
+        let negotiated = version >= 0x03;
+        // Version 0x04 added the negotiated Session protocol version
+        let versioned = version >= 0x04;
 
         let disc = data[1];
         let len = u16::from_be_bytes(
@@ -250,41 +466,116 @@ where
         Ok(
             match StartProtocolDiscriminants::from_repr(disc).ok_or(StartProtocolError::UnknownMessage)? {
                 StartProtocolDiscriminants::StartSession => {
-                    if data.len() <= data_offset + size_of::<StartChallenge>() + 1 + size_of::<u32>() {
+                    let capabilities_offset = data_offset + size_of::<StartChallenge>();
+                    if data.len() <= capabilities_offset {
+                        return Err(StartProtocolError::InvalidLength);
+                    }
+                    let (capabilities, capabilities_len) = decode_capabilities(&data[capabilities_offset..])
+                        .ok_or(StartProtocolError::ParseError("init.capabilities".into()))?;
+                    let optional_offset = capabilities_offset + capabilities_len;
+                    let (optional_capabilities, optional_len) = if negotiated {
+                        decode_capabilities(&data[optional_offset..])
+                            .map(|(optional, len)| (Some(optional), len))
+                            .ok_or(StartProtocolError::ParseError("init.optional_capabilities".into()))?
+                    } else {
+                        (None, 0)
+                    };
+                    // Only the optional capabilities may be unknown to the recipient
+                    C::try_from(capabilities & !optional_capabilities.unwrap_or_default())
+                        .map_err(|_| StartProtocolError::ParseError("init.capabilities".into()))?;
+                    let session_version_offset = optional_offset + optional_len;
+                    let additional_data_offset = session_version_offset + usize::from(versioned);
+                    let target_offset = additional_data_offset + size_of::<u32>();
+                    if data.len() <= target_offset {
                         return Err(StartProtocolError::InvalidLength);
                     }
 
+                    // The CBOR-encoded target is optionally followed by the key share
+                    let mut de = serde_cbor_2::Deserializer::from_slice(&data[target_offset..]);
+                    let target = T::deserialize(&mut de)?;
+                    let key_share = match &data[target_offset + de.byte_offset()..] {
+                        [] => None,
+                        rest if rest.len() == KEY_SHARE_SIZE => Some(
+                            rest.try_into()
+                                .map_err(|_| StartProtocolError::ParseError("init.key_share".into()))?,
+                        ),
+                        _ => return Err(StartProtocolError::InvalidLength),
+                    };
+
                     StartProtocol::StartSession(StartInitiation {
                         challenge: StartChallenge::from_be_bytes(
-                            data[data_offset..data_offset + size_of::<StartChallenge>()]
+                            data[data_offset..capabilities_offset]
                                 .try_into()
                                 .map_err(|_| StartProtocolError::ParseError("init.challenge".into()))?,
                         ),
-                        capabilities: data[data_offset + size_of::<StartChallenge>()]
-                            .try_into()
-                            .map_err(|_| StartProtocolError::ParseError("init.capabilities".into()))?,
+                        capabilities: C::from_truncated(capabilities),
+                        optional_capabilities: optional_capabilities
+                            .filter(|optional| !versioned || *optional != 0)
+                            .map(C::from_truncated),
+                        session_protocol_version: versioned.then_some(data[session_version_offset]),
                         additional_data: u32::from_be_bytes(
-                            data[data_offset + size_of::<StartChallenge>() + 1
-                                ..data_offset + size_of::<StartChallenge>() + 1 + size_of::<u32>()]
+                            data[additional_data_offset..target_offset]
                                 .try_into()
                                 .map_err(|_| StartProtocolError::ParseError("init.additional_data".into()))?,
                         ),
-                        target: serde_cbor_2::from_slice(
-                            &data[data_offset + size_of::<StartChallenge>() + 1 + size_of::<u32>()..],
-                        )?,
+                        target,
+                        key_share,
                     })
                 }
                 StartProtocolDiscriminants::SessionEstablished => {
-                    if data.len() <= data_offset + size_of::<StartChallenge>() {
+                    let capabilities_offset = data_offset + size_of::<StartChallenge>();
+                    if data.len() <= capabilities_offset {
                         return Err(StartProtocolError::InvalidLength);
                     }
+                    let (capabilities, capabilities_len) = if negotiated {
+                        decode_capabilities(&data[capabilities_offset..])
+                            .map(|(capabilities, len)| (Some(capabilities), len))
+                            .ok_or(StartProtocolError::ParseError("est.capabilities".into()))?
+                    } else {
+                        (None, 0)
+                    };
+                    let session_version_offset = capabilities_offset + capabilities_len;
+                    let session_id_offset = session_version_offset + usize::from(versioned);
+                    if data.len() <= session_id_offset {
+                        return Err(StartProtocolError::InvalidLength);
+                    }
+
+                    // The CBOR-encoded session ID is optionally followed by the resume ticket
+                    // and the key confirmation, which are told apart by their sizes.
+                    let mut de = serde_cbor_2::Deserializer::from_slice(&data[session_id_offset..]);
+                    let session_id = I::deserialize(&mut de)?;
+                    let rest = &data[session_id_offset + de.byte_offset()..];
+                    let (ticket, confirmation) = match rest.len() {
+                        0 => (None, None),
+                        n if n == size_of::<ResumeTicket>() => (Some(rest), None),
+                        KeyConfirmation::SIZE => (None, Some(rest)),
+                        n if n == size_of::<ResumeTicket>() + KeyConfirmation::SIZE => {
+                            let (ticket, confirmation) = rest.split_at(size_of::<ResumeTicket>());
+                            (Some(ticket), Some(confirmation))
+                        }
+                        _ => return Err(StartProtocolError::InvalidLength),
+                    };
+                    let resume_ticket = ticket
+                        .map(|ticket| {
+                            ticket
+                                .try_into()
+                                .map(ResumeTicket::from_be_bytes)
+                                .map_err(|_| StartProtocolError::ParseError("est.resume_ticket".into()))
+                        })
+                        .transpose()?;
+                    let key_confirmation = confirmation.map(KeyConfirmation::from_bytes).transpose()?;
+
                     StartProtocol::SessionEstablished(StartEstablished {
                         orig_challenge: StartChallenge::from_be_bytes(
-                            data[data_offset..data_offset + size_of::<StartChallenge>()]
+                            data[data_offset..capabilities_offset]
                                 .try_into()
                                 .map_err(|_| StartProtocolError::ParseError("est.challenge".into()))?,
                         ),
-                        session_id: serde_cbor_2::from_slice(&data[data_offset + size_of::<StartChallenge>()..])?,
+                        session_id,
+                        capabilities: capabilities.map(C::from_truncated),
+                        session_protocol_version: versioned.then_some(data[session_version_offset]),
+                        resume_ticket,
+                        key_confirmation,
                     })
                 }
                 StartProtocolDiscriminants::SessionError => {
@@ -298,6 +589,7 @@ where
                                 .map_err(|_| StartProtocolError::ParseError("err.challenge".into()))?,
                         ),
                         reason: StartErrorReason::from_repr(data[data_offset + size_of::<StartChallenge>()])
+                            .filter(|reason| reason.min_version() <= version)
                             .ok_or(StartProtocolError::ParseError("err.reason".into()))?,
                     })
                 }
@@ -317,6 +609,33 @@ where
                         session_id: serde_cbor_2::from_slice(&data[data_offset + 1 + size_of::<u64>()..])?,
                     })
                 }
+                StartProtocolDiscriminants::ResumeSession => {
+                    let ticket_offset = data_offset + size_of::<StartChallenge>();
+                    let additional_data_offset = ticket_offset + size_of::<ResumeTicket>();
+                    let session_id_offset = additional_data_offset + size_of::<u32>();
+                    if data.len() <= session_id_offset {
+                        return Err(StartProtocolError::InvalidLength);
+                    }
+
+                    StartProtocol::ResumeSession(StartResumption {
+                        challenge: StartChallenge::from_be_bytes(
+                            data[data_offset..ticket_offset]
+                                .try_into()
+                                .map_err(|_| StartProtocolError::ParseError("res.challenge".into()))?,
+                        ),
+                        ticket: ResumeTicket::from_be_bytes(
+                            data[ticket_offset..additional_data_offset]
+                                .try_into()
+                                .map_err(|_| StartProtocolError::ParseError("res.ticket".into()))?,
+                        ),
+                        additional_data: u32::from_be_bytes(
+                            data[additional_data_offset..session_id_offset]
+                                .try_into()
+                                .map_err(|_| StartProtocolError::ParseError("res.additional_data".into()))?,
+                        ),
+                        session_id: serde_cbor_2::from_slice(&data[session_id_offset..])?,
+                    })
+                }
             },
         )
     }
@@ -326,7 +645,7 @@ impl<I, T, C> TryFrom<StartProtocol<I, T, C>> for ApplicationData
 where
     I: serde::Serialize + for<'de> serde::Deserialize<'de>,
     T: serde::Serialize + for<'de> serde::Deserialize<'de>,
-    C: Into<u8> + TryFrom<u8>,
+    C: StartCapabilities,
 {
     type Error = StartProtocolError;
 
@@ -340,7 +659,7 @@ impl<I, T, C> TryFrom<ApplicationData> for StartProtocol<I, T, C>
 where
     I: serde::Serialize + for<'de> serde::Deserialize<'de>,
     T: serde::Serialize + for<'de> serde::Deserialize<'de>,
-    C: Into<u8> + TryFrom<u8>,
+    C: StartCapabilities,
 {
     type Error = StartProtocolError;
 
@@ -362,7 +681,10 @@ mod tests {
             challenge: 0,
             target: "127.0.0.1:1234".to_string(),
             capabilities: Default::default(),
+            optional_capabilities: None,
+            session_protocol_version: None,
             additional_data: 0x12345678,
+            key_share: None,
         });
 
         let (tag, msg) = msg_1.clone().encode()?;
@@ -377,11 +699,14 @@ mod tests {
 
     #[test]
     fn start_protocol_message_start_session_message_should_allow_for_at_least_one_surb() -> anyhow::Result<()> {
-        let msg = StartProtocol::<i32, String, u8>::StartSession(StartInitiation {
+        let msg = StartProtocol::<i32, String, u16>::StartSession(StartInitiation {
             challenge: 0,
             target: "127.0.0.1:1234".to_string(),
-            capabilities: 0xff,
+            capabilities: u16::MAX,
+            optional_capabilities: Some(u16::MAX),
+            session_protocol_version: Some(u8::MAX),
             additional_data: 0xffffffff,
+            key_share: Some([0xff; KEY_SHARE_SIZE]),
         });
 
         let len = msg.encode()?.1.len();
@@ -398,6 +723,10 @@ mod tests {
         let msg_1 = StartProtocol::SessionEstablished(StartEstablished {
             orig_challenge: 0,
             session_id: 10_i32,
+            capabilities: None,
+            session_protocol_version: None,
+            resume_ticket: None,
+            key_confirmation: None,
         });
 
         let (tag, msg) = msg_1.clone().encode()?;
@@ -406,10 +735,317 @@ mod tests {
 
         let msg_2 = StartProtocol::<i32, String, u8>::decode(tag, &msg)?;
 
+        assert_eq!(msg_1, msg_2);
+
+        let msg_1 = StartProtocol::SessionEstablished(StartEstablished {
+            orig_challenge: 0,
+            session_id: 10_i32,
+            capabilities: None,
+            session_protocol_version: None,
+            resume_ticket: Some(ResumeTicket::MAX - 1),
+            key_confirmation: None,
+        });
+
+        let (tag, msg) = msg_1.clone().encode()?;
+        let msg_2 = StartProtocol::<i32, String, u8>::decode(tag, &msg)?;
+
         assert_eq!(msg_1, msg_2);
         Ok(())
     }
 
+    #[test]
+    fn start_protocol_session_established_message_should_not_decode_with_truncated_ticket() -> anyhow::Result<()> {
+        let (tag, msg) = StartProtocol::<i32, String, u8>::SessionEstablished(StartEstablished {
+            orig_challenge: 0,
+            session_id: 10_i32,
+            capabilities: None,
+            session_protocol_version: None,
+            resume_ticket: Some(1),
+            key_confirmation: None,
+        })
+        .encode()?;
+
+        assert!(StartProtocol::<i32, String, u8>::decode(tag, &msg[..msg.len() - 1]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn start_protocol_start_session_message_should_encode_and_decode_with_key_share() -> anyhow::Result<()> {
+        let msg_1 = StartProtocol::StartSession(StartInitiation {
+            challenge: 0,
+            target: "127.0.0.1:1234".to_string(),
+            capabilities: 0x80,
+            optional_capabilities: None,
+            session_protocol_version: None,
+            additional_data: 0x12345678,
+            key_share: Some([0xaa; KEY_SHARE_SIZE]),
+        });
+
+        let (tag, msg) = msg_1.clone().encode()?;
+        let msg_2 = StartProtocol::<i32, String, u8>::decode(tag, &msg)?;
+        assert_eq!(msg_1, msg_2);
+
+        assert!(StartProtocol::<i32, String, u8>::decode(tag, &msg[..msg.len() - 1]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn start_protocol_session_established_message_should_encode_and_decode_with_key_confirmation() -> anyhow::Result<()>
+    {
+        let key_confirmation = KeyConfirmation {
+            key_share: [0xaa; KEY_SHARE_SIZE],
+            tag: [0xbb; handshake::CONFIRMATION_TAG_SIZE],
+        };
+
+        for resume_ticket in [None, Some(ResumeTicket::MAX - 1)] {
+            let msg_1 = StartProtocol::SessionEstablished(StartEstablished {
+                orig_challenge: 0,
+                session_id: 10_i32,
+                capabilities: None,
+                session_protocol_version: None,
+                resume_ticket,
+                key_confirmation: Some(key_confirmation),
+            });
+
+            let (tag, msg) = msg_1.clone().encode()?;
+            let msg_2 = StartProtocol::<i32, String, u8>::decode(tag, &msg)?;
+            assert_eq!(msg_1, msg_2);
+
+            assert!(StartProtocol::<i32, String, u8>::decode(tag, &msg[..msg.len() - 1]).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn start_protocol_resume_session_message_should_encode_and_decode() -> anyhow::Result<()> {
+        let msg_1 = StartProtocol::ResumeSession(StartResumption {
+            challenge: 10,
+            session_id: 10_i32,
+            ticket: 0x0123_4567_89ab_cdef_0123_4567_89ab_cdef,
+            additional_data: 0x12345678,
+        });
+
+        let (tag, msg) = msg_1.clone().encode()?;
+        let expected: Tag = StartProtocol::<(), (), ()>::START_PROTOCOL_MESSAGE_TAG;
+        assert_eq!(tag, expected);
+
+        let msg_2 = StartProtocol::<i32, String, u8>::decode(tag, &msg)?;
+
+        assert_eq!(msg_1, msg_2);
+        Ok(())
+    }
+
+    #[test]
+    fn start_protocol_messages_should_negotiate_capabilities() -> anyhow::Result<()> {
+        let msg_1 = StartProtocol::<i32, String, u8>::StartSession(StartInitiation {
+            challenge: 10,
+            target: "127.0.0.1:1234".to_string(),
+            capabilities: 0x83,
+            optional_capabilities: Some(0x03),
+            session_protocol_version: None,
+            additional_data: 0x12345678,
+            key_share: Some([0xaa; KEY_SHARE_SIZE]),
+        });
+        assert_eq!(0x03, msg_1.version());
+
+        let (tag, msg) = msg_1.clone().encode()?;
+        assert_eq!(0x03, msg[0]);
+        assert_eq!(msg_1, StartProtocol::<i32, String, u8>::decode(tag, &msg)?);
+
+        let msg_1 = StartProtocol::<i32, String, u8>::SessionEstablished(StartEstablished {
+            orig_challenge: 10,
+            session_id: 10_i32,
+            capabilities: Some(0x81),
+            session_protocol_version: None,
+            resume_ticket: Some(ResumeTicket::MAX - 1),
+            key_confirmation: None,
+        });
+        assert_eq!(0x03, msg_1.version());
+
+        let (tag, msg) = msg_1.clone().encode()?;
+        assert_eq!(0x03, msg[0]);
+        assert_eq!(msg_1, StartProtocol::<i32, String, u8>::decode(tag, &msg)?);
+        Ok(())
+    }
+
+    #[test]
+    fn start_protocol_capabilities_should_be_extensible_beyond_a_single_byte() -> anyhow::Result<()> {
+        for (capabilities, encoded) in [
+            (0x00_u16, &[0x00_u8][..]),
+            (0x7f, &[0x7f]),
+            (0x80, &[0x80, 0x01]),
+            (0x3fff, &[0xff, 0x7f]),
+            (u16::MAX, &[0xff, 0xff, 0x03]),
+        ] {
+            let mut out = Vec::new();
+            encode_capabilities(capabilities, &mut out);
+            assert_eq!(encoded, out.as_slice());
+            assert_eq!(Some((capabilities, encoded.len())), decode_capabilities(&out));
+        }
+
+        // Missing extension byte, overflow of 16 bits and a redundant zero extension byte
+        assert_eq!(None, decode_capabilities(&[0x80]));
+        assert_eq!(None, decode_capabilities(&[0xff, 0xff, 0x04]));
+        assert_eq!(None, decode_capabilities(&[0xff, 0xff, 0xff, 0x01]));
+        assert_eq!(None, decode_capabilities(&[0x81, 0x00]));
+
+        let msg_1 = StartProtocol::<i32, String, u16>::StartSession(StartInitiation {
+            challenge: 10,
+            target: "127.0.0.1:1234".to_string(),
+            capabilities: 0x1ff,
+            optional_capabilities: Some(0x180),
+            session_protocol_version: Some(2),
+            additional_data: 0x12345678,
+            key_share: None,
+        });
+        let (tag, msg) = msg_1.clone().encode()?;
+        assert_eq!(msg_1, StartProtocol::<i32, String, u16>::decode(tag, &msg)?);
+
+        let msg_1 = StartProtocol::<i32, String, u16>::SessionEstablished(StartEstablished {
+            orig_challenge: 10,
+            session_id: 10_i32,
+            capabilities: Some(0x100),
+            session_protocol_version: Some(2),
+            resume_ticket: None,
+            key_confirmation: None,
+        });
+        let (tag, msg) = msg_1.clone().encode()?;
+        assert_eq!(msg_1, StartProtocol::<i32, String, u16>::decode(tag, &msg)?);
+
+        // Capabilities not representable by the capability type are rejected
+        let (tag, msg) = StartProtocol::<i32, String, u16>::StartSession(StartInitiation {
+            challenge: 10,
+            target: "127.0.0.1:1234".to_string(),
+            capabilities: 0x100,
+            optional_capabilities: None,
+            session_protocol_version: None,
+            additional_data: 0,
+            key_share: None,
+        })
+        .encode()?;
+        assert!(StartProtocol::<i32, String, u8>::decode(tag, &msg).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn start_protocol_messages_should_negotiate_session_protocol_version() -> anyhow::Result<()> {
+        for optional_capabilities in [None, Some(0x03)] {
+            let msg_1 = StartProtocol::<i32, String, u8>::StartSession(StartInitiation {
+                challenge: 10,
+                target: "127.0.0.1:1234".to_string(),
+                capabilities: 0x0b,
+                optional_capabilities,
+                session_protocol_version: Some(2),
+                additional_data: 0x12345678,
+                key_share: Some([0xaa; KEY_SHARE_SIZE]),
+            });
+            assert_eq!(0x04, msg_1.version());
+
+            let (tag, msg) = msg_1.clone().encode()?;
+            assert_eq!(0x04, msg[0]);
+            assert_eq!(msg_1, StartProtocol::<i32, String, u8>::decode(tag, &msg)?);
+        }
+
+        let msg_1 = StartProtocol::<i32, String, u8>::SessionEstablished(StartEstablished {
+            orig_challenge: 10,
+            session_id: 10_i32,
+            capabilities: Some(0x08),
+            session_protocol_version: Some(2),
+            resume_ticket: Some(ResumeTicket::MAX - 1),
+            key_confirmation: None,
+        });
+        assert_eq!(0x04, msg_1.version());
+
+        let (tag, msg) = msg_1.clone().encode()?;
+        assert_eq!(0x04, msg[0]);
+        assert_eq!(msg_1, StartProtocol::<i32, String, u8>::decode(tag, &msg)?);
+
+        // The agreed capabilities must be present along with the agreed version
+        let msg = StartProtocol::<i32, String, u8>::SessionEstablished(StartEstablished {
+            orig_challenge: 10,
+            session_id: 10_i32,
+            capabilities: None,
+            session_protocol_version: Some(2),
+            resume_ticket: None,
+            key_confirmation: None,
+        });
+        assert!(msg.encode().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn start_protocol_messages_without_negotiation_should_use_oldest_version() -> anyhow::Result<()> {
+        let init = StartProtocol::<i32, String, u8>::StartSession(StartInitiation {
+            challenge: 10,
+            target: "127.0.0.1:1234".to_string(),
+            capabilities: 0x83,
+            optional_capabilities: None,
+            session_protocol_version: None,
+            additional_data: 0x12345678,
+            key_share: None,
+        });
+        let est = StartProtocol::<i32, String, u8>::SessionEstablished(StartEstablished {
+            orig_challenge: 10,
+            session_id: 10_i32,
+            capabilities: None,
+            session_protocol_version: None,
+            resume_ticket: None,
+            key_confirmation: None,
+        });
+        let err = StartProtocol::<i32, String, u8>::SessionError(StartErrorType {
+            challenge: 10,
+            reason: StartErrorReason::Busy,
+        });
+
+        for msg in [init, est, err] {
+            let (_, data) = msg.encode()?;
+            assert_eq!(StartProtocol::<i32, String, u8>::MIN_START_PROTOCOL_VERSION, data[0]);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn start_protocol_should_not_decode_unsupported_versions() -> anyhow::Result<()> {
+        let (tag, mut msg) = StartProtocol::<i32, String, u8>::SessionError(StartErrorType {
+            challenge: 10,
+            reason: StartErrorReason::NoSlotsAvailable,
+        })
+        .encode()?;
+
+        msg[0] = StartProtocol::<i32, String, u8>::MIN_START_PROTOCOL_VERSION - 1;
+        assert!(matches!(
+            StartProtocol::<i32, String, u8>::decode(tag, &msg),
+            Err(StartProtocolError::InvalidVersion)
+        ));
+
+        msg[0] = StartProtocol::<i32, String, u8>::START_PROTOCOL_VERSION + 1;
+        assert!(matches!(
+            StartProtocol::<i32, String, u8>::decode(tag, &msg),
+            Err(StartProtocolError::InvalidVersion)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn start_protocol_session_error_reasons_should_require_their_version() -> anyhow::Result<()> {
+        for reason in [
+            StartErrorReason::UnsupportedCapability,
+            StartErrorReason::TargetRejected,
+            StartErrorReason::RateLimited,
+            StartErrorReason::UnknownService,
+        ] {
+            let msg_1 = StartProtocol::<i32, String, u8>::SessionError(StartErrorType { challenge: 10, reason });
+
+            let (tag, mut msg) = msg_1.clone().encode()?;
+            assert_eq!(0x03, msg[0]);
+            assert_eq!(msg_1, StartProtocol::<i32, String, u8>::decode(tag, &msg)?);
+
+            msg[0] = 0x02;
+            assert!(StartProtocol::<i32, String, u8>::decode(tag, &msg).is_err());
+        }
+        Ok(())
+    }
+
     #[test]
     fn start_protocol_session_error_message_should_encode_and_decode() -> anyhow::Result<()> {
         let msg_1 = StartProtocol::SessionError(StartErrorType {
@@ -461,12 +1097,15 @@ mod tests {
 
     #[test]
     fn start_protocol_messages_must_fit_within_hopr_packet() -> anyhow::Result<()> {
-        let msg = StartProtocol::<i32, String, u8>::StartSession(StartInitiation {
+        let msg = StartProtocol::<i32, String, u16>::StartSession(StartInitiation {
             challenge: StartChallenge::MAX,
             target: "example-of-a-very-very-long-second-level-name.on-a-very-very-long-domain-name.info:65530"
                 .to_string(),
-            capabilities: 0x80,
+            capabilities: u16::MAX,
+            optional_capabilities: Some(u16::MAX),
+            session_protocol_version: Some(u8::MAX),
             additional_data: 0xffffffff,
+            key_share: Some([0xff; KEY_SHARE_SIZE]),
         });
 
         assert!(
@@ -475,9 +1114,16 @@ mod tests {
             HoprPacket::PAYLOAD_SIZE
         );
 
-        let msg = StartProtocol::<String, String, u8>::SessionEstablished(StartEstablished {
+        let msg = StartProtocol::<String, String, u16>::SessionEstablished(StartEstablished {
             orig_challenge: StartChallenge::MAX,
             session_id: "example-of-a-very-very-long-session-id-that-should-still-fit-the-packet".to_string(),
+            capabilities: Some(u16::MAX),
+            session_protocol_version: Some(u8::MAX),
+            resume_ticket: Some(ResumeTicket::MAX),
+            key_confirmation: Some(KeyConfirmation {
+                key_share: [0xff; KEY_SHARE_SIZE],
+                tag: [0xff; handshake::CONFIRMATION_TAG_SIZE],
+            }),
         });
 
         assert!(
@@ -508,6 +1154,17 @@ mod tests {
             HoprPacket::PAYLOAD_SIZE
         );
 
+        let msg = StartProtocol::<String, String, u8>::ResumeSession(StartResumption {
+            challenge: StartChallenge::MAX,
+            session_id: "example-of-a-very-very-long-session-id-that-should-still-fit-the-packet".to_string(),
+            ticket: ResumeTicket::MAX,
+            additional_data: 0xffffffff,
+        });
+        assert!(
+            HoprPacket::max_surbs_with_message(msg.encode()?.1.len()) >= 1,
+            "ResumeSession must allow for at least 1 SURB in packet",
+        );
+
         Ok(())
     }
 
@@ -0,0 +1,128 @@
+use std::{fs::File, io::BufReader, path::PathBuf, str::FromStr};
+
+use clap::Parser;
+use hopr_transport::{
+    CaptureFilter, CaptureReader, CapturedPacketContent, OffchainPublicKey, PacketDirection, PacketKind, PeerId,
+    api::types::{keypair::key_pair::HoprKeys, primitive::prelude::ToHex},
+};
+
+#[derive(Parser)]
+#[command(name = "hopr-capture-decode")]
+#[command(about = "Lists the packets of HOPR packet captures")]
+struct Cli {
+    /// Capture files to read, in the order they were written.
+    #[arg(value_name = "FILE", required = true)]
+    files: Vec<PathBuf>,
+
+    /// Offchain public key or peer ID of the capturing node, only its packets are listed.
+    #[arg(long, short = 'n', value_parser = parse_peer)]
+    node: Option<OffchainPublicKey>,
+
+    /// Lists only the packets received from or sent to the given peers (offchain public keys or peer IDs).
+    #[arg(long, short = 'p', value_parser = parse_peer)]
+    peer: Vec<OffchainPublicKey>,
+
+    /// Lists only the packets of the given kinds (final, forwarded, outgoing, acknowledgement).
+    #[arg(long, short = 'k', value_parser = PacketKind::from_str)]
+    kind: Vec<PacketKind>,
+
+    /// Lists only the packets of the given direction (incoming, outgoing).
+    #[arg(long, short = 'd', value_parser = PacketDirection::from_str)]
+    direction: Vec<PacketDirection>,
+
+    /// Identity file of the capturing node, used to decrypt the wire data of the packets it received.
+    #[arg(
+        long,
+        short = 'i',
+        value_name = "FILE",
+        requires = "password",
+        conflicts_with = "private_key"
+    )]
+    identity: Option<PathBuf>,
+
+    /// Password of the identity file.
+    #[arg(long, env = "HOPRD_PASSWORD", hide_env_values = true)]
+    password: Option<String>,
+
+    /// Private key of the capturing node, used instead of its identity file.
+    #[arg(long, env = "HOPRD_PRIVATE_KEY", hide_env_values = true)]
+    private_key: Option<String>,
+
+    /// Prints all the decoded fields of each packet.
+    #[arg(long, short = 'v', default_value = "false")]
+    verbose: bool,
+}
+
+fn parse_peer(value: &str) -> Result<OffchainPublicKey, String> {
+    OffchainPublicKey::from_hex(value)
+        .map_err(|e| e.to_string())
+        .or_else(|_| {
+            PeerId::from_str(value)
+                .map_err(|e| e.to_string())
+                .and_then(|peer| hopr_transport::peer_id_to_public_key(&peer).map_err(|e| e.to_string()))
+        })
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+
+    let filter = CaptureFilter {
+        directions: cli.direction.into_iter().collect(),
+        kinds: cli.kind.into_iter().collect(),
+        peers: cli.peer.into_iter().collect(),
+        ..Default::default()
+    };
+
+    let packet_key = match (&cli.identity, &cli.private_key) {
+        (Some(identity), _) => Some(
+            HoprKeys::read_eth_keystore(&identity.to_string_lossy(), cli.password.as_deref().unwrap_or_default())?
+                .0
+                .packet_key,
+        ),
+        (None, Some(private_key)) => Some(HoprKeys::from_str(private_key)?.packet_key),
+        (None, None) => None,
+    };
+
+    let mut index = 0;
+    for path in &cli.files {
+        for packet in CaptureReader::new(BufReader::new(File::open(path)?))? {
+            index += 1;
+            let packet = match packet {
+                Ok(packet) => packet,
+                Err(error) => {
+                    eprintln!("{index}: {error}");
+                    continue;
+                }
+            };
+
+            // Wire data that cannot be decrypted are listed as they are
+            let (packet, decrypt_error) = match &packet_key {
+                Some(packet_key) if matches!(packet.content, CapturedPacketContent::Wire { .. }) => {
+                    match packet.clone().decrypt_wire(packet_key) {
+                        Ok(decrypted) => (decrypted, None),
+                        Err(error) => (packet, Some(error)),
+                    }
+                }
+                _ => (packet, None),
+            };
+
+            // Records of incoming relayed packets do not carry the capturing node
+            if cli.node.is_some_and(|node| packet.me().is_some_and(|me| me != &node))
+                || !filter.matches(packet.direction(), packet.kind(), packet.peer())
+            {
+                continue;
+            }
+
+            if cli.verbose {
+                println!("{index}: {packet}\n{:#?}", packet.content);
+            } else {
+                println!("{index}: {packet}");
+            }
+            if let Some(error) = decrypt_error {
+                eprintln!("{index}: {error}");
+            }
+        }
+    }
+
+    Ok(())
+}
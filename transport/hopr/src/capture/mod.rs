@@ -1,9 +1,17 @@
-use std::{borrow::Cow, fs::File};
+use std::{
+    borrow::Cow,
+    collections::{HashSet, VecDeque},
+    fs::File,
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
 
 use bytes::Bytes;
 use futures::StreamExt;
 use hopr_api::types::{
     crypto::types::OffchainPublicKey,
+    crypto_random::random_float,
     internal::{
         prelude::{Ticket, VerifiedAcknowledgement},
         routing::ResolvedTransportRouting,
@@ -29,13 +37,35 @@ use pcap_file::{
 
 use crate::PeerId;
 
+mod reader;
+
+pub use reader::{
+    CaptureReadError, CaptureReader, CapturedPacketContent, CapturedSessionMessage, DecodedPacket, DecodedPayload,
+};
+
 /// Direction of the packet.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, strum::Display)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, strum::Display, strum::EnumString)]
+#[strum(ascii_case_insensitive)]
 pub enum PacketDirection {
     Incoming,
     Outgoing,
 }
 
+/// Kind of the captured packet, as seen by this node.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, strum::Display, strum::EnumString, strum::FromRepr)]
+#[strum(serialize_all = "snake_case")]
+#[repr(u8)]
+pub enum PacketKind {
+    /// Packet delivered to this node.
+    Final,
+    /// Packet relayed by this node, captured both when it is received and when it is sent out.
+    Forwarded,
+    /// Packet originating at this node.
+    Outgoing,
+    /// Acknowledgement received or sent by this node.
+    Acknowledgement,
+}
+
 /// A captured packet that can be written to a [`PacketWriter`].
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CapturedPacket {
@@ -62,7 +92,11 @@ impl PacketWriter for NullWriter {
 }
 
 /// A [`PacketWriter`] that writes captured packets into a Pcap file.
-pub struct PcapPacketWriter(PcapNgWriter<File>);
+pub struct PcapPacketWriter {
+    writer: PcapNgWriter<File>,
+    num_packets: usize,
+    bytes_written: u64,
+}
 
 impl PcapPacketWriter {
     pub fn new(file: File) -> std::io::Result<Self> {
@@ -76,13 +110,29 @@ impl PcapPacketWriter {
             })
             .map_err(std::io::Error::other)?;
 
-        Ok(Self(writer))
+        let bytes_written = writer.get_ref().metadata()?.len();
+        Ok(Self {
+            writer,
+            num_packets: 0,
+            bytes_written,
+        })
+    }
+
+    /// Number of packets written into the file.
+    pub fn num_packets(&self) -> usize {
+        self.num_packets
+    }
+
+    /// Size of the file in bytes, including the pcapng headers.
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
     }
 }
 
 impl PacketWriter for PcapPacketWriter {
     fn write_packet(&mut self, packet: CapturedPacket) -> std::io::Result<()> {
-        self.0
+        let written = self
+            .writer
             .write_pcapng_block(EnhancedPacketBlock {
                 interface_id: 0,
                 timestamp: packet.timestamp,
@@ -90,8 +140,120 @@ impl PacketWriter for PcapPacketWriter {
                 data: packet.data.into_vec().into(),
                 options: vec![EnhancedPacketOption::Comment(packet.direction.to_string().into())],
             })
-            .map(|_| ())
-            .map_err(std::io::Error::other)
+            .map_err(std::io::Error::other)?;
+
+        self.num_packets += 1;
+        self.bytes_written += written as u64;
+        Ok(())
+    }
+}
+
+/// Determines when the [`RotatingPcapWriter`] starts a new file and how many files it keeps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, smart_default::SmartDefault)]
+pub struct RotationPolicy {
+    /// Maximum size of a single capture file in bytes.
+    ///
+    /// The file is rotated once it reaches this size, so it can exceed it by at most one packet.
+    #[default(Some(100 * 1024 * 1024))]
+    pub max_file_size: Option<u64>,
+    /// Maximum time span between the first and the last packet in a single capture file.
+    #[default(None)]
+    pub max_file_age: Option<Duration>,
+    /// Number of the most recent capture files to keep, older files are deleted.
+    ///
+    /// At least the file currently being written is always kept.
+    #[default(10)]
+    pub max_files: usize,
+}
+
+/// A [`PacketWriter`] that writes captured packets into a series of Pcap files,
+/// starting a new one according to the [`RotationPolicy`].
+///
+/// The files are named `<prefix>.<index>.pcapng`, where the index starts at 0 with each
+/// new writer, therefore files left over from a previous capture with the same prefix are overwritten.
+pub struct RotatingPcapWriter {
+    prefix: PathBuf,
+    policy: RotationPolicy,
+    current: PcapPacketWriter,
+    first_timestamp: Option<Duration>,
+    files: VecDeque<PathBuf>,
+    next_index: usize,
+}
+
+impl RotatingPcapWriter {
+    /// Creates the writer and its first capture file.
+    pub fn new(prefix: impl Into<PathBuf>, policy: RotationPolicy) -> std::io::Result<Self> {
+        let prefix = prefix.into();
+        let path = Self::file_path(&prefix, 0);
+        let current = File::create(&path).and_then(PcapPacketWriter::new)?;
+
+        Ok(Self {
+            prefix,
+            policy,
+            current,
+            first_timestamp: None,
+            files: VecDeque::from([path]),
+            next_index: 1,
+        })
+    }
+
+    /// Paths of the capture files currently kept, from the oldest to the most recent one.
+    pub fn files(&self) -> impl Iterator<Item = &std::path::Path> {
+        self.files.iter().map(PathBuf::as_path)
+    }
+
+    fn file_path(prefix: &std::path::Path, index: usize) -> PathBuf {
+        let mut path = prefix.as_os_str().to_owned();
+        path.push(format!(".{index:06}.pcapng"));
+        path.into()
+    }
+
+    fn should_rotate(&self, packet: &CapturedPacket) -> bool {
+        // Never leave an empty file behind
+        if self.current.num_packets() == 0 {
+            return false;
+        }
+
+        self.policy
+            .max_file_size
+            .is_some_and(|max_size| self.current.bytes_written() >= max_size)
+            || self
+                .policy
+                .max_file_age
+                .zip(self.first_timestamp)
+                .is_some_and(|(max_age, first)| packet.timestamp.saturating_sub(first) >= max_age)
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        let path = Self::file_path(&self.prefix, self.next_index);
+        self.current = File::create(&path).and_then(PcapPacketWriter::new)?;
+        self.first_timestamp = None;
+        self.next_index += 1;
+        self.files.push_back(path);
+
+        while self.files.len() > self.policy.max_files.max(1) {
+            if let Some(old) = self.files.pop_front()
+                && let Err(error) = std::fs::remove_file(&old)
+                && error.kind() != std::io::ErrorKind::NotFound
+            {
+                tracing::warn!(%error, path = %old.display(), "failed to remove old packet capture file");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl PacketWriter for RotatingPcapWriter {
+    fn write_packet(&mut self, packet: CapturedPacket) -> std::io::Result<()> {
+        if self.should_rotate(&packet) {
+            self.rotate()?;
+        }
+
+        let timestamp = packet.timestamp;
+        self.current.write_packet(packet)?;
+        self.first_timestamp.get_or_insert(timestamp);
+        Ok(())
     }
 }
 
@@ -125,6 +287,167 @@ pub fn packet_capture_channel(
     (sender, ah)
 }
 
+/// Selects which packets are captured.
+///
+/// Empty sets do not restrict the capture, so the default filter captures all packets.
+#[derive(Clone, Debug, PartialEq, smart_default::SmartDefault)]
+pub struct CaptureFilter {
+    /// Directions of the captured packets.
+    pub directions: HashSet<PacketDirection>,
+    /// Kinds of the captured packets.
+    pub kinds: HashSet<PacketKind>,
+    /// Peers whose packets are captured.
+    ///
+    /// The peer is the previous hop of incoming packets and the next hop of outgoing packets.
+    pub peers: HashSet<OffchainPublicKey>,
+    /// Fraction of the packets passing the other criteria that are captured, from `0.0` to `1.0`.
+    #[default(1.0)]
+    pub sample_ratio: f64,
+    /// Also captures the raw wire data of the incoming packets, so that the capture can be replayed.
+    ///
+    /// The wire data are captured before the packets are decoded, so also for the packets that are rejected.
+    /// Their kind is not known at that point, so the [`kinds`](Self::kinds) do not apply to them.
+    pub wire: bool,
+}
+
+impl CaptureFilter {
+    /// Checks whether a packet is captured.
+    ///
+    /// Packets of unknown `kind` (the wire data of incoming packets) pass the kind criterion.
+    /// When sampling is used, the result is random for packets passing the other criteria.
+    pub fn matches(&self, direction: PacketDirection, kind: Option<PacketKind>, peer: &OffchainPublicKey) -> bool {
+        self.matches_unsampled(direction, kind, peer) && self.sample()
+    }
+
+    fn matches_unsampled(
+        &self,
+        direction: PacketDirection,
+        kind: Option<PacketKind>,
+        peer: &OffchainPublicKey,
+    ) -> bool {
+        (self.directions.is_empty() || self.directions.contains(&direction))
+            && (self.kinds.is_empty() || kind.is_none_or(|kind| self.kinds.contains(&kind)))
+            && (self.peers.is_empty() || self.peers.contains(peer))
+    }
+
+    fn sample(&self) -> bool {
+        self.sample_ratio >= 1.0 || random_float() < self.sample_ratio
+    }
+}
+
+struct ActiveCapture {
+    filter: CaptureFilter,
+    sender: crossfire::MAsyncTx<crossfire::mpsc::Array<CapturedPacket>>,
+}
+
+/// Controls packet capture of the [`CapturePacketCodec`] instances it was given to.
+///
+/// Capture can be started and stopped at any time, the clones share the same state.
+#[derive(Clone, Default)]
+pub struct PacketCapture(Arc<parking_lot::RwLock<Option<ActiveCapture>>>);
+
+impl PacketCapture {
+    /// Starts capturing packets that pass the `filter` into the `writer`.
+    ///
+    /// Replaces the capture that was previously started, the packets it has already queued are still written.
+    pub fn start(&self, writer: Box<dyn PacketWriter + Send>, filter: CaptureFilter) {
+        // The capture task terminates once all the packets are written and the sender is dropped
+        let (sender, _) = packet_capture_channel(writer);
+        *self.0.write() = Some(ActiveCapture { filter, sender });
+    }
+
+    /// Stops the capture, returning `false` if it was not started.
+    pub fn stop(&self) -> bool {
+        self.0.write().take().is_some()
+    }
+
+    /// Indicates whether packets are being captured.
+    ///
+    /// This is `false` also when the capture stopped due to a [`PacketWriter`] error.
+    pub fn is_active(&self) -> bool {
+        self.0
+            .read()
+            .as_ref()
+            .is_some_and(|active| !active.sender.is_disconnected())
+    }
+
+    /// Returns the filter of the running capture.
+    pub fn filter(&self) -> Option<CaptureFilter> {
+        self.0.read().as_ref().map(|active| active.filter.clone())
+    }
+
+    fn capture<'a>(
+        &self,
+        direction: PacketDirection,
+        kind: PacketKind,
+        peer: &OffchainPublicKey,
+        packet: impl FnOnce() -> PacketBeforeTransit<'a>,
+    ) {
+        if let Some(active) = self.0.read().as_ref()
+            && active.filter.matches(direction, Some(kind), peer)
+            && let Err(error) = active.sender.try_send(packet().into())
+        {
+            tracing::debug!(%error, %direction, %kind, "failed to send packet to capture");
+        }
+    }
+
+    /// Captures the wire data of an incoming packet before it is decoded, if the filter asks for it.
+    ///
+    /// Returns whether the packet was sampled, so that its decoded record follows the wire data.
+    fn capture_incoming_wire(&self, me: &OffchainPublicKey, sender: &PeerId, data: &[u8]) -> bool {
+        let active = self.0.read();
+        let Some(active) = active.as_ref().filter(|active| active.filter.wire) else {
+            return false;
+        };
+        let Ok(previous_hop) = crate::peer_id_to_public_key(sender) else {
+            return false;
+        };
+        if !active.filter.matches(PacketDirection::Incoming, None, &previous_hop) {
+            return false;
+        }
+
+        let wire = PacketBeforeTransit::IncomingWire {
+            me: *me,
+            previous_hop,
+            kind: None,
+            data,
+        };
+        if let Err(error) = active.sender.try_send(wire.into()) {
+            tracing::debug!(%error, "failed to send incoming wire data to capture");
+        }
+        true
+    }
+
+    /// Captures a decoded incoming packet.
+    ///
+    /// When the wire data are captured, the packet is captured only if its wire data were sampled.
+    fn capture_incoming<'a>(
+        &self,
+        kind: PacketKind,
+        previous_hop: &OffchainPublicKey,
+        wire_sampled: bool,
+        packet: impl FnOnce() -> PacketBeforeTransit<'a>,
+    ) {
+        if let Some(active) = self.0.read().as_ref()
+            && active
+                .filter
+                .matches_unsampled(PacketDirection::Incoming, Some(kind), previous_hop)
+            && (if active.filter.wire {
+                wire_sampled
+            } else {
+                active.filter.sample()
+            })
+            && let Err(error) = active.sender.try_send(packet().into())
+        {
+            tracing::debug!(%error, %kind, "failed to send incoming packet to capture");
+        }
+    }
+}
+
+/// Kind of the wire data captured before the packet is decoded.
+const UNKNOWN_PACKET_KIND: u8 = 0xff;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, strum::FromRepr)]
 #[repr(u8)]
 enum PacketType {
     Final = 0,
@@ -132,6 +455,7 @@ enum PacketType {
     Outgoing = 2,
     InAck = 3,
     OutAck = 4,
+    Wire = 5,
 }
 
 /// Represents a customized dissection of a HOPR packet before it goes into the transport.
@@ -156,6 +480,12 @@ enum PacketBeforeTransit<'a> {
         me: OffchainPublicKey,
         packet: &'a IncomingPacket,
     },
+    IncomingWire {
+        me: OffchainPublicKey,
+        previous_hop: OffchainPublicKey,
+        kind: Option<PacketKind>,
+        data: &'a [u8],
+    },
 }
 
 impl<'a> From<PacketBeforeTransit<'a>> for CapturedPacket {
@@ -180,6 +510,8 @@ impl<'a> From<PacketBeforeTransit<'a>> for CapturedPacket {
                 out.extend_from_slice(next_hop.as_ref());
                 out.extend_from_slice(next_hop.to_peerid_str().as_bytes());
                 out.push(0); // Add null terminator to the string
+                // Relayed packets are recorded without the ack challenge
+                out.push(if ack_challenge.is_empty() { 0 } else { 1 });
                 out.extend_from_slice(ack_challenge.as_ref());
                 out.push(ticket.len() as u8);
                 out.extend_from_slice(ticket.as_ref());
@@ -285,6 +617,23 @@ impl<'a> From<PacketBeforeTransit<'a>> for CapturedPacket {
                 out.extend((received_acks.len() as u16).to_be_bytes());
                 received_acks.iter().for_each(|ack| out.extend_from_slice(ack.as_ref()));
             }
+            PacketBeforeTransit::IncomingWire {
+                me,
+                previous_hop,
+                kind,
+                data,
+            } => {
+                out.push(PacketType::Wire as u8);
+                out.push(kind.map_or(UNKNOWN_PACKET_KIND, |kind| kind as u8));
+                out.extend_from_slice(previous_hop.as_ref());
+                out.extend_from_slice(previous_hop.to_peerid_str().as_bytes());
+                out.push(0); // Add null terminator to the string
+                out.extend_from_slice(me.as_ref());
+                out.extend_from_slice(me.to_peerid_str().as_bytes());
+                out.push(0); // Add null terminator to the string
+                out.extend_from_slice((data.len() as u16).to_be_bytes().as_ref());
+                out.extend_from_slice(data);
+            }
         }
 
         Self {
@@ -301,7 +650,7 @@ impl<'a> From<PacketBeforeTransit<'a>> for CapturedPacket {
 pub struct CapturePacketCodec<C> {
     inner: std::sync::Arc<C>,
     packet_key: OffchainPublicKey,
-    sender: crossfire::MAsyncTx<crossfire::mpsc::Array<CapturedPacket>>,
+    capture: PacketCapture,
 }
 
 impl<C> Clone for CapturePacketCodec<C> {
@@ -309,21 +658,17 @@ impl<C> Clone for CapturePacketCodec<C> {
         Self {
             inner: self.inner.clone(),
             packet_key: self.packet_key,
-            sender: self.sender.clone(),
+            capture: self.capture.clone(),
         }
     }
 }
 
 impl<C> CapturePacketCodec<C> {
-    pub fn new(
-        inner: C,
-        packet_key: OffchainPublicKey,
-        sender: crossfire::MAsyncTx<crossfire::mpsc::Array<CapturedPacket>>,
-    ) -> Self {
+    pub fn new(inner: C, packet_key: OffchainPublicKey, capture: PacketCapture) -> Self {
         Self {
             inner: std::sync::Arc::new(inner),
             packet_key,
-            sender,
+            capture,
         }
     }
 }
@@ -340,36 +685,39 @@ impl<C: PacketDecoder + Send + Sync> PacketDecoder for CapturePacketCodec<C> {
     type Error = C::Error;
 
     fn decode(&self, peer: PeerId, data: Bytes) -> Result<IncomingPacket, IncomingPacketError<Self::Error>> {
+        // Capture the wire data before decoding, so that the rejected packets are captured as well
+        let wire_sampled = self.capture.capture_incoming_wire(&self.packet_key, &peer, &data);
         let packet = self.inner.decode(peer, data)?;
 
-        if let Err(error) = self.sender.try_send(
+        let (kind, previous_hop) = match &packet {
+            IncomingPacket::Final(final_packet) => (PacketKind::Final, final_packet.previous_hop),
+            IncomingPacket::Forwarded(fwd_packet) => (PacketKind::Forwarded, fwd_packet.previous_hop),
+            IncomingPacket::Acknowledgement(ack_packet) => (PacketKind::Acknowledgement, ack_packet.previous_hop),
+        };
+
+        self.capture.capture_incoming(kind, &previous_hop, wire_sampled, || {
             PacketBeforeTransit::IncomingPacket {
                 me: self.packet_key,
                 packet: &packet,
             }
-            .into(),
-        ) {
-            tracing::debug!(%error, "failed to send incoming packet to capture");
-        }
+        });
 
         if let IncomingPacket::Forwarded(fwd_packet) = &packet {
             let IncomingForwardedPacket { next_hop, data, .. } = fwd_packet.as_ref();
 
-            if let Err(error) = self.sender.try_send(
-                PacketBeforeTransit::OutgoingPacket {
-                    me: self.packet_key,
-                    next_hop: *next_hop,
-                    num_surbs: 0,
-                    is_forwarded: true,
-                    data: data.as_ref().into(),
-                    ack_challenge: Default::default(),
-                    signals: None.into(),
-                    ticket: inspect_ticket_data_in_packet(data.as_ref()).into(),
-                }
-                .into(),
-            ) {
-                tracing::debug!(%error, "failed to send forwarded packet to capture");
-            }
+            self.capture
+                .capture(PacketDirection::Outgoing, PacketKind::Forwarded, next_hop, || {
+                    PacketBeforeTransit::OutgoingPacket {
+                        me: self.packet_key,
+                        next_hop: *next_hop,
+                        num_surbs: 0,
+                        is_forwarded: true,
+                        data: data.as_ref().into(),
+                        ack_challenge: Default::default(),
+                        signals: None.into(),
+                        ticket: inspect_ticket_data_in_packet(data.as_ref()).into(),
+                    }
+                });
         }
 
         Ok(packet)
@@ -385,26 +733,29 @@ impl<C: PacketEncoder + Send + Sync> PacketEncoder for CapturePacketCodec<C> {
         routing: ResolvedTransportRouting<HoprSurb>,
         signals: S,
     ) -> Result<OutgoingPacket, Self::Error> {
-        let data_clone = data.as_ref().to_vec();
+        // The data are consumed by the encoder, so copy them only when they might be captured
+        let data_clone = self.capture.is_active().then(|| data.as_ref().to_vec());
         let signals = signals.into();
         let num_surbs = routing.count_return_paths() as u8;
 
         let packet = self.inner.encode_packet(data, routing, signals)?;
 
-        if let Err(error) = self.sender.try_send(
-            PacketBeforeTransit::OutgoingPacket {
-                me: self.packet_key,
-                next_hop: packet.next_hop,
-                num_surbs,
-                is_forwarded: false,
-                data: data_clone.into(),
-                ack_challenge: packet.ack_challenge.as_ref().into(),
-                signals,
-                ticket: inspect_ticket_data_in_packet(packet.data.as_ref()).into(),
-            }
-            .into(),
-        ) {
-            tracing::debug!(%error, "failed to send outgoing packet to capture");
+        if let Some(data_clone) = data_clone {
+            self.capture.capture(
+                PacketDirection::Outgoing,
+                PacketKind::Outgoing,
+                &packet.next_hop,
+                || PacketBeforeTransit::OutgoingPacket {
+                    me: self.packet_key,
+                    next_hop: packet.next_hop,
+                    num_surbs,
+                    is_forwarded: false,
+                    data: data_clone.into(),
+                    ack_challenge: packet.ack_challenge.as_ref().into(),
+                    signals,
+                    ticket: inspect_ticket_data_in_packet(packet.data.as_ref()).into(),
+                },
+            );
         }
 
         Ok(packet)
@@ -417,17 +768,17 @@ impl<C: PacketEncoder + Send + Sync> PacketEncoder for CapturePacketCodec<C> {
     ) -> Result<OutgoingPacket, Self::Error> {
         let packet_ack = self.inner.encode_acknowledgements(acks, destination)?;
 
-        if let Err(error) = self.sender.try_send(
-            PacketBeforeTransit::OutgoingAck {
+        self.capture.capture(
+            PacketDirection::Outgoing,
+            PacketKind::Acknowledgement,
+            destination,
+            || PacketBeforeTransit::OutgoingAck {
                 me: self.packet_key,
                 is_random: false,
                 next_hop: *destination,
                 acks: acks.to_vec(),
-            }
-            .into(),
-        ) {
-            tracing::debug!(%error, "failed to send acknowledgement packet to capture");
-        }
+            },
+        );
 
         Ok(packet_ack)
     }
@@ -590,7 +941,7 @@ mod tests {
                 packet_tag: hopr_api::types::crypto_random::random_bytes(),
                 previous_hop: *OffchainKeypair::random().public(),
                 next_hop: *OffchainKeypair::random().public(),
-                data: Box::new([0x08]),
+                data: Bytes::from_static(&[0x08]),
                 ack_challenge: HalfKeyChallenge::default(),
                 received_ticket: ticket.into_unacknowledged(HalfKey::random()),
                 ack_key_prev_hop: HalfKey::random(),
@@ -672,7 +1023,10 @@ mod tests {
                 challenge: 0x01234567_89abcdef,
                 target: SessionTarget::UdpStream(SealedHost::Plain("some-dns-name.com:1234".parse()?)),
                 capabilities: (Capability::Segmentation | Capability::NoRateControl).into(),
+                optional_capabilities: None,
+                session_protocol_version: None,
                 additional_data: 0x12345678,
+                key_share: None,
             }))?
             .to_bytes()
             .into_vec()
@@ -720,7 +1074,11 @@ mod tests {
                 ByteCapabilities,
             >::SessionEstablished(StartEstablished {
                 orig_challenge: 0x01234567_89abcdef,
-                session_id: SessionId::new(1234u64, HoprPseudonym::random()),
+                session_id: HoprPseudonym::random(),
+                capabilities: None,
+                session_protocol_version: None,
+                resume_ticket: None,
+                key_confirmation: None,
             }))?
             .to_bytes()
             .into_vec()
@@ -743,8 +1101,8 @@ mod tests {
                 SessionTarget,
                 ByteCapabilities,
             >::KeepAlive(KeepAliveMessage {
-                session_id: SessionId::new(1234u64, HoprPseudonym::random()),
-                flags: 0xff,
+                session_id: HoprPseudonym::random(),
+                flags: hopr_protocol_start::KeepAliveFlags::new_truncated(0xff),
                 additional_data: 0xffffffff,
             }))?
             .to_bytes()
@@ -803,4 +1161,281 @@ mod tests {
         ah.abort();
         Ok(())
     }
+
+    fn test_packet(timestamp_ms: u64, len: usize) -> CapturedPacket {
+        CapturedPacket {
+            direction: PacketDirection::Incoming,
+            timestamp: Duration::from_millis(timestamp_ms),
+            orig_len: len as u32,
+            data: vec![0xaa; len].into_boxed_slice(),
+        }
+    }
+
+    fn capture_dir(name: &str) -> anyhow::Result<PathBuf> {
+        let dir = std::env::temp_dir().join(format!("hopr-capture-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    #[derive(Clone, Default)]
+    struct CollectingWriter(Arc<parking_lot::Mutex<Vec<CapturedPacket>>>);
+
+    impl PacketWriter for CollectingWriter {
+        fn write_packet(&mut self, packet: CapturedPacket) -> std::io::Result<()> {
+            self.0.lock().push(packet);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn capture_filter_should_match_by_direction_kind_and_peer() {
+        let peer_1 = *OffchainKeypair::random().public();
+        let peer_2 = *OffchainKeypair::random().public();
+
+        let filter = CaptureFilter::default();
+        assert!(filter.matches(PacketDirection::Incoming, Some(PacketKind::Final), &peer_1));
+        assert!(filter.matches(PacketDirection::Outgoing, Some(PacketKind::Acknowledgement), &peer_2));
+
+        let filter = CaptureFilter {
+            directions: [PacketDirection::Incoming].into(),
+            kinds: [PacketKind::Final, PacketKind::Forwarded].into(),
+            peers: [peer_1].into(),
+            ..Default::default()
+        };
+        assert!(filter.matches(PacketDirection::Incoming, Some(PacketKind::Final), &peer_1));
+        assert!(filter.matches(PacketDirection::Incoming, Some(PacketKind::Forwarded), &peer_1));
+        assert!(!filter.matches(PacketDirection::Outgoing, Some(PacketKind::Forwarded), &peer_1));
+        assert!(!filter.matches(PacketDirection::Incoming, Some(PacketKind::Acknowledgement), &peer_1));
+        assert!(!filter.matches(PacketDirection::Incoming, Some(PacketKind::Final), &peer_2));
+
+        // The kind of the wire data is not known
+        assert!(filter.matches(PacketDirection::Incoming, None, &peer_1));
+        assert!(!filter.matches(PacketDirection::Incoming, None, &peer_2));
+    }
+
+    #[test]
+    fn capture_filter_should_sample_packets() {
+        let peer = *OffchainKeypair::random().public();
+
+        let filter = CaptureFilter {
+            sample_ratio: 0.0,
+            ..Default::default()
+        };
+        assert!((0..1000).all(|_| !filter.matches(PacketDirection::Incoming, Some(PacketKind::Final), &peer)));
+
+        let filter = CaptureFilter {
+            sample_ratio: 0.25,
+            ..Default::default()
+        };
+        let sampled = (0..10_000)
+            .filter(|_| filter.matches(PacketDirection::Incoming, Some(PacketKind::Final), &peer))
+            .count();
+        assert!((2000..3000).contains(&sampled), "sampled {sampled} out of 10000");
+    }
+
+    #[test]
+    fn rotating_writer_should_rotate_by_size_and_keep_most_recent_files() -> anyhow::Result<()> {
+        let dir = capture_dir("size")?;
+        let mut writer = RotatingPcapWriter::new(
+            dir.join("capture"),
+            RotationPolicy {
+                max_file_size: Some(1000),
+                max_file_age: None,
+                max_files: 3,
+            },
+        )?;
+
+        for i in 0..20 {
+            writer.write_packet(test_packet(i, 400))?;
+        }
+
+        let kept = writer.files().map(|p| p.to_path_buf()).collect::<Vec<_>>();
+        assert_eq!(3, kept.len());
+        assert!(kept.iter().all(|p| p.exists()));
+        assert!(kept.last().is_some_and(|p| p.ends_with("capture.000006.pcapng")));
+        assert!(!dir.join("capture.000003.pcapng").exists());
+
+        let mut on_disk = std::fs::read_dir(&dir)?
+            .map(|e| e.map(|e| e.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        on_disk.sort();
+        assert_eq!(kept, on_disk);
+
+        // All rotated files reached the size limit without exceeding it by more than one packet
+        for path in &kept[..2] {
+            let len = std::fs::metadata(path)?.len();
+            assert!((1000..1500).contains(&len), "file {} has {len} bytes", path.display());
+        }
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn rotating_writer_should_rotate_by_packet_time_span() -> anyhow::Result<()> {
+        let dir = capture_dir("age")?;
+        let mut writer = RotatingPcapWriter::new(
+            dir.join("capture"),
+            RotationPolicy {
+                max_file_size: None,
+                max_file_age: Some(Duration::from_secs(1)),
+                max_files: 10,
+            },
+        )?;
+
+        for ts in [0, 500, 999, 1000, 1500, 2100, 2200] {
+            writer.write_packet(test_packet(ts, 10))?;
+        }
+
+        let read_timestamps = |path: &std::path::Path| -> anyhow::Result<Vec<Duration>> {
+            let mut reader = pcap_file::pcapng::PcapNgReader::new(File::open(path)?)?;
+            let mut timestamps = Vec::new();
+            while let Some(block) = reader.next_block() {
+                if let pcap_file::pcapng::Block::EnhancedPacket(packet) = block? {
+                    timestamps.push(packet.timestamp);
+                }
+            }
+            Ok(timestamps)
+        };
+
+        let files = writer.files().map(|p| p.to_path_buf()).collect::<Vec<_>>();
+        assert_eq!(3, files.len());
+        assert_eq!(
+            vec![0, 500, 999],
+            read_timestamps(&files[0])?
+                .into_iter()
+                .map(|d| d.as_millis())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![1000, 1500],
+            read_timestamps(&files[1])?
+                .into_iter()
+                .map(|d| d.as_millis())
+                .collect::<Vec<_>>()
+        );
+
+        drop(writer);
+        assert_eq!(
+            vec![2100, 2200],
+            read_timestamps(&files[2])?
+                .into_iter()
+                .map(|d| d.as_millis())
+                .collect::<Vec<_>>()
+        );
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn packet_capture_should_be_started_and_stopped_at_runtime() -> anyhow::Result<()> {
+        let me = *OffchainKeypair::random().public();
+        let peer = *OffchainKeypair::random().public();
+
+        let packet = IncomingPacket::Final(
+            IncomingFinalPacket {
+                packet_tag: hopr_api::types::crypto_random::random_bytes(),
+                previous_hop: peer,
+                sender: SimplePseudonym::random(),
+                plain_text: ApplicationData::new(10u64, &hex!("deadbeef"))?.to_bytes(),
+                ack_key: HalfKey::random(),
+                info: Default::default(),
+            }
+            .into(),
+        );
+        let capture_all = |capture: &PacketCapture| {
+            for kind in [PacketKind::Final, PacketKind::Acknowledgement] {
+                capture.capture(PacketDirection::Incoming, kind, &peer, || {
+                    PacketBeforeTransit::IncomingPacket { me, packet: &packet }
+                });
+            }
+        };
+
+        let capture = PacketCapture::default();
+        assert!(!capture.is_active());
+        capture_all(&capture);
+
+        let writer = CollectingWriter::default();
+        capture.start(
+            Box::new(writer.clone()),
+            CaptureFilter {
+                kinds: [PacketKind::Final].into(),
+                ..Default::default()
+            },
+        );
+        assert!(capture.is_active());
+        assert_eq!(
+            Some([PacketKind::Final].into()),
+            capture.filter().map(|filter| filter.kinds)
+        );
+
+        capture_all(&capture);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(1, writer.0.lock().len());
+
+        assert!(capture.stop());
+        assert!(!capture.stop());
+        assert!(!capture.is_active());
+
+        capture_all(&capture);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(1, writer.0.lock().len());
+
+        capture.start(Box::new(NullWriter), CaptureFilter::default());
+        capture_all(&capture);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!capture.is_active(), "capture must stop on writer error");
+
+        Ok(())
+    }
+
+    struct RejectingDecoder;
+
+    impl PacketDecoder for RejectingDecoder {
+        type Error = std::io::Error;
+
+        fn decode(&self, _: PeerId, _: Bytes) -> Result<IncomingPacket, IncomingPacketError<Self::Error>> {
+            Err(IncomingPacketError::Undecodable(std::io::Error::other("rejected")))
+        }
+    }
+
+    #[tokio::test]
+    async fn codec_should_capture_wire_data_of_rejected_packets() -> anyhow::Result<()> {
+        let me = *OffchainKeypair::random().public();
+        let peer = *OffchainKeypair::random().public();
+
+        let writer = CollectingWriter::default();
+        let capture = PacketCapture::default();
+        capture.start(
+            Box::new(writer.clone()),
+            CaptureFilter {
+                kinds: [PacketKind::Final].into(),
+                wire: true,
+                ..Default::default()
+            },
+        );
+
+        let codec = CapturePacketCodec::new(RejectingDecoder, me, capture);
+        assert!(
+            codec
+                .decode(PeerId::from(peer), Bytes::from_static(&hex!("deadbeef")))
+                .is_err()
+        );
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let captured = writer.0.lock().clone();
+        assert_eq!(1, captured.len());
+        assert_eq!(
+            CapturedPacketContent::Wire {
+                kind: None,
+                previous_hop: peer,
+                me,
+                data: hex!("deadbeef").into(),
+            },
+            DecodedPacket::try_from(&captured[0])?.content
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,1129 @@
+//! Offline decoder of the packet captures.
+//!
+//! Reads the pcapng files written by the [`PcapPacketWriter`](super::PcapPacketWriter) or
+//! the [`RotatingPcapWriter`](super::RotatingPcapWriter) and dissects each captured record
+//! into a [`DecodedPacket`].
+//!
+//! The payloads of the final and outgoing packets are captured after they have been
+//! decrypted (or before they are encrypted) by the capturing node, so they are decoded
+//! into [`ApplicationData`] and further into the Start, Session or probing protocol messages
+//! based on their [`Tag`]. The data of relayed packets remains onion-encrypted for the next hop,
+//! and the data of encrypted Sessions remains encrypted by the Session key, which never leaves the node.
+//! Such data are [opaque](DecodedPayload::Opaque).
+//!
+//! The [wire data](CapturedPacketContent::Wire) of the incoming packets can be
+//! [decrypted](DecodedPacket::decrypt_wire) given the packet key of the capturing node.
+
+use std::{io::Read, time::Duration};
+
+use hopr_api::types::{
+    crypto::{
+        keypairs::{Keypair, OffchainKeypair},
+        prelude::HalfKeyChallenge,
+        types::{HalfKey, OffchainPublicKey, PacketTag},
+    },
+    internal::prelude::{Acknowledgement, HoprPseudonym, Ticket},
+    primitive::prelude::{BytesRepresentable, KeyIdMapping, ToHex, to_hex_shortened},
+};
+use hopr_crypto_packet::prelude::{HoprKeyIdent, HoprPacket, PacketSignals};
+use hopr_protocol_app::prelude::{ApplicationData, ReservedTag, Tag};
+use hopr_transport_session::{HoprStartProtocol, SESSION_APPLICATION_TAG};
+use pcap_file::pcapng::{Block, PcapNgReader};
+
+use super::{CapturedPacket, PacketDirection, PacketKind, PacketType, UNKNOWN_PACKET_KIND};
+
+/// Session protocol message carried in a single HOPR packet.
+pub type CapturedSessionMessage = hopr_protocol_session::types::SessionMessage<{ ApplicationData::PAYLOAD_SIZE }>;
+
+/// Errors raised when reading a packet capture.
+#[derive(Debug, thiserror::Error)]
+pub enum CaptureReadError {
+    #[error("pcapng error: {0}")]
+    Pcap(#[from] pcap_file::PcapError),
+
+    #[error("unknown captured packet type {0}")]
+    UnknownPacketType(u8),
+
+    #[error("malformed captured packet: {0}")]
+    Malformed(&'static str),
+
+    #[error("cannot decrypt wire data: {0}")]
+    Undecryptable(String),
+}
+
+/// Payload of a captured packet, decoded as far as possible.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodedPayload {
+    /// Start protocol message.
+    Start(HoprStartProtocol),
+    /// Session protocol message.
+    Session(CapturedSessionMessage),
+    /// Network probing message.
+    Probe(hopr_transport_probe::content::Message),
+    /// Data of an application [`Tag`] or of a reserved tag that failed to decode.
+    Application(ApplicationData),
+    /// Data that cannot be decoded, such as the data of relayed packets.
+    Opaque(Box<[u8]>),
+}
+
+impl DecodedPayload {
+    /// Decodes serialized [`ApplicationData`] into the message of the protocol given by its [`Tag`].
+    pub fn decode(data: &[u8]) -> Self {
+        let Ok(app_data) = ApplicationData::try_from(data) else {
+            return Self::Opaque(data.into());
+        };
+
+        let tag = app_data.application_tag;
+        let decoded = if tag == HoprStartProtocol::START_PROTOCOL_MESSAGE_TAG {
+            HoprStartProtocol::decode(tag, &app_data.plain_text)
+                .ok()
+                .map(Self::Start)
+        } else if tag == SESSION_APPLICATION_TAG {
+            CapturedSessionMessage::try_from(app_data.plain_text.as_ref())
+                .ok()
+                .map(Self::Session)
+        } else if tag == Tag::from(ReservedTag::Ping) {
+            hopr_transport_probe::content::Message::try_from(app_data.plain_text.as_ref())
+                .ok()
+                .map(Self::Probe)
+        } else {
+            None
+        };
+
+        // Data of encrypted Sessions do not decode and are kept as they are
+        decoded.unwrap_or(Self::Application(app_data))
+    }
+
+    /// Returns the [`Tag`] of the payload, if it could be decoded.
+    pub fn tag(&self) -> Option<Tag> {
+        match self {
+            Self::Start(_) => Some(HoprStartProtocol::START_PROTOCOL_MESSAGE_TAG),
+            Self::Session(_) => Some(SESSION_APPLICATION_TAG),
+            Self::Probe(_) => Some(ReservedTag::Ping.into()),
+            Self::Application(data) => Some(data.application_tag),
+            Self::Opaque(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for DecodedPayload {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Start(msg) => write!(f, "start {msg:?}"),
+            Self::Session(msg) => write!(f, "session {msg}"),
+            Self::Probe(msg) => write!(f, "probe {msg:?}"),
+            Self::Application(data) => write!(f, "application {data}"),
+            Self::Opaque(data) => write!(f, "opaque {} bytes", data.len()),
+        }
+    }
+}
+
+/// Dissection of a captured packet.
+///
+/// Mirrors the records written by the [`CapturePacketCodec`](super::CapturePacketCodec).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CapturedPacketContent {
+    /// Packet delivered to the capturing node.
+    Final {
+        packet_tag: PacketTag,
+        previous_hop: OffchainPublicKey,
+        me: OffchainPublicKey,
+        sender: HoprPseudonym,
+        ack_key: HalfKey,
+        signals: PacketSignals,
+        payload: DecodedPayload,
+    },
+    /// Packet received by the capturing node to be relayed to the `next_hop`.
+    Forwarded {
+        packet_tag: PacketTag,
+        previous_hop: OffchainPublicKey,
+        next_hop: OffchainPublicKey,
+        ack_key: HalfKey,
+        ticket: Option<Ticket>,
+        data: Box<[u8]>,
+    },
+    /// Packet sent by the capturing node, either originating there or being relayed.
+    Outgoing {
+        me: OffchainPublicKey,
+        next_hop: OffchainPublicKey,
+        ack_challenge: Option<HalfKeyChallenge>,
+        ticket: Option<Ticket>,
+        num_surbs: u8,
+        is_forwarded: bool,
+        signals: PacketSignals,
+        payload: DecodedPayload,
+    },
+    /// Acknowledgements received by the capturing node.
+    IncomingAck {
+        packet_tag: PacketTag,
+        previous_hop: OffchainPublicKey,
+        me: OffchainPublicKey,
+        acks: Vec<Acknowledgement>,
+    },
+    /// Acknowledgements sent by the capturing node.
+    OutgoingAck {
+        me: OffchainPublicKey,
+        next_hop: OffchainPublicKey,
+        is_random: bool,
+        acks: Vec<Acknowledgement>,
+    },
+    /// Wire data of an incoming packet, recorded before the packet is decoded.
+    ///
+    /// The `kind` is unknown for the packets captured before decoding, which include the rejected packets.
+    Wire {
+        kind: Option<PacketKind>,
+        previous_hop: OffchainPublicKey,
+        me: OffchainPublicKey,
+        data: Box<[u8]>,
+    },
+}
+
+/// Packet read from a capture file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedPacket {
+    /// Time since the UNIX epoch when the packet was captured.
+    pub timestamp: Duration,
+    pub content: CapturedPacketContent,
+}
+
+impl DecodedPacket {
+    /// Direction of the packet, as seen by the capturing node.
+    pub fn direction(&self) -> PacketDirection {
+        match &self.content {
+            CapturedPacketContent::Final { .. }
+            | CapturedPacketContent::Forwarded { .. }
+            | CapturedPacketContent::IncomingAck { .. }
+            | CapturedPacketContent::Wire { .. } => PacketDirection::Incoming,
+            CapturedPacketContent::Outgoing { .. } | CapturedPacketContent::OutgoingAck { .. } => {
+                PacketDirection::Outgoing
+            }
+        }
+    }
+
+    /// Kind of the packet, as used by the [`CaptureFilter`](super::CaptureFilter).
+    ///
+    /// The kind of the [wire data](CapturedPacketContent::Wire) is usually unknown.
+    pub fn kind(&self) -> Option<PacketKind> {
+        match &self.content {
+            CapturedPacketContent::Final { .. } => Some(PacketKind::Final),
+            CapturedPacketContent::Forwarded { .. } => Some(PacketKind::Forwarded),
+            CapturedPacketContent::Outgoing { is_forwarded: true, .. } => Some(PacketKind::Forwarded),
+            CapturedPacketContent::Outgoing { .. } => Some(PacketKind::Outgoing),
+            CapturedPacketContent::IncomingAck { .. } | CapturedPacketContent::OutgoingAck { .. } => {
+                Some(PacketKind::Acknowledgement)
+            }
+            CapturedPacketContent::Wire { kind, .. } => *kind,
+        }
+    }
+
+    /// The capturing node, if recorded.
+    ///
+    /// Records of incoming relayed packets do not contain the capturing node.
+    pub fn me(&self) -> Option<&OffchainPublicKey> {
+        match &self.content {
+            CapturedPacketContent::Final { me, .. }
+            | CapturedPacketContent::Outgoing { me, .. }
+            | CapturedPacketContent::IncomingAck { me, .. }
+            | CapturedPacketContent::OutgoingAck { me, .. }
+            | CapturedPacketContent::Wire { me, .. } => Some(me),
+            CapturedPacketContent::Forwarded { .. } => None,
+        }
+    }
+
+    /// The peer the packet was received from or sent to.
+    pub fn peer(&self) -> &OffchainPublicKey {
+        match &self.content {
+            CapturedPacketContent::Final { previous_hop, .. }
+            | CapturedPacketContent::Forwarded { previous_hop, .. }
+            | CapturedPacketContent::IncomingAck { previous_hop, .. }
+            | CapturedPacketContent::Wire { previous_hop, .. } => previous_hop,
+            CapturedPacketContent::Outgoing { next_hop, .. } | CapturedPacketContent::OutgoingAck { next_hop, .. } => {
+                next_hop
+            }
+        }
+    }
+
+    /// Decodes the packet from its captured representation.
+    pub fn decode(timestamp: Duration, data: &[u8]) -> Result<Self, CaptureReadError> {
+        let mut cursor = RecordCursor(data);
+        let packet_type = cursor.u8("type")?;
+
+        let content =
+            match PacketType::from_repr(packet_type).ok_or(CaptureReadError::UnknownPacketType(packet_type))? {
+                PacketType::Final => {
+                    let packet_tag = cursor.array("packet tag")?;
+                    let previous_hop = cursor.peer("previous hop")?;
+                    let me = cursor.peer("me")?;
+                    let sender = cursor.parse::<HoprPseudonym>(HoprPseudonym::SIZE, "sender")?;
+                    let ack_key = cursor.parse::<HalfKey>(HalfKey::SIZE, "ack key")?;
+                    let signals = PacketSignals::new_truncated(cursor.u8("signals")?);
+                    let payload = DecodedPayload::decode(cursor.sized_data("plain text")?);
+                    CapturedPacketContent::Final {
+                        packet_tag,
+                        previous_hop,
+                        me,
+                        sender,
+                        ack_key,
+                        signals,
+                        payload,
+                    }
+                }
+                PacketType::Forwarded => {
+                    let packet_tag = cursor.array("packet tag")?;
+                    let previous_hop = cursor.peer("previous hop")?;
+                    let next_hop = cursor.peer("next hop")?;
+                    let ack_key = cursor.parse::<HalfKey>(HalfKey::SIZE, "ack key")?;
+                    let ticket = cursor.ticket()?;
+                    let data = cursor.sized_data("data")?.into();
+                    CapturedPacketContent::Forwarded {
+                        packet_tag,
+                        previous_hop,
+                        next_hop,
+                        ack_key,
+                        ticket,
+                        data,
+                    }
+                }
+                PacketType::Outgoing => {
+                    let me = cursor.peer("me")?;
+                    let next_hop = cursor.peer("next hop")?;
+                    // The ack challenge is not recorded for relayed packets
+                    let ack_challenge = match cursor.u8("ack challenge presence")? {
+                        0 => None,
+                        1 => Some(cursor.parse::<HalfKeyChallenge>(HalfKeyChallenge::SIZE, "ack challenge")?),
+                        _ => return Err(CaptureReadError::Malformed("ack challenge presence")),
+                    };
+                    let ticket = cursor.ticket()?;
+                    let num_surbs = cursor.u8("num surbs")?;
+                    let is_forwarded = match cursor.u8("is forwarded")? {
+                        0 => false,
+                        1 => true,
+                        _ => return Err(CaptureReadError::Malformed("is forwarded")),
+                    };
+                    let signals = PacketSignals::new_truncated(cursor.u8("signals")?);
+                    let data = cursor.sized_data("data")?;
+
+                    CapturedPacketContent::Outgoing {
+                        me,
+                        next_hop,
+                        ack_challenge,
+                        ticket,
+                        num_surbs,
+                        is_forwarded,
+                        signals,
+                        payload: if is_forwarded {
+                            DecodedPayload::Opaque(data.into())
+                        } else {
+                            DecodedPayload::decode(data)
+                        },
+                    }
+                }
+                PacketType::InAck => {
+                    let packet_tag = cursor.array("packet tag")?;
+                    let previous_hop = cursor.peer("previous hop")?;
+                    let me = cursor.peer("me")?;
+                    let acks = cursor.acks()?;
+                    CapturedPacketContent::IncomingAck {
+                        packet_tag,
+                        previous_hop,
+                        me,
+                        acks,
+                    }
+                }
+                PacketType::OutAck => {
+                    let me = cursor.peer("me")?;
+                    let next_hop = cursor.peer("next hop")?;
+                    let is_random = cursor.u8("is random")? != 0;
+                    let acks = cursor.acks()?;
+                    CapturedPacketContent::OutgoingAck {
+                        me,
+                        next_hop,
+                        is_random,
+                        acks,
+                    }
+                }
+                PacketType::Wire => {
+                    let kind = match cursor.u8("kind")? {
+                        UNKNOWN_PACKET_KIND => None,
+                        kind => Some(PacketKind::from_repr(kind).ok_or(CaptureReadError::Malformed("kind"))?),
+                    };
+                    let previous_hop = cursor.peer("previous hop")?;
+                    let me = cursor.peer("me")?;
+                    let data = cursor.sized_data("wire data")?.into();
+                    CapturedPacketContent::Wire {
+                        kind,
+                        previous_hop,
+                        me,
+                        data,
+                    }
+                }
+            };
+
+        if !cursor.0.is_empty() {
+            return Err(CaptureReadError::Malformed("trailing data"));
+        }
+
+        Ok(Self { timestamp, content })
+    }
+
+    /// Decrypts the [wire data](CapturedPacketContent::Wire) of a packet received by the capturing node,
+    /// using the packet key of that node.
+    ///
+    /// Packets delivered to the node are decrypted into [`CapturedPacketContent::Final`] or
+    /// [`CapturedPacketContent::IncomingAck`]. Relayed packets cannot be decrypted offline, because their
+    /// next hops are given by the key IDs known only to the node, and neither can the replies to the node,
+    /// because their reply openers never leave it. Packets that are not wire data are returned unchanged.
+    pub fn decrypt_wire(self, packet_key: &OffchainKeypair) -> Result<Self, CaptureReadError> {
+        let CapturedPacketContent::Wire {
+            previous_hop, me, data, ..
+        } = &self.content
+        else {
+            return Ok(self);
+        };
+
+        if me != packet_key.public() {
+            return Err(CaptureReadError::Undecryptable(format!(
+                "captured by {}, not by the given node",
+                me.to_peerid_str()
+            )));
+        }
+
+        let incoming = match HoprPacket::from_incoming(data, packet_key, *previous_hop, &UnknownKeyIds, |_| None) {
+            Ok(HoprPacket::Final(incoming)) => incoming,
+            Ok(_) => return Err(CaptureReadError::Undecryptable("not an incoming packet".into())),
+            Err(error) => return Err(CaptureReadError::Undecryptable(error.to_string())),
+        };
+
+        // Packets without an acknowledgement carry the acknowledgements sent by the previous hop
+        let content = match incoming.ack_key {
+            Some(ack_key) => CapturedPacketContent::Final {
+                packet_tag: incoming.packet_tag,
+                previous_hop: *previous_hop,
+                me: *me,
+                sender: incoming.sender,
+                ack_key,
+                signals: incoming.signals,
+                payload: DecodedPayload::decode(&incoming.plain_text),
+            },
+            None => CapturedPacketContent::IncomingAck {
+                packet_tag: incoming.packet_tag,
+                previous_hop: *previous_hop,
+                me: *me,
+                acks: RecordCursor(&incoming.plain_text).acks()?,
+            },
+        };
+
+        Ok(Self {
+            timestamp: self.timestamp,
+            content,
+        })
+    }
+}
+
+/// Key ID mapper of a node whose channel graph is unknown, as is the case when reading a capture.
+struct UnknownKeyIds;
+
+impl KeyIdMapping<HoprKeyIdent, OffchainPublicKey> for UnknownKeyIds {
+    fn map_key_to_id(&self, _: &OffchainPublicKey) -> Option<HoprKeyIdent> {
+        None
+    }
+
+    fn map_id_to_public(&self, _: &HoprKeyIdent) -> Option<OffchainPublicKey> {
+        None
+    }
+}
+
+impl TryFrom<&CapturedPacket> for DecodedPacket {
+    type Error = CaptureReadError;
+
+    fn try_from(value: &CapturedPacket) -> Result<Self, Self::Error> {
+        Self::decode(value.timestamp, &value.data)
+    }
+}
+
+impl std::fmt::Display for DecodedPacket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}.{:09} {} {}",
+            self.timestamp.as_secs(),
+            self.timestamp.subsec_nanos(),
+            self.direction(),
+            self.kind().map_or_else(|| "unknown".into(), |kind| kind.to_string())
+        )?;
+
+        match &self.content {
+            CapturedPacketContent::Final {
+                packet_tag,
+                previous_hop,
+                me,
+                sender,
+                ack_key,
+                signals,
+                payload,
+            } => write!(
+                f,
+                " {} -> {} tag 0x{} sender {sender} ack key {} signals {signals:?}: {payload}",
+                previous_hop.to_peerid_str(),
+                me.to_peerid_str(),
+                to_hex_shortened::<32>(packet_tag),
+                ack_key.to_hex(),
+            ),
+            CapturedPacketContent::Forwarded {
+                packet_tag,
+                previous_hop,
+                next_hop,
+                ack_key,
+                ticket,
+                data,
+            } => {
+                write!(
+                    f,
+                    " {} -> {} tag 0x{} ack key {}",
+                    previous_hop.to_peerid_str(),
+                    next_hop.to_peerid_str(),
+                    to_hex_shortened::<32>(packet_tag),
+                    ack_key.to_hex(),
+                )?;
+                if let Some(ticket) = ticket {
+                    write!(f, " {ticket}")?;
+                }
+                write!(f, ": {} bytes", data.len())
+            }
+            CapturedPacketContent::Outgoing {
+                me,
+                next_hop,
+                ack_challenge,
+                ticket,
+                num_surbs,
+                signals,
+                payload,
+                ..
+            } => {
+                write!(f, " {} -> {}", me.to_peerid_str(), next_hop.to_peerid_str())?;
+                if let Some(ack_challenge) = ack_challenge {
+                    write!(f, " ack challenge {}", ack_challenge.to_hex())?;
+                }
+                if let Some(ticket) = ticket {
+                    write!(f, " {ticket}")?;
+                }
+                write!(f, " surbs {num_surbs} signals {signals:?}: {payload}")
+            }
+            CapturedPacketContent::IncomingAck {
+                packet_tag,
+                previous_hop,
+                me,
+                acks,
+            } => write!(
+                f,
+                " {} -> {} tag 0x{}: {} acknowledgements",
+                previous_hop.to_peerid_str(),
+                me.to_peerid_str(),
+                to_hex_shortened::<32>(packet_tag),
+                acks.len()
+            ),
+            CapturedPacketContent::OutgoingAck {
+                me,
+                next_hop,
+                is_random,
+                acks,
+            } => write!(
+                f,
+                " {} -> {}: {} {}acknowledgements",
+                me.to_peerid_str(),
+                next_hop.to_peerid_str(),
+                acks.len(),
+                if *is_random { "random " } else { "" }
+            ),
+            CapturedPacketContent::Wire {
+                previous_hop, me, data, ..
+            } => write!(
+                f,
+                " {} -> {}: {} wire bytes",
+                previous_hop.to_peerid_str(),
+                me.to_peerid_str(),
+                data.len()
+            ),
+        }
+    }
+}
+
+/// Reads the fields of a single captured record.
+struct RecordCursor<'a>(&'a [u8]);
+
+impl<'a> RecordCursor<'a> {
+    fn take(&mut self, len: usize, field: &'static str) -> Result<&'a [u8], CaptureReadError> {
+        if self.0.len() < len {
+            return Err(CaptureReadError::Malformed(field));
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn u8(&mut self, field: &'static str) -> Result<u8, CaptureReadError> {
+        Ok(self.take(1, field)?[0])
+    }
+
+    fn u16(&mut self, field: &'static str) -> Result<u16, CaptureReadError> {
+        let bytes = self.take(2, field)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn array<const N: usize>(&mut self, field: &'static str) -> Result<[u8; N], CaptureReadError> {
+        self.take(N, field)?
+            .try_into()
+            .map_err(|_| CaptureReadError::Malformed(field))
+    }
+
+    fn parse<T: for<'b> TryFrom<&'b [u8]>>(&mut self, len: usize, field: &'static str) -> Result<T, CaptureReadError> {
+        T::try_from(self.take(len, field)?).map_err(|_| CaptureReadError::Malformed(field))
+    }
+
+    /// Reads the public key of a peer followed by its NUL-terminated peer ID string.
+    fn peer(&mut self, field: &'static str) -> Result<OffchainPublicKey, CaptureReadError> {
+        let key = self.parse::<OffchainPublicKey>(OffchainPublicKey::SIZE, field)?;
+        let terminator = self
+            .0
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or(CaptureReadError::Malformed(field))?;
+        self.take(terminator + 1, field)?;
+        Ok(key)
+    }
+
+    /// Reads the length-prefixed ticket, which is not recorded or not decodable in some packets.
+    fn ticket(&mut self) -> Result<Option<Ticket>, CaptureReadError> {
+        let len = self.u8("ticket length")? as usize;
+        Ok(Ticket::try_from(self.take(len, "ticket")?).ok())
+    }
+
+    fn sized_data(&mut self, field: &'static str) -> Result<&'a [u8], CaptureReadError> {
+        let len = self.u16(field)? as usize;
+        self.take(len, field)
+    }
+
+    fn acks(&mut self) -> Result<Vec<Acknowledgement>, CaptureReadError> {
+        let count = self.u16("acknowledgement count")? as usize;
+        (0..count)
+            .map(|_| self.parse::<Acknowledgement>(Acknowledgement::SIZE, "acknowledgement"))
+            .collect()
+    }
+}
+
+/// Reads the packets from a packet capture.
+///
+/// Blocks other than the captured packets are skipped.
+/// Once the capture cannot be read further (e.g. when it is truncated), the reader
+/// yields the error and then no more items.
+pub struct CaptureReader<R: Read> {
+    reader: PcapNgReader<R>,
+    done: bool,
+}
+
+impl<R: Read> CaptureReader<R> {
+    /// Creates the reader, reading the pcapng header.
+    pub fn new(reader: R) -> Result<Self, CaptureReadError> {
+        Ok(Self {
+            reader: PcapNgReader::new(reader)?,
+            done: false,
+        })
+    }
+}
+
+impl<R: Read> Iterator for CaptureReader<R> {
+    type Item = Result<DecodedPacket, CaptureReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            match self.reader.next_block() {
+                Some(Ok(Block::EnhancedPacket(packet))) => {
+                    return Some(DecodedPacket::decode(packet.timestamp, &packet.data));
+                }
+                Some(Ok(_)) => continue,
+                Some(Err(error)) => {
+                    // The reader cannot recover from pcapng errors and would keep repeating them
+                    self.done = true;
+                    return Some(Err(error.into()));
+                }
+                None => self.done = true,
+            }
+        }
+        None
+    }
+}
+
+impl<R: Read> std::iter::FusedIterator for CaptureReader<R> {}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use hex_literal::hex;
+    use hopr_api::types::{
+        crypto::prelude::{ChainKeypair, Keypair, OffchainKeypair},
+        crypto_random::Randomizable,
+        internal::prelude::{
+            TicketBuilder, TransportPath, VerifiedAcknowledgement, VerifiedTicket, WinningProbability,
+        },
+        primitive::{prelude::Address, primitives::EthereumChallenge, traits::BytesEncodable},
+    };
+    use hopr_crypto_packet::prelude::{PacketRouting, PacketSignal};
+    use hopr_protocol_hopr::{
+        IncomingAcknowledgementPacket, IncomingFinalPacket, IncomingForwardedPacket, IncomingPacket,
+    };
+    use hopr_protocol_session::types::{Segment, SeqIndicator};
+    use hopr_protocol_start::{StartErrorReason, StartErrorType};
+
+    use super::*;
+    use crate::capture::{PacketBeforeTransit, PacketWriter, PcapPacketWriter};
+
+    /// Key ID mapper knowing only the given keys.
+    struct KnownKeyIds(Vec<OffchainPublicKey>);
+
+    impl KeyIdMapping<HoprKeyIdent, OffchainPublicKey> for KnownKeyIds {
+        fn map_key_to_id(&self, key: &OffchainPublicKey) -> Option<HoprKeyIdent> {
+            self.0.iter().position(|k| k == key).map(|i| (i as u32).into())
+        }
+
+        fn map_id_to_public(&self, id: &HoprKeyIdent) -> Option<OffchainPublicKey> {
+            (0..self.0.len())
+                .find(|i| HoprKeyIdent::from(*i as u32) == *id)
+                .map(|i| self.0[i])
+        }
+    }
+
+    fn ticket() -> anyhow::Result<VerifiedTicket> {
+        Ok(TicketBuilder::default()
+            .amount(10)
+            .counterparty(Address::new(&[10u8; Address::SIZE]))
+            .eth_challenge(EthereumChallenge::default())
+            .win_prob(WinningProbability::try_from_f64(0.5)?)
+            .channel_epoch(1)
+            .index(10)
+            .build_signed(&ChainKeypair::random(), &Default::default())?)
+    }
+
+    fn decode(packet: PacketBeforeTransit<'_>) -> anyhow::Result<DecodedPacket> {
+        Ok(DecodedPacket::try_from(&CapturedPacket::from(packet))?)
+    }
+
+    #[test]
+    fn decoder_should_decode_final_packet_with_start_message() -> anyhow::Result<()> {
+        let me = *OffchainKeypair::random().public();
+        let previous_hop = *OffchainKeypair::random().public();
+        let start_msg = HoprStartProtocol::SessionError(StartErrorType {
+            challenge: 0x01234567_89abcdef,
+            reason: StartErrorReason::NoSlotsAvailable,
+        });
+        let packet_tag = hopr_api::types::crypto_random::random_bytes();
+        let sender = HoprPseudonym::random();
+        let ack_key = HalfKey::random();
+        let packet = IncomingPacket::Final(
+            IncomingFinalPacket {
+                packet_tag,
+                previous_hop,
+                sender,
+                plain_text: ApplicationData::try_from(start_msg.clone())?.to_bytes(),
+                ack_key,
+                info: Default::default(),
+            }
+            .into(),
+        );
+
+        let decoded = decode(PacketBeforeTransit::IncomingPacket { me, packet: &packet })?;
+
+        assert_eq!(PacketDirection::Incoming, decoded.direction());
+        assert_eq!(Some(PacketKind::Final), decoded.kind());
+        assert_eq!(Some(&me), decoded.me());
+        assert_eq!(&previous_hop, decoded.peer());
+        assert_eq!(
+            CapturedPacketContent::Final {
+                packet_tag,
+                previous_hop,
+                me,
+                sender,
+                ack_key,
+                signals: PacketSignals::default(),
+                payload: DecodedPayload::Start(start_msg),
+            },
+            decoded.content
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn decoder_should_decode_forwarded_packet() -> anyhow::Result<()> {
+        let me = *OffchainKeypair::random().public();
+        let ticket = ticket()?;
+        let packet_tag = hopr_api::types::crypto_random::random_bytes();
+        let previous_hop = *OffchainKeypair::random().public();
+        let next_hop = *OffchainKeypair::random().public();
+        let ack_key = HalfKey::random();
+        let packet = IncomingPacket::Forwarded(
+            IncomingForwardedPacket {
+                packet_tag,
+                previous_hop,
+                next_hop,
+                data: bytes::Bytes::from_static(&hex!("deadbeefcafe")),
+                ack_challenge: HalfKeyChallenge::default(),
+                received_ticket: ticket.into_unacknowledged(HalfKey::random()),
+                ack_key_prev_hop: ack_key,
+            }
+            .into(),
+        );
+
+        let decoded = decode(PacketBeforeTransit::IncomingPacket { me, packet: &packet })?;
+
+        assert_eq!(Some(PacketKind::Forwarded), decoded.kind());
+        assert_eq!(None, decoded.me());
+        assert_eq!(
+            CapturedPacketContent::Forwarded {
+                packet_tag,
+                previous_hop,
+                next_hop,
+                ack_key,
+                ticket: Some(*ticket.verified_ticket()),
+                data: hex!("deadbeefcafe").into(),
+            },
+            decoded.content
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn decoder_should_decode_outgoing_packets_with_and_without_ack_challenge() -> anyhow::Result<()> {
+        let me = *OffchainKeypair::random().public();
+        let next_hop = *OffchainKeypair::random().public();
+        let ticket = ticket()?;
+        let ack_challenge = HalfKey::random().to_challenge()?;
+        let msg = CapturedSessionMessage::Segment(Segment {
+            frame_id: 1,
+            seq_idx: 0,
+            seq_flags: SeqIndicator::new_with_flags(1, true),
+            data: Box::new(hex!("474554202f20485454502f312e310d0a")),
+        });
+        let data = ApplicationData::new(SESSION_APPLICATION_TAG, msg.clone().into_encoded().into_vec())?.to_bytes();
+
+        let decoded = decode(PacketBeforeTransit::OutgoingPacket {
+            me,
+            next_hop,
+            num_surbs: 3,
+            is_forwarded: false,
+            data: data.as_ref().into(),
+            ack_challenge: ack_challenge.as_ref().into(),
+            signals: PacketSignal::OutOfSurbs.into(),
+            ticket: ticket.verified_ticket().into_encoded().to_vec().into(),
+        })?;
+
+        assert_eq!(PacketDirection::Outgoing, decoded.direction());
+        assert_eq!(Some(PacketKind::Outgoing), decoded.kind());
+        assert_eq!(&next_hop, decoded.peer());
+        assert_eq!(
+            CapturedPacketContent::Outgoing {
+                me,
+                next_hop,
+                ack_challenge: Some(ack_challenge),
+                ticket: Some(*ticket.verified_ticket()),
+                num_surbs: 3,
+                is_forwarded: false,
+                signals: PacketSignal::OutOfSurbs.into(),
+                payload: DecodedPayload::Session(msg),
+            },
+            decoded.content
+        );
+
+        // Relayed packets are recorded without the ack challenge
+        let decoded = decode(PacketBeforeTransit::OutgoingPacket {
+            me,
+            next_hop,
+            num_surbs: 0,
+            is_forwarded: true,
+            data: data.as_ref().into(),
+            ack_challenge: Default::default(),
+            signals: None.into(),
+            ticket: ticket.verified_ticket().into_encoded().to_vec().into(),
+        })?;
+
+        assert_eq!(Some(PacketKind::Forwarded), decoded.kind());
+        assert_eq!(
+            CapturedPacketContent::Outgoing {
+                me,
+                next_hop,
+                ack_challenge: None,
+                ticket: Some(*ticket.verified_ticket()),
+                num_surbs: 0,
+                is_forwarded: true,
+                signals: PacketSignals::default(),
+                payload: DecodedPayload::Opaque(data),
+            },
+            decoded.content
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn decoder_should_decode_acknowledgements() -> anyhow::Result<()> {
+        let me = *OffchainKeypair::random().public();
+        let kp = OffchainKeypair::random();
+        let acks = vec![
+            VerifiedAcknowledgement::random(&kp),
+            VerifiedAcknowledgement::random(&kp),
+        ];
+
+        let decoded = decode(PacketBeforeTransit::OutgoingAck {
+            me,
+            next_hop: *kp.public(),
+            acks: acks.clone(),
+            is_random: true,
+        })?;
+        assert_eq!(
+            CapturedPacketContent::OutgoingAck {
+                me,
+                next_hop: *kp.public(),
+                is_random: true,
+                acks: acks.iter().map(|ack| ack.leak()).collect(),
+            },
+            decoded.content
+        );
+
+        let packet_tag = hopr_api::types::crypto_random::random_bytes();
+        let received_acks = acks.iter().map(|ack| ack.leak()).collect::<Vec<_>>();
+        let packet = IncomingPacket::Acknowledgement(
+            IncomingAcknowledgementPacket {
+                packet_tag,
+                previous_hop: *kp.public(),
+                received_acks: received_acks.clone(),
+            }
+            .into(),
+        );
+        let decoded = decode(PacketBeforeTransit::IncomingPacket { me, packet: &packet })?;
+
+        assert_eq!(PacketDirection::Incoming, decoded.direction());
+        assert_eq!(Some(PacketKind::Acknowledgement), decoded.kind());
+        assert_eq!(
+            CapturedPacketContent::IncomingAck {
+                packet_tag,
+                previous_hop: *kp.public(),
+                me,
+                acks: received_acks,
+            },
+            decoded.content
+        );
+
+        // Received acknowledgements can be verified offline using the key of their sender
+        if let CapturedPacketContent::IncomingAck { acks, .. } = decoded.content {
+            assert!(acks.into_iter().all(|ack| ack.verify(kp.public()).is_ok()));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn decoder_should_decrypt_wire_data_of_packets_delivered_to_the_node() -> anyhow::Result<()> {
+        let sender = ChainKeypair::random();
+        let me = OffchainKeypair::random();
+        let relay = OffchainKeypair::random();
+        let previous_hop = *OffchainKeypair::random().public();
+        let mapper = KnownKeyIds(vec![*me.public(), *relay.public()]);
+        let pseudonym = HoprPseudonym::random();
+        let start_msg = HoprStartProtocol::SessionError(StartErrorType {
+            challenge: 0x01234567_89abcdef,
+            reason: StartErrorReason::NoSlotsAvailable,
+        });
+        let plain_text = ApplicationData::try_from(start_msg.clone())?.to_bytes();
+
+        let wire = |path: Vec<OffchainPublicKey>, ticket: TicketBuilder| -> anyhow::Result<DecodedPacket> {
+            let (packet, _) = HoprPacket::into_outgoing(
+                &plain_text,
+                &pseudonym,
+                PacketRouting::ForwardPath {
+                    forward_path: TransportPath::new(path)?,
+                    return_paths: vec![],
+                },
+                &sender,
+                ticket,
+                &mapper,
+                &Default::default(),
+                PacketSignal::OutOfSurbs,
+            )?;
+            let out = packet
+                .try_as_outgoing()
+                .ok_or_else(|| anyhow::anyhow!("packet must be outgoing"))?;
+            let data = [out.packet.as_ref(), out.ticket.into_encoded().as_ref()].concat();
+            decode(PacketBeforeTransit::IncomingWire {
+                me: *me.public(),
+                previous_hop,
+                kind: None,
+                data: &data,
+            })
+        };
+        let counterparty = Address::new(&[10u8; Address::SIZE]);
+
+        let direct = wire(vec![*me.public()], TicketBuilder::zero_hop().counterparty(counterparty))?;
+        let decrypted = direct.clone().decrypt_wire(&me)?;
+        assert_eq!(direct.timestamp, decrypted.timestamp);
+        assert!(
+            matches!(
+                &decrypted.content,
+                CapturedPacketContent::Final { previous_hop: prev, me: node, sender, signals, payload, .. }
+                    if prev == &previous_hop
+                        && node == me.public()
+                        && sender == &pseudonym
+                        && signals == &PacketSignals::from(PacketSignal::OutOfSurbs)
+                        && payload == &DecodedPayload::Start(start_msg.clone())
+            ),
+            "{decrypted:?}"
+        );
+
+        // The wire data can be decrypted only by the node that captured them
+        assert!(matches!(
+            direct.decrypt_wire(&relay),
+            Err(CaptureReadError::Undecryptable(_))
+        ));
+
+        // The next hop of a relayed packet is not known offline
+        let relayed = wire(
+            vec![*me.public(), *relay.public()],
+            TicketBuilder::default()
+                .counterparty(counterparty)
+                .amount(10)
+                .index(1)
+                .win_prob(WinningProbability::ALWAYS)
+                .channel_epoch(1)
+                .eth_challenge(Default::default()),
+        )?;
+        assert!(matches!(
+            relayed.decrypt_wire(&me),
+            Err(CaptureReadError::Undecryptable(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn decoder_should_decode_wire_data() -> anyhow::Result<()> {
+        let me = *OffchainKeypair::random().public();
+        let previous_hop = *OffchainKeypair::random().public();
+        let data = hopr_api::types::crypto_random::random_bytes::<512>();
+
+        for kind in [None, Some(PacketKind::Forwarded)] {
+            let decoded = decode(PacketBeforeTransit::IncomingWire {
+                me,
+                previous_hop,
+                kind,
+                data: &data,
+            })?;
+
+            assert_eq!(PacketDirection::Incoming, decoded.direction());
+            assert_eq!(kind, decoded.kind());
+            assert_eq!(Some(&me), decoded.me());
+            assert_eq!(&previous_hop, decoded.peer());
+            assert_eq!(
+                CapturedPacketContent::Wire {
+                    kind,
+                    previous_hop,
+                    me,
+                    data: data.into(),
+                },
+                decoded.content
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn payload_decoder_should_decode_by_tag() -> anyhow::Result<()> {
+        let probe =
+            hopr_transport_probe::content::Message::Probe(hopr_transport_probe::types::NeighborProbe::random_nonce());
+        assert_eq!(
+            DecodedPayload::Probe(probe),
+            DecodedPayload::decode(&ApplicationData::try_from(probe)?.to_bytes())
+        );
+
+        let app_data = ApplicationData::new(1024_u64, &hex!("deadbeef"))?;
+        assert_eq!(
+            DecodedPayload::Application(app_data.clone()),
+            DecodedPayload::decode(&app_data.to_bytes())
+        );
+
+        // Session data that do not decode, such as those of encrypted Sessions, are kept
+        let app_data = ApplicationData::new(SESSION_APPLICATION_TAG, &hex!("ffffffffffff"))?;
+        let decoded = DecodedPayload::decode(&app_data.to_bytes());
+        assert_eq!(Some(SESSION_APPLICATION_TAG), decoded.tag());
+        assert_eq!(DecodedPayload::Application(app_data), decoded);
+
+        assert_eq!(
+            DecodedPayload::Opaque(Box::new([0x01])),
+            DecodedPayload::decode(&[0x01])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn decoder_should_reject_malformed_records() {
+        assert!(matches!(
+            DecodedPacket::decode(Duration::ZERO, &[0xff]),
+            Err(CaptureReadError::UnknownPacketType(0xff))
+        ));
+        assert!(matches!(
+            DecodedPacket::decode(Duration::ZERO, &[PacketType::Final as u8, 0x01, 0x02]),
+            Err(CaptureReadError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn reader_should_read_packets_written_into_pcap_file() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!("hopr-capture-reader-{}.pcapng", std::process::id()));
+        let me = *OffchainKeypair::random().public();
+        let kp = OffchainKeypair::random();
+
+        let mut writer = File::create(&path).and_then(PcapPacketWriter::new)?;
+        let mut written = Vec::new();
+        for is_random in [false, true, false] {
+            let packet = CapturedPacket::from(PacketBeforeTransit::OutgoingAck {
+                me,
+                next_hop: *kp.public(),
+                acks: vec![VerifiedAcknowledgement::random(&kp)],
+                is_random,
+            });
+            written.push(DecodedPacket::try_from(&packet)?);
+            writer.write_packet(packet)?;
+        }
+        drop(writer);
+
+        let read = CaptureReader::new(File::open(&path)?)?.collect::<Result<Vec<_>, _>>()?;
+        std::fs::remove_file(&path)?;
+
+        // The pcapng timestamps have nanosecond resolution
+        assert_eq!(written, read);
+        Ok(())
+    }
+
+    #[test]
+    fn reader_should_stop_after_error_in_truncated_pcap_file() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!("hopr-capture-truncated-{}.pcapng", std::process::id()));
+        let me = *OffchainKeypair::random().public();
+        let kp = OffchainKeypair::random();
+
+        let mut writer = File::create(&path).and_then(PcapPacketWriter::new)?;
+        for _ in 0..2 {
+            writer.write_packet(CapturedPacket::from(PacketBeforeTransit::OutgoingAck {
+                me,
+                next_hop: *kp.public(),
+                acks: vec![VerifiedAcknowledgement::random(&kp)],
+                is_random: false,
+            }))?;
+        }
+        drop(writer);
+
+        let mut data = std::fs::read(&path)?;
+        std::fs::remove_file(&path)?;
+        data.truncate(data.len() - 10);
+
+        let mut reader = CaptureReader::new(std::io::Cursor::new(data))?;
+        assert!(reader.next().is_some_and(|packet| packet.is_ok()));
+        assert!(matches!(reader.next(), Some(Err(CaptureReadError::Pcap(_)))));
+        assert!(reader.next().is_none());
+        assert!(reader.next().is_none());
+        Ok(())
+    }
+}
@@ -17,6 +17,7 @@ use validator::{Validate, ValidationError, ValidationErrors};
 use crate::{errors::HoprTransportError, protocol::PacketPipelineConfig};
 
 const DEFAULT_COUNTER_FLUSH_INTERVAL: Duration = Duration::from_secs(15);
+const DEFAULT_REPLAY_FILTER_SAVE_INTERVAL: Duration = Duration::from_secs(300);
 
 const DEFAULT_PER_PEER_CHANNEL_CAPACITY: usize = 5_000;
 const DEFAULT_STREAM_OPEN_TIMEOUT: Duration = Duration::from_secs(2);
@@ -148,6 +149,15 @@ fn default_counter_flush_interval() -> Duration {
     DEFAULT_COUNTER_FLUSH_INTERVAL
 }
 
+fn default_replay_filter_save_interval() -> Duration {
+    DEFAULT_REPLAY_FILTER_SAVE_INTERVAL
+}
+
+#[cfg(all(feature = "serde", feature = "surb-store-redb"))]
+fn default_surb_store_db_durable_commit_interval() -> std::num::NonZeroU64 {
+    hopr_protocol_hopr::RedbSurbStore::DEFAULT_DURABLE_COMMIT_INTERVAL
+}
+
 /// Simulated per-packet transit latency inserted between the mixer and the wire.
 ///
 /// When set on a node's config, every packet emitted by the mixer is held for a
@@ -219,6 +229,45 @@ pub struct HoprProtocolConfig {
     #[validate(nested)]
     #[cfg_attr(feature = "serde", serde(skip))]
     pub path_planner: crate::path::PathPlannerConfig,
+    /// Path to the database of the persistent SURB store.
+    ///
+    /// When set, SURBs and reply openers are kept in a `redb` database at this path
+    /// and survive node restarts. Otherwise, they are held only in memory.
+    ///
+    /// Default is `None`.
+    #[cfg(feature = "surb-store-redb")]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub surb_store_db: Option<std::path::PathBuf>,
+    /// Number of write transactions to the persistent SURB store after which a transaction is flushed to the disk.
+    ///
+    /// The store writes in the background, so higher values only relieve the disk, but more SURBs
+    /// and reply openers can be lost when the node crashes. Value of 1 flushes every transaction.
+    ///
+    /// Default is 1.
+    #[cfg(feature = "surb-store-redb")]
+    #[default(hopr_protocol_hopr::RedbSurbStore::DEFAULT_DURABLE_COMMIT_INTERVAL)]
+    #[cfg_attr(feature = "serde", serde(default = "default_surb_store_db_durable_commit_interval"))]
+    pub surb_store_db_durable_commit_interval: std::num::NonZeroU64,
+    /// Path to the file holding the state of the packet replay filter.
+    ///
+    /// When set, the state is loaded from this file when the node starts, saved
+    /// [periodically](HoprProtocolConfig::replay_filter_save_interval) and when the node shuts down,
+    /// so that packets seen before a restart cannot be replayed after it.
+    /// If the node crashes, the last periodically saved state is used.
+    ///
+    /// Default is `None`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub replay_filter_state: Option<std::path::PathBuf>,
+    /// Interval at which the state of the packet replay filter is saved to the
+    /// [configured](HoprProtocolConfig::replay_filter_state) file.
+    ///
+    /// Default is 5 minutes.
+    #[default(default_replay_filter_save_interval())]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default = "default_replay_filter_save_interval", with = "humantime_serde")
+    )]
+    pub replay_filter_save_interval: Duration,
     /// Interval at which per-peer protocol conformance counters are flushed
     /// into the network graph.
     ///
@@ -615,6 +664,12 @@ pub struct SessionGlobalConfig {
     )]
     pub surb_balance_notify_period: Option<Duration>,
 
+    /// Rate limits and target quotas of incoming Sessions.
+    ///
+    /// All limits are disabled by default.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub admission: hopr_transport_session::SessionAdmissionConfig,
+
     /// Tag allocator partition configuration.
     #[validate(nested)]
     #[cfg_attr(feature = "serde", serde(default))]
@@ -61,7 +61,7 @@ use hopr_api::{
 };
 use hopr_crypto_packet::prelude::PacketSignal;
 pub use hopr_protocol_app::prelude::{ApplicationData, ApplicationDataIn, ApplicationDataOut, Tag};
-use hopr_protocol_hopr::MemorySurbStore;
+use hopr_protocol_hopr::{MemorySurbStore, RotatingTagBloomFilter, SurbStore};
 pub use hopr_transport_probe::{NeighborTelemetry, PathTelemetry, errors::ProbeError, ping::PingQueryReplier};
 use hopr_transport_probe::{
     Probe,
@@ -71,9 +71,9 @@ pub use hopr_transport_session as session;
 #[cfg(feature = "runtime-tokio")]
 pub use hopr_transport_session::transfer_session;
 pub use hopr_transport_session::{
-    Capabilities as SessionCapabilities, Capability as SessionCapability, FlowControlConfig, HoprSession,
-    IncomingSession, SESSION_MTU, SURB_SIZE, ServiceId, SessionClientConfig, SessionId, SessionTarget,
-    SurbBalancerConfig,
+    Capabilities as SessionCapabilities, Capability as SessionCapability, EchoService, FlowControlConfig, HoprSession,
+    IncomingSession, MultipathConfig, PathStats, SESSION_MTU, SURB_SIZE, ServiceId, SessionClientConfig, SessionId,
+    SessionPathOptions, SessionService, SessionTarget, SurbBalancerConfig, SurbBalancerControllerKind,
     errors::{SessionManagerError, TransportSessionError},
 };
 use hopr_transport_session::{DispatchResult, SessionManager, SessionManagerConfig};
@@ -90,25 +90,92 @@ use hopr_utils::{
 pub use multiaddr::Protocol;
 use tracing::{Instrument, debug, error, trace, warn};
 
+#[cfg(feature = "capture")]
+pub use crate::capture::{
+    CaptureFilter, CaptureReadError, CaptureReader, CapturedPacket, CapturedPacketContent, CapturedSessionMessage,
+    DecodedPacket, DecodedPayload, NullWriter, PacketDirection, PacketKind, PacketWriter, PcapPacketWriter,
+    RotatingPcapWriter, RotationPolicy,
+};
 #[cfg(feature = "runtime-tokio")]
 use crate::path::BackgroundPathCacheRefreshable;
-pub use crate::{config::HoprProtocolConfig, protocol::PeerProtocolCounterRegistry};
+pub use crate::{
+    config::HoprProtocolConfig,
+    path::{PathCacheExplanation, RelayPolicy, RelaySafes},
+    protocol::PeerProtocolCounterRegistry,
+};
 use crate::{
     constants::SESSION_INITIATION_TIMEOUT_BASE,
     errors::HoprTransportError,
     multiaddrs::strip_p2p_protocol,
     path::{HoprGraphPathSelector, PathPlanner},
-    pipeline::HoprPacketPipelineBuilder,
+    pipeline::{HoprPacketPipelineBuilder, HoprSurbStore},
     socket::HoprSocket,
 };
 
 pub const APPLICATION_TAG_RANGE: std::ops::Range<Tag> = Tag::APPLICATION_TAG_RANGE;
 
+/// Explicit routing options following the relays of the given `path`.
+fn intermediate_path_options(path: &ValidatedPath) -> RoutingOptions {
+    RoutingOptions::IntermediatePath(path[..path.len() - 1].iter().copied().map(NodeId::Offchain).collect())
+}
+
+/// Loads the packet replay filter saved via [`HoprTransport::save_replay_filter`].
+///
+/// Returns `None` if there is no saved state, or it cannot be restored.
+fn load_replay_filter(path: &std::path::Path) -> Option<RotatingTagBloomFilter> {
+    let state = match std::fs::read(path) {
+        Ok(state) => state,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(error) => {
+            warn!(path = %path.display(), %error, "cannot read the saved packet replay filter");
+            return None;
+        }
+    };
+
+    match RotatingTagBloomFilter::from_bytes(&state) {
+        Ok(filter) => {
+            debug!(path = %path.display(), count = filter.count(), "restored the packet replay filter");
+            Some(filter)
+        }
+        Err(error) => {
+            warn!(path = %path.display(), %error, "cannot restore the saved packet replay filter");
+            None
+        }
+    }
+}
+
+/// Saves the state of the packet replay `filter` to the given `path`.
+///
+/// The state is written to a temporary file first, which then atomically replaces the previous state.
+fn persist_replay_filter(
+    filter: &parking_lot::Mutex<RotatingTagBloomFilter>,
+    path: &std::path::Path,
+) -> std::io::Result<()> {
+    use std::io::Write;
+
+    // Do not block the packet processing while the state is serialized
+    let state = filter.lock().snapshot().to_bytes();
+
+    // The data must be on the disk before the rename, so that a crash never leaves an incomplete state behind
+    let tmp_path = path.with_extension("tmp");
+    let mut tmp_file = std::fs::File::create(&tmp_path)?;
+    tmp_file.write_all(&state)?;
+    tmp_file.sync_all()?;
+    std::fs::rename(&tmp_path, path)?;
+
+    // Make the rename itself durable
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(std::path::Path::new("."));
+    std::fs::File::open(dir)?.sync_all()
+}
+
 pub use hopr_api as api;
 use hopr_api::{
     chain::{ChainReadTicketOperations, ChainWriteTicketOperations},
     tickets::TicketFactory,
-    types::internal::routing::DestinationRouting,
+    types::internal::{NodeId, path::ValidatedPath, routing::DestinationRouting},
 };
 
 // Needs lazy-static, since Duration multiplication by a constant is yet not a const-operation.
@@ -157,11 +224,10 @@ pub enum HoprTransportProcess {
     OutgoingIndexSync,
     #[strum(to_string = "periodic protocol counter flush")]
     CounterFlush,
+    #[strum(to_string = "periodic save of the packet replay filter")]
+    ReplayFilterSave,
     #[strum(to_string = "mixer→wire forwarder")]
     MixerForwarder,
-    #[cfg(feature = "capture")]
-    #[strum(to_string = "packet capture")]
-    Capture,
 }
 
 /// HOPR protocol specific instantiation of the SessionManager.
@@ -179,6 +245,17 @@ pub struct HoprSessionConfigurator {
 }
 
 impl HoprSessionConfigurator {
+    /// Creates a configurator of the given Session, which is not attached to any [`SessionManager`].
+    ///
+    /// Its operations behave as if the [`SessionManager`] has been dropped.
+    #[cfg(feature = "testing")]
+    pub fn detached(id: SessionId) -> Self {
+        Self {
+            id,
+            smgr: std::sync::Weak::new(),
+        }
+    }
+
     /// [`SessionId`] of the session this object can configure.
     pub fn id(&self) -> &SessionId {
         &self.id
@@ -200,6 +277,32 @@ impl HoprSessionConfigurator {
             .await?)
     }
 
+    /// Resumes the Session over new forward and return paths, without restarting it.
+    ///
+    /// Requires the Session to be opened with the [`Capability::Resumption`](hopr_transport_session::Capability)
+    /// flag. See [`SessionManager::resume_session`] for details.
+    pub async fn resume(&self, forward_path: RoutingOptions, return_path: RoutingOptions) -> errors::Result<()> {
+        Ok(self
+            .smgr
+            .upgrade()
+            .ok_or(HoprTransportError::Other(anyhow::anyhow!("session manager is dropped")))?
+            .resume_session(&self.id, forward_path, return_path)
+            .await?)
+    }
+
+    /// Gets the state of the paths of a multipath Session.
+    ///
+    /// Returns an error if the Session is closed, the Session manager is gone.
+    ///
+    /// Returns `Ok(None)` if the Session does not use multiple paths.
+    pub fn get_path_stats(&self) -> errors::Result<Option<Vec<PathStats>>> {
+        Ok(self
+            .smgr
+            .upgrade()
+            .ok_or(HoprTransportError::Other(anyhow::anyhow!("session manager is dropped")))?
+            .get_session_paths(&self.id)?)
+    }
+
     /// Gets the configuration of the SURB balancer.
     ///
     /// Returns an error if the Session is closed, the Session manager is gone.
@@ -248,15 +351,35 @@ pub struct HoprTransport<Chain, Graph, Net> {
     ping: Arc<OnceLock<Pinger>>,
     network: Arc<OnceLock<Net>>,
     graph: Graph,
-    path_planner: PathPlanner<MemorySurbStore, Chain, HoprGraphPathSelector<Graph>>,
+    path_planner: PathPlanner<HoprSurbStore, Chain, HoprGraphPathSelector<Graph>>,
     my_multiaddresses: Vec<Multiaddr>,
     smgr: Arc<HoprSessionManager>,
     session_telemetry_tag_allocator: Arc<dyn hopr_transport_tag_allocator::TagAllocator + Send + Sync>,
     probing_tag_allocator: Arc<dyn hopr_transport_tag_allocator::TagAllocator + Send + Sync>,
     counters: PeerProtocolCounterRegistry,
+    replay_filter: Arc<parking_lot::Mutex<RotatingTagBloomFilter>>,
+    #[cfg(feature = "capture")]
+    capture: capture::PacketCapture,
     cfg: HoprProtocolConfig,
 }
 
+impl<Chain, Graph, Net> HoprTransport<Chain, Graph, Net> {
+    /// Saves the state of the packet replay filter to the [configured](HoprProtocolConfig::replay_filter_state)
+    /// file, so that it is restored when the node starts again.
+    ///
+    /// Does nothing if no such file is configured.
+    pub fn save_replay_filter(&self) -> errors::Result<()> {
+        let Some(path) = &self.cfg.replay_filter_state else {
+            return Ok(());
+        };
+
+        persist_replay_filter(&self.replay_filter, path).map_err(|e| HoprTransportError::Other(e.into()))?;
+
+        debug!(path = %path.display(), "saved the packet replay filter");
+        Ok(())
+    }
+}
+
 impl<Chain, Graph, Net> HoprTransport<Chain, Graph, Net>
 where
     Chain: ChainReadChannelOperations
@@ -320,56 +443,79 @@ where
         let probing_tag_allocator =
             probing_tag_allocator.ok_or_else(|| HoprTransportError::Api("probing tag allocator missing".into()))?;
 
+        #[cfg(feature = "surb-store-redb")]
+        let surb_store = match &cfg.surb_store_db {
+            Some(path) => HoprSurbStore::Redb(
+                hopr_protocol_hopr::RedbSurbStore::new(path, cfg.packet.surb_store)
+                    .map_err(|e| HoprTransportError::Other(e.into()))?
+                    .with_durable_commit_interval(cfg.surb_store_db_durable_commit_interval),
+            ),
+            None => HoprSurbStore::Memory(MemorySurbStore::new(cfg.packet.surb_store)),
+        };
+        #[cfg(not(feature = "surb-store-redb"))]
+        let surb_store = HoprSurbStore::Memory(MemorySurbStore::new(cfg.packet.surb_store));
+        let session_surb_store = surb_store.clone();
+
+        let replay_filter = cfg
+            .replay_filter_state
+            .as_deref()
+            .and_then(load_replay_filter)
+            .unwrap_or_default();
+
         Ok(Self {
             packet_key: identity.1.clone(),
             chain_key: identity.0.clone(),
             ping: Arc::new(OnceLock::new()),
             network: Arc::new(OnceLock::new()),
             graph,
-            path_planner: PathPlanner::new(
-                me_offchain,
-                MemorySurbStore::new(cfg.packet.surb_store),
-                resolver.clone(),
-                selector,
-                planner_config,
-            ),
+            path_planner: PathPlanner::new(me_offchain, surb_store, resolver.clone(), selector, planner_config),
             my_multiaddresses,
-            smgr: Arc::new(SessionManager::new(SessionManagerConfig {
-                frame_mtu: std::env::var("HOPR_SESSION_FRAME_SIZE")
-                    .ok()
-                    .and_then(|s| s.parse::<usize>().ok())
-                    .unwrap_or_else(|| SessionManagerConfig::default().frame_mtu)
-                    .max(SESSION_MTU),
-                max_frame_timeout: std::env::var("HOPR_SESSION_FRAME_TIMEOUT_MS")
-                    .ok()
-                    .and_then(|s| s.parse::<u64>().ok().map(Duration::from_millis))
-                    .unwrap_or_else(|| SessionManagerConfig::default().max_frame_timeout)
-                    .max(Duration::from_millis(100)),
-                max_buffered_segments: std::env::var("HOPR_SESSION_MAX_BUFFERED_SEGMENTS")
-                    .ok()
-                    .and_then(|s| s.parse::<usize>().ok())
-                    .unwrap_or_else(|| SessionManagerConfig::default().max_buffered_segments),
-                initiation_timeout_base: SESSION_INITIATION_TIMEOUT_BASE,
-                idle_timeout: cfg.session.idle_timeout,
-                balancer_sampling_interval: cfg.session.balancer_sampling_interval,
-                initial_return_session_egress_rate: 10,
-                minimum_surb_buffer_duration: cfg.session.balancer_minimum_surb_buffer_duration,
-                maximum_surb_buffer_size: cfg.packet.surb_store.rb_capacity,
-                // The lower bound is enforced once in `SessionManager::new` via
-                // `MIN_SURB_BUFFER_NOTIFICATION_PERIOD`; don't duplicate that floor as a literal here.
-                surb_balance_notify_period: std::env::var("HOPR_SESSION_SURB_BALANCE_NOTIFY_PERIOD_MS")
-                    .ok()
-                    .and_then(|s| s.parse::<u64>().ok())
-                    .map(|ms| Some(Duration::from_millis(ms)))
-                    .unwrap_or(cfg.session.surb_balance_notify_period),
-                surb_target_notify: true,
-                maximum_sessions: cfg.session.maximum_managed_sessions,
-                ..Default::default()
-            })),
+            smgr: Arc::new(
+                SessionManager::new(SessionManagerConfig {
+                    frame_mtu: std::env::var("HOPR_SESSION_FRAME_SIZE")
+                        .ok()
+                        .and_then(|s| s.parse::<usize>().ok())
+                        .unwrap_or_else(|| SessionManagerConfig::default().frame_mtu)
+                        .max(SESSION_MTU),
+                    max_frame_timeout: std::env::var("HOPR_SESSION_FRAME_TIMEOUT_MS")
+                        .ok()
+                        .and_then(|s| s.parse::<u64>().ok().map(Duration::from_millis))
+                        .unwrap_or_else(|| SessionManagerConfig::default().max_frame_timeout)
+                        .max(Duration::from_millis(100)),
+                    max_buffered_segments: std::env::var("HOPR_SESSION_MAX_BUFFERED_SEGMENTS")
+                        .ok()
+                        .and_then(|s| s.parse::<usize>().ok())
+                        .unwrap_or_else(|| SessionManagerConfig::default().max_buffered_segments),
+                    initiation_timeout_base: SESSION_INITIATION_TIMEOUT_BASE,
+                    idle_timeout: cfg.session.idle_timeout,
+                    balancer_sampling_interval: cfg.session.balancer_sampling_interval,
+                    initial_return_session_egress_rate: 10,
+                    minimum_surb_buffer_duration: cfg.session.balancer_minimum_surb_buffer_duration,
+                    maximum_surb_buffer_size: cfg.packet.surb_store.rb_capacity,
+                    // The lower bound is enforced once in `SessionManager::new` via
+                    // `MIN_SURB_BUFFER_NOTIFICATION_PERIOD`; don't duplicate that floor as a literal here.
+                    surb_balance_notify_period: std::env::var("HOPR_SESSION_SURB_BALANCE_NOTIFY_PERIOD_MS")
+                        .ok()
+                        .and_then(|s| s.parse::<u64>().ok())
+                        .map(|ms| Some(Duration::from_millis(ms)))
+                        .unwrap_or(cfg.session.surb_balance_notify_period),
+                    surb_target_notify: true,
+                    maximum_sessions: cfg.session.maximum_managed_sessions,
+                    admission: cfg.session.admission,
+                    ..Default::default()
+                })
+                .with_packet_key(identity.1)
+                .with_surb_discarder(move |pseudonym, keep_freshest| {
+                    session_surb_store.discard_surbs(pseudonym, keep_freshest)
+                }),
+            ),
             chain_api: resolver,
             session_telemetry_tag_allocator,
             probing_tag_allocator,
             counters: PeerProtocolCounterRegistry::default(),
+            replay_filter: Arc::new(parking_lot::Mutex::new(replay_filter)),
+            #[cfg(feature = "capture")]
+            capture: Default::default(),
             cfg,
         })
     }
@@ -688,7 +834,7 @@ where
         // the encoder stage (output_concurrency) to avoid head-of-line blocking on
         // cache-miss path lookups.
         let path_planner = self.path_planner.clone();
-        let distress_threshold = self.cfg.packet.surb_store.distress_threshold;
+        let surb_store_cfg = self.cfg.packet.surb_store;
         let routing_concurrency = {
             let avail = std::thread::available_parallelism()
                 .ok()
@@ -743,12 +889,11 @@ where
                                     .unwrap_or_default();
 
                                 if resolved.is_return() {
-                                    signals_to_dst = match rem_surbs {
-                                        Some(rem) if (1..distress_threshold.max(2)).contains(&rem) => {
-                                            signals_to_dst | PacketSignal::SurbDistress
+                                    signals_to_dst = match rem_surbs.and_then(|rem| surb_store_cfg.surb_signal(rem)) {
+                                        Some(signal) => signals_to_dst | signal,
+                                        None => {
+                                            signals_to_dst - (PacketSignal::OutOfSurbs | PacketSignal::SurbDistress)
                                         }
-                                        Some(0) => signals_to_dst | PacketSignal::OutOfSurbs,
-                                        _ => signals_to_dst - (PacketSignal::OutOfSurbs | PacketSignal::SurbDistress),
                                     };
                                 } else {
                                     // Unset these flags as they make no sense on the forward path.
@@ -794,7 +939,10 @@ where
             .ticket_factory(ticket_factory)
             .channels_dst(channels_dst)
             .with_counters(self.counters.clone())
+            .with_replay_filter(self.replay_filter.clone())
             .with_config(self.cfg.packet);
+        #[cfg(feature = "capture")]
+        let pipeline_builder = pipeline_builder.with_capture(self.capture.clone());
 
         let pipeline_processes = match role {
             protocol::NodeType::Relay => pipeline_builder.with_ticket_events(ticket_events).build_for_relay(),
@@ -832,6 +980,36 @@ where
             }),
         );
 
+        // -- periodic save of the packet replay filter
+        if let Some(path) = self.cfg.replay_filter_state.clone() {
+            let replay_filter = self.replay_filter.clone();
+            let save_interval = self.cfg.replay_filter_save_interval;
+            processes.insert(
+                HoprTransportProcess::ReplayFilterSave,
+                hopr_utils::spawn_as_abortable!(async move {
+                    futures_time::stream::interval(futures_time::time::Duration::from(save_interval))
+                        .for_each(|_| {
+                            let (replay_filter, path) = (replay_filter.clone(), path.clone());
+                            async move {
+                                // Serializing and writing the state takes a while, so do it outside the executor
+                                match hopr_utils::runtime::prelude::spawn_blocking(move || {
+                                    persist_replay_filter(&replay_filter, &path)
+                                })
+                                .await
+                                {
+                                    Ok(Ok(())) => tracing::trace!("saved the packet replay filter"),
+                                    Ok(Err(error)) => {
+                                        tracing::error!(%error, "failed to save the packet replay filter")
+                                    }
+                                    Err(error) => tracing::error!(%error, "packet replay filter save task failed"),
+                                }
+                            }
+                        })
+                        .await;
+                }),
+            );
+        }
+
         // -- network probing
         let manual_ping_channel_capacity = std::env::var("HOPR_INTERNAL_MANUAL_PING_CHANNEL_CAPACITY")
             .ok()
@@ -1002,8 +1180,69 @@ where
         &self,
         destination: Address,
         target: SessionTarget,
-        cfg: SessionClientConfig,
+        mut cfg: SessionClientConfig,
     ) -> errors::Result<(HoprSession, HoprSessionConfigurator)> {
+        if let Some(multipath) = cfg
+            .multipath
+            .as_mut()
+            .filter(|multipath| multipath.additional_paths.is_empty() && multipath.path_count > 1)
+        {
+            let me = NodeId::Offchain(*self.packet_key.public());
+            let forward_paths = self
+                .path_planner
+                .select_paths(
+                    me,
+                    destination.into(),
+                    cfg.forward_path_options.clone(),
+                    multipath.path_count,
+                )
+                .await
+                .map_err(HoprTransportError::other)?;
+            let return_paths = self
+                .path_planner
+                .select_paths(
+                    destination.into(),
+                    me,
+                    cfg.return_path_options.clone(),
+                    forward_paths.len(),
+                )
+                .await
+                .map_err(HoprTransportError::other)?;
+
+            if forward_paths.len() < multipath.path_count {
+                warn!(
+                    %destination,
+                    requested = multipath.path_count,
+                    available = forward_paths.len(),
+                    "not enough distinct paths for the multipath session"
+                );
+            }
+
+            // Each forward path gets its own return path, reusing them if there are fewer
+            let mut paths = forward_paths
+                .iter()
+                .zip(return_paths.iter().cycle())
+                .map(|(forward, ret)| SessionPathOptions {
+                    forward_path_options: intermediate_path_options(forward),
+                    return_path_options: intermediate_path_options(ret),
+                });
+            if let Some(primary) = paths.next() {
+                cfg.forward_path_options = primary.forward_path_options;
+                cfg.return_path_options = primary.return_path_options;
+            }
+            multipath.additional_paths = paths.collect();
+        }
+
+        // Encrypted Sessions need the packet key of the destination for the key exchange
+        if cfg.capabilities.contains(SessionCapability::Encryption) && cfg.destination_key.is_none() {
+            cfg.destination_key = Some(
+                self.chain_api
+                    .chain_key_to_packet_key(&destination)
+                    .map_err(|e| HoprTransportError::Chain(e.into()))?
+                    .ok_or_else(|| HoprTransportError::Api(format!("unknown packet key of {destination}")))?,
+            );
+        }
+
         let session = self.smgr.new_session(destination, target, cfg).await?;
         let id = *session.id();
         Ok((
@@ -1015,6 +1254,39 @@ where
         ))
     }
 
+    /// Registers the `service` serving incoming Sessions targeting the Exit service with the given `id`.
+    ///
+    /// Sessions targeting services that are not registered are rejected.
+    pub fn register_service(&self, id: ServiceId, service: impl SessionService + 'static) -> errors::Result<()> {
+        Ok(self.smgr.register_service(id, service)?)
+    }
+
+    /// Unregisters the Exit service with the given `id`, returning `true` if it was registered.
+    pub fn unregister_service(&self, id: ServiceId) -> bool {
+        self.smgr.unregister_service(id)
+    }
+
+    /// Starts capturing packets passing the `filter` into the `writer`.
+    ///
+    /// Replaces the packet capture that is currently running, if any.
+    #[cfg(feature = "capture")]
+    pub fn start_packet_capture(&self, writer: impl PacketWriter + Send + 'static, filter: CaptureFilter) {
+        debug!(?filter, "starting packet capture");
+        self.capture.start(Box::new(writer), filter);
+    }
+
+    /// Stops the running packet capture, returning `false` if no capture was running.
+    #[cfg(feature = "capture")]
+    pub fn stop_packet_capture(&self) -> bool {
+        self.capture.stop()
+    }
+
+    /// Returns the filter of the running packet capture, or `None` if no capture is running.
+    #[cfg(feature = "capture")]
+    pub fn packet_capture_filter(&self) -> Option<CaptureFilter> {
+        self.capture.is_active().then(|| self.capture.filter()).flatten()
+    }
+
     #[tracing::instrument(level = "debug", skip(self))]
     pub async fn listening_multiaddresses(&self) -> Vec<Multiaddr> {
         self.network
@@ -1058,6 +1330,51 @@ where
         &self.graph
     }
 
+    /// Returns the relay policy currently applied to path selection.
+    pub fn relay_policy(&self) -> RelayPolicy {
+        self.path_planner.relay_policy()
+    }
+
+    /// Replaces the relay policy applied to path selection.
+    ///
+    /// [`RelayPolicy::distinct_safes`] relies on the Safes recorded via [`HoprTransport::relay_safes`].
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn set_relay_policy(&self, policy: RelayPolicy) -> errors::Result<()> {
+        self.path_planner
+            .set_relay_policy(policy)
+            .await
+            .map_err(HoprTransportError::other)
+    }
+
+    /// Returns the handle recording the Safes controlling the relays.
+    ///
+    /// It must be fed with the on-chain account announcements for [`RelayPolicy::distinct_safes`]
+    /// to admit any path with more than one relay.
+    pub fn relay_safes(&self) -> RelaySafes {
+        self.path_planner.relay_safes()
+    }
+
+    /// Lists the `(source, destination, hops)` routes currently in the path cache.
+    pub fn cached_routes(
+        &self,
+    ) -> Vec<(
+        hopr_api::types::internal::NodeId,
+        hopr_api::types::internal::NodeId,
+        u32,
+    )> {
+        self.path_planner.cached_routes()
+    }
+
+    /// Explains the cached paths of the given route, see [`PathPlanner::explain`].
+    pub async fn explain_route(
+        &self,
+        source: hopr_api::types::internal::NodeId,
+        destination: hopr_api::types::internal::NodeId,
+        hops: u32,
+    ) -> Option<PathCacheExplanation> {
+        self.path_planner.explain(source, destination, hops).await
+    }
+
     #[tracing::instrument(level = "debug", skip(self))]
     pub fn local_multiaddresses(&self) -> Vec<Multiaddr> {
         self.network
@@ -1327,4 +1644,24 @@ mod tests {
         canary.abort();
         canary.await.ok();
     }
+
+    #[test]
+    fn load_replay_filter_should_restore_saved_state_and_ignore_invalid_one() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("replay-filter.bin");
+        assert!(super::load_replay_filter(&path).is_none());
+
+        let mut filter = super::RotatingTagBloomFilter::new(10, 2);
+        filter.set(&[1; hopr_api::types::crypto::types::PACKET_TAG_LENGTH]);
+        super::persist_replay_filter(&parking_lot::Mutex::new(filter), &path)?;
+        assert!(!path.with_extension("tmp").exists());
+
+        let restored = super::load_replay_filter(&path).ok_or(anyhow::anyhow!("filter must be restored"))?;
+        assert!(restored.check(&[1; hopr_api::types::crypto::types::PACKET_TAG_LENGTH]));
+
+        std::fs::write(&path, b"garbage")?;
+        assert!(super::load_replay_filter(&path).is_none());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,209 @@
+use std::{collections::HashMap, num::NonZeroUsize};
+
+use hopr_api::OffchainPublicKey;
+
+use super::{
+    errors::Result,
+    traits::{PathSelection, PathSelector, PathWithMetrics, PruneReason, PrunedPath},
+};
+
+/// Greedily keeps the best candidates such that no relay appears in more than
+/// `max_occurrences` of the kept paths.
+///
+/// The destination is not constrained, since it terminates every path.
+/// Returns the kept and the dropped candidates.
+fn limit_node_occurrences(
+    mut candidates: Vec<PathWithMetrics>,
+    max_occurrences: NonZeroUsize,
+) -> (Vec<PathWithMetrics>, Vec<PathWithMetrics>) {
+    candidates.sort_by(|a, b| b.cost.total_cmp(&a.cost));
+
+    let mut occurrences: HashMap<OffchainPublicKey, usize> = HashMap::new();
+    candidates.into_iter().partition(|pwm| {
+        let relays = &pwm.path[..pwm.path.len().saturating_sub(1)];
+        if relays
+            .iter()
+            .any(|node| occurrences.get(node).copied().unwrap_or_default() >= max_occurrences.get())
+        {
+            return false;
+        }
+        for node in relays {
+            *occurrences.entry(*node).or_default() += 1;
+        }
+        true
+    })
+}
+
+/// A path selector that limits how often any relay appears across the returned candidates.
+///
+/// Wraps another [`PathSelector`] and filters its candidates: they are visited from the
+/// best (highest cost) down, and a path is dropped if any of its relays already appears
+/// in `max_node_occurrences` of the kept paths. This prevents a single well-performing
+/// relay from being part of most candidate paths, which would make it a single point of
+/// failure and an attractive observation point.
+///
+/// The best candidate is always kept, so the selector fails only when the inner one does.
+/// The candidates themselves are returned unchanged, including their per-path aggregates.
+#[derive(Clone)]
+pub struct DiversityConstrainedPathSelector<S> {
+    inner: S,
+    max_node_occurrences: NonZeroUsize,
+}
+
+impl<S: PathSelector> DiversityConstrainedPathSelector<S> {
+    /// Create a new selector.
+    ///
+    /// * `inner` – the selector providing the candidate paths.
+    /// * `max_node_occurrences` – maximum number of returned paths any single relay may appear in.
+    pub fn new(inner: S, max_node_occurrences: NonZeroUsize) -> Self {
+        Self {
+            inner,
+            max_node_occurrences,
+        }
+    }
+}
+
+impl<S: PathSelector> PathSelector for DiversityConstrainedPathSelector<S> {
+    fn select_path(
+        &self,
+        src: OffchainPublicKey,
+        dest: OffchainPublicKey,
+        hops: usize,
+    ) -> Result<Vec<PathWithMetrics>> {
+        self.select_path_explained(src, dest, hops)
+            .map(|selection| selection.selected)
+    }
+
+    fn select_path_explained(
+        &self,
+        src: OffchainPublicKey,
+        dest: OffchainPublicKey,
+        hops: usize,
+    ) -> Result<PathSelection> {
+        let PathSelection { selected, mut pruned } = self.inner.select_path_explained(src, dest, hops)?;
+        let found = selected.len();
+
+        let (paths, dropped) = limit_node_occurrences(selected, self.max_node_occurrences);
+        tracing::debug!(%src, %dest, hops, found, kept = paths.len(), "diversity-constrained candidates");
+
+        pruned.extend(dropped.into_iter().map(|candidate| PrunedPath {
+            candidate,
+            reason: PruneReason::RelayOverused,
+        }));
+
+        Ok(PathSelection {
+            selected: paths,
+            pruned,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Context;
+    use hopr_api::types::internal::errors::PathError;
+
+    use super::*;
+    use crate::path::{
+        errors::PathPlannerError,
+        test::{SECRET_0, SECRET_1, SECRET_2, SECRET_3, SECRET_4, pubkey},
+    };
+
+    fn make_path(path: Vec<OffchainPublicKey>, cost: f64) -> PathWithMetrics {
+        PathWithMetrics {
+            path,
+            cost,
+            total_latency_ms: Some(100),
+            min_probe_success_rate: None,
+            min_ack_rate: None,
+            capacity_floor: Some(1000),
+        }
+    }
+
+    /// Returns a fixed candidate set regardless of the query.
+    struct FixedSelector(Vec<PathWithMetrics>);
+
+    impl PathSelector for FixedSelector {
+        fn select_path(
+            &self,
+            src: OffchainPublicKey,
+            dest: OffchainPublicKey,
+            hops: usize,
+        ) -> Result<Vec<PathWithMetrics>> {
+            if self.0.is_empty() {
+                return Err(PathPlannerError::Path(PathError::PathNotFound(
+                    hops,
+                    src.to_string(),
+                    dest.to_string(),
+                )));
+            }
+            Ok(self.0.clone())
+        }
+    }
+
+    #[test]
+    fn diversity_selector_must_limit_node_occurrences() -> anyhow::Result<()> {
+        let me = pubkey(&SECRET_0);
+        let [a, b, c] = [pubkey(&SECRET_1), pubkey(&SECRET_2), pubkey(&SECRET_3)];
+        let dest = pubkey(&SECRET_4);
+
+        let selector = DiversityConstrainedPathSelector::new(
+            FixedSelector(vec![
+                make_path(vec![a, b, dest], 0.9),
+                make_path(vec![a, c, dest], 0.8),
+                make_path(vec![b, a, dest], 0.7),
+                make_path(vec![c, b, dest], 0.6),
+                make_path(vec![b, c, dest], 0.5),
+            ]),
+            NonZeroUsize::new(2).context("non-zero")?,
+        );
+
+        let paths = selector.select_path(me, dest, 2)?;
+        let kept: Vec<_> = paths.iter().map(|pwm| pwm.path.clone()).collect();
+        // [b, a] is dropped because `a` already appears twice, [b, c] because `b` does.
+        assert_eq!(kept, vec![vec![a, b, dest], vec![a, c, dest], vec![c, b, dest]]);
+
+        for node in [a, b, c] {
+            let count = paths.iter().filter(|pwm| pwm.path.contains(&node)).count();
+            assert!(count <= 2, "relay appears in {count} paths");
+        }
+
+        let pruned = selector.select_path_explained(me, dest, 2)?.pruned;
+        assert_eq!(pruned.len(), 2);
+        assert!(pruned.iter().all(|p| p.reason == PruneReason::RelayOverused));
+
+        Ok(())
+    }
+
+    #[test]
+    fn diversity_selector_must_keep_best_path_and_aggregates() -> anyhow::Result<()> {
+        let me = pubkey(&SECRET_0);
+        let [a, b] = [pubkey(&SECRET_1), pubkey(&SECRET_2)];
+        let dest = pubkey(&SECRET_4);
+
+        let selector = DiversityConstrainedPathSelector::new(
+            FixedSelector(vec![make_path(vec![b, dest], 0.4), make_path(vec![a, dest], 0.8)]),
+            NonZeroUsize::MIN,
+        );
+
+        let paths = selector.select_path(me, dest, 1)?;
+        assert_eq!(paths.len(), 2, "the destination itself must not be constrained");
+        assert_eq!(paths[0].path, vec![a, dest]);
+        assert_eq!(paths[0].total_latency_ms, Some(100));
+        assert_eq!(paths[0].capacity_floor, Some(1000));
+
+        Ok(())
+    }
+
+    #[test]
+    fn diversity_selector_must_propagate_inner_errors() {
+        let me = pubkey(&SECRET_0);
+        let dest = pubkey(&SECRET_4);
+        let selector = DiversityConstrainedPathSelector::new(FixedSelector(vec![]), NonZeroUsize::MIN);
+
+        assert!(matches!(
+            selector.select_path(me, dest, 1),
+            Err(PathPlannerError::Path(PathError::PathNotFound(..)))
+        ));
+    }
+}
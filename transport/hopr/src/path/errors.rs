@@ -19,6 +19,9 @@ pub enum PathPlannerError {
     #[error("api: {0}")]
     Api(String),
 
+    #[error("invalid relay policy: {0}")]
+    InvalidRelayPolicy(#[from] validator::ValidationErrors),
+
     #[error("cache error: {0}")]
     CacheError(#[from] Arc<Self>),
 }
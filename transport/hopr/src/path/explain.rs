@@ -0,0 +1,60 @@
+use hopr_api::types::internal::prelude::ValidatedPath;
+
+use super::traits::{PathWithMetrics, PruneReason};
+
+/// Why a candidate path found by the [`PathSelector`](super::PathSelector) is not
+/// among the cached paths of the [`PathPlanner`](super::PathPlanner).
+#[derive(Debug, Clone, PartialEq, Eq, strum::Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum RejectionReason {
+    /// Discarded by the path selector itself.
+    #[strum(to_string = "pruned: {0}")]
+    Pruned(PruneReason),
+    /// Forbidden by the [`RelayPolicy`](super::RelayPolicy).
+    RelayPolicy,
+    /// Failed the validation against the chain, e.g. because a channel along the path is not open.
+    #[strum(to_string = "chain validation: {0}")]
+    ChainValidation(String),
+}
+
+/// A candidate path found by the path selector that is not among the cached paths.
+#[derive(Debug, Clone)]
+pub struct RejectedCandidate {
+    /// The rejected candidate.
+    pub candidate: PathWithMetrics,
+    /// Why the candidate was rejected.
+    pub reason: RejectionReason,
+}
+
+/// How the selection weight of a cached path was obtained.
+///
+/// The final `weight` is the product of the path `cost` (see [`PathWithMetrics::cost`])
+/// and the `latency_factor`, `capacity_factor` and `policy_multiplier`.
+#[derive(Debug, Clone)]
+pub struct CandidateExplanation {
+    /// The validated path.
+    pub path: ValidatedPath,
+    /// Cost and per-path aggregates reported by the path selector.
+    pub metrics: PathWithMetrics,
+    /// Factor in `(0.0, 1.0]` derived from the total path latency, `1.0` if unknown.
+    pub latency_factor: f64,
+    /// Factor in `[0.05, 1.0]` derived from the path capacity floor, `1.0` if unknown or for 0-hop paths.
+    pub capacity_factor: f64,
+    /// Product of the [`RelayPolicy`](super::RelayPolicy) weights of the path relays.
+    pub policy_multiplier: f64,
+    /// The final selection weight.
+    pub weight: f64,
+    /// Probability that this path is picked for a packet.
+    pub probability: f64,
+}
+
+/// Snapshot of a single [`PathPlanner`](super::PathPlanner) cache entry.
+///
+/// Obtained via [`PathPlanner::explain`](super::PathPlanner::explain).
+#[derive(Debug, Clone, Default)]
+pub struct PathCacheExplanation {
+    /// The cached paths, in the order they are sampled from.
+    pub candidates: Vec<CandidateExplanation>,
+    /// The found candidates that did not make it into the cache.
+    pub rejected: Vec<RejectedCandidate>,
+}
@@ -0,0 +1,412 @@
+use std::collections::HashSet;
+
+use hopr_api::{
+    OffchainPublicKey,
+    graph::{NetworkGraphConnectivity, traits::EdgeObservableRead},
+    types::internal::errors::PathError,
+};
+
+use super::{
+    errors::{PathPlannerError, Result},
+    scored_graph::ScoredGraph,
+    selector::partition_for_consistency,
+    traits::{PathSelection, PathSelector, PathWithMetrics},
+};
+
+/// A path from `src` to `dest` including both endpoints, paired with its cost.
+type FullPath = (Vec<OffchainPublicKey>, f64);
+
+/// Maximum number of partial paths expanded by a single [`best_completion`] search.
+///
+/// Bounds the otherwise exponential search in dense graphs. Once exhausted, the best
+/// completion found so far is used.
+const MAX_EXPANSIONS_PER_SEARCH: usize = 10_000;
+
+/// Finds the best completion of `prefix` (which starts at `src`) to a path of the queried length.
+///
+/// Depth-first branch-and-bound search: since every edge score is at most `1.0`, the cost
+/// can only decrease along a path, so any partial path not better than the best complete
+/// path found so far is abandoned. Nodes already on the `prefix` and edges in `banned` are
+/// never used, which keeps the result cycle-free.
+///
+/// At most `budget` partial paths are expanded, the budget is decreased accordingly.
+fn best_completion<W>(
+    graph: &ScoredGraph<W>,
+    prefix: &mut Vec<OffchainPublicKey>,
+    cost: f64,
+    banned: &HashSet<(OffchainPublicKey, OffchainPublicKey)>,
+    best: &mut Option<FullPath>,
+    budget: &mut usize,
+) where
+    W: EdgeObservableRead + Send + 'static,
+{
+    let index = prefix.len() - 1;
+    if index == graph.length() {
+        if best.as_ref().is_none_or(|(_, best_cost)| cost > *best_cost) {
+            *best = Some((prefix.clone(), cost));
+        }
+        return;
+    }
+
+    if *budget == 0 {
+        return;
+    }
+    *budget -= 1;
+
+    let current = prefix[index];
+    let mut successors = graph.successors(&current, index);
+    // Visit the most promising edges first to tighten the bound early.
+    successors.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+    for (next, score) in successors {
+        let next_cost = cost * score;
+        if prefix.contains(&next)
+            || banned.contains(&(current, next))
+            || best.as_ref().is_some_and(|(_, best_cost)| next_cost <= *best_cost)
+        {
+            continue;
+        }
+        prefix.push(next);
+        best_completion(graph, prefix, next_cost, banned, best, budget);
+        prefix.pop();
+    }
+}
+
+/// Yen's algorithm restricted to paths of the queried length.
+///
+/// Returns up to `k` distinct cycle-free paths ordered from the best (highest cost) down.
+fn k_best_paths<W>(graph: &ScoredGraph<W>, k: usize) -> Vec<FullPath>
+where
+    W: EdgeObservableRead + Send + 'static,
+{
+    let mut first = None;
+    let mut budget = MAX_EXPANSIONS_PER_SEARCH;
+    best_completion(
+        graph,
+        &mut vec![graph.src()],
+        1.0,
+        &HashSet::new(),
+        &mut first,
+        &mut budget,
+    );
+
+    let mut accepted: Vec<FullPath> = first.into_iter().collect();
+    let mut candidates: Vec<FullPath> = Vec::new();
+
+    while !accepted.is_empty() && accepted.len() < k {
+        let (last, _) = accepted[accepted.len() - 1].clone();
+
+        // Every node but the destination can be a spur node.
+        for spur in 0..last.len() - 1 {
+            let root = &last[..=spur];
+            let Some(root_cost) = graph.prefix_cost(root) else {
+                continue;
+            };
+
+            // Forbid the edges leaving the spur node that previously found paths sharing
+            // this root already took.
+            let banned = accepted
+                .iter()
+                .filter(|(path, _)| path.starts_with(root))
+                .map(|(path, _)| (path[spur], path[spur + 1]))
+                .collect::<HashSet<_>>();
+
+            let mut spur_path = None;
+            let mut budget = MAX_EXPANSIONS_PER_SEARCH;
+            best_completion(
+                graph,
+                &mut root.to_vec(),
+                root_cost,
+                &banned,
+                &mut spur_path,
+                &mut budget,
+            );
+
+            if let Some(candidate) = spur_path
+                && !accepted.iter().any(|(path, _)| *path == candidate.0)
+                && !candidates.iter().any(|(path, _)| *path == candidate.0)
+            {
+                candidates.push(candidate);
+            }
+        }
+
+        let Some(best) = candidates
+            .iter()
+            .enumerate()
+            .max_by(|(_, (_, a)), (_, (_, b))| a.total_cmp(b))
+            .map(|(i, _)| i)
+        else {
+            break;
+        };
+        accepted.push(candidates.swap_remove(best));
+    }
+
+    accepted
+}
+
+/// A path selector returning the `k` best paths using Yen's k-shortest-paths algorithm.
+///
+/// Path cost is the product of edge scores, so the "shortest" paths are those with the
+/// highest cost (equivalently, the lowest sum of `-ln(score)`). In contrast to
+/// [`HoprGraphPathSelector`](super::HoprGraphPathSelector), which returns the first
+/// `max_paths` simple paths found by the traversal, this selector always returns the
+/// `max_paths` best ones.
+///
+/// Returned candidates carry the same per-path aggregates as those of
+/// `HoprGraphPathSelector` and are pruned with the same anonymity floor.
+#[derive(Clone)]
+pub struct KShortestPathSelector<G> {
+    me: OffchainPublicKey,
+    graph: G,
+    max_paths: usize,
+    edge_penalty: f64,
+    min_ack_rate: f64,
+    anonymity_floor: usize,
+}
+
+impl<G> KShortestPathSelector<G>
+where
+    G: NetworkGraphConnectivity<NodeId = OffchainPublicKey> + Clone + Send + Sync + 'static,
+    <G as NetworkGraphConnectivity>::Observed: 'static,
+{
+    /// Create a new selector.
+    ///
+    /// * `me` – the planner's own offchain public key, used to determine path direction.
+    /// * `graph` – the network graph to query.
+    /// * `max_paths` – the number `k` of best candidate paths to return per query.
+    /// * `edge_penalty` – penalty multiplier for edges lacking probe-based quality observations.
+    /// * `min_ack_rate` – minimum acceptable message acknowledgment rate for path selection.
+    /// * `anonymity_floor` – minimum candidate count below which no latency-based pruning occurs.
+    pub fn new(
+        me: OffchainPublicKey,
+        graph: G,
+        max_paths: usize,
+        edge_penalty: f64,
+        min_ack_rate: f64,
+        anonymity_floor: usize,
+    ) -> Self {
+        Self {
+            me,
+            graph,
+            max_paths,
+            edge_penalty,
+            min_ack_rate,
+            anonymity_floor,
+        }
+    }
+}
+
+impl<G> PathSelector for KShortestPathSelector<G>
+where
+    G: NetworkGraphConnectivity<NodeId = OffchainPublicKey> + Clone + Send + Sync + 'static,
+    <G as NetworkGraphConnectivity>::Observed: 'static,
+{
+    fn select_path(
+        &self,
+        src: OffchainPublicKey,
+        dest: OffchainPublicKey,
+        hops: usize,
+    ) -> Result<Vec<PathWithMetrics>> {
+        self.select_path_explained(src, dest, hops)
+            .map(|selection| selection.selected)
+    }
+
+    #[tracing::instrument(level = "trace", skip(self), fields(src = %src, dest = %dest, hops), err)]
+    fn select_path_explained(
+        &self,
+        src: OffchainPublicKey,
+        dest: OffchainPublicKey,
+        hops: usize,
+    ) -> Result<PathSelection> {
+        let graph = ScoredGraph::new(
+            self.graph.connected_edges(),
+            self.me,
+            src,
+            dest,
+            hops,
+            self.edge_penalty,
+            self.min_ack_rate,
+        );
+
+        let paths = k_best_paths(&graph, self.max_paths)
+            .into_iter()
+            .filter_map(|(path, _)| graph.evaluate(&path[1..]))
+            .collect::<Vec<_>>();
+        tracing::debug!(%src, %dest, hops, count = paths.len(), "k-shortest candidates");
+
+        if paths.is_empty() {
+            Err(PathPlannerError::Path(PathError::PathNotFound(
+                hops,
+                src.to_string(),
+                dest.to_string(),
+            )))
+        } else {
+            Ok(partition_for_consistency(paths, self.anonymity_floor, hops))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use anyhow::Context;
+    use hopr_api::graph::{
+        NetworkGraphWrite,
+        traits::{EdgeObservableWrite, EdgeWeightType},
+    };
+    use hopr_network_graph::ChannelGraph;
+
+    use super::*;
+    use crate::path::{
+        PathPlannerConfig,
+        test::{SECRET_0, SECRET_1, SECRET_2, SECRET_3, SECRET_4, pubkey},
+    };
+
+    /// Mark an edge as ready for intermediate routing with the given probe latency.
+    fn mark_edge(graph: &ChannelGraph, src: &OffchainPublicKey, dst: &OffchainPublicKey, latency_ms: u64) {
+        graph.upsert_edge(src, dst, |obs| {
+            obs.record(EdgeWeightType::Connected(true));
+            obs.record(EdgeWeightType::Immediate(Ok(Duration::from_millis(latency_ms))));
+            obs.record(EdgeWeightType::Intermediate(Ok(Duration::from_millis(latency_ms))));
+            obs.record(EdgeWeightType::Capacity(Some(1000)));
+        });
+    }
+
+    fn add_edge(graph: &ChannelGraph, src: &OffchainPublicKey, dst: &OffchainPublicKey) {
+        graph.add_edge(src, dst).unwrap();
+        mark_edge(graph, src, dst, 50);
+    }
+
+    fn test_selector(me: OffchainPublicKey, graph: ChannelGraph, k: usize) -> KShortestPathSelector<ChannelGraph> {
+        let cfg = PathPlannerConfig::default();
+        KShortestPathSelector::new(me, graph, k, cfg.edge_penalty, cfg.min_ack_rate, 0)
+    }
+
+    /// me → {a, b, c} → dest, plus a → b and b → c shortcuts for 2-hop paths.
+    fn mesh() -> (
+        OffchainPublicKey,
+        [OffchainPublicKey; 3],
+        OffchainPublicKey,
+        ChannelGraph,
+    ) {
+        let me = pubkey(&SECRET_0);
+        let relays = [pubkey(&SECRET_1), pubkey(&SECRET_2), pubkey(&SECRET_3)];
+        let dest = pubkey(&SECRET_4);
+        let graph = ChannelGraph::new(me);
+        for n in relays.iter().chain([&dest]) {
+            graph.add_node(*n);
+        }
+        for relay in &relays {
+            add_edge(&graph, &me, relay);
+            add_edge(&graph, relay, &dest);
+        }
+        add_edge(&graph, &relays[0], &relays[1]);
+        add_edge(&graph, &relays[1], &relays[2]);
+        (me, relays, dest, graph)
+    }
+
+    #[test]
+    fn k_shortest_must_return_at_most_k_distinct_cycle_free_paths() -> anyhow::Result<()> {
+        let (me, _, dest, graph) = mesh();
+        let selector = test_selector(me, graph, 2);
+
+        let paths = selector.select_path(me, dest, 1).context("1-hop paths")?;
+        assert_eq!(paths.len(), 2);
+        assert_ne!(paths[0].path, paths[1].path);
+        for pwm in &paths {
+            assert_eq!(pwm.path.len(), 2);
+            assert_eq!(pwm.path.last(), Some(&dest));
+            assert!(!pwm.path.contains(&me));
+            assert!(pwm.cost > 0.0);
+            assert!(pwm.total_latency_ms.is_some(), "aggregates must be reported");
+            assert_eq!(pwm.capacity_floor, Some(1000));
+        }
+
+        let paths = selector.select_path(me, dest, 2).context("2-hop paths")?;
+        assert_eq!(paths.len(), 2);
+        for pwm in &paths {
+            assert_eq!(pwm.path.len(), 3);
+            let unique: HashSet<_> = pwm.path.iter().collect();
+            assert_eq!(unique.len(), 3, "path must be cycle-free");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn k_shortest_must_return_paths_in_descending_cost_order() -> anyhow::Result<()> {
+        let (me, relays, dest, graph) = mesh();
+        // Degrade the edge into the first relay so that paths through it rank last.
+        for _ in 0..10 {
+            mark_edge(&graph, &me, &relays[0], 500);
+        }
+        let selector = test_selector(me, graph, 10);
+
+        let paths = selector.select_path(me, dest, 1).context("1-hop paths")?;
+        assert_eq!(paths.len(), 3, "all three 1-hop paths exist");
+        assert!(paths.windows(2).all(|w| w[0].cost >= w[1].cost));
+        assert_eq!(paths[2].path[0], relays[0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn k_shortest_must_find_return_paths() -> anyhow::Result<()> {
+        let (me, relays, dest, graph) = mesh();
+        for relay in &relays {
+            add_edge(&graph, &dest, relay);
+            add_edge(&graph, relay, &me);
+        }
+        let selector = test_selector(me, graph, 10);
+
+        let paths = selector.select_path(dest, me, 1).context("return paths")?;
+        assert_eq!(paths.len(), 3);
+        for pwm in &paths {
+            assert_eq!(pwm.path.last(), Some(&me));
+            assert!(!pwm.path.contains(&dest));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn best_completion_must_stop_when_the_budget_is_exhausted() {
+        let (me, _, dest, graph) = mesh();
+        let cfg = PathPlannerConfig::default();
+        let graph = ScoredGraph::new(
+            graph.connected_edges(),
+            me,
+            me,
+            dest,
+            2,
+            cfg.edge_penalty,
+            cfg.min_ack_rate,
+        );
+
+        let mut best = None;
+        let mut budget = 2;
+        best_completion(&graph, &mut vec![me], 1.0, &HashSet::new(), &mut best, &mut budget);
+        assert_eq!(budget, 0);
+        assert!(best.is_none(), "a 2-hop path needs more than 2 expansions");
+
+        let mut budget = MAX_EXPANSIONS_PER_SEARCH;
+        best_completion(&graph, &mut vec![me], 1.0, &HashSet::new(), &mut best, &mut budget);
+        assert!(budget > 0);
+        assert!(best.is_some_and(|(path, _)| path.len() == 4 && path.last() == Some(&dest)));
+    }
+
+    #[test]
+    fn k_shortest_must_fail_when_destination_unreachable() {
+        let me = pubkey(&SECRET_0);
+        let dest = pubkey(&SECRET_1);
+        let graph = ChannelGraph::new(me);
+        graph.add_node(dest);
+        let selector = test_selector(me, graph, 4);
+
+        assert!(matches!(
+            selector.select_path(dest, me, 1),
+            Err(PathPlannerError::Path(PathError::PathNotFound(..)))
+        ));
+    }
+}
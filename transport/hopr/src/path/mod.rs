@@ -3,18 +3,40 @@
 //! This crate provides:
 //! - [`traits::PathSelector`][crate::path::traits::PathSelector]: Trait for selecting multi-hop paths through the
 //!   network.
+//! - [`HoprGraphPathSelector`], [`KShortestPathSelector`], [`RandomWalkPathSelector`] and
+//!   [`DiversityConstrainedPathSelector`]: the available [`PathSelector`][crate::path::traits::PathSelector]
+//!   implementations.
 //! - [`PathPlanner`]: Resolves `DestinationRouting` to `ResolvedTransportRouting`, delegating path discovery to any
 //!   [`PathSelector`][crate::path::traits::PathSelector] implementation and maintaining a `moka`-backed cache of
 //!   fully-validated `ValidatedPath` objects keyed by `(source, destination, options)`.
+//! - [`RelayPolicy`]: Runtime-updatable deny-list, allow-list, per-relay weights and Safe-distinctness rule applied by
+//!   the [`PathPlanner`] to the selected candidates.
+//! - [`PathCacheExplanation`]: Introspection of a planner cache entry, with the weight breakdown of each cached path
+//!   and the reasons why the other found candidates were rejected.
 //! - [`PathPlannerConfig`][crate::path::PathPlannerConfig]: Configuration for the planner's cache and background
 //!   refresh.
 
+pub mod diversity;
 pub mod errors;
+pub mod explain;
+pub mod k_shortest;
 pub mod planner;
+pub mod policy;
+pub mod random_walk;
+mod scored_graph;
 pub mod selector;
+#[cfg(test)]
+mod test;
 pub mod traits;
 
+pub use diversity::DiversityConstrainedPathSelector;
 pub use errors::{PathPlannerError, Result};
-pub use planner::{PathPlanner, PathPlannerConfig};
+pub use explain::{CandidateExplanation, PathCacheExplanation, RejectedCandidate, RejectionReason};
+pub use k_shortest::KShortestPathSelector;
+pub use planner::{PathPlanner, PathPlannerConfig, RelaySafes};
+pub use policy::RelayPolicy;
+pub use random_walk::RandomWalkPathSelector;
 pub use selector::HoprGraphPathSelector;
-pub use traits::{BackgroundPathCacheRefreshable, PathSelector, PathWithMetrics};
+pub use traits::{
+    BackgroundPathCacheRefreshable, PathSelection, PathSelector, PathWithMetrics, PruneReason, PrunedPath,
+};
@@ -9,7 +9,7 @@ use hopr_api::{
     types::{
         crypto::crypto_traits::Randomizable,
         internal::{errors::PathError, prelude::*},
-        primitive::traits::ToHex,
+        primitive::{prelude::Address, traits::ToHex},
     },
 };
 use hopr_crypto_packet::prelude::*;
@@ -19,7 +19,9 @@ use validator::{Validate, ValidationError};
 
 use super::{
     errors::{PathPlannerError, Result},
-    traits::{BackgroundPathCacheRefreshable, PathSelector, PathWithMetrics},
+    explain::{CandidateExplanation, PathCacheExplanation, RejectedCandidate, RejectionReason},
+    policy::{ActiveRelayPolicy, RelayPolicy},
+    traits::{BackgroundPathCacheRefreshable, PathSelection, PathSelector, PathWithMetrics},
 };
 
 #[cfg(all(feature = "telemetry", not(test)))]
@@ -117,14 +119,13 @@ fn capacity_factor(c: u128, reference: u128) -> f64 {
     (log / ref_log).clamp(0.05, 1.0)
 }
 
-/// Composite selection weight for a candidate path.
+/// Latency and capacity factors of the composite weight of a candidate path.
 ///
-/// Refines `pwc.cost` (the `EdgeValueFn` output) with latency and capacity factors
-/// derived from the per-path aggregates.  Factors are neutral (1.0) when the
-/// corresponding aggregate is unavailable to avoid penalising unprobed paths.
-/// For 0-hop routes (`hops == 0`) the capacity factor is always 1.0 — direct
-/// `me -> dest` packets use no payment channel, so `capacity_floor = None` is expected.
-fn composite_weight(pwc: &PathWithMetrics, hops: usize, params: WeightingParams) -> f64 {
+/// Factors are neutral (1.0) when the corresponding aggregate is unavailable to avoid
+/// penalising unprobed paths. For 0-hop routes (`hops == 0`) the capacity factor is
+/// always 1.0 — direct `me -> dest` packets use no payment channel, so
+/// `capacity_floor = None` is expected.
+fn weight_factors(pwc: &PathWithMetrics, hops: usize, params: WeightingParams) -> (f64, f64) {
     let lat = pwc
         .total_latency_ms
         .map(|ms| latency_factor(Duration::from_millis(ms as u64), params.latency_halflife))
@@ -136,6 +137,15 @@ fn composite_weight(pwc: &PathWithMetrics, hops: usize, params: WeightingParams)
             .map(|c| capacity_factor(c, params.capacity_reference))
             .unwrap_or(1.0)
     };
+    (lat, cap)
+}
+
+/// Composite selection weight for a candidate path.
+///
+/// Refines `pwc.cost` (the `EdgeValueFn` output) with the latency and capacity factors
+/// derived from the per-path aggregates (see [`weight_factors`]).
+fn composite_weight(pwc: &PathWithMetrics, hops: usize, params: WeightingParams) -> f64 {
+    let (lat, cap) = weight_factors(pwc, hops, params);
     pwc.cost * lat * cap
 }
 
@@ -144,7 +154,150 @@ fn composite_weight(pwc: &PathWithMetrics, hops: usize, params: WeightingParams)
 /// Only the `Hops` variant of [`RoutingOptions`] is cached (explicit intermediate
 /// paths bypass the cache), so the key stores the hop count as a plain `u32`.
 type PlannerCacheKey = (NodeId, NodeId, u32);
-type PlannerCacheValue = Arc<hopr_utils::statistics::WeightedCollection<ValidatedPath>>;
+type PlannerCacheValue = Arc<CachedPaths>;
+
+/// The validated paths cached for a single [`PlannerCacheKey`], with the explanation of
+/// how they were obtained.
+struct CachedPaths {
+    paths: hopr_utils::statistics::WeightedCollection<ValidatedPath>,
+    explanation: PathCacheExplanation,
+}
+
+/// Queries the `selector` for candidate paths, filters them by the relay `policy`,
+/// validates them against the chain and computes their selection weights.
+///
+/// Every found candidate that does not make it into the result is recorded in the
+/// explanation, together with the reason.
+#[allow(clippy::too_many_arguments)]
+async fn plan_paths<R, S>(
+    selector: &S,
+    resolver: &R,
+    policy: &parking_lot::RwLock<ActiveRelayPolicy>,
+    weighting: WeightingParams,
+    source: NodeId,
+    src_key: OffchainPublicKey,
+    dest_key: OffchainPublicKey,
+    hops: usize,
+) -> Result<CachedPaths>
+where
+    R: ChainKeyOperations + ChainReadChannelOperations + Send + Sync + 'static,
+    S: PathSelector,
+{
+    let PathSelection { selected, pruned } = selector.select_path_explained(src_key, dest_key, hops)?;
+    let mut rejected: Vec<RejectedCandidate> = pruned
+        .into_iter()
+        .map(|pruned| RejectedCandidate {
+            candidate: pruned.candidate,
+            reason: RejectionReason::Pruned(pruned.reason),
+        })
+        .collect();
+
+    let permitted = {
+        let policy = policy.read();
+        selected
+            .into_iter()
+            .filter_map(|pwc| match policy.path_multiplier(&pwc.path) {
+                Some(multiplier) => Some((pwc, multiplier)),
+                None => {
+                    rejected.push(RejectedCandidate {
+                        candidate: pwc,
+                        reason: RejectionReason::RelayPolicy,
+                    });
+                    None
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let chain_resolver = ChainPathResolver::from(resolver);
+    let mut valid_paths: Vec<(ValidatedPath, f64)> = Vec::with_capacity(permitted.len());
+    let mut candidates: Vec<CandidateExplanation> = Vec::with_capacity(permitted.len());
+    for (pwc, policy_multiplier) in permitted {
+        let node_ids = pwc.path.iter().copied().map(NodeId::Offchain).collect::<Vec<_>>();
+        match ValidatedPath::new(source, node_ids, &chain_resolver).await {
+            Ok(path) => {
+                let (latency_factor, capacity_factor) = weight_factors(&pwc, hops, weighting);
+                let weight = composite_weight(&pwc, hops, weighting) * policy_multiplier;
+                valid_paths.push((path.clone(), weight));
+                candidates.push(CandidateExplanation {
+                    path,
+                    metrics: pwc,
+                    latency_factor,
+                    capacity_factor,
+                    policy_multiplier,
+                    weight,
+                    probability: 0.0,
+                });
+            }
+            Err(e) => {
+                tracing::debug!(error = %e, "path candidate failed validation");
+                rejected.push(RejectedCandidate {
+                    candidate: pwc,
+                    reason: RejectionReason::ChainValidation(e.to_string()),
+                });
+            }
+        }
+    }
+
+    if valid_paths.is_empty() {
+        return Err(PathPlannerError::Path(PathError::PathNotFound(
+            hops,
+            src_key.to_hex(),
+            dest_key.to_hex(),
+        )));
+    }
+
+    let paths = hopr_utils::statistics::WeightedCollection::new(valid_paths);
+    for candidate in candidates.iter_mut() {
+        candidate.probability = paths.probability_of(candidate.weight);
+        let pwm = &candidate.metrics;
+        tracing::debug!(
+            destination = %dest_key,
+            hops,
+            path = %candidate.path,
+            cost = pwm.cost,
+            composite_weight = candidate.weight,
+            sampling_probability = candidate.probability,
+            total_latency_ms = ?pwm.total_latency_ms,
+            min_probe_success_rate = ?pwm.min_probe_success_rate,
+            min_ack_rate = ?pwm.min_ack_rate,
+            capacity_floor = ?pwm.capacity_floor,
+            "weighted candidate path",
+        );
+    }
+    if !rejected.is_empty() {
+        trace!(destination = %dest_key, hops, rejected = rejected.len(), "rejected candidate paths");
+    }
+
+    Ok(CachedPaths {
+        paths,
+        explanation: PathCacheExplanation { candidates, rejected },
+    })
+}
+
+/// Records the Safes controlling the relays of a [`PathPlanner`].
+///
+/// Obtained via [`PathPlanner::relay_safes`] and meant to be fed from the on-chain
+/// account announcements, so that [`RelayPolicy::distinct_safes`] follows the chain.
+#[derive(Clone)]
+pub struct RelaySafes {
+    relay_policy: Arc<parking_lot::RwLock<ActiveRelayPolicy>>,
+    cache: moka::future::Cache<PlannerCacheKey, PlannerCacheValue>,
+}
+
+impl RelaySafes {
+    /// Records the Safe controlling the `relay`, or that it has none.
+    ///
+    /// If the Safe changes while [`RelayPolicy::distinct_safes`] is applied, the path cache
+    /// is invalidated, since paths may have become admissible or forbidden.
+    pub fn update(&self, relay: OffchainPublicKey, safe: Option<Address>) {
+        let mut active = self.relay_policy.write();
+        if active.update_safe(relay, safe) && active.policy.distinct_safes {
+            tracing::debug!(%relay, "Safe of a relay changed, invalidating the entire path cache");
+            self.cache.invalidate_all();
+        }
+    }
+}
 
 /// Path planner that resolves [`DestinationRouting`] to [`ResolvedTransportRouting`].
 ///
@@ -153,10 +306,16 @@ type PlannerCacheValue = Arc<hopr_utils::statistics::WeightedCollection<Validate
 /// their traversal cost, keyed by `(source: NodeId, destination: NodeId, hops: u32)`.
 ///
 /// On a cache miss the planner calls the selector, validates every candidate against
-/// the chain resolver, and stores an `Arc<WeightedCollection<ValidatedPath>>` in the
+/// the chain resolver, and stores a `WeightedCollection<ValidatedPath>` in the
 /// cache. On a cache hit a candidate is picked via weighted random selection (higher
 /// cost = higher quality = higher probability).
 ///
+/// The cache contents, including the weight breakdown of every cached path and the
+/// candidates that were rejected, can be inspected via [`PathPlanner::explain`].
+///
+/// Candidates are filtered and re-weighted by the runtime-updatable [`RelayPolicy`]
+/// before validation; see [`PathPlanner::set_relay_policy`].
+///
 /// A background sweep (`background_refresh`) can be spawned to
 /// proactively re-warm the cache for all previously-seen keys.
 #[derive(Clone)]
@@ -168,6 +327,7 @@ pub struct PathPlanner<Surb, R, S> {
     cache: moka::future::Cache<PlannerCacheKey, PlannerCacheValue>,
     refresh_period: Duration,
     weighting: WeightingParams,
+    relay_policy: Arc<parking_lot::RwLock<ActiveRelayPolicy>>,
 }
 
 impl<Surb, R, S> PathPlanner<Surb, R, S>
@@ -196,9 +356,82 @@ where
                 latency_halflife: config.latency_halflife,
                 capacity_reference: config.capacity_reference,
             },
+            relay_policy: Default::default(),
         }
     }
 
+    /// Returns the currently applied [`RelayPolicy`].
+    pub fn relay_policy(&self) -> RelayPolicy {
+        self.relay_policy.read().policy.clone()
+    }
+
+    /// Replaces the applied [`RelayPolicy`].
+    ///
+    /// The Safes controlling the relays are kept and updated via [`PathPlanner::relay_safes`].
+    ///
+    /// Cached entries containing a path whose admissibility or weight changes are invalidated.
+    /// If the new policy permits a path the previous one forbade, the whole cache is invalidated,
+    /// since any query could gain new candidates.
+    pub async fn set_relay_policy(&self, policy: RelayPolicy) -> Result<()> {
+        policy.validate()?;
+
+        let (previous, new) = {
+            let mut active = self.relay_policy.write();
+            let previous = std::mem::replace(&mut active.policy, policy);
+            let previous = ActiveRelayPolicy {
+                policy: previous,
+                safes: active.safes.clone(),
+            };
+            (previous, active.clone())
+        };
+
+        if new.relaxes(&previous) {
+            tracing::debug!("relay policy relaxed, invalidating the entire path cache");
+            self.cache.invalidate_all();
+            return Ok(());
+        }
+
+        let mut invalidated = 0_usize;
+        for (key, cached) in self.cache.iter() {
+            if cached
+                .paths
+                .iter()
+                .any(|(path, _)| previous.path_multiplier(path) != new.path_multiplier(path))
+            {
+                self.cache.invalidate(key.as_ref()).await;
+                invalidated += 1;
+            }
+        }
+        tracing::debug!(invalidated, "relay policy updated");
+
+        Ok(())
+    }
+
+    /// Returns a handle recording the Safes controlling the relays, as consulted by
+    /// [`RelayPolicy::distinct_safes`].
+    pub fn relay_safes(&self) -> RelaySafes {
+        RelaySafes {
+            relay_policy: self.relay_policy.clone(),
+            cache: self.cache.clone(),
+        }
+    }
+
+    /// Lists the `(source, destination, hops)` keys currently in the path cache.
+    pub fn cached_routes(&self) -> Vec<(NodeId, NodeId, u32)> {
+        self.cache.iter().map(|(key, _)| *key).collect()
+    }
+
+    /// Explains the cached paths from `source` to `destination` with `hops` relays.
+    ///
+    /// Returns `None` if there is no such entry in the cache. The cache is never populated
+    /// by this call.
+    pub async fn explain(&self, source: NodeId, destination: NodeId, hops: u32) -> Option<PathCacheExplanation> {
+        self.cache
+            .get(&(source, destination, hops))
+            .await
+            .map(|cached| cached.explanation.clone())
+    }
+
     /// Resolve a [`NodeId`] to an [`OffchainPublicKey`].
     async fn resolve_node_id_to_offchain_key(&self, node_id: &NodeId) -> Result<OffchainPublicKey> {
         match node_id {
@@ -216,6 +449,103 @@ where
         }
     }
 
+    /// Returns the cached paths from `source` to `destination` with `hops` relays,
+    /// populating the cache entry on a miss.
+    ///
+    /// The offchain keys of `source` and `destination` are returned as well.
+    async fn cached_paths(
+        &self,
+        source: NodeId,
+        destination: NodeId,
+        hops: usize,
+    ) -> Result<(PlannerCacheValue, OffchainPublicKey, OffchainPublicKey)> {
+        let src_key = self.resolve_node_id_to_offchain_key(&source).await?;
+        let dest_key = self.resolve_node_id_to_offchain_key(&destination).await?;
+
+        let cache_key: PlannerCacheKey = (source, destination, hops as u32);
+
+        let resolver = self.resolver.clone();
+        let selector = self.selector.clone();
+        let weighting = self.weighting;
+        let relay_policy = self.relay_policy.clone();
+
+        let cached = self
+            .cache
+            .try_get_with(cache_key, async move {
+                trace!(hops, "path cache miss, querying selector");
+                plan_paths(
+                    &*selector,
+                    &*resolver,
+                    &relay_policy,
+                    weighting,
+                    source,
+                    src_key,
+                    dest_key,
+                    hops,
+                )
+                .await
+                .map(Arc::new)
+            })
+            .await
+            .map_err(PathPlannerError::CacheError)?;
+
+        Ok((cached, src_key, dest_key))
+    }
+
+    /// Selects up to `count` distinct paths from `source` to `destination` satisfying the `options`.
+    ///
+    /// Paths are drawn from the planner cache by weighted random sampling, preferring paths that
+    /// share no relay with the already selected ones. Fewer paths are returned if not enough distinct
+    /// paths are known. An explicit [`RoutingOptions::IntermediatePath`] or a 0-hop route always
+    /// yields a single path.
+    ///
+    /// This is used to pick the independent paths of a multipath Session.
+    pub async fn select_paths(
+        &self,
+        source: NodeId,
+        destination: NodeId,
+        options: RoutingOptions,
+        count: usize,
+    ) -> Result<Vec<ValidatedPath>> {
+        let hops: usize = match &options {
+            RoutingOptions::Hops(hops) if u32::from(*hops) > 0 => (*hops).into(),
+            _ => return Ok(vec![self.resolve_path(source, destination, options).await?]),
+        };
+
+        let (cached, src_key, dest_key) = self.cached_paths(source, destination, hops).await?;
+        let candidates =
+            hopr_utils::statistics::WeightedCollection::new(cached.paths.iter().cloned().collect()).into_shuffled();
+
+        let relays = |path: &ValidatedPath| path[..path.len() - 1].to_vec();
+        let mut selected: Vec<ValidatedPath> = Vec::with_capacity(count);
+        let mut remaining = Vec::new();
+        for path in candidates {
+            if selected.len() == count {
+                break;
+            }
+            let path_relays = relays(&path);
+            if selected
+                .iter()
+                .all(|other| relays(other).iter().all(|relay| !path_relays.contains(relay)))
+            {
+                selected.push(path);
+            } else {
+                remaining.push(path);
+            }
+        }
+        // Not enough relay-disjoint paths, so fill up with the remaining distinct ones
+        selected.extend(remaining.into_iter().take(count.saturating_sub(selected.len())));
+
+        if selected.is_empty() {
+            return Err(PathPlannerError::Path(PathError::PathNotFound(
+                hops,
+                src_key.to_hex(),
+                dest_key.to_hex(),
+            )));
+        }
+        Ok(selected)
+    }
+
     #[tracing::instrument(level = "trace", skip(self))]
     async fn resolve_path(
         &self,
@@ -254,68 +584,8 @@ where
                 let hops_usize: usize = hops.into();
                 trace!(hops = hops_usize, "resolving path via planner cache");
 
-                let src_key = self.resolve_node_id_to_offchain_key(&source).await?;
-                let dest_key = self.resolve_node_id_to_offchain_key(&destination).await?;
-
-                let cache_key: PlannerCacheKey = (source, destination, u32::from(hops));
-
-                let resolver = self.resolver.clone();
-                let selector = self.selector.clone();
-                let weighting = self.weighting;
-
-                let paths = self
-                    .cache
-                    .try_get_with(cache_key, async move {
-                        trace!(hops = hops_usize, "path cache miss, querying selector");
-                        let candidates = selector.select_path(src_key, dest_key, hops_usize)?;
-
-                        let chain_resolver = ChainPathResolver::from(&*resolver);
-                        let mut valid_paths: Vec<(ValidatedPath, f64)> = Vec::with_capacity(candidates.len());
-                        let mut path_metrics: Vec<PathWithMetrics> = Vec::with_capacity(candidates.len());
-                        for mut pwc in candidates {
-                            let path_nodes = std::mem::take(&mut pwc.path);
-                            let node_ids: Vec<NodeId> =
-                                path_nodes.into_iter().map(NodeId::Offchain).collect::<Vec<_>>();
-                            match ValidatedPath::new(source, node_ids, &chain_resolver).await {
-                                Ok(vp) => {
-                                    valid_paths.push((vp, composite_weight(&pwc, hops_usize, weighting)));
-                                    path_metrics.push(pwc);
-                                }
-                                Err(e) => tracing::debug!(error = %e, "path candidate failed validation"),
-                            }
-                        }
-
-                        if valid_paths.is_empty() {
-                            return Err(PathPlannerError::Path(PathError::PathNotFound(
-                                hops_usize,
-                                src_key.to_hex(),
-                                dest_key.to_hex(),
-                            )));
-                        }
-
-                        let weighted = hopr_utils::statistics::WeightedCollection::new(valid_paths);
-                        let total_wt = weighted.total_weight();
-                        for ((vp, w), pwm) in weighted.iter().zip(path_metrics.iter()) {
-                            tracing::debug!(
-                                %destination,
-                                hops = hops_usize,
-                                path = %vp,
-                                cost = pwm.cost,
-                                composite_weight = w,
-                                sampling_probability = if total_wt > 0.0 && *w > 0.0 { *w / total_wt } else { 0.0 },
-                                total_latency_ms = ?pwm.total_latency_ms,
-                                min_probe_success_rate = ?pwm.min_probe_success_rate,
-                                min_ack_rate = ?pwm.min_ack_rate,
-                                capacity_floor = ?pwm.capacity_floor,
-                                "weighted candidate path",
-                            );
-                        }
-                        Ok(Arc::new(weighted))
-                    })
-                    .await
-                    .map_err(PathPlannerError::CacheError)?;
-
-                paths.pick_one().ok_or_else(|| {
+                let (cached, src_key, dest_key) = self.cached_paths(source, destination, hops_usize).await?;
+                cached.paths.pick_one().ok_or_else(|| {
                     PathPlannerError::Path(PathError::PathNotFound(hops_usize, src_key.to_hex(), dest_key.to_hex()))
                 })?
             }
@@ -425,6 +695,7 @@ where
         let selector = self.selector.clone();
         let refresh_period = self.refresh_period;
         let weighting = self.weighting;
+        let relay_policy = self.relay_policy.clone();
 
         // run at a non-zero interval
         futures_time::stream::interval(futures_time::time::Duration::from_millis(
@@ -435,6 +706,7 @@ where
             let resolver = resolver.clone();
             let selector = selector.clone();
             let weighting = weighting;
+            let relay_policy = relay_policy.clone();
 
             async move {
                 for (key, _) in cache.iter() {
@@ -464,47 +736,19 @@ where
                     };
 
                     if let (Some(src_key), Some(dest_key)) = (resolve_key(src).await, resolve_key(dest).await)
-                        && let Ok(candidates) = selector.select_path(src_key, dest_key, hops_usize)
+                        && let Ok(cached) = plan_paths(
+                            &*selector,
+                            &*resolver,
+                            &relay_policy,
+                            weighting,
+                            src,
+                            src_key,
+                            dest_key,
+                            hops_usize,
+                        )
+                        .await
                     {
-                        let chain_resolver = ChainPathResolver::from(&*resolver);
-                        let mut valid_paths: Vec<(ValidatedPath, f64)> = Vec::with_capacity(candidates.len());
-                        let mut path_metrics: Vec<PathWithMetrics> = Vec::with_capacity(candidates.len());
-                        for mut pwc in candidates {
-                            let path_nodes = std::mem::take(&mut pwc.path);
-                            let node_ids: Vec<NodeId> =
-                                path_nodes.into_iter().map(NodeId::Offchain).collect::<Vec<_>>();
-                            match ValidatedPath::new(src, node_ids, &chain_resolver).await {
-                                Ok(vp) => {
-                                    valid_paths.push((vp, composite_weight(&pwc, hops_usize, weighting)));
-                                    path_metrics.push(pwc);
-                                }
-                                Err(e) => {
-                                    tracing::debug!(error = %e, "background refresh: path candidate failed validation")
-                                }
-                            }
-                        }
-
-                        if !valid_paths.is_empty() {
-                            let weighted = hopr_utils::statistics::WeightedCollection::new(valid_paths);
-                            let total_wt = weighted.total_weight();
-                            for ((vp, w), pwm) in weighted.iter().zip(path_metrics.iter()) {
-                                tracing::debug!(
-                                    kind = "background-refresh",
-                                    destination = %dest_key,
-                                    hops = hops_usize,
-                                    path = %vp,
-                                    cost = pwm.cost,
-                                    composite_weight = w,
-                                    sampling_probability = if total_wt > 0.0 && *w > 0.0 { *w / total_wt } else { 0.0 },
-                                    total_latency_ms = ?pwm.total_latency_ms,
-                                    min_probe_success_rate = ?pwm.min_probe_success_rate,
-                                    min_ack_rate = ?pwm.min_ack_rate,
-                                    capacity_floor = ?pwm.capacity_floor,
-                                    "weighted candidate path",
-                                );
-                            }
-                            cache.insert((src, dest, hops_u32), Arc::new(weighted)).await;
-                        }
+                        cache.insert((src, dest, hops_u32), Arc::new(cached)).await;
                     }
                 }
             }
@@ -514,7 +758,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use std::str::FromStr;
+    use std::{collections::HashMap, str::FromStr};
 
     use bimap::BiMap;
     use futures::stream::{self, BoxStream};
@@ -547,6 +791,7 @@ mod tests {
     const SECRET_ME: [u8; 32] = hex!("60741b83b99e36aa0c1331578156e16b8e21166d01834abb6c64b103f885734d");
     const SECRET_A: [u8; 32] = hex!("71bf1f42ebbfcd89c3e197a3fd7cda79b92499e509b6fefa0fe44d02821d146a");
     const SECRET_DEST: [u8; 32] = hex!("c24bd833704dd2abdae3933fcc9962c2ac404f84132224c474147382d4db2299");
+    const SECRET_B: [u8; 32] = hex!("e0bf93e9c916104da00b1850adc4608bd7e9087bbd3f805451f4556aa6b3fd6e");
 
     fn pubkey(secret: &[u8; 32]) -> OffchainPublicKey {
         *OffchainKeypair::from_secret(secret).expect("valid secret").public()
@@ -670,6 +915,9 @@ mod tests {
     fn a_addr() -> Address {
         Address::from_str("0x200060ddced1e33c9647a71f4fc2cf4ed33e4a9d").expect("valid addr")
     }
+    fn b_addr() -> Address {
+        Address::from_str("0x4000a7c3b3e1d2b4c6f8e0a2c4e6f8a0b2c4d6e8").expect("valid addr")
+    }
     fn dest_addr() -> Address {
         Address::from_str("0x30004105095c8c10f804109b4d1199a9ac40ed46").expect("valid addr")
     }
@@ -921,13 +1169,258 @@ mod tests {
 
         let cached = planner.cache.get(&cache_key).await;
         assert!(cached.is_some(), "cache should be populated after first call");
-        let paths = cached.unwrap();
+        let cached = cached.unwrap();
+        let paths = &cached.paths;
         assert!(!paths.is_empty(), "cached paths must be non-empty");
         let (first_path, first_cost) = paths.iter().next().expect("at least one cached path");
         assert_eq!(first_path.num_hops(), 2, "path should have 2 hops [a, dest]");
         assert!(*first_cost > 0.0, "cost should be positive");
     }
 
+    #[tokio::test]
+    async fn relay_policy_should_filter_candidates_and_invalidate_cache() -> anyhow::Result<()> {
+        let me = pubkey(&SECRET_ME);
+        let a = pubkey(&SECRET_A);
+        let dest = pubkey(&SECRET_DEST);
+
+        let graph = ChannelGraph::new(me);
+        graph.add_node(a);
+        graph.add_node(dest);
+        graph.add_edge(&me, &a)?;
+        graph.add_edge(&a, &dest)?;
+        mark_edge_full(&graph, &me, &a);
+        mark_edge_full(&graph, &a, &dest);
+
+        let cfg = small_config();
+        let selector = HoprGraphPathSelector::new(
+            me,
+            graph,
+            cfg.max_cached_paths,
+            cfg.edge_penalty,
+            cfg.min_ack_rate,
+            cfg.min_paths_anonymity_floor,
+        );
+        let chain_api = TestChainApi::new(me, me_addr(), vec![(a, a_addr()), (dest, dest_addr())])
+            .with_open_channel(me_addr(), a_addr())
+            .with_open_channel(a_addr(), dest_addr());
+        let surb_store = hopr_protocol_hopr::MemorySurbStore::default();
+        let planner = PathPlanner::new(me, surb_store, chain_api, selector, small_config());
+
+        let cache_key: PlannerCacheKey = (NodeId::Offchain(me), NodeId::Offchain(dest), 1);
+        let make_routing = || DestinationRouting::Forward {
+            destination: Box::new(NodeId::Offchain(dest)),
+            pseudonym: None,
+            forward_options: RoutingOptions::Hops(1.try_into().expect("valid 1")),
+            return_options: None,
+        };
+
+        planner.resolve_routing(100, 0, make_routing()).await?;
+        let (_, unweighted) = planner
+            .cache
+            .get(&cache_key)
+            .await
+            .and_then(|cached| cached.paths.iter().next().cloned())
+            .ok_or(anyhow::anyhow!("expected a cached path"))?;
+
+        // Changing the weight of a used relay invalidates the entry and re-weights the path.
+        let weighted_policy = RelayPolicy {
+            weights: HashMap::from([(a, 0.5)]),
+            ..Default::default()
+        };
+        planner.set_relay_policy(weighted_policy.clone()).await?;
+        assert_eq!(planner.relay_policy(), weighted_policy);
+        assert!(
+            planner.cache.get(&cache_key).await.is_none(),
+            "entry must be invalidated"
+        );
+
+        planner.resolve_routing(100, 0, make_routing()).await?;
+        let (_, weighted) = planner
+            .cache
+            .get(&cache_key)
+            .await
+            .and_then(|cached| cached.paths.iter().next().cloned())
+            .ok_or(anyhow::anyhow!("expected a cached path"))?;
+        assert!((weighted - unweighted * 0.5).abs() < f64::EPSILON);
+
+        // Denying the only relay leaves no candidates.
+        let deny_policy = RelayPolicy {
+            deny: [a].into(),
+            ..Default::default()
+        };
+        planner.set_relay_policy(deny_policy).await?;
+        assert!(
+            planner.cache.get(&cache_key).await.is_none(),
+            "entry must be invalidated"
+        );
+        assert!(planner.resolve_routing(100, 0, make_routing()).await.is_err());
+
+        // Lifting the ban makes the relay usable again.
+        planner.set_relay_policy(RelayPolicy::default()).await?;
+        planner.resolve_routing(100, 0, make_routing()).await?;
+
+        let invalid_policy = RelayPolicy {
+            weights: HashMap::from([(a, -1.0)]),
+            ..Default::default()
+        };
+        assert!(matches!(
+            planner.set_relay_policy(invalid_policy).await,
+            Err(PathPlannerError::InvalidRelayPolicy(_))
+        ));
+        assert_eq!(planner.relay_policy(), RelayPolicy::default());
+
+        // Safe updates invalidate the cache only while distinct Safes are enforced.
+        planner.resolve_routing(100, 0, make_routing()).await?;
+        planner.relay_safes().update(a, Some(a_addr()));
+        assert!(planner.cache.get(&cache_key).await.is_some(), "entry must be kept");
+
+        planner
+            .set_relay_policy(RelayPolicy {
+                distinct_safes: true,
+                ..Default::default()
+            })
+            .await?;
+        planner.resolve_routing(100, 0, make_routing()).await?;
+        planner.relay_safes().update(a, Some(b_addr()));
+        assert!(
+            planner.cache.get(&cache_key).await.is_none(),
+            "entry must be invalidated"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn explain_should_report_cached_and_rejected_candidates() -> anyhow::Result<()> {
+        let me = pubkey(&SECRET_ME);
+        let a = pubkey(&SECRET_A);
+        let b = pubkey(&SECRET_B);
+        let dest = pubkey(&SECRET_DEST);
+
+        let graph = ChannelGraph::new(me);
+        for node in [a, b, dest] {
+            graph.add_node(node);
+        }
+        for (src, dst) in [(me, a), (a, dest), (me, b), (b, dest)] {
+            graph.add_edge(&src, &dst)?;
+            mark_edge_full(&graph, &src, &dst);
+        }
+
+        let cfg = small_config();
+        let selector = HoprGraphPathSelector::new(
+            me,
+            graph,
+            cfg.max_cached_paths,
+            cfg.edge_penalty,
+            cfg.min_ack_rate,
+            cfg.min_paths_anonymity_floor,
+        );
+        // No channel from `me` to `b`, so the path via `b` fails the chain validation.
+        let chain_api = TestChainApi::new(me, me_addr(), vec![(a, a_addr()), (b, b_addr()), (dest, dest_addr())])
+            .with_open_channel(me_addr(), a_addr())
+            .with_open_channel(a_addr(), dest_addr())
+            .with_open_channel(b_addr(), dest_addr());
+        let surb_store = hopr_protocol_hopr::MemorySurbStore::default();
+        let planner = PathPlanner::new(me, surb_store, chain_api, selector, small_config());
+
+        let (source, destination) = (NodeId::Offchain(me), NodeId::Offchain(dest));
+        assert!(planner.explain(source, destination, 1).await.is_none());
+
+        let routing = DestinationRouting::Forward {
+            destination: Box::new(destination),
+            pseudonym: None,
+            forward_options: RoutingOptions::Hops(1.try_into()?),
+            return_options: None,
+        };
+        planner.resolve_routing(100, 0, routing).await?;
+        assert_eq!(planner.cached_routes(), vec![(source, destination, 1)]);
+
+        let explanation = planner
+            .explain(source, destination, 1)
+            .await
+            .ok_or(anyhow::anyhow!("expected a cache entry"))?;
+
+        assert_eq!(explanation.candidates.len(), 1);
+        let candidate = &explanation.candidates[0];
+        assert_eq!(candidate.metrics.path, vec![a, dest]);
+        assert_eq!(candidate.policy_multiplier, 1.0);
+        let expected_weight =
+            candidate.metrics.cost * candidate.latency_factor * candidate.capacity_factor * candidate.policy_multiplier;
+        assert!((candidate.weight - expected_weight).abs() < f64::EPSILON);
+        assert!((candidate.probability - 1.0).abs() < f64::EPSILON);
+
+        assert_eq!(explanation.rejected.len(), 1);
+        assert_eq!(explanation.rejected[0].candidate.path, vec![b, dest]);
+        assert!(matches!(
+            explanation.rejected[0].reason,
+            RejectionReason::ChainValidation(_)
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn select_paths_should_return_distinct_relay_disjoint_paths() -> anyhow::Result<()> {
+        let me = pubkey(&SECRET_ME);
+        let a = pubkey(&SECRET_A);
+        let b = pubkey(&SECRET_B);
+        let dest = pubkey(&SECRET_DEST);
+
+        let graph = ChannelGraph::new(me);
+        for node in [a, b, dest] {
+            graph.add_node(node);
+        }
+        for (src, dst) in [(me, a), (a, dest), (me, b), (b, dest)] {
+            graph.add_edge(&src, &dst)?;
+            mark_edge_full(&graph, &src, &dst);
+        }
+
+        let cfg = small_config();
+        let selector = HoprGraphPathSelector::new(
+            me,
+            graph,
+            cfg.max_cached_paths,
+            cfg.edge_penalty,
+            cfg.min_ack_rate,
+            cfg.min_paths_anonymity_floor,
+        );
+        let chain_api = TestChainApi::new(me, me_addr(), vec![(a, a_addr()), (b, b_addr()), (dest, dest_addr())])
+            .with_open_channel(me_addr(), a_addr())
+            .with_open_channel(a_addr(), dest_addr())
+            .with_open_channel(me_addr(), b_addr())
+            .with_open_channel(b_addr(), dest_addr());
+        let surb_store = hopr_protocol_hopr::MemorySurbStore::default();
+        let planner = PathPlanner::new(me, surb_store, chain_api, selector, small_config());
+
+        let (source, destination) = (NodeId::Offchain(me), NodeId::Offchain(dest));
+
+        // Asking for more paths than known returns all of them
+        let paths = planner
+            .select_paths(source, destination, RoutingOptions::Hops(1.try_into()?), 3)
+            .await?;
+        assert_eq!(paths.len(), 2);
+        let relays = paths.iter().map(|path| path[0]).collect::<Vec<_>>();
+        assert!(
+            relays.contains(&a) && relays.contains(&b),
+            "paths must not share relays"
+        );
+        assert!(paths.iter().all(|path| path[1] == dest));
+
+        let paths = planner
+            .select_paths(source, destination, RoutingOptions::Hops(1.try_into()?), 1)
+            .await?;
+        assert_eq!(paths.len(), 1);
+
+        // 0-hop routes have a single path only
+        let paths = planner
+            .select_paths(source, destination, RoutingOptions::Hops(0.try_into()?), 3)
+            .await?;
+        assert_eq!(paths.len(), 1);
+        assert_eq!(&paths[0][..], &[dest]);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn planner_cache_hit_should_return_valid_path() {
         let me = pubkey(&SECRET_ME);
@@ -0,0 +1,237 @@
+use std::collections::{HashMap, HashSet};
+
+use hopr_api::{OffchainPublicKey, types::primitive::prelude::Address};
+use validator::{Validate, ValidationError, ValidationErrors};
+
+/// Constraints on the relays the [`PathPlanner`](super::PathPlanner) may use.
+///
+/// The policy applies to the relays (intermediate hops) of paths found by the
+/// [`PathSelector`](super::PathSelector); the destination of a path is never constrained.
+/// Explicit intermediate paths and 0-hop routes bypass the policy.
+///
+/// The default policy permits every relay with a neutral weight.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RelayPolicy {
+    /// Relays that must never be used. Takes precedence over `allow`.
+    pub deny: HashSet<OffchainPublicKey>,
+    /// If set, only these relays may be used.
+    pub allow: Option<HashSet<OffchainPublicKey>>,
+    /// Multipliers of the selection weight of every path that uses the given relay.
+    ///
+    /// Relays not listed use `1.0`. Must be finite and positive.
+    pub weights: HashMap<OffchainPublicKey, f64>,
+    /// Never use two relays controlled by the same Safe within a single path.
+    ///
+    /// A relay whose Safe is not known cannot be shown to be distinct, so paths with more
+    /// than one relay never use it while this is set.
+    pub distinct_safes: bool,
+}
+
+impl Validate for RelayPolicy {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+
+        if !self.weights.values().all(|w| w.is_finite() && *w > 0.0) {
+            errors.add(
+                "weights",
+                ValidationError::new("relay weights must be finite and positive"),
+            );
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+impl RelayPolicy {
+    /// Checks whether the `relay` may be used according to the deny- and allow-lists.
+    pub fn permits(&self, relay: &OffchainPublicKey) -> bool {
+        !self.deny.contains(relay) && self.allow.as_ref().is_none_or(|allow| allow.contains(relay))
+    }
+
+    /// Selection weight multiplier of the `relay`.
+    pub fn weight(&self, relay: &OffchainPublicKey) -> f64 {
+        self.weights.get(relay).copied().unwrap_or(1.0)
+    }
+}
+
+/// A [`RelayPolicy`] together with the Safe controlling each known relay.
+#[derive(Debug, Clone, Default)]
+pub(super) struct ActiveRelayPolicy {
+    pub policy: RelayPolicy,
+    pub safes: HashMap<OffchainPublicKey, Address>,
+}
+
+impl ActiveRelayPolicy {
+    /// Selection weight multiplier of a path `[relays..., dest]`, or `None` if the policy
+    /// forbids the path.
+    ///
+    /// With `distinct_safes` set, a relay with no known Safe violates the rule in every path
+    /// with more than one relay.
+    pub fn path_multiplier(&self, path: &[OffchainPublicKey]) -> Option<f64> {
+        let relays = &path[..path.len().saturating_sub(1)];
+        if !relays.iter().all(|relay| self.policy.permits(relay)) {
+            return None;
+        }
+
+        if self.policy.distinct_safes && relays.len() > 1 {
+            let mut seen = HashSet::with_capacity(relays.len());
+            if !relays
+                .iter()
+                .all(|relay| self.safes.get(relay).is_some_and(|safe| seen.insert(safe)))
+            {
+                return None;
+            }
+        }
+
+        Some(relays.iter().map(|relay| self.policy.weight(relay)).product())
+    }
+
+    /// Records the Safe controlling the `relay`, or that it has none.
+    ///
+    /// Returns `true` if the known Safe of the `relay` has changed.
+    pub fn update_safe(&mut self, relay: OffchainPublicKey, safe: Option<Address>) -> bool {
+        match safe {
+            Some(safe) => self.safes.insert(relay, safe) != Some(safe),
+            None => self.safes.remove(&relay).is_some(),
+        }
+    }
+
+    /// Checks whether `self` may permit a path that the `previous` policy forbade.
+    ///
+    /// Such a change can add new candidates to any query, so it cannot be attributed to
+    /// individual cache entries.
+    pub fn relaxes(&self, previous: &Self) -> bool {
+        let (new, old) = (&self.policy, &previous.policy);
+
+        let undenied = old.deny.iter().any(|relay| !new.deny.contains(relay));
+        let allowed = match (&old.allow, &new.allow) {
+            (_, None) => old.allow.is_some(),
+            (None, Some(_)) => false,
+            (Some(old_allow), Some(new_allow)) => new_allow.iter().any(|relay| !old_allow.contains(relay)),
+        };
+        let safes = old.distinct_safes && !new.distinct_safes;
+
+        undenied || allowed || safes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::test::{SECRET_1, SECRET_2, SECRET_3, SECRET_4, pubkey};
+
+    #[test]
+    fn relay_policy_must_not_constrain_destination() {
+        let [a, dest] = [pubkey(&SECRET_1), pubkey(&SECRET_4)];
+        let active = ActiveRelayPolicy {
+            policy: RelayPolicy {
+                deny: HashSet::from([dest]),
+                allow: Some(HashSet::from([a])),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(active.path_multiplier(&[a, dest]), Some(1.0));
+        assert_eq!(active.path_multiplier(&[dest]), Some(1.0));
+    }
+
+    #[test]
+    fn relay_policy_must_apply_deny_allow_and_weights() {
+        let [a, b, c, dest] = [
+            pubkey(&SECRET_1),
+            pubkey(&SECRET_2),
+            pubkey(&SECRET_3),
+            pubkey(&SECRET_4),
+        ];
+        let active = ActiveRelayPolicy {
+            policy: RelayPolicy {
+                deny: HashSet::from([b]),
+                allow: Some(HashSet::from([a, b])),
+                weights: HashMap::from([(a, 0.5)]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(active.path_multiplier(&[a, dest]), Some(0.5));
+        assert_eq!(active.path_multiplier(&[a, b, dest]), None, "deny takes precedence");
+        assert_eq!(active.path_multiplier(&[a, c, dest]), None, "c is not allow-listed");
+    }
+
+    #[test]
+    fn relay_policy_must_reject_relays_sharing_a_safe() {
+        let [a, b, c, dest] = [
+            pubkey(&SECRET_1),
+            pubkey(&SECRET_2),
+            pubkey(&SECRET_3),
+            pubkey(&SECRET_4),
+        ];
+        let safe = Address::new(&[1u8; 20]);
+        let mut active = ActiveRelayPolicy {
+            policy: RelayPolicy {
+                distinct_safes: true,
+                ..Default::default()
+            },
+            safes: HashMap::from([(a, safe), (b, safe), (dest, safe)]),
+        };
+
+        assert_eq!(active.path_multiplier(&[a, b, dest]), None);
+        assert_eq!(
+            active.path_multiplier(&[a, c, dest]),
+            None,
+            "unknown Safe is not distinct"
+        );
+        assert_eq!(
+            active.path_multiplier(&[c, dest]),
+            Some(1.0),
+            "a single relay never conflicts"
+        );
+
+        assert!(active.update_safe(c, Some(Address::new(&[2u8; 20]))));
+        assert!(!active.update_safe(c, Some(Address::new(&[2u8; 20]))));
+        assert_eq!(active.path_multiplier(&[a, c, dest]), Some(1.0));
+
+        assert!(active.update_safe(b, None));
+        assert_eq!(
+            active.path_multiplier(&[a, b, dest]),
+            None,
+            "b has no known Safe anymore"
+        );
+
+        active.policy.distinct_safes = false;
+        assert_eq!(active.path_multiplier(&[a, b, dest]), Some(1.0));
+    }
+
+    #[test]
+    fn relay_policy_relaxation_must_be_detected() {
+        let [a, b] = [pubkey(&SECRET_1), pubkey(&SECRET_2)];
+        let policy = |deny: &[OffchainPublicKey], allow: Option<&[OffchainPublicKey]>| ActiveRelayPolicy {
+            policy: RelayPolicy {
+                deny: deny.iter().copied().collect(),
+                allow: allow.map(|allow| allow.iter().copied().collect()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert!(!policy(&[a, b], None).relaxes(&policy(&[a], None)));
+        assert!(policy(&[b], None).relaxes(&policy(&[a], None)));
+        assert!(!policy(&[], Some(&[a])).relaxes(&policy(&[], None)));
+        assert!(policy(&[], None).relaxes(&policy(&[], Some(&[a]))));
+        assert!(policy(&[], Some(&[a, b])).relaxes(&policy(&[], Some(&[a]))));
+        assert!(!policy(&[], Some(&[a])).relaxes(&policy(&[], Some(&[a, b]))));
+    }
+
+    #[test]
+    fn relay_policy_must_reject_invalid_weights() {
+        let a = pubkey(&SECRET_1);
+        for weight in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            let policy = RelayPolicy {
+                weights: HashMap::from([(a, weight)]),
+                ..Default::default()
+            };
+            assert!(policy.validate().is_err(), "weight {weight} must be rejected");
+        }
+    }
+}
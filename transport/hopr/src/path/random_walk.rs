@@ -0,0 +1,333 @@
+use std::collections::HashSet;
+
+use hopr_api::{
+    OffchainPublicKey,
+    graph::{NetworkGraphConnectivity, traits::EdgeObservableRead},
+    types::{crypto_random::random_float, internal::errors::PathError},
+};
+
+use super::{
+    errors::{PathPlannerError, Result},
+    scored_graph::ScoredGraph,
+    selector::partition_for_consistency,
+    traits::{PathSelection, PathSelector, PathWithMetrics},
+};
+
+/// Picks one of the `options` with probability proportional to its weight.
+///
+/// `sample` must be uniformly distributed in `[0.0, 1.0)`.
+fn pick_weighted(options: &[(OffchainPublicKey, f64)], sample: f64) -> Option<OffchainPublicKey> {
+    let total: f64 = options.iter().map(|(_, weight)| weight).sum();
+    if total <= 0.0 {
+        return None;
+    }
+
+    let mut target = sample * total;
+    for (node, weight) in options {
+        if target < *weight {
+            return Some(*node);
+        }
+        target -= weight;
+    }
+    // Guard against floating-point rounding leaving a tiny remainder.
+    options.last().map(|(node, _)| *node)
+}
+
+/// Performs a single random walk from `src` to `dest`.
+///
+/// At every step the next node is drawn from the unvisited successors with probability
+/// proportional to the edge score. Before the last relay is drawn, successors without a
+/// usable edge to `dest` are skipped, so that walks do not dead-end one step short.
+/// Returns `None` if the walk nevertheless gets stuck.
+fn random_walk<W>(graph: &ScoredGraph<W>) -> Option<Vec<OffchainPublicKey>>
+where
+    W: EdgeObservableRead + Send + 'static,
+{
+    let length = graph.length();
+    let mut path = Vec::with_capacity(length);
+    let mut current = graph.src();
+
+    for index in 0..length {
+        let options = graph
+            .successors(&current, index)
+            .into_iter()
+            .filter(|(node, _)| *node != graph.src() && !path.contains(node))
+            .filter(|(node, _)| index + 2 != length || graph.edge_score(node, &graph.dest(), index + 1).is_some())
+            .collect::<Vec<_>>();
+
+        current = pick_weighted(&options, random_float())?;
+        path.push(current);
+    }
+
+    Some(path)
+}
+
+/// A path selector sampling paths by weighted random walks over the network graph.
+///
+/// Each walk picks the next hop with probability proportional to the quality score
+/// of the edge leading to it, so paths are sampled roughly in proportion to their
+/// cost while low-quality paths still get a chance to be selected. This spreads
+/// traffic over a wider set of relays than a deterministic best-first search.
+///
+/// Up to `max_walks` walks are performed per query, and the selector stops early once
+/// `max_paths` distinct paths have been found. Returned candidates carry the same
+/// per-path aggregates as those of [`HoprGraphPathSelector`](super::HoprGraphPathSelector)
+/// and are pruned with the same anonymity floor.
+#[derive(Clone)]
+pub struct RandomWalkPathSelector<G> {
+    me: OffchainPublicKey,
+    graph: G,
+    max_paths: usize,
+    max_walks: usize,
+    edge_penalty: f64,
+    min_ack_rate: f64,
+    anonymity_floor: usize,
+}
+
+impl<G> RandomWalkPathSelector<G>
+where
+    G: NetworkGraphConnectivity<NodeId = OffchainPublicKey> + Clone + Send + Sync + 'static,
+    <G as NetworkGraphConnectivity>::Observed: 'static,
+{
+    /// Create a new selector.
+    ///
+    /// * `me` – the planner's own offchain public key, used to determine path direction.
+    /// * `graph` – the network graph to query.
+    /// * `max_paths` – maximum number of distinct candidate paths to return per query.
+    /// * `max_walks` – maximum number of random walks performed per query.
+    /// * `edge_penalty` – penalty multiplier for edges lacking probe-based quality observations.
+    /// * `min_ack_rate` – minimum acceptable message acknowledgment rate for path selection.
+    /// * `anonymity_floor` – minimum candidate count below which no latency-based pruning occurs.
+    pub fn new(
+        me: OffchainPublicKey,
+        graph: G,
+        max_paths: usize,
+        max_walks: usize,
+        edge_penalty: f64,
+        min_ack_rate: f64,
+        anonymity_floor: usize,
+    ) -> Self {
+        Self {
+            me,
+            graph,
+            max_paths,
+            max_walks,
+            edge_penalty,
+            min_ack_rate,
+            anonymity_floor,
+        }
+    }
+}
+
+impl<G> PathSelector for RandomWalkPathSelector<G>
+where
+    G: NetworkGraphConnectivity<NodeId = OffchainPublicKey> + Clone + Send + Sync + 'static,
+    <G as NetworkGraphConnectivity>::Observed: 'static,
+{
+    fn select_path(
+        &self,
+        src: OffchainPublicKey,
+        dest: OffchainPublicKey,
+        hops: usize,
+    ) -> Result<Vec<PathWithMetrics>> {
+        self.select_path_explained(src, dest, hops)
+            .map(|selection| selection.selected)
+    }
+
+    #[tracing::instrument(level = "trace", skip(self), fields(src = %src, dest = %dest, hops), err)]
+    fn select_path_explained(
+        &self,
+        src: OffchainPublicKey,
+        dest: OffchainPublicKey,
+        hops: usize,
+    ) -> Result<PathSelection> {
+        let graph = ScoredGraph::new(
+            self.graph.connected_edges(),
+            self.me,
+            src,
+            dest,
+            hops,
+            self.edge_penalty,
+            self.min_ack_rate,
+        );
+
+        let mut seen = HashSet::new();
+        let mut paths = Vec::new();
+        for _ in 0..self.max_walks {
+            if paths.len() >= self.max_paths {
+                break;
+            }
+            if let Some(path) = random_walk(&graph)
+                && seen.insert(path.clone())
+                && let Some(pwm) = graph.evaluate(&path)
+            {
+                paths.push(pwm);
+            }
+        }
+        tracing::debug!(%src, %dest, hops, count = paths.len(), "random walk candidates");
+
+        if paths.is_empty() {
+            Err(PathPlannerError::Path(PathError::PathNotFound(
+                hops,
+                src.to_string(),
+                dest.to_string(),
+            )))
+        } else {
+            Ok(partition_for_consistency(paths, self.anonymity_floor, hops))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, time::Duration};
+
+    use anyhow::Context;
+    use hopr_api::graph::{
+        NetworkGraphWrite,
+        traits::{EdgeObservableWrite, EdgeWeightType},
+    };
+    use hopr_network_graph::ChannelGraph;
+
+    use super::*;
+    use crate::path::{
+        PathPlannerConfig,
+        test::{SECRET_0, SECRET_1, SECRET_2, SECRET_3, SECRET_4, pubkey},
+    };
+
+    /// Mark an edge as ready for intermediate routing with the given probe latency.
+    fn mark_edge(graph: &ChannelGraph, src: &OffchainPublicKey, dst: &OffchainPublicKey, latency_ms: u64) {
+        graph.upsert_edge(src, dst, |obs| {
+            obs.record(EdgeWeightType::Connected(true));
+            obs.record(EdgeWeightType::Immediate(Ok(Duration::from_millis(latency_ms))));
+            obs.record(EdgeWeightType::Intermediate(Ok(Duration::from_millis(latency_ms))));
+            obs.record(EdgeWeightType::Capacity(Some(1000)));
+        });
+    }
+
+    fn add_edge(graph: &ChannelGraph, src: &OffchainPublicKey, dst: &OffchainPublicKey) {
+        graph.add_edge(src, dst).unwrap();
+        mark_edge(graph, src, dst, 50);
+    }
+
+    fn test_selector(
+        me: OffchainPublicKey,
+        graph: ChannelGraph,
+        max_paths: usize,
+        max_walks: usize,
+    ) -> RandomWalkPathSelector<ChannelGraph> {
+        let cfg = PathPlannerConfig::default();
+        RandomWalkPathSelector::new(me, graph, max_paths, max_walks, cfg.edge_penalty, cfg.min_ack_rate, 0)
+    }
+
+    /// me → {a, b, c} → dest, plus a ↔ b ↔ c links for 2-hop paths.
+    fn mesh() -> (
+        OffchainPublicKey,
+        [OffchainPublicKey; 3],
+        OffchainPublicKey,
+        ChannelGraph,
+    ) {
+        let me = pubkey(&SECRET_0);
+        let relays = [pubkey(&SECRET_1), pubkey(&SECRET_2), pubkey(&SECRET_3)];
+        let dest = pubkey(&SECRET_4);
+        let graph = ChannelGraph::new(me);
+        for n in relays.iter().chain([&dest]) {
+            graph.add_node(*n);
+        }
+        for relay in &relays {
+            add_edge(&graph, &me, relay);
+            add_edge(&graph, relay, &dest);
+        }
+        for (a, b) in [(0, 1), (1, 0), (1, 2), (2, 1)] {
+            add_edge(&graph, &relays[a], &relays[b]);
+        }
+        (me, relays, dest, graph)
+    }
+
+    #[test]
+    fn pick_weighted_must_respect_weights() {
+        let a = pubkey(&SECRET_1);
+        let b = pubkey(&SECRET_2);
+        let options = [(a, 0.25), (b, 0.75)];
+
+        assert_eq!(pick_weighted(&options, 0.0), Some(a));
+        assert_eq!(pick_weighted(&options, 0.24), Some(a));
+        assert_eq!(pick_weighted(&options, 0.26), Some(b));
+        assert_eq!(pick_weighted(&options, 0.999), Some(b));
+        assert_eq!(pick_weighted(&[], 0.5), None);
+    }
+
+    #[test]
+    fn random_walk_must_return_distinct_cycle_free_paths() -> anyhow::Result<()> {
+        let (me, _, dest, graph) = mesh();
+        let selector = test_selector(me, graph, 10, 200);
+
+        let paths = selector.select_path(me, dest, 2).context("2-hop paths")?;
+        // a→b, b→a, b→c, c→b
+        assert_eq!(paths.len(), 4, "all 2-hop paths should be found");
+
+        let unique: HashSet<_> = paths.iter().map(|pwm| pwm.path.clone()).collect();
+        assert_eq!(unique.len(), paths.len());
+        for pwm in &paths {
+            assert_eq!(pwm.path.len(), 3);
+            assert_eq!(pwm.path.last(), Some(&dest));
+            assert!(!pwm.path.contains(&me));
+            let nodes: HashSet<_> = pwm.path.iter().collect();
+            assert_eq!(nodes.len(), 3, "path must be cycle-free");
+            assert!(pwm.cost > 0.0);
+            assert!(pwm.total_latency_ms.is_some(), "aggregates must be reported");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn random_walk_must_stop_after_max_paths() -> anyhow::Result<()> {
+        let (me, _, dest, graph) = mesh();
+        let selector = test_selector(me, graph, 2, 200);
+
+        let paths = selector.select_path(me, dest, 1).context("1-hop paths")?;
+        assert_eq!(paths.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn random_walk_must_prefer_better_edges() -> anyhow::Result<()> {
+        let (me, relays, dest, graph) = mesh();
+        // Make the first relay considerably worse than the others.
+        for _ in 0..10 {
+            mark_edge(&graph, &me, &relays[0], 500);
+        }
+        let selector = test_selector(me, graph, 1, 1);
+
+        let mut first_hops = HashMap::new();
+        for _ in 0..300 {
+            let paths = selector.select_path(me, dest, 1).context("1-hop path")?;
+            *first_hops.entry(paths[0].path[0]).or_insert(0usize) += 1;
+        }
+
+        let worse = first_hops.get(&relays[0]).copied().unwrap_or_default();
+        let better = first_hops.get(&relays[1]).copied().unwrap_or_default();
+        assert!(
+            worse < better,
+            "worse relay picked {worse} times, better one {better} times"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn random_walk_must_fail_when_destination_unreachable() {
+        let me = pubkey(&SECRET_0);
+        let dest = pubkey(&SECRET_1);
+        let graph = ChannelGraph::new(me);
+        graph.add_node(dest);
+        let selector = test_selector(me, graph, 4, 10);
+
+        assert!(matches!(
+            selector.select_path(dest, me, 1),
+            Err(PathPlannerError::Path(PathError::PathNotFound(..)))
+        ));
+    }
+}
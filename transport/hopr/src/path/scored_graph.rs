@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+
+use hopr_api::{
+    OffchainPublicKey,
+    graph::{
+        function::{BasicValueFn, EdgeValueFn},
+        traits::{EdgeObservableRead, ValueFn},
+    },
+};
+
+use super::{
+    selector::{MetricsValueFn, PathCostWithMetrics},
+    traits::PathWithMetrics,
+};
+
+/// A per-query view of the network graph that scores individual edges.
+///
+/// Used by the selectors that explore the graph edge-by-edge instead of enumerating
+/// simple paths. Edges are scored with the same direction-dependent [`EdgeValueFn`]
+/// as in [`HoprGraphPathSelector`](super::HoprGraphPathSelector):
+/// - forward path (`src == me`): [`EdgeValueFn::forward`]
+/// - return path (`dest == me`): [`EdgeValueFn::returning`]
+///
+/// Since the value function is a product of per-edge scores in `(0.0, 1.0]`, the score
+/// of a single edge is obtained by folding it into the neutral value `1.0`.
+///
+/// As in the extended forward search of `HoprGraphPathSelector`, a forward path may
+/// end with a hop to `dest` that has no graph edge; such a hop contributes a neutral
+/// `1.0` multiplier.
+///
+/// The view is built from a snapshot of the graph edges, which are indexed by their source
+/// node once, so that the successors of a node are found without scanning the whole graph.
+pub(super) struct ScoredGraph<W> {
+    src: OffchainPublicKey,
+    dest: OffchainPublicKey,
+    length: usize,
+    forward: bool,
+    initial: PathCostWithMetrics,
+    value_fn: BasicValueFn<PathCostWithMetrics, W>,
+    edges: HashMap<OffchainPublicKey, HashMap<OffchainPublicKey, W>>,
+}
+
+impl<W> ScoredGraph<W>
+where
+    W: EdgeObservableRead + Send + 'static,
+{
+    /// Creates a view for paths from `src` to `dest` using `hops` relays over the given `edges`.
+    ///
+    /// The `edges` are usually obtained from
+    /// [`NetworkGraphConnectivity::connected_edges`](hopr_api::graph::NetworkGraphConnectivity::connected_edges).
+    pub fn new(
+        edges: impl IntoIterator<Item = (OffchainPublicKey, OffchainPublicKey, W)>,
+        me: OffchainPublicKey,
+        src: OffchainPublicKey,
+        dest: OffchainPublicKey,
+        hops: usize,
+        edge_penalty: f64,
+        min_ack_rate: f64,
+    ) -> Self {
+        let length = std::num::NonZeroUsize::new(hops + 1)
+            .expect("can never fail, it is physically at least 1 after the addition");
+        let forward = src == me;
+        let value_fn = MetricsValueFn {
+            inner: if forward {
+                EdgeValueFn::forward(length, edge_penalty, min_ack_rate)
+            } else {
+                EdgeValueFn::returning(length, edge_penalty, min_ack_rate)
+            },
+        };
+
+        let mut index: HashMap<OffchainPublicKey, HashMap<OffchainPublicKey, W>> = HashMap::new();
+        for (from, to, observed) in edges {
+            if from != to {
+                index.entry(from).or_default().insert(to, observed);
+            }
+        }
+
+        Self {
+            src,
+            dest,
+            length: length.get(),
+            forward,
+            initial: value_fn.initial_value(),
+            value_fn: value_fn.into_value_fn(),
+            edges: index,
+        }
+    }
+
+    /// Source node of the queried paths.
+    pub fn src(&self) -> OffchainPublicKey {
+        self.src
+    }
+
+    /// Destination node of the queried paths.
+    pub fn dest(&self) -> OffchainPublicKey {
+        self.dest
+    }
+
+    /// Number of edges of every queried path (= `hops + 1`).
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    fn allows_missing_edge(&self, to: &OffchainPublicKey, index: usize) -> bool {
+        self.forward && self.length > 1 && index == self.length - 1 && *to == self.dest
+    }
+
+    fn edge(&self, from: &OffchainPublicKey, to: &OffchainPublicKey) -> Option<&W> {
+        self.edges.get(from).and_then(|targets| targets.get(to))
+    }
+
+    fn observed_score(&self, observed: &W, index: usize) -> Option<f64> {
+        let cost = (self.value_fn)(self.initial.clone(), observed, index).cost;
+        (cost > 0.0).then_some(cost)
+    }
+
+    /// Score of the edge `from -> to` at position `index` of the path, or `None` if the
+    /// edge cannot be used at that position.
+    pub fn edge_score(&self, from: &OffchainPublicKey, to: &OffchainPublicKey, index: usize) -> Option<f64> {
+        match self.edge(from, to) {
+            Some(observed) => self.observed_score(observed, index),
+            None if self.allows_missing_edge(to, index) => Some(1.0),
+            None => None,
+        }
+    }
+
+    /// Nodes that can follow `from` at position `index` of the path, paired with the edge score.
+    ///
+    /// At the last position only `dest` qualifies, at every other position `dest` is excluded.
+    pub fn successors(&self, from: &OffchainPublicKey, index: usize) -> Vec<(OffchainPublicKey, f64)> {
+        if index + 1 == self.length {
+            return self
+                .edge_score(from, &self.dest, index)
+                .map(|score| vec![(self.dest, score)])
+                .unwrap_or_default();
+        }
+
+        self.edges
+            .get(from)
+            .into_iter()
+            .flatten()
+            .filter(|(node, _)| **node != self.dest)
+            .filter_map(|(node, observed)| self.observed_score(observed, index).map(|score| (*node, score)))
+            .collect()
+    }
+
+    /// Product of the edge scores along `prefix`, which starts with `src`.
+    pub fn prefix_cost(&self, prefix: &[OffchainPublicKey]) -> Option<f64> {
+        prefix.windows(2).enumerate().try_fold(1.0, |cost, (index, edge)| {
+            self.edge_score(&edge[0], &edge[1], index).map(|score| cost * score)
+        })
+    }
+
+    /// Folds the per-path aggregates along `path` (`[intermediates..., dest]`, `src` excluded).
+    ///
+    /// Returns `None` if the path has the wrong length, is not cycle-free, or any of its
+    /// edges is unusable.
+    pub fn evaluate(&self, path: &[OffchainPublicKey]) -> Option<PathWithMetrics> {
+        if path.len() != self.length || path.last() != Some(&self.dest) {
+            return None;
+        }
+        let cycle_free = std::iter::once(&self.src)
+            .chain(path.iter())
+            .enumerate()
+            .all(|(i, node)| !path[i..].contains(node));
+        if !cycle_free {
+            return None;
+        }
+
+        let mut prev = self.src;
+        let mut metrics = self.initial.clone();
+        for (index, node) in path.iter().enumerate() {
+            match self.edge(&prev, node) {
+                Some(observed) => metrics = (self.value_fn)(metrics, observed, index),
+                None if self.allows_missing_edge(node, index) => {}
+                None => return None,
+            }
+            prev = *node;
+        }
+
+        (metrics.cost > 0.0).then(|| PathWithMetrics::from((metrics, path.to_vec())))
+    }
+}
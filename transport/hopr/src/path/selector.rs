@@ -14,7 +14,7 @@ use hopr_api::{
 
 use super::{
     errors::{PathPlannerError, Result},
-    traits::{PathSelector, PathWithMetrics},
+    traits::{PathSelection, PathSelector, PathWithMetrics, PruneReason, PrunedPath},
 };
 
 /// Accumulated path cost and quality aggregates, folded edge-by-edge during DFS.
@@ -22,8 +22,8 @@ use super::{
 /// `PartialOrd` / `PartialEq` compare only the `cost` field so the DFS
 /// pruning threshold (`min_cost`) operates on the same scalar as before.
 #[derive(Clone, Debug)]
-struct PathCostWithMetrics {
-    cost: f64,
+pub(super) struct PathCostWithMetrics {
+    pub(super) cost: f64,
     total_latency_ms: Option<u32>,
     min_probe_success_rate: Option<f64>,
     min_ack_rate: Option<f64>,
@@ -69,8 +69,8 @@ fn opt_min<T: PartialOrd>(a: Option<T>, b: Option<T>) -> Option<T> {
 /// All cost semantics are delegated to the inner `EdgeValueFn`; the wrapper
 /// only adds the aggregate fold on the same `&Weight` reference already
 /// available during DFS, so no extra graph lookups are needed.
-struct MetricsValueFn<W: EdgeObservableRead> {
-    inner: EdgeValueFn<f64, W>,
+pub(super) struct MetricsValueFn<W: EdgeObservableRead> {
+    pub(super) inner: EdgeValueFn<f64, W>,
 }
 
 impl<W> ValueFn for MetricsValueFn<W>
@@ -156,15 +156,23 @@ where
 /// expected) OR `capacity_floor` is also known.  This prevents 0-hop direct paths from
 /// being demoted simply because they carry no channel-capacity data.
 pub fn prune_for_consistency(candidates: Vec<PathWithMetrics>, floor: usize, hops: usize) -> Vec<PathWithMetrics> {
+    partition_for_consistency(candidates, floor, hops).selected
+}
+
+/// Same as [`prune_for_consistency`], but also returns the dropped candidates with the reason.
+pub fn partition_for_consistency(candidates: Vec<PathWithMetrics>, floor: usize, hops: usize) -> PathSelection {
     // floor == 0 means "no pruning" — caller opts out entirely.
     if floor == 0 || candidates.len() <= floor {
-        return candidates;
+        return PathSelection {
+            selected: candidates,
+            pruned: Vec::new(),
+        };
     }
 
     let fully_measured =
         |p: &PathWithMetrics| p.total_latency_ms.is_some() && (hops == 0 || p.capacity_floor.is_some());
 
-    let (mut populated, unpopulated): (Vec<_>, Vec<_>) = candidates.into_iter().partition(|p| fully_measured(p));
+    let (mut populated, mut unpopulated): (Vec<_>, Vec<_>) = candidates.into_iter().partition(|p| fully_measured(p));
 
     // Sort populated ascending by latency (lowest first → drop from the end).
     populated.sort_by_key(|p| p.total_latency_ms.unwrap_or(u32::MAX));
@@ -174,13 +182,30 @@ pub fn prune_for_consistency(candidates: Vec<PathWithMetrics>, floor: usize, hop
     // latency-measured candidates are never discarded when unprobed paths alone
     // would satisfy the floor.
     let target_populated = populated.len().min(floor);
-    populated.truncate(target_populated);
     let remaining = floor - target_populated;
 
-    let mut result = populated;
-    result.extend(unpopulated.into_iter().take(remaining));
+    let pruned = populated
+        .split_off(target_populated)
+        .into_iter()
+        .map(|candidate| PrunedPath {
+            candidate,
+            reason: PruneReason::HigherLatency,
+        })
+        .chain(
+            unpopulated
+                .split_off(remaining.min(unpopulated.len()))
+                .into_iter()
+                .map(|candidate| PrunedPath {
+                    candidate,
+                    reason: PruneReason::NotFullyMeasured,
+                }),
+        )
+        .collect();
+
+    let mut selected = populated;
+    selected.extend(unpopulated);
 
-    result
+    PathSelection { selected, pruned }
 }
 
 /// Compute candidate paths from `src` to `dest` through `graph`.
@@ -346,13 +371,23 @@ where
     /// The function has a potential to run expensive operations, it should be benchmarked
     /// in a production environment and possibly guarded (e.g. by offloading the long execution
     /// in an async executor to avoid blocking the caller).
-    #[tracing::instrument(level = "trace", skip(self), fields(src = %src, dest = %dest, hops), ret, err)]
     fn select_path(
         &self,
         src: OffchainPublicKey,
         dest: OffchainPublicKey,
         hops: usize,
     ) -> Result<Vec<PathWithMetrics>> {
+        self.select_path_explained(src, dest, hops)
+            .map(|selection| selection.selected)
+    }
+
+    #[tracing::instrument(level = "trace", skip(self), fields(src = %src, dest = %dest, hops), ret, err)]
+    fn select_path_explained(
+        &self,
+        src: OffchainPublicKey,
+        dest: OffchainPublicKey,
+        hops: usize,
+    ) -> Result<PathSelection> {
         let direction = if src == self.me { "forward" } else { "return" };
         tracing::debug!(%src, %dest, hops, direction, "computing paths from graph");
 
@@ -428,7 +463,7 @@ where
                 dest.to_string(),
             )))
         } else {
-            Ok(prune_for_consistency(paths, self.anonymity_floor, hops))
+            Ok(partition_for_consistency(paths, self.anonymity_floor, hops))
         }
     }
 }
@@ -1064,6 +1099,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn partition_reports_pruned_candidates_with_reason() {
+        let mut candidates: Vec<_> = (0..10u32)
+            .map(|i| make_path_with_capacity(Some(i * 10), Some(1_000_000)))
+            .collect();
+        candidates.extend((0..3).map(|_| make_path_with_latency(None)));
+
+        let selection = partition_for_consistency(candidates, 8, 1);
+        assert_eq!(selection.selected.len(), 8);
+        assert_eq!(selection.pruned.len(), 5);
+        let higher_latency: Vec<_> = selection
+            .pruned
+            .iter()
+            .filter(|p| p.reason == PruneReason::HigherLatency)
+            .map(|p| p.candidate.total_latency_ms)
+            .collect();
+        assert_eq!(higher_latency, vec![Some(80), Some(90)]);
+        assert_eq!(
+            selection
+                .pruned
+                .iter()
+                .filter(|p| p.reason == PruneReason::NotFullyMeasured)
+                .count(),
+            3
+        );
+    }
+
     #[test]
     fn prune_for_consistency_floor_zero_returns_all() {
         // floor == 0 must be treated as "no pruning" — all candidates survive unchanged.
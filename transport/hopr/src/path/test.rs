@@ -0,0 +1,12 @@
+use hex_literal::hex;
+use hopr_api::types::crypto::prelude::{Keypair, OffchainKeypair, OffchainPublicKey};
+
+pub const SECRET_0: [u8; 32] = hex!("60741b83b99e36aa0c1331578156e16b8e21166d01834abb6c64b103f885734d");
+pub const SECRET_1: [u8; 32] = hex!("71bf1f42ebbfcd89c3e197a3fd7cda79b92499e509b6fefa0fe44d02821d146a");
+pub const SECRET_2: [u8; 32] = hex!("c24bd833704dd2abdae3933fcc9962c2ac404f84132224c474147382d4db2299");
+pub const SECRET_3: [u8; 32] = hex!("e0bf93e9c916104da00b1850adc4608bd7e9087bbd3f805451f4556aa6b3fd6e");
+pub const SECRET_4: [u8; 32] = hex!("cfc66f718ec66fb822391775d749d7a0d66b690927673634816b63339bc12a3c");
+
+pub fn pubkey(secret: &[u8; 32]) -> OffchainPublicKey {
+    *OffchainKeypair::from_secret(secret).expect("valid secret").public()
+}
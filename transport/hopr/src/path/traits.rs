@@ -31,6 +31,39 @@ pub struct PathWithMetrics {
     pub capacity_floor: Option<u128>,
 }
 
+/// Why a [`PathSelector`] discarded a found candidate path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum PruneReason {
+    /// Dropped from the high-latency tail of the fully measured candidates to
+    /// reduce latency variance (see [`prune_for_consistency`](crate::path::selector::prune_for_consistency)).
+    HigherLatency,
+    /// Not fully measured, and enough measured candidates were available
+    /// (see [`prune_for_consistency`](crate::path::selector::prune_for_consistency)).
+    NotFullyMeasured,
+    /// A relay of the path already appears in the maximum allowed number of candidates
+    /// (see [`DiversityConstrainedPathSelector`](crate::path::DiversityConstrainedPathSelector)).
+    RelayOverused,
+}
+
+/// A candidate path discarded by a [`PathSelector`], together with the reason.
+#[derive(Debug, Clone)]
+pub struct PrunedPath {
+    /// The discarded candidate.
+    pub candidate: PathWithMetrics,
+    /// Why the candidate was discarded.
+    pub reason: PruneReason,
+}
+
+/// Result of [`PathSelector::select_path_explained`].
+#[derive(Debug, Clone, Default)]
+pub struct PathSelection {
+    /// The candidates returned by [`PathSelector::select_path`].
+    pub selected: Vec<PathWithMetrics>,
+    /// The found candidates the selector discarded.
+    pub pruned: Vec<PrunedPath>,
+}
+
 /// Selects multi-hop paths through the network.
 ///
 /// Implementors are responsible for determining how paths are found.
@@ -57,6 +90,22 @@ pub trait PathSelector {
     /// Returns `Err` when no paths can be found.
     fn select_path(&self, src: OffchainPublicKey, dest: OffchainPublicKey, hops: usize)
     -> Result<Vec<PathWithMetrics>>;
+
+    /// Same as [`select_path`](PathSelector::select_path), but also reports the found
+    /// candidates that were discarded, and why.
+    ///
+    /// The default implementation reports no discarded candidates.
+    fn select_path_explained(
+        &self,
+        src: OffchainPublicKey,
+        dest: OffchainPublicKey,
+        hops: usize,
+    ) -> Result<PathSelection> {
+        self.select_path(src, dest, hops).map(|selected| PathSelection {
+            selected,
+            pruned: Vec::new(),
+        })
+    }
 }
 
 /// A selector that can run a background path-cache refresh loop.
@@ -7,7 +7,10 @@ use hopr_api::{
         internal::{prelude::*, routing::ResolvedTransportRouting},
     },
 };
-use hopr_crypto_packet::HoprSurb;
+use hopr_crypto_packet::{
+    HoprSurb,
+    prelude::{HoprSenderId, HoprSurbId, ReplyOpener},
+};
 use hopr_protocol_app::prelude::*;
 use hopr_protocol_hopr::prelude::*;
 use hopr_utils::runtime::AbortableList;
@@ -18,6 +21,61 @@ use crate::{
     protocol::{PacketPipelineBuilder, Unset},
 };
 
+/// [`SurbStore`] backend the [`HoprTransport`](crate::HoprTransport) wires into the
+/// [`HoprPacketPipelineBuilder`] and the path planner.
+///
+/// The persistent backend is available only with the `surb-store-redb` feature.
+#[derive(Clone)]
+pub(crate) enum HoprSurbStore {
+    /// SURBs and reply openers are lost when the node restarts.
+    Memory(MemorySurbStore),
+    /// SURBs and reply openers are persisted in a `redb` database.
+    #[cfg(feature = "surb-store-redb")]
+    Redb(RedbSurbStore),
+}
+
+impl SurbStore for HoprSurbStore {
+    fn find_surb(&self, matcher: SurbMatcher) -> Option<FoundSurb> {
+        match self {
+            Self::Memory(store) => store.find_surb(matcher),
+            #[cfg(feature = "surb-store-redb")]
+            Self::Redb(store) => store.find_surb(matcher),
+        }
+    }
+
+    fn insert_surbs(&self, pseudonym: HoprPseudonym, surbs: Vec<(HoprSurbId, HoprSurb)>) -> usize {
+        match self {
+            Self::Memory(store) => store.insert_surbs(pseudonym, surbs),
+            #[cfg(feature = "surb-store-redb")]
+            Self::Redb(store) => store.insert_surbs(pseudonym, surbs),
+        }
+    }
+
+    fn discard_surbs(&self, pseudonym: &HoprPseudonym, keep_freshest: usize) -> usize {
+        match self {
+            Self::Memory(store) => store.discard_surbs(pseudonym, keep_freshest),
+            #[cfg(feature = "surb-store-redb")]
+            Self::Redb(store) => store.discard_surbs(pseudonym, keep_freshest),
+        }
+    }
+
+    fn insert_reply_opener(&self, sender_id: HoprSenderId, opener: ReplyOpener) {
+        match self {
+            Self::Memory(store) => store.insert_reply_opener(sender_id, opener),
+            #[cfg(feature = "surb-store-redb")]
+            Self::Redb(store) => store.insert_reply_opener(sender_id, opener),
+        }
+    }
+
+    fn find_reply_opener(&self, sender_id: &HoprSenderId) -> Option<ReplyOpener> {
+        match self {
+            Self::Memory(store) => store.find_reply_opener(sender_id),
+            #[cfg(feature = "surb-store-redb")]
+            Self::Redb(store) => store.find_reply_opener(sender_id),
+        }
+    }
+}
+
 /// Builder for the HOPR packet pipeline.
 ///
 /// Creates the encoder/decoder, the unacknowledged-ticket processor, optionally hooks up the
@@ -66,6 +124,9 @@ pub struct HoprPacketPipelineBuilder<
     channels_dst: Option<Hash>,
     cfg: HoprPacketPipelineConfig,
     ticket_events: Option<TEvt>,
+    replay_filter: Option<std::sync::Arc<parking_lot::Mutex<RotatingTagBloomFilter>>>,
+    #[cfg(feature = "capture")]
+    capture: crate::capture::PacketCapture,
 }
 
 impl Default
@@ -112,6 +173,9 @@ impl
             channels_dst: None,
             cfg: HoprPacketPipelineConfig::default(),
             ticket_events: None,
+            replay_filter: None,
+            #[cfg(feature = "capture")]
+            capture: Default::default(),
         }
     }
 }
@@ -133,6 +197,29 @@ impl<WIn, WOut, Chain, S, TFact, AppOut, AppIn, TEvt>
         self
     }
 
+    /// Sets the handle controlling the packet capture of the built pipeline.
+    ///
+    /// By default, the capture can only be started via the `HOPR_CAPTURE_PACKETS` environment variable.
+    #[cfg(feature = "capture")]
+    #[must_use]
+    pub(crate) fn with_capture(mut self, capture: crate::capture::PacketCapture) -> Self {
+        self.capture = capture;
+        self
+    }
+
+    /// Sets the packet replay filter used by the decoder, e.g., one restored after a restart.
+    ///
+    /// The filter stays shared with the caller, so that its state can be persisted while the pipeline runs.
+    /// By default, the decoder starts with an empty filter.
+    #[must_use]
+    pub fn with_replay_filter(
+        mut self,
+        replay_filter: std::sync::Arc<parking_lot::Mutex<RotatingTagBloomFilter>>,
+    ) -> Self {
+        self.replay_filter = Some(replay_filter);
+        self
+    }
+
     /// Sets the node identity (chain and offchain keypairs).
     #[must_use]
     pub fn identity<'a, I>(mut self, identity: I) -> Self
@@ -170,6 +257,9 @@ impl<WIn, WOut, Chain, S, TFact, AppOut, AppIn, TEvt>
             channels_dst: self.channels_dst,
             cfg: self.cfg,
             ticket_events: self.ticket_events,
+            replay_filter: self.replay_filter,
+            #[cfg(feature = "capture")]
+            capture: self.capture,
         }
     }
 
@@ -191,6 +281,9 @@ impl<WIn, WOut, Chain, S, TFact, AppOut, AppIn, TEvt>
             channels_dst: self.channels_dst,
             cfg: self.cfg,
             ticket_events: self.ticket_events,
+            replay_filter: self.replay_filter,
+            #[cfg(feature = "capture")]
+            capture: self.capture,
         }
     }
 
@@ -212,6 +305,9 @@ impl<WIn, WOut, Chain, S, TFact, AppOut, AppIn, TEvt>
             channels_dst: self.channels_dst,
             cfg: self.cfg,
             ticket_events: self.ticket_events,
+            replay_filter: self.replay_filter,
+            #[cfg(feature = "capture")]
+            capture: self.capture,
         }
     }
 
@@ -233,6 +329,9 @@ impl<WIn, WOut, Chain, S, TFact, AppOut, AppIn, TEvt>
             channels_dst: self.channels_dst,
             cfg: self.cfg,
             ticket_events: self.ticket_events,
+            replay_filter: self.replay_filter,
+            #[cfg(feature = "capture")]
+            capture: self.capture,
         }
     }
 
@@ -254,6 +353,9 @@ impl<WIn, WOut, Chain, S, TFact, AppOut, AppIn, TEvt>
             channels_dst: self.channels_dst,
             cfg: self.cfg,
             ticket_events: self.ticket_events,
+            replay_filter: self.replay_filter,
+            #[cfg(feature = "capture")]
+            capture: self.capture,
         }
     }
 
@@ -276,6 +378,9 @@ impl<WIn, WOut, Chain, S, TFact, AppOut, AppIn, TEvt>
             channels_dst: self.channels_dst,
             cfg: self.cfg,
             ticket_events: Some(ticket_events),
+            replay_filter: self.replay_filter,
+            #[cfg(feature = "capture")]
+            capture: self.capture,
         }
     }
 }
@@ -302,8 +407,7 @@ where
     AppIn: futures::Stream<Item = (ResolvedTransportRouting<HoprSurb>, ApplicationDataOut)> + Send + 'static,
 {
     /// Builds the codec pair (and capture wiring when enabled) and the unacknowledged ticket
-    /// processor, returning them together with an [`AbortableList`] already containing the
-    /// capture task if any was started.
+    /// processor.
     #[allow(clippy::type_complexity)]
     fn prepare(
         self,
@@ -316,7 +420,6 @@ where
         Option<TEvt>,
         HoprPacketPipelineConfig,
         // Codec parts in their final shape (possibly wrapped by capture)
-        AbortableList<HoprTransportProcess>,
         BuiltCodec<Chain, S, TFact>,
     ) {
         let HoprPacketPipelineBuilder {
@@ -331,6 +434,9 @@ where
             channels_dst,
             cfg,
             ticket_events,
+            replay_filter,
+            #[cfg(feature = "capture")]
+            capture,
         } = self;
 
         let packet_key = packet_key.expect("identity() must be called before building the pipeline");
@@ -353,41 +459,37 @@ where
             cfg.codec,
         );
 
-        let decoder = HoprDecoder::new(
+        let decoder = HoprDecoder::new_with_replay_filter(
             (packet_key.clone(), chain_key.clone()),
             chain_api.clone(),
             surb_store,
             ticket_factory.clone(),
             channels_dst,
             cfg.codec,
+            replay_filter.unwrap_or_default(),
         );
 
-        #[allow(unused_mut)]
-        let mut processes = AbortableList::default();
-
         #[cfg(feature = "capture")]
         let codec = {
             use crate::capture;
 
-            let writer: Box<dyn capture::PacketWriter + Send + 'static> =
-                if let Ok(desc) = std::env::var("HOPR_CAPTURE_PACKETS") {
-                    if let Ok(pcap_writer) = std::fs::File::create(&desc).and_then(capture::PcapPacketWriter::new) {
+            if let Ok(desc) = std::env::var("HOPR_CAPTURE_PACKETS")
+                && !capture.is_active()
+            {
+                match std::fs::File::create(&desc).and_then(capture::PcapPacketWriter::new) {
+                    Ok(pcap_writer) => {
                         tracing::warn!("pcap file packet capture initialized to {desc}");
-                        Box::new(pcap_writer)
-                    } else {
-                        tracing::error!(desc, "failed to create packet capture: invalid socket address or file");
-                        Box::new(capture::NullWriter)
+                        capture.start(Box::new(pcap_writer), Default::default());
+                    }
+                    Err(error) => {
+                        tracing::error!(desc, %error, "failed to create packet capture: invalid file");
                     }
-                } else {
-                    tracing::warn!("no packet capture specified");
-                    Box::new(capture::NullWriter)
-                };
+                }
+            }
 
-            let (sender, ah) = capture::packet_capture_channel(writer);
-            processes.insert(HoprTransportProcess::Capture, ah);
             BuiltCodec::Captured(
-                capture::CapturePacketCodec::new(encoder, *packet_key.public(), sender.clone()),
-                capture::CapturePacketCodec::new(decoder, *packet_key.public(), sender),
+                capture::CapturePacketCodec::new(encoder, *packet_key.public(), capture.clone()),
+                capture::CapturePacketCodec::new(decoder, *packet_key.public(), capture),
             )
         };
 
@@ -402,7 +504,6 @@ where
             unack_ticket_proc,
             ticket_events,
             cfg,
-            processes,
             codec,
         )
     }
@@ -459,8 +560,7 @@ where
     /// # Panics
     /// Panics if [`HoprPacketPipelineBuilder::with_ticket_events`] was not called.
     pub fn build_for_relay(self) -> AbortableList<HoprTransportProcess> {
-        let (packet_key, wire_msg, api, counters, unack_ticket_proc, ticket_events, _cfg, mut processes, codec) =
-            self.prepare();
+        let (packet_key, wire_msg, api, counters, unack_ticket_proc, ticket_events, _cfg, codec) = self.prepare();
 
         let ticket_events = ticket_events.expect("Relay node requires ticket events; call with_ticket_events() first");
 
@@ -485,6 +585,7 @@ where
                 .build_for_relay(),
         };
 
+        let mut processes = AbortableList::default();
         processes.flat_map_extend_from(inner, HoprTransportProcess::Pipeline);
         processes
     }
@@ -515,7 +616,7 @@ where
     ///
     /// The incoming acknowledgement pipeline is not started; ticket events (if any) are ignored.
     pub fn build_for_entry(self) -> AbortableList<HoprTransportProcess> {
-        let (packet_key, wire_msg, api, counters, _unack, _ticket_events, _cfg, mut processes, codec) = self.prepare();
+        let (packet_key, wire_msg, api, counters, _unack, _ticket_events, _cfg, codec) = self.prepare();
 
         let inner = match codec {
             #[cfg(not(feature = "capture"))]
@@ -536,6 +637,7 @@ where
                 .build_for_entry(),
         };
 
+        let mut processes = AbortableList::default();
         processes.flat_map_extend_from(inner, HoprTransportProcess::Pipeline);
         processes
     }
@@ -545,7 +647,7 @@ where
     /// The incoming acknowledgement pipeline is started but its acknowledgements are drained
     /// (never forwarded to a ticket processor); ticket events (if any) are ignored.
     pub fn build_for_exit(self) -> AbortableList<HoprTransportProcess> {
-        let (packet_key, wire_msg, api, counters, _unack, _ticket_events, _cfg, mut processes, codec) = self.prepare();
+        let (packet_key, wire_msg, api, counters, _unack, _ticket_events, _cfg, codec) = self.prepare();
 
         let inner = match codec {
             #[cfg(not(feature = "capture"))]
@@ -566,6 +668,7 @@ where
                 .build_for_exit(),
         };
 
+        let mut processes = AbortableList::default();
         processes.flat_map_extend_from(inner, HoprTransportProcess::Pipeline);
         processes
     }
@@ -5,7 +5,8 @@
 //  - `CHAIN_DATA` — pre-seeded Blokli emulator state (channels, balances, ticket price).
 //  - Payload generators: `random_packets_of_count`, `random_packet_of_size`.
 //  - Routing helpers: `resolve_mock_path`, `make_routing`, `make_outgoing_packets`.
-//  - Per-peer pipeline wiring: `peer_setup_for`, `peer_setup_for_with_cfg`, `peer_setup_for_with_counters`.
+//  - Per-peer pipeline wiring: `peer_setup_for`, `peer_setup_for_with_cfg`, `peer_setup_for_with_counters`, and
+//    `single_peer_setup` for a peer over any chain API, with a hook wrapping its codec.
 //  - In-process software transport: `emulate_channel_communication` — routes `(PeerId, Bytes)` between peers without
 //    any real network sockets.
 //  - Convenience combined harness: `send_and_receive_packets`, `send_relay_receive_channel_of_n_peers`.
@@ -42,7 +43,7 @@ use hopr_crypto_packet::HoprSurb;
 use hopr_protocol_app::prelude::*;
 use hopr_protocol_hopr::{
     HoprCodecConfig, HoprDecoder, HoprEncoder, HoprUnacknowledgedTicketProcessor,
-    HoprUnacknowledgedTicketProcessorConfig, MemorySurbStore, SurbStoreConfig,
+    HoprUnacknowledgedTicketProcessorConfig, MemorySurbStore, PacketDecoder, PacketEncoder, SurbStoreConfig,
 };
 use hopr_ticket_manager::{HoprTicketFactory, RedbStore};
 use hopr_transport_mixer::config::MixerConfig;
@@ -51,7 +52,9 @@ use lazy_static::lazy_static;
 use libp2p::PeerId;
 use tracing::debug;
 
-use crate::protocol::{PacketPipelineBuilder, PacketPipelineConfig, PeerProtocolCounterRegistry};
+use crate::protocol::{
+    PacketPipelineBuilder, PacketPipelineConfig, PacketPipelineProcesses, PeerProtocolCounterRegistry,
+};
 
 lazy_static! {
     static ref DEFAULT_PRICE_PER_PACKET: HoprBalance = HoprBalance::from_str("0.1 wxHOPR").unwrap();
@@ -290,6 +293,93 @@ pub async fn send_and_receive_packets(
     Ok((recv_packets, ticket_channels, processes))
 }
 
+/// Builds and starts the relay pipeline of a single peer over the given `chain_api`.
+///
+/// The `codec_hook` receives the encoder and decoder of the peer and returns the codec of its pipeline,
+/// so that they can be wrapped (e.g. to capture the packets of the peer).
+#[allow(clippy::too_many_arguments)]
+pub fn single_peer_setup<Chain, T, C, D, F>(
+    (packet_key, chain_key): (&OffchainKeypair, &ChainKeypair),
+    chain_api: Chain,
+    channels_dst: Hash,
+    ticket_factory: T,
+    codec_config: HoprCodecConfig,
+    cfg: PacketPipelineConfig,
+    counters: PeerProtocolCounterRegistry,
+    codec_hook: F,
+) -> (
+    WireChannels,
+    LogicalChannels,
+    TicketChannel,
+    AbortableList<PacketPipelineProcesses>,
+)
+where
+    Chain: ChainKeyOperations
+        + ChainReadChannelOperations
+        + ChainReadTicketOperations
+        + ChainValues
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+    T: hopr_api::tickets::TicketFactory + Clone + Send + Sync + 'static,
+    C: PacketEncoder + Send + Sync + 'static,
+    D: PacketDecoder + Send + Sync + 'static,
+    F: FnOnce(HoprEncoder<Chain, MemorySurbStore, T>, HoprDecoder<Chain, MemorySurbStore, T>) -> (C, D),
+{
+    let (received_ack_tickets_tx, received_ack_tickets_rx) = futures::channel::mpsc::unbounded::<TicketEvent>();
+
+    let (wire_msg_send_tx, wire_msg_send_rx) = futures::channel::mpsc::unbounded::<(PeerId, Bytes)>();
+    let (mixer_channel_tx, mixer_channel_rx) = hopr_transport_mixer::channel::<(PeerId, Bytes)>(MixerConfig::default());
+
+    let (api_send_tx, api_send_rx) =
+        futures::channel::mpsc::unbounded::<(ResolvedTransportRouting<HoprSurb>, ApplicationDataOut)>();
+    let (api_recv_tx, api_recv_rx) = futures::channel::mpsc::unbounded::<(HoprPseudonym, ApplicationDataIn)>();
+
+    let surb_store = MemorySurbStore::new(SurbStoreConfig::default());
+
+    let ticket_proc = HoprUnacknowledgedTicketProcessor::new(
+        chain_api.clone(),
+        chain_key.clone(),
+        channels_dst,
+        HoprUnacknowledgedTicketProcessorConfig::default(),
+    );
+
+    let encoder = HoprEncoder::new(
+        chain_key.clone(),
+        chain_api.clone(),
+        surb_store.clone(),
+        ticket_factory.clone(),
+        channels_dst,
+        codec_config,
+    );
+
+    let decoder = HoprDecoder::new(
+        (packet_key.clone(), chain_key.clone()),
+        chain_api,
+        surb_store,
+        ticket_factory,
+        channels_dst,
+        codec_config,
+    );
+
+    let node_processes = PacketPipelineBuilder::new(packet_key.clone())
+        .transport((mixer_channel_tx, wire_msg_send_rx))
+        .codec(codec_hook(encoder, decoder))
+        .api((api_recv_tx, api_send_rx))
+        .with_counters(counters)
+        .with_config(cfg)
+        .with_ticket_processing(ticket_proc, received_ack_tickets_tx)
+        .build_for_relay();
+
+    (
+        (wire_msg_send_tx, mixer_channel_rx),
+        (api_send_tx, api_recv_rx),
+        received_ack_tickets_rx,
+        node_processes,
+    )
+}
+
 // Sets up N peers with the given `PacketPipelineConfig` and returns per-peer counter registries.
 async fn peer_setup_for_with_all(
     count: usize,
@@ -328,16 +418,6 @@ async fn peer_setup_for_with_all(
     let mut counter_registries = Vec::new();
 
     for i in 0..peer_count {
-        let (received_ack_tickets_tx, received_ack_tickets_rx) = futures::channel::mpsc::unbounded::<TicketEvent>();
-
-        let (wire_msg_send_tx, wire_msg_send_rx) = futures::channel::mpsc::unbounded::<(PeerId, Bytes)>();
-        let (mixer_channel_tx, mixer_channel_rx) =
-            hopr_transport_mixer::channel::<(PeerId, Bytes)>(MixerConfig::default());
-
-        let (api_send_tx, api_send_rx) =
-            futures::channel::mpsc::unbounded::<(ResolvedTransportRouting<HoprSurb>, ApplicationDataOut)>();
-        let (api_recv_tx, api_recv_rx) = futures::channel::mpsc::unbounded::<(HoprPseudonym, ApplicationDataIn)>();
-
         let mut connector = create_trustful_hopr_blokli_connector(
             &PEERS_CHAIN[i],
             Default::default(),
@@ -348,7 +428,6 @@ async fn peer_setup_for_with_all(
         connector.connect().await?;
 
         let connector = Arc::new(connector);
-        let surb_store = MemorySurbStore::new(SurbStoreConfig::default());
         let channels_dst = connector.domain_separators().await?.channel;
 
         let codec_config = HoprCodecConfig {
@@ -357,47 +436,22 @@ async fn peer_setup_for_with_all(
             outgoing_win_prob: Some(WinningProbability::ALWAYS),
         };
 
-        let ticket_proc = HoprUnacknowledgedTicketProcessor::new(
-            connector.clone(),
-            PEERS_CHAIN[i].clone(),
-            channels_dst,
-            HoprUnacknowledgedTicketProcessorConfig::default(),
-        );
-
-        let ticket_factory = Arc::new(HoprTicketFactory::new(RedbStore::new_temp()?));
-
-        let encoder = HoprEncoder::new(
-            PEERS_CHAIN[i].clone(),
-            connector.clone(),
-            surb_store.clone(),
-            ticket_factory.clone(),
-            channels_dst,
-            codec_config,
-        );
+        let counters = PeerProtocolCounterRegistry::default();
 
-        let decoder = HoprDecoder::new(
-            (PEERS[i].clone(), PEERS_CHAIN[i].clone()),
-            connector.clone(),
-            surb_store,
-            ticket_factory.clone(),
+        let (wire, api, ticket_events, node_processes) = single_peer_setup(
+            (&PEERS[i], &PEERS_CHAIN[i]),
+            connector,
             channels_dst,
+            Arc::new(HoprTicketFactory::new(RedbStore::new_temp()?)),
             codec_config,
+            cfg,
+            counters.clone(),
+            |encoder, decoder| (encoder, decoder),
         );
 
-        let counters = PeerProtocolCounterRegistry::default();
-
-        let node_processes = PacketPipelineBuilder::new(PEERS[i].clone())
-            .transport((mixer_channel_tx, wire_msg_send_rx))
-            .codec((encoder, decoder))
-            .api((api_recv_tx, api_send_rx))
-            .with_counters(counters.clone())
-            .with_config(cfg)
-            .with_ticket_processing(ticket_proc, received_ack_tickets_tx)
-            .build_for_relay();
-
-        wire_channels.insert(PeerId::from(*PEERS[i].public()), (wire_msg_send_tx, mixer_channel_rx));
-        logical_channels.push((api_send_tx, api_recv_rx));
-        ticket_channels.push(received_ack_tickets_rx);
+        wire_channels.insert(PeerId::from(*PEERS[i].public()), wire);
+        logical_channels.push(api);
+        ticket_channels.push(ticket_events);
         counter_registries.push(counters);
         processes.insert(i, node_processes);
     }
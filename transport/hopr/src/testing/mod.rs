@@ -6,3 +6,11 @@ pub mod stubs;
 /// pipeline wiring, and the in-process software transport (`emulate_channel_communication`).
 /// Used by both the protocol integration tests and the transport benches.
 pub mod harness;
+
+/// Deterministic replay of packet captures into the packet pipeline, over the chain API stubs.
+#[cfg(feature = "capture")]
+pub mod replay;
+
+/// Fault-injecting variant of the in-process software transport: per-link loss, duplication,
+/// reordering, bandwidth caps and timed partitions, scriptable per test.
+pub mod network;
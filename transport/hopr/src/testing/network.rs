@@ -0,0 +1,607 @@
+// Fault-injecting in-process network for the emulated peers.
+//
+// `emulate_faulty_channel_communication` routes `(PeerId, Bytes)` between peers like
+// `emulate_channel_communication`, but subjects every packet to the fault model of its link:
+//  - `LinkFaults` — latency, random and bursty (Gilbert-Elliott) loss, duplication, reordering window and bandwidth cap
+//    of a single direction of a link.
+//  - `EmulatedNetwork` — handle to change the faults and partition peers while the test runs.
+//  - `FaultScript` — timed sequence of `FaultAction`s played against the `EmulatedNetwork`.
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+use bytes::Bytes;
+use futures::{SinkExt, StreamExt};
+use futures_concurrency::stream::StreamGroup;
+use hopr_api::types::crypto_random::random_float;
+use libp2p::PeerId;
+use tokio::time::Instant;
+
+use crate::testing::harness::WireChannels;
+
+/// Gilbert-Elliott model of bursty packet loss.
+///
+/// The link alternates between a good and a bad state, each with its own loss probability.
+/// The state transitions are evaluated with every packet sent over the link.
+#[derive(Clone, Copy, Debug, PartialEq, smart_default::SmartDefault)]
+pub struct GilbertElliott {
+    /// Probability of moving from the good to the bad state.
+    #[default(0.05)]
+    pub good_to_bad: f64,
+    /// Probability of moving from the bad to the good state.
+    #[default(0.25)]
+    pub bad_to_good: f64,
+    /// Loss probability in the good state.
+    #[default(0.0)]
+    pub loss_in_good: f64,
+    /// Loss probability in the bad state.
+    #[default(1.0)]
+    pub loss_in_bad: f64,
+}
+
+/// Faults injected into the packets sent in one direction of a link.
+///
+/// The default delivers every packet immediately and in order.
+#[derive(Clone, Copy, Debug, PartialEq, smart_default::SmartDefault)]
+pub struct LinkFaults {
+    /// Fixed transit latency.
+    pub latency: Duration,
+    /// Probability that a packet is lost.
+    #[default(0.0)]
+    pub drop_probability: f64,
+    /// Bursty loss, applied in addition to the `drop_probability`.
+    pub burst_loss: Option<GilbertElliott>,
+    /// Probability that a packet is delivered twice.
+    #[default(0.0)]
+    pub duplicate_probability: f64,
+    /// Each packet is delayed by a random duration up to this window on top of the `latency`,
+    /// so packets sent within the window can overtake each other.
+    ///
+    /// Zero keeps the link FIFO.
+    pub reorder_window: Duration,
+    /// Maximum throughput in bytes per second.
+    ///
+    /// Packets exceeding it are queued behind each other, none are lost.
+    pub bandwidth: Option<u64>,
+}
+
+/// Counters of the packets that went through the [`EmulatedNetwork`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NetworkStats {
+    /// Packets sent by the peers.
+    pub sent: usize,
+    /// Packets delivered to the peers, including duplicates.
+    pub delivered: usize,
+    /// Packets lost due to random or bursty loss.
+    pub dropped: usize,
+    /// Packets that were duplicated.
+    pub duplicated: usize,
+    /// Packets lost due to a partition.
+    pub partitioned: usize,
+}
+
+/// Single change of the [`EmulatedNetwork`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum FaultAction {
+    /// Sets the faults of all links without their own faults.
+    SetDefault(LinkFaults),
+    /// Sets the faults of the link from `from` to `to`.
+    SetLink {
+        from: PeerId,
+        to: PeerId,
+        faults: LinkFaults,
+    },
+    /// Makes the link from `from` to `to` use the default faults again.
+    ClearLink { from: PeerId, to: PeerId },
+    /// Cuts all links between the two groups of peers in both directions,
+    /// for the given duration or until [`FaultAction::Heal`].
+    Partition {
+        left: Vec<PeerId>,
+        right: Vec<PeerId>,
+        duration: Option<Duration>,
+    },
+    /// Removes all partitions.
+    Heal,
+}
+
+/// Timed sequence of [`FaultAction`]s, see [`EmulatedNetwork::play`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FaultScript(Vec<(Duration, FaultAction)>);
+
+impl FaultScript {
+    /// Adds an action performed at the given offset from the start of the script.
+    #[must_use]
+    pub fn at(mut self, offset: Duration, action: FaultAction) -> Self {
+        self.0.push((offset, action));
+        self
+    }
+}
+
+struct Partition {
+    left: HashSet<PeerId>,
+    right: HashSet<PeerId>,
+    until: Option<Instant>,
+}
+
+impl Partition {
+    fn separates(&self, a: &PeerId, b: &PeerId, now: Instant) -> bool {
+        self.until.is_none_or(|until| now < until)
+            && ((self.left.contains(a) && self.right.contains(b)) || (self.left.contains(b) && self.right.contains(a)))
+    }
+}
+
+/// Dynamic state of one direction of a link.
+struct LinkState {
+    in_bad_state: bool,
+    busy_until: Instant,
+    last_arrival: Instant,
+}
+
+#[derive(Default)]
+struct NetworkState {
+    default_faults: LinkFaults,
+    link_faults: HashMap<(PeerId, PeerId), LinkFaults>,
+    links: HashMap<(PeerId, PeerId), LinkState>,
+    partitions: Vec<Partition>,
+    stats: NetworkStats,
+}
+
+impl NetworkState {
+    fn apply(&mut self, action: FaultAction, now: Instant) {
+        match action {
+            FaultAction::SetDefault(faults) => self.default_faults = faults,
+            FaultAction::SetLink { from, to, faults } => {
+                self.link_faults.insert((from, to), faults);
+            }
+            FaultAction::ClearLink { from, to } => {
+                self.link_faults.remove(&(from, to));
+            }
+            FaultAction::Partition { left, right, duration } => self.partitions.push(Partition {
+                left: left.into_iter().collect(),
+                right: right.into_iter().collect(),
+                until: duration.map(|duration| now + duration),
+            }),
+            FaultAction::Heal => self.partitions.clear(),
+        }
+    }
+
+    /// Returns the arrival times of the copies of a packet of `len` bytes sent at `now`,
+    /// which is empty if the packet is lost.
+    fn schedule(&mut self, from: PeerId, to: PeerId, len: usize, now: Instant) -> Vec<Instant> {
+        self.stats.sent += 1;
+        self.partitions
+            .retain(|partition| partition.until.is_none_or(|until| now < until));
+
+        if self
+            .partitions
+            .iter()
+            .any(|partition| partition.separates(&from, &to, now))
+        {
+            self.stats.partitioned += 1;
+            return Vec::new();
+        }
+
+        let faults = self
+            .link_faults
+            .get(&(from, to))
+            .copied()
+            .unwrap_or(self.default_faults);
+        let link = self.links.entry((from, to)).or_insert_with(|| LinkState {
+            in_bad_state: false,
+            busy_until: now,
+            last_arrival: now,
+        });
+
+        let mut lost = faults.drop_probability > 0.0 && random_float() < faults.drop_probability;
+        if let Some(model) = faults.burst_loss {
+            link.in_bad_state = if link.in_bad_state {
+                random_float() >= model.bad_to_good
+            } else {
+                random_float() < model.good_to_bad
+            };
+            let loss = if link.in_bad_state {
+                model.loss_in_bad
+            } else {
+                model.loss_in_good
+            };
+            lost |= random_float() < loss;
+        }
+        if lost {
+            self.stats.dropped += 1;
+            return Vec::new();
+        }
+
+        let departure = match faults.bandwidth {
+            Some(bandwidth) if bandwidth > 0 => {
+                link.busy_until = link.busy_until.max(now) + Duration::from_secs_f64(len as f64 / bandwidth as f64);
+                link.busy_until
+            }
+            _ => now,
+        };
+
+        let copies = if faults.duplicate_probability > 0.0 && random_float() < faults.duplicate_probability {
+            self.stats.duplicated += 1;
+            2
+        } else {
+            1
+        };
+
+        (0..copies)
+            .map(|_| {
+                let arrival = if faults.reorder_window.is_zero() {
+                    // Keep the link FIFO even when its latency decreases
+                    (departure + faults.latency).max(link.last_arrival)
+                } else {
+                    departure + faults.latency + faults.reorder_window.mul_f64(random_float())
+                };
+                link.last_arrival = link.last_arrival.max(arrival);
+                arrival
+            })
+            .collect()
+    }
+}
+
+/// Handle controlling the faults of the network emulated by [`emulate_faulty_channel_communication`].
+///
+/// The clones share the same network, so the faults can be changed while the network is running.
+#[derive(Clone, Default)]
+pub struct EmulatedNetwork(Arc<parking_lot::Mutex<NetworkState>>);
+
+impl EmulatedNetwork {
+    /// Creates a network whose links all have the given faults.
+    pub fn new(default_faults: LinkFaults) -> Self {
+        let network = Self::default();
+        network.apply(FaultAction::SetDefault(default_faults));
+        network
+    }
+
+    /// Performs the action immediately.
+    pub fn apply(&self, action: FaultAction) {
+        self.0.lock().apply(action, Instant::now());
+    }
+
+    /// Sets the faults of the link from `from` to `to`, the opposite direction is not affected.
+    pub fn set_link_faults(&self, from: PeerId, to: PeerId, faults: LinkFaults) {
+        self.apply(FaultAction::SetLink { from, to, faults });
+    }
+
+    /// Cuts all links between the two groups of peers, for the given duration or until [`EmulatedNetwork::heal`].
+    ///
+    /// Packets already in flight are still delivered.
+    pub fn partition(
+        &self,
+        left: impl IntoIterator<Item = PeerId>,
+        right: impl IntoIterator<Item = PeerId>,
+        duration: Option<Duration>,
+    ) {
+        self.apply(FaultAction::Partition {
+            left: left.into_iter().collect(),
+            right: right.into_iter().collect(),
+            duration,
+        });
+    }
+
+    /// Removes all partitions.
+    pub fn heal(&self) {
+        self.apply(FaultAction::Heal);
+    }
+
+    /// Returns the counters of the packets that went through the network.
+    pub fn stats(&self) -> NetworkStats {
+        self.0.lock().stats
+    }
+
+    /// Spawns a task performing the actions of the script at their offsets from now.
+    pub fn play(&self, script: FaultScript) -> tokio::task::JoinHandle<()> {
+        let network = self.clone();
+        let start = Instant::now();
+        let mut actions = script.0;
+        actions.sort_by_key(|(offset, _)| *offset);
+
+        tokio::task::spawn(async move {
+            for (offset, action) in actions {
+                tokio::time::sleep_until(start + offset).await;
+                tracing::debug!(?offset, ?action, "applying network fault action");
+                network.apply(action);
+            }
+        })
+    }
+}
+
+/// Packet waiting for its arrival time.
+struct InFlight {
+    arrival: Instant,
+    seq: u64,
+    from: PeerId,
+    to: PeerId,
+    data: Bytes,
+}
+
+impl PartialEq for InFlight {
+    fn eq(&self, other: &Self) -> bool {
+        (self.arrival, self.seq) == (other.arrival, other.seq)
+    }
+}
+
+impl Eq for InFlight {}
+
+impl PartialOrd for InFlight {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for InFlight {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.arrival, self.seq).cmp(&(other.arrival, other.seq))
+    }
+}
+
+/// Routes packets between the peers like
+/// [`emulate_channel_communication`](super::harness::emulate_channel_communication), applying the faults of the
+/// `network` to each of them.
+///
+/// Finishes once all peers stop sending and the packets in flight are delivered.
+#[tracing::instrument(level = "debug", skip(components, network))]
+pub async fn emulate_faulty_channel_communication(components: HashMap<PeerId, WireChannels>, network: EmulatedNetwork) {
+    let (mut senders, streams): (HashMap<_, _>, Vec<_>) = components
+        .into_iter()
+        .map(|(peer, (tx, rx))| ((peer, tx), rx.map(move |(target, msg)| (peer, target, msg))))
+        .unzip();
+
+    let mut stream_group = StreamGroup::from_iter(streams);
+    let mut in_flight = BinaryHeap::<Reverse<InFlight>>::new();
+    let mut seq = 0;
+    let mut sending = true;
+
+    while sending || !in_flight.is_empty() {
+        let next_arrival = in_flight.peek().map(|Reverse(packet)| packet.arrival);
+
+        tokio::select! {
+            biased;
+            _ = tokio::time::sleep_until(next_arrival.unwrap_or_else(Instant::now)), if next_arrival.is_some() => {
+                let now = Instant::now();
+                while in_flight.peek().is_some_and(|Reverse(packet)| packet.arrival <= now) {
+                    let Some(Reverse(packet)) = in_flight.pop() else {
+                        break;
+                    };
+                    let target_sender = senders
+                        .get_mut(&packet.to)
+                        .unwrap_or_else(|| panic!("peer {} should be part of the test setup", packet.to));
+
+                    tracing::trace!(sender = %packet.from, target = %packet.to, "transporting packet");
+
+                    target_sender
+                        .send((packet.from, packet.data))
+                        .await
+                        .expect("failed to send packet to peer");
+                    network.0.lock().stats.delivered += 1;
+                }
+            }
+            msg = stream_group.next(), if sending => match msg {
+                Some((sender, target, msg)) => {
+                    let arrivals = network.0.lock().schedule(sender, target, msg.len(), Instant::now());
+                    for arrival in arrivals {
+                        seq += 1;
+                        in_flight.push(Reverse(InFlight {
+                            arrival,
+                            seq,
+                            from: sender,
+                            to: target,
+                            data: msg.clone(),
+                        }));
+                    }
+                }
+                None => sending = false,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEN: usize = 1000;
+
+    fn peers() -> (PeerId, PeerId, PeerId) {
+        (PeerId::random(), PeerId::random(), PeerId::random())
+    }
+
+    #[test]
+    fn perfect_link_should_deliver_everything_immediately_in_order() {
+        let (a, b, _) = peers();
+        let mut state = NetworkState::default();
+        let now = Instant::now();
+
+        for _ in 0..100 {
+            assert_eq!(vec![now], state.schedule(a, b, LEN, now));
+        }
+        assert_eq!(
+            NetworkStats {
+                sent: 100,
+                ..Default::default()
+            },
+            state.stats
+        );
+    }
+
+    #[test]
+    fn lossy_link_should_drop_and_duplicate_packets() {
+        let (a, b, c) = peers();
+        let mut state = NetworkState::default();
+        let now = Instant::now();
+
+        state.apply(
+            FaultAction::SetLink {
+                from: a,
+                to: b,
+                faults: LinkFaults {
+                    drop_probability: 1.0,
+                    ..Default::default()
+                },
+            },
+            now,
+        );
+        state.apply(
+            FaultAction::SetLink {
+                from: a,
+                to: c,
+                faults: LinkFaults {
+                    duplicate_probability: 1.0,
+                    ..Default::default()
+                },
+            },
+            now,
+        );
+
+        assert!(state.schedule(a, b, LEN, now).is_empty());
+        assert_eq!(2, state.schedule(a, c, LEN, now).len());
+        // The opposite direction is not affected
+        assert_eq!(1, state.schedule(b, a, LEN, now).len());
+
+        state.apply(FaultAction::ClearLink { from: a, to: b }, now);
+        assert_eq!(1, state.schedule(a, b, LEN, now).len());
+
+        assert_eq!(1, state.stats.dropped);
+        assert_eq!(1, state.stats.duplicated);
+    }
+
+    #[test]
+    fn bursty_loss_should_drop_packets_in_bad_state_only() {
+        let (a, b, _) = peers();
+        let mut state = NetworkState::default();
+        let now = Instant::now();
+
+        let model = GilbertElliott {
+            good_to_bad: 0.0,
+            bad_to_good: 0.0,
+            ..Default::default()
+        };
+        state.apply(
+            FaultAction::SetDefault(LinkFaults {
+                burst_loss: Some(model),
+                ..Default::default()
+            }),
+            now,
+        );
+        assert!((0..50).all(|_| state.schedule(a, b, LEN, now).len() == 1));
+
+        state.apply(
+            FaultAction::SetDefault(LinkFaults {
+                burst_loss: Some(GilbertElliott {
+                    good_to_bad: 1.0,
+                    ..model
+                }),
+                ..Default::default()
+            }),
+            now,
+        );
+        assert!(state.schedule(a, b, LEN, now).is_empty());
+
+        // The link stays in the bad state
+        state.apply(
+            FaultAction::SetDefault(LinkFaults {
+                burst_loss: Some(model),
+                ..Default::default()
+            }),
+            now,
+        );
+        assert!((0..50).all(|_| state.schedule(a, b, LEN, now).is_empty()));
+        assert_eq!(51, state.stats.dropped);
+    }
+
+    #[test]
+    fn bandwidth_cap_should_queue_packets() {
+        let (a, b, _) = peers();
+        let mut state = NetworkState::default();
+        let now = Instant::now();
+
+        state.apply(
+            FaultAction::SetDefault(LinkFaults {
+                latency: Duration::from_millis(10),
+                bandwidth: Some(100_000),
+                ..Default::default()
+            }),
+            now,
+        );
+
+        // 1000 bytes take 10 ms at 100 kB/s
+        for i in 1..=5 {
+            assert_eq!(
+                vec![now + Duration::from_millis(10 + 10 * i)],
+                state.schedule(a, b, LEN, now)
+            );
+        }
+        assert_eq!(0, state.stats.dropped);
+    }
+
+    #[test]
+    fn reorder_window_should_reorder_packets() {
+        let (a, b, _) = peers();
+        let mut state = NetworkState::default();
+        let now = Instant::now();
+
+        state.apply(
+            FaultAction::SetDefault(LinkFaults {
+                latency: Duration::from_millis(5),
+                reorder_window: Duration::from_millis(100),
+                ..Default::default()
+            }),
+            now,
+        );
+
+        let arrivals = (0..100)
+            .flat_map(|i| state.schedule(a, b, LEN, now + Duration::from_millis(i)))
+            .collect::<Vec<_>>();
+
+        assert!(!arrivals.is_sorted());
+        assert!(
+            arrivals
+                .iter()
+                .enumerate()
+                .all(|(i, arrival)| *arrival >= now + Duration::from_millis(i as u64 + 5)
+                    && *arrival <= now + Duration::from_millis(i as u64 + 105))
+        );
+    }
+
+    #[test]
+    fn partition_should_cut_links_between_groups_until_it_expires() {
+        let (a, b, c) = peers();
+        let mut state = NetworkState::default();
+        let now = Instant::now();
+
+        state.apply(
+            FaultAction::Partition {
+                left: vec![a],
+                right: vec![b],
+                duration: Some(Duration::from_secs(1)),
+            },
+            now,
+        );
+
+        assert!(state.schedule(a, b, LEN, now).is_empty());
+        assert!(state.schedule(b, a, LEN, now).is_empty());
+        assert_eq!(1, state.schedule(a, c, LEN, now).len());
+        assert_eq!(1, state.schedule(c, b, LEN, now).len());
+
+        let later = now + Duration::from_secs(1);
+        assert_eq!(1, state.schedule(a, b, LEN, later).len());
+        assert_eq!(2, state.stats.partitioned);
+
+        state.apply(
+            FaultAction::Partition {
+                left: vec![a, c],
+                right: vec![b],
+                duration: None,
+            },
+            later,
+        );
+        assert!(state.schedule(c, b, LEN, later).is_empty());
+        state.apply(FaultAction::Heal, later);
+        assert_eq!(1, state.schedule(c, b, LEN, later).len());
+    }
+}
@@ -0,0 +1,662 @@
+// Deterministic replay of packet captures into the packet pipeline.
+//
+// A capture recorded with `CaptureFilter::wire` contains the wire data of every packet received
+// by the capturing node, including the packets it rejected. `CaptureReplay` feeds them into a fresh pipeline of the
+// same node, built in-process over the `StubChainApi`, keeping the recorded delays between the packets (optionally sped
+// up). The replayed pipeline is captured as well, and the `ReplayReport` compares its records, deliveries
+// and ticket events with what was recorded.
+//
+// The capture must not be sampled, and the chain state of the stub must match the one the capturing
+// node saw (key IDs, ticket price and winning probability), otherwise the packets fail to decode.
+// The channels, the channels domain separator and the outgoing ticket state of the capturing node
+// at the start of the capture are given by the `ReplayConfig`.
+use std::{fs::File, io::BufReader, path::Path, str::FromStr, sync::Arc, time::Duration};
+
+use bytes::Bytes;
+use futures::{StreamExt, stream};
+use futures_time::future::FutureExt;
+use hopr_api::{
+    node::TicketEvent,
+    types::{crypto::prelude::*, internal::prelude::*, primitive::prelude::HoprBalance},
+};
+use hopr_protocol_app::prelude::*;
+use hopr_protocol_hopr::HoprCodecConfig;
+use hopr_ticket_manager::{HoprTicketFactory, MemoryStore};
+use libp2p::PeerId;
+
+/// Handle controlling the packet capture of a peer set up with [`capture_codec`].
+pub use crate::capture::PacketCapture;
+use crate::{
+    capture::{
+        CaptureFilter, CapturePacketCodec, CaptureReader, CapturedPacket, CapturedPacketContent, DecodedPacket,
+        DecodedPayload, PacketWriter,
+    },
+    protocol::PacketPipelineConfig,
+    testing::{
+        harness::{PEERS, PEERS_CHAIN, single_peer_setup},
+        stubs::StubChainApi,
+    },
+};
+
+/// Returns the [`StubChainApi`] of the peer `index` of the [`PEERS`] fixtures.
+///
+/// All the fixture peers are known, and there are open channels in both directions
+/// between the consecutive peers.
+pub fn stub_chain_api_for(index: usize) -> StubChainApi {
+    fn channel(src: usize, dst: usize) -> ChannelEntry {
+        ChannelEntry::builder()
+            .between(
+                PEERS_CHAIN[src].public().to_address(),
+                PEERS_CHAIN[dst].public().to_address(),
+            )
+            .balance(HoprBalance::from_str("100 wxHOPR").expect("valid balance"))
+            .ticket_index(0)
+            .status(ChannelStatus::Open)
+            .epoch(1)
+            .build()
+            .expect("valid channel")
+    }
+
+    let mut builder = StubChainApi::builder().me(PEERS_CHAIN[index].public().to_address());
+    for (offchain, chain) in PEERS.iter().zip(PEERS_CHAIN.iter()) {
+        builder = builder.peer(offchain.public(), chain.public().to_address());
+    }
+    for i in 0..PEERS_CHAIN.len() - 1 {
+        builder = builder.channel(channel(i, i + 1)).channel(channel(i + 1, i));
+    }
+
+    builder
+        .ticket_price(HoprBalance::from_str("0.1 wxHOPR").expect("valid balance"))
+        .build()
+}
+
+/// Codec hook of the [`single_peer_setup`] capturing the packets of the peer into the `capture`.
+pub fn capture_codec<E, D>(
+    packet_key: OffchainPublicKey,
+    capture: PacketCapture,
+) -> impl FnOnce(E, D) -> (CapturePacketCodec<E>, CapturePacketCodec<D>) {
+    move |encoder, decoder| {
+        (
+            CapturePacketCodec::new(encoder, packet_key, capture.clone()),
+            CapturePacketCodec::new(decoder, packet_key, capture),
+        )
+    }
+}
+
+/// Configuration of the [`CaptureReplay`].
+#[derive(Clone, smart_default::SmartDefault)]
+pub struct ReplayConfig {
+    /// Factor by which the recorded delays between the packets are shortened, must be positive.
+    ///
+    /// `1.0` keeps the original timing, `f64::INFINITY` feeds the packets without any delays.
+    #[default(1.0)]
+    pub speedup: f64,
+    /// The replay finishes once the pipeline produces nothing for this long after the last packet was fed.
+    #[default(Duration::from_millis(500))]
+    pub quiet_period: Duration,
+    /// Codec configuration of the replayed node, should match the one of the capturing node.
+    pub codec: HoprCodecConfig,
+    /// Pipeline configuration of the replayed node.
+    pub pipeline: PacketPipelineConfig,
+    /// Channels domain separator of the chain the capturing node was on.
+    pub channels_dst: Hash,
+    /// Channels of the capturing node as they were when the capture started.
+    ///
+    /// They replace the channels with the same ID given by the [`StubChainApi`] of the replay.
+    pub channels: Vec<ChannelEntry>,
+    /// Ticket factory holding the state of the outgoing tickets of the capturing node when the capture started.
+    ///
+    /// The replay issues tickets from this factory, so each replay needs a factory of its own.
+    #[default(Arc::new(HoprTicketFactory::new(MemoryStore::default())))]
+    pub ticket_factory: Arc<HoprTicketFactory<MemoryStore>>,
+}
+
+impl std::fmt::Debug for ReplayConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReplayConfig")
+            .field("speedup", &self.speedup)
+            .field("quiet_period", &self.quiet_period)
+            .field("codec", &self.codec)
+            .field("pipeline", &self.pipeline)
+            .field("channels_dst", &self.channels_dst)
+            .field("channels", &self.channels)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Outcome of a [`CaptureReplay`].
+///
+/// The records are compared regardless of their order, since the pipeline processes packets concurrently.
+/// Outgoing acknowledgements are compared one by one, as their batching depends on timing.
+#[derive(Debug, Default)]
+pub struct ReplayReport {
+    /// Number of wire packets fed into the pipeline.
+    pub replayed: usize,
+    /// Captured records that the replay did not reproduce.
+    pub missing: Vec<CapturedPacketContent>,
+    /// Records of the replay that are not in the capture.
+    pub unexpected: Vec<CapturedPacketContent>,
+    /// Captured final packets that the replay did not deliver to the application.
+    pub undelivered: Vec<CapturedPacketContent>,
+    /// Data delivered by the replayed pipeline to the application.
+    pub delivered: Vec<(HoprPseudonym, ApplicationDataIn)>,
+    /// Ticket events emitted by the replayed pipeline.
+    pub ticket_events: Vec<TicketEvent>,
+    /// Tickets of the ticket events that were not received in any captured packet.
+    pub unrecorded_tickets: Vec<Ticket>,
+    /// Tickets received in captured packets for which the replayed pipeline emitted no ticket event.
+    ///
+    /// Losing tickets emit no event, so only the tickets that always win make the replay unfaithful.
+    pub unreproduced_tickets: Vec<Ticket>,
+}
+
+impl ReplayReport {
+    /// Indicates whether the replay reproduced exactly what was captured.
+    pub fn is_faithful(&self) -> bool {
+        self.missing.is_empty()
+            && self.unexpected.is_empty()
+            && self.undelivered.is_empty()
+            && self.unrecorded_tickets.is_empty()
+            && self
+                .unreproduced_tickets
+                .iter()
+                .all(|ticket| !ticket.win_prob().approx_eq(&WinningProbability::ALWAYS))
+    }
+}
+
+/// Replays packet captures of a single node into its pipeline built over the [`StubChainApi`].
+pub struct CaptureReplay {
+    packet_key: OffchainKeypair,
+    chain_key: ChainKeypair,
+    chain_api: StubChainApi,
+    cfg: ReplayConfig,
+}
+
+impl CaptureReplay {
+    pub fn new(
+        packet_key: OffchainKeypair,
+        chain_key: ChainKeypair,
+        chain_api: StubChainApi,
+        cfg: ReplayConfig,
+    ) -> Self {
+        Self {
+            packet_key,
+            chain_key,
+            chain_api,
+            cfg,
+        }
+    }
+
+    /// Replays the capture files, in the order they were written.
+    pub async fn replay_files<P: AsRef<Path>>(
+        &self,
+        paths: impl IntoIterator<Item = P>,
+    ) -> anyhow::Result<ReplayReport> {
+        let mut packets = Vec::new();
+        for path in paths {
+            for packet in CaptureReader::new(BufReader::new(File::open(path)?))? {
+                packets.push(packet?);
+            }
+        }
+        self.replay(packets).await
+    }
+
+    /// Replays the captured packets.
+    ///
+    /// Only the wire data are fed into the pipeline, the other records of the capturing node
+    /// are compared with those of the replay. Records of other nodes are ignored.
+    pub async fn replay(&self, packets: impl IntoIterator<Item = DecodedPacket>) -> anyhow::Result<ReplayReport> {
+        anyhow::ensure!(self.cfg.speedup > 0.0, "replay speedup must be positive");
+
+        let me = *self.packet_key.public();
+        let (records_tx, records_rx) = futures::channel::mpsc::unbounded();
+        let capture = PacketCapture::default();
+        capture.start(Box::new(RecordCollector(records_tx)), CaptureFilter::default());
+
+        let mut chain_api = self.chain_api.clone();
+        self.cfg
+            .channels
+            .iter()
+            .cloned()
+            .for_each(|channel| chain_api.insert_channel(channel));
+
+        let ((wire_in, wire_out), (_api_send, api_recv), ticket_events, processes) = single_peer_setup(
+            (&self.packet_key, &self.chain_key),
+            chain_api,
+            self.cfg.channels_dst,
+            self.cfg.ticket_factory.clone(),
+            self.cfg.codec,
+            self.cfg.pipeline,
+            Default::default(),
+            capture_codec(me, capture.clone()),
+        );
+
+        let mut report = ReplayReport::default();
+        let mut recorded = Vec::new();
+        let mut last_timestamp = None;
+        for packet in packets {
+            // Records of incoming relayed packets do not carry the capturing node
+            if packet.me().is_some_and(|node| node != &me) {
+                continue;
+            }
+
+            match packet.content {
+                CapturedPacketContent::Wire { previous_hop, data, .. } => {
+                    if let Some(last) = last_timestamp.replace(packet.timestamp) {
+                        tokio::time::sleep(packet.timestamp.saturating_sub(last).div_f64(self.cfg.speedup)).await;
+                    }
+                    wire_in.unbounded_send((PeerId::from(previous_hop), Bytes::from(data.into_vec())))?;
+                    report.replayed += 1;
+                }
+                // Packets originating at the node are not replayed
+                CapturedPacketContent::Outgoing {
+                    is_forwarded: false, ..
+                } => {}
+                content => recorded.extend(split_acknowledgements(content)),
+            }
+        }
+
+        enum Output {
+            Record(CapturedPacket),
+            Delivery((HoprPseudonym, ApplicationDataIn)),
+            Ticket(TicketEvent),
+            Wire,
+        }
+
+        let mut outputs = stream::select(
+            stream::select(records_rx.map(Output::Record), api_recv.map(Output::Delivery)),
+            stream::select(ticket_events.map(Output::Ticket), wire_out.map(|_| Output::Wire)),
+        );
+
+        let mut reproduced = Vec::new();
+        while let Ok(Some(output)) = outputs
+            .next()
+            .timeout(futures_time::time::Duration::from(self.cfg.quiet_period))
+            .await
+        {
+            match output {
+                Output::Record(record) => match DecodedPacket::try_from(&record)?.content {
+                    CapturedPacketContent::Outgoing {
+                        is_forwarded: false, ..
+                    } => {}
+                    content => reproduced.extend(split_acknowledgements(content)),
+                },
+                Output::Delivery(delivery) => report.delivered.push(delivery),
+                Output::Ticket(event) => report.ticket_events.push(event),
+                Output::Wire => {}
+            }
+        }
+
+        capture.stop();
+        processes.abort_all();
+
+        let recorded_tickets = recorded
+            .iter()
+            .filter_map(|content| match content {
+                CapturedPacketContent::Forwarded { ticket, .. } => *ticket,
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let event_tickets = report
+            .ticket_events
+            .iter()
+            .map(|event| match event {
+                TicketEvent::WinningTicket(ticket) => *ticket.verified_ticket(),
+                TicketEvent::RejectedTicket(ticket, _) => **ticket,
+            })
+            .collect::<Vec<_>>();
+        report.unrecorded_tickets = event_tickets
+            .iter()
+            .filter(|ticket| !recorded_tickets.contains(ticket))
+            .copied()
+            .collect();
+        report.unreproduced_tickets = recorded_tickets
+            .into_iter()
+            .filter(|ticket| !event_tickets.contains(ticket))
+            .collect();
+
+        let mut deliveries = report
+            .delivered
+            .iter()
+            .map(|(pseudonym, data)| (*pseudonym, DecodedPayload::decode(&data.data.to_bytes())))
+            .collect::<Vec<_>>();
+        report.undelivered = recorded
+            .iter()
+            .filter(|content| match content {
+                CapturedPacketContent::Final { sender, payload, .. } => {
+                    match deliveries
+                        .iter()
+                        .position(|(pseudonym, data)| pseudonym == sender && data == payload)
+                    {
+                        Some(index) => {
+                            deliveries.swap_remove(index);
+                            false
+                        }
+                        None => true,
+                    }
+                }
+                _ => false,
+            })
+            .cloned()
+            .collect();
+
+        (report.missing, report.unexpected) = multiset_difference(recorded, reproduced);
+        Ok(report)
+    }
+}
+
+/// [`PacketWriter`] passing the captured packets of the replayed pipeline to the replay.
+struct RecordCollector(futures::channel::mpsc::UnboundedSender<CapturedPacket>);
+
+impl PacketWriter for RecordCollector {
+    fn write_packet(&mut self, packet: CapturedPacket) -> std::io::Result<()> {
+        self.0.unbounded_send(packet).map_err(std::io::Error::other)
+    }
+}
+
+/// Splits the outgoing acknowledgement records into records of a single acknowledgement.
+fn split_acknowledgements(content: CapturedPacketContent) -> Vec<CapturedPacketContent> {
+    match content {
+        CapturedPacketContent::OutgoingAck {
+            me,
+            next_hop,
+            is_random,
+            acks,
+        } => acks
+            .into_iter()
+            .map(|ack| CapturedPacketContent::OutgoingAck {
+                me,
+                next_hop,
+                is_random,
+                acks: vec![ack],
+            })
+            .collect(),
+        content => vec![content],
+    }
+}
+
+/// Returns the items only in `left` and the items only in `right`, counting repeated items.
+fn multiset_difference<T: PartialEq>(left: Vec<T>, mut right: Vec<T>) -> (Vec<T>, Vec<T>) {
+    let mut only_left = Vec::new();
+    for item in left {
+        match right.iter().position(|other| other == &item) {
+            Some(index) => {
+                right.swap_remove(index);
+            }
+            None => only_left.push(item),
+        }
+    }
+    (only_left, right)
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::SinkExt;
+    use hopr_api::types::{internal::routing::ResolvedTransportRouting, primitive::prelude::Address};
+    use hopr_crypto_packet::HoprSurb;
+
+    use super::*;
+    use crate::{
+        capture::PcapPacketWriter,
+        testing::harness::{
+            emulate_channel_communication, make_outgoing_packets, random_packets_of_count, resolve_mock_path,
+        },
+    };
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Sends `count` packets from the peer 0 to the peer 2 via the peer 1, capturing the packets of the peer
+    /// `capturing`.
+    ///
+    /// Finally, an undecodable packet is sent to the capturing peer.
+    async fn record_capture(capturing: usize, path: &Path, count: usize) -> anyhow::Result<()> {
+        let capture = PacketCapture::default();
+        capture.start(
+            Box::new(File::create(path).and_then(PcapPacketWriter::new)?),
+            CaptureFilter {
+                wire: true,
+                ..Default::default()
+            },
+        );
+
+        let mut wire = std::collections::HashMap::new();
+        let mut capturing_wire_in = None;
+        let mut apis = Vec::new();
+        let mut tickets = Vec::new();
+        let mut processes = Vec::new();
+        for i in 0..3 {
+            let (peer_wire, api, ticket_events, peer_processes) = single_peer_setup(
+                (&PEERS[i], &PEERS_CHAIN[i]),
+                stub_chain_api_for(i),
+                Default::default(),
+                Arc::new(HoprTicketFactory::new(MemoryStore::default())),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                capture_codec(
+                    *PEERS[i].public(),
+                    if i == capturing {
+                        capture.clone()
+                    } else {
+                        PacketCapture::default()
+                    },
+                ),
+            );
+            if i == capturing {
+                capturing_wire_in = Some(peer_wire.0.clone());
+            }
+            wire.insert(PeerId::from(*PEERS[i].public()), peer_wire);
+            apis.push(api);
+            tickets.push(ticket_events);
+            processes.push(peer_processes);
+        }
+        tokio::task::spawn(emulate_channel_communication(wire));
+
+        let path_to_recipient = resolve_mock_path(
+            PEERS_CHAIN[0].public().to_address(),
+            PEERS_CHAIN[1..3]
+                .iter()
+                .map(|key| key.public().to_address())
+                .collect::<Vec<Address>>(),
+        )
+        .await?;
+
+        let out_msgs: Vec<(ResolvedTransportRouting<HoprSurb>, ApplicationDataOut)> =
+            make_outgoing_packets(&random_packets_of_count(count), path_to_recipient);
+        apis[0].0.send_all(&mut stream::iter(out_msgs).map(Ok)).await?;
+
+        let (_, mut api_recv): (Vec<_>, Vec<_>) = apis.into_iter().unzip();
+        let received = api_recv
+            .remove(2)
+            .take(count)
+            .collect::<Vec<_>>()
+            .timeout(futures_time::time::Duration::from(TIMEOUT))
+            .await?;
+        assert_eq!(count, received.len());
+
+        let tickets = tickets
+            .remove(1)
+            .take(count)
+            .collect::<Vec<_>>()
+            .timeout(futures_time::time::Duration::from(TIMEOUT))
+            .await?;
+        assert_eq!(count, tickets.len());
+
+        capturing_wire_in
+            .ok_or(anyhow::anyhow!("capturing peer must exist"))?
+            .unbounded_send((
+                PeerId::from(*PEERS[(capturing + 1) % 3].public()),
+                Bytes::from(hopr_api::types::crypto_random::random_bytes::<512>().to_vec()),
+            ))?;
+
+        // Let the acknowledgements settle and the capture be written
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert!(capture.stop());
+        processes.into_iter().for_each(|p| p.abort_all());
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[case::sender(0)]
+    #[case::relay(1)]
+    #[case::recipient(2)]
+    #[test_log::test(tokio::test)]
+    async fn replay_should_reproduce_recorded_traffic(#[case] capturing: usize) -> anyhow::Result<()> {
+        const PACKET_COUNT: usize = 5;
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("capture.pcapng");
+        record_capture(capturing, &path, PACKET_COUNT).await?;
+
+        let replay = CaptureReplay::new(
+            PEERS[capturing].clone(),
+            PEERS_CHAIN[capturing].clone(),
+            stub_chain_api_for(capturing),
+            ReplayConfig {
+                speedup: 2.0,
+                ..Default::default()
+            },
+        );
+        let report = replay.replay_files([&path]).await?;
+
+        assert!(report.is_faithful(), "replay is not faithful: {report:#?}");
+        assert!(report.unreproduced_tickets.is_empty());
+        match capturing {
+            // Acknowledgements of the sent packets, possibly batched
+            0 => {
+                assert!(report.replayed > 0);
+                assert!(report.delivered.is_empty());
+                assert!(report.ticket_events.is_empty());
+            }
+            // Relayed packets and their acknowledgements
+            1 => {
+                assert!(report.replayed > PACKET_COUNT);
+                assert!(report.delivered.is_empty());
+                assert_eq!(PACKET_COUNT, report.ticket_events.len());
+                assert!(report.ticket_events.iter().all(|event| event.is_winning_ticket()));
+            }
+            // Delivered packets and the rejected packet
+            _ => {
+                assert_eq!(PACKET_COUNT + 1, report.replayed);
+                assert_eq!(PACKET_COUNT, report.delivered.len());
+                assert!(report.ticket_events.is_empty());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn replay_should_report_differences_from_recorded_traffic() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("capture.pcapng");
+        record_capture(2, &path, 3).await?;
+
+        let mut packets = CaptureReader::new(BufReader::new(File::open(&path)?))?.collect::<Result<Vec<_>, _>>()?;
+
+        // Drop the wire data of the first packet, so that its delivery is not reproduced
+        let first_wire = packets
+            .iter()
+            .position(|packet| matches!(packet.content, CapturedPacketContent::Wire { .. }))
+            .ok_or(anyhow::anyhow!("capture must contain wire data"))?;
+        packets.remove(first_wire);
+
+        let report = CaptureReplay::new(
+            PEERS[2].clone(),
+            PEERS_CHAIN[2].clone(),
+            stub_chain_api_for(2),
+            ReplayConfig {
+                speedup: f64::INFINITY,
+                ..Default::default()
+            },
+        )
+        .replay(packets)
+        .await?;
+
+        assert!(!report.is_faithful());
+        // The rejected packet is replayed too
+        assert_eq!(3, report.replayed);
+        assert_eq!(2, report.delivered.len());
+        assert_eq!(1, report.undelivered.len());
+        // The final packet and its acknowledgement are missing
+        assert_eq!(2, report.missing.len());
+        assert!(report.unexpected.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn replay_should_start_from_the_channels_of_the_config() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("capture.pcapng");
+        record_capture(1, &path, 3).await?;
+
+        // The channel from the sender was reopened, so the captured tickets are of the previous epoch
+        let reopened = ChannelEntry::builder()
+            .between(
+                PEERS_CHAIN[0].public().to_address(),
+                PEERS_CHAIN[1].public().to_address(),
+            )
+            .balance(HoprBalance::from_str("100 wxHOPR")?)
+            .ticket_index(0)
+            .status(ChannelStatus::Open)
+            .epoch(2)
+            .build()?;
+
+        let report = CaptureReplay::new(
+            PEERS[1].clone(),
+            PEERS_CHAIN[1].clone(),
+            stub_chain_api_for(1),
+            ReplayConfig {
+                speedup: f64::INFINITY,
+                channels: vec![reopened],
+                ..Default::default()
+            },
+        )
+        .replay_files([&path])
+        .await?;
+
+        assert!(!report.is_faithful());
+        assert!(report.ticket_events.iter().all(|event| !event.is_winning_ticket()));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn replay_should_report_captured_tickets_without_ticket_events() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("capture.pcapng");
+        record_capture(1, &path, 3).await?;
+
+        // Drop the wire data of the acknowledgements from the next hop, so that no ticket is resolved
+        let packets = CaptureReader::new(BufReader::new(File::open(&path)?))?
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter(|packet| {
+                !matches!(
+                    packet.clone().decrypt_wire(&PEERS[1]).map(|packet| packet.content),
+                    Ok(CapturedPacketContent::IncomingAck { .. })
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let report = CaptureReplay::new(
+            PEERS[1].clone(),
+            PEERS_CHAIN[1].clone(),
+            stub_chain_api_for(1),
+            ReplayConfig {
+                speedup: f64::INFINITY,
+                ..Default::default()
+            },
+        )
+        .replay(packets)
+        .await?;
+
+        assert!(!report.is_faithful());
+        assert!(report.ticket_events.is_empty());
+        assert!(report.unrecorded_tickets.is_empty());
+        assert_eq!(3, report.unreproduced_tickets.len());
+        Ok(())
+    }
+}
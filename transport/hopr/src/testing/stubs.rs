@@ -139,6 +139,12 @@ impl StubChainApi {
     pub fn channels(&self) -> &[ChannelEntry] {
         &self.channels
     }
+
+    /// Inserts the channel, replacing the channel with the same ID.
+    pub fn insert_channel(&mut self, entry: ChannelEntry) {
+        self.channels.retain(|channel| channel.get_id() != entry.get_id());
+        self.channels.push(entry);
+    }
 }
 
 impl ChainKeyOperations for StubChainApi {
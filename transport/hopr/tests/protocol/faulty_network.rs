@@ -0,0 +1,222 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{PEERS, PEERS_CHAIN, make_outgoing_packets, peer_setup_for, random_packets_of_count, resolve_mock_path};
+use futures::{SinkExt, StreamExt};
+use futures_time::future::FutureExt;
+use hopr_api::types::crypto::prelude::*;
+use hopr_crypto_packet::prelude::HoprPacket;
+use hopr_transport::testing::network::{
+    EmulatedNetwork, FaultAction, FaultScript, LinkFaults, emulate_faulty_channel_communication,
+};
+use libp2p::PeerId;
+use serial_test::serial;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+const SHORT_WAIT: Duration = Duration::from_millis(600);
+
+fn peer_id(index: usize) -> PeerId {
+    PeerId::from(*PEERS[index].public())
+}
+
+async fn path_to_recipient() -> anyhow::Result<hopr_api::types::internal::prelude::ValidatedPath> {
+    resolve_mock_path(
+        PEERS_CHAIN[0].public().to_address(),
+        PEERS_CHAIN[1..3].iter().map(|k| k.public().to_address()).collect(),
+    )
+    .await
+}
+
+/// Duplicated packets are rejected by the packet replay protection: every message is delivered
+/// exactly once and the relay wins exactly one ticket per message.
+#[serial]
+#[test_log::test(tokio::test)]
+async fn duplicated_packets_delivered_once() -> anyhow::Result<()> {
+    let count = 5;
+    let (wire_apis, mut apis, mut ticket_channels, _processes) = peer_setup_for(3).await?;
+
+    let network = EmulatedNetwork::new(LinkFaults {
+        duplicate_probability: 1.0,
+        ..Default::default()
+    });
+    tokio::task::spawn(emulate_faulty_channel_communication(wire_apis, network.clone()));
+
+    let out_msgs = make_outgoing_packets(&random_packets_of_count(count), path_to_recipient().await?);
+    apis[0].0.send_all(&mut futures::stream::iter(out_msgs).map(Ok)).await?;
+
+    let recv = (&mut apis[2].1)
+        .take(count)
+        .collect::<Vec<_>>()
+        .timeout(futures_time::time::Duration::from(TIMEOUT))
+        .await?;
+    assert_eq!(recv.len(), count);
+
+    let winning = (&mut ticket_channels[1])
+        .take(count)
+        .filter(|e| futures::future::ready(e.is_winning_ticket()))
+        .count()
+        .timeout(futures_time::time::Duration::from(TIMEOUT))
+        .await?;
+    assert_eq!(winning, count);
+
+    tokio::time::sleep(SHORT_WAIT).await;
+    let duplicate = tokio::time::timeout(Duration::from_millis(100), apis[2].1.next()).await;
+    assert!(duplicate.is_err(), "duplicated packets must not be delivered again");
+
+    let extra_ticket = tokio::time::timeout(Duration::from_millis(100), ticket_channels[1].next()).await;
+    assert!(extra_ticket.is_err(), "duplicated packets must not yield more tickets");
+
+    assert!(
+        network.stats().duplicated >= 2 * count,
+        "packets and acks must be duplicated"
+    );
+    Ok(())
+}
+
+/// Reordered packets with latency are all delivered and all relay tickets are won.
+#[serial]
+#[test_log::test(tokio::test)]
+async fn reordered_packets_all_delivered() -> anyhow::Result<()> {
+    let count = 20;
+    let (wire_apis, mut apis, mut ticket_channels, _processes) = peer_setup_for(3).await?;
+
+    let network = EmulatedNetwork::new(LinkFaults {
+        latency: Duration::from_millis(10),
+        reorder_window: Duration::from_millis(100),
+        ..Default::default()
+    });
+    tokio::task::spawn(emulate_faulty_channel_communication(wire_apis, network));
+
+    let out_msgs = make_outgoing_packets(&random_packets_of_count(count), path_to_recipient().await?);
+    apis[0].0.send_all(&mut futures::stream::iter(out_msgs).map(Ok)).await?;
+
+    let recv = (&mut apis[2].1)
+        .take(count)
+        .collect::<Vec<_>>()
+        .timeout(futures_time::time::Duration::from(TIMEOUT))
+        .await?;
+    assert_eq!(recv.len(), count);
+
+    let winning = (&mut ticket_channels[1])
+        .take(count)
+        .filter(|e| futures::future::ready(e.is_winning_ticket()))
+        .count()
+        .timeout(futures_time::time::Duration::from(TIMEOUT))
+        .await?;
+    assert_eq!(winning, count);
+
+    Ok(())
+}
+
+/// When the acknowledgements from the recipient are lost, the messages are still delivered,
+/// but the relay never learns the ticket solutions and wins no tickets.
+#[serial]
+#[test_log::test(tokio::test)]
+async fn lost_acknowledgements_yield_no_tickets() -> anyhow::Result<()> {
+    let count = 5;
+    let (wire_apis, mut apis, mut ticket_channels, _processes) = peer_setup_for(3).await?;
+
+    let network = EmulatedNetwork::default();
+    network.set_link_faults(
+        peer_id(2),
+        peer_id(1),
+        LinkFaults {
+            drop_probability: 1.0,
+            ..Default::default()
+        },
+    );
+    tokio::task::spawn(emulate_faulty_channel_communication(wire_apis, network.clone()));
+
+    let out_msgs = make_outgoing_packets(&random_packets_of_count(count), path_to_recipient().await?);
+    apis[0].0.send_all(&mut futures::stream::iter(out_msgs).map(Ok)).await?;
+
+    let recv = (&mut apis[2].1)
+        .take(count)
+        .collect::<Vec<_>>()
+        .timeout(futures_time::time::Duration::from(TIMEOUT))
+        .await?;
+    assert_eq!(recv.len(), count);
+
+    tokio::time::sleep(SHORT_WAIT).await;
+    let ticket_result = tokio::time::timeout(Duration::from_millis(100), ticket_channels[1].next()).await;
+    assert!(
+        ticket_result.is_err(),
+        "no ticket event expected without acknowledgements"
+    );
+    assert!(network.stats().dropped > 0, "acknowledgements must be dropped");
+
+    Ok(())
+}
+
+/// Messages sent during a partition are lost, those sent after the script heals it are delivered.
+#[serial]
+#[test_log::test(tokio::test)]
+async fn partition_loses_packets_until_healed() -> anyhow::Result<()> {
+    let count = 3;
+    let (wire_apis, mut apis, _ticket_channels, _processes) = peer_setup_for(3).await?;
+
+    let network = EmulatedNetwork::default();
+    network.partition([peer_id(0)], [peer_id(1), peer_id(2)], None);
+    let script = network.play(FaultScript::default().at(Duration::from_millis(400), FaultAction::Heal));
+    tokio::task::spawn(emulate_faulty_channel_communication(wire_apis, network.clone()));
+
+    let path = path_to_recipient().await?;
+    let out_msgs = make_outgoing_packets(&random_packets_of_count(count), path.clone());
+    apis[0].0.send_all(&mut futures::stream::iter(out_msgs).map(Ok)).await?;
+
+    let received = tokio::time::timeout(Duration::from_millis(200), apis[2].1.next()).await;
+    assert!(received.is_err(), "no delivery expected during the partition");
+
+    script.await?;
+    assert_eq!(network.stats().partitioned, count);
+
+    let out_msgs = make_outgoing_packets(&random_packets_of_count(count), path);
+    apis[0].0.send_all(&mut futures::stream::iter(out_msgs).map(Ok)).await?;
+
+    let recv = (&mut apis[2].1)
+        .take(count)
+        .collect::<Vec<_>>()
+        .timeout(futures_time::time::Duration::from(TIMEOUT))
+        .await?;
+    assert_eq!(recv.len(), count, "packets sent after the partition must be delivered");
+
+    Ok(())
+}
+
+/// A bandwidth cap delays the packets, but loses none of them.
+#[serial]
+#[test_log::test(tokio::test)]
+async fn bandwidth_cap_delays_packets() -> anyhow::Result<()> {
+    let count = 10;
+    let bandwidth = 20 * HoprPacket::SIZE as u64;
+    let (wire_apis, mut apis, _ticket_channels, _processes) = peer_setup_for(3).await?;
+
+    let network = EmulatedNetwork::default();
+    network.set_link_faults(
+        peer_id(0),
+        peer_id(1),
+        LinkFaults {
+            bandwidth: Some(bandwidth),
+            ..Default::default()
+        },
+    );
+    tokio::task::spawn(emulate_faulty_channel_communication(wire_apis, network.clone()));
+
+    let started = std::time::Instant::now();
+    let out_msgs = make_outgoing_packets(&random_packets_of_count(count), path_to_recipient().await?);
+    apis[0].0.send_all(&mut futures::stream::iter(out_msgs).map(Ok)).await?;
+
+    let recv = (&mut apis[2].1)
+        .take(count)
+        .collect::<Vec<_>>()
+        .timeout(futures_time::time::Duration::from(TIMEOUT))
+        .await?;
+    assert_eq!(recv.len(), count);
+
+    // 10 packets at 20 packets per second take at least half a second
+    assert!(started.elapsed() >= Duration::from_millis(500));
+    assert_eq!(network.stats().dropped, 0);
+
+    Ok(())
+}
@@ -1,6 +1,9 @@
 use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
 use futures::{SinkExt, StreamExt, future::BoxFuture};
-use hopr_transport_mixer::{MixerSink, channel, config::MixerConfig};
+use hopr_transport_mixer::{
+    MixerSink, channel,
+    config::{MixerConfig, MixerOverflowPolicy, MixingStrategyConfig},
+};
 use rust_stream_ext_concurrent::then_concurrent::StreamThenConcurrentExt;
 
 const SAMPLE_SIZE: usize = 10;
@@ -17,6 +20,37 @@ fn minimal_delay_mixer_cfg() -> MixerConfig {
     }
 }
 
+#[inline]
+fn minimal_exponential_mixer_cfg() -> MixerConfig {
+    MixerConfig {
+        min_delay: std::time::Duration::from_millis(0),
+        strategy: MixingStrategyConfig::Exponential {
+            mean_delay: std::time::Duration::from_micros(500),
+        },
+        ..MixerConfig::default()
+    }
+}
+
+#[inline]
+fn threshold_pool_mixer_cfg() -> MixerConfig {
+    MixerConfig {
+        strategy: MixingStrategyConfig::ThresholdPool {
+            threshold: 100,
+            pool_size: 10,
+        },
+        ..MixerConfig::default()
+    }
+}
+
+#[inline]
+fn bounded_mixer_cfg(policy: MixerOverflowPolicy) -> MixerConfig {
+    MixerConfig {
+        max_buffered: Some(1024),
+        overflow_policy: policy,
+        ..minimal_delay_mixer_cfg()
+    }
+}
+
 pub fn mixer_throughput(
     c: &mut Criterion,
     cfg: MixerConfig,
@@ -61,6 +95,64 @@ fn send_continuous_channel_load(item: &str, iterations: usize, cfg: MixerConfig)
     })
 }
 
+// Pool-based strategies hold the last items until the sender is gone
+fn send_continuous_channel_load_with_close(
+    item: &'static str,
+    iterations: usize,
+    cfg: MixerConfig,
+) -> BoxFuture<'static, ()> {
+    Box::pin(async move {
+        let (tx, mut rx) = channel(cfg);
+
+        for _ in 0..iterations {
+            tx.send(item).expect("send must succeed");
+        }
+        drop(tx);
+
+        for _ in 0..iterations {
+            rx.next().await.expect("receive must succeed");
+        }
+    })
+}
+
+// Benchmark the throughput of the bounded mixer channel, where the sender waits for the receiver
+fn send_continuous_bounded_channel_load(
+    item: &'static str,
+    iterations: usize,
+    cfg: MixerConfig,
+) -> BoxFuture<'static, ()> {
+    Box::pin(async move {
+        let (tx, mut rx) = channel(cfg);
+
+        let pipe = tokio::task::spawn(futures::stream::repeat(item).take(iterations).map(Ok).forward(tx));
+
+        for _ in 0..iterations {
+            rx.next().await.expect("receive must succeed");
+        }
+
+        pipe.await.expect("pipe must not panic").expect("pipe must succeed");
+    })
+}
+
+// Benchmark the overhead of the bounded mixer channel dropping items under a flood
+fn send_flooding_bounded_channel_load(
+    item: &'static str,
+    iterations: usize,
+    cfg: MixerConfig,
+) -> BoxFuture<'static, ()> {
+    Box::pin(async move {
+        let (tx, rx) = channel(cfg);
+
+        for _ in 0..iterations {
+            tx.send(item).expect("send must succeed");
+        }
+        drop(tx);
+
+        let received = rx.count().await;
+        assert!(received <= cfg.max_buffered.unwrap_or(usize::MAX));
+    })
+}
+
 // Benchmark the throughput of the mixer channel when used in a pipe
 #[allow(dead_code)]
 fn send_continuous_channel_load_through_sink_pipe(
@@ -143,6 +235,47 @@ pub fn mixer_sink_throughput_minimal_mixing(c: &mut Criterion) {
     );
 }
 
+pub fn mixer_sink_throughput_strategies(c: &mut Criterion) {
+    mixer_throughput(
+        c,
+        minimal_exponential_mixer_cfg(),
+        "mixer_sink_exponential",
+        &[10 * 1024 * 2 * RANDOM_GIBBERISH.len()],
+        send_continuous_sink_load,
+    );
+    mixer_throughput(
+        c,
+        threshold_pool_mixer_cfg(),
+        "mixer_channel_threshold_pool",
+        &[10 * 1024 * 2 * RANDOM_GIBBERISH.len()],
+        send_continuous_channel_load_with_close,
+    );
+}
+
+pub fn mixer_bounded_channel_throughput(c: &mut Criterion) {
+    mixer_throughput(
+        c,
+        bounded_mixer_cfg(MixerOverflowPolicy::Backpressure),
+        "bounded_mixer_channel_backpressure",
+        &[10 * 1024 * 2 * RANDOM_GIBBERISH.len()],
+        send_continuous_bounded_channel_load,
+    );
+    mixer_throughput(
+        c,
+        bounded_mixer_cfg(MixerOverflowPolicy::DropNewest),
+        "bounded_mixer_channel_drop_newest",
+        &[10 * 1024 * 2 * RANDOM_GIBBERISH.len()],
+        send_flooding_bounded_channel_load,
+    );
+    mixer_throughput(
+        c,
+        bounded_mixer_cfg(MixerOverflowPolicy::DropEarliestRelease),
+        "bounded_mixer_channel_drop_earliest_release",
+        &[10 * 1024 * 2 * RANDOM_GIBBERISH.len()],
+        send_flooding_bounded_channel_load,
+    );
+}
+
 pub fn mixer_stream_throughput_minimal_mixing(c: &mut Criterion) {
     mixer_throughput(
         c,
@@ -156,6 +289,8 @@ pub fn mixer_stream_throughput_minimal_mixing(c: &mut Criterion) {
 criterion_group!(
     benches,
     mixer_sink_throughput_minimal_mixing,
+    mixer_sink_throughput_strategies,
+    mixer_bounded_channel_throughput,
     mixer_stream_throughput_minimal_mixing
 );
 criterion_main!(benches);
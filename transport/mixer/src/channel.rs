@@ -1,13 +1,11 @@
 use std::{
-    cmp::Reverse,
-    collections::BinaryHeap,
     future::poll_fn,
     sync::{
         Arc,
         atomic::{AtomicBool, AtomicUsize, Ordering},
     },
     task::Poll,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use futures::{FutureExt, Stream, StreamExt};
@@ -15,7 +13,10 @@ use futures_timer::Delay;
 use parking_lot::Mutex;
 use tracing::trace;
 
-use crate::{config::MixerConfig, data::DelayedData};
+use crate::{
+    config::{MixerConfig, MixerOverflowPolicy},
+    strategy::{BoundedPush, ConfiguredMixingStrategy, MixingStrategy, push_bounded},
+};
 
 #[cfg(all(feature = "telemetry", not(test)))]
 lazy_static::lazy_static! {
@@ -26,13 +27,37 @@ lazy_static::lazy_static! {
         "Average mixer packet delay averaged over a packet window"
     )
     .unwrap();
+    pub static ref METRIC_MIXER_ANONYMITY_SET_SIZE: hopr_types::telemetry::SimpleGauge =
+        hopr_types::telemetry::SimpleGauge::new(
+            "hopr_mixer_anonymity_set_size",
+            "Estimated size of the anonymity set of the mixed packets"
+        )
+        .unwrap();
+    pub static ref METRIC_MIXER_ENTROPY: hopr_types::telemetry::SimpleGauge = hopr_types::telemetry::SimpleGauge::new(
+        "hopr_mixer_entropy_bits",
+        "Estimated entropy of the mixer output in bits"
+    )
+    .unwrap();
+    pub static ref METRIC_MIXER_DROPPED: hopr_types::telemetry::SimpleCounter =
+        hopr_types::telemetry::SimpleCounter::new(
+            "hopr_mixer_dropped_items",
+            "Number of items dropped because the bounded mixer was full"
+        )
+        .unwrap();
 }
 
-/// Mixing and delaying channel using random delay function.
+/// Updates the anonymity metrics from the estimates of the given mixing strategy.
+#[cfg(all(feature = "telemetry", not(test)))]
+pub(crate) fn record_anonymity_metrics<T>(strategy: &impl MixingStrategy<T>) {
+    METRIC_MIXER_ANONYMITY_SET_SIZE.set(strategy.anonymity_set_size() as f64);
+    METRIC_MIXER_ENTROPY.set(strategy.entropy_bits());
+}
+
+/// Mixing and delaying channel using the configured [mixing strategy](MixingStrategy).
 ///
-/// Mixing is performed by assigning random delays to the ingress timestamp of data,
-/// then storing the values inside a binary heap with reversed ordering (max heap).
-/// This effectively creates a min heap behavior, which is required to ensure that
+/// With the default delay-based strategies, mixing is performed by assigning random delays
+/// to the ingress timestamp of data, then storing the values inside a binary heap with reversed
+/// ordering (max heap). This effectively creates a min heap behavior, which is required to ensure that
 /// data is released in order of their delay expiration.
 ///
 /// When data arrives:
@@ -40,19 +65,41 @@ lazy_static::lazy_static! {
 /// 2. Data is stored in the heap with its release timestamp
 /// 3. The heap maintains ordering so items with earliest release time are at the top
 ///
-/// This channel is **unbounded** by nature using the `capacity` in the configuration
-/// to solely pre-allocate the buffer.
+/// Pool-based strategies instead hold the data in a pool and release randomly chosen batches.
+///
+/// This channel is **unbounded** by default, using the `capacity` in the configuration
+/// to solely pre-allocate the buffer. If `max_buffered` is set in the configuration,
+/// the channel is bounded and the `overflow_policy` determines what happens once it is full.
 ///
 /// The timer used by the receiver to wait for the next release deadline is **not** stored
 /// behind this mutex — it lives on the [`Receiver`] itself. Keeping it out of the shared
 /// state is what lets the receiver poll the timer without blocking senders.
 struct Channel<T> {
-    /// Buffer holding the data with a timestamp ordering to ensure the min heap behavior.
-    buffer: BinaryHeap<Reverse<DelayedData<T>>>,
+    /// Mixing strategy holding the buffered data.
+    buffer: ConfiguredMixingStrategy<T>,
     waker: Option<std::task::Waker>,
+    /// Wakers of the senders waiting for the bounded channel to release some items.
+    sender_wakers: Vec<std::task::Waker>,
+    /// Number of slots reserved by the senders whose `poll_ready` has completed.
+    reserved: usize,
+    bound: Option<usize>,
     cfg: MixerConfig,
 }
 
+impl<T> Channel<T> {
+    /// Indicates whether the senders must wait before sending more items.
+    fn must_wait(&self) -> bool {
+        self.cfg.overflow_policy == MixerOverflowPolicy::Backpressure
+            && self
+                .bound
+                .is_some_and(|bound| self.buffer.len().saturating_add(self.reserved) >= bound)
+    }
+
+    fn wake_senders(&mut self) {
+        self.sender_wakers.drain(..).for_each(|waker| waker.wake());
+    }
+}
+
 /// Channel with sender and receiver counters allowing closure tracking.
 struct TrackedChannel<T> {
     channel: Arc<Mutex<Channel<T>>>,
@@ -76,11 +123,16 @@ pub enum SenderError {
     /// The channel is closed due to receiver being dropped.
     #[error("Channel is closed")]
     Closed,
+    /// The bounded channel with backpressure is full.
+    #[error("Channel is full")]
+    Full,
 }
 
 /// Sender object interacting with the mixing channel.
 pub struct Sender<T> {
     channel: TrackedChannel<T>,
+    /// Indicates whether this sender holds a slot reserved by `poll_ready`.
+    reserved: bool,
 }
 
 impl<T> Clone for Sender<T> {
@@ -88,12 +140,21 @@ impl<T> Clone for Sender<T> {
         let channel = self.channel.clone();
         channel.sender_count.fetch_add(1, Ordering::Relaxed);
 
-        Sender { channel }
+        Sender {
+            channel,
+            reserved: false,
+        }
     }
 }
 
 impl<T> Drop for Sender<T> {
     fn drop(&mut self) {
+        if self.reserved {
+            let mut channel = self.channel.channel.lock();
+            channel.reserved = channel.reserved.saturating_sub(1);
+            channel.wake_senders();
+        }
+
         if self.channel.sender_count.fetch_sub(1, Ordering::Relaxed) == 1
             && !self.channel.receiver_active.load(Ordering::Relaxed)
         {
@@ -104,25 +165,57 @@ impl<T> Drop for Sender<T> {
 
 impl<T> Sender<T> {
     /// Send one item to the mixing channel.
+    ///
+    /// If the channel is bounded with backpressure and full, [`SenderError::Full`] is returned.
+    /// Use the [`Sink`](futures::sink::Sink) interface to wait for the capacity instead.
     pub fn send(&self, item: T) -> Result<(), SenderError> {
-        self.push_item(item)
+        self.push_item(item, false)
     }
 
     /// Locked critical section shared between `Sink::start_send` and [`Sender::send`].
+    ///
+    /// If `reserved` is set, the slot reserved by `Sink::poll_ready` is consumed by the item.
+    /// The slots reserved by other senders are never taken.
     #[tracing::instrument(level = "trace", skip(self, item))]
-    fn push_item(&self, item: T) -> Result<(), SenderError> {
+    fn push_item(&self, item: T, reserved: bool) -> Result<(), SenderError> {
         if !self.channel.receiver_active.load(Ordering::Relaxed) {
             return Err(SenderError::Closed);
         }
 
         let mut channel = self.channel.channel.lock();
+        if reserved {
+            channel.reserved = channel.reserved.saturating_sub(1);
+        }
 
-        let random_delay = channel.cfg.random_delay();
+        let bound = channel.bound.map(|bound| bound.saturating_sub(channel.reserved));
+        let policy = channel.cfg.overflow_policy;
+        let random_delay = match push_bounded(&mut channel.buffer, item, Instant::now(), bound, policy) {
+            BoundedPush::Inserted { delay, evicted } => {
+                if evicted {
+                    trace!("dropped the earliest item from the full mixer");
+
+                    #[cfg(all(feature = "telemetry", not(test)))]
+                    {
+                        METRIC_MIXER_DROPPED.increment();
+                        METRIC_QUEUE_SIZE.decrement(1.0f64);
+                    }
+                }
+                delay
+            }
+            BoundedPush::Dropped => {
+                trace!("dropped the new item, because the mixer is full");
+
+                #[cfg(all(feature = "telemetry", not(test)))]
+                METRIC_MIXER_DROPPED.increment();
 
-        trace!(delay_in_ms = random_delay.as_millis(), "generated mixer delay",);
+                return Ok(());
+            }
+            BoundedPush::Full => return Err(SenderError::Full),
+        };
 
-        let delayed_data: DelayedData<T> = (std::time::Instant::now() + random_delay, item).into();
-        channel.buffer.push(Reverse(delayed_data));
+        if let Some(random_delay) = random_delay {
+            trace!(delay_in_ms = random_delay.as_millis(), "generated mixer delay",);
+        }
 
         if let Some(waker) = channel.waker.as_ref() {
             waker.wake_by_ref();
@@ -132,10 +225,13 @@ impl<T> Sender<T> {
         {
             METRIC_QUEUE_SIZE.increment(1.0f64);
 
-            let weight = 1.0f64 / channel.cfg.metric_delay_window as f64;
-            METRIC_MIXER_AVERAGE_DELAY.set(
-                (weight * random_delay.as_millis() as f64) + ((1.0f64 - weight) * METRIC_MIXER_AVERAGE_DELAY.get()),
-            );
+            if let Some(random_delay) = random_delay {
+                let weight = 1.0f64 / channel.cfg.metric_delay_window as f64;
+                METRIC_MIXER_AVERAGE_DELAY.set(
+                    (weight * random_delay.as_millis() as f64) + ((1.0f64 - weight) * METRIC_MIXER_AVERAGE_DELAY.get()),
+                );
+            }
+            record_anonymity_metrics(&channel.buffer);
         }
 
         Ok(())
@@ -145,17 +241,38 @@ impl<T> Sender<T> {
 impl<T> futures::sink::Sink<T> for Sender<T> {
     type Error = SenderError;
 
-    fn poll_ready(self: std::pin::Pin<&mut Self>, _cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+    fn poll_ready(self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
         let is_active = self.channel.receiver_active.load(Ordering::Relaxed);
-        if is_active {
-            Poll::Ready(Ok(()))
+        if !is_active {
+            return Poll::Ready(Err(SenderError::Closed));
+        }
+
+        if self.reserved {
+            return Poll::Ready(Ok(()));
+        }
+
+        let this = std::pin::Pin::into_inner(self);
+        let mut channel = this.channel.channel.lock();
+        if channel.must_wait() {
+            if !channel.sender_wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                channel.sender_wakers.push(cx.waker().clone());
+            }
+            trace!("pending (mixer full)");
+            Poll::Pending
         } else {
-            Poll::Ready(Err(SenderError::Closed))
+            // Reserve the slot, so that concurrent senders cannot take it before `start_send`
+            if channel.cfg.overflow_policy == MixerOverflowPolicy::Backpressure && channel.bound.is_some() {
+                channel.reserved += 1;
+                this.reserved = true;
+            }
+            Poll::Ready(Ok(()))
         }
     }
 
     fn start_send(self: std::pin::Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
-        self.push_item(item)
+        let this = std::pin::Pin::into_inner(self);
+        let reserved = std::mem::take(&mut this.reserved);
+        this.push_item(item, reserved)
     }
 
     fn poll_flush(self: std::pin::Pin<&mut Self>, _cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
@@ -194,7 +311,7 @@ impl<T> Stream for Receiver<T> {
 
     #[tracing::instrument(level = "trace", skip(self, cx))]
     fn poll_next(self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Option<Self::Item>> {
-        let now = std::time::Instant::now();
+        let now = Instant::now();
         let no_senders = self.channel.sender_count.load(Ordering::Relaxed) == 0;
 
         // Phase 1: under lock, try to pop a due item; otherwise register the waker and
@@ -207,18 +324,21 @@ impl<T> Stream for Receiver<T> {
         let sleep_for = {
             let mut channel = self.channel.channel.lock();
 
-            if channel.buffer.peek().map(|x| x.0.release_at < now).unwrap_or(false) {
-                let data = channel
-                    .buffer
-                    .pop()
-                    .expect("value must be present during the same locked access")
-                    .0
-                    .item;
+            // Pool-based strategies might hold the items until more items arrive,
+            // which will not happen once all the senders are gone.
+            if no_senders {
+                channel.buffer.release_all(now);
+            }
 
+            if let Some(data) = channel.buffer.pop_released(now) {
                 trace!(from = "direct", "yield item");
+                channel.wake_senders();
 
                 #[cfg(all(feature = "telemetry", not(test)))]
-                METRIC_QUEUE_SIZE.decrement(1.0f64);
+                {
+                    METRIC_QUEUE_SIZE.decrement(1.0f64);
+                    record_anonymity_metrics(&channel.buffer);
+                }
 
                 return Poll::Ready(Some(data));
             }
@@ -236,12 +356,12 @@ impl<T> Stream for Receiver<T> {
                 None => channel.waker = Some(cx.waker().clone()),
             }
 
-            match channel.buffer.peek() {
-                Some(next) => next.0.release_at.duration_since(now),
+            match channel.buffer.next_release() {
+                Some(next) => next.saturating_duration_since(now),
                 None => {
                     // Senders still alive (else we would have returned `None` above) —
                     // wait for one of them to push.
-                    trace!(from = "direct", "pending (empty buffer)");
+                    trace!(from = "direct", "pending (nothing to release)");
                     return Poll::Pending;
                 }
             }
@@ -253,29 +373,45 @@ impl<T> Stream for Receiver<T> {
         this.timer.reset(sleep_for);
         futures::ready!(this.timer.poll_unpin(cx));
 
-        // Phase 3: timer fired. Re-take the lock. Because there is only one receiver,
-        // the item at the top is still present; senders can only have added more.
-        // A newly-pushed item with an earlier deadline is also safe to pop — it merely
-        // yields in a different order than originally expected, which is consistent
-        // with the mixer's design of not preserving input order.
+        // Phase 3: timer fired. Re-take the lock and ask the strategy for a due item.
+        // Senders may have pushed in the meantime, which can change what the strategy
+        // releases: an item with an earlier deadline merely yields in a different order,
+        // while a pool-based strategy may release nothing yet (e.g. its pool shrank below
+        // the flush size). In that case the next release is re-armed by polling again.
         let mut channel = this.channel.channel.lock();
-        match channel.buffer.pop() {
-            Some(entry) => {
+        match channel.buffer.pop_released(Instant::now()) {
+            Some(item) => {
                 trace!(from = "timer", "yield item");
+                channel.wake_senders();
 
                 #[cfg(all(feature = "telemetry", not(test)))]
-                METRIC_QUEUE_SIZE.decrement(1.0f64);
+                {
+                    METRIC_QUEUE_SIZE.decrement(1.0f64);
+                    record_anonymity_metrics(&channel.buffer);
+                }
 
-                Poll::Ready(Some(entry.0.item))
+                Poll::Ready(Some(item))
             }
             None => {
-                trace!(from = "timer", "buffer drained before we re-acquired the lock");
+                trace!(from = "timer", "nothing released after the timer fired");
+                if channel.buffer.next_release().is_some() {
+                    // Poll again to re-arm the timer for the next release
+                    cx.waker().wake_by_ref();
+                }
                 Poll::Pending
             }
         }
     }
 }
 
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.channel.receiver_active.store(false, Ordering::Relaxed);
+        // Senders waiting for the capacity must observe the closure
+        self.channel.channel.lock().wake_senders();
+    }
+}
+
 impl<T> Receiver<T> {
     /// Receive a single delayed mixed item.
     pub async fn recv(&mut self) -> Option<T> {
@@ -290,15 +426,18 @@ pub fn channel<T>(cfg: crate::config::MixerConfig) -> (Sender<T>, Receiver<T>) {
         // Initialize the lazy statics here
         lazy_static::initialize(&METRIC_QUEUE_SIZE);
         lazy_static::initialize(&METRIC_MIXER_AVERAGE_DELAY);
+        lazy_static::initialize(&METRIC_MIXER_ANONYMITY_SET_SIZE);
+        lazy_static::initialize(&METRIC_MIXER_ENTROPY);
+        lazy_static::initialize(&METRIC_MIXER_DROPPED);
     }
 
-    let mut buffer = BinaryHeap::new();
-    buffer.reserve(cfg.capacity);
-
     let channel = TrackedChannel {
         channel: Arc::new(Mutex::new(Channel::<T> {
-            buffer,
+            buffer: ConfiguredMixingStrategy::from(&cfg),
             waker: None,
+            sender_wakers: Vec::new(),
+            reserved: 0,
+            bound: cfg.buffer_bound(),
             cfg,
         })),
         sender_count: Arc::new(AtomicUsize::new(1)),
@@ -307,6 +446,7 @@ pub fn channel<T>(cfg: crate::config::MixerConfig) -> (Sender<T>, Receiver<T>) {
     (
         Sender {
             channel: channel.clone(),
+            reserved: false,
         },
         Receiver {
             channel,
@@ -646,4 +786,214 @@ mod tests {
         assert!(rx.next().await.is_none(), "expected channel closed with no more items");
         Ok(())
     }
+
+    #[tokio::test]
+    async fn mixer_channel_should_use_exponential_strategy() -> anyhow::Result<()> {
+        const ITERATIONS: usize = 20;
+
+        let (tx, rx) = channel(MixerConfig {
+            min_delay: Duration::from_millis(1),
+            strategy: crate::config::MixingStrategyConfig::Exponential {
+                mean_delay: Duration::from_millis(10),
+            },
+            ..MixerConfig::default()
+        });
+
+        let input = (0..ITERATIONS).collect::<Vec<_>>();
+        for i in input.iter() {
+            tx.send(*i)?;
+        }
+
+        let mut mixed_output = timeout(Duration::from_secs(2), rx.take(ITERATIONS).collect::<Vec<_>>()).await?;
+        assert_ne!(input, mixed_output);
+
+        mixed_output.sort();
+        assert_eq!(input, mixed_output);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn mixer_channel_should_release_threshold_pool_batches_and_drain_on_close() -> anyhow::Result<()> {
+        let (tx, mut rx) = channel(MixerConfig {
+            strategy: crate::config::MixingStrategyConfig::ThresholdPool {
+                threshold: 3,
+                pool_size: 2,
+            },
+            ..MixerConfig::default()
+        });
+
+        for i in 0..4 {
+            tx.send(i)?;
+        }
+
+        // Threshold not reached yet
+        assert!(timeout(Duration::from_millis(50), rx.next()).await.is_err());
+
+        tx.send(4)?;
+        let mut received = Vec::new();
+        for _ in 0..3 {
+            received.push(timeout(PROCESSING_LEEWAY, rx.next()).await?.expect("batch item"));
+        }
+        assert!(timeout(Duration::from_millis(50), rx.next()).await.is_err());
+
+        // Once the sender is gone, the pool must be drained
+        drop(tx);
+        while let Some(item) = timeout(PROCESSING_LEEWAY, rx.next()).await? {
+            received.push(item);
+        }
+
+        received.sort();
+        assert_eq!((0..5).collect::<Vec<_>>(), received);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn mixer_channel_should_release_timed_pool_every_interval() -> anyhow::Result<()> {
+        let interval = Duration::from_millis(100);
+        let (tx, mut rx) = channel(MixerConfig {
+            strategy: crate::config::MixingStrategyConfig::TimedPool { interval, pool_size: 1 },
+            ..MixerConfig::default()
+        });
+
+        let start = std::time::Instant::now();
+        for i in 0..5 {
+            tx.send(i)?;
+        }
+
+        for _ in 0..4 {
+            timeout(interval + PROCESSING_LEEWAY, rx.next())
+                .await?
+                .expect("pool item");
+        }
+        assert!(start.elapsed() >= interval);
+
+        // The pool size is kept until the sender is gone
+        assert!(timeout(2 * interval, rx.next()).await.is_err());
+        drop(tx);
+        assert!(timeout(PROCESSING_LEEWAY, rx.next()).await?.is_some());
+        assert!(timeout(PROCESSING_LEEWAY, rx.next()).await?.is_none());
+        Ok(())
+    }
+
+    fn bounded_cfg(policy: MixerOverflowPolicy) -> MixerConfig {
+        MixerConfig {
+            min_delay: Duration::from_millis(100),
+            delay_range: Duration::from_millis(1),
+            max_buffered: Some(2),
+            overflow_policy: policy,
+            ..MixerConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn bounded_mixer_channel_should_apply_backpressure_when_full() -> anyhow::Result<()> {
+        let (mut tx, mut rx) = channel::<u32>(bounded_cfg(MixerOverflowPolicy::Backpressure));
+
+        SinkExt::send(&mut tx, 0).await?;
+        SinkExt::send(&mut tx, 1).await?;
+        assert!(matches!(tx.send(2), Err(SenderError::Full)));
+
+        // The sender waits until the receiver takes an item out of the channel
+        let start = std::time::Instant::now();
+        let send_task = tokio::task::spawn(async move {
+            SinkExt::send(&mut tx, 2).await?;
+            Ok::<_, SenderError>(start.elapsed())
+        });
+
+        assert!(timeout(PROCESSING_LEEWAY, rx.next()).await?.is_some());
+        let waited = timeout(PROCESSING_LEEWAY, send_task).await???;
+        assert!(waited >= Duration::from_millis(90), "sender did not wait: {waited:?}");
+
+        assert!(timeout(PROCESSING_LEEWAY, rx.next()).await?.is_some());
+        assert!(timeout(PROCESSING_LEEWAY, rx.next()).await?.is_some());
+        assert!(timeout(PROCESSING_LEEWAY, rx.next()).await?.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn bounded_mixer_channel_should_not_exceed_the_bound_with_concurrent_senders() -> anyhow::Result<()> {
+        use futures::Sink;
+
+        let (mut tx_a, mut rx) = channel::<u32>(bounded_cfg(MixerOverflowPolicy::Backpressure));
+        let mut tx_b = tx_a.clone();
+        let mut cx = std::task::Context::from_waker(futures::task::noop_waker_ref());
+
+        tx_a.send(0)?;
+
+        // Only one of the senders can get the last free slot
+        assert!(matches!(
+            std::pin::Pin::new(&mut tx_a).poll_ready(&mut cx),
+            Poll::Ready(Ok(()))
+        ));
+        assert!(std::pin::Pin::new(&mut tx_b).poll_ready(&mut cx).is_pending());
+        assert!(matches!(tx_b.send(1), Err(SenderError::Full)));
+
+        std::pin::Pin::new(&mut tx_a).start_send(1)?;
+        assert_eq!(2, tx_a.channel.channel.lock().buffer.len());
+        assert!(std::pin::Pin::new(&mut tx_a).poll_ready(&mut cx).is_pending());
+
+        // Slot reserved by a dropped sender is released
+        assert!(timeout(PROCESSING_LEEWAY, rx.next()).await?.is_some());
+        assert!(matches!(
+            std::pin::Pin::new(&mut tx_b).poll_ready(&mut cx),
+            Poll::Ready(Ok(()))
+        ));
+        assert!(matches!(tx_a.send(2), Err(SenderError::Full)));
+        drop(tx_b);
+        tx_a.send(2)?;
+        assert_eq!(2, tx_a.channel.channel.lock().buffer.len());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn bounded_mixer_channel_should_close_waiting_senders_when_receiver_drops() -> anyhow::Result<()> {
+        let (mut tx, rx) = channel::<u32>(bounded_cfg(MixerOverflowPolicy::Backpressure));
+
+        SinkExt::send(&mut tx, 0).await?;
+        SinkExt::send(&mut tx, 1).await?;
+
+        let send_task = tokio::task::spawn(async move { SinkExt::send(&mut tx, 2).await });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        drop(rx);
+
+        let result = timeout(PROCESSING_LEEWAY, send_task).await??;
+        assert!(matches!(result, Err(SenderError::Closed)), "got {result:?}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn bounded_mixer_channel_should_drop_newest_items_when_full() -> anyhow::Result<()> {
+        let (tx, rx) = channel::<u32>(bounded_cfg(MixerOverflowPolicy::DropNewest));
+
+        for i in 0..5 {
+            tx.send(i)?;
+        }
+        drop(tx);
+
+        let mut received = timeout(PROCESSING_LEEWAY, rx.collect::<Vec<_>>()).await?;
+        received.sort();
+        assert_eq!(vec![0, 1], received);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn bounded_mixer_channel_should_drop_earliest_released_items_when_full() -> anyhow::Result<()> {
+        let (tx, rx) = channel::<u32>(MixerConfig {
+            min_delay: Duration::ZERO,
+            delay_range: Duration::ZERO,
+            max_buffered: Some(2),
+            overflow_policy: MixerOverflowPolicy::DropEarliestRelease,
+            ..MixerConfig::default()
+        });
+
+        for i in 0..5 {
+            tx.send(i)?;
+            tokio::time::sleep(Duration::from_micros(10)).await; // ensure distinct release times
+        }
+        drop(tx);
+
+        let received = timeout(PROCESSING_LEEWAY, rx.collect::<Vec<_>>()).await?;
+        assert_eq!(vec![3, 4], received);
+        Ok(())
+    }
 }
@@ -10,6 +10,64 @@ fn default_metric_delay_window() -> u64 {
     HOPR_MIXER_DELAY_METRIC_WINDOW
 }
 
+/// Selects the [mixing strategy](crate::strategy::MixingStrategy) used by the mixer.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type", rename_all = "snake_case")
+)]
+pub enum MixingStrategyConfig {
+    /// Each item is delayed uniformly at random by `min_delay` up to `min_delay + delay_range`.
+    #[default]
+    Uniform,
+    /// Each item is delayed by `min_delay` plus an exponentially distributed delay with the given mean
+    /// (Poisson or "stop-and-go" mix).
+    ///
+    /// The `delay_range` is ignored.
+    Exponential {
+        /// Mean of the exponentially distributed part of the delay.
+        #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
+        mean_delay: Duration,
+    },
+    /// Once `threshold + pool_size` items are buffered, `threshold` randomly chosen items are released.
+    ///
+    /// The `min_delay` and `delay_range` are ignored.
+    ThresholdPool {
+        /// Number of items released at once.
+        threshold: usize,
+        /// Number of items that always remain in the pool.
+        pool_size: usize,
+    },
+    /// Every `interval`, all but `pool_size` randomly chosen buffered items are released.
+    ///
+    /// The `min_delay` and `delay_range` are ignored.
+    TimedPool {
+        /// Interval between two consecutive releases.
+        #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
+        interval: Duration,
+        /// Number of items that always remain in the pool.
+        pool_size: usize,
+    },
+}
+
+/// Determines what happens when an item is sent to a full bounded mixer.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum MixerOverflowPolicy {
+    /// Senders wait until the mixer releases some items.
+    #[default]
+    Backpressure,
+    /// The newly sent item is dropped.
+    DropNewest,
+    /// The buffered item that would be released first is dropped to make room for the new item.
+    DropEarliestRelease,
+}
+
 /// Mixer configuration.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, smart_default::SmartDefault)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -31,23 +89,49 @@ pub struct MixerConfig {
     #[default(HOPR_MIXER_DELAY_METRIC_WINDOW)]
     #[cfg_attr(feature = "serde", serde(skip_serializing, default = "default_metric_delay_window"))]
     pub metric_delay_window: u64,
+    /// Strategy used to mix the items.
+    ///
+    /// Default is [`MixingStrategyConfig::Uniform`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub strategy: MixingStrategyConfig,
+    /// Maximum number of items buffered in the mixer.
+    ///
+    /// When set, the mixer is bounded and the `overflow_policy` applies once it is full.
+    /// With the [`MixingStrategyConfig::ThresholdPool`] strategy, the bound is at least `threshold + pool_size`,
+    /// and with the [`MixingStrategyConfig::TimedPool`] strategy at least `pool_size + 1`,
+    /// so that the pool can always be released.
+    ///
+    /// Default is `None` (unbounded).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_buffered: Option<usize>,
+    /// What happens when an item is sent to the full bounded mixer.
+    ///
+    /// Has no effect if `max_buffered` is not set.
+    ///
+    /// Default is [`MixerOverflowPolicy::Backpressure`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub overflow_policy: MixerOverflowPolicy,
 }
 
 impl MixerConfig {
+    /// Returns the effective maximum number of items buffered in the mixer, if bounded.
+    pub fn buffer_bound(&self) -> Option<usize> {
+        self.max_buffered.map(|bound| match self.strategy {
+            MixingStrategyConfig::ThresholdPool { threshold, pool_size } => {
+                bound.max(threshold.max(1).saturating_add(pool_size))
+            }
+            MixingStrategyConfig::TimedPool { pool_size, .. } => bound.max(pool_size.saturating_add(1)),
+            _ => bound.max(1),
+        })
+    }
+
     /// Get a random delay duration from the specified minimum and maximum delay available
     /// inside the configuration.
     pub fn random_delay(&self) -> Duration {
-        let max_delay = self.min_delay.saturating_add(self.delay_range);
-
-        let random_delay = if max_delay.as_millis() == 0 {
-            max_delay.as_millis() as u64
-        } else {
-            hopr_types::crypto_random::random_integer(
-                self.min_delay.as_millis() as u64,
-                Some(max_delay.as_millis() as u64),
-            )
-        };
-
-        Duration::from_millis(random_delay)
+        crate::strategy::DelayDistribution::Uniform {
+            min: self.min_delay,
+            range: self.delay_range,
+        }
+        .sample()
     }
 }
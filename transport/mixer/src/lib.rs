@@ -2,7 +2,9 @@ pub mod channel;
 pub mod config;
 pub mod data;
 pub mod sink;
+pub mod strategy;
 
 pub use channel::channel;
-pub use config::MixerConfig;
+pub use config::{MixerConfig, MixingStrategyConfig};
 pub use sink::MixerSink;
+pub use strategy::MixingStrategy;
@@ -1,6 +1,4 @@
 use std::{
-    cmp::Reverse,
-    collections::BinaryHeap,
     pin::Pin,
     task::{Context, Poll},
     time::{Duration, Instant},
@@ -8,35 +6,40 @@ use std::{
 
 use futures::{FutureExt, Sink};
 use futures_timer::Delay;
-use tracing::trace;
+use tracing::{trace, warn};
 
 #[cfg(all(feature = "telemetry", not(test)))]
-use crate::channel::{METRIC_MIXER_AVERAGE_DELAY, METRIC_QUEUE_SIZE};
-use crate::{config::MixerConfig, data::DelayedData};
+use crate::channel::{METRIC_MIXER_AVERAGE_DELAY, METRIC_MIXER_DROPPED, METRIC_QUEUE_SIZE, record_anonymity_metrics};
+use crate::{
+    config::{MixerConfig, MixerOverflowPolicy},
+    strategy::{BoundedPush, ConfiguredMixingStrategy, MixingStrategy, push_bounded},
+};
 
 /// A [`Sink`] adapter that applies random delays to items before forwarding them to an inner sink.
 ///
-/// Items pushed via `start_send` are held in an internal heap until their randomly-assigned
-/// release time, then forwarded to the wrapped sink. `poll_flush` drains due items and parks
+/// Items pushed via `start_send` are held by the configured [mixing strategy](MixingStrategy)
+/// until their release time, then forwarded to the wrapped sink. `poll_flush` drains due items and parks
 /// on a timer for the next pending item, so the owning task wakes up automatically when items
 /// become ready — no separate forwarding task is required.
 ///
+/// If `max_buffered` is set in the configuration, the sink is bounded. With the
+/// [`MixerOverflowPolicy::Backpressure`] policy, `poll_ready` forwards the due items and waits
+/// until there is space for another item, so that no item is dropped.
+///
 /// Cloning creates a fresh, empty sink that shares only the inner sink clone and configuration;
 /// each clone maintains an independent delay heap.
 pub struct MixerSink<S, T> {
     inner: S,
-    heap: BinaryHeap<Reverse<DelayedData<T>>>,
+    heap: ConfiguredMixingStrategy<T>,
     timer: Delay,
     cfg: MixerConfig,
 }
 
 impl<S, T> MixerSink<S, T> {
     pub fn new(inner: S, cfg: MixerConfig) -> Self {
-        let mut heap = BinaryHeap::new();
-        heap.reserve(cfg.capacity);
         Self {
             inner,
-            heap,
+            heap: ConfiguredMixingStrategy::from(&cfg),
             timer: Delay::new(Duration::ZERO),
             cfg,
         }
@@ -49,90 +52,162 @@ impl<S: Clone, T> Clone for MixerSink<S, T> {
     }
 }
 
-impl<S, T> Sink<T> for MixerSink<S, T>
+impl<S, T> MixerSink<S, T>
 where
     S: Sink<T> + Unpin,
     T: Unpin,
 {
-    type Error = S::Error;
-
-    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        Poll::Ready(Ok(()))
-    }
-
-    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
-        let this = Pin::into_inner(self);
-        let random_delay = this.cfg.random_delay();
-
-        trace!(delay_ms = random_delay.as_millis(), "mixer: delaying item");
-
-        this.heap
-            .push(Reverse(DelayedData::from((Instant::now() + random_delay, item))));
-
-        #[cfg(all(feature = "telemetry", not(test)))]
-        {
-            METRIC_QUEUE_SIZE.increment(1.0f64);
-
-            let weight = 1.0f64 / this.cfg.metric_delay_window as f64;
-            METRIC_MIXER_AVERAGE_DELAY.set(
-                (weight * random_delay.as_millis() as f64) + ((1.0f64 - weight) * METRIC_MIXER_AVERAGE_DELAY.get()),
-            );
-        }
-
-        Ok(())
+    /// Indicates whether the sink is bounded with backpressure and full.
+    fn is_full(&self) -> bool {
+        self.cfg.overflow_policy == MixerOverflowPolicy::Backpressure
+            && self.cfg.buffer_bound().is_some_and(|bound| self.heap.len() >= bound)
     }
 
-    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        let this = Pin::into_inner(self);
-
+    /// Forwards the due items to the inner sink, parking on the timer for the next pending item.
+    ///
+    /// Completes once nothing more can be released or, if `until_not_full` is set,
+    /// once the sink is no longer full.
+    fn poll_forward(&mut self, cx: &mut Context<'_>, until_not_full: bool) -> Poll<Result<(), S::Error>> {
         loop {
             let now = Instant::now();
 
-            while this.heap.peek().is_some_and(|x| x.0.release_at <= now) {
-                let item = this.heap.pop().unwrap().0.item;
-
-                match Pin::new(&mut this.inner).poll_ready(cx) {
+            while self.heap.next_release().is_some_and(|release_at| release_at <= now) {
+                // Check the inner sink first, so that the released item does not need to be put back
+                match Pin::new(&mut self.inner).poll_ready(cx) {
                     Poll::Ready(Ok(())) => {}
                     Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
-                    Poll::Pending => {
-                        this.heap.push(Reverse(DelayedData::from((now, item))));
-                        break;
-                    }
+                    Poll::Pending => break,
                 }
 
-                if let Err(e) = Pin::new(&mut this.inner).start_send(item) {
+                let Some(item) = self.heap.pop_released(now) else {
+                    break;
+                };
+
+                if let Err(e) = Pin::new(&mut self.inner).start_send(item) {
                     return Poll::Ready(Err(e));
                 }
 
                 #[cfg(all(feature = "telemetry", not(test)))]
-                METRIC_QUEUE_SIZE.decrement(1.0f64);
+                {
+                    METRIC_QUEUE_SIZE.decrement(1.0f64);
+                    record_anonymity_metrics(&self.heap);
+                }
             }
 
-            futures::ready!(Pin::new(&mut this.inner).poll_flush(cx))?;
+            futures::ready!(Pin::new(&mut self.inner).poll_flush(cx))?;
 
-            if this.heap.is_empty() {
+            if until_not_full && !self.is_full() {
                 return Poll::Ready(Ok(()));
             }
 
-            let next_release = this.heap.peek().unwrap().0.release_at;
+            // Items held by pool-based strategies until more items arrive do not block the flush
+            let Some(next_release) = self.heap.next_release() else {
+                return Poll::Ready(Ok(()));
+            };
+
             let sleep_for = next_release.saturating_duration_since(now);
 
             if sleep_for.is_zero() {
                 // Reachable only because inner.poll_ready returned Pending earlier in
-                // this poll (we pushed the item back with release_at = now). The inner
+                // this poll (the due item stayed in the mixer). The inner
                 // sink already registered its waker via that poll_ready call, and any
                 // items it had buffered were flushed by the poll_flush call above —
                 // delegate to the lower sink's wake-up signal and yield.
                 return Poll::Pending;
             }
 
-            this.timer.reset(sleep_for);
+            self.timer.reset(sleep_for);
             // Park until the timer fires; cx.waker() is registered inside poll_unpin.
-            futures::ready!(this.timer.poll_unpin(cx));
+            futures::ready!(self.timer.poll_unpin(cx));
+        }
+    }
+}
+
+impl<S, T> Sink<T> for MixerSink<S, T>
+where
+    S: Sink<T> + Unpin,
+    T: Unpin,
+{
+    type Error = S::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = Pin::into_inner(self);
+        if !this.is_full() {
+            return Poll::Ready(Ok(()));
+        }
+
+        // Forward the due items to make space. The buffer bound leaves the mixing strategy
+        // always something to release, so this completes only once there is space for another item.
+        futures::ready!(this.poll_forward(cx, true))?;
+        if this.is_full() {
+            trace!("mixer: full, but nothing can be released");
+            return Poll::Pending;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        let this = Pin::into_inner(self);
+        let bound = this.cfg.buffer_bound();
+        let random_delay = match push_bounded(&mut this.heap, item, Instant::now(), bound, this.cfg.overflow_policy) {
+            BoundedPush::Inserted { delay, evicted } => {
+                if evicted {
+                    trace!("mixer: dropped the earliest item from the full mixer");
+
+                    #[cfg(all(feature = "telemetry", not(test)))]
+                    {
+                        METRIC_MIXER_DROPPED.increment();
+                        METRIC_QUEUE_SIZE.decrement(1.0f64);
+                    }
+                }
+                delay
+            }
+            BoundedPush::Full => {
+                // Only reachable if the item was sent without waiting for `poll_ready`
+                warn!("mixer: dropped the new item sent to the full mixer without waiting for poll_ready");
+
+                #[cfg(all(feature = "telemetry", not(test)))]
+                METRIC_MIXER_DROPPED.increment();
+
+                return Ok(());
+            }
+            BoundedPush::Dropped => {
+                trace!("mixer: dropped the new item, because the mixer is full");
+
+                #[cfg(all(feature = "telemetry", not(test)))]
+                METRIC_MIXER_DROPPED.increment();
+
+                return Ok(());
+            }
+        };
+
+        if let Some(random_delay) = random_delay {
+            trace!(delay_ms = random_delay.as_millis(), "mixer: delaying item");
         }
+
+        #[cfg(all(feature = "telemetry", not(test)))]
+        {
+            METRIC_QUEUE_SIZE.increment(1.0f64);
+
+            if let Some(random_delay) = random_delay {
+                let weight = 1.0f64 / this.cfg.metric_delay_window as f64;
+                METRIC_MIXER_AVERAGE_DELAY.set(
+                    (weight * random_delay.as_millis() as f64) + ((1.0f64 - weight) * METRIC_MIXER_AVERAGE_DELAY.get()),
+                );
+            }
+            record_anonymity_metrics(&this.heap);
+        }
+
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::into_inner(self).poll_forward(cx, false)
     }
 
     fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // No more items will arrive, so nothing can be held waiting for them
+        self.heap.release_all(Instant::now());
         futures::ready!(self.as_mut().poll_flush(cx))?;
         Pin::new(&mut Pin::into_inner(self).inner).poll_close(cx)
     }
@@ -157,6 +232,7 @@ mod tests {
             delay_range: Duration::from_millis(10),
             capacity: 16,
             metric_delay_window: 100,
+            ..Default::default()
         };
 
         let mut sink = MixerSink::new(tx, cfg);
@@ -179,6 +255,7 @@ mod tests {
             delay_range: Duration::from_millis(0),
             capacity: 16,
             metric_delay_window: 100,
+            ..Default::default()
         };
 
         let mut sink = MixerSink::new(tx, cfg);
@@ -204,6 +281,7 @@ mod tests {
             delay_range: Duration::from_millis(10),
             capacity: 16,
             metric_delay_window: 100,
+            ..Default::default()
         };
 
         let mut sink = MixerSink::new(tx, cfg);
@@ -264,6 +342,7 @@ mod tests {
             delay_range: Duration::from_millis(0),
             capacity: 16,
             metric_delay_window: 100,
+            ..Default::default()
         };
         let mut sink = MixerSink::new(AlwaysFullNoOpFlushSink, cfg);
         sink.start_send_unpin(42u32).unwrap();
@@ -278,4 +357,86 @@ mod tests {
              should not have become ready"
         );
     }
+
+    #[tokio::test]
+    async fn bounded_sink_should_forward_due_items_before_accepting_more() {
+        let (tx, mut rx) = mpsc::channel::<u32>(100);
+        let cfg = MixerConfig {
+            min_delay: Duration::from_millis(50),
+            delay_range: Duration::from_millis(1),
+            max_buffered: Some(2),
+            ..Default::default()
+        };
+
+        let mut sink = MixerSink::new(tx, cfg);
+        let start = Instant::now();
+        for i in 0u32..3 {
+            sink.feed(i).await.unwrap();
+        }
+
+        // The third item had to wait for the first one to be forwarded
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        assert!(timeout(Duration::from_millis(10), rx.next()).await.is_ok());
+
+        sink.flush().await.unwrap();
+        let mut received = 1;
+        while let Ok(Some(_)) = timeout(Duration::from_millis(10), rx.next()).await {
+            received += 1;
+        }
+        assert_eq!(3, received);
+    }
+
+    #[tokio::test]
+    async fn bounded_sink_should_drop_newest_items_when_full() {
+        let (tx, mut rx) = mpsc::channel::<u32>(100);
+        let cfg = MixerConfig {
+            min_delay: Duration::from_millis(10),
+            delay_range: Duration::from_millis(1),
+            max_buffered: Some(2),
+            overflow_policy: MixerOverflowPolicy::DropNewest,
+            ..Default::default()
+        };
+
+        let mut sink = MixerSink::new(tx, cfg);
+        for i in 0u32..5 {
+            sink.feed(i).await.unwrap();
+        }
+        sink.flush().await.unwrap();
+
+        let mut received = Vec::new();
+        while let Ok(Some(item)) = timeout(Duration::from_millis(10), rx.next()).await {
+            received.push(item);
+        }
+        received.sort();
+        assert_eq!(vec![0, 1], received);
+    }
+
+    #[tokio::test]
+    async fn bounded_sink_with_timed_pool_should_not_drop_items_when_full() {
+        let (tx, mut rx) = mpsc::channel::<u32>(100);
+        let cfg = MixerConfig {
+            strategy: crate::config::MixingStrategyConfig::TimedPool {
+                interval: Duration::from_millis(20),
+                pool_size: 2,
+            },
+            max_buffered: Some(1),
+            ..Default::default()
+        };
+
+        let mut sink = MixerSink::new(tx, cfg);
+        for i in 0u32..5 {
+            timeout(LEEWAY, sink.feed(i))
+                .await
+                .expect("send should not wait forever")
+                .unwrap();
+        }
+        sink.close().await.unwrap();
+
+        let mut received = Vec::new();
+        while let Ok(Some(item)) = timeout(Duration::from_millis(10), rx.next()).await {
+            received.push(item);
+        }
+        received.sort();
+        assert_eq!(vec![0, 1, 2, 3, 4], received);
+    }
 }
@@ -0,0 +1,623 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    config::{MixerConfig, MixerOverflowPolicy, MixingStrategyConfig},
+    data::DelayedData,
+};
+
+/// Strategy deciding when the items buffered inside the mixer are released.
+///
+/// The strategy owns the buffered items. The mixer pushes each incoming item into the strategy
+/// and repeatedly asks for released items, sleeping until [`MixingStrategy::next_release`] in between.
+pub trait MixingStrategy<T> {
+    /// Inserts a new item into the strategy.
+    ///
+    /// Returns the delay assigned to the item, if the strategy assigns the delays upfront.
+    fn push(&mut self, item: T, now: Instant) -> Option<Duration>;
+
+    /// Pops an item that is due for release at `now`, if any.
+    fn pop_released(&mut self, now: Instant) -> Option<T>;
+
+    /// Time at which the next item will be released.
+    ///
+    /// Returns `None` if no item can be released until more items are pushed.
+    /// The returned time can be in the past, meaning an item can be popped immediately.
+    fn next_release(&self) -> Option<Instant>;
+
+    /// Number of items currently buffered.
+    fn len(&self) -> usize;
+
+    /// Indicates whether there are no items buffered.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes the buffered item that would be released first, regardless of whether it is due.
+    fn evict_next(&mut self) -> Option<T>;
+
+    /// Makes all items that would otherwise wait for more input releasable.
+    ///
+    /// Called once no more items can arrive, so that buffered items are not held forever.
+    fn release_all(&mut self, now: Instant);
+
+    /// Estimated size of the anonymity set of the released items.
+    ///
+    /// This is the number of buffered items an outgoing item cannot be distinguished from.
+    fn anonymity_set_size(&self) -> usize;
+
+    /// Estimated Shannon entropy (in bits) of the mapping between the incoming and the outgoing items.
+    ///
+    /// The default implementation assumes all items in the anonymity set are equally likely.
+    fn entropy_bits(&self) -> f64 {
+        (self.anonymity_set_size().max(1) as f64).log2()
+    }
+}
+
+/// Distribution of the per-item delays used by the [`DelayMix`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DelayDistribution {
+    /// Delay drawn uniformly from `[min, min + range]`.
+    Uniform { min: Duration, range: Duration },
+    /// Delay of `min` plus an exponentially distributed delay with the given `mean`.
+    ///
+    /// Exponential delays are memoryless, which makes all the buffered items equally likely
+    /// to be released next (a continuous-time or "stop-and-go" mix).
+    Exponential { min: Duration, mean: Duration },
+}
+
+impl DelayDistribution {
+    /// Draws a random delay from this distribution.
+    pub fn sample(&self) -> Duration {
+        match *self {
+            DelayDistribution::Uniform { min, range } => {
+                let max_delay = min.saturating_add(range);
+
+                let random_delay = if max_delay.as_millis() <= min.as_millis() {
+                    max_delay.as_millis() as u64
+                } else {
+                    hopr_types::crypto_random::random_integer(
+                        min.as_millis() as u64,
+                        Some(max_delay.as_millis() as u64),
+                    )
+                };
+
+                Duration::from_millis(random_delay)
+            }
+            DelayDistribution::Exponential { min, mean } => {
+                // Inverse transform sampling, the random float is from [0, 1)
+                let sample = -(1.0 - hopr_types::crypto_random::random_float()).ln() * mean.as_secs_f64();
+                min.saturating_add(Duration::try_from_secs_f64(sample).unwrap_or(Duration::MAX))
+            }
+        }
+    }
+}
+
+/// Mixing strategy assigning a random delay to each item independently.
+///
+/// The delays are capped at [`DelayMix::MAX_DELAY`].
+pub struct DelayMix<T> {
+    buffer: BinaryHeap<Reverse<DelayedData<T>>>,
+    distribution: DelayDistribution,
+}
+
+impl<T> DelayMix<T> {
+    /// Maximum delay of a single item, regardless of the configured distribution.
+    pub const MAX_DELAY: Duration = Duration::from_secs(24 * 3600);
+
+    pub fn new(distribution: DelayDistribution, capacity: usize) -> Self {
+        let mut buffer = BinaryHeap::new();
+        buffer.reserve(capacity);
+        Self { buffer, distribution }
+    }
+}
+
+impl<T> MixingStrategy<T> for DelayMix<T> {
+    fn push(&mut self, item: T, now: Instant) -> Option<Duration> {
+        // Very large configured delays would overflow the release time
+        let delay = self.distribution.sample().min(Self::MAX_DELAY);
+        let release_at = now.checked_add(delay).unwrap_or(now);
+        self.buffer.push(Reverse(DelayedData::from((release_at, item))));
+        Some(delay)
+    }
+
+    fn pop_released(&mut self, now: Instant) -> Option<T> {
+        if self.buffer.peek().is_some_and(|x| x.0.release_at <= now) {
+            self.buffer.pop().map(|x| x.0.item)
+        } else {
+            None
+        }
+    }
+
+    fn next_release(&self) -> Option<Instant> {
+        self.buffer.peek().map(|x| x.0.release_at)
+    }
+
+    fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    fn evict_next(&mut self) -> Option<T> {
+        self.buffer.pop().map(|x| x.0.item)
+    }
+
+    fn release_all(&mut self, _now: Instant) {
+        // Every item already has its release time assigned
+    }
+
+    fn anonymity_set_size(&self) -> usize {
+        // For exponential delays, this is exact. For other distributions, it is an upper bound.
+        self.buffer.len()
+    }
+}
+
+/// Pool of items from which a random subset is released at once.
+struct Pool<T> {
+    pool: Vec<T>,
+    released: VecDeque<T>,
+    last_flush: Option<Instant>,
+    last_flush_pool_size: usize,
+}
+
+impl<T> Pool<T> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            pool: Vec::with_capacity(capacity),
+            released: VecDeque::new(),
+            last_flush: None,
+            last_flush_pool_size: 0,
+        }
+    }
+
+    /// Moves all but `keep` randomly chosen items from the pool to the released items.
+    fn flush(&mut self, keep: usize, now: Instant) {
+        if self.pool.len() <= keep {
+            return;
+        }
+
+        self.last_flush = Some(now);
+        self.last_flush_pool_size = self.pool.len();
+        while self.pool.len() > keep {
+            let index = hopr_types::crypto_random::random_integer(0, Some(self.pool.len() as u64)) as usize;
+            self.released.push_back(self.pool.swap_remove(index));
+        }
+    }
+
+    fn pop_released(&mut self) -> Option<T> {
+        self.released.pop_front()
+    }
+
+    fn evict_next(&mut self) -> Option<T> {
+        // Items in the pool have no release order, so any of them can be evicted
+        self.released.pop_front().or_else(|| {
+            (!self.pool.is_empty()).then(|| {
+                let index = hopr_types::crypto_random::random_integer(0, Some(self.pool.len() as u64)) as usize;
+                self.pool.swap_remove(index)
+            })
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.pool.len() + self.released.len()
+    }
+}
+
+/// Threshold pool mix.
+///
+/// Items are collected in a pool, and once the pool holds `threshold + pool_size` items,
+/// `threshold` randomly chosen items are released at once. The remaining `pool_size` items
+/// stay in the pool, mixing with the future items.
+///
+/// Under low traffic, the items are held until enough other items arrive.
+pub struct ThresholdPoolMix<T> {
+    pool: Pool<T>,
+    threshold: usize,
+    pool_size: usize,
+}
+
+impl<T> ThresholdPoolMix<T> {
+    pub fn new(threshold: usize, pool_size: usize) -> Self {
+        let threshold = threshold.max(1);
+        Self {
+            pool: Pool::new(threshold.saturating_add(pool_size)),
+            threshold,
+            pool_size,
+        }
+    }
+}
+
+impl<T> MixingStrategy<T> for ThresholdPoolMix<T> {
+    fn push(&mut self, item: T, now: Instant) -> Option<Duration> {
+        self.pool.pool.push(item);
+        if self.pool.pool.len() >= self.threshold.saturating_add(self.pool_size) {
+            self.pool.flush(self.pool_size, now);
+        }
+        None
+    }
+
+    fn pop_released(&mut self, _now: Instant) -> Option<T> {
+        self.pool.pop_released()
+    }
+
+    fn next_release(&self) -> Option<Instant> {
+        if self.pool.released.is_empty() {
+            None
+        } else {
+            self.pool.last_flush
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.pool.len()
+    }
+
+    fn evict_next(&mut self) -> Option<T> {
+        self.pool.evict_next()
+    }
+
+    fn release_all(&mut self, now: Instant) {
+        self.pool.flush(0, now);
+    }
+
+    fn anonymity_set_size(&self) -> usize {
+        self.pool.last_flush_pool_size
+    }
+}
+
+/// Timed pool mix.
+///
+/// Every `interval`, all but `pool_size` randomly chosen items are released from the pool.
+pub struct TimedPoolMix<T> {
+    pool: Pool<T>,
+    interval: Duration,
+    pool_size: usize,
+    next_flush: Option<Instant>,
+}
+
+impl<T> TimedPoolMix<T> {
+    pub fn new(interval: Duration, pool_size: usize, capacity: usize) -> Self {
+        Self {
+            pool: Pool::new(capacity),
+            interval: interval.max(Duration::from_millis(1)),
+            pool_size,
+            next_flush: None,
+        }
+    }
+
+    /// Moves the next flush time to the first interval boundary after `now`.
+    fn advance_next_flush(&mut self, now: Instant) {
+        let next_flush = self.next_flush.get_or_insert(now + self.interval);
+        if *next_flush <= now {
+            let missed = (now - *next_flush).as_nanos() / self.interval.as_nanos() + 1;
+            *next_flush += self.interval * missed.min(u32::MAX as u128) as u32;
+        }
+    }
+}
+
+impl<T> MixingStrategy<T> for TimedPoolMix<T> {
+    fn push(&mut self, item: T, now: Instant) -> Option<Duration> {
+        // Skip the intervals in which there was nothing to release
+        if self.pool.pool.len() <= self.pool_size {
+            self.advance_next_flush(now);
+        }
+        self.pool.pool.push(item);
+        None
+    }
+
+    fn pop_released(&mut self, now: Instant) -> Option<T> {
+        if self.pool.released.is_empty() && self.next_flush.is_some_and(|next_flush| next_flush <= now) {
+            self.pool.flush(self.pool_size, now);
+            self.advance_next_flush(now);
+        }
+        self.pool.pop_released()
+    }
+
+    fn next_release(&self) -> Option<Instant> {
+        if !self.pool.released.is_empty() {
+            self.pool.last_flush
+        } else if self.pool.pool.len() > self.pool_size {
+            self.next_flush
+        } else {
+            None
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.pool.len()
+    }
+
+    fn evict_next(&mut self) -> Option<T> {
+        self.pool.evict_next()
+    }
+
+    fn release_all(&mut self, now: Instant) {
+        self.pool.flush(0, now);
+    }
+
+    fn anonymity_set_size(&self) -> usize {
+        self.pool.last_flush_pool_size
+    }
+}
+
+/// Mixing strategy selected by the [`MixerConfig`].
+pub enum ConfiguredMixingStrategy<T> {
+    Delay(DelayMix<T>),
+    ThresholdPool(ThresholdPoolMix<T>),
+    TimedPool(TimedPoolMix<T>),
+}
+
+impl<T> From<&MixerConfig> for ConfiguredMixingStrategy<T> {
+    fn from(cfg: &MixerConfig) -> Self {
+        match cfg.strategy {
+            MixingStrategyConfig::Uniform => Self::Delay(DelayMix::new(
+                DelayDistribution::Uniform {
+                    min: cfg.min_delay,
+                    range: cfg.delay_range,
+                },
+                cfg.capacity,
+            )),
+            MixingStrategyConfig::Exponential { mean_delay } => Self::Delay(DelayMix::new(
+                DelayDistribution::Exponential {
+                    min: cfg.min_delay,
+                    mean: mean_delay,
+                },
+                cfg.capacity,
+            )),
+            MixingStrategyConfig::ThresholdPool { threshold, pool_size } => {
+                Self::ThresholdPool(ThresholdPoolMix::new(threshold, pool_size))
+            }
+            MixingStrategyConfig::TimedPool { interval, pool_size } => {
+                Self::TimedPool(TimedPoolMix::new(interval, pool_size, cfg.capacity))
+            }
+        }
+    }
+}
+
+macro_rules! delegate {
+    ($self:ident, $s:ident => $e:expr) => {
+        match $self {
+            ConfiguredMixingStrategy::Delay($s) => $e,
+            ConfiguredMixingStrategy::ThresholdPool($s) => $e,
+            ConfiguredMixingStrategy::TimedPool($s) => $e,
+        }
+    };
+}
+
+impl<T> MixingStrategy<T> for ConfiguredMixingStrategy<T> {
+    fn push(&mut self, item: T, now: Instant) -> Option<Duration> {
+        delegate!(self, s => s.push(item, now))
+    }
+
+    fn pop_released(&mut self, now: Instant) -> Option<T> {
+        delegate!(self, s => s.pop_released(now))
+    }
+
+    fn next_release(&self) -> Option<Instant> {
+        delegate!(self, s => s.next_release())
+    }
+
+    fn len(&self) -> usize {
+        delegate!(self, s => s.len())
+    }
+
+    fn evict_next(&mut self) -> Option<T> {
+        delegate!(self, s => s.evict_next())
+    }
+
+    fn release_all(&mut self, now: Instant) {
+        delegate!(self, s => s.release_all(now))
+    }
+
+    fn anonymity_set_size(&self) -> usize {
+        delegate!(self, s => s.anonymity_set_size())
+    }
+
+    fn entropy_bits(&self) -> f64 {
+        delegate!(self, s => s.entropy_bits())
+    }
+}
+
+/// Result of [`push_bounded`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BoundedPush {
+    /// The item was inserted, possibly evicting another item.
+    Inserted { delay: Option<Duration>, evicted: bool },
+    /// The item was dropped because the mixer is full.
+    Dropped,
+    /// The mixer is full and the sender must wait.
+    Full,
+}
+
+/// Inserts the item into the strategy while respecting the optional `bound` according to the `policy`.
+pub(crate) fn push_bounded<T, S: MixingStrategy<T>>(
+    strategy: &mut S,
+    item: T,
+    now: Instant,
+    bound: Option<usize>,
+    policy: MixerOverflowPolicy,
+) -> BoundedPush {
+    let mut evicted = false;
+    if bound.is_some_and(|bound| strategy.len() >= bound) {
+        match policy {
+            MixerOverflowPolicy::Backpressure => return BoundedPush::Full,
+            MixerOverflowPolicy::DropNewest => return BoundedPush::Dropped,
+            MixerOverflowPolicy::DropEarliestRelease => evicted = strategy.evict_next().is_some(),
+        }
+    }
+
+    BoundedPush::Inserted {
+        delay: strategy.push(item, now),
+        evicted,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exponential_delays_must_have_the_configured_mean() {
+        const SAMPLES: u32 = 20_000;
+
+        let distribution = DelayDistribution::Exponential {
+            min: Duration::from_millis(5),
+            mean: Duration::from_millis(20),
+        };
+
+        let samples = (0..SAMPLES).map(|_| distribution.sample()).collect::<Vec<_>>();
+        assert!(samples.iter().all(|d| *d >= Duration::from_millis(5)));
+
+        let mean = samples.iter().sum::<Duration>() / SAMPLES;
+        assert!(
+            mean > Duration::from_millis(24) && mean < Duration::from_millis(26),
+            "unexpected mean {mean:?}"
+        );
+
+        // Unlike the uniform distribution, exponential delays are not bounded by 4 times the mean
+        assert!(samples.iter().any(|d| *d > Duration::from_millis(5 + 80)));
+    }
+
+    #[test]
+    fn delay_mix_must_release_items_only_after_their_delay() {
+        let mut mix = DelayMix::new(
+            DelayDistribution::Uniform {
+                min: Duration::from_millis(10),
+                range: Duration::ZERO,
+            },
+            4,
+        );
+        let now = Instant::now();
+
+        assert_eq!(Some(Duration::from_millis(10)), mix.push(1, now));
+        assert_eq!(Some(now + Duration::from_millis(10)), mix.next_release());
+        assert_eq!(None, mix.pop_released(now));
+        assert_eq!(1, mix.anonymity_set_size());
+
+        assert_eq!(Some(1), mix.pop_released(now + Duration::from_millis(10)));
+        assert!(mix.is_empty());
+        assert_eq!(0.0, mix.entropy_bits());
+    }
+
+    #[test]
+    fn delay_mix_must_cap_very_large_delays() {
+        let mut mix = DelayMix::new(
+            DelayDistribution::Exponential {
+                min: Duration::MAX,
+                mean: Duration::MAX,
+            },
+            4,
+        );
+        let now = Instant::now();
+
+        assert_eq!(Some(DelayMix::<i32>::MAX_DELAY), mix.push(1, now));
+        assert_eq!(Some(now + DelayMix::<i32>::MAX_DELAY), mix.next_release());
+    }
+
+    #[test]
+    fn threshold_pool_mix_must_release_batches() {
+        let mut mix = ThresholdPoolMix::new(4, 2);
+        let now = Instant::now();
+
+        for i in 0..5 {
+            assert_eq!(None, mix.push(i, now));
+            assert_eq!(None, mix.next_release());
+            assert_eq!(None, mix.pop_released(now));
+        }
+
+        // Threshold + pool size reached: 4 out of 6 items are released
+        mix.push(5, now);
+        assert_eq!(Some(now), mix.next_release());
+        assert_eq!(6, mix.anonymity_set_size());
+        assert_eq!(6.0f64.log2(), mix.entropy_bits());
+
+        let released = std::iter::from_fn(|| mix.pop_released(now)).collect::<Vec<_>>();
+        assert_eq!(4, released.len());
+        assert_eq!(2, mix.len());
+        assert_eq!(None, mix.next_release());
+
+        // The rest is released only when no more items can arrive
+        mix.release_all(now);
+        let mut all = std::iter::from_fn(|| mix.pop_released(now))
+            .chain(released)
+            .collect::<Vec<_>>();
+        all.sort();
+        assert_eq!((0..6).collect::<Vec<_>>(), all);
+    }
+
+    #[test]
+    fn timed_pool_mix_must_release_all_but_pool_size_items_every_interval() {
+        let interval = Duration::from_millis(100);
+        let mut mix = TimedPoolMix::new(interval, 1, 10);
+        let now = Instant::now();
+
+        mix.push(0, now);
+        assert_eq!(None, mix.next_release(), "must keep the pool size");
+
+        mix.push(1, now);
+        mix.push(2, now);
+        assert_eq!(Some(now + interval), mix.next_release());
+        assert_eq!(None, mix.pop_released(now + interval / 2));
+
+        let released = std::iter::from_fn(|| mix.pop_released(now + interval)).collect::<Vec<_>>();
+        assert_eq!(2, released.len());
+        assert_eq!(1, mix.len());
+        assert_eq!(3, mix.anonymity_set_size());
+
+        // Intervals without anything to release are skipped
+        mix.push(3, now + 5 * interval / 2);
+        assert_eq!(Some(now + 3 * interval), mix.next_release());
+        assert_eq!(1, std::iter::from_fn(|| mix.pop_released(now + 3 * interval)).count());
+        assert_eq!(Some(now + 4 * interval), mix.next_flush);
+    }
+
+    #[test]
+    fn push_bounded_must_apply_the_overflow_policy() {
+        let now = Instant::now();
+        let new_mix = || {
+            let mut mix = DelayMix::new(
+                DelayDistribution::Uniform {
+                    min: Duration::from_millis(10),
+                    range: Duration::from_millis(10),
+                },
+                4,
+            );
+            mix.push(1, now);
+            mix.push(2, now);
+            mix
+        };
+
+        let mut mix = new_mix();
+        let policy = MixerOverflowPolicy::Backpressure;
+        assert_eq!(BoundedPush::Full, push_bounded(&mut mix, 3, now, Some(2), policy));
+        assert!(matches!(
+            push_bounded(&mut mix, 3, now, Some(3), policy),
+            BoundedPush::Inserted { evicted: false, .. }
+        ));
+        assert_eq!(BoundedPush::Full, push_bounded(&mut mix, 4, now, Some(3), policy));
+        assert_eq!(3, mix.len());
+
+        let mut mix = new_mix();
+        let policy = MixerOverflowPolicy::DropNewest;
+        assert_eq!(BoundedPush::Dropped, push_bounded(&mut mix, 3, now, Some(2), policy));
+        assert_eq!(2, mix.len());
+
+        let mut mix = new_mix();
+        let earliest = mix.buffer.peek().map(|x| x.0.item);
+        let policy = MixerOverflowPolicy::DropEarliestRelease;
+        assert!(matches!(
+            push_bounded(&mut mix, 3, now, Some(2), policy),
+            BoundedPush::Inserted { evicted: true, .. }
+        ));
+        assert_eq!(2, mix.len());
+        assert!(mix.buffer.iter().all(|x| Some(x.0.item) != earliest));
+        assert!(mix.buffer.iter().any(|x| x.0.item == 3));
+
+        assert!(matches!(
+            push_bounded(&mut mix, 4, now, None, policy),
+            BoundedPush::Inserted { evicted: false, .. }
+        ));
+    }
+}
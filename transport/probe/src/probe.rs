@@ -551,6 +551,9 @@ mod tests {
         get_peers: Arc<RwLock<VecDeque<Vec<OffchainPublicKey>>>>,
         #[allow(clippy::type_complexity)]
         on_finished: Arc<RwLock<Vec<(OffchainPublicKey, crate::errors::Result<Duration>)>>>,
+        /// IDs of the finished loopback probes, `Err` if the probe timed out.
+        #[allow(clippy::type_complexity)]
+        loopbacks_finished: Arc<RwLock<Vec<std::result::Result<Vec<u8>, Vec<u8>>>>>,
     }
 
     impl NetworkGraphUpdate for PeerStore {
@@ -573,6 +576,20 @@ mod tests {
                         Err(ProbeError::TrafficError(NetworkGraphError::ProbeNeighborTimeout(peer))),
                     ));
                 }
+                hopr_api::graph::MeasurableEdge::Probe(Ok(EdgeTransportTelemetry::Loopback(path_telemetry))) => {
+                    self.loopbacks_finished
+                        .write()
+                        .unwrap()
+                        .push(Ok(path_telemetry.id().to_vec()));
+                }
+                hopr_api::graph::MeasurableEdge::Probe(Err(NetworkGraphError::ProbeLoopbackTimeout(
+                    path_telemetry,
+                ))) => {
+                    self.loopbacks_finished
+                        .write()
+                        .unwrap()
+                        .push(Err(path_telemetry.id().to_vec()));
+                }
                 _ => panic!("unexpected telemetry type, unimplemented"),
             }
         }
@@ -736,6 +753,7 @@ mod tests {
             me: *OFFCHAIN_KEYPAIR.public(),
             get_peers: Arc::new(RwLock::new(VecDeque::new())),
             on_finished: Arc::new(RwLock::new(Vec::new())),
+            loopbacks_finished: Arc::new(RwLock::new(Vec::new())),
         };
 
         test_with_probing(cfg, store, move |iface: TestInterface| async move {
@@ -779,6 +797,7 @@ mod tests {
             me: *OFFCHAIN_KEYPAIR.public(),
             get_peers: Arc::new(RwLock::new(VecDeque::new())),
             on_finished: Arc::new(RwLock::new(Vec::new())),
+            loopbacks_finished: Arc::new(RwLock::new(Vec::new())),
         };
 
         test_with_probing(cfg, store, move |iface: TestInterface| async move {
@@ -824,6 +843,7 @@ mod tests {
                 neighbors
             })),
             on_finished: Arc::new(RwLock::new(Vec::new())),
+            loopbacks_finished: Arc::new(RwLock::new(Vec::new())),
         };
 
         test_with_probing(cfg, store.clone(), move |iface: TestInterface| async move {
@@ -881,6 +901,7 @@ mod tests {
                 neighbors
             })),
             on_finished: Arc::new(RwLock::new(Vec::new())),
+            loopbacks_finished: Arc::new(RwLock::new(Vec::new())),
         };
 
         let timeout = cfg.timeout * 2;
@@ -939,6 +960,7 @@ mod tests {
             me: *OFFCHAIN_KEYPAIR.public(),
             get_peers: Arc::new(RwLock::new(VecDeque::new())),
             on_finished: Arc::new(RwLock::new(Vec::new())),
+            loopbacks_finished: Arc::new(RwLock::new(Vec::new())),
         };
 
         test_with_probing(cfg, store, move |iface: TestInterface| async move {
@@ -1009,6 +1031,7 @@ mod tests {
                 neighbors
             })),
             on_finished: Arc::new(RwLock::new(Vec::new())),
+            loopbacks_finished: Arc::new(RwLock::new(Vec::new())),
         };
 
         test_with_probing(cfg, store.clone(), move |iface: TestInterface| async move {
@@ -1038,6 +1061,128 @@ mod tests {
         .await
     }
 
+    #[tokio::test]
+    async fn late_loopback_probe_must_not_be_delivered_to_the_next_owner_of_its_quarantined_tag() -> anyhow::Result<()>
+    {
+        let cfg = ProbeConfig {
+            timeout: std::time::Duration::from_millis(50),
+            interval: std::time::Duration::from_secs(0),
+            ..Default::default()
+        };
+
+        let probing_allocator = hopr_transport_tag_allocator::create_allocators_with_quarantine(
+            ReservedTag::range().end..u16::MAX as u64 + 1,
+            [
+                (hopr_transport_tag_allocator::Usage::Session, 4, 4),
+                (hopr_transport_tag_allocator::Usage::SessionTerminalTelemetry, 4, 4),
+                (hopr_transport_tag_allocator::Usage::ProvingTelemetry, 4, 4),
+            ],
+            hopr_transport_tag_allocator::TagQuarantineConfig {
+                period: Some(std::time::Duration::from_secs(30)),
+                ..Default::default()
+            },
+        )?
+        .into_iter()
+        .find_map(|(u, alloc)| matches!(u, hopr_transport_tag_allocator::Usage::ProvingTelemetry).then_some(alloc))
+        .ok_or_else(|| anyhow::anyhow!("probing allocator should exist"))?;
+
+        let store = PeerStore {
+            me: *OFFCHAIN_KEYPAIR.public(),
+            get_peers: Arc::new(RwLock::new(VecDeque::new())),
+            on_finished: Arc::new(RwLock::new(Vec::new())),
+            loopbacks_finished: Arc::new(RwLock::new(Vec::new())),
+        };
+
+        let (probes_tx, probes_rx) = futures::channel::mpsc::unbounded();
+        let strategy = TestProbeStrategy::Channel {
+            probes: Arc::new(RwLock::new(Some(probes_rx))),
+        };
+
+        let (from_probing_to_network_tx, mut from_probing_to_network_rx) =
+            futures::channel::mpsc::channel::<(DestinationRouting, ApplicationDataOut)>(100);
+        let (_manual_probe_tx, manual_probe_rx) =
+            futures::channel::mpsc::channel::<(OffchainPublicKey, PingQueryReplier)>(100);
+
+        let (jhs, probe_classifier) = Probe::new(cfg, probing_allocator.clone())
+            .continuously_scan(
+                from_probing_to_network_tx.clone(),
+                manual_probe_rx,
+                strategy,
+                store.clone(),
+            )
+            .await;
+
+        let loopback = || {
+            hopr_api::ct::ProbeRouting::Looping((
+                DestinationRouting::Forward {
+                    destination: Box::new((*OFFCHAIN_KEYPAIR.public()).into()),
+                    pseudonym: Some(HoprPseudonym::random()),
+                    forward_options: RoutingOptions::Hops(1.try_into().expect("1 is a valid u8")),
+                    return_options: None,
+                },
+                [1, 2, 3, 4, 5],
+            ))
+        };
+
+        // The first probe is sent, but its packet arrives only after the probe timed out
+        probes_tx.unbounded_send(loopback())?;
+        let (_, late_packet) =
+            tokio::time::timeout(std::time::Duration::from_secs(1), from_probing_to_network_rx.next())
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("no probe emitted"))?;
+
+        tokio::time::sleep(cfg.timeout * 2).await;
+        probe_classifier.active_path_probes.run_pending_tasks().await;
+        assert!(
+            matches!(store.loopbacks_finished.read().unwrap().as_slice(), [Err(_)]),
+            "the first probe must have timed out"
+        );
+        assert_eq!(probing_allocator.stats().quarantined, 1);
+
+        // The next probe must not get the tag of the timed out probe
+        probes_tx.unbounded_send(loopback())?;
+        let (_, next_packet) =
+            tokio::time::timeout(std::time::Duration::from_secs(1), from_probing_to_network_rx.next())
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("no probe emitted"))?;
+        assert_ne!(late_packet.data.application_tag, next_packet.data.application_tag);
+
+        // The late packet is not consumed as the telemetry of the next probe
+        let dispatch = probe_classifier
+            .classify(
+                from_probing_to_network_tx.clone(),
+                HoprPseudonym::random(),
+                ApplicationDataIn {
+                    data: late_packet.data,
+                    packet_info: Default::default(),
+                },
+            )
+            .await;
+        assert!(matches!(dispatch, ProbeDispatch::Passthrough(..)));
+        assert_eq!(store.loopbacks_finished.read().unwrap().len(), 1);
+
+        // While the packet of the next probe is delivered to it
+        let dispatch = probe_classifier
+            .classify(
+                from_probing_to_network_tx,
+                HoprPseudonym::random(),
+                ApplicationDataIn {
+                    data: next_packet.data,
+                    packet_info: Default::default(),
+                },
+            )
+            .await;
+        assert!(matches!(dispatch, ProbeDispatch::Consumed));
+        assert!(matches!(
+            store.loopbacks_finished.read().unwrap().as_slice(),
+            [Err(_), Ok(_)]
+        ));
+
+        jhs.abort_all();
+
+        Ok(())
+    }
+
     /// How a probe gets triggered inside the test: either via the manual ping channel
     /// (for neighbor probes) or by injecting a single [`ProbeRouting::Looping`] into the
     /// probing traffic generator (for loopback probes).
@@ -1051,6 +1196,10 @@ mod tests {
             routing: DestinationRouting,
             path_id: hopr_api::types::internal::routing::PathId,
         },
+        Channel {
+            #[allow(clippy::type_complexity)]
+            probes: Arc<RwLock<Option<futures::channel::mpsc::UnboundedReceiver<hopr_api::ct::ProbeRouting>>>>,
+        },
     }
 
     impl hopr_api::ct::ProbingTrafficGeneration for TestProbeStrategy {
@@ -1079,6 +1228,10 @@ mod tests {
                         futures::stream::pending(),
                     ))
                 }
+                Self::Channel { probes } => match probes.write().unwrap().take() {
+                    Some(probes) => Box::pin(probes),
+                    None => Box::pin(futures::stream::pending()),
+                },
             }
         }
     }
@@ -1141,6 +1294,7 @@ mod tests {
             me: *OFFCHAIN_KEYPAIR.public(),
             get_peers: Arc::new(RwLock::new(VecDeque::new())),
             on_finished: Arc::new(RwLock::new(Vec::new())),
+            loopbacks_finished: Arc::new(RwLock::new(Vec::new())),
         };
 
         // Kick off the probing process before triggering — the strategy's stream drives
@@ -72,8 +72,11 @@ fn make_start_protocol_data() -> ApplicationDataIn {
     let msg = StartProtocol::<SessionId, SessionTarget, ByteCapabilities>::StartSession(StartInitiation {
         challenge,
         target,
-        capabilities: ByteCapabilities::try_from(0u8).unwrap(),
+        capabilities: ByteCapabilities::try_from(0u16).unwrap(),
+        optional_capabilities: None,
+        session_protocol_version: None,
         additional_data: 0,
+        key_share: None,
     });
     let (tag, bytes) = msg.encode().unwrap();
     debug_assert_eq!(tag, START_PROTOCOL_MESSAGE_TAG);
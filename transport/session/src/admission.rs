@@ -0,0 +1,467 @@
+//! Admission control of incoming Sessions.
+//!
+//! Before an Exit allocates a Session slot for an incoming initiation, the initiation must pass:
+//!
+//! 1. the [rate limits](InitiationRateLimit) of Start initiations, both per initiator pseudonym and global,
+//! 2. the operator-provided [`SessionAdmissionPolicy`], if any,
+//! 3. the quotas of concurrently open Sessions per [`SessionTarget`].
+//!
+//! A rejected initiation is answered with [`StartErrorReason::RateLimited`] if it exceeded a rate limit,
+//! [`StartErrorReason::TargetRejected`] if it exceeded a target quota, or with whatever reason the policy chose.
+//!
+//! Keep in mind that the initiator chooses its pseudonym freely, so the per-pseudonym rate limit
+//! only restrains well-behaved clients re-initiating in a loop. The global rate limit is what
+//! protects the Exit from an initiator cycling through pseudonyms.
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use hopr_api::types::{crypto::keypairs::OffchainKeypair, internal::prelude::HoprPseudonym};
+use hopr_protocol_start::StartErrorReason;
+use hopr_utils::network_types::types::{IpOrHost, SealedHost};
+
+use crate::{Capabilities, ServiceId, SessionTarget};
+
+/// Maximum number of initiators whose per-pseudonym rate limit is tracked at once.
+const MAX_RATE_LIMITED_INITIATORS: u64 = 100_000;
+
+/// Token bucket rate limit of Start initiations.
+///
+/// Allows up to `burst` initiations at once, replenishing them evenly over the `period`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct InitiationRateLimit {
+    /// Maximum number of initiations allowed at once.
+    pub burst: u32,
+    /// Period over which the full `burst` is replenished.
+    #[serde(with = "humantime_serde")]
+    pub period: Duration,
+}
+
+impl InitiationRateLimit {
+    /// Rate limit allowing `burst` initiations per `period`.
+    pub fn new(burst: u32, period: Duration) -> Self {
+        Self { burst, period }
+    }
+}
+
+/// Configuration of the admission control of incoming Sessions.
+///
+/// All limits are disabled by default.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SessionAdmissionConfig {
+    /// Rate limit of Start initiations coming from a single initiator pseudonym.
+    pub per_pseudonym_rate: Option<InitiationRateLimit>,
+    /// Rate limit of all Start initiations.
+    pub global_rate: Option<InitiationRateLimit>,
+    /// Maximum number of concurrent [UDP](SessionTarget::UdpStream) Sessions to a single host.
+    ///
+    /// Sealed targets are unsealed with the [packet key](crate::SessionManager::with_packet_key) of the node.
+    /// Those that cannot be unsealed all count towards a single host.
+    pub max_udp_sessions_per_host: Option<usize>,
+    /// Maximum number of concurrent [TCP](SessionTarget::TcpStream) Sessions to a single host.
+    ///
+    /// Sealed targets are unsealed with the [packet key](crate::SessionManager::with_packet_key) of the node.
+    /// Those that cannot be unsealed all count towards a single host.
+    pub max_tcp_sessions_per_host: Option<usize>,
+    /// Maximum number of concurrent Sessions to a single [Exit service](SessionTarget::ExitNode).
+    pub max_sessions_per_service: Option<usize>,
+}
+
+/// Incoming Session initiation presented to the [`SessionAdmissionPolicy`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SessionAdmissionRequest<'a> {
+    /// Pseudonym of the Session initiator.
+    pub initiator: &'a HoprPseudonym,
+    /// Requested target of the Session.
+    pub target: &'a SessionTarget,
+    /// Capabilities negotiated for the Session.
+    pub capabilities: Capabilities,
+}
+
+/// Decides whether an incoming Session is admitted at the Exit.
+///
+/// The policy is consulted for every initiation that passed the rate limits,
+/// before any Session slot is allocated. Implementations must not block.
+///
+/// Any closure `Fn(&SessionAdmissionRequest) -> Result<(), StartErrorReason>` is a policy.
+pub trait SessionAdmissionPolicy: Send + Sync {
+    /// Admits the Session, or rejects it with the reason sent back to the initiator.
+    fn admit(&self, request: &SessionAdmissionRequest<'_>) -> Result<(), StartErrorReason>;
+}
+
+impl<F> SessionAdmissionPolicy for F
+where
+    F: Fn(&SessionAdmissionRequest<'_>) -> Result<(), StartErrorReason> + Send + Sync,
+{
+    fn admit(&self, request: &SessionAdmissionRequest<'_>) -> Result<(), StartErrorReason> {
+        self(request)
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: &InitiationRateLimit, now: Instant) -> Self {
+        Self {
+            tokens: limit.burst as f64,
+            updated: now,
+        }
+    }
+
+    fn try_take(&mut self, limit: &InitiationRateLimit, now: Instant) -> bool {
+        let refill = now.saturating_duration_since(self.updated).as_secs_f64() / limit.period.as_secs_f64();
+        self.tokens = (self.tokens + refill * limit.burst as f64).min(limit.burst as f64);
+        self.updated = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum TargetHost {
+    Dns(String),
+    Ip(IpAddr),
+    /// Any sealed host that could not be unsealed.
+    ///
+    /// Sealing is randomized, so the sealed form cannot tell the hosts apart.
+    Sealed,
+}
+
+impl From<&IpOrHost> for TargetHost {
+    fn from(value: &IpOrHost) -> Self {
+        match value {
+            IpOrHost::Dns(host, _) => Self::Dns(host.to_ascii_lowercase()),
+            IpOrHost::Ip(addr) => Self::Ip(addr.ip()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum QuotaKey {
+    Udp(TargetHost),
+    Tcp(TargetHost),
+    Service(ServiceId),
+}
+
+type QuotaCounts = Arc<parking_lot::Mutex<HashMap<QuotaKey, usize>>>;
+
+/// Holds a unit of a [`SessionTarget`] quota, returning it once dropped.
+#[derive(Debug)]
+pub(crate) struct AdmissionPermit {
+    counts: QuotaCounts,
+    key: QuotaKey,
+}
+
+impl Drop for AdmissionPermit {
+    fn drop(&mut self) {
+        let mut counts = self.counts.lock();
+        if let Some(count) = counts.get_mut(&self.key) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                counts.remove(&self.key);
+            }
+        }
+    }
+}
+
+/// Enforces the [`SessionAdmissionConfig`] and the [`SessionAdmissionPolicy`] on incoming Sessions.
+#[derive(Clone)]
+pub(crate) struct SessionAdmission {
+    cfg: SessionAdmissionConfig,
+    global_bucket: Arc<parking_lot::Mutex<Option<TokenBucket>>>,
+    initiator_buckets: moka::sync::Cache<HoprPseudonym, Arc<parking_lot::Mutex<TokenBucket>>>,
+    quotas: QuotaCounts,
+    policy: Option<Arc<dyn SessionAdmissionPolicy>>,
+    unseal_key: Option<Arc<OffchainKeypair>>,
+}
+
+impl SessionAdmission {
+    pub fn new(mut cfg: SessionAdmissionConfig) -> Self {
+        // Limits that would never admit anything are turned into ones admitting one initiation per period
+        for limit in [&mut cfg.per_pseudonym_rate, &mut cfg.global_rate]
+            .into_iter()
+            .flatten()
+        {
+            limit.burst = limit.burst.max(1);
+            limit.period = limit.period.max(Duration::from_millis(1));
+        }
+
+        let mut initiator_buckets = moka::sync::Cache::builder().max_capacity(MAX_RATE_LIMITED_INITIATORS);
+        if let Some(limit) = &cfg.per_pseudonym_rate {
+            // The bucket of an initiator idle for the whole period is full again, so it can be forgotten
+            initiator_buckets = initiator_buckets.time_to_idle(limit.period);
+        }
+
+        Self {
+            cfg,
+            global_bucket: Default::default(),
+            initiator_buckets: initiator_buckets.build(),
+            quotas: Default::default(),
+            policy: None,
+            unseal_key: None,
+        }
+    }
+
+    pub fn set_policy(&mut self, policy: Arc<dyn SessionAdmissionPolicy>) {
+        self.policy = Some(policy);
+    }
+
+    /// Sets the key used to unseal sealed targets, so they count towards the quota of their actual host.
+    pub fn set_unseal_key(&mut self, key: &OffchainKeypair) {
+        self.unseal_key = Some(Arc::new(key.clone()));
+    }
+
+    /// Counts a Start initiation from the `initiator` towards the rate limits.
+    pub fn check_rate(&self, initiator: &HoprPseudonym) -> Result<(), StartErrorReason> {
+        self.check_rate_at(initiator, Instant::now())
+    }
+
+    fn check_rate_at(&self, initiator: &HoprPseudonym, now: Instant) -> Result<(), StartErrorReason> {
+        if let Some(limit) = &self.cfg.per_pseudonym_rate {
+            let bucket = self.initiator_buckets.get_with(*initiator, || {
+                Arc::new(parking_lot::Mutex::new(TokenBucket::new(limit, now)))
+            });
+            if !bucket.lock().try_take(limit, now) {
+                return Err(StartErrorReason::RateLimited);
+            }
+        }
+
+        if let Some(limit) = &self.cfg.global_rate {
+            let mut bucket = self.global_bucket.lock();
+            if !bucket
+                .get_or_insert_with(|| TokenBucket::new(limit, now))
+                .try_take(limit, now)
+            {
+                return Err(StartErrorReason::RateLimited);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn target_host(&self, host: &SealedHost) -> TargetHost {
+        match host {
+            SealedHost::Plain(host) => host.into(),
+            SealedHost::Sealed(_) => self
+                .unseal_key
+                .as_ref()
+                .and_then(|key| host.clone().unseal(key).ok())
+                .map_or(TargetHost::Sealed, |host| (&host).into()),
+        }
+    }
+
+    fn quota(&self, target: &SessionTarget) -> Option<(QuotaKey, usize)> {
+        match target {
+            SessionTarget::UdpStream(host) => self
+                .cfg
+                .max_udp_sessions_per_host
+                .map(|max| (QuotaKey::Udp(self.target_host(host)), max)),
+            SessionTarget::TcpStream(host) => self
+                .cfg
+                .max_tcp_sessions_per_host
+                .map(|max| (QuotaKey::Tcp(self.target_host(host)), max)),
+            SessionTarget::ExitNode(service) => self
+                .cfg
+                .max_sessions_per_service
+                .map(|max| (QuotaKey::Service(*service), max)),
+        }
+    }
+
+    /// Total number of target quota units held by the open Sessions.
+    #[cfg(test)]
+    pub fn quotas_in_use(&self) -> usize {
+        self.quotas.lock().values().sum()
+    }
+
+    /// Admits the Session described by the `request`.
+    ///
+    /// The returned permit holds the quota of the Session's target until it is dropped.
+    pub fn admit(&self, request: &SessionAdmissionRequest<'_>) -> Result<Option<AdmissionPermit>, StartErrorReason> {
+        if let Some(policy) = &self.policy {
+            policy.admit(request)?;
+        }
+
+        let Some((key, max)) = self.quota(request.target) else {
+            return Ok(None);
+        };
+
+        let mut counts = self.quotas.lock();
+        let count = counts.entry(key.clone()).or_default();
+        if *count >= max {
+            return Err(StartErrorReason::TargetRejected);
+        }
+        *count += 1;
+
+        Ok(Some(AdmissionPermit {
+            counts: self.quotas.clone(),
+            key,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hopr_api::types::{crypto::keypairs::Keypair, crypto_random::Randomizable};
+
+    use super::*;
+
+    fn udp_target(host: &str) -> anyhow::Result<SessionTarget> {
+        Ok(SessionTarget::UdpStream(SealedHost::Plain(host.parse()?)))
+    }
+
+    #[test]
+    fn initiation_rate_limit_should_replenish_over_period() -> anyhow::Result<()> {
+        let admission = SessionAdmission::new(SessionAdmissionConfig {
+            per_pseudonym_rate: Some(InitiationRateLimit::new(2, Duration::from_secs(10))),
+            ..Default::default()
+        });
+
+        let alice = HoprPseudonym::random();
+        let bob = HoprPseudonym::random();
+        let now = Instant::now();
+
+        assert_eq!(Ok(()), admission.check_rate_at(&alice, now));
+        assert_eq!(Ok(()), admission.check_rate_at(&alice, now));
+        assert_eq!(Err(StartErrorReason::RateLimited), admission.check_rate_at(&alice, now));
+        assert_eq!(Ok(()), admission.check_rate_at(&bob, now));
+
+        assert_eq!(
+            Err(StartErrorReason::RateLimited),
+            admission.check_rate_at(&alice, now + Duration::from_secs(4))
+        );
+        assert_eq!(Ok(()), admission.check_rate_at(&alice, now + Duration::from_secs(5)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn global_rate_limit_should_apply_to_all_initiators() -> anyhow::Result<()> {
+        let admission = SessionAdmission::new(SessionAdmissionConfig {
+            per_pseudonym_rate: Some(InitiationRateLimit::new(5, Duration::from_secs(1))),
+            global_rate: Some(InitiationRateLimit::new(3, Duration::from_secs(1))),
+            ..Default::default()
+        });
+
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert_eq!(Ok(()), admission.check_rate_at(&HoprPseudonym::random(), now));
+        }
+        assert_eq!(
+            Err(StartErrorReason::RateLimited),
+            admission.check_rate_at(&HoprPseudonym::random(), now)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn target_quota_should_be_released_with_the_permit() -> anyhow::Result<()> {
+        let admission = SessionAdmission::new(SessionAdmissionConfig {
+            max_udp_sessions_per_host: Some(1),
+            ..Default::default()
+        });
+
+        let initiator = HoprPseudonym::random();
+        let first = udp_target("Example.com:53")?;
+        let second = udp_target("example.com:5353")?;
+        let other = udp_target("127.0.0.1:53")?;
+        let request = |target| SessionAdmissionRequest {
+            initiator: &initiator,
+            target,
+            capabilities: Capabilities::empty(),
+        };
+
+        let permit = admission.admit(&request(&first));
+        assert!(permit.as_ref().is_ok_and(|permit| permit.is_some()));
+        assert_eq!(
+            Err(StartErrorReason::TargetRejected),
+            admission.admit(&request(&second)).map(|_| ())
+        );
+        assert!(admission.admit(&request(&other)).is_ok_and(|permit| permit.is_some()));
+        let tcp = SessionTarget::TcpStream(SealedHost::Plain("example.com:80".parse()?));
+        assert!(admission.admit(&request(&tcp)).is_ok_and(|permit| permit.is_none()));
+
+        drop(permit);
+        assert!(admission.admit(&request(&second)).is_ok_and(|permit| permit.is_some()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sealed_targets_should_not_bypass_the_host_quota() -> anyhow::Result<()> {
+        let mut admission = SessionAdmission::new(SessionAdmissionConfig {
+            max_udp_sessions_per_host: Some(1),
+            ..Default::default()
+        });
+        admission.set_unseal_key(&OffchainKeypair::random());
+
+        let initiator = HoprPseudonym::random();
+        let request = |target| SessionAdmissionRequest {
+            initiator: &initiator,
+            target,
+            capabilities: Capabilities::empty(),
+        };
+
+        // Sealing is randomized, so two seals of the same host never share their bytes
+        let first = SessionTarget::UdpStream(SealedHost::Sealed(vec![1; 32].into_boxed_slice()));
+        let second = SessionTarget::UdpStream(SealedHost::Sealed(vec![2; 32].into_boxed_slice()));
+
+        let permit = admission.admit(&request(&first));
+        assert!(permit.as_ref().is_ok_and(|permit| permit.is_some()));
+        assert_eq!(
+            Err(StartErrorReason::TargetRejected),
+            admission.admit(&request(&second)).map(|_| ())
+        );
+
+        drop(permit);
+        assert!(admission.admit(&request(&second)).is_ok_and(|permit| permit.is_some()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn admission_policy_should_reject_before_quota_is_taken() -> anyhow::Result<()> {
+        let mut admission = SessionAdmission::new(SessionAdmissionConfig {
+            max_sessions_per_service: Some(1),
+            ..Default::default()
+        });
+        admission.set_policy(Arc::new(|request: &SessionAdmissionRequest<'_>| match request.target {
+            SessionTarget::ExitNode(0) => Err(StartErrorReason::TargetRejected),
+            _ => Ok(()),
+        }));
+
+        let initiator = HoprPseudonym::random();
+        let request = |target| SessionAdmissionRequest {
+            initiator: &initiator,
+            target,
+            capabilities: Capabilities::empty(),
+        };
+
+        assert_eq!(
+            Err(StartErrorReason::TargetRejected),
+            admission.admit(&request(&SessionTarget::ExitNode(0))).map(|_| ())
+        );
+        assert!(
+            admission
+                .admit(&request(&SessionTarget::ExitNode(1)))
+                .is_ok_and(|permit| permit.is_some())
+        );
+        assert!(admission.quotas.lock().get(&QuotaKey::Service(0)).is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,351 @@
+use crate::balancer::{BalancerControllerBounds, SurbBalancerController};
+
+/// Fraction of the output limit the output must change by between two steps,
+/// so that the output gain can be estimated from the buffer level response.
+const GAIN_EXCITATION_THRESHOLD: f64 = 0.05;
+
+/// Maximum factor by which the output gain estimate can deviate from its initial value.
+///
+/// Together with the minimum horizon of 3 steps, this keeps the closed loop stable
+/// even with the worst possible gain estimate.
+const MAX_GAIN_DEVIATION: f64 = 4.0;
+
+/// Parameters of the [`AdaptiveBalancerController`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AdaptiveControllerParams {
+    /// Initial estimate of the buffer level change during one sampling step caused by a unit of control output.
+    ///
+    /// Since the control output is in SURBs per second, this is the sampling interval in seconds.
+    pub initial_output_gain: f64,
+    /// Number of sampling steps in which the controller plans to reach the target when the buffer error is largest.
+    ///
+    /// Clamped to at least 3.
+    pub min_horizon: f64,
+    /// Number of sampling steps in which the controller plans to reach the target when the buffer is at the target.
+    pub max_horizon: f64,
+    /// Rate in `(0, 1]` at which the demand and output gain estimates follow new observations.
+    pub adaptation_rate: f64,
+}
+
+impl AdaptiveControllerParams {
+    /// Creates parameters for a controller sampled at the given `sampling_interval`.
+    pub fn for_sampling_interval(sampling_interval: std::time::Duration) -> Self {
+        Self {
+            initial_output_gain: sampling_interval.as_secs_f64(),
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for AdaptiveControllerParams {
+    fn default() -> Self {
+        Self {
+            initial_output_gain: 0.1,
+            min_horizon: 3.0,
+            max_horizon: 10.0,
+            adaptation_rate: 0.2,
+        }
+    }
+}
+
+/// Implementation of [`SurbBalancerController`] that tunes itself online.
+///
+/// The controller predicts the buffer level using a model `level' = level + gain * output - demand`,
+/// whose `demand` (SURBs lost from the buffer during a sampling step) and `gain` (SURBs added per unit of output
+/// during a sampling step) are estimated from the observed buffer level history.
+/// At each step, it outputs the SURB rate that reaches the target within the prediction horizon.
+///
+/// The horizon is scheduled by the buffer error: the farther the buffer level is from the target,
+/// the shorter horizon (and therefore the more aggressive output) is used. Near the target,
+/// the longer horizon smooths the output.
+///
+/// Since the demand is estimated from the output actually applied, the controller does not wind up
+/// when the output is saturated and reaches the target without steady-state error.
+#[derive(Clone, Debug)]
+pub struct AdaptiveBalancerController {
+    bounds: BalancerControllerBounds,
+    params: AdaptiveControllerParams,
+    output_gain: f64,
+    demand: f64,
+    last_level: Option<u64>,
+    last_change: Option<f64>,
+    last_output: f64,
+    prev_output: f64,
+}
+
+impl AdaptiveBalancerController {
+    /// Creates new instance given the `setpoint`, `output_limit` and controller parameters.
+    pub fn new(setpoint: u64, output_limit: u64, params: AdaptiveControllerParams) -> Self {
+        let params = AdaptiveControllerParams {
+            initial_output_gain: if params.initial_output_gain.is_finite() && params.initial_output_gain > 0.0 {
+                params.initial_output_gain
+            } else {
+                AdaptiveControllerParams::default().initial_output_gain
+            },
+            min_horizon: params.min_horizon.max(3.0),
+            max_horizon: params.max_horizon.max(params.min_horizon.max(3.0)),
+            adaptation_rate: params.adaptation_rate.clamp(f64::EPSILON, 1.0),
+        };
+        Self {
+            bounds: BalancerControllerBounds::new(setpoint, output_limit),
+            output_gain: params.initial_output_gain,
+            params,
+            demand: 0.0,
+            last_level: None,
+            last_change: None,
+            last_output: 0.0,
+            prev_output: 0.0,
+        }
+    }
+
+    /// Creates new instance with setpoint and output limit set to 0.
+    ///
+    /// Needs to be [reconfigured](SurbBalancerController::set_target_and_limit) in order to function
+    /// correctly.
+    pub fn from_params(params: AdaptiveControllerParams) -> Self {
+        Self::new(0, 0, params)
+    }
+
+    fn update_estimates(&mut self, current_buffer_level: u64) {
+        let Some(last_level) = self.last_level else {
+            return;
+        };
+        let change = current_buffer_level as f64 - last_level as f64;
+        let rate = self.params.adaptation_rate;
+
+        // The output gain can only be observed when the output changes substantially,
+        // assuming the demand did not change much in the meantime.
+        let output_change = self.last_output - self.prev_output;
+        if let Some(last_change) = self.last_change
+            && output_change.abs() >= GAIN_EXCITATION_THRESHOLD * (self.bounds.output_limit() as f64).max(1.0)
+        {
+            let initial = self.params.initial_output_gain;
+            let observed_gain = ((change - last_change) / output_change)
+                .clamp(initial / MAX_GAIN_DEVIATION, initial * MAX_GAIN_DEVIATION);
+            self.output_gain += rate * (observed_gain - self.output_gain);
+        }
+
+        let observed_demand = self.output_gain * self.last_output - change;
+        self.demand += rate * (observed_demand - self.demand);
+        self.last_change = Some(change);
+    }
+}
+
+impl Default for AdaptiveBalancerController {
+    /// The default instance does nothing unless [reconfigured](SurbBalancerController::set_target_and_limit).
+    fn default() -> Self {
+        Self::from_params(AdaptiveControllerParams::default())
+    }
+}
+
+impl SurbBalancerController for AdaptiveBalancerController {
+    fn bounds(&self) -> BalancerControllerBounds {
+        self.bounds
+    }
+
+    fn set_target_and_limit(&mut self, bounds: BalancerControllerBounds) {
+        // The estimates describe the Session traffic, so they remain valid
+        self.bounds = bounds;
+    }
+
+    fn next_control_output(&mut self, current_buffer_level: u64) -> u64 {
+        self.update_estimates(current_buffer_level);
+        self.last_level = Some(current_buffer_level);
+
+        let (target, limit) = (self.bounds.target() as f64, self.bounds.output_limit() as f64);
+        let error = target - current_buffer_level as f64;
+
+        let error_ratio = (error.abs() / target.max(1.0)).min(1.0);
+        let horizon = self.params.max_horizon - (self.params.max_horizon - self.params.min_horizon) * error_ratio;
+
+        let output = ((self.demand + error / horizon) / self.output_gain)
+            .clamp(0.0, limit)
+            .round();
+        self.prev_output = self.last_output;
+        self.last_output = output;
+        output as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::balancer::pid::{PidBalancerController, PidControllerGains};
+
+    const TARGET: u64 = 5_000;
+    const LIMIT: u64 = 2_500;
+    const SAMPLING_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+    /// Simulates the SURB buffer driven by the controller against the given demand (SURBs per second).
+    ///
+    /// The `plant_gain` is the true number of SURBs added to the buffer per sampling step and unit of output.
+    fn simulate<C: SurbBalancerController>(
+        controller: &mut C,
+        plant_gain: f64,
+        demand: impl Fn(usize) -> f64,
+        steps: usize,
+    ) -> Vec<u64> {
+        let dt = SAMPLING_INTERVAL.as_secs_f64();
+        let mut level = 0.0_f64;
+        (0..steps)
+            .map(|step| {
+                let output = controller.next_control_output(level.round() as u64);
+                level = (level + plant_gain * output as f64 - demand(step) * dt).max(0.0);
+                level.round() as u64
+            })
+            .collect()
+    }
+
+    fn adaptive_controller() -> AdaptiveBalancerController {
+        AdaptiveBalancerController::new(
+            TARGET,
+            LIMIT,
+            AdaptiveControllerParams::for_sampling_interval(SAMPLING_INTERVAL),
+        )
+    }
+
+    fn max_abs_error(levels: &[u64]) -> u64 {
+        levels.iter().map(|l| l.abs_diff(TARGET)).max().unwrap_or_default()
+    }
+
+    #[test]
+    fn controller_default_has_zero_bounds() {
+        let ctrl = AdaptiveBalancerController::default();
+        assert_eq!(ctrl.bounds().unzip(), (0, 0));
+    }
+
+    #[test]
+    fn controller_set_target_and_limit_updates_bounds() {
+        let mut ctrl = AdaptiveBalancerController::default();
+        ctrl.set_target_and_limit(BalancerControllerBounds::new(200, 100));
+        assert_eq!(ctrl.bounds().unzip(), (200, 100));
+    }
+
+    #[test]
+    fn controller_output_should_respect_limit() {
+        let mut ctrl = adaptive_controller();
+        assert_eq!(LIMIT, ctrl.next_control_output(0));
+        assert_eq!(0, ctrl.next_control_output(2 * TARGET));
+    }
+
+    #[test]
+    fn controller_step_response_snapshot() {
+        let mut ctrl = adaptive_controller();
+        let levels = simulate(&mut ctrl, SAMPLING_INTERVAL.as_secs_f64(), |_| 0.0, 40);
+        insta::assert_yaml_snapshot!(levels);
+    }
+
+    #[test]
+    fn controller_should_converge_from_empty_buffer_without_overshoot() {
+        let mut ctrl = adaptive_controller();
+        let levels = simulate(&mut ctrl, SAMPLING_INTERVAL.as_secs_f64(), |_| 1_000.0, 100);
+
+        assert!(levels.iter().all(|&l| l <= TARGET * 102 / 100), "overshoot: {levels:?}");
+        assert!(
+            max_abs_error(&levels[60..]) <= TARGET / 100,
+            "no convergence: {levels:?}"
+        );
+    }
+
+    #[test]
+    fn controller_should_reject_demand_step() {
+        let mut ctrl = adaptive_controller();
+        // Demand steps from 200 to 2000 SURBs/s once the buffer settles
+        let levels = simulate(
+            &mut ctrl,
+            SAMPLING_INTERVAL.as_secs_f64(),
+            |step| if step < 100 { 200.0 } else { 2_000.0 },
+            250,
+        );
+
+        assert!(
+            max_abs_error(&levels[80..100]) <= TARGET / 100,
+            "no convergence: {levels:?}"
+        );
+        assert!(
+            max_abs_error(&levels[100..]) <= TARGET / 10,
+            "large drop after step: {levels:?}"
+        );
+        assert!(max_abs_error(&levels[180..]) <= TARGET / 100, "no recovery: {levels:?}");
+        assert!(
+            (ctrl.demand - 2_000.0 * SAMPLING_INTERVAL.as_secs_f64()).abs() < 5.0,
+            "demand estimate: {}",
+            ctrl.demand
+        );
+    }
+
+    #[test]
+    fn controller_should_remain_stable_with_bursty_demand() {
+        let mut ctrl = adaptive_controller();
+        // Bursts of 2000 SURBs/s for 1 second every 3 seconds, otherwise 100 SURBs/s
+        let levels = simulate(
+            &mut ctrl,
+            SAMPLING_INTERVAL.as_secs_f64(),
+            |step| if step % 30 < 10 { 2_000.0 } else { 100.0 },
+            600,
+        );
+
+        // The buffer neither runs dry nor grows unbounded once filled
+        assert!(
+            levels[100..].iter().all(|&l| l > TARGET / 2),
+            "buffer drained: {levels:?}"
+        );
+        assert!(max_abs_error(&levels[100..]) <= TARGET / 5, "unstable: {levels:?}");
+
+        // The error oscillation does not grow over time
+        assert!(
+            max_abs_error(&levels[500..]) <= max_abs_error(&levels[100..200]) * 11 / 10,
+            "growing oscillation: {levels:?}"
+        );
+    }
+
+    #[test]
+    fn controller_should_adapt_to_mismatched_output_gain() {
+        let initial_gain = SAMPLING_INTERVAL.as_secs_f64();
+        for plant_gain in [0.5, 2.0].map(|f| f * initial_gain) {
+            let mut ctrl = adaptive_controller();
+            let levels = simulate(
+                &mut ctrl,
+                plant_gain,
+                |step| if step < 100 { 500.0 } else { 1_000.0 },
+                300,
+            );
+
+            assert!(
+                max_abs_error(&levels[250..]) <= TARGET / 100,
+                "no convergence with plant gain {plant_gain}: {levels:?}"
+            );
+            assert!(
+                (ctrl.output_gain - plant_gain).abs() < (initial_gain - plant_gain).abs(),
+                "gain estimate {} did not move towards {plant_gain}",
+                ctrl.output_gain
+            );
+        }
+    }
+
+    #[test]
+    fn controller_should_settle_faster_than_pid_after_demand_step() {
+        let demand = |step: usize| if step < 100 { 200.0 } else { 2_000.0 };
+        let settled_at = |levels: &[u64]| {
+            levels
+                .iter()
+                .rposition(|l| l.abs_diff(TARGET) > TARGET / 100)
+                .map_or(0, |pos| pos + 1)
+        };
+
+        let adaptive = simulate(&mut adaptive_controller(), SAMPLING_INTERVAL.as_secs_f64(), demand, 400);
+        let pid = simulate(
+            &mut PidBalancerController::new(TARGET, LIMIT, PidControllerGains::default()),
+            SAMPLING_INTERVAL.as_secs_f64(),
+            demand,
+            400,
+        );
+
+        assert!(
+            settled_at(&adaptive) < settled_at(&pid),
+            "adaptive settled at {}, pid at {}",
+            settled_at(&adaptive),
+            settled_at(&pid)
+        );
+    }
+}
@@ -13,6 +13,8 @@ use tracing::{Instrument, instrument};
 use super::{
     BalancerControllerBounds, MIN_BALANCER_SAMPLING_INTERVAL, SimpleSurbFlowEstimator, SurbBalancerController,
     SurbFlowController, SurbFlowEstimator,
+    adaptive::{AdaptiveBalancerController, AdaptiveControllerParams},
+    pid::{PidBalancerController, PidControllerGains},
 };
 use crate::SessionId;
 
@@ -87,6 +89,54 @@ pub struct SurbBalancerConfig {
     /// The default is `(60, 0.05)` (5% of the target buffer size is discarded every 60 seconds).
     #[default(_code = "Some((Duration::from_secs(60), 0.05))")]
     pub surb_decay: Option<(Duration, f64)>,
+
+    /// Kind of the controller regulating the SURB flow.
+    ///
+    /// The controller is chosen when the Session is established and cannot be changed
+    /// afterward, [updating](crate::SessionManager::update_surb_balancer_config) it to a different kind
+    /// is rejected.
+    ///
+    /// The default is [`SurbBalancerControllerKind::Pid`].
+    pub controller: SurbBalancerControllerKind,
+}
+
+/// Kind of the [`SurbBalancerController`] used by the [`SurbBalancer`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "snake_case")]
+#[repr(u8)]
+pub enum SurbBalancerControllerKind {
+    /// [PID controller](PidBalancerController) with gains [given by the
+    /// environment](PidControllerGains::from_env_or_default).
+    #[default]
+    Pid = 0,
+    /// [Self-tuning controller](AdaptiveBalancerController) adapting its model of the SURB flow online.
+    Adaptive = 1,
+}
+
+impl SurbBalancerControllerKind {
+    /// Creates a new controller of this kind, which is going to be sampled at the given `sampling_interval`.
+    ///
+    /// The setpoint and output limit of the controller are set to 0 and are expected to be
+    /// [reconfigured](SurbBalancerController::set_target_and_limit) by the [`SurbBalancer`].
+    pub(crate) fn new_controller(&self, sampling_interval: Duration) -> Box<dyn SurbBalancerController + Send + Sync> {
+        match self {
+            Self::Pid => Box::new(PidBalancerController::from_gains(
+                PidControllerGains::from_env_or_default(),
+            )),
+            Self::Adaptive => Box::new(AdaptiveBalancerController::from_params(
+                AdaptiveControllerParams::for_sampling_interval(sampling_interval.max(MIN_BALANCER_SAMPLING_INTERVAL)),
+            )),
+        }
+    }
+}
+
+impl From<u8> for SurbBalancerControllerKind {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => Self::Adaptive,
+            _ => Self::Pid,
+        }
+    }
 }
 
 impl SurbBalancerConfig {
@@ -105,6 +155,7 @@ pub struct BalancerStateValues {
     pub decay_duration_msec: AtomicU64,
     pub decay_volume_pct: AtomicU8,
     pub buffer_level: AtomicU64,
+    pub controller: AtomicU8,
 }
 
 impl BalancerStateValues {
@@ -113,10 +164,16 @@ impl BalancerStateValues {
         let state = Self::default();
         state.update(&cfg);
         state
+            .controller
+            .store(cfg.controller as u8, std::sync::atomic::Ordering::Relaxed);
+        state
     }
 
     /// Performs update of the [`BalancerStateValues`] from the [`SurbBalancerConfig`] and
     /// enables it.
+    ///
+    /// The [controller kind](SurbBalancerConfig::controller) is kept, because the running
+    /// controller cannot be swapped.
     pub fn update(&self, cfg: &SurbBalancerConfig) {
         self.target_surb_buffer_size
             .store(cfg.target_surb_buffer_size, std::sync::atomic::Ordering::Relaxed);
@@ -142,6 +199,7 @@ impl BalancerStateValues {
             target_surb_buffer_size: self.target_surb_buffer_size.load(std::sync::atomic::Ordering::Relaxed),
             max_surbs_per_sec: self.max_surbs_per_sec.load(std::sync::atomic::Ordering::Relaxed),
             surb_decay: self.surb_decay(),
+            controller: self.controller.load(std::sync::atomic::Ordering::Relaxed).into(),
         }
     }
 
@@ -399,7 +457,7 @@ mod tests {
     use hopr_api::types::{crypto_random::Randomizable, internal::prelude::HoprPseudonym};
 
     use super::*;
-    use crate::balancer::{AtomicSurbFlowEstimator, MockSurbFlowController, pid::PidBalancerController};
+    use crate::balancer::{AtomicSurbFlowEstimator, MockSurbFlowController};
 
     #[test]
     fn surb_balancer_config_should_be_convertible_to_atomics() {
@@ -408,6 +466,24 @@ mod tests {
         assert_eq!(cfg, state_data.as_config());
     }
 
+    #[test]
+    fn surb_balancer_config_controller_kind_should_be_convertible_to_atomics() {
+        let cfg = SurbBalancerConfig {
+            controller: SurbBalancerControllerKind::Adaptive,
+            ..Default::default()
+        };
+        let state_data = BalancerStateValues::new(cfg);
+        assert_eq!(cfg, state_data.as_config());
+    }
+
+    #[test]
+    fn surb_balancer_controller_kind_should_create_unconfigured_controller() {
+        for kind in [SurbBalancerControllerKind::Pid, SurbBalancerControllerKind::Adaptive] {
+            let controller = kind.new_controller(Duration::from_millis(100));
+            assert_eq!(controller.bounds().unzip(), (0, 0), "{kind}");
+        }
+    }
+
     #[test]
     fn surb_balancer_config_default_snapshot() {
         let cfg = SurbBalancerConfig::default();
@@ -420,6 +496,7 @@ mod tests {
             target_surb_buffer_size: 1000,
             max_surbs_per_sec: 500,
             surb_decay: None,
+            ..Default::default()
         };
         let bounds = cfg.as_controller_bounds();
         assert_eq!(bounds.target(), 1000);
@@ -432,6 +509,7 @@ mod tests {
             target_surb_buffer_size: 0,
             max_surbs_per_sec: 0,
             surb_decay: None,
+            ..Default::default()
         };
         let state = BalancerStateValues::new(cfg);
         assert!(state.is_disabled());
@@ -450,18 +528,35 @@ mod tests {
             target_surb_buffer_size: 3000,
             max_surbs_per_sec: 1500,
             surb_decay: Some((Duration::from_secs(30), 0.10)),
+            ..Default::default()
         };
         state.update(&cfg);
         assert_eq!(state.as_config(), cfg);
         assert_eq!(state.controller_bounds(), cfg.as_controller_bounds());
     }
 
+    #[test]
+    fn balancer_state_values_update_must_keep_the_controller_kind() {
+        let state = BalancerStateValues::new(SurbBalancerConfig {
+            controller: SurbBalancerControllerKind::Adaptive,
+            ..Default::default()
+        });
+        state.update(&SurbBalancerConfig {
+            target_surb_buffer_size: 3000,
+            controller: SurbBalancerControllerKind::Pid,
+            ..Default::default()
+        });
+        assert_eq!(state.as_config().controller, SurbBalancerControllerKind::Adaptive);
+        assert_eq!(state.as_config().target_surb_buffer_size, 3000);
+    }
+
     #[test]
     fn balancer_state_values_surb_decay_none_maps_to_none() {
         let cfg = SurbBalancerConfig {
             target_surb_buffer_size: 1000,
             max_surbs_per_sec: 500,
             surb_decay: None,
+            ..Default::default()
         };
         let state = BalancerStateValues::new(cfg);
         assert!(state.surb_decay().is_none());
@@ -486,6 +581,7 @@ mod tests {
             target_surb_buffer_size: 5000,
             max_surbs_per_sec: 2500,
             surb_decay: Some((Duration::from_secs(60), 0.05)),
+            ..Default::default()
         };
         let state: BalancerStateValues = cfg.into();
         assert_eq!(state.as_config(), cfg);
@@ -556,6 +652,7 @@ mod tests {
                     target_surb_buffer_size: 5_000,
                     max_surbs_per_sec: 2500,
                     surb_decay: None,
+                    ..Default::default()
                 }
                 .into(),
             ),
@@ -582,6 +679,67 @@ mod tests {
         }
     }
 
+    #[test_log::test]
+    fn surb_balancer_with_adaptive_controller_should_approach_target_without_overshoot() {
+        let production_rate = Arc::new(AtomicU64::new(0));
+        let consumption_rate = 100;
+        let steps = 10;
+        let step_duration = std::time::Duration::from_millis(200);
+
+        let mut controller = MockSurbFlowController::new();
+        let production_rate_clone = production_rate.clone();
+        controller.expect_adjust_surb_flow().times(steps).returning(move |r| {
+            production_rate_clone.store(r as u64, std::sync::atomic::Ordering::Relaxed);
+        });
+
+        let cfg = SurbBalancerConfig {
+            target_surb_buffer_size: 1_000,
+            max_surbs_per_sec: 2500,
+            surb_decay: None,
+            controller: SurbBalancerControllerKind::Adaptive,
+        };
+        let surb_estimator = AtomicSurbFlowEstimator::default();
+        let mut balancer = SurbBalancer::new(
+            HoprPseudonym::random(),
+            cfg.controller.new_controller(step_duration),
+            surb_estimator.clone(),
+            controller,
+            Arc::new(cfg.into()),
+        );
+
+        let mut levels = Vec::with_capacity(steps);
+        for _ in 0..steps {
+            std::thread::sleep(step_duration);
+            let elapsed_ms = step_duration.as_millis() as u64;
+            surb_estimator.produced.fetch_add(
+                production_rate.load(std::sync::atomic::Ordering::Relaxed) * elapsed_ms / 1000,
+                std::sync::atomic::Ordering::Relaxed,
+            );
+            surb_estimator.consumed.fetch_add(
+                consumption_rate * elapsed_ms / 1000,
+                std::sync::atomic::Ordering::Relaxed,
+            );
+            levels.push(balancer.update());
+        }
+
+        assert!(
+            levels.windows(2).skip(1).all(|w| w[1] >= w[0]),
+            "buffer levels should be non-decreasing: {levels:?}"
+        );
+        assert!(
+            levels
+                .iter()
+                .all(|&level| level <= cfg.target_surb_buffer_size * 11 / 10),
+            "buffer level should not overshoot the target: {levels:?}"
+        );
+        assert!(
+            levels
+                .last()
+                .is_some_and(|&level| level >= cfg.target_surb_buffer_size / 2),
+            "buffer level should approach the target: {levels:?}"
+        );
+    }
+
     #[test_log::test]
     fn surb_balancer_should_start_decrease_level_when_above_target() {
         let production_rate = Arc::new(AtomicU64::new(11_000));
@@ -643,6 +801,7 @@ mod tests {
             target_surb_buffer_size: 5_000,
             max_surbs_per_sec: 2500,
             surb_decay: Some((Duration::from_millis(200), 0.05)),
+            ..Default::default()
         };
 
         let mut mock_flow_ctl = MockSurbFlowController::new();
@@ -1,3 +1,6 @@
+/// Contains a self-tuning implementation of the [`SurbBalancerController`] trait, adapting its model
+/// of the SURB flow online.
+pub mod adaptive;
 mod controller;
 /// Contains implementation of the [`SurbBalancerController`] trait using a Proportional Integral Derivative (PID)
 /// controller.
@@ -7,7 +10,7 @@ mod rate_limiting;
 /// Contains a simple proportional output implementation of the [`SurbBalancerController`] trait.
 pub mod simple;
 
-pub use controller::{BalancerStateValues, SurbBalancer, SurbBalancerConfig};
+pub use controller::{BalancerStateValues, SurbBalancer, SurbBalancerConfig, SurbBalancerControllerKind};
 pub use rate_limiting::{RateController, RateLimitSinkExt, RateLimitStreamExt};
 
 /// Smallest possible interval for balancer sampling.
@@ -99,6 +102,20 @@ pub trait SurbBalancerController {
     fn next_control_output(&mut self, current_buffer_level: u64) -> u64;
 }
 
+impl<C: SurbBalancerController + ?Sized> SurbBalancerController for Box<C> {
+    fn bounds(&self) -> BalancerControllerBounds {
+        (**self).bounds()
+    }
+
+    fn set_target_and_limit(&mut self, bounds: BalancerControllerBounds) {
+        (**self).set_target_and_limit(bounds)
+    }
+
+    fn next_control_output(&mut self, current_buffer_level: u64) -> u64 {
+        (**self).next_control_output(current_buffer_level)
+    }
+}
+
 /// Implementation of [`SurbFlowEstimator`] that tracks the number of produced
 /// and consumed SURBs via two `u64`s.
 ///
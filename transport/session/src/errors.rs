@@ -60,6 +60,16 @@ pub enum SessionManagerError {
     TooManySessions,
     #[error("loopback sessions are not allowed")]
     Loopback,
+    #[error("encrypted sessions require the packet key of the destination")]
+    MissingDestinationKey,
+    #[error("encryption cannot be an optional session capability")]
+    OptionalEncryption,
+    #[error("counterparty agreed to session capabilities that do not match the requested ones")]
+    CapabilityMismatch,
+    #[error("counterparty agreed to a session protocol version that was not offered")]
+    ProtocolVersionMismatch,
+    #[error("service {0} is already registered")]
+    ServiceAlreadyRegistered(crate::ServiceId),
     #[error(transparent)]
     Other(anyhow::Error),
 }
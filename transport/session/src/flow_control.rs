@@ -1,7 +1,7 @@
 //! HOPR-transport wiring for the Session send-window flow control.
 //!
-//! The algorithm itself (the AIMD [`WindowController`], the honest-clock [`DeliverySignal`], the
-//! [`SupplyConstraint`] trait) lives in [`hopr_protocol_session::flow_control`]. This module supplies
+//! The algorithm itself (the [`CongestionController`] implementations, the honest-clock [`DeliverySignal`],
+//! the [`SupplyConstraint`] trait) lives in [`hopr_protocol_session::flow_control`]. This module supplies
 //! the two HOPR-specific pieces:
 //!
 //! * [`SurbSupply`] — the anti-grief SURB ceiling, reading the existing atomic [`BalancerStateValues`] as a
@@ -14,11 +14,11 @@ use std::{
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use hopr_protocol_session::flow_control::{
-    Backoff, DeliveryClock, DeliverySignal, FlowControlConfig, SupplyConstraint, WindowController,
+    Backoff, CongestionController, DeliveryClock, DeliverySignal, FlowControlConfig, SupplyConstraint,
 };
 
 use crate::balancer::BalancerStateValues;
@@ -81,14 +81,14 @@ impl SupplyConstraint for SurbSupply {
     }
 }
 
-/// Wraps a Session socket, admitting writes only while the [`WindowController`] has room against the
-/// honest delivery clock and the SURB ceiling. Reads are delegated untouched (never gated), so the
-/// duplex socket cannot deadlock; the window always keeps at least `min_window_size` admissible.
+/// Wraps a Session socket, admitting writes only while the [`CongestionController`] selected by the
+/// [`FlowControlConfig`] has room against the honest delivery clock and the SURB ceiling. Reads are delegated untouched
+/// (never gated), so the duplex socket cannot deadlock; the window always keeps at least `min_window_size` admissible.
 ///
 /// `S` is `Unpin` (the boxed Session socket is), so this needs no pin projection.
 pub struct PacedWriter<S> {
     inner: S,
-    window: WindowController,
+    window: Box<dyn CongestionController>,
     clock: DeliveryClock,
     supply: SurbSupply,
     /// Keep-progress deadline: how long to park when the window is momentarily full before
@@ -115,7 +115,7 @@ impl<S> PacedWriter<S> {
     pub fn new(inner: S, cfg: FlowControlConfig, clock: DeliveryClock, supply: SurbSupply) -> Self {
         Self {
             inner,
-            window: WindowController::new(cfg),
+            window: cfg.controller(),
             clock,
             supply,
             deadline: cfg.no_honest_deadline,
@@ -133,7 +133,7 @@ impl<S> PacedWriter<S> {
     /// down via the ceiling, in [`Self::admissible`]).
     fn refresh_window(&mut self) {
         let delivered = self.clock.poll_delivered();
-        self.window.apply_delivery(delivered);
+        self.window.apply_delivery(delivered, Instant::now());
         if delivered.acked_bytes > 0 || delivered.lost_bytes > 0 {
             self.stalled_parks = 0;
         }
@@ -155,7 +155,7 @@ impl<S> PacedWriter<S> {
 
     /// Bytes admissible right now, against the live SURB ceiling.
     ///
-    /// Normally this is `min(cwnd, surb_ceiling) − inflight`: the AIMD window bounds the send rate to
+    /// Normally this is `min(cwnd, surb_ceiling) − inflight`: the congestion window bounds the send rate to
     /// the drain rate, and the SURB ceiling clamps it down when supply is low (never up — invariant 2).
     ///
     /// **Persist probe (opt-in, anti-deadlock).** At end-of-stream a HOPR session has no half-close,
@@ -183,7 +183,7 @@ impl<S> PacedWriter<S> {
 
 /// Pure admission decision, including the persist probe. Extracted for unit testing.
 ///
-/// * `normal` = the AIMD/ceiling-bounded admissible bytes. If positive, use it (no probe).
+/// * `normal` = the window/ceiling-bounded admissible bytes. If positive, use it (no probe).
 /// * Otherwise, if the persist probe is enabled (`persist_after > 0`) and `stalled_parks` has reached it, admit up to
 ///   `min_window_size` **beyond `inflight`**, but never past the SURB `ceiling` — the anti-deadlock persist probe.
 ///   `persist_after == 0` disables the probe entirely (the default clean behaviour).
@@ -234,7 +234,7 @@ impl<S: futures::AsyncWrite + Unpin> futures::AsyncWrite for PacedWriter<S> {
             let to_write = buf.len().min(admissible);
             return match Pin::new(&mut this.inner).poll_write(cx, &buf[..to_write]) {
                 Poll::Ready(Ok(n)) => {
-                    this.window.on_sent(n);
+                    this.window.on_sent(n, Instant::now());
                     this.sent_total = this.sent_total.wrapping_add(n as u64);
                     this.stalled_parks = 0; // forward progress — reset the persist counter
                     Poll::Ready(Ok(n))
@@ -270,7 +270,7 @@ fn sleep(dur: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
 #[cfg(test)]
 mod tests {
     use futures::AsyncWriteExt;
-    use hopr_protocol_session::flow_control::DeliveryMeter;
+    use hopr_protocol_session::flow_control::{CongestionControlAlgorithm, DeliveryMeter};
 
     use super::*;
 
@@ -428,6 +428,27 @@ mod tests {
         assert!(second > 0, "delivery must reopen the window");
     }
 
+    #[tokio::test]
+    async fn paced_writer_with_delay_based_controller_reopens_after_delivery() {
+        let meter = DeliveryMeter::default();
+        let clock = DeliveryClock::new(meter.clone(), None);
+        let supply = SurbSupply::new(balancer(0, 0), 1_000);
+        let cfg = FlowControlConfig {
+            algorithm: CongestionControlAlgorithm::DelayBased,
+            ..small_cfg()
+        };
+        let mut w = PacedWriter::new(Sink::default(), cfg, clock, supply);
+
+        let first = w.write(&[0u8; 10_000]).await.unwrap();
+        assert!(
+            first > 0 && first <= 1_000,
+            "first write cannot exceed the floor window, got {first}"
+        );
+        meter.record_acked(first);
+        let second = w.write(&[0u8; 10_000]).await.unwrap();
+        assert!(second > first, "delivery must open the delay-based window");
+    }
+
     #[tokio::test]
     async fn paced_writer_empty_write_completes_immediately() {
         // An empty write must never be flow-controlled: even with the window pinned to the floor and
@@ -7,23 +7,36 @@
 //!
 //! This crate implements [RFC-0007](https://github.com/hoprnet/rfc/tree/main/rfcs/RFC-0007-session-protocol).
 
+mod admission;
 pub(crate) mod balancer;
 pub mod counters;
 pub mod errors;
 pub mod flow_control;
 mod manager;
+mod multipath;
+mod services;
 #[cfg(feature = "telemetry")]
 mod telemetry;
 mod types;
 mod utils;
 
-pub use balancer::{AtomicSurbFlowEstimator, BalancerStateValues, MIN_BALANCER_SAMPLING_INTERVAL, SurbBalancerConfig};
+pub use admission::{InitiationRateLimit, SessionAdmissionConfig, SessionAdmissionPolicy, SessionAdmissionRequest};
+pub use balancer::{
+    AtomicSurbFlowEstimator, BalancerStateValues, MIN_BALANCER_SAMPLING_INTERVAL, SurbBalancerConfig,
+    SurbBalancerControllerKind,
+};
 use hopr_api::types::internal::routing::RoutingOptions;
-pub use hopr_protocol_session::{AcknowledgementMode, flow_control::FlowControlConfig};
+pub use hopr_protocol_session::{
+    AcknowledgementMode, FecConfig,
+    flow_control::{CongestionControlAlgorithm, FlowControlConfig},
+};
+pub use hopr_protocol_start::ResumeTicket;
 pub use hopr_utils::network_types::types::*;
 #[cfg(feature = "benchmark")]
 pub use manager::SESSION_FORWARD_CAPACITY;
 pub use manager::{DispatchResult, MIN_SURB_BUFFER_DURATION, SessionManager, SessionManagerConfig};
+pub use multipath::{MultipathConfig, PathStats, SessionPathOptions};
+pub use services::{EchoService, SessionService};
 #[cfg(feature = "telemetry")]
 pub use telemetry::{SessionAckMode, SessionLifecycleState};
 pub use types::{
@@ -39,6 +52,13 @@ pub use utils::transfer_session;
 pub const SESSION_MTU: usize =
     hopr_protocol_session::session_socket_mtu::<{ hopr_protocol_app::v1::ApplicationData::PAYLOAD_SIZE }>();
 
+/// Number of bytes that can be sent in a single Session protocol payload of a Session
+/// with the [`Capability::Encryption`] flag set.
+///
+/// This is smaller than [`SESSION_MTU`] by the overhead of the encryption.
+pub const ENCRYPTED_SESSION_MTU: usize =
+    hopr_protocol_session::session_socket_mtu::<{ types::ENCRYPTED_PAYLOAD_SIZE }>();
+
 /// Size of the HOPR SURB in bytes.
 ///
 /// This is the re-export of [`hopr_crypto_packet::HoprSurb::SIZE`].
@@ -46,9 +66,13 @@ pub const SURB_SIZE: usize = hopr_crypto_packet::HoprSurb::SIZE;
 
 flagset::flags! {
     /// Individual capabilities of a Session.
-    #[repr(u8)]
+    ///
+    /// The Start protocol encodes the capabilities using 7 bits per byte, reserving the most significant
+    /// bit of each byte to signal an extension byte. Capabilities from `0x80` onwards are therefore carried
+    /// in an extension byte, which recipients that do not know about the extension cannot decode.
+    #[repr(u16)]
     #[derive(PartialOrd, Ord, strum::EnumString, strum::Display, serde_repr::Serialize_repr, serde_repr::Deserialize_repr)]
-    pub enum Capability : u8 {
+    pub enum Capability : u16 {
         /// Frame segmentation.
         Segmentation = 0b0000_1000,
         /// Frame retransmission (ACK-based)
@@ -67,6 +91,23 @@ flagset::flags! {
         ///
         /// This applies only to the recipient of the Session (Exit).
         NoRateControl = 0b0001_0000,
+        /// Forward error correction of frames using repair segments.
+        ///
+        /// Implies [`Segmentation`].
+        ForwardErrorCorrection = 0b0010_1000,
+        /// Allows the initiator to resume the Session after its path has changed.
+        ///
+        /// Resumption after the recipient has restarted is not supported.
+        /// See [`SessionManager::resume_session`].
+        Resumption = 0b0100_0000,
+        /// End-to-end encryption of the Session data with a key negotiated in the Start protocol.
+        ///
+        /// The Session initiator must know the packet key of the recipient
+        /// (see [`SessionClientConfig::destination_key`]), and the recipient must have its
+        /// packet key set on its [`SessionManager`].
+        ///
+        /// This is the first capability carried in the extension byte.
+        Encryption = 0b0000_0000_1000_0000,
     }
 }
 
@@ -88,6 +129,15 @@ pub struct SessionClientConfig {
     /// Capabilities offered by the session.
     #[default(_code = "Capability::Segmentation.into()")]
     pub capabilities: Capabilities,
+    /// Subset of `capabilities` the Session can do without, if the recipient does not support them.
+    ///
+    /// If non-empty, the capabilities are negotiated with the recipient, and the Session uses only
+    /// those the recipient agreed to. Recipients on older protocol versions cannot negotiate: if such
+    /// a recipient does not answer, the initiation is retried once with the optional capabilities left out.
+    ///
+    /// Default is empty: all the `capabilities` are required.
+    #[default(Capabilities::empty())]
+    pub optional_capabilities: Capabilities,
     /// Optional pseudonym used for the session. Mostly useful for testing only.
     #[default(None)]
     pub pseudonym: Option<hopr_api::types::internal::protocol::HoprPseudonym>,
@@ -113,6 +163,17 @@ pub struct SessionClientConfig {
     /// the client's explicit dial (only meaningful on a reliable / `RetransmissionAck` session).
     #[default(None)]
     pub flow_control: Option<FlowControlConfig>,
+    /// Opt-in striping of the Session over multiple forward paths.
+    ///
+    /// `None` (the default) sends all Session packets over the single path given by
+    /// `forward_path_options` and `return_path_options`. See [`MultipathConfig`] for details.
+    #[default(None)]
+    pub multipath: Option<MultipathConfig>,
+    /// Packet key of the Session recipient.
+    ///
+    /// Required by the [`Capability::Encryption`] flag, ignored otherwise.
+    #[default(None)]
+    pub destination_key: Option<hopr_api::types::crypto::types::OffchainPublicKey>,
 }
 
 #[cfg(test)]
@@ -122,7 +183,9 @@ mod tests {
     use hopr_protocol_app::v1::ApplicationData;
     use hopr_protocol_session::session_socket_mtu;
     use hopr_protocol_start::{
-        KeepAliveMessage, StartChallenge, StartErrorReason, StartErrorType, StartEstablished, StartInitiation,
+        KeepAliveMessage, ResumeTicket, StartChallenge, StartErrorReason, StartErrorType, StartEstablished,
+        StartInitiation, StartResumption,
+        handshake::{CONFIRMATION_TAG_SIZE, KEY_SHARE_SIZE, KeyConfirmation, SessionCipher},
     };
 
     use super::*;
@@ -132,6 +195,7 @@ mod tests {
     fn test_session_mtu() {
         assert_eq!(SESSION_MTU, session_socket_mtu::<{ ApplicationData::PAYLOAD_SIZE }>());
         assert_eq!(1020, SESSION_MTU); // Needs to be changed when HOPR packet payload size changes
+        assert_eq!(SESSION_MTU - SessionCipher::OVERHEAD, ENCRYPTED_SESSION_MTU);
     }
 
     #[test]
@@ -142,7 +206,10 @@ mod tests {
                 "example-of-a-very-very-long-second-level-name.on-a-very-very-long-domain-name.info:65530".parse()?,
             )),
             capabilities: Capabilities::full().into(),
+            optional_capabilities: None,
+            session_protocol_version: None,
             additional_data: 0xffffffff,
+            key_share: Some([0xff; KEY_SHARE_SIZE]),
         });
 
         assert!(
@@ -154,6 +221,13 @@ mod tests {
         let msg = HoprStartProtocol::SessionEstablished(StartEstablished {
             orig_challenge: StartChallenge::MAX,
             session_id: HoprPseudonym::random(),
+            capabilities: None,
+            session_protocol_version: None,
+            resume_ticket: Some(ResumeTicket::MAX),
+            key_confirmation: Some(KeyConfirmation {
+                key_share: [0xff; KEY_SHARE_SIZE],
+                tag: [0xff; CONFIRMATION_TAG_SIZE],
+            }),
         });
 
         assert!(
@@ -184,6 +258,18 @@ mod tests {
             HoprPacket::PAYLOAD_SIZE
         );
 
+        let msg = HoprStartProtocol::ResumeSession(StartResumption {
+            challenge: StartChallenge::MAX,
+            session_id: HoprPseudonym::random(),
+            ticket: ResumeTicket::MAX,
+            additional_data: 0xffffffff,
+        });
+        let len = msg.encode()?.1.len();
+        assert!(
+            HoprPacket::max_surbs_with_message(len) >= 1,
+            "Hopr ResumeSession message size ({len}) must allow for at least 1 SURB in packet",
+        );
+
         Ok(())
     }
 
@@ -196,7 +282,10 @@ mod tests {
                 "example-of-a-very-very-long-second-level-name.on-a-very-very-long-domain-name.info:65530".parse()?,
             )),
             capabilities: Capabilities::full().into(),
+            optional_capabilities: None,
+            session_protocol_version: None,
             additional_data: 0xffffffff,
+            key_share: Some([0xff; KEY_SHARE_SIZE]),
         });
         let len = msg.encode()?.1.len();
         assert!(
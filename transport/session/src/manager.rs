@@ -8,6 +8,7 @@ use anyhow::anyhow;
 use futures::{Sink, SinkExt, StreamExt, TryStreamExt, future::AbortHandle};
 use futures_time::future::FutureExt as TimeExt;
 use hopr_api::types::{
+    crypto::keypairs::OffchainKeypair,
     crypto_random::Randomizable,
     internal::{
         prelude::HoprPseudonym,
@@ -18,10 +19,12 @@ use hopr_api::types::{
 use hopr_crypto_packet::prelude::HoprPacket;
 use hopr_protocol_app::prelude::*;
 use hopr_protocol_start::{
-    KeepAliveFlag, KeepAliveMessage, StartChallenge, StartErrorReason, StartErrorType, StartEstablished,
-    StartInitiation,
+    KeepAliveFlag, KeepAliveMessage, ResumeTicket, StartChallenge, StartErrorReason, StartErrorType, StartEstablished,
+    StartInitiation, StartResumption,
+    handshake::{HandshakeInitiator, HandshakeResponder, SessionCipher},
 };
 use hopr_utils::runtime::AbortableList;
+use subtle::ConstantTimeEq;
 use tracing::{debug, error, info, trace, warn};
 
 #[cfg(feature = "telemetry")]
@@ -30,16 +33,21 @@ use crate::telemetry::{
     set_session_state,
 };
 use crate::{
-    Capability, HoprSession, IncomingSession, SESSION_MTU, SessionClientConfig, SessionId, SessionTarget,
+    Capabilities, Capability, FecConfig, HoprSession, IncomingSession, SESSION_MTU, SessionAdmissionConfig,
+    SessionAdmissionPolicy, SessionAdmissionRequest, SessionClientConfig, SessionId, SessionService, SessionTarget,
     SurbBalancerConfig,
+    admission::{AdmissionPermit, SessionAdmission},
     balancer::{
         AtomicSurbFlowEstimator, BalancerStateValues, RateController, RateLimitSinkExt, SurbBalancer,
-        SurbControllerWithCorrection,
-        pid::{PidBalancerController, PidControllerGains},
-        simple::SimpleBalancerController,
+        SurbControllerWithCorrection, simple::SimpleBalancerController,
     },
     errors::{SessionManagerError, TransportSessionError},
-    types::{ByteCapabilities, ClosureReason, HoprSessionConfig, HoprStartProtocol, SESSION_APPLICATION_TAG},
+    multipath::{PathScheduler, PathStats},
+    services::SessionServiceRegistry,
+    types::{
+        ByteCapabilities, ClosureReason, HoprSessionConfig, HoprStartProtocol, MIN_SESSION_PROTOCOL_VERSION,
+        SESSION_APPLICATION_TAG, SESSION_PROTOCOL_VERSION, without_capabilities,
+    },
     utils,
     utils::{SurbNotificationMode, insert_into_next_slot},
 };
@@ -93,6 +101,9 @@ fn close_session(session_id: SessionId, session_data: SessionSlot, reason: Closu
     // Terminate any additional tasks spawned by the Session
     session_data.abort_handles.lock().abort_all();
 
+    // Release the quota of the Session target
+    session_data.admission.lock().take();
+
     #[cfg(all(feature = "telemetry", not(test)))]
     METRIC_ACTIVE_SESSIONS.decrement(1.0);
 }
@@ -118,6 +129,20 @@ const MIN_FRAME_TIMEOUT: Duration = Duration::from_millis(10);
 /// Timeout when sending Start protocol messages to the sink
 const EXTERNAL_SEND_TIMEOUT: Duration = Duration::from_millis(200);
 
+/// For how long the Start protocol version of a Session destination is remembered.
+const PEER_START_VERSION_TTL: Duration = Duration::from_secs(3600);
+
+/// Maximum number of Session destinations whose Start protocol version is remembered.
+const PEER_START_VERSION_CAPACITY: u64 = 10_000;
+
+/// Number of negotiated Session initiations in a row that must time out, while their retries without
+/// negotiation are answered, before the destination is remembered not to negotiate.
+const NEGOTIATION_TIMEOUTS_BEFORE_FALLBACK: u32 = 3;
+
+/// Fraction of the initiation timeout given to a negotiated initiation, if it is not known yet
+/// whether the destination negotiates.
+const UNKNOWN_START_VERSION_TIMEOUT_DIVISOR: u32 = 2;
+
 /// How many packets can be buffered if the HoprSession socket is not fast enough.
 #[allow(dead_code)]
 pub const SESSION_FORWARD_CAPACITY: usize = 10000;
@@ -127,9 +152,13 @@ pub const SESSION_FORWARD_CAPACITY: usize = 10000;
 // It also cannot be enclosed in an Arc, since calling `send` consumes the oneshot Sender.
 type SessionInitiationCache = moka::sync::Cache<
     StartChallenge,
-    crossfire::MTx<crossfire::mpsc::One<Result<StartEstablished<SessionId>, StartErrorType>>>,
+    crossfire::MTx<crossfire::mpsc::One<Result<StartEstablished<SessionId, ByteCapabilities>, StartErrorType>>>,
 >;
 
+/// Receives the outcome of a Start protocol exchange identified by a challenge.
+type SessionInitiationRx =
+    crossfire::AsyncRx<crossfire::mpsc::One<Result<StartEstablished<SessionId, ByteCapabilities>, StartErrorType>>>;
+
 /// Handles to streams and tasks spawned by the Session.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, strum::Display)]
 enum SessionHandles {
@@ -139,14 +168,27 @@ enum SessionHandles {
     KeepAlive,
     /// Handle to the process that monitors and balances SURBs.
     Balancer,
+    /// Handle to the Exit service serving the Session.
+    Service,
 }
 
+type SessionPathScheduler = PathScheduler<{ ApplicationData::PAYLOAD_SIZE }>;
+
+type SessionProtocolMessage = hopr_protocol_session::types::SessionMessage<{ ApplicationData::PAYLOAD_SIZE }>;
+
+type SharedSessionService = Arc<dyn SessionService>;
+
 #[derive(Clone)]
 pub(crate) struct SessionSlot {
     // Sender does not need to be in Arc, because the receiver part is always
     // wrapped inside DropAbortable wrapper, with abort handle added to `abort_handles`.
     session_tx: crossfire::MTx<crossfire::mpsc::Array<ApplicationDataIn>>,
-    routing_opts: DestinationRouting,
+    // Replaced when an outgoing Session is resumed over different paths.
+    routing_opts: Arc<parking_lot::RwLock<DestinationRouting>>,
+    // Ticket that allows resuming the Session, if it was requested on initiation.
+    resume_ticket: Option<ResumeTicket>,
+    // Scheduler of an outgoing multipath Session.
+    paths: Option<Arc<parking_lot::Mutex<SessionPathScheduler>>>,
     // Additional tasks spawned by the Session.
     abort_handles: Arc<parking_lot::Mutex<AbortableList<SessionHandles>>>,
     // Allows reconfiguring of the SURB balancer on-the-fly
@@ -155,6 +197,20 @@ pub(crate) struct SessionSlot {
     // SURB flow updates happening outside of Session protocol
     // (e.g., due to Start protocol messages).
     surb_estimator: AtomicSurbFlowEstimator,
+    // Quota of the Session target held by an incoming Session, released when the Session is closed.
+    admission: Arc<parking_lot::Mutex<Option<AdmissionPermit>>>,
+}
+
+/// Outgoing Session agreed with the Exit, either on initiation or on resumption.
+struct EstablishedSession {
+    session_id: SessionId,
+    destination: Address,
+    pseudonym: HoprPseudonym,
+    forward_routing: DestinationRouting,
+    resume_ticket: Option<ResumeTicket>,
+    capabilities: Capabilities,
+    protocol_version: u8,
+    cipher: Option<Arc<SessionCipher>>,
 }
 
 /// RAII guard that rolls back a freshly inserted [`SessionSlot`] unless the
@@ -255,6 +311,12 @@ pub struct SessionManagerConfig {
     #[default(0)]
     pub max_buffered_segments: usize,
 
+    /// Forward error correction of Sessions with the [`Capability::ForwardErrorCorrection`] flag set.
+    ///
+    /// Both the initiator and the recipient of such Session use their own configuration
+    /// for the frames they send.
+    pub fec: FecConfig,
+
     /// The base timeout for initiation of Session initiation.
     ///
     /// The actual timeout is adjusted according to the number of hops for that Session:
@@ -349,6 +411,24 @@ pub struct SessionManagerConfig {
     #[default(10_000)]
     pub maximum_sessions: usize,
 
+    /// Capabilities that incoming Sessions may use.
+    ///
+    /// Optional capabilities outside of this set are left out when an incoming Session is negotiated,
+    /// and Sessions requiring any of them are rejected with [`StartErrorReason::UnsupportedCapability`].
+    /// The [`Capability::Encryption`] flag is also supported only if the
+    /// [packet key](SessionManager::with_packet_key) has been set.
+    ///
+    /// Default is all capabilities.
+    #[default(Capabilities::full())]
+    pub supported_capabilities: Capabilities,
+
+    /// Rate limits and target quotas of incoming Sessions.
+    ///
+    /// Additional rules can be enforced by an [admission policy](SessionManager::with_admission_policy).
+    ///
+    /// Default is no limits.
+    pub admission: SessionAdmissionConfig,
+
     /// How many packets can be buffered if the [`HoprSession`] input socket is not fast enough.
     ///
     /// Controls the capacity of the internal `crossfire` channel used for each session slot.
@@ -370,7 +450,14 @@ type SessionNotifiers = (
 // Sink for processing Start protocol messages.
 // Must be within Arc to be shared across SessionManager clones.
 // The inner OnceLock is set once in `start()` and read in `dispatch_message`.
-type StartProtocolMsgSink = Arc<OnceLock<crossfire::MTx<crossfire::mpsc::Array<(HoprPseudonym, HoprStartProtocol)>>>>;
+// Each message is sent together with the number of SURBs its packet carried.
+type StartProtocolMsgSink =
+    Arc<OnceLock<crossfire::MTx<crossfire::mpsc::Array<(HoprPseudonym, HoprStartProtocol, usize)>>>>;
+
+/// Discards SURBs stored under the given pseudonym, keeping only the given number of the freshest ones.
+///
+/// Returns the number of discarded SURBs.
+type SurbDiscarder = Arc<dyn Fn(&HoprPseudonym, usize) -> usize + Send + Sync>;
 
 /// Manages lifecycles of Sessions.
 ///
@@ -531,7 +618,15 @@ pub struct SessionManager<S> {
     /// and decremented at every removal path (explicit close, eviction, guard rollback).
     active_sessions: Arc<std::sync::atomic::AtomicUsize>,
     sessions: moka::sync::Cache<SessionId, SessionSlot>,
+    /// Start protocol version the destinations of outgoing Sessions are known to understand.
+    peer_start_versions: moka::sync::Cache<Address, u8>,
+    /// Number of negotiated initiations in a row that timed out at destinations of unknown Start protocol version.
+    peer_negotiation_timeouts: moka::sync::Cache<Address, u32>,
     msg_sender: Arc<OnceLock<S>>,
+    handshake: Option<Arc<HandshakeResponder>>,
+    admission: SessionAdmission,
+    services: SessionServiceRegistry,
+    surb_discarder: Option<SurbDiscarder>,
     cfg: SessionManagerConfig,
 }
 
@@ -543,8 +638,14 @@ impl<S> Clone for SessionManager<S> {
             start_protocol_tx: self.start_protocol_tx.clone(),
             active_sessions: self.active_sessions.clone(),
             sessions: self.sessions.clone(),
+            peer_start_versions: self.peer_start_versions.clone(),
+            peer_negotiation_timeouts: self.peer_negotiation_timeouts.clone(),
             cfg: self.cfg.clone(),
             msg_sender: self.msg_sender.clone(),
+            handshake: self.handshake.clone(),
+            admission: self.admission.clone(),
+            services: self.services.clone(),
+            surb_discarder: self.surb_discarder.clone(),
         }
     }
 }
@@ -555,6 +656,8 @@ fn session_config(cfg: &SessionManagerConfig, capabilities: crate::Capabilities)
         frame_mtu: cfg.frame_mtu,
         frame_timeout: cfg.max_frame_timeout,
         max_buffered_segments: cfg.max_buffered_segments,
+        fec: cfg.fec,
+        ..Default::default()
     }
 }
 
@@ -601,6 +704,66 @@ where
     Ok(())
 }
 
+/// Rejects a Session initiation identified by the `challenge` with the given `reason`.
+async fn send_session_error<S>(
+    msg_sender: &mut S,
+    routing: DestinationRouting,
+    challenge: StartChallenge,
+    reason: StartErrorReason,
+) -> crate::errors::Result<()>
+where
+    S: futures::Sink<(DestinationRouting, ApplicationDataOut)> + Unpin,
+    S::Error: std::error::Error + Send + Sync + Clone + 'static,
+{
+    let data = HoprStartProtocol::SessionError(StartErrorType { challenge, reason });
+    send_via_msg_sender(msg_sender, routing, data, "session error message").await?;
+
+    #[cfg(all(feature = "telemetry", not(test)))]
+    METRIC_SENT_SESSION_ERRS.increment(&[&reason.to_string()]);
+
+    Ok(())
+}
+
+/// Leaves out the optional capabilities of the Session, so that it can be initiated without negotiation.
+fn without_negotiation(cfg: SessionClientConfig) -> SessionClientConfig {
+    SessionClientConfig {
+        capabilities: without_capabilities(cfg.capabilities, cfg.optional_capabilities),
+        optional_capabilities: Capabilities::empty(),
+        ..cfg
+    }
+}
+
+/// Indicates whether the destination of an outgoing Session answered its initiation.
+fn peer_answered(result: &crate::errors::Result<HoprSession>) -> bool {
+    matches!(result, Ok(_) | Err(TransportSessionError::Rejected(_)))
+}
+
+/// Seals the Session data of an encrypted Session, leaving the Start protocol messages untouched.
+fn seal_session_data(cipher: Option<&SessionCipher>, mut data: ApplicationDataOut) -> ApplicationDataOut {
+    if let Some(cipher) = cipher.filter(|_| data.data.application_tag == SESSION_APPLICATION_TAG) {
+        data.data.plain_text = cipher.seal(&data.data.plain_text);
+    }
+    data
+}
+
+/// Opens the Session data of an encrypted Session, discarding the data that cannot be authenticated.
+fn open_session_data(
+    session_id: &SessionId,
+    cipher: Option<&SessionCipher>,
+    mut data: ApplicationDataIn,
+) -> Option<ApplicationDataIn> {
+    if let Some(cipher) = cipher {
+        match cipher.open(&data.data.plain_text) {
+            Ok(plain_text) => data.data.plain_text = plain_text,
+            Err(error) => {
+                warn!(%session_id, %error, "discarding session data that cannot be authenticated");
+                return None;
+            }
+        }
+    }
+    Some(data)
+}
+
 impl<S> SessionManager<S>
 where
     S: futures::Sink<(DestinationRouting, ApplicationDataOut)> + Clone + Send + Sync + Unpin + 'static,
@@ -648,13 +811,82 @@ where
                     _ => {}
                 })
                 .build(),
+            peer_start_versions: moka::sync::Cache::builder()
+                .max_capacity(PEER_START_VERSION_CAPACITY)
+                .time_to_live(PEER_START_VERSION_TTL)
+                .build(),
+            peer_negotiation_timeouts: moka::sync::Cache::builder()
+                .max_capacity(PEER_START_VERSION_CAPACITY)
+                .time_to_live(PEER_START_VERSION_TTL)
+                .build(),
             session_notifiers: Arc::new(OnceLock::new()),
             start_protocol_tx: Arc::new(OnceLock::new()),
             active_sessions,
+            handshake: None,
+            admission: SessionAdmission::new(cfg.admission),
+            services: Default::default(),
+            surb_discarder: None,
             cfg,
         }
     }
 
+    /// Sets the packet key of this node, allowing it to accept incoming Sessions
+    /// with the [`Capability::Encryption`] flag set.
+    ///
+    /// Without it, such Sessions are rejected with [`StartErrorReason::HandshakeFailed`].
+    ///
+    /// The key is also used to unseal sealed Session targets, so that they count towards
+    /// the [quotas](SessionAdmissionConfig) of their actual hosts.
+    pub fn with_packet_key(mut self, packet_key: &OffchainKeypair) -> Self {
+        self.handshake = Some(Arc::new(HandshakeResponder::new(packet_key)));
+        self.admission.set_unseal_key(packet_key);
+        self
+    }
+
+    /// Sets the policy deciding whether an incoming Session is admitted.
+    ///
+    /// The policy is consulted after the [rate limits](SessionAdmissionConfig) of initiations
+    /// and before the target quotas.
+    pub fn with_admission_policy(mut self, policy: impl SessionAdmissionPolicy + 'static) -> Self {
+        self.admission.set_policy(Arc::new(policy));
+        self
+    }
+
+    /// Sets the function discarding SURBs stored under a pseudonym, keeping only the given number
+    /// of the freshest ones.
+    ///
+    /// When an incoming Session is [resumed](SessionManager::resume_session), the SURBs received
+    /// before the resumption request were created for the old return path, so they are discarded
+    /// using this function. Without it, they are consumed as usual, which may stall the resumed Session
+    /// until the old SURBs are exhausted.
+    pub fn with_surb_discarder(
+        mut self,
+        discarder: impl Fn(&HoprPseudonym, usize) -> usize + Send + Sync + 'static,
+    ) -> Self {
+        self.surb_discarder = Some(Arc::new(discarder));
+        self
+    }
+
+    /// Registers the `service` serving incoming Sessions targeting the [Exit service](SessionTarget::ExitNode)
+    /// with the given `id`.
+    ///
+    /// Such Sessions are not passed to the incoming Session notifier given to [`SessionManager::start`].
+    /// Fails if another service has already been registered with the same `id`.
+    pub fn register_service(
+        &self,
+        id: crate::ServiceId,
+        service: impl SessionService + 'static,
+    ) -> crate::errors::Result<()> {
+        Ok(self.services.register(id, Arc::new(service))?)
+    }
+
+    /// Unregisters the service with the given `id`, returning `true` if it was registered.
+    ///
+    /// Sessions already being served by the service are not affected.
+    pub fn unregister_service(&self, id: crate::ServiceId) -> bool {
+        self.services.unregister(id)
+    }
+
     /// Starts the instance with the given `msg_sender` `Sink`
     /// and a channel `new_session_notifier` used to notify when a new incoming session is opened to us.
     ///
@@ -751,7 +983,7 @@ where
             "session_start_protocol_processor",
             start_protocol_rx.into_stream().for_each_concurrent(
                 Some(self.cfg.maximum_sessions + 10),
-                move |(pseudonym, protocol_msg)| {
+                move |(pseudonym, protocol_msg, num_saved_surbs)| {
                     let myself = myself.clone();
                     async move {
                         let result = match protocol_msg {
@@ -763,6 +995,9 @@ where
                                 myself.handle_session_error(error_type).await
                             }
                             HoprStartProtocol::KeepAlive(msg) => myself.handle_keep_alive(msg).await,
+                            HoprStartProtocol::ResumeSession(req) => {
+                                myself.handle_session_resumption(pseudonym, req, num_saved_surbs).await
+                            }
                         };
 
                         if let Err(error) = result {
@@ -842,6 +1077,32 @@ where
         }
     }
 
+    /// Allocates a free challenge for a new Start protocol exchange.
+    ///
+    /// Returns the challenge and the receiver of the counterparty's response to it.
+    fn allocate_challenge(&self) -> crate::errors::Result<(StartChallenge, SessionInitiationRx)> {
+        let (tx_initiation_done, rx_initiation_done): (
+            crossfire::MTx<crossfire::mpsc::One<_>>,
+            crossfire::AsyncRx<crossfire::mpsc::One<_>>,
+        ) = crossfire::mpsc::build(crossfire::mpsc::One::new());
+
+        let (challenge, _) = insert_into_next_slot(
+            &self.session_initiations,
+            |ch| {
+                if let Some(challenge) = ch {
+                    ((challenge + 1) % hopr_api::types::crypto_random::MAX_RANDOM_INTEGER).max(MIN_CHALLENGE)
+                } else {
+                    hopr_api::types::crypto_random::random_integer(MIN_CHALLENGE, None)
+                }
+            },
+            |_| tx_initiation_done,
+            Some(self.cfg.maximum_sessions as u64),
+        )
+        .ok_or(SessionManagerError::NoChallengeSlots)?; // almost impossible with u64
+
+        Ok((challenge, rx_initiation_done))
+    }
+
     /// Initiates a new outgoing Session to `destination` with the given configuration.
     ///
     /// If the Session's counterparty does not respond within
@@ -849,11 +1110,93 @@ where
     /// this method returns [`TransportSessionError::Timeout`].
     ///
     /// It will also fail if the instance has not been [started](SessionManager::start).
+    ///
+    /// If the configuration has [optional capabilities](SessionClientConfig::optional_capabilities),
+    /// the Session uses only those agreed by the counterparty.
+    /// Sessions using [`Capability::Segmentation`] also negotiate the version of the Session protocol.
+    ///
+    /// Counterparties on older versions of the Start protocol cannot negotiate and do not answer such
+    /// initiation. The version of the Start protocol understood by the counterparty is therefore
+    /// remembered from its answers: counterparties known to negotiate are never initiated without
+    /// negotiation, and those known not to negotiate are initiated with only the required capabilities
+    /// and the oldest Session protocol version right away.
+    /// If the version of the counterparty is not known yet, the negotiated initiation times out sooner
+    /// and is then retried once without negotiation. As the negotiated initiation may have been just lost,
+    /// the counterparty is remembered not to negotiate only after this happened several times in a row.
     pub async fn new_session(
         &self,
         destination: Address,
         target: SessionTarget,
         cfg: SessionClientConfig,
+    ) -> crate::errors::Result<HoprSession> {
+        let timeout_base = self.cfg.initiation_timeout_base;
+        if cfg.optional_capabilities.is_empty() && !cfg.capabilities.contains(Capability::Segmentation) {
+            return self
+                .initiate_session(destination, target, cfg, false, timeout_base)
+                .await;
+        }
+
+        let negotiating_version = HoprStartProtocol::START_PROTOCOL_VERSION;
+        match self.peer_start_versions.get(&destination) {
+            Some(version) if version >= negotiating_version => {
+                self.initiate_session(destination, target, cfg, true, timeout_base)
+                    .await
+            }
+            Some(version) => {
+                debug!(%destination, version, "destination does not negotiate, initiating session without negotiation");
+                self.initiate_session(destination, target, without_negotiation(cfg), false, timeout_base)
+                    .await
+            }
+            None => {
+                let result = self
+                    .initiate_session(
+                        destination,
+                        target.clone(),
+                        cfg.clone(),
+                        true,
+                        timeout_base / UNKNOWN_START_VERSION_TIMEOUT_DIVISOR,
+                    )
+                    .await;
+                if peer_answered(&result) {
+                    self.peer_start_versions.insert(destination, negotiating_version);
+                    self.peer_negotiation_timeouts.invalidate(&destination);
+                }
+                if !matches!(result, Err(TransportSessionError::Timeout)) {
+                    return result;
+                }
+
+                warn!(%destination, "negotiated session initiation timed out, retrying without negotiation");
+                let result = self
+                    .initiate_session(destination, target, without_negotiation(cfg), false, timeout_base)
+                    .await;
+                if peer_answered(&result) {
+                    let timeouts = self.peer_negotiation_timeouts.get(&destination).unwrap_or_default() + 1;
+                    if timeouts >= NEGOTIATION_TIMEOUTS_BEFORE_FALLBACK {
+                        info!(%destination, "destination does not negotiate sessions");
+                        self.peer_start_versions
+                            .insert(destination, HoprStartProtocol::MIN_START_PROTOCOL_VERSION);
+                        self.peer_negotiation_timeouts.invalidate(&destination);
+                    } else {
+                        debug!(%destination, timeouts, "destination answered only without negotiation");
+                        self.peer_negotiation_timeouts.insert(destination, timeouts);
+                    }
+                }
+                result
+            }
+        }
+    }
+
+    /// Initiates a new outgoing Session, offering the highest supported Session protocol version
+    /// if `negotiate_version` is set.
+    ///
+    /// The initiation times out after the `timeout_base` per hop of the round trip.
+    async fn initiate_session(
+        &self,
+        destination: Address,
+        target: SessionTarget,
+        cfg: SessionClientConfig,
+        negotiate_version: bool,
+        timeout_base: Duration,
     ) -> crate::errors::Result<HoprSession> {
         self.sessions.run_pending_tasks();
         if self.cfg.maximum_sessions <= self.active_sessions.load(Ordering::Relaxed) {
@@ -862,31 +1205,27 @@ where
 
         let mut msg_sender = self.msg_sender.get().cloned().ok_or(SessionManagerError::NotStarted)?;
 
-        let (tx_initiation_done, rx_initiation_done): (
-            crossfire::MTx<crossfire::mpsc::One<_>>,
-            crossfire::AsyncRx<crossfire::mpsc::One<_>>,
-        ) = crossfire::mpsc::build(crossfire::mpsc::One::new());
+        let destination_key = if cfg.capabilities.contains(Capability::Encryption) {
+            // Encryption negotiated away could not be told apart from a downgrade by an attacker
+            if cfg.optional_capabilities.contains(Capability::Encryption) {
+                return Err(SessionManagerError::OptionalEncryption.into());
+            }
+            Some(cfg.destination_key.ok_or(SessionManagerError::MissingDestinationKey)?)
+        } else {
+            None
+        };
 
-        let (challenge, _) = insert_into_next_slot(
-            &self.session_initiations,
-            |ch| {
-                if let Some(challenge) = ch {
-                    ((challenge + 1) % hopr_api::types::crypto_random::MAX_RANDOM_INTEGER).max(MIN_CHALLENGE)
-                } else {
-                    hopr_api::types::crypto_random::random_integer(MIN_CHALLENGE, None)
-                }
-            },
-            |_| tx_initiation_done,
-            Some(self.cfg.maximum_sessions as u64),
-        )
-        .ok_or(SessionManagerError::NoChallengeSlots)?; // almost impossible with u64
+        let (challenge, rx_initiation_done) = self.allocate_challenge()?;
 
         // Prepare the session initiation message in the Start protocol
         trace!(challenge, ?cfg, "initiating session with config");
-        let start_session_msg = HoprStartProtocol::StartSession(StartInitiation {
+        let mut start_session_msg = StartInitiation {
             challenge,
             target,
             capabilities: ByteCapabilities(cfg.capabilities),
+            optional_capabilities: (!cfg.optional_capabilities.is_empty())
+                .then_some(ByteCapabilities(cfg.capabilities & cfg.optional_capabilities)),
+            session_protocol_version: negotiate_version.then_some(SESSION_PROTOCOL_VERSION),
             additional_data: if !cfg.capabilities.contains(Capability::NoRateControl) {
                 cfg.surb_management
                     .map(|c| c.target_surb_buffer_size)
@@ -902,7 +1241,22 @@ where
             } else {
                 0
             },
-        });
+            key_share: None,
+        };
+
+        // The key exchange is bound to the negotiation offered in the initiation
+        let handshake = destination_key
+            .map(|key| HandshakeInitiator::new(&key, &start_session_msg))
+            .transpose()
+            .map_err(|error| {
+                self.session_initiations.remove(&challenge);
+                TransportSessionError::from(error)
+            })?
+            .map(|(handshake, key_share)| {
+                start_session_msg.key_share = Some(key_share);
+                handshake
+            });
+        let start_session_msg = HoprStartProtocol::StartSession(start_session_msg);
 
         let pseudonym = cfg.pseudonym.unwrap_or(HoprPseudonym::random());
         let forward_routing = DestinationRouting::Forward {
@@ -928,7 +1282,7 @@ where
 
         // The timeout is given by the number of hops requested
         let initiation_timeout: futures_time::time::Duration = initiation_timeout_max_one_way(
-            self.cfg.initiation_timeout_base,
+            timeout_base,
             cfg.forward_path_options.count_hops() + cfg.return_path_options.count_hops() + 2,
         )
         .into();
@@ -945,251 +1299,65 @@ where
             Ok(Ok(Some(est))) => {
                 // Session has been established, construct it
                 let session_id = est.session_id;
+                let resume_ticket = est.resume_ticket;
                 debug!(challenge = est.orig_challenge, ?session_id, "started a new session");
 
-                let (session_tx, session_rx) =
-                    crossfire::mpsc::bounded_blocking_async::<ApplicationDataIn>(self.cfg.session_forward_capacity);
-                let (session_rx, session_rx_ah) = hopr_utils::runtime::DropAbortable::new(session_rx.into_stream());
-
-                let mut abort_handles = AbortableList::default();
-                abort_handles.insert(SessionHandles::Ingress, session_rx_ah);
-
-                let notifier = self
-                    .session_notifiers
-                    .get()
-                    .map(|(_, notifier)| {
-                        let notifier = notifier.clone();
-                        Box::new(move |session_id: SessionId, reason: ClosureReason| {
-                            let _ = notifier
-                                .try_send((session_id, reason))
-                                .inspect_err(|error| error!(%session_id, %error, "failed to notify session closure"));
-                        })
-                    })
-                    .ok_or(SessionManagerError::NotStarted)?;
-
-                // NOTE: the Exit node can have different `max_surb_buffer_size`
-                // setting on the Session manager, so it does not make sense to cap it here
-                // with our maximum value.
-                if let Some(balancer_config) = cfg.surb_management {
-                    let surb_estimator = AtomicSurbFlowEstimator::default();
-
-                    // Sender responsible for keep-alive and Session data will be counting produced SURBs
-                    let surb_estimator_clone = surb_estimator.clone();
-                    let full_surb_scoring_sender =
-                        msg_sender.with(move |(routing, data): (DestinationRouting, ApplicationDataOut)| {
-                            let produced = data.estimate_surbs_with_msg() as u64;
-                            // Count how many SURBs we sent with each packet
-                            surb_estimator_clone
-                                .produced
-                                .fetch_add(produced, std::sync::atomic::Ordering::Relaxed);
-                            #[cfg(feature = "telemetry")]
-                            crate::telemetry::record_session_surb_produced(&session_id, produced);
-                            futures::future::ok::<_, S::Error>((routing, data))
-                        });
-
-                    // For standard Session data we first reduce the number of SURBs we want to produce,
-                    // unless requested to always max them out
-                    let max_out_organic_surbs = cfg.always_max_out_surbs;
-                    let reduced_surb_scoring_sender = full_surb_scoring_sender.clone().with(
-                        // NOTE: this is put in-front of the `full_surb_scoring_sender`,
-                        // so that its estimate of SURBs gets automatically updated based on
-                        // the `max_surbs_in_packets` set here.
-                        move |(routing, mut data): (DestinationRouting, ApplicationDataOut)| {
-                            if !max_out_organic_surbs {
-                                // TODO: make this dynamic to honor the balancer target (#7439)
-                                data.packet_info
-                                    .get_or_insert_with(|| OutgoingPacketInfo {
-                                        max_surbs_in_packet: 1,
-                                        ..Default::default()
-                                    })
-                                    .max_surbs_in_packet = 1;
-                            }
-                            futures::future::ok::<_, S::Error>((routing, data))
-                        },
-                    );
-
-                    let surb_mgmt = Arc::new(BalancerStateValues::from(balancer_config));
-
-                    // Spawn the SURB-bearing keep alive stream towards the Exit
-                    let (ka_controller, ka_abort_handle) = utils::spawn_keep_alive_stream(
-                        session_id,
-                        full_surb_scoring_sender,
-                        forward_routing.clone(),
-                        if self.cfg.surb_target_notify {
-                            SurbNotificationMode::Target
-                        } else {
-                            SurbNotificationMode::DoNotNotify
-                        },
-                        surb_mgmt.clone(),
-                    );
-                    abort_handles.insert(SessionHandles::KeepAlive, ka_abort_handle);
-
-                    // Spawn the SURB balancer, which will decide on the initial SURB rate.
-                    debug!(%session_id, ?balancer_config ,"spawning entry SURB balancer");
-                    let balancer = SurbBalancer::new(
-                        session_id,
-                        // The setpoint and output limit is immediately reconfigured by the SurbBalancer
-                        PidBalancerController::from_gains(PidControllerGains::from_env_or_default()),
-                        surb_estimator.clone(),
-                        // Currently, a keep-alive message can bear `HoprPacket::MAX_SURBS_IN_PACKET` SURBs,
-                        // so the correction by this factor is applied.
-                        SurbControllerWithCorrection(ka_controller, HoprPacket::MAX_SURBS_IN_PACKET as u32),
-                        surb_mgmt.clone(),
-                    );
-
-                    let (level_stream, balancer_abort_handle) =
-                        balancer.start_control_loop(self.cfg.balancer_sampling_interval);
-                    abort_handles.insert(SessionHandles::Balancer, balancer_abort_handle);
-
-                    // Insert the slot before the SURB readiness wait so any echo packets that
-                    // arrive during pre-loading are accepted rather than dropped as unknown.
-                    // Early return from the wait below drops slot_guard uncommitted, which removes
-                    // the slot and calls close_session → abort_all() on the spawned tasks.
-                    let mut slot_guard = self
-                        .allocate_session_slot(
-                            session_id,
-                            SessionSlot {
-                                session_tx,
-                                routing_opts: forward_routing.clone(),
-                                abort_handles: Arc::new(parking_lot::Mutex::new(abort_handles)),
-                                surb_mgmt: surb_mgmt.clone(),
-                                surb_estimator: surb_estimator.clone(),
-                            },
-                        )
-                        .ok_or_else(|| {
-                            // Session already exists; it means it is most likely a loopback attempt
-                            error!(%session_id, "session already exists - loopback attempt");
-                            SessionManagerError::Loopback
-                        })?;
-
-                    // Prevent the slot from being evicted by time_to_idle while SURBs are
-                    // pre-loading. Each `get()` call resets the idle timer; the task is
-                    // aborted as soon as the readiness wait resolves.
-                    let sessions_keepalive = self.sessions.clone();
-                    let touch_period = (self.cfg.idle_timeout / 2).max(self.cfg.min_session_touch_period);
-                    let slot_keepalive = hopr_utils::runtime::prelude::spawn(async move {
-                        loop {
-                            hopr_utils::runtime::prelude::sleep(touch_period).await;
-                            let _ = sessions_keepalive.get(&session_id);
-                        }
-                    });
-
-                    // TODO: consider making this interactive = other party reports the exact level periodically
-                    let wait_result = level_stream
-                        .skip_while(|current_level| {
-                            futures::future::ready(*current_level < balancer_config.target_surb_buffer_size / 2)
-                        })
-                        .next()
-                        .timeout(futures_time::time::Duration::from(SESSION_READINESS_TIMEOUT))
-                        .await;
-                    slot_keepalive.abort();
-                    match wait_result {
-                        Ok(Some(surb_level)) => {
-                            info!(%session_id, surb_level, "session is ready");
-                        }
-                        Ok(None) => {
-                            return Err(
-                                SessionManagerError::other(anyhow!("surb balancer was cancelled prematurely")).into(),
-                            );
-                        }
-                        Err(_) => {
-                            warn!(%session_id, "session didn't reach target SURB buffer size in time");
-                        }
+                // If a key exchange was requested, the Exit must have confirmed it, which also authenticates
+                // the capabilities and the Session protocol version it agreed to
+                let cipher = handshake
+                    .map(|handshake| handshake.finish(&est))
+                    .transpose()
+                    .inspect_err(|error| error!(%session_id, %error, "session key exchange failed"))?
+                    .map(|keys| Arc::new(SessionCipher::new(keys)));
+
+                // The Exit may leave out only the capabilities that were optional
+                let capabilities = match est.capabilities {
+                    Some(ByteCapabilities(agreed))
+                        if cfg.capabilities.contains(agreed)
+                            && agreed.contains(without_capabilities(cfg.capabilities, cfg.optional_capabilities)) =>
+                    {
+                        agreed
                     }
+                    Some(ByteCapabilities(agreed)) => {
+                        error!(%session_id, ?agreed, requested = ?cfg.capabilities, "session capabilities mismatch");
+                        return Err(SessionManagerError::CapabilityMismatch.into());
+                    }
+                    None => cfg.capabilities,
+                };
+                if capabilities != cfg.capabilities {
+                    info!(%session_id, ?capabilities, requested = ?cfg.capabilities, "session capabilities reduced");
+                }
 
-                    #[cfg(all(feature = "telemetry", not(test)))]
-                    METRIC_NUM_INITIATED_SESSIONS.increment();
-
-                    let surb_estimator_for_rx = surb_estimator.clone();
-                    let session = HoprSession::new_with_surb_state(
-                        session_id,
-                        forward_routing,
-                        session_config(&self.cfg, cfg.capabilities),
-                        (
-                            reduced_surb_scoring_sender,
-                            session_rx.inspect(move |_| {
-                                // Received packets = SURB consumption estimate
-                                // The received packets always consume a single SURB.
-                                surb_estimator_for_rx
-                                    .consumed
-                                    .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-                                #[cfg(feature = "telemetry")]
-                                crate::telemetry::record_session_surb_consumed(&session_id, 1);
-                            }),
-                        ),
-                        Some(notifier),
-                        // Entry (sending) side: give flow control the SURB balancer state as its
-                        // anti-grief down-only ceiling, and the client's opt-in flow-control config.
-                        Some(surb_mgmt.clone()),
-                        cfg.flow_control,
-                    )?;
-
-                    #[cfg(feature = "telemetry")]
-                    initialize_session_telemetry(
-                        session_id,
-                        &self.cfg,
-                        cfg.capabilities,
-                        Some(&surb_estimator),
-                        Some(&surb_mgmt),
-                    );
-
-                    slot_guard.commit();
-                    Ok(session)
-                } else {
-                    warn!(%session_id, "session ready without SURB balancing");
-
-                    // Insert the slot and obtain a guard that rolls it back if any
-                    // subsequent setup step fails.
-                    let mut slot_guard = self
-                        .allocate_session_slot(
-                            session_id,
-                            SessionSlot {
-                                session_tx,
-                                routing_opts: forward_routing.clone(),
-                                abort_handles: Arc::new(parking_lot::Mutex::new(abort_handles)),
-                                surb_mgmt: Default::default(), // Disabled SURB management
-                                surb_estimator: Default::default(), // No SURB estimator needed
-                            },
-                        )
-                        .ok_or_else(|| {
-                            // Session already exists; it means it is most likely a loopback attempt
-                            error!(%session_id, "session already exists - loopback attempt");
-                            SessionManagerError::Loopback
-                        })?;
-
-                    #[cfg(all(feature = "telemetry", not(test)))]
-                    METRIC_NUM_INITIATED_SESSIONS.increment();
-
-                    // For standard Session data we first reduce the number of SURBs we want to produce,
-                    // unless requested to always max them out
-                    let max_out_organic_surbs = cfg.always_max_out_surbs;
-                    let reduced_surb_sender =
-                        msg_sender.with(move |(routing, mut data): (DestinationRouting, ApplicationDataOut)| {
-                            if !max_out_organic_surbs {
-                                data.packet_info
-                                    .get_or_insert_with(|| OutgoingPacketInfo {
-                                        max_surbs_in_packet: 1,
-                                        ..Default::default()
-                                    })
-                                    .max_surbs_in_packet = 1;
-                            }
-                            futures::future::ok::<_, S::Error>((routing, data))
-                        });
-
-                    let session = HoprSession::new(
+                // The Exit may agree only to a Session protocol version that was offered
+                let protocol_version = match est.session_protocol_version {
+                    Some(version)
+                        if negotiate_version
+                            && (MIN_SESSION_PROTOCOL_VERSION..=SESSION_PROTOCOL_VERSION).contains(&version) =>
+                    {
+                        version
+                    }
+                    Some(version) => {
+                        error!(%session_id, version, "session protocol version mismatch");
+                        return Err(SessionManagerError::ProtocolVersionMismatch.into());
+                    }
+                    None => MIN_SESSION_PROTOCOL_VERSION,
+                };
+                debug!(%session_id, protocol_version, "session protocol version agreed");
+                self.open_outgoing_session(
+                    EstablishedSession {
                         session_id,
+                        destination,
+                        pseudonym,
                         forward_routing,
-                        session_config(&self.cfg, cfg.capabilities),
-                        (reduced_surb_sender, session_rx),
-                        Some(notifier),
-                    )?;
-
-                    #[cfg(feature = "telemetry")]
-                    initialize_session_telemetry(session_id, &self.cfg, cfg.capabilities, None, None);
-
-                    slot_guard.commit();
-                    Ok(session)
-                }
+                        resume_ticket,
+                        capabilities,
+                        protocol_version,
+                        cipher,
+                    },
+                    cfg,
+                    msg_sender,
+                )
+                .await
             }
             Ok(Ok(None)) => {
                 self.session_initiations.remove(&challenge);
@@ -1220,814 +1388,3042 @@ where
         }
     }
 
-    /// Sends a keep-alive packet with the given [`SessionId`].
-    ///
-    /// This currently "fires & forgets" and does not expect nor await any "pong" response.
-    pub async fn ping_session(&self, id: &SessionId) -> crate::errors::Result<()> {
-        if let Some(session_data) = self.sessions.get(id) {
-            trace!(session_id = ?id, "pinging manually session");
-            let mut msg_sender = self.msg_sender.get().cloned().ok_or(SessionManagerError::NotStarted)?;
-            send_via_msg_sender(
-                &mut msg_sender,
-                session_data.routing_opts.clone(),
-                HoprStartProtocol::KeepAlive((*id).into()),
-                "session ping message",
-            )
-            .await
-            .map_err(TransportSessionError::packet_sending)
-        } else {
-            Err(SessionManagerError::NonExistingSession.into())
-        }
-    }
+    /// Opens the [`HoprSession`] object of an outgoing Session agreed with the Exit.
+    async fn open_outgoing_session(
+        &self,
+        established: EstablishedSession,
+        cfg: SessionClientConfig,
+        msg_sender: S,
+    ) -> crate::errors::Result<HoprSession> {
+        let EstablishedSession {
+            session_id,
+            destination,
+            pseudonym,
+            forward_routing,
+            resume_ticket,
+            capabilities,
+            protocol_version,
+            cipher,
+        } = established;
+
+        let hopr_session_cfg = HoprSessionConfig {
+            protocol_version,
+            ..session_config(&self.cfg, capabilities)
+        };
 
-    /// Returns [`SessionIds`](SessionId) of all currently active sessions.
-    pub fn active_sessions(&self) -> Vec<SessionId> {
-        self.sessions.run_pending_tasks();
-        self.sessions.iter().map(|(k, _)| *k).collect()
-    }
+        // Session data is sealed right before it is sent, after the path scheduling
+        let outgoing_cipher = cipher.clone();
+        let msg_sender = msg_sender.with(move |(routing, data): (DestinationRouting, ApplicationDataOut)| {
+            futures::future::ok::<_, S::Error>((routing, seal_session_data(outgoing_cipher.as_deref(), data)))
+        });
 
-    /// Explicitly closes the session with the given `id`.
-    ///
-    /// Removes the entry from the internal session cache, closes the data channel,
-    /// and aborts any auxiliary tasks. Returns `true` if a session was found and
-    /// closed, `false` otherwise.
-    ///
-    /// This avoids waiting for the idle timeout (`time_to_idle`) or the LRU
-    /// capacity bound to evict the entry, which is the desired behaviour when
-    /// the caller (e.g. REST `DELETE /session`) knows the session is finished.
-    pub fn close_session(&self, id: &SessionId) -> bool {
-        if let Some(slot) = self.sessions.remove(id) {
-            self.active_sessions.fetch_sub(1, Ordering::Relaxed);
-            close_session(*id, slot, ClosureReason::Eviction);
-            true
-        } else {
-            false
-        }
-    }
+        // All Session messages follow the current routing, which changes when the Session is resumed
+        let routing_opts = Arc::new(parking_lot::RwLock::new(forward_routing.clone()));
+
+        // A multipath Session schedules each message over one of its paths instead
+        let paths = cfg
+            .multipath
+            .as_ref()
+            .filter(|multipath| !multipath.additional_paths.is_empty())
+            .map(|multipath| {
+                let additional_routings = multipath
+                    .additional_paths
+                    .iter()
+                    .map(|path| DestinationRouting::Forward {
+                        destination: Box::new(destination.into()),
+                        pseudonym: Some(pseudonym),
+                        forward_options: path.forward_path_options.clone(),
+                        return_options: path.return_path_options.clone().into(),
+                    });
+                debug!(%session_id, num_paths = multipath.additional_paths.len() + 1, "multipath session");
+                Arc::new(parking_lot::Mutex::new(SessionPathScheduler::new(
+                    forward_routing.clone(),
+                    additional_routings,
+                    multipath,
+                    capabilities,
+                )))
+            });
 
-    /// Updates the configuration of the SURB balancer on the given [`SessionId`].
-    ///
-    /// Returns an error if the Session with the given `id` does not exist, or
-    /// if it does not use SURB balancing.
-    pub fn update_surb_balancer_config(&self, id: &SessionId, config: SurbBalancerConfig) -> crate::errors::Result<()> {
-        let cfg = self
-            .sessions
-            .get(id)
-            .ok_or(SessionManagerError::NonExistingSession)?
-            .surb_mgmt;
+        let current_routing = routing_opts.clone();
+        let outgoing_paths = paths.clone();
+        let msg_sender = msg_sender.with(move |(_, data): (DestinationRouting, ApplicationDataOut)| {
+            let routing = match &outgoing_paths {
+                Some(paths) if data.data.application_tag == SESSION_APPLICATION_TAG => {
+                    // Only the segment IDs are read from the headers, the messages are not decoded
+                    let segments = SessionProtocolMessage::segment_ids(&data.data.plain_text);
+                    paths.lock().route_outgoing(segments, std::time::Instant::now())
+                }
+                Some(paths) => paths.lock().route_control(),
+                None => current_routing.read().clone(),
+            };
+            futures::future::ok::<_, S::Error>((routing, data))
+        });
 
-        // Only update the config if there already was one before
-        if !cfg.is_disabled() {
-            cfg.update(&config);
-            Ok(())
-        } else {
-            Err(SessionManagerError::other(anyhow!("session does not use SURB balancing")).into())
-        }
-    }
+        let (session_tx, session_rx) =
+            crossfire::mpsc::bounded_blocking_async::<ApplicationDataIn>(self.cfg.session_forward_capacity);
+        let (session_rx, session_rx_ah) = hopr_utils::runtime::DropAbortable::new(session_rx.into_stream());
 
-    /// Retrieves the configuration of SURB balancing for the given Session.
-    ///
-    /// Returns an error if the Session with the given `id` does not exist.
-    pub fn get_surb_balancer_config(&self, id: &SessionId) -> crate::errors::Result<Option<SurbBalancerConfig>> {
-        match self.sessions.get(id) {
-            Some(session) => Ok(Some(session.surb_mgmt.as_ref())
-                .filter(|c| !c.is_disabled())
-                .map(|d| d.as_config())),
-            None => Err(SessionManagerError::NonExistingSession.into()),
-        }
-    }
+        // Feedback of the Exit drives the path scheduling of a multipath Session
+        let incoming_paths = paths.clone();
+        let session_rx = session_rx
+            .filter_map(move |data| futures::future::ready(open_session_data(&session_id, cipher.as_deref(), data)))
+            .inspect(move |data| {
+                if let Some(paths) = &incoming_paths {
+                    paths
+                        .lock()
+                        .on_incoming(&data.data.plain_text, std::time::Instant::now());
+                }
+            });
 
-    /// Gets estimations produced/received and consumed SURBs by the Session.
-    ///
-    /// For an outgoing Session (Entry) the pair is the number of SURBs sent (by us) and used (by the Exit).
-    /// For an incoming Session (Exit) the pair is the number of SURBs received (from Entry) and used (by us).
-    ///
-    /// Returns an error if the Session with the given `id` does not exist.
-    pub fn get_surb_level_estimates(&self, id: &SessionId) -> crate::errors::Result<(u64, u64)> {
-        match self.sessions.get(id) {
-            Some(session) => Ok((
-                session
-                    .surb_estimator
-                    .produced
-                    .load(std::sync::atomic::Ordering::Relaxed),
-                session
-                    .surb_estimator
-                    .consumed
-                    .load(std::sync::atomic::Ordering::Relaxed),
-            )),
-            None => Err(SessionManagerError::NonExistingSession.into()),
-        }
-    }
+        let mut abort_handles = AbortableList::default();
+        abort_handles.insert(SessionHandles::Ingress, session_rx_ah);
 
-    /// The main method to be called whenever data are received.
-    ///
-    /// It tries to recognize the message and correctly dispatches either
-    /// the Session protocol or Start protocol messages.
-    ///
-    /// If the data are not recognized, they are returned as [`DispatchResult::Unrelated`].
-    pub fn dispatch_message(
-        &self,
-        pseudonym: HoprPseudonym,
-        in_data: ApplicationDataIn,
-    ) -> crate::errors::Result<DispatchResult> {
-        if in_data.data.application_tag == HoprStartProtocol::START_PROTOCOL_MESSAGE_TAG {
-            // This is a Start protocol message, so we send it to the handler
-            trace!("dispatching Start protocol message");
-            if let Some(start_protocol_tx) = self.start_protocol_tx.get() {
-                start_protocol_tx
-                    .try_send((pseudonym, HoprStartProtocol::try_from(in_data.data)?))
-                    .map_err(|error| {
-                        error!(%error, "failed to send Start protocol message to processing task");
-                        SessionManagerError::other(error)
-                    })?;
-            } else {
-                return Err(SessionManagerError::NotStarted.into());
+        let notifier = self
+            .session_notifiers
+            .get()
+            .map(|(_, notifier)| {
+                let notifier = notifier.clone();
+                Box::new(move |session_id: SessionId, reason: ClosureReason| {
+                    let _ = notifier
+                        .try_send((session_id, reason))
+                        .inspect_err(|error| error!(%session_id, %error, "failed to notify session closure"));
+                })
+            })
+            .ok_or(SessionManagerError::NotStarted)?;
+
+        // NOTE: the Exit node can have different `max_surb_buffer_size`
+        // setting on the Session manager, so it does not make sense to cap it here
+        // with our maximum value.
+        if let Some(balancer_config) = cfg.surb_management {
+            let surb_estimator = AtomicSurbFlowEstimator::default();
+
+            // Sender responsible for keep-alive and Session data will be counting produced SURBs
+            let surb_estimator_clone = surb_estimator.clone();
+            let full_surb_scoring_sender =
+                msg_sender.with(move |(routing, data): (DestinationRouting, ApplicationDataOut)| {
+                    let produced = data.estimate_surbs_with_msg() as u64;
+                    // Count how many SURBs we sent with each packet
+                    surb_estimator_clone
+                        .produced
+                        .fetch_add(produced, std::sync::atomic::Ordering::Relaxed);
+                    #[cfg(feature = "telemetry")]
+                    crate::telemetry::record_session_surb_produced(&session_id, produced);
+                    futures::future::ok::<_, S::Error>((routing, data))
+                });
+
+            // For standard Session data we first reduce the number of SURBs we want to produce,
+            // unless requested to always max them out
+            let max_out_organic_surbs = cfg.always_max_out_surbs;
+            let reduced_surb_scoring_sender = full_surb_scoring_sender.clone().with(
+                // NOTE: this is put in-front of the `full_surb_scoring_sender`,
+                // so that its estimate of SURBs gets automatically updated based on
+                // the `max_surbs_in_packets` set here.
+                move |(routing, mut data): (DestinationRouting, ApplicationDataOut)| {
+                    if !max_out_organic_surbs {
+                        // TODO: make this dynamic to honor the balancer target (#7439)
+                        data.packet_info
+                            .get_or_insert_with(|| OutgoingPacketInfo {
+                                max_surbs_in_packet: 1,
+                                ..Default::default()
+                            })
+                            .max_surbs_in_packet = 1;
+                    }
+                    futures::future::ok::<_, S::Error>((routing, data))
+                },
+            );
+
+            let surb_mgmt = Arc::new(BalancerStateValues::from(balancer_config));
+
+            // Spawn the SURB-bearing keep alive stream towards the Exit
+            let (ka_controller, ka_abort_handle) = utils::spawn_keep_alive_stream(
+                session_id,
+                full_surb_scoring_sender,
+                forward_routing.clone(),
+                if self.cfg.surb_target_notify {
+                    SurbNotificationMode::Target
+                } else {
+                    SurbNotificationMode::DoNotNotify
+                },
+                surb_mgmt.clone(),
+            );
+            abort_handles.insert(SessionHandles::KeepAlive, ka_abort_handle);
+
+            // Spawn the SURB balancer, which will decide on the initial SURB rate.
+            debug!(%session_id, ?balancer_config ,"spawning entry SURB balancer");
+            let balancer = SurbBalancer::new(
+                session_id,
+                // The setpoint and output limit is immediately reconfigured by the SurbBalancer
+                balancer_config
+                    .controller
+                    .new_controller(self.cfg.balancer_sampling_interval),
+                surb_estimator.clone(),
+                // Currently, a keep-alive message can bear `HoprPacket::MAX_SURBS_IN_PACKET` SURBs,
+                // so the correction by this factor is applied.
+                SurbControllerWithCorrection(ka_controller, HoprPacket::MAX_SURBS_IN_PACKET as u32),
+                surb_mgmt.clone(),
+            );
+
+            let (level_stream, balancer_abort_handle) =
+                balancer.start_control_loop(self.cfg.balancer_sampling_interval);
+            abort_handles.insert(SessionHandles::Balancer, balancer_abort_handle);
+
+            // Insert the slot before the SURB readiness wait so any echo packets that
+            // arrive during pre-loading are accepted rather than dropped as unknown.
+            // Early return from the wait below drops slot_guard uncommitted, which removes
+            // the slot and calls close_session → abort_all() on the spawned tasks.
+            let mut slot_guard = self
+                .allocate_session_slot(
+                    session_id,
+                    SessionSlot {
+                        session_tx,
+                        routing_opts,
+                        resume_ticket,
+                        paths,
+                        abort_handles: Arc::new(parking_lot::Mutex::new(abort_handles)),
+                        surb_mgmt: surb_mgmt.clone(),
+                        surb_estimator: surb_estimator.clone(),
+                        admission: Default::default(),
+                    },
+                )
+                .ok_or_else(|| {
+                    // Session already exists; it means it is most likely a loopback attempt
+                    error!(%session_id, "session already exists - loopback attempt");
+                    SessionManagerError::Loopback
+                })?;
+
+            // Prevent the slot from being evicted by time_to_idle while SURBs are
+            // pre-loading. Each `get()` call resets the idle timer; the task is
+            // aborted as soon as the readiness wait resolves.
+            let sessions_keepalive = self.sessions.clone();
+            let touch_period = (self.cfg.idle_timeout / 2).max(self.cfg.min_session_touch_period);
+            let slot_keepalive = hopr_utils::runtime::prelude::spawn(async move {
+                loop {
+                    hopr_utils::runtime::prelude::sleep(touch_period).await;
+                    let _ = sessions_keepalive.get(&session_id);
+                }
+            });
+
+            // TODO: consider making this interactive = other party reports the exact level periodically
+            let wait_result = level_stream
+                .skip_while(|current_level| {
+                    futures::future::ready(*current_level < balancer_config.target_surb_buffer_size / 2)
+                })
+                .next()
+                .timeout(futures_time::time::Duration::from(SESSION_READINESS_TIMEOUT))
+                .await;
+            slot_keepalive.abort();
+            match wait_result {
+                Ok(Some(surb_level)) => {
+                    info!(%session_id, surb_level, "session is ready");
+                }
+                Ok(None) => {
+                    return Err(SessionManagerError::other(anyhow!("surb balancer was cancelled prematurely")).into());
+                }
+                Err(_) => {
+                    warn!(%session_id, "session didn't reach target SURB buffer size in time");
+                }
             }
 
             #[cfg(all(feature = "telemetry", not(test)))]
-            METRIC_DISPATCHED_MSGS.increment_by(&["processed"], 1);
+            METRIC_NUM_INITIATED_SESSIONS.increment();
 
-            return Ok(DispatchResult::Processed);
-        } else if in_data.data.application_tag == SESSION_APPLICATION_TAG {
-            let session_id = pseudonym;
+            let surb_estimator_for_rx = surb_estimator.clone();
+            let session = HoprSession::new_with_surb_state(
+                session_id,
+                forward_routing,
+                hopr_session_cfg,
+                (
+                    reduced_surb_scoring_sender,
+                    session_rx.inspect(move |_| {
+                        // Received packets = SURB consumption estimate
+                        // The received packets always consume a single SURB.
+                        surb_estimator_for_rx
+                            .consumed
+                            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        #[cfg(feature = "telemetry")]
+                        crate::telemetry::record_session_surb_consumed(&session_id, 1);
+                    }),
+                ),
+                Some(notifier),
+                // Entry (sending) side: give flow control the SURB balancer state as its
+                // anti-grief down-only ceiling, and the client's opt-in flow-control config.
+                Some(surb_mgmt.clone()),
+                cfg.flow_control,
+            )?
+            .with_resume_ticket(resume_ticket);
 
-            return if let Some(session_slot) = self.sessions.get(&session_id) {
-                trace!(%session_id, "received data for a registered session");
+            #[cfg(feature = "telemetry")]
+            initialize_session_telemetry(
+                session_id,
+                &self.cfg,
+                capabilities,
+                Some(&surb_estimator),
+                Some(&surb_mgmt),
+            );
 
-                Ok(session_slot
-                    .session_tx
-                    .try_send(in_data)
-                    .map(|_| {
-                        #[cfg(all(feature = "telemetry", not(test)))]
-                        METRIC_DISPATCHED_MSGS.increment_by(&["processed"], 1);
+            slot_guard.commit();
+            Ok(session)
+        } else {
+            warn!(%session_id, "session ready without SURB balancing");
 
-                        DispatchResult::Processed
-                    })
-                    .map_err(|error| {
-                        error!(%session_id, %error, "failed to dispatch session data");
-                        crate::counters::SESSION_INBOX_DROPS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-                        SessionManagerError::other(error)
-                    })?)
-            } else {
-                error!(%session_id, "received data from an unestablished session");
-                crate::counters::SESSION_UNKNOWN_DATA_DROPS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-                Err(TransportSessionError::UnknownData)
-            };
-        }
+            // Insert the slot and obtain a guard that rolls it back if any
+            // subsequent setup step fails.
+            let mut slot_guard = self
+                .allocate_session_slot(
+                    session_id,
+                    SessionSlot {
+                        session_tx,
+                        routing_opts,
+                        resume_ticket,
+                        paths,
+                        abort_handles: Arc::new(parking_lot::Mutex::new(abort_handles)),
+                        surb_mgmt: Default::default(),      // Disabled SURB management
+                        surb_estimator: Default::default(), // No SURB estimator needed
+                        admission: Default::default(),
+                    },
+                )
+                .ok_or_else(|| {
+                    // Session already exists; it means it is most likely a loopback attempt
+                    error!(%session_id, "session already exists - loopback attempt");
+                    SessionManagerError::Loopback
+                })?;
 
-        trace!(tag = %in_data.data.application_tag, "received data not associated with session protocol or any existing session");
+            #[cfg(all(feature = "telemetry", not(test)))]
+            METRIC_NUM_INITIATED_SESSIONS.increment();
+
+            // For standard Session data we first reduce the number of SURBs we want to produce,
+            // unless requested to always max them out
+            let max_out_organic_surbs = cfg.always_max_out_surbs;
+            let reduced_surb_sender =
+                msg_sender.with(move |(routing, mut data): (DestinationRouting, ApplicationDataOut)| {
+                    if !max_out_organic_surbs {
+                        data.packet_info
+                            .get_or_insert_with(|| OutgoingPacketInfo {
+                                max_surbs_in_packet: 1,
+                                ..Default::default()
+                            })
+                            .max_surbs_in_packet = 1;
+                    }
+                    futures::future::ok::<_, S::Error>((routing, data))
+                });
 
-        crate::counters::SESSION_UNRELATED_DATA_DISPATCHES.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            let session = HoprSession::new(
+                session_id,
+                forward_routing,
+                hopr_session_cfg,
+                (reduced_surb_sender, session_rx),
+                Some(notifier),
+            )?
+            .with_resume_ticket(resume_ticket);
 
-        #[cfg(all(feature = "telemetry", not(test)))]
-        METRIC_DISPATCHED_MSGS.increment_by(&["unrelated"], 1);
+            #[cfg(feature = "telemetry")]
+            initialize_session_telemetry(session_id, &self.cfg, capabilities, None, None);
 
-        Ok(DispatchResult::Unrelated(in_data))
+            slot_guard.commit();
+            Ok(session)
+        }
     }
 
-    /// Pre-populates the sessions cache with a session slot for benchmarking.
+    /// Resumes the outgoing Session with the given `id` over new forward and return paths.
     ///
-    /// Intended for benchmarks that need a session to exist before calling
-    /// [`SessionManager::dispatch_message`].
+    /// The Session must have been opened with the [`Capability::Resumption`] flag. Instead of starting
+    /// a new Session, the Exit is asked to re-attach the existing one using the ticket it issued on
+    /// establishment. The [`HoprSession`] object therefore keeps working: unacknowledged frames are
+    /// retransmitted over the new paths, and the SURB balancer carries on with its current state.
     ///
-    /// Requires the `"benchmark"` feature.
-    #[cfg(feature = "benchmark")]
-    pub fn pre_populate_session(&self, session_id: SessionId, routing_opts: DestinationRouting) {
-        let (session_tx, _) =
-            crossfire::mpsc::bounded_blocking_async::<ApplicationDataIn>(self.cfg.session_forward_capacity);
-        let slot = SessionSlot {
-            session_tx,
-            routing_opts,
-            abort_handles: Default::default(),
-            surb_mgmt: Arc::new(BalancerStateValues::default()),
-            surb_estimator: Default::default(),
-        };
-        self.sessions.insert(session_id, slot);
-    }
-
-    /// Like [`pre_populate_session`](SessionManager::pre_populate_session) but also returns the
-    /// session channel receiver so the caller can spawn a drain task.
+    /// If resumption fails, the Session keeps using its previous paths. When the Exit no longer knows
+    /// the Session (e.g., it has been restarted), [`TransportSessionError::Rejected`] with
+    /// [`StartErrorReason::UnknownSession`] is returned, and a new Session must be opened via
+    /// [`SessionManager::new_session`].
     ///
-    /// Requires the `"benchmark"` feature.
-    #[cfg(feature = "benchmark")]
-    pub fn pre_populate_session_with_receiver(
-        &self,
-        session_id: SessionId,
-        routing_opts: DestinationRouting,
-    ) -> crossfire::AsyncRx<crossfire::mpsc::Array<ApplicationDataIn>> {
-        let (session_tx, session_rx) =
-            crossfire::mpsc::bounded_blocking_async::<ApplicationDataIn>(self.cfg.session_forward_capacity);
-        let slot = SessionSlot {
-            session_tx,
-            routing_opts,
-            abort_handles: Default::default(),
-            surb_mgmt: Arc::new(BalancerStateValues::default()),
-            surb_estimator: Default::default(),
-        };
-        self.sessions.insert(session_id, slot);
-        session_rx
-    }
-
-    async fn handle_incoming_session_initiation(
+    /// Sessions no longer known to this manager can be resumed via [`SessionManager::resume_session_with`].
+    pub async fn resume_session(
         &self,
-        pseudonym: HoprPseudonym,
-        session_req: StartInitiation<SessionTarget, ByteCapabilities>,
+        id: &SessionId,
+        forward_path_options: RoutingOptions,
+        return_path_options: RoutingOptions,
     ) -> crate::errors::Result<()> {
-        trace!(challenge = session_req.challenge, "received session initiation request");
-
-        debug!(%pseudonym, "got new session request, searching for a free session slot");
+        let slot = self.sessions.get(id).ok_or(SessionManagerError::NonExistingSession)?;
+        let DestinationRouting::Forward {
+            destination, pseudonym, ..
+        } = slot.routing_opts.read().clone()
+        else {
+            return Err(SessionManagerError::other(anyhow!("only outgoing sessions can be resumed")).into());
+        };
+        let ticket = slot
+            .resume_ticket
+            .ok_or_else(|| SessionManagerError::other(anyhow!("session was not opened as resumable")))?;
 
         let mut msg_sender = self.msg_sender.get().cloned().ok_or(SessionManagerError::NotStarted)?;
 
-        let (new_session_notifier, close_session_notifier) = self
-            .session_notifiers
-            .get()
-            .cloned()
-            .ok_or(SessionManagerError::NotStarted)?;
-
-        // Reply routing uses SURBs only with the pseudonym of this Session's ID
-        let reply_routing = DestinationRouting::Return(pseudonym.into());
-
-        // Use constant application tag for all sessions
-        self.sessions.run_pending_tasks();
-
-        // A repeated initiation for a pseudonym that already has a Session means the
-        // initiator has lost or abandoned its side of it (it never received our
-        // SessionEstablished reply, or it reuses its pseudonym on reconnect).
-        // The pseudonym is known only to the initiator, so the existing Session cannot
-        // serve anyone else anymore: close it and let this initiation take the slot over.
-        // Otherwise, re-initiations would keep being rejected with NoSlotsAvailable
-        // until the stale Session gets evicted by the idle timeout.
-        if let Some(stale_slot) = self.sessions.remove(&pseudonym) {
-            self.active_sessions.fetch_sub(1, Ordering::Relaxed);
-            info!(%pseudonym, "closing stale session superseded by a new initiation with the same pseudonym");
-            close_session(pseudonym, stale_slot, ClosureReason::Eviction);
-        }
+        let (challenge, rx_resumption_done) = self.allocate_challenge()?;
 
-        let session_id = pseudonym;
+        let resume_session_msg = HoprStartProtocol::ResumeSession(StartResumption {
+            challenge,
+            session_id: *id,
+            ticket,
+            // Let the Exit know our current SURB balancer target, as on initiation
+            additional_data: if !slot.surb_mgmt.is_disabled() {
+                slot.surb_mgmt.as_config().target_surb_buffer_size.min(u32::MAX as u64) as u32
+            } else {
+                0
+            },
+        });
 
-        let (session_tx, session_rx) =
-            crossfire::mpsc::bounded_blocking_async::<ApplicationDataIn>(self.cfg.session_forward_capacity);
-        let (session_rx, session_rx_ah) = hopr_utils::runtime::DropAbortable::new(session_rx.into_stream());
+        let initiation_timeout: futures_time::time::Duration = initiation_timeout_max_one_way(
+            self.cfg.initiation_timeout_base,
+            forward_path_options.count_hops() + return_path_options.count_hops() + 2,
+        )
+        .into();
 
-        let slot = SessionSlot {
-            session_tx,
-            routing_opts: reply_routing.clone(),
-            abort_handles: Default::default(),
-            surb_mgmt: Default::default(),
-            surb_estimator: Default::default(),
+        let new_routing = DestinationRouting::Forward {
+            destination,
+            pseudonym,
+            forward_options: forward_path_options,
+            return_options: return_path_options.into(),
         };
-        slot.abort_handles.lock().insert(SessionHandles::Ingress, session_rx_ah);
 
-        // Insert the slot and obtain a guard. Any failure from here on rolls the slot
-        // back, otherwise it would block this pseudonym until idle eviction. The atomic
-        // insert (inside the helper) also prevents a TOCTOU race, so only one concurrent
-        // request can claim the slot for a given pseudonym.
-        let Some(mut slot_guard) = self.allocate_session_slot(session_id, slot.clone()) else {
-            // Either the maximum number of sessions has been reached, or a concurrent
-            // initiation for the same pseudonym has claimed the slot first.
-            error!(%pseudonym, "no session slot available");
-            let reason = StartErrorReason::NoSlotsAvailable;
-            let data = HoprStartProtocol::SessionError(StartErrorType {
-                challenge: session_req.challenge,
-                reason,
-            });
-            send_via_msg_sender(&mut msg_sender, reply_routing.clone(), data, "session error message").await?;
-            return Ok(());
+        info!(challenge, session_id = %id, "session resumption request");
+        send_via_msg_sender(
+            &mut msg_sender,
+            new_routing.clone(),
+            resume_session_msg,
+            "session resumption message",
+        )
+        .await
+        .map_err(|error| {
+            self.session_initiations.remove(&challenge);
+            TransportSessionError::packet_sending(error)
+        })?;
+
+        match rx_resumption_done
+            .into_stream()
+            .try_next()
+            .timeout(initiation_timeout)
+            .await
+        {
+            Ok(Ok(Some(est))) if est.session_id == *id => {
+                // From now on, all Session data and keep-alives follow the new routing
+                if let Some(paths) = &slot.paths {
+                    paths.lock().set_primary_routing(new_routing.clone());
+                }
+                *slot.routing_opts.write() = new_routing;
+                info!(session_id = %id, "session resumed");
+                Ok(())
+            }
+            Ok(Ok(_)) => {
+                self.session_initiations.remove(&challenge);
+                Err(SessionManagerError::other(anyhow!("invalid response to session resumption")).into())
+            }
+            Ok(Err(error)) => {
+                error!(session_id = %id, ?error, "the other party rejected the session resumption");
+                Err(TransportSessionError::Rejected(error.reason))
+            }
+            Err(_) => {
+                error!(challenge, session_id = %id, "session resumption attempt timed out");
+                self.session_initiations.remove(&challenge);
+                Err(TransportSessionError::Timeout)
+            }
+        }
+    }
+
+    /// Resumes the outgoing Session with the given `id` at the `destination`, using the `ticket`
+    /// the Exit [issued](HoprSession::resume_ticket) on its establishment.
+    ///
+    /// Unlike [`SessionManager::resume_session`], the Session does not need to be known to this manager,
+    /// so that it can be resumed even after the Entry has been restarted. A new [`HoprSession`] object
+    /// is returned, which follows the paths and the SURB balancing given in the `cfg`.
+    ///
+    /// The new object starts with a fresh Session protocol state and without any Session keys.
+    /// Therefore, only Sessions opened without the [`Capability::Segmentation`] and [`Capability::Encryption`]
+    /// flags can be resumed this way, and the `cfg` must request the same capabilities the Session was opened with.
+    ///
+    /// When the Exit no longer knows the Session, [`TransportSessionError::Rejected`] with
+    /// [`StartErrorReason::UnknownSession`] is returned.
+    pub async fn resume_session_with(
+        &self,
+        destination: Address,
+        id: SessionId,
+        ticket: ResumeTicket,
+        cfg: SessionClientConfig,
+    ) -> crate::errors::Result<HoprSession> {
+        if cfg.capabilities.contains(Capability::Segmentation) || cfg.capabilities.contains(Capability::Encryption) {
+            return Err(SessionManagerError::other(anyhow!(
+                "sessions with segmentation or encryption cannot be resumed without their state"
+            ))
+            .into());
+        }
+
+        self.sessions.run_pending_tasks();
+        if self.sessions.contains_key(&id) {
+            return Err(
+                SessionManagerError::other(anyhow!("session is still active and must be resumed in place")).into(),
+            );
+        }
+        if self.cfg.maximum_sessions <= self.active_sessions.load(Ordering::Relaxed) {
+            return Err(SessionManagerError::TooManySessions.into());
+        }
+
+        let mut msg_sender = self.msg_sender.get().cloned().ok_or(SessionManagerError::NotStarted)?;
+
+        let (challenge, rx_resumption_done) = self.allocate_challenge()?;
+
+        let resume_session_msg = HoprStartProtocol::ResumeSession(StartResumption {
+            challenge,
+            session_id: id,
+            ticket,
+            // Without a SURB balancer, the Exit keeps its current target
+            additional_data: cfg
+                .surb_management
+                .as_ref()
+                .map(|balancer_config| balancer_config.target_surb_buffer_size.min(u32::MAX as u64) as u32)
+                .unwrap_or(0),
+        });
+
+        let initiation_timeout: futures_time::time::Duration = initiation_timeout_max_one_way(
+            self.cfg.initiation_timeout_base,
+            cfg.forward_path_options.count_hops() + cfg.return_path_options.count_hops() + 2,
+        )
+        .into();
+
+        let forward_routing = DestinationRouting::Forward {
+            destination: Box::new(destination.into()),
+            pseudonym: Some(id),
+            forward_options: cfg.forward_path_options.clone(),
+            return_options: cfg.return_path_options.clone().into(),
+        };
+
+        info!(challenge, session_id = %id, %destination, "session resumption request with ticket");
+        send_via_msg_sender(
+            &mut msg_sender,
+            forward_routing.clone(),
+            resume_session_msg,
+            "session resumption message",
+        )
+        .await
+        .map_err(|error| {
+            self.session_initiations.remove(&challenge);
+            TransportSessionError::packet_sending(error)
+        })?;
+
+        match rx_resumption_done
+            .into_stream()
+            .try_next()
+            .timeout(initiation_timeout)
+            .await
+        {
+            Ok(Ok(Some(est))) if est.session_id == id => {
+                info!(session_id = %id, "session resumed with ticket");
+                let capabilities = cfg.capabilities;
+                self.open_outgoing_session(
+                    EstablishedSession {
+                        session_id: id,
+                        destination,
+                        pseudonym: id,
+                        forward_routing,
+                        resume_ticket: est.resume_ticket,
+                        capabilities,
+                        protocol_version: MIN_SESSION_PROTOCOL_VERSION,
+                        cipher: None,
+                    },
+                    cfg,
+                    msg_sender,
+                )
+                .await
+            }
+            Ok(Ok(_)) => {
+                self.session_initiations.remove(&challenge);
+                Err(SessionManagerError::other(anyhow!("invalid response to session resumption")).into())
+            }
+            Ok(Err(error)) => {
+                error!(session_id = %id, ?error, "the other party rejected the session resumption");
+                Err(TransportSessionError::Rejected(error.reason))
+            }
+            Err(_) => {
+                error!(challenge, session_id = %id, "session resumption attempt timed out");
+                self.session_initiations.remove(&challenge);
+                Err(TransportSessionError::Timeout)
+            }
+        }
+    }
+
+    /// Sends a keep-alive packet with the given [`SessionId`].
+    ///
+    /// This currently "fires & forgets" and does not expect nor await any "pong" response.
+    pub async fn ping_session(&self, id: &SessionId) -> crate::errors::Result<()> {
+        if let Some(session_data) = self.sessions.get(id) {
+            trace!(session_id = ?id, "pinging manually session");
+            let mut msg_sender = self.msg_sender.get().cloned().ok_or(SessionManagerError::NotStarted)?;
+            let routing = session_data.routing_opts.read().clone();
+            send_via_msg_sender(
+                &mut msg_sender,
+                routing,
+                HoprStartProtocol::KeepAlive((*id).into()),
+                "session ping message",
+            )
+            .await
+            .map_err(TransportSessionError::packet_sending)
+        } else {
+            Err(SessionManagerError::NonExistingSession.into())
+        }
+    }
+
+    /// Returns [`SessionIds`](SessionId) of all currently active sessions.
+    pub fn active_sessions(&self) -> Vec<SessionId> {
+        self.sessions.run_pending_tasks();
+        self.sessions.iter().map(|(k, _)| *k).collect()
+    }
+
+    /// Explicitly closes the session with the given `id`.
+    ///
+    /// Removes the entry from the internal session cache, closes the data channel,
+    /// and aborts any auxiliary tasks. Returns `true` if a session was found and
+    /// closed, `false` otherwise.
+    ///
+    /// This avoids waiting for the idle timeout (`time_to_idle`) or the LRU
+    /// capacity bound to evict the entry, which is the desired behaviour when
+    /// the caller (e.g. REST `DELETE /session`) knows the session is finished.
+    pub fn close_session(&self, id: &SessionId) -> bool {
+        if let Some(slot) = self.sessions.remove(id) {
+            self.active_sessions.fetch_sub(1, Ordering::Relaxed);
+            close_session(*id, slot, ClosureReason::Eviction);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Updates the configuration of the SURB balancer on the given [`SessionId`].
+    ///
+    /// Returns an error if the Session with the given `id` does not exist,
+    /// if it does not use SURB balancing, or if the config asks for a different
+    /// [controller kind](SurbBalancerConfig::controller) than the Session runs.
+    pub fn update_surb_balancer_config(&self, id: &SessionId, config: SurbBalancerConfig) -> crate::errors::Result<()> {
+        let cfg = self
+            .sessions
+            .get(id)
+            .ok_or(SessionManagerError::NonExistingSession)?
+            .surb_mgmt;
+
+        // Only update the config if there already was one before
+        if cfg.is_disabled() {
+            return Err(SessionManagerError::other(anyhow!("session does not use SURB balancing")).into());
+        }
+
+        // The controller is instantiated once, when the Session is established
+        let running_controller = cfg.as_config().controller;
+        if config.controller != running_controller {
+            return Err(SessionManagerError::other(anyhow!(
+                "cannot change the SURB balancer controller from {running_controller} to {}",
+                config.controller
+            ))
+            .into());
+        }
+
+        cfg.update(&config);
+        Ok(())
+    }
+
+    /// Retrieves the configuration of SURB balancing for the given Session.
+    ///
+    /// Returns an error if the Session with the given `id` does not exist.
+    pub fn get_surb_balancer_config(&self, id: &SessionId) -> crate::errors::Result<Option<SurbBalancerConfig>> {
+        match self.sessions.get(id) {
+            Some(session) => Ok(Some(session.surb_mgmt.as_ref())
+                .filter(|c| !c.is_disabled())
+                .map(|d| d.as_config())),
+            None => Err(SessionManagerError::NonExistingSession.into()),
+        }
+    }
+
+    /// Retrieves the state of the paths of a multipath Session.
+    ///
+    /// The primary path of the Session comes first.
+    ///
+    /// Returns an error if the Session with the given `id` does not exist.
+    /// Returns `Ok(None)` if the Session does not use multiple paths.
+    pub fn get_session_paths(&self, id: &SessionId) -> crate::errors::Result<Option<Vec<PathStats>>> {
+        match self.sessions.get(id) {
+            Some(session) => Ok(session.paths.as_ref().map(|paths| paths.lock().stats())),
+            None => Err(SessionManagerError::NonExistingSession.into()),
+        }
+    }
+
+    /// Gets estimations produced/received and consumed SURBs by the Session.
+    ///
+    /// For an outgoing Session (Entry) the pair is the number of SURBs sent (by us) and used (by the Exit).
+    /// For an incoming Session (Exit) the pair is the number of SURBs received (from Entry) and used (by us).
+    ///
+    /// Returns an error if the Session with the given `id` does not exist.
+    pub fn get_surb_level_estimates(&self, id: &SessionId) -> crate::errors::Result<(u64, u64)> {
+        match self.sessions.get(id) {
+            Some(session) => Ok((
+                session
+                    .surb_estimator
+                    .produced
+                    .load(std::sync::atomic::Ordering::Relaxed),
+                session
+                    .surb_estimator
+                    .consumed
+                    .load(std::sync::atomic::Ordering::Relaxed),
+            )),
+            None => Err(SessionManagerError::NonExistingSession.into()),
+        }
+    }
+
+    /// The main method to be called whenever data are received.
+    ///
+    /// It tries to recognize the message and correctly dispatches either
+    /// the Session protocol or Start protocol messages.
+    ///
+    /// If the data are not recognized, they are returned as [`DispatchResult::Unrelated`].
+    pub fn dispatch_message(
+        &self,
+        pseudonym: HoprPseudonym,
+        in_data: ApplicationDataIn,
+    ) -> crate::errors::Result<DispatchResult> {
+        if in_data.data.application_tag == HoprStartProtocol::START_PROTOCOL_MESSAGE_TAG {
+            // This is a Start protocol message, so we send it to the handler
+            trace!("dispatching Start protocol message");
+            if let Some(start_protocol_tx) = self.start_protocol_tx.get() {
+                start_protocol_tx
+                    .try_send((
+                        pseudonym,
+                        HoprStartProtocol::try_from(in_data.data)?,
+                        in_data.packet_info.num_saved_surbs,
+                    ))
+                    .map_err(|error| {
+                        error!(%error, "failed to send Start protocol message to processing task");
+                        SessionManagerError::other(error)
+                    })?;
+            } else {
+                return Err(SessionManagerError::NotStarted.into());
+            }
+
+            #[cfg(all(feature = "telemetry", not(test)))]
+            METRIC_DISPATCHED_MSGS.increment_by(&["processed"], 1);
+
+            return Ok(DispatchResult::Processed);
+        } else if in_data.data.application_tag == SESSION_APPLICATION_TAG {
+            let session_id = pseudonym;
+
+            return if let Some(session_slot) = self.sessions.get(&session_id) {
+                trace!(%session_id, "received data for a registered session");
+
+                Ok(session_slot
+                    .session_tx
+                    .try_send(in_data)
+                    .map(|_| {
+                        #[cfg(all(feature = "telemetry", not(test)))]
+                        METRIC_DISPATCHED_MSGS.increment_by(&["processed"], 1);
+
+                        DispatchResult::Processed
+                    })
+                    .map_err(|error| {
+                        error!(%session_id, %error, "failed to dispatch session data");
+                        crate::counters::SESSION_INBOX_DROPS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        SessionManagerError::other(error)
+                    })?)
+            } else {
+                error!(%session_id, "received data from an unestablished session");
+                crate::counters::SESSION_UNKNOWN_DATA_DROPS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                Err(TransportSessionError::UnknownData)
+            };
+        }
+
+        trace!(tag = %in_data.data.application_tag, "received data not associated with session protocol or any existing session");
+
+        crate::counters::SESSION_UNRELATED_DATA_DISPATCHES.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+
+        #[cfg(all(feature = "telemetry", not(test)))]
+        METRIC_DISPATCHED_MSGS.increment_by(&["unrelated"], 1);
+
+        Ok(DispatchResult::Unrelated(in_data))
+    }
+
+    /// Pre-populates the sessions cache with a session slot for benchmarking.
+    ///
+    /// Intended for benchmarks that need a session to exist before calling
+    /// [`SessionManager::dispatch_message`].
+    ///
+    /// Requires the `"benchmark"` feature.
+    #[cfg(feature = "benchmark")]
+    pub fn pre_populate_session(&self, session_id: SessionId, routing_opts: DestinationRouting) {
+        let (session_tx, _) =
+            crossfire::mpsc::bounded_blocking_async::<ApplicationDataIn>(self.cfg.session_forward_capacity);
+        let slot = SessionSlot {
+            session_tx,
+            routing_opts: Arc::new(parking_lot::RwLock::new(routing_opts)),
+            resume_ticket: None,
+            paths: None,
+            abort_handles: Default::default(),
+            surb_mgmt: Arc::new(BalancerStateValues::default()),
+            surb_estimator: Default::default(),
+            admission: Default::default(),
+        };
+        self.sessions.insert(session_id, slot);
+    }
+
+    /// Like [`pre_populate_session`](SessionManager::pre_populate_session) but also returns the
+    /// session channel receiver so the caller can spawn a drain task.
+    ///
+    /// Requires the `"benchmark"` feature.
+    #[cfg(feature = "benchmark")]
+    pub fn pre_populate_session_with_receiver(
+        &self,
+        session_id: SessionId,
+        routing_opts: DestinationRouting,
+    ) -> crossfire::AsyncRx<crossfire::mpsc::Array<ApplicationDataIn>> {
+        let (session_tx, session_rx) =
+            crossfire::mpsc::bounded_blocking_async::<ApplicationDataIn>(self.cfg.session_forward_capacity);
+        let slot = SessionSlot {
+            session_tx,
+            routing_opts: Arc::new(parking_lot::RwLock::new(routing_opts)),
+            resume_ticket: None,
+            paths: None,
+            abort_handles: Default::default(),
+            surb_mgmt: Arc::new(BalancerStateValues::default()),
+            surb_estimator: Default::default(),
+            admission: Default::default(),
+        };
+        self.sessions.insert(session_id, slot);
+        session_rx
+    }
+
+    /// Admits an incoming Session, returning the Exit service that serves it (if any)
+    /// and the permit of its target quota (if any).
+    fn admit_session(
+        &self,
+        request: &SessionAdmissionRequest<'_>,
+    ) -> Result<(Option<SharedSessionService>, Option<AdmissionPermit>), StartErrorReason> {
+        // Sessions targeting an Exit service can be served only by a service registered for it
+        let service = match request.target {
+            SessionTarget::ExitNode(id) => {
+                let service = self.services.get(*id).ok_or(StartErrorReason::UnknownService)?;
+                service.accept(request)?;
+                Some(service)
+            }
+            _ => None,
+        };
+
+        Ok((service, self.admission.admit(request)?))
+    }
+
+    async fn handle_incoming_session_initiation(
+        &self,
+        pseudonym: HoprPseudonym,
+        session_req: StartInitiation<SessionTarget, ByteCapabilities>,
+    ) -> crate::errors::Result<()> {
+        trace!(challenge = session_req.challenge, "received session initiation request");
+
+        debug!(%pseudonym, "got new session request, searching for a free session slot");
+
+        let mut msg_sender = self.msg_sender.get().cloned().ok_or(SessionManagerError::NotStarted)?;
+
+        let (new_session_notifier, close_session_notifier) = self
+            .session_notifiers
+            .get()
+            .cloned()
+            .ok_or(SessionManagerError::NotStarted)?;
+
+        // Reply routing uses SURBs only with the pseudonym of this Session's ID
+        let reply_routing = DestinationRouting::Return(pseudonym.into());
+
+        // Count the initiation towards the rate limits before it can affect any existing Session
+        if let Err(reason) = self.admission.check_rate(&pseudonym) {
+            warn!(%pseudonym, %reason, "session initiation exceeded the rate limit");
+            return send_session_error(&mut msg_sender, reply_routing, session_req.challenge, reason).await;
+        }
+
+        // Use constant application tag for all sessions
+        self.sessions.run_pending_tasks();
+
+        // A repeated initiation for a pseudonym that already has a Session means the
+        // initiator has lost or abandoned its side of it (it never received our
+        // SessionEstablished reply, or it reuses its pseudonym on reconnect).
+        // The pseudonym is known only to the initiator, so the existing Session cannot
+        // serve anyone else anymore: close it and let this initiation take the slot over.
+        // Otherwise, re-initiations would keep being rejected with NoSlotsAvailable
+        // until the stale Session gets evicted by the idle timeout.
+        if let Some(stale_slot) = self.sessions.remove(&pseudonym) {
+            self.active_sessions.fetch_sub(1, Ordering::Relaxed);
+            info!(%pseudonym, "closing stale session superseded by a new initiation with the same pseudonym");
+            close_session(pseudonym, stale_slot, ClosureReason::Eviction);
+        }
+
+        let session_id = pseudonym;
+
+        // Leave out the optional capabilities that are not supported, reject if any required one is not
+        let supported = if self.handshake.is_some() {
+            self.cfg.supported_capabilities
+        } else {
+            without_capabilities(self.cfg.supported_capabilities, Capability::Encryption.into())
+        };
+        let required = without_capabilities(
+            session_req.capabilities.0,
+            session_req
+                .optional_capabilities
+                .map(Capabilities::from)
+                .unwrap_or_default(),
+        );
+        if !supported.contains(required) {
+            error!(%pseudonym, ?required, ?supported, "session requires unsupported capabilities");
+            return send_session_error(
+                &mut msg_sender,
+                reply_routing,
+                session_req.challenge,
+                StartErrorReason::UnsupportedCapability,
+            )
+            .await;
+        }
+        let capabilities = Capabilities::new_truncated((session_req.capabilities.0 & supported).bits());
+
+        // The highest Session protocol version supported by both sides is used, if the initiator offers any
+        let protocol_version = session_req
+            .session_protocol_version
+            .map(|version| version.clamp(MIN_SESSION_PROTOCOL_VERSION, SESSION_PROTOCOL_VERSION));
+        let hopr_session_cfg = HoprSessionConfig {
+            protocol_version: protocol_version.unwrap_or(MIN_SESSION_PROTOCOL_VERSION),
+            ..session_config(&self.cfg, capabilities)
+        };
+
+        // The target quota is held for as long as the Session slot exists
+        let (service, admission) = match self.admit_session(&SessionAdmissionRequest {
+            initiator: &pseudonym,
+            target: &session_req.target,
+            capabilities,
+        }) {
+            Ok((service, permit)) => (service, Arc::new(parking_lot::Mutex::new(permit))),
+            Err(reason) => {
+                warn!(%pseudonym, %reason, target = ?session_req.target, "session not admitted");
+                return send_session_error(&mut msg_sender, reply_routing, session_req.challenge, reason).await;
+            }
+        };
+
+        // The ticket is issued only if the initiator wishes to resume the Session later
+        let resume_ticket = capabilities
+            .contains(Capability::Resumption)
+            .then(|| ResumeTicket::from_be_bytes(hopr_api::types::crypto_random::random_bytes()));
+
+        // Set our peer ID in the session ID sent back to the initiator
+        let mut established = StartEstablished {
+            orig_challenge: session_req.challenge,
+            session_id,
+            // Agreed capabilities are sent back only to initiators that negotiate them
+            capabilities: (session_req.optional_capabilities.is_some() || protocol_version.is_some())
+                .then_some(ByteCapabilities(capabilities)),
+            session_protocol_version: protocol_version,
+            resume_ticket,
+            key_confirmation: None,
+        };
+
+        // Derive the Session key if the initiator asked for an encrypted Session,
+        // the key exchange is bound to both the initiation and the establishment
+        let cipher = if capabilities.contains(Capability::Encryption) {
+            let key_exchange = match &self.handshake {
+                Some(responder) => responder
+                    .respond(&session_req, &established)
+                    .map_err(anyhow::Error::from),
+                None => Err(anyhow!("packet key is not set")),
+            };
+
+            match key_exchange {
+                Ok((keys, confirmation)) => {
+                    established.key_confirmation = Some(confirmation);
+                    Some(Arc::new(SessionCipher::new(keys)))
+                }
+                Err(error) => {
+                    error!(%pseudonym, %error, "cannot perform session key exchange");
+                    return send_session_error(
+                        &mut msg_sender,
+                        reply_routing,
+                        session_req.challenge,
+                        StartErrorReason::HandshakeFailed,
+                    )
+                    .await;
+                }
+            }
+        } else {
+            None
+        };
+
+        // Session data is sealed right before it is sent
+        let outgoing_cipher = cipher.clone();
+        let mut msg_sender = msg_sender.with(move |(routing, data): (DestinationRouting, ApplicationDataOut)| {
+            futures::future::ok::<_, S::Error>((routing, seal_session_data(outgoing_cipher.as_deref(), data)))
+        });
+
+        let (session_tx, session_rx) =
+            crossfire::mpsc::bounded_blocking_async::<ApplicationDataIn>(self.cfg.session_forward_capacity);
+        let (session_rx, session_rx_ah) = hopr_utils::runtime::DropAbortable::new(session_rx.into_stream());
+        let session_rx = session_rx
+            .filter_map(move |data| futures::future::ready(open_session_data(&session_id, cipher.as_deref(), data)));
+
+        let slot = SessionSlot {
+            session_tx,
+            routing_opts: Arc::new(parking_lot::RwLock::new(reply_routing.clone())),
+            resume_ticket,
+            paths: None,
+            abort_handles: Default::default(),
+            surb_mgmt: Default::default(),
+            surb_estimator: Default::default(),
+            admission,
+        };
+        slot.abort_handles.lock().insert(SessionHandles::Ingress, session_rx_ah);
+
+        // Insert the slot and obtain a guard. Any failure from here on rolls the slot
+        // back, otherwise it would block this pseudonym until idle eviction. The atomic
+        // insert (inside the helper) also prevents a TOCTOU race, so only one concurrent
+        // request can claim the slot for a given pseudonym.
+        let Some(mut slot_guard) = self.allocate_session_slot(session_id, slot.clone()) else {
+            // Either the maximum number of sessions has been reached, or a concurrent
+            // initiation for the same pseudonym has claimed the slot first.
+            error!(%pseudonym, "no session slot available");
+            return send_session_error(
+                &mut msg_sender,
+                reply_routing,
+                session_req.challenge,
+                StartErrorReason::NoSlotsAvailable,
+            )
+            .await;
+        };
+
+        debug!(?pseudonym, ?session_req, "assigned a new session");
+
+        let closure_notifier = Box::new(move |session_id: SessionId, reason: ClosureReason| {
+            if let Err(error) = close_session_notifier.try_send((session_id, reason)) {
+                error!(%session_id, %error, %reason, "failed to notify session closure");
+            }
+        });
+
+        let session = if !capabilities.contains(Capability::NoRateControl) {
+            // Because of SURB scarcity, control the egress rate of incoming sessions
+            let egress_rate_control =
+                RateController::new(self.cfg.initial_return_session_egress_rate, Duration::from_secs(1));
+
+            // The Session request carries a "hint" as additional data telling what
+            // the Session initiator has configured as its target buffer size in the Balancer.
+            let target_surb_buffer_size = if session_req.additional_data > 0 {
+                (session_req.additional_data as u64).min(self.cfg.maximum_surb_buffer_size as u64)
+            } else {
+                self.cfg.initial_return_session_egress_rate as u64
+                    * self
+                        .cfg
+                        .minimum_surb_buffer_duration
+                        .max(MIN_SURB_BUFFER_DURATION)
+                        .as_secs()
+            };
+
+            let surb_estimator_clone = slot.surb_estimator.clone();
+            let session = HoprSession::new(
+                session_id,
+                reply_routing.clone(),
+                hopr_session_cfg,
+                (
+                    // Sent packets = SURB consumption estimate
+                    msg_sender
+                        .clone()
+                        .with(move |(routing, data): (DestinationRouting, ApplicationDataOut)| {
+                            // Each outgoing packet consumes one SURB
+                            surb_estimator_clone
+                                .consumed
+                                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                            #[cfg(feature = "telemetry")]
+                            crate::telemetry::record_session_surb_consumed(&session_id, 1);
+                            futures::future::ok::<_, S::Error>((routing, data))
+                        })
+                        .rate_limit_with_controller(&egress_rate_control)
+                        .buffer((2 * target_surb_buffer_size) as usize),
+                    // Received packets = SURB retrieval estimate
+                    session_rx.inspect(move |data| {
+                        let produced = data.num_surbs_with_msg() as u64;
+                        // Count the number of SURBs delivered with each incoming packet
+                        surb_estimator_clone
+                            .produced
+                            .fetch_add(produced, std::sync::atomic::Ordering::Relaxed);
+                        #[cfg(feature = "telemetry")]
+                        crate::telemetry::record_session_surb_produced(&session_id, produced);
+                    }),
+                ),
+                Some(closure_notifier),
+            )?;
+
+            // The SURB balancer will start intervening by rate-limiting the
+            // egress of the Session, once the estimated number of SURBs drops below
+            // the target defined here. Otherwise, the maximum egress is allowed.
+            let balancer_config = SurbBalancerConfig {
+                target_surb_buffer_size,
+                // At maximum egress, the SURB buffer drains in `minimum_surb_buffer_duration` seconds
+                max_surbs_per_sec: target_surb_buffer_size / self.cfg.minimum_surb_buffer_duration.as_secs(),
+                // No SURB decay at the Exit, since we know almost exactly how many SURBs
+                // were received
+                surb_decay: None,
+                // The Exit always uses the `SimpleBalancerController`
+                ..Default::default()
+            };
+
+            slot.surb_mgmt.update(&balancer_config);
+
+            // Spawn the SURB balancer only once we know we have registered the
+            // abort handle with the pre-allocated Session slot
+            debug!(%session_id, ?balancer_config ,"spawning exit SURB balancer");
+            let balancer = SurbBalancer::new(
+                session_id,
+                SimpleBalancerController::default(),
+                slot.surb_estimator.clone(),
+                SurbControllerWithCorrection(egress_rate_control, 1), // 1 SURB per egress packet
+                slot.surb_mgmt.clone(),
+            );
+
+            // Assign the SURB balancer and abort handles to the already allocated Session slot
+            let (_, balancer_abort_handle) = balancer.start_control_loop(self.cfg.balancer_sampling_interval);
+            slot.abort_handles
+                .lock()
+                .insert(SessionHandles::Balancer, balancer_abort_handle);
+
+            // Spawn a keep-alive stream notifying about the SURB buffer level towards the Entry
+            if let Some(period) = self.cfg.surb_balance_notify_period {
+                let surb_estimator_clone = slot.surb_estimator.clone();
+                let (ka_controller, ka_abort_handle) = utils::spawn_keep_alive_stream(
+                    session_id,
+                    // Sent Keep-Alive packets also contribute to SURB consumption
+                    msg_sender
+                        .clone()
+                        .with(move |(routing, data): (DestinationRouting, ApplicationDataOut)| {
+                            // Each sent keepalive consumes 1 SURB
+                            surb_estimator_clone
+                                .consumed
+                                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                            #[cfg(feature = "telemetry")]
+                            crate::telemetry::record_session_surb_consumed(&session_id, 1);
+                            futures::future::ok::<_, S::Error>((routing, data))
+                        }),
+                    reply_routing.clone(),
+                    SurbNotificationMode::Level(slot.surb_estimator.clone()),
+                    slot.surb_mgmt.clone(),
+                );
+
+                // Start keepalive stream towards the Entry with a predefined period
+                hopr_utils::runtime::prelude::spawn(async move {
+                    // Delay the stream execution by one period
+                    hopr_utils::runtime::prelude::sleep(period).await;
+                    ka_controller.set_rate_per_unit(1, period);
+                });
+
+                slot.abort_handles
+                    .lock()
+                    .insert(SessionHandles::KeepAlive, ka_abort_handle);
+
+                debug!(%session_id, ?period, "started SURB level-notifying keep-alive stream");
+            }
+
+            session
+        } else {
+            HoprSession::new(
+                session_id,
+                reply_routing.clone(),
+                hopr_session_cfg,
+                (msg_sender.clone(), session_rx),
+                Some(closure_notifier),
+            )?
+        };
+
+        // Extract useful information about the session from the Start protocol message
+        let incoming_session = IncomingSession {
+            id: session_id,
+            session,
+            target: session_req.target,
+        };
+
+        if let Some(service) = service {
+            // The service stops serving the Session once it is closed
+            let service_abort_handle = hopr_utils::spawn_as_abortable!(async move {
+                match service.serve(incoming_session).await {
+                    Ok(()) => debug!(%session_id, "exit service finished serving the session"),
+                    Err(error) => error!(%session_id, %error, "exit service failed to serve the session"),
+                }
+            });
+            slot.abort_handles
+                .lock()
+                .insert(SessionHandles::Service, service_abort_handle);
+
+            trace!(?session_id, "session handed over to the exit service");
+        } else {
+            // Notify that a new incoming session has been created. Lock the sink and send
+            // directly into it, so no extra forwarding task between channels is needed.
+            match async {
+                let mut guard = new_session_notifier.lock().await;
+                guard.send(incoming_session).await
+            }
+            .timeout(futures_time::time::Duration::from(EXTERNAL_SEND_TIMEOUT))
+            .await
+            {
+                Err(_) => {
+                    error!(%session_id, "timeout to notify about new incoming session");
+                    return Err(TransportSessionError::Timeout);
+                }
+                Ok(Err(error)) => {
+                    error!(%session_id, %error, "failed to notify about new incoming session");
+                    return Err(SessionManagerError::other(error).into());
+                }
+                _ => {}
+            };
+
+            trace!(?session_id, "session notification sent");
+        }
+
+        // Notify the sender that the session has been established.
+        let data = HoprStartProtocol::SessionEstablished(established);
+
+        send_via_msg_sender(
+            &mut msg_sender,
+            reply_routing.clone(),
+            data,
+            "session establishment message",
+        )
+        .await?;
+
+        #[cfg(feature = "telemetry")]
+        initialize_session_telemetry(
+            session_id,
+            &self.cfg,
+            capabilities,
+            Some(&slot.surb_estimator),
+            Some(&slot.surb_mgmt),
+        );
+
+        info!(%session_id, "new session established");
+
+        #[cfg(all(feature = "telemetry", not(test)))]
+        METRIC_NUM_ESTABLISHED_SESSIONS.increment();
+
+        slot_guard.commit();
+        Ok(())
+    }
+
+    async fn handle_session_established(
+        &self,
+        est: StartEstablished<SessionId, ByteCapabilities>,
+    ) -> crate::errors::Result<()> {
+        trace!(
+            session_id = ?est.session_id,
+            "received session establishment confirmation"
+        );
+        let challenge = est.orig_challenge;
+        let session_id = est.session_id;
+        if let Some(tx_est) = self.session_initiations.remove(&est.orig_challenge) {
+            if let Err(error) = tx_est.try_send(Ok(est)) {
+                error!(%challenge, %session_id, %error, "failed to send session establishment confirmation");
+                return Err(SessionManagerError::other(error).into());
+            }
+            debug!(?session_id, challenge, "session establishment complete");
+        } else {
+            error!(%session_id, challenge, "unknown session establishment attempt or expired");
+        }
+        Ok(())
+    }
+
+    async fn handle_session_resumption(
+        &self,
+        pseudonym: HoprPseudonym,
+        req: StartResumption<SessionId>,
+        num_saved_surbs: usize,
+    ) -> crate::errors::Result<()> {
+        trace!(challenge = req.challenge, "received session resumption request");
+
+        let mut msg_sender = self.msg_sender.get().cloned().ok_or(SessionManagerError::NotStarted)?;
+        let reply_routing = DestinationRouting::Return(pseudonym.into());
+        let session_id = req.session_id;
+
+        // Only the initiator of an incoming Session can resume it, and only with the ticket issued for it.
+        // Looking the Session up also refreshes its idle timeout.
+        let Some(slot) = self.sessions.get(&session_id).filter(|slot| {
+            session_id == pseudonym
+                && slot
+                    .resume_ticket
+                    .is_some_and(|ticket| ticket.ct_eq(&req.ticket).into())
+                && matches!(*slot.routing_opts.read(), DestinationRouting::Return(_))
+        }) else {
+            warn!(%pseudonym, %session_id, "rejecting resumption of an unknown session");
+            return send_session_error(
+                &mut msg_sender,
+                reply_routing,
+                req.challenge,
+                StartErrorReason::UnknownSession,
+            )
+            .await;
+        };
+
+        // Only the SURBs carried by the resumption request were created for the new return path
+        if let Some(discarder) = &self.surb_discarder {
+            let num_discarded = discarder(&pseudonym, num_saved_surbs);
+            debug!(%session_id, num_discarded, "discarded surbs of the old return path");
+        }
+
+        // The Session request carries the initiator's current SURB balancer target
+        if req.additional_data > 0 && !slot.surb_mgmt.is_disabled() {
+            let target_surb_buffer_size = (req.additional_data as u64).min(self.cfg.maximum_surb_buffer_size as u64);
+            slot.surb_mgmt
+                .target_surb_buffer_size
+                .store(target_surb_buffer_size, std::sync::atomic::Ordering::Relaxed);
+            slot.surb_mgmt.max_surbs_per_sec.store(
+                target_surb_buffer_size / self.cfg.minimum_surb_buffer_duration.as_secs(),
+                std::sync::atomic::Ordering::Relaxed,
+            );
+        }
+
+        let data = HoprStartProtocol::SessionEstablished(StartEstablished {
+            orig_challenge: req.challenge,
+            session_id,
+            capabilities: None,
+            session_protocol_version: None,
+            resume_ticket: slot.resume_ticket,
+            key_confirmation: None,
+        });
+        send_via_msg_sender(&mut msg_sender, reply_routing, data, "session establishment message").await?;
+
+        info!(%session_id, "session resumed");
+        Ok(())
+    }
+
+    async fn handle_session_error(&self, error_type: StartErrorType) -> crate::errors::Result<()> {
+        trace!(
+            challenge = error_type.challenge,
+            error = ?error_type.reason,
+            "failed to initialize a session",
+        );
+        // Currently, we do not distinguish between individual error types
+        // and just discard the initiation attempt and pass on the error.
+        if let Some(tx_est) = self.session_initiations.remove(&error_type.challenge) {
+            if let Err(error) = tx_est.try_send(Err(error_type)) {
+                error!(%error, ?error_type, "could not send session error message");
+                return Err(SessionManagerError::other(error).into());
+            }
+            error!(
+                challenge = error_type.challenge,
+                ?error_type,
+                "session establishment error received"
+            );
+        } else {
+            error!(
+                challenge = error_type.challenge,
+                ?error_type,
+                "session establishment attempt expired before error could be delivered"
+            );
+        }
+
+        #[cfg(all(feature = "telemetry", not(test)))]
+        METRIC_RECEIVED_SESSION_ERRS.increment(&[&error_type.reason.to_string()]);
+
+        Ok(())
+    }
+
+    async fn handle_keep_alive(&self, msg: KeepAliveMessage<SessionId>) -> crate::errors::Result<()> {
+        let session_id = msg.session_id;
+        if let Some(session_slot) = self.sessions.get(&session_id) {
+            trace!(?session_id, "received keep-alive message");
+            match &*session_slot.routing_opts.read() {
+                // Session is outgoing - keep-alive was received from the Exit
+                DestinationRouting::Forward { .. } => {
+                    if msg.flags.contains(KeepAliveFlag::BalancerState)
+                        && !session_slot.surb_mgmt.is_disabled()
+                        && session_slot.surb_mgmt.buffer_level() != msg.additional_data
+                    {
+                        // Update the buffer level as sent to us from the Exit
+                        session_slot
+                            .surb_mgmt
+                            .buffer_level
+                            .store(msg.additional_data, std::sync::atomic::Ordering::Relaxed);
+                        debug!(%session_id, surb_level = msg.additional_data, "keep-alive updated SURB buffer size from the Exit");
+                    }
+
+                    // Increase the number of consumed SURBs in the estimator
+                    session_slot
+                        .surb_estimator
+                        .consumed
+                        .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    #[cfg(feature = "telemetry")]
+                    crate::telemetry::record_session_surb_consumed(&session_id, 1);
+                }
+                // Session is incoming - keep-alive was received from the Entry
+                DestinationRouting::Return(_) => {
+                    // Allow updating SURB balancer target based on the received Keep-Alive message
+                    if msg.flags.contains(KeepAliveFlag::BalancerTarget)
+                        && msg.additional_data > 0
+                        && !session_slot.surb_mgmt.is_disabled()
+                        && session_slot.surb_mgmt.controller_bounds().target() != msg.additional_data
+                    {
+                        // Update the target buffer size as sent to us from the Entry
+                        session_slot
+                            .surb_mgmt
+                            .target_surb_buffer_size
+                            .store(msg.additional_data, std::sync::atomic::Ordering::Relaxed);
+                        // Update maximum SURBs per second based on the new target
+                        session_slot.surb_mgmt.max_surbs_per_sec.store(
+                            msg.additional_data / self.cfg.minimum_surb_buffer_duration.as_secs(),
+                            std::sync::atomic::Ordering::Relaxed,
+                        );
+                        debug!(%session_id, target_surb_buffer_size = msg.additional_data, "keep-alive updated SURB balancer target buffer size from the Entry");
+                    }
+
+                    // Increase the number of received SURBs in the estimator.
+                    // Typically, 2 SURBs per Keep-Alive message
+                    let produced = KeepAliveMessage::<SessionId>::MIN_SURBS_PER_MESSAGE as u64;
+                    session_slot
+                        .surb_estimator
+                        .produced
+                        .fetch_add(produced, std::sync::atomic::Ordering::Relaxed);
+                    #[cfg(feature = "telemetry")]
+                    crate::telemetry::record_session_surb_produced(&session_id, produced);
+                }
+            }
+        } else {
+            debug!(%session_id, "received keep-alive request for an unknown session");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::{Context, anyhow};
+    use futures::{AsyncReadExt, AsyncWriteExt, channel::mpsc::UnboundedSender, future::BoxFuture, pin_mut};
+    use hopr_api::types::{
+        crypto::{keypairs::ChainKeypair, prelude::Keypair},
+        crypto_random::Randomizable,
+        internal::routing::SurbMatcher,
+        primitive::prelude::Address,
+    };
+    use hopr_protocol_start::{StartProtocol, StartProtocolDiscriminants};
+    use hopr_utils::network_types::prelude::SealedHost;
+    use moka::future::FutureExt;
+    use tokio::time::timeout;
+
+    use super::*;
+    use crate::{
+        Capabilities, MultipathConfig, SessionPathOptions,
+        balancer::{SurbBalancerConfig, SurbBalancerControllerKind},
+        types::SessionTarget,
+    };
+
+    #[test]
+    fn session_config_forwards_max_buffered_segments() {
+        assert_eq!(
+            SessionManagerConfig::default().max_buffered_segments,
+            0,
+            "default must leave the transport unbuffered"
+        );
+
+        for segments in [0, 64] {
+            let cfg = SessionManagerConfig {
+                max_buffered_segments: segments,
+                ..Default::default()
+            };
+            assert_eq!(
+                session_config(&cfg, Capabilities::empty()).max_buffered_segments,
+                segments
+            );
+        }
+    }
+
+    #[test]
+    fn session_config_forwards_fec() {
+        let cfg = SessionManagerConfig {
+            fec: FecConfig {
+                redundancy: 50,
+                adaptive: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            session_config(&cfg, Capability::ForwardErrorCorrection.into()).fec,
+            cfg.fec
+        );
+    }
+
+    #[async_trait::async_trait]
+    trait SendMsg {
+        async fn send_message(
+            &self,
+            routing: DestinationRouting,
+            data: ApplicationDataOut,
+        ) -> crate::errors::Result<()>;
+    }
+
+    mockall::mock! {
+        MsgSender {}
+        impl SendMsg for MsgSender {
+            fn send_message<'a, 'b>(&'a self, routing: DestinationRouting, data: ApplicationDataOut)
+            -> BoxFuture<'b, crate::errors::Result<()>> where 'a: 'b, Self: Sync + 'b;
+        }
+    }
+
+    fn mock_packet_planning(
+        sender: MockMsgSender,
+    ) -> (
+        UnboundedSender<(DestinationRouting, ApplicationDataOut)>,
+        tokio::task::JoinHandle<()>,
+    ) {
+        let (tx, rx) = futures::channel::mpsc::unbounded();
+        let handle = tokio::task::spawn(async move {
+            pin_mut!(rx);
+            while let Some((routing, data)) = rx.next().await {
+                sender
+                    .send_message(routing, data)
+                    .await
+                    .expect("send message must not fail in mock");
+            }
+        });
+        (tx, handle)
+    }
+
+    fn msg_type(data: &ApplicationDataOut, expected: StartProtocolDiscriminants) -> bool {
+        HoprStartProtocol::decode(data.data.application_tag, &data.data.plain_text)
+            .map(|d| StartProtocolDiscriminants::from(d) == expected)
+            .unwrap_or(false)
+    }
+
+    fn start_msg_match(data: &ApplicationDataOut, msg: impl Fn(HoprStartProtocol) -> bool) -> bool {
+        HoprStartProtocol::decode(data.data.application_tag, &data.data.plain_text)
+            .map(msg)
+            .unwrap_or(false)
+    }
+
+    /// Waits (bounded) until the manager reports no active sessions.
+    ///
+    /// The session-slot rollback runs on a spawned task, so its effect is observed
+    /// asynchronously; this polls [`SessionManager::active_sessions`] until it drains.
+    async fn wait_for_no_active_sessions(
+        mgr: &SessionManager<UnboundedSender<(DestinationRouting, ApplicationDataOut)>>,
+    ) -> bool {
+        for _ in 0..50 {
+            if mgr.active_sessions().is_empty() {
+                return true;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        mgr.active_sessions().is_empty()
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn session_manager_should_follow_start_protocol_to_establish_new_session_and_close_it() -> anyhow::Result<()>
+    {
+        let alice_pseudonym = HoprPseudonym::random();
+        let bob_peer: Address = (&ChainKeypair::random()).into();
+
+        let alice_mgr = SessionManager::new(Default::default());
+        let bob_mgr = SessionManager::new(Default::default());
+
+        let mut sequence = mockall::Sequence::new();
+        let mut alice_transport = MockMsgSender::new();
+        let mut bob_transport = MockMsgSender::new();
+
+        // Alice sends the StartSession message
+        let bob_mgr_clone = bob_mgr.clone();
+        alice_transport
+            .expect_send_message()
+            .once()
+            .in_sequence(&mut sequence)
+            .withf(move |peer, data| {
+                info!("alice sends {}", data.data.application_tag);
+                msg_type(data, StartProtocolDiscriminants::StartSession)
+                    && matches!(peer, DestinationRouting::Forward { destination, .. } if destination.as_ref() == &bob_peer.into())
+            })
+            .returning(move |_, data| {
+                let bob_mgr_clone = bob_mgr_clone.clone();
+                Box::pin(async move {
+                    bob_mgr_clone
+                        .dispatch_message(
+                            alice_pseudonym,
+                            ApplicationDataIn {
+                                data: data.data,
+                                packet_info: Default::default(),
+                            },
+                        )
+                        ?;
+                    Ok(())
+                })
+            });
+
+        // Bob sends the SessionEstablished message
+        let alice_mgr_clone = alice_mgr.clone();
+        bob_transport
+            .expect_send_message()
+            .once()
+            .in_sequence(&mut sequence)
+            .withf(move |peer, data| {
+                info!("bob sends {}", data.data.application_tag);
+                msg_type(data, StartProtocolDiscriminants::SessionEstablished)
+                    && matches!(peer, DestinationRouting::Return(SurbMatcher::Pseudonym(p)) if p == &alice_pseudonym)
+            })
+            .returning(move |_, data| {
+                let alice_mgr_clone = alice_mgr_clone.clone();
+
+                Box::pin(async move {
+                    alice_mgr_clone.dispatch_message(
+                        alice_pseudonym,
+                        ApplicationDataIn {
+                            data: data.data,
+                            packet_info: Default::default(),
+                        },
+                    )?;
+                    Ok(())
+                })
+            });
+
+        // Alice sends the terminating segment to close the Session
+        let bob_mgr_clone = bob_mgr.clone();
+        alice_transport
+            .expect_send_message()
+            .once()
+            .in_sequence(&mut sequence)
+            .withf(move |peer, data| {
+                hopr_protocol_session::types::SessionMessage::<{ ApplicationData::PAYLOAD_SIZE }>::try_from(
+                    data.data.plain_text.as_ref(),
+                )
+                .expect("must be a session message")
+                .try_as_segment()
+                .expect("must be a segment")
+                .is_terminating()
+                    && matches!(peer, DestinationRouting::Forward { destination, .. } if destination.as_ref() == &bob_peer.into())
+            })
+            .returning(move |_, data| {
+                let bob_mgr_clone = bob_mgr_clone.clone();
+                Box::pin(async move {
+                    bob_mgr_clone
+                        .dispatch_message(
+                            alice_pseudonym,
+                            ApplicationDataIn {
+                                data: data.data,
+                                packet_info: Default::default(),
+                            },
+                        )
+                        ?;
+                    Ok(())
+                })
+            });
+
+        let mut ahs = Vec::new();
+
+        // Start Alice
+        let (new_session_tx_alice, _) = futures::channel::mpsc::channel(1024);
+        let (alice_sender, alice_handle) = mock_packet_planning(alice_transport);
+        ahs.extend(alice_mgr.start(alice_sender.clone(), new_session_tx_alice)?);
+        assert!(alice_mgr.is_started());
+
+        // Start Bob
+        let (new_session_tx_bob, new_session_rx_bob) = futures::channel::mpsc::channel(1024);
+        let (bob_sender, bob_handle) = mock_packet_planning(bob_transport);
+        ahs.extend(bob_mgr.start(bob_sender.clone(), new_session_tx_bob)?);
+        assert!(bob_mgr.is_started());
+
+        let target = SealedHost::Plain("127.0.0.1:80".parse()?);
+
+        pin_mut!(new_session_rx_bob);
+        let (alice_session, bob_session) = timeout(
+            Duration::from_secs(2),
+            futures::future::join(
+                alice_mgr.new_session(
+                    bob_peer,
+                    SessionTarget::TcpStream(target.clone()),
+                    SessionClientConfig {
+                        pseudonym: alice_pseudonym.into(),
+                        capabilities: Capability::NoRateControl | Capability::Segmentation,
+                        surb_management: None,
+                        ..Default::default()
+                    },
+                ),
+                new_session_rx_bob.next(),
+            ),
+        )
+        .await?;
+
+        let mut alice_session = alice_session?;
+        let bob_session = bob_session.ok_or(anyhow!("bob must get an incoming session"))?;
+
+        assert_eq!(
+            alice_session.config().capabilities,
+            Capability::Segmentation | Capability::NoRateControl
+        );
+        assert_eq!(
+            alice_session.config().capabilities,
+            bob_session.session.config().capabilities
+        );
+        assert!(matches!(bob_session.target, SessionTarget::TcpStream(host) if host == target));
+
+        assert_eq!(vec![*alice_session.id()], alice_mgr.active_sessions());
+        assert_eq!(None, alice_mgr.get_surb_balancer_config(alice_session.id())?);
+        assert!(
+            alice_mgr
+                .update_surb_balancer_config(alice_session.id(), SurbBalancerConfig::default())
+                .is_err()
+        );
+
+        assert_eq!(vec![*bob_session.session.id()], bob_mgr.active_sessions());
+        assert_eq!(None, bob_mgr.get_surb_balancer_config(bob_session.session.id())?);
+        assert!(
+            bob_mgr
+                .update_surb_balancer_config(bob_session.session.id(), SurbBalancerConfig::default())
+                .is_err()
+        );
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        alice_session.close().await?;
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        assert!(matches!(
+            alice_mgr.ping_session(alice_session.id()).await,
+            Err(TransportSessionError::Manager(SessionManagerError::NonExistingSession))
+        ));
+
+        futures::stream::iter(ahs)
+            .for_each(|ah| async move { ah.abort() })
+            .await;
+
+        // Cleanup: close senders and await handles
+        alice_sender.close_channel();
+        bob_sender.close_channel();
+        let _ = alice_handle.await;
+        let _ = bob_handle.await;
+
+        Ok(())
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn session_manager_should_resume_session_over_new_path_without_restarting_it() -> anyhow::Result<()> {
+        let alice_pseudonym = HoprPseudonym::random();
+        let bob_peer: Address = (&ChainKeypair::random()).into();
+
+        let alice_mgr = SessionManager::new(Default::default());
+
+        // Bob records the SURBs discarded on resumption
+        let discarded_surbs = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let discarded_surbs_clone = discarded_surbs.clone();
+        let bob_mgr = SessionManager::new(Default::default()).with_surb_discarder(move |pseudonym, keep_freshest| {
+            discarded_surbs_clone.lock().push((*pseudonym, keep_freshest));
+            0
+        });
+
+        // Alice delivers everything to Bob and records the routing of her Session data
+        let alice_routings = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let alice_routings_clone = alice_routings.clone();
+        let mut alice_transport = MockMsgSender::new();
+        let bob_mgr_clone = bob_mgr.clone();
+        alice_transport.expect_send_message().returning(move |routing, data| {
+            if data.data.application_tag == SESSION_APPLICATION_TAG {
+                alice_routings_clone.lock().push(routing);
+            }
+            let bob_mgr_clone = bob_mgr_clone.clone();
+            Box::pin(async move {
+                let _ = bob_mgr_clone.dispatch_message(
+                    alice_pseudonym,
+                    ApplicationDataIn {
+                        data: data.data,
+                        packet_info: Default::default(),
+                    },
+                );
+                Ok(())
+            })
+        });
+
+        let mut bob_transport = MockMsgSender::new();
+        let alice_mgr_clone = alice_mgr.clone();
+        bob_transport.expect_send_message().returning(move |_, data| {
+            let alice_mgr_clone = alice_mgr_clone.clone();
+            Box::pin(async move {
+                let _ = alice_mgr_clone.dispatch_message(
+                    alice_pseudonym,
+                    ApplicationDataIn {
+                        data: data.data,
+                        packet_info: Default::default(),
+                    },
+                );
+                Ok(())
+            })
+        });
+
+        let mut ahs = Vec::new();
+        let (new_session_tx_alice, _) = futures::channel::mpsc::channel(1024);
+        let (alice_sender, alice_handle) = mock_packet_planning(alice_transport);
+        ahs.extend(alice_mgr.start(alice_sender.clone(), new_session_tx_alice)?);
+
+        let (new_session_tx_bob, new_session_rx_bob) = futures::channel::mpsc::channel(1024);
+        let (bob_sender, bob_handle) = mock_packet_planning(bob_transport);
+        ahs.extend(bob_mgr.start(bob_sender.clone(), new_session_tx_bob)?);
+
+        pin_mut!(new_session_rx_bob);
+        let (alice_session, bob_session) = timeout(
+            Duration::from_secs(2),
+            futures::future::join(
+                alice_mgr.new_session(
+                    bob_peer,
+                    SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
+                    SessionClientConfig {
+                        pseudonym: alice_pseudonym.into(),
+                        capabilities: Capability::NoRateControl | Capability::Segmentation | Capability::Resumption,
+                        surb_management: None,
+                        ..Default::default()
+                    },
+                ),
+                new_session_rx_bob.next(),
+            ),
+        )
+        .await?;
+
+        let mut alice_session = alice_session?;
+        let mut bob_session = bob_session.ok_or(anyhow!("bob must get an incoming session"))?;
+
+        let mut buf = [0u8; 5];
+        alice_session.write_all(b"hello").await?;
+        alice_session.flush().await?;
+        timeout(Duration::from_secs(2), bob_session.session.read_exact(&mut buf)).await??;
+        assert_eq!(b"hello", &buf);
+
+        // Resume over a different path: the same Session objects keep working on both sides
+        let new_forward_path = RoutingOptions::Hops(1_u32.try_into()?);
+        timeout(
+            Duration::from_secs(2),
+            alice_mgr.resume_session(
+                alice_session.id(),
+                new_forward_path.clone(),
+                RoutingOptions::Hops(1_u32.try_into()?),
+            ),
+        )
+        .await??;
+
+        alice_session.write_all(b"world").await?;
+        alice_session.flush().await?;
+        timeout(Duration::from_secs(2), bob_session.session.read_exact(&mut buf)).await??;
+        assert_eq!(b"world", &buf);
+
+        assert!(matches!(
+            alice_routings.lock().last(),
+            Some(DestinationRouting::Forward { forward_options, .. }) if forward_options == &new_forward_path
+        ));
+        assert_eq!(vec![*alice_session.id()], alice_mgr.active_sessions());
+        assert_eq!(vec![*bob_session.session.id()], bob_mgr.active_sessions());
+        assert_eq!(vec![(alice_pseudonym, 0)], *discarded_surbs.lock());
+
+        // Once the Exit forgets the Session, it cannot be resumed anymore
+        assert!(bob_mgr.close_session(bob_session.session.id()));
+        let res = timeout(
+            Duration::from_secs(2),
+            alice_mgr.resume_session(
+                alice_session.id(),
+                RoutingOptions::Hops(0_u32.try_into()?),
+                RoutingOptions::Hops(0_u32.try_into()?),
+            ),
+        )
+        .await?;
+        assert!(
+            matches!(
+                res,
+                Err(TransportSessionError::Rejected(StartErrorReason::UnknownSession))
+            ),
+            "{res:?}"
+        );
+        assert_eq!(1, discarded_surbs.lock().len());
+
+        futures::stream::iter(ahs)
+            .for_each(|ah| async move { ah.abort() })
+            .await;
+
+        alice_sender.close_channel();
+        bob_sender.close_channel();
+        let _ = alice_handle.await;
+        let _ = bob_handle.await;
+
+        Ok(())
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn session_manager_should_stripe_multipath_session_over_all_paths() -> anyhow::Result<()> {
+        let alice_pseudonym = HoprPseudonym::random();
+        let bob_peer: Address = (&ChainKeypair::random()).into();
+
+        let alice_mgr = SessionManager::new(Default::default());
+        let bob_mgr = SessionManager::new(Default::default());
+
+        // Alice delivers everything to Bob and records the routing of her Session data
+        let alice_routings = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let alice_routings_clone = alice_routings.clone();
+        let mut alice_transport = MockMsgSender::new();
+        let bob_mgr_clone = bob_mgr.clone();
+        alice_transport.expect_send_message().returning(move |routing, data| {
+            if data.data.application_tag == SESSION_APPLICATION_TAG {
+                alice_routings_clone.lock().push(routing);
+            }
+            let bob_mgr_clone = bob_mgr_clone.clone();
+            Box::pin(async move {
+                let _ = bob_mgr_clone.dispatch_message(
+                    alice_pseudonym,
+                    ApplicationDataIn {
+                        data: data.data,
+                        packet_info: Default::default(),
+                    },
+                );
+                Ok(())
+            })
+        });
+
+        let mut bob_transport = MockMsgSender::new();
+        let alice_mgr_clone = alice_mgr.clone();
+        bob_transport.expect_send_message().returning(move |_, data| {
+            let alice_mgr_clone = alice_mgr_clone.clone();
+            Box::pin(async move {
+                let _ = alice_mgr_clone.dispatch_message(
+                    alice_pseudonym,
+                    ApplicationDataIn {
+                        data: data.data,
+                        packet_info: Default::default(),
+                    },
+                );
+                Ok(())
+            })
+        });
+
+        let mut ahs = Vec::new();
+        let (new_session_tx_alice, _) = futures::channel::mpsc::channel(1024);
+        let (alice_sender, alice_handle) = mock_packet_planning(alice_transport);
+        ahs.extend(alice_mgr.start(alice_sender.clone(), new_session_tx_alice)?);
+
+        let (new_session_tx_bob, new_session_rx_bob) = futures::channel::mpsc::channel(1024);
+        let (bob_sender, bob_handle) = mock_packet_planning(bob_transport);
+        ahs.extend(bob_mgr.start(bob_sender.clone(), new_session_tx_bob)?);
+
+        let primary_path = RoutingOptions::Hops(0_u32.try_into()?);
+        let additional_path = RoutingOptions::Hops(1_u32.try_into()?);
+
+        pin_mut!(new_session_rx_bob);
+        let (alice_session, bob_session) = timeout(
+            Duration::from_secs(2),
+            futures::future::join(
+                alice_mgr.new_session(
+                    bob_peer,
+                    SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
+                    SessionClientConfig {
+                        pseudonym: alice_pseudonym.into(),
+                        forward_path_options: primary_path.clone(),
+                        return_path_options: primary_path.clone(),
+                        capabilities: Capability::NoRateControl | Capability::RetransmissionAck,
+                        surb_management: None,
+                        multipath: Some(MultipathConfig {
+                            additional_paths: vec![SessionPathOptions {
+                                forward_path_options: additional_path.clone(),
+                                return_path_options: additional_path.clone(),
+                            }],
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    },
+                ),
+                new_session_rx_bob.next(),
+            ),
+        )
+        .await?;
+
+        let mut alice_session = alice_session?;
+        let mut bob_session = bob_session.ok_or(anyhow!("bob must get an incoming session"))?;
+
+        // Several frames, each spanning multiple segments
+        let data = (0..8 * SESSION_MTU).map(|i| i as u8).collect::<Vec<_>>();
+        for chunk in data.chunks(2 * SESSION_MTU) {
+            alice_session.write_all(chunk).await?;
+            alice_session.flush().await?;
+        }
+
+        let mut received = vec![0u8; data.len()];
+        timeout(Duration::from_secs(2), bob_session.session.read_exact(&mut received)).await??;
+        assert_eq!(data, received);
+
+        let used_paths = alice_routings
+            .lock()
+            .iter()
+            .filter_map(|routing| match routing {
+                DestinationRouting::Forward { forward_options, .. } => Some(forward_options.clone()),
+                DestinationRouting::Return(_) => None,
+            })
+            .collect::<Vec<_>>();
+        assert!(used_paths.contains(&primary_path));
+        assert!(used_paths.contains(&additional_path));
+
+        let paths = alice_mgr
+            .get_session_paths(alice_session.id())?
+            .ok_or(anyhow!("must be a multipath session"))?;
+        assert_eq!(2, paths.len());
+        assert!(paths.iter().all(|path| path.sent_segments > 0), "{paths:?}");
+        assert!(paths.iter().all(|path| !path.degraded), "{paths:?}");
+        assert_eq!(None, bob_mgr.get_session_paths(bob_session.session.id())?);
+
+        futures::stream::iter(ahs)
+            .for_each(|ah| async move { ah.abort() })
+            .await;
+
+        alice_sender.close_channel();
+        bob_sender.close_channel();
+        let _ = alice_handle.await;
+        let _ = bob_handle.await;
+
+        Ok(())
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn session_manager_should_encrypt_session_data_with_negotiated_key() -> anyhow::Result<()> {
+        let alice_pseudonym = HoprPseudonym::random();
+        let bob_peer: Address = (&ChainKeypair::random()).into();
+        let bob_packet_key = OffchainKeypair::random();
+
+        let alice_mgr = SessionManager::new(Default::default());
+        let bob_mgr = SessionManager::new(Default::default()).with_packet_key(&bob_packet_key);
+
+        // Alice delivers everything to Bob and records the Session data she sends
+        let alice_sent = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let alice_sent_clone = alice_sent.clone();
+        let mut alice_transport = MockMsgSender::new();
+        let bob_mgr_clone = bob_mgr.clone();
+        alice_transport.expect_send_message().returning(move |_, data| {
+            if data.data.application_tag == SESSION_APPLICATION_TAG {
+                alice_sent_clone.lock().push(data.data.plain_text.clone());
+            }
+            let bob_mgr_clone = bob_mgr_clone.clone();
+            Box::pin(async move {
+                let _ = bob_mgr_clone.dispatch_message(
+                    alice_pseudonym,
+                    ApplicationDataIn {
+                        data: data.data,
+                        packet_info: Default::default(),
+                    },
+                );
+                Ok(())
+            })
+        });
+
+        let mut bob_transport = MockMsgSender::new();
+        let alice_mgr_clone = alice_mgr.clone();
+        bob_transport.expect_send_message().returning(move |_, data| {
+            let alice_mgr_clone = alice_mgr_clone.clone();
+            Box::pin(async move {
+                let _ = alice_mgr_clone.dispatch_message(
+                    alice_pseudonym,
+                    ApplicationDataIn {
+                        data: data.data,
+                        packet_info: Default::default(),
+                    },
+                );
+                Ok(())
+            })
+        });
+
+        let mut ahs = Vec::new();
+        let (new_session_tx_alice, _) = futures::channel::mpsc::channel(1024);
+        let (alice_sender, alice_handle) = mock_packet_planning(alice_transport);
+        ahs.extend(alice_mgr.start(alice_sender.clone(), new_session_tx_alice)?);
+
+        let (new_session_tx_bob, new_session_rx_bob) = futures::channel::mpsc::channel(1024);
+        let (bob_sender, bob_handle) = mock_packet_planning(bob_transport);
+        ahs.extend(bob_mgr.start(bob_sender.clone(), new_session_tx_bob)?);
+
+        let session_cfg = SessionClientConfig {
+            pseudonym: alice_pseudonym.into(),
+            capabilities: Capability::NoRateControl | Capability::Segmentation | Capability::Encryption,
+            surb_management: None,
+            ..Default::default()
+        };
+
+        // The key exchange cannot start without the packet key of the Exit
+        let res = alice_mgr
+            .new_session(
+                bob_peer,
+                SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
+                session_cfg.clone(),
+            )
+            .await;
+        assert!(
+            matches!(
+                res,
+                Err(TransportSessionError::Manager(
+                    SessionManagerError::MissingDestinationKey
+                ))
+            ),
+            "{res:?}"
+        );
+
+        // Encryption cannot be negotiated away
+        let res = alice_mgr
+            .new_session(
+                bob_peer,
+                SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
+                SessionClientConfig {
+                    optional_capabilities: Capability::Encryption.into(),
+                    destination_key: Some(*bob_packet_key.public()),
+                    ..session_cfg.clone()
+                },
+            )
+            .await;
+        assert!(
+            matches!(
+                res,
+                Err(TransportSessionError::Manager(SessionManagerError::OptionalEncryption))
+            ),
+            "{res:?}"
+        );
+
+        pin_mut!(new_session_rx_bob);
+        let (alice_session, bob_session) = timeout(
+            Duration::from_secs(2),
+            futures::future::join(
+                alice_mgr.new_session(
+                    bob_peer,
+                    SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
+                    SessionClientConfig {
+                        destination_key: Some(*bob_packet_key.public()),
+                        ..session_cfg
+                    },
+                ),
+                new_session_rx_bob.next(),
+            ),
+        )
+        .await?;
+
+        let mut alice_session = alice_session?;
+        let mut bob_session = bob_session.ok_or(anyhow!("bob must get an incoming session"))?;
+
+        let mut buf = [0u8; 11];
+        alice_session.write_all(b"hello world").await?;
+        alice_session.flush().await?;
+        timeout(Duration::from_secs(2), bob_session.session.read_exact(&mut buf)).await??;
+        assert_eq!(b"hello world", &buf);
+
+        bob_session.session.write_all(b"hello alice").await?;
+        bob_session.session.flush().await?;
+        timeout(Duration::from_secs(2), alice_session.read_exact(&mut buf)).await??;
+        assert_eq!(b"hello alice", &buf);
+
+        // No Session data leaves Alice in plain text
+        let alice_sent = alice_sent.lock().clone();
+        assert!(!alice_sent.is_empty());
+        assert!(
+            alice_sent
+                .iter()
+                .all(|data| !data.windows(buf.len()).any(|w| w == b"hello world"))
+        );
+        assert!(
+            alice_sent
+                .iter()
+                .all(|data| data.len() <= ApplicationData::PAYLOAD_SIZE)
+        );
+
+        futures::stream::iter(ahs)
+            .for_each(|ah| async move { ah.abort() })
+            .await;
+
+        alice_sender.close_channel();
+        bob_sender.close_channel();
+        let _ = alice_handle.await;
+        let _ = bob_handle.await;
+
+        Ok(())
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn session_manager_should_reject_encrypted_session_without_packet_key() -> anyhow::Result<()> {
+        let alice_pseudonym = HoprPseudonym::random();
+        let bob_peer: Address = (&ChainKeypair::random()).into();
+
+        let alice_mgr = SessionManager::new(Default::default());
+        let bob_mgr = SessionManager::new(Default::default());
+
+        let mut alice_transport = MockMsgSender::new();
+        let bob_mgr_clone = bob_mgr.clone();
+        alice_transport.expect_send_message().returning(move |_, data| {
+            let bob_mgr_clone = bob_mgr_clone.clone();
+            Box::pin(async move {
+                let _ = bob_mgr_clone.dispatch_message(
+                    alice_pseudonym,
+                    ApplicationDataIn {
+                        data: data.data,
+                        packet_info: Default::default(),
+                    },
+                );
+                Ok(())
+            })
+        });
+
+        let mut bob_transport = MockMsgSender::new();
+        let alice_mgr_clone = alice_mgr.clone();
+        bob_transport
+            .expect_send_message()
+            .once()
+            .withf(move |_, data| {
+                start_msg_match(data, |msg| {
+                    matches!(
+                        msg,
+                        HoprStartProtocol::SessionError(StartErrorType {
+                            reason: StartErrorReason::UnsupportedCapability,
+                            ..
+                        })
+                    )
+                })
+            })
+            .returning(move |_, data| {
+                let alice_mgr_clone = alice_mgr_clone.clone();
+                Box::pin(async move {
+                    let _ = alice_mgr_clone.dispatch_message(
+                        alice_pseudonym,
+                        ApplicationDataIn {
+                            data: data.data,
+                            packet_info: Default::default(),
+                        },
+                    );
+                    Ok(())
+                })
+            });
+
+        let mut ahs = Vec::new();
+        let (new_session_tx_alice, _) = futures::channel::mpsc::channel(1024);
+        let (alice_sender, alice_handle) = mock_packet_planning(alice_transport);
+        ahs.extend(alice_mgr.start(alice_sender.clone(), new_session_tx_alice)?);
+
+        let (new_session_tx_bob, _new_session_rx_bob) = futures::channel::mpsc::channel(1024);
+        let (bob_sender, bob_handle) = mock_packet_planning(bob_transport);
+        ahs.extend(bob_mgr.start(bob_sender.clone(), new_session_tx_bob)?);
+
+        let res = timeout(
+            Duration::from_secs(2),
+            alice_mgr.new_session(
+                bob_peer,
+                SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
+                SessionClientConfig {
+                    pseudonym: alice_pseudonym.into(),
+                    capabilities: Capability::NoRateControl | Capability::Segmentation | Capability::Encryption,
+                    surb_management: None,
+                    destination_key: Some(*OffchainKeypair::random().public()),
+                    ..Default::default()
+                },
+            ),
+        )
+        .await?;
+
+        assert!(
+            matches!(
+                res,
+                Err(TransportSessionError::Rejected(StartErrorReason::UnsupportedCapability))
+            ),
+            "{res:?}"
+        );
+        assert!(wait_for_no_active_sessions(&bob_mgr).await);
+
+        futures::stream::iter(ahs)
+            .for_each(|ah| async move { ah.abort() })
+            .await;
+
+        alice_sender.close_channel();
+        bob_sender.close_channel();
+        let _ = alice_handle.await;
+        let _ = bob_handle.await;
+
+        Ok(())
+    }
+
+    /// Connects Alice's and Bob's managers, optionally dropping some of the messages Alice sends.
+    fn connect_managers(
+        alice_pseudonym: HoprPseudonym,
+        alice_mgr: &SessionManager<UnboundedSender<(DestinationRouting, ApplicationDataOut)>>,
+        bob_mgr: &SessionManager<UnboundedSender<(DestinationRouting, ApplicationDataOut)>>,
+        drop_from_alice: impl Fn(&ApplicationDataOut) -> bool + Send + Sync + 'static,
+    ) -> (MockMsgSender, MockMsgSender) {
+        let mut alice_transport = MockMsgSender::new();
+        let bob_mgr_clone = bob_mgr.clone();
+        alice_transport.expect_send_message().returning(move |_, data| {
+            let bob_mgr_clone = bob_mgr_clone.clone();
+            let drop = drop_from_alice(&data);
+            Box::pin(async move {
+                if !drop {
+                    let _ = bob_mgr_clone.dispatch_message(
+                        alice_pseudonym,
+                        ApplicationDataIn {
+                            data: data.data,
+                            packet_info: Default::default(),
+                        },
+                    );
+                }
+                Ok(())
+            })
+        });
+
+        let mut bob_transport = MockMsgSender::new();
+        let alice_mgr_clone = alice_mgr.clone();
+        bob_transport.expect_send_message().returning(move |_, data| {
+            let alice_mgr_clone = alice_mgr_clone.clone();
+            Box::pin(async move {
+                let _ = alice_mgr_clone.dispatch_message(
+                    alice_pseudonym,
+                    ApplicationDataIn {
+                        data: data.data,
+                        packet_info: Default::default(),
+                    },
+                );
+                Ok(())
+            })
+        });
+
+        (alice_transport, bob_transport)
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn session_manager_should_negotiate_optional_capabilities() -> anyhow::Result<()> {
+        let alice_pseudonym = HoprPseudonym::random();
+        let bob_peer: Address = (&ChainKeypair::random()).into();
+
+        let alice_mgr = SessionManager::new(Default::default());
+        let bob_mgr = SessionManager::new(SessionManagerConfig {
+            supported_capabilities: Capabilities::full() - Capability::Resumption,
+            ..Default::default()
+        });
+
+        let (alice_transport, bob_transport) = connect_managers(alice_pseudonym, &alice_mgr, &bob_mgr, |_| false);
+
+        let mut ahs = Vec::new();
+        let (new_session_tx_alice, _) = futures::channel::mpsc::channel(1024);
+        let (alice_sender, alice_handle) = mock_packet_planning(alice_transport);
+        ahs.extend(alice_mgr.start(alice_sender.clone(), new_session_tx_alice)?);
+
+        let (new_session_tx_bob, new_session_rx_bob) = futures::channel::mpsc::channel(1024);
+        let (bob_sender, bob_handle) = mock_packet_planning(bob_transport);
+        ahs.extend(bob_mgr.start(bob_sender.clone(), new_session_tx_bob)?);
+
+        pin_mut!(new_session_rx_bob);
+        let (alice_session, bob_session) = timeout(
+            Duration::from_secs(2),
+            futures::future::join(
+                alice_mgr.new_session(
+                    bob_peer,
+                    SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
+                    SessionClientConfig {
+                        pseudonym: alice_pseudonym.into(),
+                        capabilities: Capability::NoRateControl | Capability::Segmentation | Capability::Resumption,
+                        optional_capabilities: Capability::Resumption.into(),
+                        surb_management: None,
+                        ..Default::default()
+                    },
+                ),
+                new_session_rx_bob.next(),
+            ),
+        )
+        .await?;
+
+        let alice_session = alice_session?;
+        let bob_session = bob_session.ok_or(anyhow!("bob must get an incoming session"))?;
+
+        let agreed: Capabilities = Capability::NoRateControl | Capability::Segmentation;
+        assert_eq!(agreed, alice_session.config().capabilities);
+        assert_eq!(agreed, bob_session.session.config().capabilities);
+
+        // Both sides use the newest Session protocol version
+        assert_eq!(SESSION_PROTOCOL_VERSION, alice_session.config().protocol_version);
+        assert_eq!(SESSION_PROTOCOL_VERSION, bob_session.session.config().protocol_version);
+
+        // Bob issued no resume ticket, because Resumption was left out
+        let res = alice_mgr
+            .resume_session(
+                alice_session.id(),
+                RoutingOptions::Hops(0_u32.try_into()?),
+                RoutingOptions::Hops(0_u32.try_into()?),
+            )
+            .await;
+        assert!(matches!(
+            res,
+            Err(TransportSessionError::Manager(SessionManagerError::Other(_)))
+        ));
+
+        futures::stream::iter(ahs)
+            .for_each(|ah| async move { ah.abort() })
+            .await;
+
+        alice_sender.close_channel();
+        bob_sender.close_channel();
+        let _ = alice_handle.await;
+        let _ = bob_handle.await;
+
+        Ok(())
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn session_manager_should_reject_session_requiring_unsupported_capability() -> anyhow::Result<()> {
+        let alice_pseudonym = HoprPseudonym::random();
+        let bob_peer: Address = (&ChainKeypair::random()).into();
+
+        let alice_mgr = SessionManager::new(Default::default());
+        let bob_mgr = SessionManager::new(SessionManagerConfig {
+            supported_capabilities: Capabilities::full() - Capability::Resumption,
+            ..Default::default()
+        });
+
+        let (alice_transport, bob_transport) = connect_managers(alice_pseudonym, &alice_mgr, &bob_mgr, |_| false);
+
+        let mut ahs = Vec::new();
+        let (new_session_tx_alice, _) = futures::channel::mpsc::channel(1024);
+        let (alice_sender, alice_handle) = mock_packet_planning(alice_transport);
+        ahs.extend(alice_mgr.start(alice_sender.clone(), new_session_tx_alice)?);
+
+        let (new_session_tx_bob, _new_session_rx_bob) = futures::channel::mpsc::channel(1024);
+        let (bob_sender, bob_handle) = mock_packet_planning(bob_transport);
+        ahs.extend(bob_mgr.start(bob_sender.clone(), new_session_tx_bob)?);
+
+        // Only the NoDelay capability is optional, Resumption is required
+        let res = timeout(
+            Duration::from_secs(2),
+            alice_mgr.new_session(
+                bob_peer,
+                SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
+                SessionClientConfig {
+                    pseudonym: alice_pseudonym.into(),
+                    capabilities: Capability::Segmentation | Capability::NoDelay | Capability::Resumption,
+                    optional_capabilities: Capability::NoDelay.into(),
+                    surb_management: None,
+                    ..Default::default()
+                },
+            ),
+        )
+        .await?;
+
+        assert!(
+            matches!(
+                res,
+                Err(TransportSessionError::Rejected(StartErrorReason::UnsupportedCapability))
+            ),
+            "{res:?}"
+        );
+        assert!(wait_for_no_active_sessions(&bob_mgr).await);
+
+        futures::stream::iter(ahs)
+            .for_each(|ah| async move { ah.abort() })
+            .await;
+
+        alice_sender.close_channel();
+        bob_sender.close_channel();
+        let _ = alice_handle.await;
+        let _ = bob_handle.await;
+
+        Ok(())
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn session_manager_should_rate_limit_initiations_of_a_pseudonym() -> anyhow::Result<()> {
+        let alice_pseudonym = HoprPseudonym::random();
+        let bob_peer: Address = (&ChainKeypair::random()).into();
+
+        let alice_mgr = SessionManager::new(Default::default());
+        let bob_mgr = SessionManager::new(SessionManagerConfig {
+            admission: SessionAdmissionConfig {
+                per_pseudonym_rate: Some(crate::InitiationRateLimit::new(1, Duration::from_secs(60))),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        let (alice_transport, bob_transport) = connect_managers(alice_pseudonym, &alice_mgr, &bob_mgr, |_| false);
+
+        let mut ahs = Vec::new();
+        let (new_session_tx_alice, _) = futures::channel::mpsc::channel(1024);
+        let (alice_sender, alice_handle) = mock_packet_planning(alice_transport);
+        ahs.extend(alice_mgr.start(alice_sender.clone(), new_session_tx_alice)?);
+
+        let (new_session_tx_bob, _new_session_rx_bob) = futures::channel::mpsc::channel(1024);
+        let (bob_sender, bob_handle) = mock_packet_planning(bob_transport);
+        ahs.extend(bob_mgr.start(bob_sender.clone(), new_session_tx_bob)?);
+
+        let client_cfg = SessionClientConfig {
+            pseudonym: alice_pseudonym.into(),
+            capabilities: Capability::NoRateControl | Capability::Segmentation,
+            surb_management: None,
+            ..Default::default()
+        };
+        let target = SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?));
+
+        let first = timeout(
+            Duration::from_secs(2),
+            alice_mgr.new_session(bob_peer, target.clone(), client_cfg.clone()),
+        )
+        .await??;
+
+        let res = timeout(
+            Duration::from_secs(2),
+            alice_mgr.new_session(bob_peer, target, client_cfg),
+        )
+        .await?;
+        assert!(
+            matches!(res, Err(TransportSessionError::Rejected(StartErrorReason::RateLimited))),
+            "{res:?}"
+        );
+
+        // The rejected initiation did not supersede the existing Session
+        assert_eq!(vec![*first.id()], bob_mgr.active_sessions());
+
+        futures::stream::iter(ahs)
+            .for_each(|ah| async move { ah.abort() })
+            .await;
+
+        alice_sender.close_channel();
+        bob_sender.close_channel();
+        let _ = alice_handle.await;
+        let _ = bob_handle.await;
+
+        Ok(())
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn session_manager_should_enforce_target_quotas_and_admission_policy() -> anyhow::Result<()> {
+        let alice_pseudonym = HoprPseudonym::random();
+        let bob_peer: Address = (&ChainKeypair::random()).into();
+
+        let alice_mgr = SessionManager::new(Default::default());
+        let bob_mgr = SessionManager::new(SessionManagerConfig {
+            idle_timeout: Duration::from_millis(200),
+            admission: SessionAdmissionConfig {
+                max_udp_sessions_per_host: Some(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_admission_policy(|request: &SessionAdmissionRequest<'_>| match request.target {
+            SessionTarget::TcpStream(_) => Err(StartErrorReason::TargetRejected),
+            _ => Ok(()),
+        });
+
+        let (alice_transport, bob_transport) = connect_managers(alice_pseudonym, &alice_mgr, &bob_mgr, |_| false);
+
+        let mut ahs = Vec::new();
+        let (new_session_tx_alice, _) = futures::channel::mpsc::channel(1024);
+        let (alice_sender, alice_handle) = mock_packet_planning(alice_transport);
+        ahs.extend(alice_mgr.start(alice_sender.clone(), new_session_tx_alice)?);
+
+        let (new_session_tx_bob, _new_session_rx_bob) = futures::channel::mpsc::channel(1024);
+        let (bob_sender, bob_handle) = mock_packet_planning(bob_transport);
+        ahs.extend(bob_mgr.start(bob_sender.clone(), new_session_tx_bob)?);
+
+        let client_cfg = SessionClientConfig {
+            pseudonym: alice_pseudonym.into(),
+            capabilities: Capability::NoRateControl | Capability::Segmentation,
+            surb_management: None,
+            ..Default::default()
         };
 
-        debug!(?pseudonym, ?session_req, "assigned a new session");
+        let res = timeout(
+            Duration::from_secs(2),
+            alice_mgr.new_session(
+                bob_peer,
+                SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
+                client_cfg.clone(),
+            ),
+        )
+        .await?;
+        assert!(
+            matches!(
+                res,
+                Err(TransportSessionError::Rejected(StartErrorReason::TargetRejected))
+            ),
+            "{res:?}"
+        );
+        assert!(bob_mgr.active_sessions().is_empty());
 
-        let closure_notifier = Box::new(move |session_id: SessionId, reason: ClosureReason| {
-            if let Err(error) = close_session_notifier.try_send((session_id, reason)) {
-                error!(%session_id, %error, %reason, "failed to notify session closure");
+        let _session = timeout(
+            Duration::from_secs(2),
+            alice_mgr.new_session(
+                bob_peer,
+                SessionTarget::UdpStream(SealedHost::Plain("127.0.0.1:53".parse()?)),
+                client_cfg,
+            ),
+        )
+        .await??;
+        assert_eq!(1, bob_mgr.admission.quotas_in_use());
+
+        // The quota is released once the Session is closed at the Exit
+        assert!(wait_for_no_active_sessions(&bob_mgr).await);
+        assert_eq!(0, bob_mgr.admission.quotas_in_use());
+
+        futures::stream::iter(ahs)
+            .for_each(|ah| async move { ah.abort() })
+            .await;
+
+        alice_sender.close_channel();
+        bob_sender.close_channel();
+        let _ = alice_handle.await;
+        let _ = bob_handle.await;
+
+        Ok(())
+    }
+
+    struct RejectingService;
+
+    impl SessionService for RejectingService {
+        fn accept(&self, _: &SessionAdmissionRequest<'_>) -> Result<(), StartErrorReason> {
+            Err(StartErrorReason::TargetRejected)
+        }
+
+        fn serve(&self, _: IncomingSession) -> BoxFuture<'static, std::io::Result<()>> {
+            Box::pin(futures::future::ok(()))
+        }
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn session_manager_should_hand_sessions_over_to_registered_services() -> anyhow::Result<()> {
+        let alice_pseudonym = HoprPseudonym::random();
+        let bob_peer: Address = (&ChainKeypair::random()).into();
+
+        let alice_mgr = SessionManager::new(Default::default());
+        let bob_mgr = SessionManager::new(Default::default());
+
+        bob_mgr.register_service(1, crate::EchoService)?;
+        bob_mgr.register_service(2, RejectingService)?;
+        assert!(matches!(
+            bob_mgr.register_service(1, crate::EchoService),
+            Err(TransportSessionError::Manager(
+                SessionManagerError::ServiceAlreadyRegistered(1)
+            ))
+        ));
+
+        let (alice_transport, bob_transport) = connect_managers(alice_pseudonym, &alice_mgr, &bob_mgr, |_| false);
+
+        let mut ahs = Vec::new();
+        let (new_session_tx_alice, _) = futures::channel::mpsc::channel(1024);
+        let (alice_sender, alice_handle) = mock_packet_planning(alice_transport);
+        ahs.extend(alice_mgr.start(alice_sender.clone(), new_session_tx_alice)?);
+
+        let (new_session_tx_bob, mut new_session_rx_bob) = futures::channel::mpsc::channel(1024);
+        let (bob_sender, bob_handle) = mock_packet_planning(bob_transport);
+        ahs.extend(bob_mgr.start(bob_sender.clone(), new_session_tx_bob)?);
+
+        let client_cfg = SessionClientConfig {
+            pseudonym: alice_pseudonym.into(),
+            capabilities: Capability::NoRateControl | Capability::Segmentation,
+            surb_management: None,
+            ..Default::default()
+        };
+
+        for (service, reason) in [
+            (2, StartErrorReason::TargetRejected),
+            (3, StartErrorReason::UnknownService),
+        ] {
+            let res = timeout(
+                Duration::from_secs(2),
+                alice_mgr.new_session(bob_peer, SessionTarget::ExitNode(service), client_cfg.clone()),
+            )
+            .await?;
+            assert!(
+                matches!(res, Err(TransportSessionError::Rejected(r)) if r == reason),
+                "{res:?}"
+            );
+        }
+
+        let mut alice_session = timeout(
+            Duration::from_secs(2),
+            alice_mgr.new_session(bob_peer, SessionTarget::ExitNode(1), client_cfg),
+        )
+        .await??;
+
+        let mut buf = [0u8; 11];
+        alice_session.write_all(b"hello world").await?;
+        alice_session.flush().await?;
+        timeout(Duration::from_secs(2), alice_session.read_exact(&mut buf)).await??;
+        assert_eq!(b"hello world", &buf);
+
+        // The Session was served by the service and never reached the incoming Session stream
+        assert!(new_session_rx_bob.try_recv().is_err());
+
+        futures::stream::iter(ahs)
+            .for_each(|ah| async move { ah.abort() })
+            .await;
+
+        alice_sender.close_channel();
+        bob_sender.close_channel();
+        let _ = alice_handle.await;
+        let _ = bob_handle.await;
+
+        Ok(())
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn session_manager_should_retry_with_required_capabilities_if_not_negotiated() -> anyhow::Result<()> {
+        let alice_pseudonym = HoprPseudonym::random();
+        let bob_peer: Address = (&ChainKeypair::random()).into();
+
+        let alice_mgr = SessionManager::new(SessionManagerConfig {
+            initiation_timeout_base: Duration::from_millis(100),
+            ..Default::default()
+        });
+        let bob_mgr = SessionManager::new(Default::default());
+
+        // Bob behaves as a node on an older protocol version that cannot decode negotiated initiations
+        let negotiated_initiations = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let drop_initiations = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let (negotiated_initiations_clone, drop_initiations_clone) =
+            (negotiated_initiations.clone(), drop_initiations.clone());
+        let (alice_transport, bob_transport) = connect_managers(alice_pseudonym, &alice_mgr, &bob_mgr, move |data| {
+            if !msg_type(data, StartProtocolDiscriminants::StartSession) {
+                false
+            } else if data.data.plain_text[0] > HoprStartProtocol::MIN_START_PROTOCOL_VERSION {
+                negotiated_initiations_clone.fetch_add(1, Ordering::Relaxed);
+                true
+            } else {
+                drop_initiations_clone.load(Ordering::Relaxed)
             }
         });
 
-        let session = if !session_req.capabilities.0.contains(Capability::NoRateControl) {
-            // Because of SURB scarcity, control the egress rate of incoming sessions
-            let egress_rate_control =
-                RateController::new(self.cfg.initial_return_session_egress_rate, Duration::from_secs(1));
+        let mut ahs = Vec::new();
+        let (new_session_tx_alice, _) = futures::channel::mpsc::channel(1024);
+        let (alice_sender, alice_handle) = mock_packet_planning(alice_transport);
+        ahs.extend(alice_mgr.start(alice_sender.clone(), new_session_tx_alice)?);
 
-            // The Session request carries a "hint" as additional data telling what
-            // the Session initiator has configured as its target buffer size in the Balancer.
-            let target_surb_buffer_size = if session_req.additional_data > 0 {
-                (session_req.additional_data as u64).min(self.cfg.maximum_surb_buffer_size as u64)
-            } else {
-                self.cfg.initial_return_session_egress_rate as u64
-                    * self
-                        .cfg
-                        .minimum_surb_buffer_duration
-                        .max(MIN_SURB_BUFFER_DURATION)
-                        .as_secs()
-            };
+        let (new_session_tx_bob, new_session_rx_bob) = futures::channel::mpsc::channel(1024);
+        let (bob_sender, bob_handle) = mock_packet_planning(bob_transport);
+        ahs.extend(bob_mgr.start(bob_sender.clone(), new_session_tx_bob)?);
 
-            let surb_estimator_clone = slot.surb_estimator.clone();
-            let session = HoprSession::new(
-                session_id,
-                reply_routing.clone(),
-                session_config(&self.cfg, session_req.capabilities.into()),
-                (
-                    // Sent packets = SURB consumption estimate
-                    msg_sender
-                        .clone()
-                        .with(move |(routing, data): (DestinationRouting, ApplicationDataOut)| {
-                            // Each outgoing packet consumes one SURB
-                            surb_estimator_clone
-                                .consumed
-                                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-                            #[cfg(feature = "telemetry")]
-                            crate::telemetry::record_session_surb_consumed(&session_id, 1);
-                            futures::future::ok::<_, S::Error>((routing, data))
-                        })
-                        .rate_limit_with_controller(&egress_rate_control)
-                        .buffer((2 * target_surb_buffer_size) as usize),
-                    // Received packets = SURB retrieval estimate
-                    session_rx.inspect(move |data| {
-                        let produced = data.num_surbs_with_msg() as u64;
-                        // Count the number of SURBs delivered with each incoming packet
-                        surb_estimator_clone
-                            .produced
-                            .fetch_add(produced, std::sync::atomic::Ordering::Relaxed);
-                        #[cfg(feature = "telemetry")]
-                        crate::telemetry::record_session_surb_produced(&session_id, produced);
-                    }),
+        let client_cfg = SessionClientConfig {
+            pseudonym: alice_pseudonym.into(),
+            capabilities: Capability::NoRateControl | Capability::Segmentation | Capability::NoDelay,
+            optional_capabilities: Capability::NoDelay.into(),
+            surb_management: None,
+            ..Default::default()
+        };
+
+        // A single timed out negotiated initiation could have been lost, so Bob is remembered
+        // not to negotiate only after repeated timeouts
+        pin_mut!(new_session_rx_bob);
+        for attempt in 1..=NEGOTIATION_TIMEOUTS_BEFORE_FALLBACK as usize {
+            let (alice_session, bob_session) = timeout(
+                Duration::from_secs(2),
+                futures::future::join(
+                    alice_mgr.new_session(
+                        bob_peer,
+                        SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
+                        client_cfg.clone(),
+                    ),
+                    new_session_rx_bob.next(),
                 ),
-                Some(closure_notifier),
-            )?;
+            )
+            .await?;
 
-            // The SURB balancer will start intervening by rate-limiting the
-            // egress of the Session, once the estimated number of SURBs drops below
-            // the target defined here. Otherwise, the maximum egress is allowed.
-            let balancer_config = SurbBalancerConfig {
-                target_surb_buffer_size,
-                // At maximum egress, the SURB buffer drains in `minimum_surb_buffer_duration` seconds
-                max_surbs_per_sec: target_surb_buffer_size / self.cfg.minimum_surb_buffer_duration.as_secs(),
-                // No SURB decay at the Exit, since we know almost exactly how many SURBs
-                // were received
-                surb_decay: None,
-            };
+            let mut alice_session = alice_session?;
+            let bob_session = bob_session.ok_or(anyhow!("bob must get an incoming session"))?;
 
-            slot.surb_mgmt.update(&balancer_config);
+            let required: Capabilities = Capability::NoRateControl | Capability::Segmentation;
+            assert_eq!(required, alice_session.config().capabilities);
+            assert_eq!(required, bob_session.session.config().capabilities);
 
-            // Spawn the SURB balancer only once we know we have registered the
-            // abort handle with the pre-allocated Session slot
-            debug!(%session_id, ?balancer_config ,"spawning exit SURB balancer");
-            let balancer = SurbBalancer::new(
-                session_id,
-                SimpleBalancerController::default(),
-                slot.surb_estimator.clone(),
-                SurbControllerWithCorrection(egress_rate_control, 1), // 1 SURB per egress packet
-                slot.surb_mgmt.clone(),
+            // Without negotiation, both sides fall back to the oldest Session protocol version
+            assert_eq!(MIN_SESSION_PROTOCOL_VERSION, alice_session.config().protocol_version);
+            assert_eq!(
+                MIN_SESSION_PROTOCOL_VERSION,
+                bob_session.session.config().protocol_version
             );
+            assert_eq!(attempt, negotiated_initiations.load(Ordering::Relaxed));
 
-            // Assign the SURB balancer and abort handles to the already allocated Session slot
-            let (_, balancer_abort_handle) = balancer.start_control_loop(self.cfg.balancer_sampling_interval);
-            slot.abort_handles
-                .lock()
-                .insert(SessionHandles::Balancer, balancer_abort_handle);
+            // The next attempt uses the same pseudonym, so this Session must be gone first
+            alice_session.close().await?;
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
 
-            // Spawn a keep-alive stream notifying about the SURB buffer level towards the Entry
-            if let Some(period) = self.cfg.surb_balance_notify_period {
-                let surb_estimator_clone = slot.surb_estimator.clone();
-                let (ka_controller, ka_abort_handle) = utils::spawn_keep_alive_stream(
-                    session_id,
-                    // Sent Keep-Alive packets also contribute to SURB consumption
-                    msg_sender
-                        .clone()
-                        .with(move |(routing, data): (DestinationRouting, ApplicationDataOut)| {
-                            // Each sent keepalive consumes 1 SURB
-                            surb_estimator_clone
-                                .consumed
-                                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-                            #[cfg(feature = "telemetry")]
-                            crate::telemetry::record_session_surb_consumed(&session_id, 1);
-                            futures::future::ok::<_, S::Error>((routing, data))
-                        }),
-                    slot.routing_opts.clone(),
-                    SurbNotificationMode::Level(slot.surb_estimator.clone()),
-                    slot.surb_mgmt.clone(),
-                );
+        // Bob is known not to negotiate, so the next initiation does not try to negotiate first
+        drop_initiations.store(true, Ordering::Relaxed);
+        let result = timeout(
+            Duration::from_secs(2),
+            alice_mgr.new_session(
+                bob_peer,
+                SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
+                client_cfg,
+            ),
+        )
+        .await?;
+        assert!(matches!(result, Err(TransportSessionError::Timeout)));
+        assert_eq!(
+            NEGOTIATION_TIMEOUTS_BEFORE_FALLBACK as usize,
+            negotiated_initiations.load(Ordering::Relaxed)
+        );
 
-                // Start keepalive stream towards the Entry with a predefined period
-                hopr_utils::runtime::prelude::spawn(async move {
-                    // Delay the stream execution by one period
-                    hopr_utils::runtime::prelude::sleep(period).await;
-                    ka_controller.set_rate_per_unit(1, period);
-                });
+        futures::stream::iter(ahs)
+            .for_each(|ah| async move { ah.abort() })
+            .await;
 
-                slot.abort_handles
-                    .lock()
-                    .insert(SessionHandles::KeepAlive, ka_abort_handle);
+        alice_sender.close_channel();
+        bob_sender.close_channel();
+        let _ = alice_handle.await;
+        let _ = bob_handle.await;
 
-                debug!(%session_id, ?period, "started SURB level-notifying keep-alive stream");
-            }
+        Ok(())
+    }
 
-            session
-        } else {
-            HoprSession::new(
-                session_id,
-                reply_routing.clone(),
-                session_config(&self.cfg, session_req.capabilities.into()),
-                (msg_sender.clone(), session_rx),
-                Some(closure_notifier),
-            )?
-        };
+    #[test_log::test(tokio::test)]
+    async fn session_manager_should_not_retry_without_negotiation_if_destination_negotiates() -> anyhow::Result<()> {
+        let alice_pseudonym = HoprPseudonym::random();
+        let bob_peer: Address = (&ChainKeypair::random()).into();
 
-        // Extract useful information about the session from the Start protocol message
-        let incoming_session = IncomingSession {
-            id: session_id,
-            session,
-            target: session_req.target,
-        };
+        let alice_mgr = SessionManager::new(SessionManagerConfig {
+            initiation_timeout_base: Duration::from_millis(100),
+            ..Default::default()
+        });
+        let bob_mgr = SessionManager::new(Default::default());
 
-        // Notify that a new incoming session has been created. Lock the sink and send
-        // directly into it, so no extra forwarding task between channels is needed.
-        match async {
-            let mut guard = new_session_notifier.lock().await;
-            guard.send(incoming_session).await
-        }
-        .timeout(futures_time::time::Duration::from(EXTERNAL_SEND_TIMEOUT))
-        .await
-        {
-            Err(_) => {
-                error!(%session_id, "timeout to notify about new incoming session");
-                return Err(TransportSessionError::Timeout);
+        // Once Bob has negotiated a Session, an attacker starts dropping the initiations
+        let legacy_initiations = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let drop_initiations = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let (legacy_initiations_clone, drop_initiations_clone) = (legacy_initiations.clone(), drop_initiations.clone());
+        let (alice_transport, bob_transport) = connect_managers(alice_pseudonym, &alice_mgr, &bob_mgr, move |data| {
+            if !msg_type(data, StartProtocolDiscriminants::StartSession) {
+                return false;
             }
-            Ok(Err(error)) => {
-                error!(%session_id, %error, "failed to notify about new incoming session");
-                return Err(SessionManagerError::other(error).into());
+            if data.data.plain_text[0] == HoprStartProtocol::MIN_START_PROTOCOL_VERSION {
+                legacy_initiations_clone.fetch_add(1, Ordering::Relaxed);
             }
-            _ => {}
-        };
+            drop_initiations_clone.load(Ordering::Relaxed)
+        });
 
-        trace!(?session_id, "session notification sent");
+        let mut ahs = Vec::new();
+        let (new_session_tx_alice, _) = futures::channel::mpsc::channel(1024);
+        let (alice_sender, alice_handle) = mock_packet_planning(alice_transport);
+        ahs.extend(alice_mgr.start(alice_sender.clone(), new_session_tx_alice)?);
 
-        // Notify the sender that the session has been established.
-        // Set our peer ID in the session ID sent back to them.
-        let data = HoprStartProtocol::SessionEstablished(StartEstablished {
-            orig_challenge: session_req.challenge,
-            session_id,
-        });
+        let (new_session_tx_bob, new_session_rx_bob) = futures::channel::mpsc::channel(1024);
+        let (bob_sender, bob_handle) = mock_packet_planning(bob_transport);
+        ahs.extend(bob_mgr.start(bob_sender.clone(), new_session_tx_bob)?);
 
-        send_via_msg_sender(
-            &mut msg_sender,
-            reply_routing.clone(),
-            data,
-            "session establishment message",
+        let client_cfg = SessionClientConfig {
+            pseudonym: alice_pseudonym.into(),
+            capabilities: Capability::NoRateControl | Capability::Segmentation | Capability::NoDelay,
+            optional_capabilities: Capability::NoDelay.into(),
+            surb_management: None,
+            ..Default::default()
+        };
+
+        pin_mut!(new_session_rx_bob);
+        let (alice_session, bob_session) = timeout(
+            Duration::from_secs(2),
+            futures::future::join(
+                alice_mgr.new_session(
+                    bob_peer,
+                    SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
+                    client_cfg.clone(),
+                ),
+                new_session_rx_bob.next(),
+            ),
         )
         .await?;
+        let alice_session = alice_session?;
+        bob_session.ok_or(anyhow!("bob must get an incoming session"))?;
+        assert_eq!(SESSION_PROTOCOL_VERSION, alice_session.config().protocol_version);
 
-        #[cfg(feature = "telemetry")]
-        initialize_session_telemetry(
-            session_id,
-            &self.cfg,
-            session_req.capabilities.0,
-            Some(&slot.surb_estimator),
-            Some(&slot.surb_mgmt),
-        );
-
-        info!(%session_id, "new session established");
+        // The initiation is not downgraded to one without negotiation
+        drop_initiations.store(true, Ordering::Relaxed);
+        let result = timeout(
+            Duration::from_secs(2),
+            alice_mgr.new_session(
+                bob_peer,
+                SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
+                client_cfg,
+            ),
+        )
+        .await?;
+        assert!(matches!(result, Err(TransportSessionError::Timeout)));
+        assert_eq!(0, legacy_initiations.load(Ordering::Relaxed));
 
-        #[cfg(all(feature = "telemetry", not(test)))]
-        METRIC_NUM_ESTABLISHED_SESSIONS.increment();
+        futures::stream::iter(ahs)
+            .for_each(|ah| async move { ah.abort() })
+            .await;
 
-        slot_guard.commit();
-        Ok(())
-    }
+        alice_sender.close_channel();
+        bob_sender.close_channel();
+        let _ = alice_handle.await;
+        let _ = bob_handle.await;
 
-    async fn handle_session_established(&self, est: StartEstablished<SessionId>) -> crate::errors::Result<()> {
-        trace!(
-            session_id = ?est.session_id,
-            "received session establishment confirmation"
-        );
-        let challenge = est.orig_challenge;
-        let session_id = est.session_id;
-        if let Some(tx_est) = self.session_initiations.remove(&est.orig_challenge) {
-            if let Err(error) = tx_est.try_send(Ok(est)) {
-                error!(%challenge, %session_id, %error, "failed to send session establishment confirmation");
-                return Err(SessionManagerError::other(error).into());
-            }
-            debug!(?session_id, challenge, "session establishment complete");
-        } else {
-            error!(%session_id, challenge, "unknown session establishment attempt or expired");
-        }
         Ok(())
     }
 
-    async fn handle_session_error(&self, error_type: StartErrorType) -> crate::errors::Result<()> {
-        trace!(
-            challenge = error_type.challenge,
-            error = ?error_type.reason,
-            "failed to initialize a session",
-        );
-        // Currently, we do not distinguish between individual error types
-        // and just discard the initiation attempt and pass on the error.
-        if let Some(tx_est) = self.session_initiations.remove(&error_type.challenge) {
-            if let Err(error) = tx_est.try_send(Err(error_type)) {
-                error!(%error, ?error_type, "could not send session error message");
-                return Err(SessionManagerError::other(error).into());
-            }
-            error!(
-                challenge = error_type.challenge,
-                ?error_type,
-                "session establishment error received"
-            );
-        } else {
-            error!(
-                challenge = error_type.challenge,
-                ?error_type,
-                "session establishment attempt expired before error could be delivered"
-            );
-        }
+    #[test_log::test(tokio::test)]
+    async fn session_manager_should_resume_session_with_ticket_after_entry_restart() -> anyhow::Result<()> {
+        let alice_pseudonym = HoprPseudonym::random();
+        let bob_peer: Address = (&ChainKeypair::random()).into();
 
-        #[cfg(all(feature = "telemetry", not(test)))]
-        METRIC_RECEIVED_SESSION_ERRS.increment(&[&error_type.reason.to_string()]);
+        let bob_mgr = SessionManager::new(Default::default());
+        let alice_transport = || {
+            let mut alice_transport = MockMsgSender::new();
+            let bob_mgr_clone = bob_mgr.clone();
+            alice_transport.expect_send_message().returning(move |_, data| {
+                let bob_mgr_clone = bob_mgr_clone.clone();
+                Box::pin(async move {
+                    let _ = bob_mgr_clone.dispatch_message(
+                        alice_pseudonym,
+                        ApplicationDataIn {
+                            data: data.data,
+                            packet_info: Default::default(),
+                        },
+                    );
+                    Ok(())
+                })
+            });
+            alice_transport
+        };
 
-        Ok(())
-    }
+        // Bob always replies to the current Entry manager of Alice
+        let alice_mgr = SessionManager::new(Default::default());
+        let current_alice_mgr = Arc::new(parking_lot::RwLock::new(alice_mgr.clone()));
+        let current_alice_mgr_clone = current_alice_mgr.clone();
+        let mut bob_transport = MockMsgSender::new();
+        bob_transport.expect_send_message().returning(move |_, data| {
+            let alice_mgr_clone = current_alice_mgr_clone.read().clone();
+            Box::pin(async move {
+                let _ = alice_mgr_clone.dispatch_message(
+                    alice_pseudonym,
+                    ApplicationDataIn {
+                        data: data.data,
+                        packet_info: Default::default(),
+                    },
+                );
+                Ok(())
+            })
+        });
 
-    async fn handle_keep_alive(&self, msg: KeepAliveMessage<SessionId>) -> crate::errors::Result<()> {
-        let session_id = msg.session_id;
-        if let Some(session_slot) = self.sessions.get(&session_id) {
-            trace!(?session_id, "received keep-alive message");
-            match &session_slot.routing_opts {
-                // Session is outgoing - keep-alive was received from the Exit
-                DestinationRouting::Forward { .. } => {
-                    if msg.flags.contains(KeepAliveFlag::BalancerState)
-                        && !session_slot.surb_mgmt.is_disabled()
-                        && session_slot.surb_mgmt.buffer_level() != msg.additional_data
-                    {
-                        // Update the buffer level as sent to us from the Exit
-                        session_slot
-                            .surb_mgmt
-                            .buffer_level
-                            .store(msg.additional_data, std::sync::atomic::Ordering::Relaxed);
-                        debug!(%session_id, surb_level = msg.additional_data, "keep-alive updated SURB buffer size from the Exit");
-                    }
+        let mut alice_ahs = Vec::new();
+        let (new_session_tx_alice, _) = futures::channel::mpsc::channel(1024);
+        let (alice_sender, alice_handle) = mock_packet_planning(alice_transport());
+        alice_ahs.extend(alice_mgr.start(alice_sender.clone(), new_session_tx_alice)?);
 
-                    // Increase the number of consumed SURBs in the estimator
-                    session_slot
-                        .surb_estimator
-                        .consumed
-                        .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-                    #[cfg(feature = "telemetry")]
-                    crate::telemetry::record_session_surb_consumed(&session_id, 1);
-                }
-                // Session is incoming - keep-alive was received from the Entry
-                DestinationRouting::Return(_) => {
-                    // Allow updating SURB balancer target based on the received Keep-Alive message
-                    if msg.flags.contains(KeepAliveFlag::BalancerTarget)
-                        && msg.additional_data > 0
-                        && !session_slot.surb_mgmt.is_disabled()
-                        && session_slot.surb_mgmt.controller_bounds().target() != msg.additional_data
-                    {
-                        // Update the target buffer size as sent to us from the Entry
-                        session_slot
-                            .surb_mgmt
-                            .target_surb_buffer_size
-                            .store(msg.additional_data, std::sync::atomic::Ordering::Relaxed);
-                        // Update maximum SURBs per second based on the new target
-                        session_slot.surb_mgmt.max_surbs_per_sec.store(
-                            msg.additional_data / self.cfg.minimum_surb_buffer_duration.as_secs(),
-                            std::sync::atomic::Ordering::Relaxed,
-                        );
-                        debug!(%session_id, target_surb_buffer_size = msg.additional_data, "keep-alive updated SURB balancer target buffer size from the Entry");
-                    }
+        let mut ahs = Vec::new();
+        let (new_session_tx_bob, new_session_rx_bob) = futures::channel::mpsc::channel(1024);
+        let (bob_sender, bob_handle) = mock_packet_planning(bob_transport);
+        ahs.extend(bob_mgr.start(bob_sender.clone(), new_session_tx_bob)?);
 
-                    // Increase the number of received SURBs in the estimator.
-                    // Typically, 2 SURBs per Keep-Alive message
-                    let produced = KeepAliveMessage::<SessionId>::MIN_SURBS_PER_MESSAGE as u64;
-                    session_slot
-                        .surb_estimator
-                        .produced
-                        .fetch_add(produced, std::sync::atomic::Ordering::Relaxed);
-                    #[cfg(feature = "telemetry")]
-                    crate::telemetry::record_session_surb_produced(&session_id, produced);
-                }
-            }
-        } else {
-            debug!(%session_id, "received keep-alive request for an unknown session");
-        }
-        Ok(())
-    }
-}
+        let client_cfg = SessionClientConfig {
+            pseudonym: alice_pseudonym.into(),
+            capabilities: Capability::NoRateControl | Capability::Resumption,
+            surb_management: None,
+            ..Default::default()
+        };
 
-#[cfg(test)]
-mod tests {
-    use anyhow::{Context, anyhow};
-    use futures::{AsyncWriteExt, channel::mpsc::UnboundedSender, future::BoxFuture, pin_mut};
-    use hopr_api::types::{
-        crypto::{keypairs::ChainKeypair, prelude::Keypair},
-        crypto_random::Randomizable,
-        internal::routing::SurbMatcher,
-        primitive::prelude::Address,
-    };
-    use hopr_protocol_start::{StartProtocol, StartProtocolDiscriminants};
-    use hopr_utils::network_types::prelude::SealedHost;
-    use moka::future::FutureExt;
-    use tokio::time::timeout;
+        pin_mut!(new_session_rx_bob);
+        let (alice_session, bob_session) = timeout(
+            Duration::from_secs(2),
+            futures::future::join(
+                alice_mgr.new_session(
+                    bob_peer,
+                    SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
+                    client_cfg.clone(),
+                ),
+                new_session_rx_bob.next(),
+            ),
+        )
+        .await?;
 
-    use super::*;
-    use crate::{Capabilities, balancer::SurbBalancerConfig, types::SessionTarget};
+        let mut alice_session = alice_session?;
+        let mut bob_session = bob_session.ok_or(anyhow!("bob must get an incoming session"))?;
+        let session_id = *alice_session.id();
+        let ticket = alice_session
+            .resume_ticket()
+            .ok_or(anyhow!("resumable session must carry a ticket"))?;
+
+        let mut buf = [0u8; 5];
+        alice_session.write_all(b"hello").await?;
+        alice_session.flush().await?;
+        timeout(Duration::from_secs(2), bob_session.session.read_exact(&mut buf)).await??;
+        assert_eq!(b"hello", &buf);
+
+        // The Entry is restarted: the Session object and the manager that opened it are lost
+        futures::stream::iter(alice_ahs)
+            .for_each(|ah| async move { ah.abort() })
+            .await;
+        alice_sender.close_channel();
+        let _ = alice_handle.await;
+        drop(alice_session);
+        drop(alice_mgr);
 
-    #[test]
-    fn session_config_forwards_max_buffered_segments() {
-        assert_eq!(
-            SessionManagerConfig::default().max_buffered_segments,
-            0,
-            "default must leave the transport unbuffered"
-        );
+        let alice_mgr = SessionManager::new(Default::default());
+        *current_alice_mgr.write() = alice_mgr.clone();
+        let (new_session_tx_alice, _) = futures::channel::mpsc::channel(1024);
+        let (alice_sender, alice_handle) = mock_packet_planning(alice_transport());
+        ahs.extend(alice_mgr.start(alice_sender.clone(), new_session_tx_alice)?);
 
-        for segments in [0, 64] {
-            let cfg = SessionManagerConfig {
-                max_buffered_segments: segments,
-                ..Default::default()
-            };
-            assert_eq!(
-                session_config(&cfg, Capabilities::empty()).max_buffered_segments,
-                segments
-            );
-        }
-    }
+        let mut alice_session = timeout(
+            Duration::from_secs(2),
+            alice_mgr.resume_session_with(bob_peer, session_id, ticket, client_cfg),
+        )
+        .await??;
+        assert_eq!(session_id, *alice_session.id());
+        assert_eq!(Some(ticket), alice_session.resume_ticket());
 
-    #[async_trait::async_trait]
-    trait SendMsg {
-        async fn send_message(
-            &self,
-            routing: DestinationRouting,
-            data: ApplicationDataOut,
-        ) -> crate::errors::Result<()>;
-    }
+        alice_session.write_all(b"world").await?;
+        alice_session.flush().await?;
+        timeout(Duration::from_secs(2), bob_session.session.read_exact(&mut buf)).await??;
+        assert_eq!(b"world", &buf);
 
-    mockall::mock! {
-        MsgSender {}
-        impl SendMsg for MsgSender {
-            fn send_message<'a, 'b>(&'a self, routing: DestinationRouting, data: ApplicationDataOut)
-            -> BoxFuture<'b, crate::errors::Result<()>> where 'a: 'b, Self: Sync + 'b;
-        }
-    }
+        bob_session.session.write_all(b"hello").await?;
+        bob_session.session.flush().await?;
+        timeout(Duration::from_secs(2), alice_session.read_exact(&mut buf)).await??;
+        assert_eq!(b"hello", &buf);
 
-    fn mock_packet_planning(
-        sender: MockMsgSender,
-    ) -> (
-        UnboundedSender<(DestinationRouting, ApplicationDataOut)>,
-        tokio::task::JoinHandle<()>,
-    ) {
-        let (tx, rx) = futures::channel::mpsc::unbounded();
-        let handle = tokio::task::spawn(async move {
-            pin_mut!(rx);
-            while let Some((routing, data)) = rx.next().await {
-                sender
-                    .send_message(routing, data)
-                    .await
-                    .expect("send message must not fail in mock");
-            }
-        });
-        (tx, handle)
-    }
+        assert_eq!(vec![session_id], alice_mgr.active_sessions());
+        assert_eq!(vec![session_id], bob_mgr.active_sessions());
 
-    fn msg_type(data: &ApplicationDataOut, expected: StartProtocolDiscriminants) -> bool {
-        HoprStartProtocol::decode(data.data.application_tag, &data.data.plain_text)
-            .map(|d| StartProtocolDiscriminants::from(d) == expected)
-            .unwrap_or(false)
-    }
+        futures::stream::iter(ahs)
+            .for_each(|ah| async move { ah.abort() })
+            .await;
 
-    fn start_msg_match(data: &ApplicationDataOut, msg: impl Fn(HoprStartProtocol) -> bool) -> bool {
-        HoprStartProtocol::decode(data.data.application_tag, &data.data.plain_text)
-            .map(msg)
-            .unwrap_or(false)
-    }
+        alice_sender.close_channel();
+        bob_sender.close_channel();
+        let _ = alice_handle.await;
+        let _ = bob_handle.await;
 
-    /// Waits (bounded) until the manager reports no active sessions.
-    ///
-    /// The session-slot rollback runs on a spawned task, so its effect is observed
-    /// asynchronously; this polls [`SessionManager::active_sessions`] until it drains.
-    async fn wait_for_no_active_sessions(
-        mgr: &SessionManager<UnboundedSender<(DestinationRouting, ApplicationDataOut)>>,
-    ) -> bool {
-        for _ in 0..50 {
-            if mgr.active_sessions().is_empty() {
-                return true;
-            }
-            tokio::time::sleep(Duration::from_millis(20)).await;
-        }
-        mgr.active_sessions().is_empty()
+        Ok(())
     }
 
     #[test_log::test(tokio::test)]
-    async fn session_manager_should_follow_start_protocol_to_establish_new_session_and_close_it() -> anyhow::Result<()>
-    {
+    async fn session_manager_should_not_resume_session_opened_without_resumption() -> anyhow::Result<()> {
         let alice_pseudonym = HoprPseudonym::random();
         let bob_peer: Address = (&ChainKeypair::random()).into();
 
         let alice_mgr = SessionManager::new(Default::default());
         let bob_mgr = SessionManager::new(Default::default());
 
-        let mut sequence = mockall::Sequence::new();
         let mut alice_transport = MockMsgSender::new();
-        let mut bob_transport = MockMsgSender::new();
-
-        // Alice sends the StartSession message
         let bob_mgr_clone = bob_mgr.clone();
-        alice_transport
-            .expect_send_message()
-            .once()
-            .in_sequence(&mut sequence)
-            .withf(move |peer, data| {
-                info!("alice sends {}", data.data.application_tag);
-                msg_type(data, StartProtocolDiscriminants::StartSession)
-                    && matches!(peer, DestinationRouting::Forward { destination, .. } if destination.as_ref() == &bob_peer.into())
+        alice_transport.expect_send_message().returning(move |_, data| {
+            let bob_mgr_clone = bob_mgr_clone.clone();
+            Box::pin(async move {
+                let _ = bob_mgr_clone.dispatch_message(
+                    alice_pseudonym,
+                    ApplicationDataIn {
+                        data: data.data,
+                        packet_info: Default::default(),
+                    },
+                );
+                Ok(())
             })
-            .returning(move |_, data| {
-                let bob_mgr_clone = bob_mgr_clone.clone();
-                Box::pin(async move {
-                    bob_mgr_clone
-                        .dispatch_message(
-                            alice_pseudonym,
-                            ApplicationDataIn {
-                                data: data.data,
-                                packet_info: Default::default(),
-                            },
-                        )
-                        ?;
-                    Ok(())
-                })
-            });
+        });
 
-        // Bob sends the SessionEstablished message
+        let mut bob_transport = MockMsgSender::new();
         let alice_mgr_clone = alice_mgr.clone();
-        bob_transport
-            .expect_send_message()
-            .once()
-            .in_sequence(&mut sequence)
-            .withf(move |peer, data| {
-                info!("bob sends {}", data.data.application_tag);
-                msg_type(data, StartProtocolDiscriminants::SessionEstablished)
-                    && matches!(peer, DestinationRouting::Return(SurbMatcher::Pseudonym(p)) if p == &alice_pseudonym)
-            })
-            .returning(move |_, data| {
-                let alice_mgr_clone = alice_mgr_clone.clone();
-
-                Box::pin(async move {
-                    alice_mgr_clone.dispatch_message(
-                        alice_pseudonym,
-                        ApplicationDataIn {
-                            data: data.data,
-                            packet_info: Default::default(),
-                        },
-                    )?;
-                    Ok(())
-                })
-            });
-
-        // Alice sends the terminating segment to close the Session
-        let bob_mgr_clone = bob_mgr.clone();
-        alice_transport
-            .expect_send_message()
-            .once()
-            .in_sequence(&mut sequence)
-            .withf(move |peer, data| {
-                hopr_protocol_session::types::SessionMessage::<{ ApplicationData::PAYLOAD_SIZE }>::try_from(
-                    data.data.plain_text.as_ref(),
-                )
-                .expect("must be a session message")
-                .try_as_segment()
-                .expect("must be a segment")
-                .is_terminating()
-                    && matches!(peer, DestinationRouting::Forward { destination, .. } if destination.as_ref() == &bob_peer.into())
+        bob_transport.expect_send_message().returning(move |_, data| {
+            let alice_mgr_clone = alice_mgr_clone.clone();
+            Box::pin(async move {
+                let _ = alice_mgr_clone.dispatch_message(
+                    alice_pseudonym,
+                    ApplicationDataIn {
+                        data: data.data,
+                        packet_info: Default::default(),
+                    },
+                );
+                Ok(())
             })
-            .returning(move |_, data| {
-                let bob_mgr_clone = bob_mgr_clone.clone();
-                Box::pin(async move {
-                    bob_mgr_clone
-                        .dispatch_message(
-                            alice_pseudonym,
-                            ApplicationDataIn {
-                                data: data.data,
-                                packet_info: Default::default(),
-                            },
-                        )
-                        ?;
-                    Ok(())
-                })
-            });
+        });
 
         let mut ahs = Vec::new();
-
-        // Start Alice
         let (new_session_tx_alice, _) = futures::channel::mpsc::channel(1024);
         let (alice_sender, alice_handle) = mock_packet_planning(alice_transport);
         ahs.extend(alice_mgr.start(alice_sender.clone(), new_session_tx_alice)?);
-        assert!(alice_mgr.is_started());
 
-        // Start Bob
         let (new_session_tx_bob, new_session_rx_bob) = futures::channel::mpsc::channel(1024);
         let (bob_sender, bob_handle) = mock_packet_planning(bob_transport);
         ahs.extend(bob_mgr.start(bob_sender.clone(), new_session_tx_bob)?);
-        assert!(bob_mgr.is_started());
-
-        let target = SealedHost::Plain("127.0.0.1:80".parse()?);
 
         pin_mut!(new_session_rx_bob);
         let (alice_session, bob_session) = timeout(
@@ -2035,7 +4431,7 @@ mod tests {
             futures::future::join(
                 alice_mgr.new_session(
                     bob_peer,
-                    SessionTarget::TcpStream(target.clone()),
+                    SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
                     SessionClientConfig {
                         pseudonym: alice_pseudonym.into(),
                         capabilities: Capability::NoRateControl | Capability::Segmentation,
@@ -2048,42 +4444,31 @@ mod tests {
         )
         .await?;
 
-        let mut alice_session = alice_session?;
-        let bob_session = bob_session.ok_or(anyhow!("bob must get an incoming session"))?;
-
-        assert_eq!(
-            alice_session.config().capabilities,
-            Capability::Segmentation | Capability::NoRateControl
-        );
-        assert_eq!(
-            alice_session.config().capabilities,
-            bob_session.session.config().capabilities
-        );
-        assert!(matches!(bob_session.target, SessionTarget::TcpStream(host) if host == target));
-
-        assert_eq!(vec![*alice_session.id()], alice_mgr.active_sessions());
-        assert_eq!(None, alice_mgr.get_surb_balancer_config(alice_session.id())?);
-        assert!(
-            alice_mgr
-                .update_surb_balancer_config(alice_session.id(), SurbBalancerConfig::default())
-                .is_err()
-        );
-
-        assert_eq!(vec![*bob_session.session.id()], bob_mgr.active_sessions());
-        assert_eq!(None, bob_mgr.get_surb_balancer_config(bob_session.session.id())?);
-        assert!(
-            bob_mgr
-                .update_surb_balancer_config(bob_session.session.id(), SurbBalancerConfig::default())
-                .is_err()
-        );
-
-        tokio::time::sleep(Duration::from_millis(100)).await;
-        alice_session.close().await?;
+        let alice_session = alice_session?;
+        let _bob_session = bob_session.ok_or(anyhow!("bob must get an incoming session"))?;
 
-        tokio::time::sleep(Duration::from_millis(100)).await;
+        let res = alice_mgr
+            .resume_session(
+                alice_session.id(),
+                RoutingOptions::Hops(0_u32.try_into()?),
+                RoutingOptions::Hops(0_u32.try_into()?),
+            )
+            .await;
+        assert!(matches!(
+            res,
+            Err(TransportSessionError::Manager(SessionManagerError::Other(_)))
+        ));
 
+        // A forged resumption request is rejected by the Exit
+        let res = alice_mgr
+            .resume_session(
+                &HoprPseudonym::random(),
+                RoutingOptions::Hops(0_u32.try_into()?),
+                RoutingOptions::Hops(0_u32.try_into()?),
+            )
+            .await;
         assert!(matches!(
-            alice_mgr.ping_session(alice_session.id()).await,
+            res,
             Err(TransportSessionError::Manager(SessionManagerError::NonExistingSession))
         ));
 
@@ -2091,7 +4476,6 @@ mod tests {
             .for_each(|ah| async move { ah.abort() })
             .await;
 
-        // Cleanup: close senders and await handles
         alice_sender.close_channel();
         bob_sender.close_channel();
         let _ = alice_handle.await;
@@ -2254,10 +4638,15 @@ mod tests {
             session_id,
             SessionSlot {
                 session_tx: dummy_tx,
-                routing_opts: DestinationRouting::Return(SurbMatcher::Pseudonym(alice_pseudonym)),
+                routing_opts: Arc::new(parking_lot::RwLock::new(DestinationRouting::Return(
+                    SurbMatcher::Pseudonym(alice_pseudonym),
+                ))),
+                resume_ticket: None,
+                paths: None,
                 abort_handles: Default::default(),
                 surb_mgmt: Arc::new(BalancerStateValues::from(balancer_cfg)),
                 surb_estimator: Default::default(),
+                admission: Default::default(),
             },
         );
 
@@ -2278,6 +4667,22 @@ mod tests {
             .ok_or(anyhow!("session must have a surb balancer config"))?;
         assert_eq!(actual_cfg, new_cfg);
 
+        let controller_change = SurbBalancerConfig {
+            target_surb_buffer_size: 3000,
+            controller: SurbBalancerControllerKind::Adaptive,
+            ..new_cfg
+        };
+        assert!(
+            alice_mgr
+                .update_surb_balancer_config(&session_id, controller_change)
+                .is_err()
+        );
+
+        let actual_cfg = alice_mgr
+            .get_surb_balancer_config(&session_id)?
+            .ok_or(anyhow!("session must have a surb balancer config"))?;
+        assert_eq!(actual_cfg, new_cfg);
+
         Ok(())
     }
 
@@ -2454,7 +4859,10 @@ mod tests {
                     challenge: MIN_CHALLENGE,
                     target: SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
                     capabilities: ByteCapabilities(Capabilities::empty()),
+                    optional_capabilities: None,
+                    session_protocol_version: None,
                     additional_data: 0,
+                    key_share: None,
                 },
             )
             .await;
@@ -2500,7 +4908,10 @@ mod tests {
                     challenge: MIN_CHALLENGE,
                     target: SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
                     capabilities: ByteCapabilities(Capabilities::empty()),
+                    optional_capabilities: None,
+                    session_protocol_version: None,
                     additional_data: 0,
+                    key_share: None,
                 },
             )
             .await;
@@ -2868,7 +5279,10 @@ mod tests {
                     challenge: MIN_CHALLENGE,
                     target: SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
                     capabilities: ByteCapabilities(Capabilities::empty()),
+                    optional_capabilities: None,
+                    session_protocol_version: None,
                     additional_data: 0,
+                    key_share: None,
                 },
             )
             .await;
@@ -2889,7 +5303,10 @@ mod tests {
                     challenge: MIN_CHALLENGE + 1,
                     target: SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
                     capabilities: ByteCapabilities(Capabilities::empty()),
+                    optional_capabilities: None,
+                    session_protocol_version: None,
                     additional_data: 0,
+                    key_share: None,
                 },
             )
             .await;
@@ -3167,7 +5584,10 @@ mod tests {
                 challenge: MIN_CHALLENGE,
                 target: SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
                 capabilities: ByteCapabilities(Capabilities::empty()),
+                optional_capabilities: None,
+                session_protocol_version: None,
                 additional_data: 0,
+                key_share: None,
             },
         )
         .await?;
@@ -3184,7 +5604,10 @@ mod tests {
                     challenge: MIN_CHALLENGE,
                     target: SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
                     capabilities: ByteCapabilities(Capabilities::empty()),
+                    optional_capabilities: None,
+                    session_protocol_version: None,
                     additional_data: 0,
+                    key_share: None,
                 },
             )
             .await;
@@ -3241,7 +5664,10 @@ mod tests {
                     challenge: MIN_CHALLENGE + i as u64,
                     target: SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
                     capabilities: ByteCapabilities(Capabilities::empty()),
+                    optional_capabilities: None,
+                    session_protocol_version: None,
                     additional_data: 0,
+                    key_share: None,
                 },
             )
             .await?;
@@ -3445,7 +5871,10 @@ mod tests {
                 challenge: MIN_CHALLENGE,
                 target: SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
                 capabilities: ByteCapabilities(Capabilities::empty()),
+                optional_capabilities: None,
+                session_protocol_version: None,
                 additional_data: 0,
+                key_share: None,
             },
         )
         .await?;
@@ -3496,15 +5925,18 @@ mod tests {
             session_id,
             SessionSlot {
                 session_tx: dummy_tx,
-                routing_opts: DestinationRouting::Forward {
+                routing_opts: Arc::new(parking_lot::RwLock::new(DestinationRouting::Forward {
                     destination: Box::new(peer_address.into()),
                     pseudonym: Some(alice_pseudonym),
                     forward_options: RoutingOptions::Hops(hopr_api::types::primitive::bounded::BoundedSize::MIN),
                     return_options: RoutingOptions::Hops(hopr_api::types::primitive::bounded::BoundedSize::MIN).into(),
-                },
+                })),
+                resume_ticket: None,
+                paths: None,
                 abort_handles: Default::default(),
                 surb_mgmt: Arc::new(BalancerStateValues::from(balancer_cfg)),
                 surb_estimator: Default::default(),
+                admission: Default::default(),
             },
         );
 
@@ -3589,10 +6021,15 @@ mod tests {
             session_id,
             SessionSlot {
                 session_tx: dummy_tx,
-                routing_opts: DestinationRouting::Return(SurbMatcher::Pseudonym(alice_pseudonym)),
+                routing_opts: Arc::new(parking_lot::RwLock::new(DestinationRouting::Return(
+                    SurbMatcher::Pseudonym(alice_pseudonym),
+                ))),
+                resume_ticket: None,
+                paths: None,
                 abort_handles: Default::default(),
                 surb_mgmt: Arc::new(BalancerStateValues::from(balancer_cfg)),
                 surb_estimator: Default::default(),
+                admission: Default::default(),
             },
         );
 
@@ -3680,7 +6117,10 @@ mod tests {
                 challenge: MIN_CHALLENGE,
                 target: SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
                 capabilities: ByteCapabilities(Capabilities::empty()),
+                optional_capabilities: None,
+                session_protocol_version: None,
                 additional_data: 0,
+                key_share: None,
             },
         )
         .await?;
@@ -3738,7 +6178,10 @@ mod tests {
                 challenge: MIN_CHALLENGE,
                 target: SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
                 capabilities: ByteCapabilities(Capabilities::empty()),
+                optional_capabilities: None,
+                session_protocol_version: None,
                 additional_data: 0,
+                key_share: None,
             },
         )
         .await?;
@@ -3755,7 +6198,10 @@ mod tests {
                     challenge: MIN_CHALLENGE,
                     target: SessionTarget::TcpStream(SealedHost::Plain("127.0.0.1:80".parse()?)),
                     capabilities: ByteCapabilities(Capabilities::empty()),
+                    optional_capabilities: None,
+                    session_protocol_version: None,
                     additional_data: 0,
+                    key_share: None,
                 },
             )
             .await;
@@ -0,0 +1,637 @@
+//! Striping of a single Session over multiple forward paths.
+//!
+//! A multipath Session sends its segments over several independently selected forward paths,
+//! each of them carrying SURBs for its own return path. The [`PathScheduler`] picks the path for
+//! every outgoing packet using smooth weighted round-robin, where the weight of each path follows
+//! its observed quality:
+//!
+//! * Outgoing segments are recorded together with the path they were sent over.
+//! * Frame acknowledgements coming back from the Exit mark the segments of the acknowledged frames as delivered, which
+//!   also yields an RTT sample of the path.
+//! * Retransmission requests mark the requested segments as lost on the path that carried them.
+//! * Segments without any feedback within [`MultipathConfig::loss_timeout`] count as lost for ACK-based Sessions and as
+//!   delivered for NACK-based Sessions.
+//!
+//! A path whose loss rate exceeds [`MultipathConfig::degraded_loss_rate`] is considered degraded and
+//! is only kept with the [`MultipathConfig::probe_share`] of the traffic, so that it can recover.
+//! Sessions without retransmission provide no feedback, and their load is spread evenly.
+//!
+//! Since segments of a single frame may travel over paths of different latencies, they arrive
+//! out-of-order at the Exit, where the Session sequencer puts them back together.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+use hopr_api::types::internal::routing::{DestinationRouting, RoutingOptions};
+use hopr_protocol_session::{FrameId, SegmentId, types::SessionMessage};
+
+use crate::{Capabilities, Capability};
+
+/// Forward and return path options of a single path of a multipath Session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionPathOptions {
+    /// The forward path options.
+    pub forward_path_options: RoutingOptions,
+    /// The return path options, used for the SURBs sent over this path.
+    pub return_path_options: RoutingOptions,
+}
+
+/// Configuration of a multipath Session.
+///
+/// The primary path is given by the forward and return path options of the
+/// [`SessionClientConfig`](crate::SessionClientConfig), the other paths by `additional_paths`.
+#[derive(Debug, Clone, PartialEq, smart_default::SmartDefault)]
+pub struct MultipathConfig {
+    /// Total number of paths to stripe the Session over, including the primary one.
+    ///
+    /// Used by the HOPR transport to select the `additional_paths` when none are given explicitly.
+    #[default(2)]
+    pub path_count: usize,
+    /// Paths used in addition to the primary path.
+    ///
+    /// If empty, the Session uses only the primary path.
+    #[default(Vec::new())]
+    pub additional_paths: Vec<SessionPathOptions>,
+    /// Loss rate above which a path is considered degraded.
+    #[default(0.25)]
+    pub degraded_loss_rate: f64,
+    /// Minimum share of the traffic each path receives, so that degraded paths are still probed.
+    #[default(0.05)]
+    pub probe_share: f64,
+    /// Time after which a segment without any feedback is resolved.
+    #[default(Duration::from_secs(3))]
+    pub loss_timeout: Duration,
+}
+
+/// Snapshot of the state of a single path of a multipath Session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathStats {
+    /// Routing of the path.
+    pub routing: DestinationRouting,
+    /// Smoothed round-trip time of the path, if already measured.
+    pub srtt: Option<Duration>,
+    /// Moving average of the segment loss rate of the path.
+    pub loss_rate: f64,
+    /// Whether the path is considered degraded.
+    pub degraded: bool,
+    /// Share of the traffic currently scheduled over the path.
+    pub share: f64,
+    /// Number of segments sent over the path.
+    pub sent_segments: u64,
+    /// Number of segments sent over the path known to be delivered.
+    pub delivered_segments: u64,
+    /// Number of segments sent over the path known to be lost.
+    pub lost_segments: u64,
+}
+
+/// Source of the path quality feedback, given by the Session capabilities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PathFeedback {
+    /// Frames are acknowledged: unresolved segments are considered lost.
+    Acknowledgements,
+    /// Only missing segments are requested: unresolved segments are considered delivered.
+    Requests,
+    /// No feedback at all.
+    None,
+}
+
+impl From<Capabilities> for PathFeedback {
+    fn from(value: Capabilities) -> Self {
+        if value.contains(Capability::RetransmissionAck) {
+            Self::Acknowledgements
+        } else if value.contains(Capability::RetransmissionNack) {
+            Self::Requests
+        } else {
+            Self::None
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PathState {
+    routing: DestinationRouting,
+    srtt: Option<Duration>,
+    loss_rate: f64,
+    current_weight: f64,
+    sent: u64,
+    delivered: u64,
+    lost: u64,
+}
+
+impl PathState {
+    /// Gain of the moving average of the loss rate.
+    const LOSS_GAIN: f64 = 1.0 / 16.0;
+    /// Gain of the moving average of the RTT.
+    const RTT_GAIN: f64 = 1.0 / 8.0;
+
+    fn new(routing: DestinationRouting) -> Self {
+        Self {
+            routing,
+            srtt: None,
+            loss_rate: 0.0,
+            current_weight: 0.0,
+            sent: 0,
+            delivered: 0,
+            lost: 0,
+        }
+    }
+
+    fn on_delivered(&mut self, rtt: Option<Duration>) {
+        self.delivered += 1;
+        self.loss_rate -= self.loss_rate * Self::LOSS_GAIN;
+        if let Some(rtt) = rtt {
+            self.srtt = Some(match self.srtt {
+                Some(srtt) => srtt.mul_f64(1.0 - Self::RTT_GAIN) + rtt.mul_f64(Self::RTT_GAIN),
+                None => rtt,
+            });
+        }
+    }
+
+    fn on_lost(&mut self) {
+        self.lost += 1;
+        self.loss_rate += (1.0 - self.loss_rate) * Self::LOSS_GAIN;
+    }
+}
+
+/// Schedules outgoing packets of a multipath Session over its paths.
+///
+/// The first path is the primary path of the Session.
+#[derive(Debug)]
+pub(crate) struct PathScheduler<const C: usize> {
+    paths: Vec<PathState>,
+    feedback: PathFeedback,
+    degraded_loss_rate: f64,
+    probe_share: f64,
+    loss_timeout: Duration,
+    // Path index and the last send time of segments awaiting feedback
+    outstanding: BTreeMap<SegmentId, (usize, Instant)>,
+    // Send order of the outstanding segments, used to resolve them on timeout
+    send_order: VecDeque<(SegmentId, Instant)>,
+    // Round-robin counter for packets other than Session segments
+    next_control_path: usize,
+}
+
+impl<const C: usize> PathScheduler<C> {
+    /// Creates a scheduler over the `primary` routing and the given additional routings.
+    pub fn new(
+        primary: DestinationRouting,
+        additional: impl IntoIterator<Item = DestinationRouting>,
+        cfg: &MultipathConfig,
+        capabilities: Capabilities,
+    ) -> Self {
+        Self {
+            paths: std::iter::once(primary).chain(additional).map(PathState::new).collect(),
+            feedback: capabilities.into(),
+            degraded_loss_rate: cfg.degraded_loss_rate,
+            probe_share: cfg.probe_share.clamp(0.0, 1.0),
+            loss_timeout: cfg.loss_timeout,
+            outstanding: BTreeMap::new(),
+            send_order: VecDeque::new(),
+            next_control_path: 0,
+        }
+    }
+
+    /// Replaces the routing of the primary path.
+    pub fn set_primary_routing(&mut self, routing: DestinationRouting) {
+        self.paths[0] = PathState::new(routing);
+        self.outstanding.retain(|_, (path, _)| *path != 0);
+    }
+
+    fn is_degraded(&self, path: &PathState) -> bool {
+        self.feedback != PathFeedback::None && path.loss_rate > self.degraded_loss_rate
+    }
+
+    /// Shares of the traffic per path, summing up to 1.
+    fn shares(&self) -> Vec<f64> {
+        let min_srtt = self.paths.iter().filter_map(|p| p.srtt).min();
+        let mut shares = self
+            .paths
+            .iter()
+            .map(|path| {
+                if self.is_degraded(path) {
+                    return 0.0;
+                }
+                // Faster paths get proportionally more traffic
+                let rtt_factor = match (min_srtt, path.srtt) {
+                    (Some(min), Some(srtt)) if !srtt.is_zero() => min.as_secs_f64() / srtt.as_secs_f64(),
+                    _ => 1.0,
+                };
+                (1.0 - path.loss_rate).max(0.0) * rtt_factor
+            })
+            .collect::<Vec<_>>();
+
+        let total: f64 = shares.iter().sum();
+        if total <= 0.0 {
+            // All paths are degraded
+            return vec![1.0 / shares.len() as f64; shares.len()];
+        }
+        shares
+            .iter_mut()
+            .for_each(|share| *share = (*share / total).max(self.probe_share));
+
+        let total: f64 = shares.iter().sum();
+        shares.iter_mut().for_each(|share| *share /= total);
+        shares
+    }
+
+    // Smooth weighted round-robin over the path shares
+    fn next_path(&mut self) -> usize {
+        let shares = self.shares();
+        self.paths
+            .iter_mut()
+            .zip(shares)
+            .for_each(|(path, share)| path.current_weight += share);
+
+        let selected = self
+            .paths
+            .iter()
+            .enumerate()
+            // On ties, prefer the path that comes first
+            .rev()
+            .max_by(|(_, a), (_, b)| a.current_weight.total_cmp(&b.current_weight))
+            .map(|(i, _)| i)
+            .unwrap_or_default();
+
+        // Shares sum up to 1
+        self.paths[selected].current_weight -= 1.0;
+        selected
+    }
+
+    /// Picks the routing for an outgoing Session protocol packet carrying the given `segments`.
+    pub fn route_outgoing(
+        &mut self,
+        segments: impl IntoIterator<Item = SegmentId>,
+        now: Instant,
+    ) -> DestinationRouting {
+        self.resolve_expired(now);
+
+        let path = self.next_path();
+        for id in segments {
+            self.paths[path].sent += 1;
+            if self.feedback != PathFeedback::None {
+                self.outstanding.insert(id, (path, now));
+                self.send_order.push_back((id, now));
+            }
+        }
+
+        self.paths[path].routing.clone()
+    }
+
+    /// Picks the routing for an outgoing packet which is not a Session protocol message.
+    ///
+    /// These packets are spread evenly over all paths, regardless of their quality.
+    pub fn route_control(&mut self) -> DestinationRouting {
+        let path = self.next_control_path % self.paths.len();
+        self.next_control_path = self.next_control_path.wrapping_add(1);
+        self.paths[path].routing.clone()
+    }
+
+    fn delivered(&mut self, id: SegmentId, now: Instant) {
+        if let Some((path, sent_at)) = self.outstanding.remove(&id) {
+            self.paths[path].on_delivered(Some(now.saturating_duration_since(sent_at)));
+        }
+    }
+
+    fn frame_delivered(&mut self, frame_id: FrameId, now: Instant) {
+        let segments = self
+            .outstanding
+            .range(SegmentId(frame_id, 0)..=SegmentId(frame_id, u16::MAX))
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        segments.into_iter().for_each(|id| self.delivered(id, now));
+    }
+
+    fn lost(&mut self, id: SegmentId) {
+        if let Some((path, _)) = self.outstanding.remove(&id) {
+            self.paths[path].on_lost();
+        }
+    }
+
+    /// Processes an incoming Session protocol message, updating the quality of the paths.
+    pub fn on_incoming(&mut self, message: &[u8], now: Instant) {
+        match SessionMessage::<C>::try_from(message) {
+            Ok(SessionMessage::Acknowledge(acks)) => {
+                acks.into_iter()
+                    .for_each(|frame_id| self.frame_delivered(frame_id, now));
+            }
+            Ok(SessionMessage::SelectiveAcknowledge(sack)) => {
+                let cumulative = self
+                    .outstanding
+                    .range(..=SegmentId(sack.cumulative(), u16::MAX))
+                    .map(|(id, _)| *id)
+                    .collect::<Vec<_>>();
+                cumulative.into_iter().for_each(|id| self.delivered(id, now));
+
+                // Only the frames in flight can be acknowledged
+                let in_flight = self
+                    .outstanding
+                    .keys()
+                    .next()
+                    .zip(self.outstanding.keys().next_back())
+                    .map(|(oldest, newest)| oldest.0..=newest.0);
+                if let Some(window) = in_flight {
+                    let frames = sack.selective_frames_within(window).collect::<Vec<_>>();
+                    frames
+                        .into_iter()
+                        .for_each(|frame_id| self.frame_delivered(frame_id, now));
+                }
+            }
+            Ok(SessionMessage::Request(request)) => {
+                request.into_iter().for_each(|id| self.lost(id));
+            }
+            Ok(SessionMessage::RangeRequest(request)) => {
+                // Only the segments in flight can be lost
+                let lost = request
+                    .ranges()
+                    .flat_map(|range| self.outstanding.range(range).map(|(id, _)| *id))
+                    .collect::<Vec<_>>();
+                lost.into_iter().for_each(|id| self.lost(id));
+            }
+            Ok(SessionMessage::Segment(_)) | Err(_) => {}
+        }
+    }
+
+    fn resolve_expired(&mut self, now: Instant) {
+        while let Some((id, sent_at)) = self
+            .send_order
+            .front()
+            .filter(|(_, sent_at)| now.saturating_duration_since(*sent_at) >= self.loss_timeout)
+            .copied()
+        {
+            self.send_order.pop_front();
+            // Skip segments that have been resolved or re-sent in the meantime
+            if self.outstanding.get(&id).is_some_and(|(_, t)| *t == sent_at) {
+                if self.feedback == PathFeedback::Acknowledgements {
+                    self.lost(id);
+                } else if let Some((path, _)) = self.outstanding.remove(&id) {
+                    self.paths[path].on_delivered(None);
+                }
+            }
+        }
+    }
+
+    /// Returns the current state of all paths.
+    pub fn stats(&self) -> Vec<PathStats> {
+        self.paths
+            .iter()
+            .zip(self.shares())
+            .map(|(path, share)| PathStats {
+                routing: path.routing.clone(),
+                srtt: path.srtt,
+                loss_rate: path.loss_rate,
+                degraded: self.is_degraded(path),
+                share,
+                sent_segments: path.sent,
+                delivered_segments: path.delivered,
+                lost_segments: path.lost,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hopr_api::types::{
+        crypto::{keypairs::ChainKeypair, prelude::Keypair},
+        crypto_random::Randomizable,
+        internal::prelude::HoprPseudonym,
+        primitive::prelude::Address,
+    };
+    use hopr_protocol_session::{
+        FrameAcknowledgements,
+        types::{SegmentRangeRequest, SelectiveAcknowledgement},
+    };
+
+    use super::*;
+
+    const C: usize = 1000;
+
+    fn routings(count: u32) -> anyhow::Result<Vec<DestinationRouting>> {
+        let destination: Address = (&ChainKeypair::random()).into();
+        let pseudonym = HoprPseudonym::random();
+        (0..count)
+            .map(|hops| {
+                Ok(DestinationRouting::Forward {
+                    destination: Box::new(destination.into()),
+                    pseudonym: Some(pseudonym),
+                    forward_options: RoutingOptions::Hops(hops.try_into()?),
+                    return_options: RoutingOptions::Hops(hops.try_into()?).into(),
+                })
+            })
+            .collect()
+    }
+
+    fn new_scheduler(routings: &[DestinationRouting], capabilities: Capabilities) -> PathScheduler<C> {
+        PathScheduler::new(
+            routings[0].clone(),
+            routings[1..].iter().cloned(),
+            &MultipathConfig::default(),
+            capabilities,
+        )
+    }
+
+    fn segment(frame_id: FrameId, seq_idx: u16) -> Option<SegmentId> {
+        Some(SegmentId(frame_id, seq_idx))
+    }
+
+    fn acknowledgement(frames: Vec<FrameId>) -> anyhow::Result<Box<[u8]>> {
+        Ok(SessionMessage::<C>::Acknowledge(FrameAcknowledgements::try_from(frames)?).into_encoded())
+    }
+
+    fn path_of(routings: &[DestinationRouting], routing: &DestinationRouting) -> usize {
+        routings
+            .iter()
+            .position(|r| r == routing)
+            .expect("must be a known routing")
+    }
+
+    #[test]
+    fn scheduler_should_spread_segments_evenly_over_healthy_paths() -> anyhow::Result<()> {
+        let routings = routings(3)?;
+        let mut scheduler = new_scheduler(&routings, Capability::Segmentation.into());
+
+        let now = Instant::now();
+        let mut counts = [0; 3];
+        for frame_id in 1..=30 {
+            counts[path_of(&routings, &scheduler.route_outgoing(segment(frame_id, 0), now))] += 1;
+        }
+
+        assert_eq!([10, 10, 10], counts);
+        assert!(
+            scheduler.outstanding.is_empty(),
+            "sessions without feedback must not track segments"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn scheduler_should_shift_load_away_from_lossy_path() -> anyhow::Result<()> {
+        let routings = routings(2)?;
+        let mut scheduler = new_scheduler(&routings, Capability::RetransmissionAck.into());
+
+        let mut now = Instant::now();
+        for frame_id in 1..=200 {
+            let path = path_of(&routings, &scheduler.route_outgoing(segment(frame_id, 0), now));
+            now += Duration::from_millis(10);
+            if path == 0 {
+                scheduler.on_incoming(&acknowledgement(vec![frame_id])?, now);
+            } else {
+                let request: SegmentRangeRequest<C> = [SegmentId(frame_id, 0)].into_iter().collect();
+                scheduler.on_incoming(&SessionMessage::<C>::RangeRequest(request).into_encoded(), now);
+            }
+        }
+
+        let stats = scheduler.stats();
+        assert!(!stats[0].degraded);
+        assert!(stats[1].degraded, "{stats:?}");
+        assert!(stats[1].lost_segments > 0);
+        assert_eq!(0, stats[0].lost_segments);
+
+        // The degraded path is only probed
+        let shares = scheduler.shares();
+        assert!((shares[1] - 0.05 / 1.05).abs() < 1e-9, "{shares:?}");
+
+        let mut counts = [0; 2];
+        for frame_id in 1000..1100 {
+            counts[path_of(&routings, &scheduler.route_outgoing(segment(frame_id, 0), now))] += 1;
+        }
+        assert!(counts[1] <= 5, "{counts:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn scheduler_should_prefer_faster_path() -> anyhow::Result<()> {
+        let routings = routings(2)?;
+        let mut scheduler = new_scheduler(&routings, Capability::RetransmissionAck.into());
+
+        let mut now = Instant::now();
+        for frame_id in 1..=100 {
+            let path = path_of(&routings, &scheduler.route_outgoing(segment(frame_id, 0), now));
+            let rtt = if path == 0 { 100 } else { 300 };
+            scheduler.on_incoming(&acknowledgement(vec![frame_id])?, now + Duration::from_millis(rtt));
+            now += Duration::from_millis(1);
+        }
+
+        let stats = scheduler.stats();
+        assert_eq!(Some(Duration::from_millis(100)), stats[0].srtt);
+        assert_eq!(Some(Duration::from_millis(300)), stats[1].srtt);
+        assert!((stats[0].share - 0.75).abs() < 1e-6, "{stats:?}");
+        assert!((stats[1].share - 0.25).abs() < 1e-6, "{stats:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn scheduler_should_acknowledge_all_segments_of_a_frame() -> anyhow::Result<()> {
+        let routings = routings(2)?;
+        let mut scheduler = new_scheduler(&routings, Capability::RetransmissionAck.into());
+
+        let now = Instant::now();
+        scheduler.route_outgoing(segment(1, 0), now);
+        scheduler.route_outgoing(segment(1, 1), now);
+        scheduler.route_outgoing(segment(2, 0), now);
+        assert_eq!(3, scheduler.outstanding.len());
+
+        scheduler.on_incoming(&acknowledgement(vec![1])?, now + Duration::from_millis(50));
+        assert_eq!(
+            vec![SegmentId(2, 0)],
+            scheduler.outstanding.keys().copied().collect::<Vec<_>>()
+        );
+
+        let stats = scheduler.stats();
+        assert_eq!(1, stats[0].delivered_segments);
+        assert_eq!(1, stats[1].delivered_segments);
+        Ok(())
+    }
+
+    #[test]
+    fn scheduler_should_only_process_selective_acknowledgements_of_frames_in_flight() -> anyhow::Result<()> {
+        let routings = routings(2)?;
+        let mut scheduler = new_scheduler(&routings, Capability::RetransmissionAck.into());
+
+        let now = Instant::now();
+        scheduler.route_outgoing(segment(5, 0), now);
+        scheduler.route_outgoing(segment(6, 0), now);
+        scheduler.route_outgoing(segment(7, 0), now);
+
+        let sack = SelectiveAcknowledgement::<C>::new_multiple(1, [3, 6].into_iter().chain(1000..5000)).remove(0);
+        scheduler.on_incoming(
+            &SessionMessage::<C>::SelectiveAcknowledge(sack).into_encoded(),
+            now + Duration::from_millis(50),
+        );
+        assert_eq!(
+            vec![SegmentId(5, 0), SegmentId(7, 0)],
+            scheduler.outstanding.keys().copied().collect::<Vec<_>>()
+        );
+
+        // Only the requested segments in flight are marked as lost
+        let request: SegmentRangeRequest<C> = (0..10)
+            .map(|seq_idx| SegmentId(4, seq_idx))
+            .chain((0..1000).map(|seq_idx| SegmentId(5, seq_idx)))
+            .collect();
+        scheduler.on_incoming(&SessionMessage::<C>::RangeRequest(request).into_encoded(), now);
+        let stats = scheduler.stats();
+        assert_eq!(1, stats.iter().map(|s| s.lost_segments).sum::<u64>());
+        assert_eq!(
+            vec![SegmentId(7, 0)],
+            scheduler.outstanding.keys().copied().collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn scheduler_should_resolve_segments_without_feedback_on_timeout() -> anyhow::Result<()> {
+        let routings = routings(2)?;
+        let timeout = MultipathConfig::default().loss_timeout;
+
+        // ACK-based Session: no acknowledgement means loss
+        let mut scheduler = new_scheduler(&routings, Capability::RetransmissionAck.into());
+        let now = Instant::now();
+        scheduler.route_outgoing(segment(1, 0), now);
+        scheduler.route_outgoing(segment(2, 0), now + timeout);
+        assert_eq!(1, scheduler.stats()[0].lost_segments);
+        assert_eq!(1, scheduler.outstanding.len());
+
+        // NACK-based Session: no request means delivery
+        let mut scheduler = new_scheduler(&routings, Capability::RetransmissionNack.into());
+        scheduler.route_outgoing(segment(1, 0), now);
+        scheduler.route_outgoing(segment(2, 0), now + timeout);
+        let stats = scheduler.stats();
+        assert_eq!(1, stats[0].delivered_segments);
+        assert_eq!(0, stats[0].lost_segments);
+        assert_eq!(None, stats[0].srtt);
+        Ok(())
+    }
+
+    #[test]
+    fn scheduler_should_spread_control_packets_round_robin() -> anyhow::Result<()> {
+        let routings = routings(3)?;
+        let mut scheduler = new_scheduler(&routings, Capability::RetransmissionAck.into());
+
+        let picked = (0..6).map(|_| scheduler.route_control()).collect::<Vec<_>>();
+        assert_eq!(
+            routings.iter().chain(routings.iter()).cloned().collect::<Vec<_>>(),
+            picked
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn scheduler_should_reset_primary_path_on_new_routing() -> anyhow::Result<()> {
+        let routings = routings(3)?;
+        let mut scheduler = new_scheduler(&routings[..2], Capability::RetransmissionAck.into());
+
+        let now = Instant::now();
+        scheduler.route_outgoing(segment(1, 0), now);
+        scheduler.route_outgoing(segment(2, 0), now);
+
+        scheduler.set_primary_routing(routings[2].clone());
+        let stats = scheduler.stats();
+        assert_eq!(routings[2], stats[0].routing);
+        assert_eq!(0, stats[0].sent_segments);
+        assert_eq!(1, stats[1].sent_segments);
+        assert_eq!(1, scheduler.outstanding.len());
+        Ok(())
+    }
+}
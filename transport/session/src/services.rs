@@ -0,0 +1,81 @@
+//! Services running directly at the Exit node.
+//!
+//! Incoming Sessions targeting an [Exit service](SessionTarget::ExitNode) are not passed
+//! to the incoming Session stream of the [`SessionManager`](crate::SessionManager), but are served by the
+//! [`SessionService`] registered under the requested [`ServiceId`]:
+//!
+//! 1. At the Start time, the service decides whether to [accept](SessionService::accept) the Session.
+//! 2. Once the Session is established, it is handed over to the service to [serve](SessionService::serve) it.
+//!
+//! Sessions requesting a service that has not been registered are rejected with
+//! [`StartErrorReason::UnknownService`].
+
+use std::{collections::HashMap, sync::Arc};
+
+use futures::{AsyncReadExt, AsyncWriteExt, future::BoxFuture};
+use hopr_protocol_start::StartErrorReason;
+
+use crate::{IncomingSession, ServiceId, SessionAdmissionRequest, errors::SessionManagerError};
+
+/// Service serving incoming Sessions targeting its [`ServiceId`].
+pub trait SessionService: Send + Sync {
+    /// Decides at the Start time whether the Session is accepted.
+    ///
+    /// The rejection reason is sent back to the Session initiator.
+    /// The default implementation accepts all Sessions.
+    fn accept(&self, request: &SessionAdmissionRequest<'_>) -> Result<(), StartErrorReason> {
+        let _ = request;
+        Ok(())
+    }
+
+    /// Serves an established Session.
+    ///
+    /// The returned future is aborted once the Session is closed.
+    fn serve(&self, session: IncomingSession) -> BoxFuture<'static, std::io::Result<()>>;
+}
+
+/// Service sending all the data received over the Session back to the initiator.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EchoService;
+
+impl SessionService for EchoService {
+    fn serve(&self, session: IncomingSession) -> BoxFuture<'static, std::io::Result<()>> {
+        Box::pin(async move {
+            let mut session = session.session;
+            let mut buf = vec![0u8; crate::SESSION_MTU];
+            loop {
+                let len = session.read(&mut buf).await?;
+                if len == 0 {
+                    break;
+                }
+                // Flush each chunk, so it is not held back until the Session buffer fills up
+                session.write_all(&buf[..len]).await?;
+                session.flush().await?;
+            }
+            session.close().await
+        })
+    }
+}
+
+/// Services registered at the Exit node, shared by all clones of the [`SessionManager`](crate::SessionManager).
+#[derive(Clone, Default)]
+pub(crate) struct SessionServiceRegistry(Arc<parking_lot::RwLock<HashMap<ServiceId, Arc<dyn SessionService>>>>);
+
+impl SessionServiceRegistry {
+    pub fn register(&self, id: ServiceId, service: Arc<dyn SessionService>) -> Result<(), SessionManagerError> {
+        let mut services = self.0.write();
+        if services.contains_key(&id) {
+            return Err(SessionManagerError::ServiceAlreadyRegistered(id));
+        }
+        services.insert(id, service);
+        Ok(())
+    }
+
+    pub fn unregister(&self, id: ServiceId) -> bool {
+        self.0.write().remove(&id).is_some()
+    }
+
+    pub fn get(&self, id: ServiceId) -> Option<Arc<dyn SessionService>> {
+        self.0.read().get(&id).cloned()
+    }
+}
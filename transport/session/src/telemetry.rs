@@ -468,10 +468,10 @@ impl hopr_protocol_session::SessionTelemetryTracker for SessionIdWrapper {
             SessionMessageDiscriminants::Segment => {
                 METRIC_SESSION_ACK_INCOMING_SEGMENTS_TOTAL.increment(&[self.label()])
             }
-            SessionMessageDiscriminants::Request => {
+            SessionMessageDiscriminants::Request | SessionMessageDiscriminants::RangeRequest => {
                 METRIC_SESSION_ACK_INCOMING_RETRANSMISSION_REQUESTS_TOTAL.increment(&[self.label()])
             }
-            SessionMessageDiscriminants::Acknowledge => {
+            SessionMessageDiscriminants::Acknowledge | SessionMessageDiscriminants::SelectiveAcknowledge => {
                 METRIC_SESSION_ACK_INCOMING_ACKNOWLEDGED_FRAMES_TOTAL.increment(&[self.label()])
             }
         }
@@ -482,10 +482,10 @@ impl hopr_protocol_session::SessionTelemetryTracker for SessionIdWrapper {
             SessionMessageDiscriminants::Segment => {
                 METRIC_SESSION_ACK_OUTGOING_SEGMENTS_TOTAL.increment(&[self.label()])
             }
-            SessionMessageDiscriminants::Request => {
+            SessionMessageDiscriminants::Request | SessionMessageDiscriminants::RangeRequest => {
                 METRIC_SESSION_ACK_OUTGOING_RETRANSMISSION_REQUESTS_TOTAL.increment(&[self.label()])
             }
-            SessionMessageDiscriminants::Acknowledge => {
+            SessionMessageDiscriminants::Acknowledge | SessionMessageDiscriminants::SelectiveAcknowledge => {
                 METRIC_SESSION_ACK_OUTGOING_ACKNOWLEDGED_FRAMES_TOTAL.increment(&[self.label()])
             }
         }
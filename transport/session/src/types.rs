@@ -7,17 +7,18 @@ use std::{
     time::Duration,
 };
 
+use flagset::Flags;
 use futures::{SinkExt, StreamExt, TryStreamExt};
 use hopr_api::types::{internal::routing::DestinationRouting, primitive::errors::GeneralError};
 use hopr_protocol_app::prelude::{ApplicationData, ApplicationDataIn, ApplicationDataOut, ReservedTag, Tag};
 #[cfg(feature = "telemetry")]
 use hopr_protocol_session::NoopTracker;
 use hopr_protocol_session::{
-    AcknowledgementMode, AcknowledgementState, AcknowledgementStateConfig, ReliableSocket, SessionSocketConfig,
-    UnreliableSocket,
+    AcknowledgementMode, AcknowledgementState, AcknowledgementStateConfig, FecConfig, ReliableSocket,
+    SessionSocketConfig, UnreliableSocket,
     flow_control::{DeliveryClock, DeliveryMeter, DeliveryTap, FlowControlConfig},
 };
-use hopr_protocol_start::StartProtocol;
+use hopr_protocol_start::{ResumeTicket, StartProtocol, handshake::SessionCipher};
 use hopr_utils::network_types::utils::{AsyncWriteSink, DuplexIO};
 use tracing::{debug, instrument};
 
@@ -28,21 +29,27 @@ use crate::{
     flow_control::{PacedWriter, SurbSupply},
 };
 
-/// Wrapper for [`Capabilities`] that makes conversion to/from `u8` possible.
+/// Wrapper for [`Capabilities`] that makes conversion to/from `u16` possible.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct ByteCapabilities(pub Capabilities);
 
-impl TryFrom<u8> for ByteCapabilities {
+impl TryFrom<u16> for ByteCapabilities {
     type Error = GeneralError;
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
         Capabilities::new(value)
             .map(Self)
             .map_err(|_| GeneralError::ParseError("capabilities".into()))
     }
 }
 
-impl From<ByteCapabilities> for u8 {
+impl hopr_protocol_start::StartCapabilities for ByteCapabilities {
+    fn from_truncated(bits: u16) -> Self {
+        Self(Capabilities::new_truncated(bits))
+    }
+}
+
+impl From<ByteCapabilities> for u16 {
     fn from(value: ByteCapabilities) -> Self {
         *value.0.as_ref()
     }
@@ -82,6 +89,32 @@ pub const SESSION_APPLICATION_TAG: Tag = Tag::Reserved(ReservedTag::Session as u
 /// - `SessionTarget` describes where data received over the session is forwarded.
 pub use hopr_utils::network_types::types::{ServiceId, SessionId, SessionTarget};
 
+/// Removes the `removed` capabilities from `capabilities`, but keeps the capabilities they imply.
+///
+/// For example, removing [`Capability::NoDelay`] keeps [`Capability::Segmentation`],
+/// which a plain set difference would remove as well. Capabilities implying a removed one
+/// are removed too.
+pub(crate) fn without_capabilities(capabilities: Capabilities, removed: Capabilities) -> Capabilities {
+    let implied_by = |flag: Capability| {
+        Capability::LIST
+            .iter()
+            .filter(move |&&other| other != flag && Capabilities::from(flag).contains(other))
+            .fold(Capabilities::empty(), |implied, &other| implied | other)
+    };
+
+    // Remove only the bits that are not shared with implied capabilities,
+    // skipping capabilities that are in `removed` only because another one implies them
+    let remaining = removed
+        .into_iter()
+        .filter(|&flag| !removed.into_iter().any(|other| implied_by(other).contains(flag)))
+        .fold(capabilities, |remaining, flag| {
+            remaining - (Capabilities::from(flag) - implied_by(flag))
+        });
+
+    // Keep only the capabilities whose bits all remained
+    Capabilities::new_truncated(remaining.bits())
+}
+
 pub(crate) fn caps_to_ack_mode(caps: Capabilities) -> AcknowledgementMode {
     if caps.contains(Capability::RetransmissionAck | Capability::RetransmissionNack) {
         AcknowledgementMode::Both
@@ -139,6 +172,157 @@ pub struct HoprSessionConfig {
     /// Default is 0.
     #[default(0)]
     pub max_buffered_segments: usize,
+    /// Forward error correction used if the [`Capability::ForwardErrorCorrection`] is set.
+    pub fec: FecConfig,
+    /// Version of the Session protocol agreed with the counterparty during the Session establishment.
+    ///
+    /// Default is 1.
+    #[default(1)]
+    pub protocol_version: u8,
+}
+
+/// Builds the Session protocol socket (or the raw transport) over the HOPR transport,
+/// using Session protocol messages of at most `C` bytes.
+fn open_socket<const C: usize, Tx, Rx>(
+    id: SessionId,
+    routing: DestinationRouting,
+    cfg: HoprSessionConfig,
+    hopr: (Tx, Rx),
+    surb_mgmt: Option<Arc<BalancerStateValues>>,
+    flow_control: Option<FlowControlConfig>,
+) -> Result<Box<dyn AsyncReadWrite>, TransportSessionError>
+where
+    Tx: futures::Sink<(DestinationRouting, ApplicationDataOut)> + Send + Unpin + 'static,
+    Rx: futures::Stream<Item = ApplicationDataIn> + Send + Unpin + 'static,
+    Tx::Error: std::error::Error + Send + Sync,
+{
+    #[cfg(feature = "telemetry")]
+    let (session_id_write, session_id_read) = (id, id);
+
+    // Wrap the HOPR transport so that it appears as regular transport to the SessionSocket
+    let transport = DuplexIO(
+        AsyncWriteSink::<C, _>(hopr.0.sink_map_err(std::io::Error::other).with(move |buf: Box<[u8]>| {
+            #[cfg(feature = "telemetry")]
+            crate::telemetry::record_session_write(&session_id_write, buf.len());
+            // The Session protocol does not set any packet info on outgoing packets.
+            // However, the SessionManager on top usually overrides this.
+            futures::future::ready(
+                ApplicationData::new(SESSION_APPLICATION_TAG, buf.into_vec())
+                    .map(|data| (routing.clone(), ApplicationDataOut::with_no_packet_info(data)))
+                    .map_err(std::io::Error::other),
+            )
+        })),
+        // The Session protocol ignores the packet info on incoming packets.
+        // It is typically SessionManager's job to interpret those.
+        hopr.1
+            .map(move |data| {
+                #[cfg(feature = "telemetry")]
+                crate::telemetry::record_session_read(&session_id_read, data.data.plain_text.len());
+                Ok::<_, std::io::Error>(data.data.plain_text)
+            })
+            .into_async_read(),
+    );
+
+    // Based on the requested capabilities, see if we should use the Session protocol
+    let inner: Box<dyn AsyncReadWrite> = if cfg.capabilities.contains(Capability::Segmentation) {
+        let socket_cfg = SessionSocketConfig {
+            frame_size: cfg.frame_mtu,
+            frame_timeout: cfg.frame_timeout,
+            capacity: SESSION_SOCKET_CAPACITY,
+            flush_immediately: cfg.capabilities.contains(Capability::NoDelay),
+            max_buffered_segments: cfg.max_buffered_segments,
+            fec: cfg
+                .capabilities
+                .contains(Capability::ForwardErrorCorrection)
+                .then_some(cfg.fec),
+            protocol_version: cfg.protocol_version,
+            ..Default::default()
+        };
+
+        // Need to test the capabilities separately, because any Retransmission capability
+        // implies Segmentation, and therefore `is_disjoint` would fail
+        if cfg.capabilities.contains(Capability::RetransmissionAck)
+            || cfg.capabilities.contains(Capability::RetransmissionNack)
+        {
+            let fc = flow_control;
+
+            // TODO: update config values
+            let ack_cfg = AcknowledgementStateConfig {
+                // This is a very coarse assumption, that a single 3-hop packet
+                // takes on average 200 ms to deliver.
+                // We can no longer base this timeout on the number of hops because
+                // it is not known for SURB-based routing.
+                expected_packet_latency: Duration::from_millis(200),
+                mode: caps_to_ack_mode(cfg.capabilities),
+                backoff_base: 0.2,
+                max_incoming_frame_retries: 1,
+                // Under flow control the sender is paced to the SURB drain rate, so an un-acked
+                // frame is usually just a *delayed* ack on a temporarily-starved return path, not
+                // a genuine loss. The retry budget is a flow-control config knob (`frame_retries`,
+                // default 2 = original); a robust profile raises it so delayed frames recover
+                // instead of being abandoned (an abandoned frame leaves a gap → stream corruption).
+                // `.max(1)`: never drop the retry budget to 0 — an abandoned frame under
+                // reliable-mode flow control leaves a gap and corrupts the stream.
+                max_outgoing_frame_retries: fc.map(|c| c.frame_retries.max(1) as usize).unwrap_or(2),
+                ..Default::default()
+            };
+
+            debug!(
+                ?socket_cfg,
+                ?ack_cfg,
+                flow_control = fc.is_some(),
+                "opening new stateful session socket"
+            );
+
+            // Opt-in client-side flow control: when enabled, install the honest-clock tap on the
+            // ack state and keep the paired clock to drive the paced writer.
+            let (ack_state, flow_control) = match fc {
+                Some(fc_cfg) => {
+                    let meter = DeliveryMeter::default();
+                    let ack_state = AcknowledgementState::<C>::new(id, ack_cfg)
+                        .with_delivery_tap(DeliveryTap::new(meter.clone(), cfg.frame_mtu));
+                    let clock = DeliveryClock::new(meter, Some(ack_cfg.expected_packet_latency));
+                    (ack_state, Some((fc_cfg, clock)))
+                }
+                None => (AcknowledgementState::<C>::new(id, ack_cfg), None),
+            };
+
+            let socket = ReliableSocket::new(
+                transport,
+                ack_state,
+                socket_cfg,
+                #[cfg(feature = "telemetry")]
+                NoopTracker,
+            )?;
+
+            match flow_control {
+                Some((fc_cfg, clock)) => {
+                    let surb_state = surb_mgmt
+                        .clone()
+                        .unwrap_or_else(|| Arc::new(BalancerStateValues::default()));
+                    let supply = SurbSupply::new(surb_state, cfg.frame_mtu);
+                    debug!(?fc_cfg, "wrapping session socket with paced flow-control writer");
+                    Box::new(PacedWriter::new(socket, fc_cfg, clock, supply))
+                }
+                None => Box::new(socket),
+            }
+        } else {
+            debug!(?socket_cfg, "opening new stateless session socket");
+
+            Box::new(UnreliableSocket::<C>::new_stateless(
+                id,
+                transport,
+                socket_cfg,
+                #[cfg(feature = "telemetry")]
+                NoopTracker,
+            )?)
+        }
+    } else {
+        debug!("opening raw session socket");
+        Box::new(transport)
+    };
+
+    Ok(inner)
 }
 
 /// Represents the Session protocol socket over HOPR.
@@ -152,11 +336,25 @@ pub struct HoprSession {
     inner: Box<dyn AsyncReadWrite>,
     routing: DestinationRouting,
     cfg: HoprSessionConfig,
+    resume_ticket: Option<ResumeTicket>,
     on_close: Option<Box<dyn FnOnce(SessionId, ClosureReason) + Send + Sync>>,
 }
 
 pub(crate) const SESSION_SOCKET_CAPACITY: usize = 16384;
 
+/// Highest version of the Session protocol offered and accepted during the Session establishment.
+pub(crate) const SESSION_PROTOCOL_VERSION: u8 =
+    hopr_protocol_session::types::SessionMessage::<{ ApplicationData::PAYLOAD_SIZE }>::VERSION;
+
+/// Lowest version of the Session protocol, used when the counterparty does not negotiate any.
+pub(crate) const MIN_SESSION_PROTOCOL_VERSION: u8 =
+    hopr_protocol_session::types::SessionMessage::<{ ApplicationData::PAYLOAD_SIZE }>::MIN_VERSION;
+
+/// Maximum size of a Session protocol message of an encrypted Session.
+///
+/// Every message is sealed by the [`SessionCipher`] before it is sent over HOPR.
+pub(crate) const ENCRYPTED_PAYLOAD_SIZE: usize = ApplicationData::PAYLOAD_SIZE - SessionCipher::OVERHEAD;
+
 impl HoprSession {
     /// Creates a new HOPR Session.
     ///
@@ -200,132 +398,18 @@ impl HoprSession {
         Rx: futures::Stream<Item = ApplicationDataIn> + Send + Unpin + 'static,
         Tx::Error: std::error::Error + Send + Sync,
     {
-        let routing_clone = routing.clone();
-
-        #[cfg(feature = "telemetry")]
-        let (session_id_write, session_id_read) = (id, id);
-
-        // Wrap the HOPR transport so that it appears as regular transport to the SessionSocket
-        let transport = DuplexIO(
-            AsyncWriteSink::<{ ApplicationData::PAYLOAD_SIZE }, _>(hopr.0.sink_map_err(std::io::Error::other).with(
-                move |buf: Box<[u8]>| {
-                    #[cfg(feature = "telemetry")]
-                    crate::telemetry::record_session_write(&session_id_write, buf.len());
-                    // The Session protocol does not set any packet info on outgoing packets.
-                    // However, the SessionManager on top usually overrides this.
-                    futures::future::ready(
-                        ApplicationData::new(SESSION_APPLICATION_TAG, buf.into_vec())
-                            .map(|data| (routing_clone.clone(), ApplicationDataOut::with_no_packet_info(data)))
-                            .map_err(std::io::Error::other),
-                    )
-                },
-            )),
-            // The Session protocol ignores the packet info on incoming packets.
-            // It is typically SessionManager's job to interpret those.
-            hopr.1
-                .map(move |data| {
-                    #[cfg(feature = "telemetry")]
-                    crate::telemetry::record_session_read(&session_id_read, data.data.plain_text.len());
-                    Ok::<_, std::io::Error>(data.data.plain_text)
-                })
-                .into_async_read(),
-        );
-
-        // Based on the requested capabilities, see if we should use the Session protocol
-        let inner: Box<dyn AsyncReadWrite> = if cfg.capabilities.contains(Capability::Segmentation) {
-            let socket_cfg = SessionSocketConfig {
-                frame_size: cfg.frame_mtu,
-                frame_timeout: cfg.frame_timeout,
-                capacity: SESSION_SOCKET_CAPACITY,
-                flush_immediately: cfg.capabilities.contains(Capability::NoDelay),
-                max_buffered_segments: cfg.max_buffered_segments,
-                ..Default::default()
-            };
-
-            // Need to test the capabilities separately, because any Retransmission capability
-            // implies Segmentation, and therefore `is_disjoint` would fail
-            if cfg.capabilities.contains(Capability::RetransmissionAck)
-                || cfg.capabilities.contains(Capability::RetransmissionNack)
-            {
-                let fc = flow_control;
-
-                // TODO: update config values
-                let ack_cfg = AcknowledgementStateConfig {
-                    // This is a very coarse assumption, that a single 3-hop packet
-                    // takes on average 200 ms to deliver.
-                    // We can no longer base this timeout on the number of hops because
-                    // it is not known for SURB-based routing.
-                    expected_packet_latency: Duration::from_millis(200),
-                    mode: caps_to_ack_mode(cfg.capabilities),
-                    backoff_base: 0.2,
-                    max_incoming_frame_retries: 1,
-                    // Under flow control the sender is paced to the SURB drain rate, so an un-acked
-                    // frame is usually just a *delayed* ack on a temporarily-starved return path, not
-                    // a genuine loss. The retry budget is a flow-control config knob (`frame_retries`,
-                    // default 2 = original); a robust profile raises it so delayed frames recover
-                    // instead of being abandoned (an abandoned frame leaves a gap → stream corruption).
-                    // `.max(1)`: never drop the retry budget to 0 — an abandoned frame under
-                    // reliable-mode flow control leaves a gap and corrupts the stream.
-                    max_outgoing_frame_retries: fc.map(|c| c.frame_retries.max(1) as usize).unwrap_or(2),
-                    ..Default::default()
-                };
-
-                debug!(
-                    ?socket_cfg,
-                    ?ack_cfg,
-                    flow_control = fc.is_some(),
-                    "opening new stateful session socket"
-                );
-
-                // Opt-in client-side flow control: when enabled, install the honest-clock tap on the
-                // ack state and keep the paired clock to drive the paced writer.
-                let (ack_state, flow_control) = match fc {
-                    Some(fc_cfg) => {
-                        let meter = DeliveryMeter::default();
-                        let ack_state = AcknowledgementState::<{ ApplicationData::PAYLOAD_SIZE }>::new(id, ack_cfg)
-                            .with_delivery_tap(DeliveryTap::new(meter.clone(), cfg.frame_mtu));
-                        let clock = DeliveryClock::new(meter, Some(ack_cfg.expected_packet_latency));
-                        (ack_state, Some((fc_cfg, clock)))
-                    }
-                    None => (
-                        AcknowledgementState::<{ ApplicationData::PAYLOAD_SIZE }>::new(id, ack_cfg),
-                        None,
-                    ),
-                };
-
-                let socket = ReliableSocket::new(
-                    transport,
-                    ack_state,
-                    socket_cfg,
-                    #[cfg(feature = "telemetry")]
-                    NoopTracker,
-                )?;
-
-                match flow_control {
-                    Some((fc_cfg, clock)) => {
-                        let surb_state = surb_mgmt
-                            .clone()
-                            .unwrap_or_else(|| Arc::new(BalancerStateValues::default()));
-                        let supply = SurbSupply::new(surb_state, cfg.frame_mtu);
-                        debug!(?fc_cfg, "wrapping session socket with paced flow-control writer");
-                        Box::new(PacedWriter::new(socket, fc_cfg, clock, supply))
-                    }
-                    None => Box::new(socket),
-                }
-            } else {
-                debug!(?socket_cfg, "opening new stateless session socket");
-
-                Box::new(UnreliableSocket::<{ ApplicationData::PAYLOAD_SIZE }>::new_stateless(
-                    id,
-                    transport,
-                    socket_cfg,
-                    #[cfg(feature = "telemetry")]
-                    NoopTracker,
-                )?)
-            }
+        // Encrypted Sessions leave room for the AEAD overhead in each Session protocol message
+        let inner = if cfg.capabilities.contains(Capability::Encryption) {
+            open_socket::<ENCRYPTED_PAYLOAD_SIZE, _, _>(id, routing.clone(), cfg, hopr, surb_mgmt, flow_control)?
         } else {
-            debug!("opening raw session socket");
-            Box::new(transport)
+            open_socket::<{ ApplicationData::PAYLOAD_SIZE }, _, _>(
+                id,
+                routing.clone(),
+                cfg,
+                hopr,
+                surb_mgmt,
+                flow_control,
+            )?
         };
 
         Ok(Self {
@@ -333,10 +417,17 @@ impl HoprSession {
             inner,
             routing,
             cfg,
+            resume_ticket: None,
             on_close,
         })
     }
 
+    /// Sets the ticket issued by the Exit for resuming this Session.
+    pub(crate) fn with_resume_ticket(mut self, resume_ticket: Option<ResumeTicket>) -> Self {
+        self.resume_ticket = resume_ticket;
+        self
+    }
+
     /// ID of this Session.
     pub fn id(&self) -> &SessionId {
         &self.id
@@ -351,6 +442,15 @@ impl HoprSession {
     pub fn config(&self) -> &HoprSessionConfig {
         &self.cfg
     }
+
+    /// Ticket issued by the Exit on establishment of an outgoing Session opened with the
+    /// [`Capability::Resumption`] flag.
+    ///
+    /// Keeping it allows [resuming](crate::SessionManager::resume_session_with) the Session
+    /// even after this object or its [`SessionManager`](crate::SessionManager) has been lost.
+    pub fn resume_ticket(&self) -> Option<ResumeTicket> {
+        self.resume_ticket
+    }
 }
 
 impl std::fmt::Debug for HoprSession {
@@ -454,25 +554,61 @@ mod tests {
     // --- ByteCapabilities tests ---
 
     #[test]
-    fn byte_capabilities_roundtrip_via_u8() -> anyhow::Result<()> {
-        let flags: Capabilities = Capability::Segmentation.into();
+    fn byte_capabilities_roundtrip_via_u16() -> anyhow::Result<()> {
+        let flags: Capabilities = Capability::Segmentation | Capability::Encryption;
         let caps = ByteCapabilities::from(flags);
-        let byte_val: u8 = caps.into();
+        let byte_val: u16 = caps.into();
         let restored = ByteCapabilities::try_from(byte_val)?;
         assert_eq!(caps, restored);
         Ok(())
     }
 
     #[test]
-    fn byte_capabilities_invalid_bits_are_rejected() {
-        // 0xFF has bits set that don't correspond to any Capability
-        assert!(ByteCapabilities::try_from(0xFF_u8).is_err());
+    fn byte_capabilities_should_reject_unassigned_bits() -> anyhow::Result<()> {
+        assert_eq!(Capabilities::full(), ByteCapabilities::try_from(0xFF_u16)?.0);
+        assert!(ByteCapabilities::try_from(0x100_u16).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn start_initiation_should_ignore_unknown_optional_capabilities() -> anyhow::Result<()> {
+        const UNKNOWN: u16 = 0x100;
+        let required: Capabilities = Capability::Segmentation.into();
+        let optional: Capabilities = Capability::RetransmissionAck.into();
+
+        // An initiator on a newer version offers an optional capability unknown to this node
+        let initiation = |unknown_optional: u16| {
+            hopr_protocol_start::StartProtocol::<SessionId, SessionTarget, u16>::StartSession(
+                hopr_protocol_start::StartInitiation {
+                    challenge: 1,
+                    target: SessionTarget::ExitNode(42),
+                    capabilities: *(required | optional).as_ref() | UNKNOWN,
+                    optional_capabilities: Some(*optional.as_ref() | unknown_optional),
+                    session_protocol_version: Some(1),
+                    additional_data: 0,
+                    key_share: None,
+                },
+            )
+            .encode()
+        };
+
+        let (tag, data) = initiation(UNKNOWN)?;
+        let HoprStartProtocol::StartSession(init) = HoprStartProtocol::decode(tag, &data)? else {
+            anyhow::bail!("not an initiation");
+        };
+        assert_eq!(required | optional, init.capabilities.0);
+        assert_eq!(Some(optional), init.optional_capabilities.map(Capabilities::from));
+
+        // Unknown capabilities that are not optional still cannot be accepted
+        let (tag, data) = initiation(0)?;
+        assert!(HoprStartProtocol::decode(tag, &data).is_err());
+        Ok(())
     }
 
     #[test]
     fn byte_capabilities_empty_is_zero() {
         let caps = ByteCapabilities::from(Capabilities::empty());
-        let byte_val: u8 = caps.into();
+        let byte_val: u16 = caps.into();
         assert_eq!(byte_val, 0);
     }
 
@@ -480,12 +616,40 @@ mod tests {
     fn byte_capabilities_combined_flags() -> anyhow::Result<()> {
         let caps: Capabilities = Capability::Segmentation | Capability::NoRateControl;
         let byte_caps = ByteCapabilities::from(caps);
-        let byte_val: u8 = byte_caps.into();
+        let byte_val: u16 = byte_caps.into();
         let restored = ByteCapabilities::try_from(byte_val)?;
         assert_eq!(*restored.as_ref(), caps);
         Ok(())
     }
 
+    #[test]
+    fn without_capabilities_should_keep_implied_capabilities() {
+        let caps: Capabilities = Capability::NoDelay | Capability::NoRateControl;
+        assert_eq!(
+            Capability::Segmentation | Capability::NoRateControl,
+            without_capabilities(caps, Capability::NoDelay.into())
+        );
+
+        let caps: Capabilities = Capability::RetransmissionAck | Capability::ForwardErrorCorrection;
+        assert_eq!(
+            Capabilities::from(Capability::RetransmissionAck),
+            without_capabilities(caps, Capability::ForwardErrorCorrection.into())
+        );
+        assert_eq!(
+            caps,
+            without_capabilities(caps, Capability::Resumption | Capability::Encryption)
+        );
+    }
+
+    #[test]
+    fn without_capabilities_should_remove_capabilities_implying_removed_ones() {
+        let caps: Capabilities = Capability::NoDelay | Capability::NoRateControl;
+        assert_eq!(
+            Capabilities::from(Capability::NoRateControl),
+            without_capabilities(caps, Capability::Segmentation.into())
+        );
+    }
+
     // --- caps_to_ack_mode tests ---
 
     #[test]
@@ -517,6 +681,13 @@ mod tests {
         assert_eq!(caps_to_ack_mode(caps), AcknowledgementMode::Partial);
     }
 
+    #[test]
+    fn forward_error_correction_capability_should_imply_segmentation() {
+        let caps: Capabilities = Capability::ForwardErrorCorrection.into();
+        assert!(caps.contains(Capability::Segmentation));
+        assert_eq!(caps_to_ack_mode(caps), AcknowledgementMode::Partial);
+    }
+
     // --- ClosureReason tests ---
 
     #[test]
@@ -731,4 +902,69 @@ mod tests {
 
         Ok(())
     }
+
+    #[test_log::test(tokio::test)]
+    async fn test_session_with_fec_should_tolerate_packet_loss() -> anyhow::Result<()> {
+        let dst: Address = (&ChainKeypair::random()).into();
+        let id: SessionId = HoprPseudonym::random();
+        const DATA_LEN: usize = 5000;
+
+        let (alice_tx, bob_rx) = futures::channel::mpsc::unbounded::<(DestinationRouting, ApplicationDataOut)>();
+        let (bob_tx, alice_rx) = futures::channel::mpsc::unbounded::<(DestinationRouting, ApplicationDataOut)>();
+
+        // Each frame has 2 data segments and 1 repair segment, so one loss per frame can be recovered
+        let cfg = HoprSessionConfig {
+            capabilities: Capability::ForwardErrorCorrection.into(),
+            ..Default::default()
+        };
+
+        let mut alice_session = HoprSession::new(
+            id,
+            DestinationRouting::forward_only(dst, RoutingOptions::Hops(0.try_into()?)),
+            cfg,
+            (
+                alice_tx,
+                alice_rx.map(|(_, data)| ApplicationDataIn {
+                    data: data.data,
+                    packet_info: Default::default(),
+                }),
+            ),
+            None,
+        )?;
+
+        let mut bob_session = HoprSession::new(
+            id,
+            DestinationRouting::Return(id.into()),
+            cfg,
+            (
+                bob_tx,
+                bob_rx
+                    .enumerate()
+                    .filter(|(i, _)| futures::future::ready(i % 5 != 4))
+                    .map(|(_, (_, data))| ApplicationDataIn {
+                        data: data.data,
+                        packet_info: Default::default(),
+                    }),
+            ),
+            None,
+        )?;
+
+        let alice_sent = hopr_api::types::crypto_random::random_bytes::<DATA_LEN>();
+        let mut bob_recv = [0u8; DATA_LEN];
+
+        tokio::time::timeout(Duration::from_secs(1), alice_session.write_all(&alice_sent))
+            .await
+            .context("alice write failed")?
+            .context("alice write timed out")?;
+        alice_session.flush().await?;
+
+        tokio::time::timeout(Duration::from_secs(1), bob_session.read_exact(&mut bob_recv))
+            .await
+            .context("bob read failed")?
+            .context("bob read timed out")?;
+
+        assert_eq!(alice_sent, bob_recv);
+
+        Ok(())
+    }
 }
@@ -4,7 +4,7 @@ use std::{
     sync::Arc,
 };
 
-use crate::bitmap::TagBitmap;
+use crate::allocator::TagPool;
 
 /// A tag value that is automatically returned to its allocator on drop.
 ///
@@ -12,12 +12,20 @@ use crate::bitmap::TagBitmap;
 pub struct AllocatedTag {
     value: u64,
     index: u64,
-    bitmap: Arc<TagBitmap>,
+    owner: usize,
+    lender: usize,
+    pool: Arc<TagPool>,
 }
 
 impl AllocatedTag {
-    pub(crate) fn new(value: u64, index: u64, bitmap: Arc<TagBitmap>) -> Self {
-        Self { value, index, bitmap }
+    pub(crate) fn new(value: u64, index: u64, owner: usize, lender: usize, pool: Arc<TagPool>) -> Self {
+        Self {
+            value,
+            index,
+            owner,
+            lender,
+            pool,
+        }
     }
 
     pub fn value(&self) -> u64 {
@@ -27,7 +35,7 @@ impl AllocatedTag {
 
 impl Drop for AllocatedTag {
     fn drop(&mut self) {
-        self.bitmap.deallocate(self.index);
+        self.pool.release(self.owner, self.lender, self.index);
     }
 }
 
@@ -1,39 +1,195 @@
-use std::{ops::Range, sync::Arc};
+use std::{
+    ops::Range,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
 
-use crate::{TagAllocator, allocated_tag::AllocatedTag, bitmap::TagBitmap};
+use crate::{
+    PartitionStats, TagAllocator, Usage, allocated_tag::AllocatedTag, bitmap::TagBitmap, config::TagQuarantineConfig,
+};
 
-/// A partition allocator that yields unique tags from a contiguous sub-range.
+/// A single partition of the shared tag pool.
 ///
-/// Tags are tracked via a lock-free bitmap — one bit per tag. Allocation
-/// scans for the first available bit; deallocation (via [`AllocatedTag::drop`])
-/// sets the bit back.
-pub(crate) struct PartitionAllocator {
+/// The partition owns the contiguous sub-range `[base, base + capacity)`. Up to
+/// `capacity - guaranteed` of its tags may be lent to other partitions whose own
+/// sub-range is exhausted, so the partition itself can always hold at least
+/// `guaranteed` tags.
+struct Partition {
+    usage: Usage,
     base: u64,
-    bitmap: Arc<TagBitmap>,
+    bitmap: TagBitmap,
+    guaranteed: u64,
+    /// Tags of this partition's sub-range currently held by other partitions.
+    lent: AtomicU64,
+    /// Tags currently held by this partition, regardless of which sub-range they come from.
+    in_use: AtomicU64,
+    /// Tags currently held by this partition that come from another partition's sub-range.
+    borrowed: AtomicU64,
+    high_water_mark: AtomicU64,
+    allocation_failures: AtomicU64,
 }
 
-impl PartitionAllocator {
-    pub fn new(base: u64, size: u64) -> Self {
+impl Partition {
+    fn new(usage: Usage, base: u64, capacity: u64, guaranteed: u64, quarantine: TagQuarantineConfig) -> Self {
         Self {
+            usage,
             base,
-            bitmap: Arc::new(TagBitmap::new(size)),
+            bitmap: TagBitmap::with_quarantine(capacity, quarantine),
+            guaranteed,
+            lent: AtomicU64::new(0),
+            in_use: AtomicU64::new(0),
+            borrowed: AtomicU64::new(0),
+            high_water_mark: AtomicU64::new(0),
+            allocation_failures: AtomicU64::new(0),
+        }
+    }
+
+    /// Takes a tag index from this partition's sub-range on behalf of another partition.
+    ///
+    /// The lent slot is reserved before touching the bitmap, so concurrent borrowers can
+    /// never push the partition below its guaranteed minimum. Lent tags still in quarantine
+    /// count as lent, as they cannot be used by this partition either.
+    fn lend(&self) -> Option<u64> {
+        let lendable = self.bitmap.capacity() - self.guaranteed;
+        self.lent
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |lent| {
+                (lent + self.bitmap.quarantined_lent() < lendable).then_some(lent + 1)
+            })
+            .ok()?;
+
+        let index = self.bitmap.allocate();
+        if index.is_none() {
+            self.lent.fetch_sub(1, Ordering::AcqRel);
+        }
+        index
+    }
+
+    fn stats(&self) -> PartitionStats {
+        PartitionStats {
+            usage: self.usage,
+            capacity: self.bitmap.capacity(),
+            guaranteed: self.guaranteed,
+            in_use: self.in_use.load(Ordering::Acquire),
+            borrowed: self.borrowed.load(Ordering::Acquire),
+            lent: self.lent.load(Ordering::Acquire),
+            quarantined: self.bitmap.quarantined(),
+            high_water_mark: self.high_water_mark.load(Ordering::Acquire),
+            allocation_failures: self.allocation_failures.load(Ordering::Acquire),
+        }
+    }
+}
+
+/// Tag pool shared by all partition allocators created together.
+///
+/// Each partition first allocates from its own sub-range and only borrows from
+/// the other partitions once that is exhausted.
+pub(crate) struct TagPool {
+    partitions: Box<[Partition]>,
+}
+
+impl TagPool {
+    /// Lays out the given `(usage, capacity, guaranteed)` partitions contiguously from `start`.
+    ///
+    /// Freed tags of every partition are subject to the given `quarantine`.
+    /// The caller is responsible for validating the partition sizes.
+    pub fn new(start: u64, partitions: &[(Usage, u64, u64)], quarantine: TagQuarantineConfig) -> Arc<Self> {
+        let mut base = start;
+        let partitions = partitions
+            .iter()
+            .map(|(usage, capacity, guaranteed)| {
+                let partition = Partition::new(*usage, base, *capacity, *guaranteed, quarantine);
+                base += capacity;
+                partition
+            })
+            .collect();
+        Arc::new(Self { partitions })
+    }
+
+    fn allocate(self: &Arc<Self>, owner: usize) -> Option<AllocatedTag> {
+        let home = &self.partitions[owner];
+        let (lender, index) = match home.bitmap.allocate() {
+            Some(index) => (owner, index),
+            None => match self.borrow(owner) {
+                Some(borrowed) => borrowed,
+                None => {
+                    home.allocation_failures.fetch_add(1, Ordering::Relaxed);
+                    return None;
+                }
+            },
+        };
+
+        if lender != owner {
+            home.borrowed.fetch_add(1, Ordering::AcqRel);
+        }
+        let in_use = home.in_use.fetch_add(1, Ordering::AcqRel) + 1;
+        home.high_water_mark.fetch_max(in_use, Ordering::AcqRel);
+
+        let value = self.partitions[lender].base + index;
+        Some(AllocatedTag::new(value, index, owner, lender, self.clone()))
+    }
+
+    fn borrow(&self, owner: usize) -> Option<(usize, u64)> {
+        self.partitions
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != owner)
+            .find_map(|(i, partition)| partition.lend().map(|index| (i, index)))
+    }
+
+    /// Returns a tag previously handed out to partition `owner` from the sub-range of `lender`.
+    pub fn release(&self, owner: usize, lender: usize, index: u64) {
+        if lender != owner {
+            // Quarantine the tag before giving up the lent slot, so it is never unaccounted
+            self.partitions[lender].bitmap.deallocate_lent(index);
+            self.partitions[lender].lent.fetch_sub(1, Ordering::AcqRel);
+            self.partitions[owner].borrowed.fetch_sub(1, Ordering::AcqRel);
+        } else {
+            self.partitions[lender].bitmap.deallocate(index);
         }
+        self.partitions[owner].in_use.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// A partition allocator that yields unique tags, primarily from its own contiguous sub-range.
+///
+/// Tags are tracked via a lock-free bitmap — one bit per tag. Allocation
+/// scans for the first available bit; deallocation (via [`AllocatedTag::drop`])
+/// sets the bit back. When the own sub-range is exhausted, the allocator borrows
+/// spare tags from the other partitions of the same [`TagPool`].
+pub(crate) struct PartitionAllocator {
+    pool: Arc<TagPool>,
+    index: usize,
+}
+
+impl PartitionAllocator {
+    pub fn new(pool: Arc<TagPool>, index: usize) -> Self {
+        assert!(index < pool.partitions.len(), "partition index out of range");
+        Self { pool, index }
+    }
+
+    fn partition(&self) -> &Partition {
+        &self.pool.partitions[self.index]
     }
 }
 
 impl TagAllocator for PartitionAllocator {
     fn allocate(&self) -> Option<AllocatedTag> {
-        self.bitmap
-            .allocate()
-            .map(|index| AllocatedTag::new(self.base + index, index, self.bitmap.clone()))
+        self.pool.allocate(self.index)
     }
 
     fn capacity(&self) -> u64 {
-        self.bitmap.capacity()
+        self.partition().bitmap.capacity()
     }
 
     fn tag_range(&self) -> Range<u64> {
-        self.base..self.base + self.bitmap.capacity()
+        let partition = self.partition();
+        partition.base..partition.base + partition.bitmap.capacity()
+    }
+
+    fn stats(&self) -> PartitionStats {
+        self.partition().stats()
     }
 }
 
@@ -41,9 +197,31 @@ impl TagAllocator for PartitionAllocator {
 mod tests {
     use super::*;
 
+    fn single(base: u64, size: u64) -> PartitionAllocator {
+        PartitionAllocator::new(
+            TagPool::new(base, &[(Usage::Session, size, size)], Default::default()),
+            0,
+        )
+    }
+
+    fn pair(first: (u64, u64), second: (u64, u64)) -> (PartitionAllocator, PartitionAllocator) {
+        let pool = TagPool::new(
+            100,
+            &[
+                (Usage::Session, first.0, first.1),
+                (Usage::ProvingTelemetry, second.0, second.1),
+            ],
+            Default::default(),
+        );
+        (
+            PartitionAllocator::new(pool.clone(), 0),
+            PartitionAllocator::new(pool, 1),
+        )
+    }
+
     #[test]
     fn allocates_sequentially_from_base() {
-        let alloc = PartitionAllocator::new(100, 3);
+        let alloc = single(100, 3);
         let t0 = alloc.allocate().unwrap();
         let t1 = alloc.allocate().unwrap();
         let t2 = alloc.allocate().unwrap();
@@ -54,7 +232,7 @@ mod tests {
 
     #[test]
     fn exhaustion_returns_none() {
-        let alloc = PartitionAllocator::new(10, 2);
+        let alloc = single(10, 2);
         let _t0 = alloc.allocate().unwrap();
         let _t1 = alloc.allocate().unwrap();
         assert!(alloc.allocate().is_none());
@@ -62,7 +240,7 @@ mod tests {
 
     #[test]
     fn drop_returns_tag_to_pool() {
-        let alloc = PartitionAllocator::new(50, 2);
+        let alloc = single(50, 2);
         let t0 = alloc.allocate().unwrap();
         let _t1 = alloc.allocate().unwrap();
         assert!(alloc.allocate().is_none());
@@ -78,7 +256,7 @@ mod tests {
     fn concurrent_no_duplicates() {
         use std::{collections::HashSet, thread};
 
-        let alloc = Arc::new(PartitionAllocator::new(1, 1000));
+        let alloc = Arc::new(single(1, 1000));
         let mut handles = Vec::new();
 
         for _ in 0..10 {
@@ -97,4 +275,96 @@ mod tests {
         let all_values: HashSet<u64> = all_tags.iter().flatten().map(|t| t.value()).collect();
         assert_eq!(all_values.len(), 1000);
     }
+
+    #[test]
+    fn exhausted_partition_must_borrow_spare_tags_from_other_partitions() -> anyhow::Result<()> {
+        let (session, probing) = pair((2, 2), (10, 4));
+
+        let own: Vec<AllocatedTag> = (0..2).filter_map(|_| session.allocate()).collect();
+        assert_eq!(own.len(), 2);
+
+        // Only 10 - 4 = 6 probing tags may be lent out.
+        let borrowed: Vec<AllocatedTag> = (0..10).filter_map(|_| session.allocate()).collect();
+        assert_eq!(borrowed.len(), 6);
+        assert!(borrowed.iter().all(|t| probing.tag_range().contains(&t.value())));
+
+        let stats = session.stats();
+        assert_eq!(stats.in_use, 8);
+        assert_eq!(stats.borrowed, 6);
+        assert_eq!(stats.high_water_mark, 8);
+        assert_eq!(stats.allocation_failures, 4);
+        assert_eq!(probing.stats().lent, 6);
+
+        Ok(())
+    }
+
+    #[test]
+    fn borrowing_must_never_violate_guaranteed_minimum() -> anyhow::Result<()> {
+        let (session, probing) = pair((1, 1), (8, 3));
+
+        let _session_tags: Vec<AllocatedTag> = (0..20).filter_map(|_| session.allocate()).collect();
+        let probing_tags: Vec<AllocatedTag> = (0..20).filter_map(|_| probing.allocate()).collect();
+        assert_eq!(probing_tags.len(), 3, "probing must still get its guaranteed minimum");
+
+        // Session partition lends nothing because its guarantee equals its capacity.
+        assert_eq!(probing.stats().borrowed, 0);
+        assert_eq!(probing.stats().allocation_failures, 17);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dropping_borrowed_tag_must_return_it_to_the_lender() -> anyhow::Result<()> {
+        let (session, probing) = pair((1, 1), (2, 0));
+
+        let _own = session.allocate().ok_or(anyhow::anyhow!("expected own tag"))?;
+        let borrowed = session.allocate().ok_or(anyhow::anyhow!("expected borrowed tag"))?;
+        assert_eq!(probing.stats().lent, 1);
+
+        let value = borrowed.value();
+        drop(borrowed);
+
+        let stats = session.stats();
+        assert_eq!((stats.in_use, stats.borrowed, stats.high_water_mark), (1, 0, 2));
+        assert_eq!(probing.stats().lent, 0);
+
+        let t0 = probing.allocate().ok_or(anyhow::anyhow!("expected probing tag"))?;
+        assert_eq!(t0.value(), value);
+
+        Ok(())
+    }
+
+    #[test]
+    fn quarantined_lent_tag_must_still_count_against_the_lendable_tags() -> anyhow::Result<()> {
+        let pool = TagPool::new(
+            100,
+            &[(Usage::Session, 1, 1), (Usage::ProvingTelemetry, 3, 2)],
+            TagQuarantineConfig {
+                period: Some(std::time::Duration::from_secs(30)),
+                ..Default::default()
+            },
+        );
+        let (session, probing) = (
+            PartitionAllocator::new(pool.clone(), 0),
+            PartitionAllocator::new(pool, 1),
+        );
+
+        let _own = session.allocate().ok_or(anyhow::anyhow!("expected own tag"))?;
+        let borrowed = session.allocate().ok_or(anyhow::anyhow!("expected borrowed tag"))?;
+        assert!(probing.tag_range().contains(&borrowed.value()));
+        drop(borrowed);
+
+        let stats = probing.stats();
+        assert_eq!((stats.lent, stats.quarantined), (0, 1));
+
+        // The only lendable tag is still in quarantine, so nothing more can be lent
+        assert!(session.allocate().is_none());
+        assert_eq!(probing.stats().lent, 0);
+
+        // The probing partition still gets its guaranteed minimum
+        let probing_tags: Vec<AllocatedTag> = (0..3).filter_map(|_| probing.allocate()).collect();
+        assert_eq!(probing_tags.len(), 2);
+
+        Ok(())
+    }
 }
@@ -1,17 +1,84 @@
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Instant,
+};
+
+use parking_lot::Mutex;
+
+use crate::config::TagQuarantineConfig;
 
 /// Lock-free bitmap for tag availability tracking.
 ///
 /// Each bit represents a tag: `1` = available, `0` = allocated.
 /// Uses `AtomicU64` words so each word covers 64 tags.
+///
+/// Optionally, freed tags can be put into [quarantine](TagQuarantineConfig)
+/// instead of becoming available right away.
 pub(crate) struct TagBitmap {
     words: Box<[AtomicU64]>,
     capacity: u64,
+    quarantine: Option<Quarantine>,
+}
+
+/// A freed tag waiting in quarantine.
+struct QuarantinedTag {
+    index: u64,
+    since: Instant,
+    attempt: u64,
+    /// Whether the tag was lent to another partition before it was freed.
+    lent: bool,
+}
+
+/// Book-keeping of quarantined tags.
+///
+/// Tags enter the queue in the order they are freed, and since both the
+/// timestamp and the allocation attempt counter are monotonic, tags always
+/// leave the quarantine from the front of the queue. A separate bitmap
+/// (`1` = quarantined) allows detecting repeated deallocation in O(1).
+struct Quarantine {
+    cfg: TagQuarantineConfig,
+    bits: Box<[AtomicU64]>,
+    queue: Mutex<VecDeque<QuarantinedTag>>,
+    len: AtomicU64,
+    /// Number of quarantined tags that were lent to other partitions.
+    lent: AtomicU64,
+    attempts: AtomicU64,
+}
+
+impl Quarantine {
+    fn new(cfg: TagQuarantineConfig, num_words: u64) -> Self {
+        Self {
+            cfg,
+            bits: (0..num_words).map(|_| AtomicU64::new(0)).collect(),
+            queue: Mutex::new(VecDeque::new()),
+            len: AtomicU64::new(0),
+            lent: AtomicU64::new(0),
+            attempts: AtomicU64::new(0),
+        }
+    }
+
+    fn is_expired(&self, tag: &QuarantinedTag, now: Instant, attempt: u64) -> bool {
+        self.cfg
+            .period
+            .is_none_or(|period| now.saturating_duration_since(tag.since) >= period)
+            && self
+                .cfg
+                .allocations
+                .is_none_or(|allocations| attempt.saturating_sub(tag.attempt) > allocations)
+    }
 }
 
 impl TagBitmap {
     /// Create a new bitmap with all `capacity` tags marked as available.
+    #[cfg(test)]
     pub fn new(capacity: u64) -> Self {
+        Self::with_quarantine(capacity, TagQuarantineConfig::default())
+    }
+
+    /// Create a new bitmap with all `capacity` tags marked as available, which
+    /// quarantines freed tags according to the given configuration.
+    pub fn with_quarantine(capacity: u64, quarantine: TagQuarantineConfig) -> Self {
         let num_words = capacity.div_ceil(64);
         let mut words: Vec<AtomicU64> = (0..num_words).map(|_| AtomicU64::new(u64::MAX)).collect();
 
@@ -26,14 +93,22 @@ impl TagBitmap {
         Self {
             words: words.into_boxed_slice(),
             capacity,
+            quarantine: quarantine.is_enabled().then(|| Quarantine::new(quarantine, num_words)),
         }
     }
 
     /// Allocate the next available tag. Returns the tag index (0-based), or
-    /// `None` if all tags are in use.
+    /// `None` if all tags are in use or quarantined.
     ///
     /// Scans words sequentially and within each word picks the lowest set bit.
     pub fn allocate(&self) -> Option<u64> {
+        if let Some(quarantine) = &self.quarantine {
+            let attempt = quarantine.attempts.fetch_add(1, Ordering::AcqRel) + 1;
+            if quarantine.len.load(Ordering::Acquire) > 0 {
+                self.release_expired(quarantine, Instant::now(), attempt);
+            }
+        }
+
         for (word_idx, word) in self.words.iter().enumerate() {
             loop {
                 let val = word.load(Ordering::Acquire);
@@ -59,13 +134,83 @@ impl TagBitmap {
         self.capacity
     }
 
+    /// The number of freed tags currently waiting in quarantine.
+    pub fn quarantined(&self) -> u64 {
+        self.quarantine
+            .as_ref()
+            .map(|quarantine| quarantine.len.load(Ordering::Acquire))
+            .unwrap_or(0)
+    }
+
+    /// The number of freed tags waiting in quarantine that were lent to other partitions.
+    pub fn quarantined_lent(&self) -> u64 {
+        self.quarantine
+            .as_ref()
+            .map(|quarantine| quarantine.lent.load(Ordering::Acquire))
+            .unwrap_or(0)
+    }
+
     /// Return a previously allocated tag, making it available again.
+    ///
+    /// If quarantine is enabled, the tag becomes available only once it leaves the quarantine.
     pub fn deallocate(&self, index: u64) {
+        self.deallocate_inner(index, false)
+    }
+
+    /// Same as [`deallocate`](Self::deallocate), for a tag that was lent to another partition.
+    ///
+    /// The tag is accounted in [`quarantined_lent`](Self::quarantined_lent) until it leaves the quarantine.
+    pub fn deallocate_lent(&self, index: u64) {
+        self.deallocate_inner(index, true)
+    }
+
+    fn deallocate_inner(&self, index: u64, lent: bool) {
         debug_assert!(index < self.capacity, "index {index} out of range");
         let word_idx = (index / 64) as usize;
         let bit = index % 64;
         let mask = 1u64 << bit;
-        self.words[word_idx].fetch_or(mask, Ordering::Release);
+
+        match &self.quarantine {
+            Some(quarantine) => {
+                if quarantine.bits[word_idx].fetch_or(mask, Ordering::AcqRel) & mask != 0 {
+                    return; // already quarantined
+                }
+                // Timestamp and attempt counter are taken under the lock to keep the queue ordered.
+                let mut queue = quarantine.queue.lock();
+                queue.push_back(QuarantinedTag {
+                    index,
+                    since: Instant::now(),
+                    attempt: quarantine.attempts.load(Ordering::Acquire),
+                    lent,
+                });
+                quarantine.len.fetch_add(1, Ordering::AcqRel);
+                if lent {
+                    quarantine.lent.fetch_add(1, Ordering::AcqRel);
+                }
+            }
+            None => {
+                self.words[word_idx].fetch_or(mask, Ordering::Release);
+            }
+        }
+    }
+
+    /// Moves all tags whose quarantine has expired back to the available set.
+    fn release_expired(&self, quarantine: &Quarantine, now: Instant, attempt: u64) {
+        let mut queue = quarantine.queue.lock();
+        while let Some(tag) = queue.front() {
+            if !quarantine.is_expired(tag, now, attempt) {
+                break;
+            }
+            let word_idx = (tag.index / 64) as usize;
+            let mask = 1u64 << (tag.index % 64);
+            quarantine.bits[word_idx].fetch_and(!mask, Ordering::AcqRel);
+            self.words[word_idx].fetch_or(mask, Ordering::Release);
+            quarantine.len.fetch_sub(1, Ordering::AcqRel);
+            if tag.lent {
+                quarantine.lent.fetch_sub(1, Ordering::AcqRel);
+            }
+            queue.pop_front();
+        }
     }
 }
 
@@ -650,4 +795,122 @@ mod tests {
         }
         assert_eq!(recovered.len(), 500);
     }
+
+    // ── Quarantine ──────────────────────────────────────────────────
+
+    #[test]
+    fn quarantined_tag_must_stay_unavailable_for_configured_allocations() {
+        let bm = TagBitmap::with_quarantine(
+            2,
+            TagQuarantineConfig {
+                allocations: Some(3),
+                ..Default::default()
+            },
+        );
+        assert_eq!(bm.allocate(), Some(0));
+        assert_eq!(bm.allocate(), Some(1));
+
+        bm.deallocate(0);
+        assert_eq!(bm.quarantined(), 1);
+        for _ in 0..3 {
+            assert_eq!(bm.allocate(), None);
+        }
+
+        assert_eq!(bm.allocate(), Some(0));
+        assert_eq!(bm.quarantined(), 0);
+    }
+
+    #[test]
+    fn quarantined_tag_must_stay_unavailable_for_configured_period() {
+        let bm = TagBitmap::with_quarantine(
+            2,
+            TagQuarantineConfig {
+                period: Some(std::time::Duration::from_secs(30)),
+                ..Default::default()
+            },
+        );
+        assert_eq!(bm.allocate(), Some(0));
+        bm.deallocate(0);
+
+        // The other free tag is handed out instead of the quarantined one.
+        assert_eq!(bm.allocate(), Some(1));
+        for _ in 0..100 {
+            assert_eq!(bm.allocate(), None);
+        }
+        assert_eq!(bm.quarantined(), 1);
+    }
+
+    #[test]
+    fn quarantined_tag_must_become_available_after_period_elapses() {
+        let bm = TagBitmap::with_quarantine(
+            1,
+            TagQuarantineConfig {
+                period: Some(std::time::Duration::from_millis(1)),
+                ..Default::default()
+            },
+        );
+        assert_eq!(bm.allocate(), Some(0));
+        bm.deallocate(0);
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        assert_eq!(bm.allocate(), Some(0));
+        assert_eq!(bm.quarantined(), 0);
+    }
+
+    #[test]
+    fn quarantine_must_require_both_period_and_allocations() {
+        let bm = TagBitmap::with_quarantine(
+            1,
+            TagQuarantineConfig {
+                period: Some(std::time::Duration::from_secs(30)),
+                allocations: Some(1),
+            },
+        );
+        assert_eq!(bm.allocate(), Some(0));
+        bm.deallocate(0);
+
+        for _ in 0..10 {
+            assert_eq!(bm.allocate(), None);
+        }
+    }
+
+    #[test]
+    fn quarantine_must_release_all_expired_tags() {
+        let bm = TagBitmap::with_quarantine(
+            4,
+            TagQuarantineConfig {
+                allocations: Some(1),
+                ..Default::default()
+            },
+        );
+        let tags: Vec<u64> = (0..4).map(|_| bm.allocate().unwrap()).collect();
+        bm.deallocate(tags[2]);
+        bm.deallocate(tags[0]);
+
+        // The first attempt after freeing is still covered by the quarantine.
+        assert_eq!(bm.allocate(), None);
+        // The second one releases both tags, the lowest index is picked first.
+        assert_eq!(bm.allocate(), Some(0));
+        assert_eq!(bm.allocate(), Some(2));
+        assert_eq!(bm.allocate(), None);
+    }
+
+    #[test]
+    fn quarantine_deallocate_idempotent() {
+        let bm = TagBitmap::with_quarantine(
+            4,
+            TagQuarantineConfig {
+                allocations: Some(1),
+                ..Default::default()
+            },
+        );
+        let idx = bm.allocate().unwrap();
+        bm.deallocate(idx);
+        bm.deallocate(idx);
+        assert_eq!(bm.quarantined(), 1);
+
+        assert_eq!(bm.allocate(), Some(1));
+        assert_eq!(bm.allocate(), Some(idx));
+        assert_eq!(bm.allocate(), Some(2));
+    }
 }
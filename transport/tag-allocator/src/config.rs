@@ -1,4 +1,4 @@
-use std::ops::Range;
+use std::{ops::Range, time::Duration};
 
 use hopr_protocol_app::prelude::ReservedTag;
 use validator::{Validate, ValidationError, ValidationErrors};
@@ -13,9 +13,50 @@ pub const DEFAULT_SESSION_PROBING_CAPACITY: u64 = 4000;
 pub const DEFAULT_PROBING_TELEMETRY_CAPACITY: u64 =
     TAG_RANGE_SIZE - DEFAULT_SESSION_CAPACITY - DEFAULT_SESSION_PROBING_CAPACITY;
 
+/// Cool-down applied to freed tags before they can be allocated again.
+///
+/// While quarantined, a tag cannot be handed out to a new owner, so late packets
+/// still in flight for its previous owner are not misrouted. A freed tag leaves
+/// the quarantine only once **all** the configured conditions are met. When
+/// neither is set, freed tags are immediately available again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, smart_default::SmartDefault)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
+pub struct TagQuarantineConfig {
+    /// Minimum time a freed tag stays unavailable.
+    ///
+    /// Default is `None`.
+    #[cfg_attr(feature = "serde", serde(default, with = "humantime_serde::option"))]
+    pub period: Option<Duration>,
+
+    /// Number of subsequent allocation attempts in the tag's partition during which a
+    /// freed tag stays unavailable.
+    ///
+    /// Failed attempts are counted too, so an exhausted partition is guaranteed to
+    /// eventually release its quarantined tags.
+    ///
+    /// Default is `None`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub allocations: Option<u64>,
+}
+
+impl TagQuarantineConfig {
+    /// Indicates whether freed tags are quarantined at all.
+    pub fn is_enabled(&self) -> bool {
+        self.period.is_some_and(|period| !period.is_zero()) || self.allocations.is_some_and(|n| n > 0)
+    }
+}
+
 /// Configuration for the tag allocator partitions.
 ///
-/// Each field specifies the number of tags reserved for that usage category.
+/// The capacity fields specify the number of tags reserved for each usage
+/// category. By default, partitions are fixed: an exhausted partition cannot
+/// allocate more tags even if another one is idle. Setting a guaranteed minimum
+/// for a partition allows it to lend the tags above that minimum to other
+/// partitions which have run out of their own.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, smart_default::SmartDefault)]
 #[cfg_attr(
     feature = "serde",
@@ -41,6 +82,39 @@ pub struct TagAllocatorConfig {
     /// [`TAG_RANGE_SIZE`]; this is validated at allocator creation time.
     #[default(DEFAULT_PROBING_TELEMETRY_CAPACITY)]
     pub probing_telemetry: u64,
+
+    /// Minimum number of tags always available to sessions.
+    ///
+    /// The remaining `session - session_guaranteed` tags may be lent to other
+    /// exhausted partitions. Must not exceed `session`.
+    ///
+    /// Default is `None`, which means no session tags are lent.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub session_guaranteed: Option<u64>,
+
+    /// Minimum number of tags always available to session terminal telemetry.
+    ///
+    /// The remaining `session_probing - session_probing_guaranteed` tags may be
+    /// lent to other exhausted partitions. Must not exceed `session_probing`.
+    ///
+    /// Default is `None`, which means no session terminal telemetry tags are lent.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub session_probing_guaranteed: Option<u64>,
+
+    /// Minimum number of tags always available to probing telemetry.
+    ///
+    /// The remaining `probing_telemetry - probing_telemetry_guaranteed` tags may
+    /// be lent to other exhausted partitions. Must not exceed `probing_telemetry`.
+    ///
+    /// Default is `None`, which means no probing telemetry tags are lent.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub probing_telemetry_guaranteed: Option<u64>,
+
+    /// Quarantine of freed tags in all partitions.
+    ///
+    /// Default is no quarantine.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub quarantine: TagQuarantineConfig,
 }
 
 impl TagAllocatorConfig {
@@ -64,6 +138,17 @@ impl TagAllocatorConfig {
         }
     }
 
+    /// Returns the guaranteed minimum number of tags for the given [`Usage`] partition.
+    ///
+    /// Equals the partition capacity when no guaranteed minimum is configured.
+    pub fn guaranteed_for(&self, usage: Usage) -> u64 {
+        match usage {
+            Usage::Session => self.session_guaranteed.unwrap_or(self.session),
+            Usage::SessionTerminalTelemetry => self.session_probing_guaranteed.unwrap_or(self.session_probing),
+            Usage::ProvingTelemetry => self.probing_telemetry_guaranteed.unwrap_or(self.probing_telemetry),
+        }
+    }
+
     /// The full tag range covered by this configuration.
     ///
     /// Starts at [`ReservedTag::UPPER_BOUND`] and spans the sum of all
@@ -94,6 +179,27 @@ impl Validate for TagAllocatorConfig {
             );
         }
 
+        for (field, guaranteed, capacity) in [
+            ("session_guaranteed", self.session_guaranteed, self.session),
+            (
+                "session_probing_guaranteed",
+                self.session_probing_guaranteed,
+                self.session_probing,
+            ),
+            (
+                "probing_telemetry_guaranteed",
+                self.probing_telemetry_guaranteed,
+                self.probing_telemetry,
+            ),
+        ] {
+            if guaranteed.is_some_and(|guaranteed| guaranteed > capacity) {
+                errors.add(
+                    field,
+                    ValidationError::new("guaranteed minimum exceeds partition capacity"),
+                );
+            }
+        }
+
         let total = self.session + self.session_probing + self.probing_telemetry;
         if total > TAG_RANGE_SIZE {
             let mut err = ValidationError::new("total capacity exceeds available tag range");
@@ -154,6 +260,7 @@ mod tests {
             session: TAG_RANGE_SIZE,
             session_probing: 1,
             probing_telemetry: 1,
+            ..Default::default()
         };
         let err = cfg.validate().unwrap_err();
         assert!(err.field_errors().contains_key("probing_telemetry"));
@@ -165,10 +272,31 @@ mod tests {
             session: 1000,
             session_probing: 1000,
             probing_telemetry: 1000,
+            ..Default::default()
         };
         assert!(cfg.validate().is_ok());
     }
 
+    #[test]
+    fn guaranteed_minimum_exceeding_capacity_is_invalid() {
+        let cfg = TagAllocatorConfig {
+            session_probing_guaranteed: Some(DEFAULT_SESSION_PROBING_CAPACITY + 1),
+            ..Default::default()
+        };
+        let err = cfg.validate().unwrap_err();
+        assert!(err.field_errors().contains_key("session_probing_guaranteed"));
+    }
+
+    #[test]
+    fn guaranteed_for_should_default_to_partition_capacity() {
+        let cfg = TagAllocatorConfig {
+            probing_telemetry_guaranteed: Some(1000),
+            ..Default::default()
+        };
+        assert_eq!(cfg.guaranteed_for(Usage::Session), cfg.session);
+        assert_eq!(cfg.guaranteed_for(Usage::ProvingTelemetry), 1000);
+    }
+
     #[rstest]
     #[case::session(Usage::Session)]
     #[case::session_terminal(Usage::SessionTerminalTelemetry)]
@@ -9,6 +9,13 @@ pub enum TagAllocatorError {
     /// The supplied range is empty.
     #[error("tag range is empty")]
     EmptyRange,
+    /// A partition's guaranteed minimum is larger than its capacity.
+    #[error("partition {usage:?} guarantees {guaranteed} tags but has capacity of only {capacity}")]
+    GuaranteeExceedsCapacity {
+        usage: Usage,
+        guaranteed: u64,
+        capacity: u64,
+    },
     /// A partition was requested with zero capacity.
     #[error("partition {0:?} has zero capacity")]
     ZeroCapacity(Usage),
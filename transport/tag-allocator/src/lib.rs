@@ -3,13 +3,15 @@ mod allocator;
 mod bitmap;
 pub mod config;
 pub mod errors;
+mod stats;
 
 use std::{ops::Range, sync::Arc};
 
 pub use allocated_tag::AllocatedTag;
-pub use config::TagAllocatorConfig;
+pub use config::{TagAllocatorConfig, TagQuarantineConfig};
 pub use errors::TagAllocatorError;
 use hopr_protocol_app::prelude::ReservedTag;
+pub use stats::PartitionStats;
 
 /// Identifies which component a partition belongs to.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -36,16 +38,22 @@ const _: () = assert!(
     "tag range exceeds u16 — a different allocation strategy is needed"
 );
 
-/// Allocates unique tags from a fixed partition of the tag range.
+/// Allocates unique tags from a partition of the tag range.
 pub trait TagAllocator {
-    /// Obtain the next available tag, or `None` if the partition is exhausted.
+    /// Obtain the next available tag, or `None` if the partition is exhausted
+    /// and no other partition has a tag to spare.
     fn allocate(&self) -> Option<AllocatedTag>;
 
-    /// The total number of tags managed by this allocator.
+    /// The total number of tags in this allocator's own partition.
     fn capacity(&self) -> u64;
 
-    /// The tag value range `[base, base + capacity)` managed by this allocator.
+    /// The tag value range `[base, base + capacity)` of this allocator's own partition.
+    ///
+    /// Tags borrowed from other partitions lie outside this range.
     fn tag_range(&self) -> Range<u64>;
+
+    /// Current usage statistics of this allocator's partition.
+    fn stats(&self) -> PartitionStats;
 }
 
 /// Result type returned by [`create_allocators`].
@@ -54,20 +62,33 @@ pub type CreateAllocatorsResult = Result<Vec<(Usage, Arc<dyn TagAllocator + Send
 /// Create allocators from a [`TagAllocatorConfig`].
 ///
 /// Uses [`TagAllocatorConfig::tag_range`] as the available range and
-/// partitions it according to the configured capacities.
+/// partitions it according to the configured capacities. Partitions with a
+/// configured guaranteed minimum lend their remaining tags to exhausted
+/// partitions, see [`create_borrowing_allocators`]. Freed tags are subject to
+/// the configured quarantine, see [`create_allocators_with_quarantine`].
 ///
 /// # Errors
 ///
-/// Returns [`TagAllocatorError`] if any partition has zero capacity or the
-/// total requested capacity exceeds the range.
+/// Returns [`TagAllocatorError`] if any partition has zero capacity, a
+/// guaranteed minimum exceeds its partition capacity or the total requested
+/// capacity exceeds the range.
 pub fn create_allocators_from_config(cfg: &TagAllocatorConfig) -> CreateAllocatorsResult {
-    create_allocators(
+    create_allocators_with_quarantine(
         TAG_RANGE_START..TAG_RANGE_END,
         [
-            (Usage::Session, cfg.session),
-            (Usage::SessionTerminalTelemetry, cfg.session_probing),
-            (Usage::ProvingTelemetry, cfg.probing_telemetry),
+            (Usage::Session, cfg.session, cfg.guaranteed_for(Usage::Session)),
+            (
+                Usage::SessionTerminalTelemetry,
+                cfg.session_probing,
+                cfg.guaranteed_for(Usage::SessionTerminalTelemetry),
+            ),
+            (
+                Usage::ProvingTelemetry,
+                cfg.probing_telemetry,
+                cfg.guaranteed_for(Usage::ProvingTelemetry),
+            ),
         ],
+        cfg.quarantine,
     )
 }
 
@@ -82,18 +103,64 @@ pub fn create_allocators_from_config(cfg: &TagAllocatorConfig) -> CreateAllocato
 /// Returns [`TagAllocatorError`] if the range is empty, any partition has
 /// zero capacity, or the total requested capacity exceeds the range.
 pub fn create_allocators(range: Range<u64>, partitions: [(Usage, u64); 3]) -> CreateAllocatorsResult {
+    create_borrowing_allocators(range, partitions.map(|(usage, capacity)| (usage, capacity, capacity)))
+}
+
+/// Create one [`TagAllocator`] per partition that may borrow tags from the others.
+///
+/// Each partition is given as `(usage, capacity, guaranteed)`. The `range` is
+/// divided into non-overlapping sub-ranges of the given capacities, exactly as
+/// in [`create_allocators`]. An allocator first yields tags from its own
+/// sub-range; once that is exhausted, it borrows free tags from the other
+/// partitions. A partition never lends out so many tags that it could not
+/// hold `guaranteed` tags itself.
+///
+/// Setting `guaranteed` equal to `capacity` prevents the partition from lending
+/// any of its tags.
+///
+/// # Errors
+///
+/// Returns [`TagAllocatorError`] if the range is empty, any partition has
+/// zero capacity, a guaranteed minimum exceeds its partition capacity, or the
+/// total requested capacity exceeds the range.
+pub fn create_borrowing_allocators(range: Range<u64>, partitions: [(Usage, u64, u64); 3]) -> CreateAllocatorsResult {
+    create_allocators_with_quarantine(range, partitions, TagQuarantineConfig::default())
+}
+
+/// Create borrowing [`TagAllocator`]s whose freed tags are quarantined before reuse.
+///
+/// Behaves like [`create_borrowing_allocators`], except that a tag released by
+/// dropping its [`AllocatedTag`] stays unavailable (to any partition) until its
+/// `quarantine` expires. This prevents late packets addressed to the previous
+/// owner of a tag from being delivered to its next owner.
+///
+/// # Errors
+///
+/// Same as [`create_borrowing_allocators`].
+pub fn create_allocators_with_quarantine(
+    range: Range<u64>,
+    partitions: [(Usage, u64, u64); 3],
+    quarantine: TagQuarantineConfig,
+) -> CreateAllocatorsResult {
     let range_size = range.end.saturating_sub(range.start);
     if range_size == 0 {
         return Err(TagAllocatorError::EmptyRange);
     }
 
-    for (usage, capacity) in &partitions {
+    for (usage, capacity, guaranteed) in &partitions {
         if *capacity == 0 {
             return Err(TagAllocatorError::ZeroCapacity(*usage));
         }
+        if guaranteed > capacity {
+            return Err(TagAllocatorError::GuaranteeExceedsCapacity {
+                usage: *usage,
+                guaranteed: *guaranteed,
+                capacity: *capacity,
+            });
+        }
     }
 
-    let total_requested: u64 = partitions.iter().map(|(_, cap)| cap).sum();
+    let total_requested: u64 = partitions.iter().map(|(_, cap, _)| cap).sum();
     if total_requested > range_size {
         return Err(TagAllocatorError::CapacityExceedsRange {
             total_requested,
@@ -101,12 +168,12 @@ pub fn create_allocators(range: Range<u64>, partitions: [(Usage, u64); 3]) -> Cr
         });
     }
 
-    let mut base = range.start;
+    let pool = allocator::TagPool::new(range.start, &partitions, quarantine);
     Ok(partitions
         .iter()
-        .map(|(usage, capacity)| {
-            let alloc = Arc::new(allocator::PartitionAllocator::new(base, *capacity));
-            base += capacity;
+        .enumerate()
+        .map(|(index, (usage, ..))| {
+            let alloc = Arc::new(allocator::PartitionAllocator::new(pool.clone(), index));
             (*usage, alloc as Arc<dyn TagAllocator + Send + Sync>)
         })
         .collect())
@@ -286,4 +353,128 @@ mod tests {
         let t2 = alloc.allocate().unwrap();
         assert_eq!(t2.value(), val);
     }
+
+    #[test]
+    fn exhausted_session_partition_must_borrow_from_idle_probing_partition() -> anyhow::Result<()> {
+        let allocators = create_borrowing_allocators(
+            ReservedTag::range().end..u16::MAX as u64 + 1,
+            [
+                (Usage::Session, 2, 2),
+                (Usage::SessionTerminalTelemetry, 10, 10),
+                (Usage::ProvingTelemetry, 10, 5),
+            ],
+        )?;
+        let (_, session) = &allocators[0];
+        let (_, probing) = &allocators[2];
+
+        let tags: Vec<AllocatedTag> = (0..10).filter_map(|_| session.allocate()).collect();
+        assert_eq!(tags.len(), 7);
+
+        let all_values: HashSet<u64> = tags.iter().map(|t| t.value()).collect();
+        assert_eq!(all_values.len(), 7);
+
+        let stats = session.stats();
+        assert_eq!(stats.in_use, 7);
+        assert_eq!(stats.borrowed, 5);
+        assert_eq!(stats.allocation_failures, 3);
+        assert!(stats.utilisation() > 1.0);
+
+        let probing_stats = probing.stats();
+        assert_eq!(probing_stats.lent, 5);
+        assert_eq!(probing_stats.available(), 5);
+
+        drop(tags);
+        let stats = session.stats();
+        assert_eq!((stats.in_use, stats.borrowed, stats.high_water_mark), (0, 0, 7));
+        assert_eq!(probing.stats().lent, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn error_if_guarantee_exceeds_capacity() {
+        let result = create_borrowing_allocators(
+            ReservedTag::range().end..u16::MAX as u64 + 1,
+            [
+                (Usage::Session, 10, 10),
+                (Usage::SessionTerminalTelemetry, 10, 11),
+                (Usage::ProvingTelemetry, 10, 0),
+            ],
+        );
+        assert!(matches!(
+            result,
+            Err(TagAllocatorError::GuaranteeExceedsCapacity {
+                usage: Usage::SessionTerminalTelemetry,
+                guaranteed: 11,
+                capacity: 10,
+            })
+        ));
+    }
+
+    #[test]
+    fn quarantine_must_withhold_freed_tag_from_new_owners() -> anyhow::Result<()> {
+        let allocators = create_allocators_with_quarantine(
+            ReservedTag::range().end..u16::MAX as u64 + 1,
+            [
+                (Usage::Session, 4, 4),
+                (Usage::SessionTerminalTelemetry, 4, 4),
+                (Usage::ProvingTelemetry, 4, 4),
+            ],
+            TagQuarantineConfig {
+                allocations: Some(8),
+                ..Default::default()
+            },
+        )?;
+        let (_, alloc) = &allocators[0];
+
+        let old = alloc.allocate().ok_or(anyhow::anyhow!("expected tag"))?;
+        let freed_tag = old.value();
+        drop(old);
+        assert_eq!(alloc.stats().quarantined, 1);
+
+        // New owners keep coming for as long as the quarantine lasts.
+        let mut new_tags = Vec::new();
+        for _ in 0..8 {
+            if let Some(tag) = alloc.allocate() {
+                new_tags.push(tag);
+            }
+        }
+        assert_eq!(new_tags.len(), 3, "only the non-quarantined tags may be handed out");
+        assert!(new_tags.iter().all(|tag| tag.value() != freed_tag));
+
+        // Once the quarantine is over, the tag can be reused.
+        drop(new_tags);
+        let reused = alloc.allocate().ok_or(anyhow::anyhow!("expected tag"))?;
+        assert_eq!(reused.value(), freed_tag);
+
+        Ok(())
+    }
+
+    #[test]
+    fn quarantined_tag_must_not_be_lent_to_other_partitions() -> anyhow::Result<()> {
+        let allocators = create_allocators_with_quarantine(
+            ReservedTag::range().end..u16::MAX as u64 + 1,
+            [
+                (Usage::Session, 1, 1),
+                (Usage::SessionTerminalTelemetry, 1, 0),
+                (Usage::ProvingTelemetry, 1, 1),
+            ],
+            TagQuarantineConfig {
+                period: Some(std::time::Duration::from_secs(30)),
+                ..Default::default()
+            },
+        )?;
+        let (_, session) = &allocators[0];
+        let (_, telemetry) = &allocators[1];
+
+        let freed = telemetry.allocate().ok_or(anyhow::anyhow!("expected tag"))?;
+        drop(freed);
+
+        let _own = session.allocate().ok_or(anyhow::anyhow!("expected tag"))?;
+        assert!(session.allocate().is_none());
+        assert!(telemetry.allocate().is_none());
+        assert_eq!(telemetry.stats().available(), 0);
+
+        Ok(())
+    }
 }
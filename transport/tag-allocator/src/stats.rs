@@ -0,0 +1,49 @@
+use crate::Usage;
+
+/// Point-in-time usage statistics of a single tag allocator partition.
+///
+/// Obtained via [`crate::TagAllocator::stats`]. The individual counters are read
+/// independently, so under concurrent allocation they may be mutually slightly off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartitionStats {
+    /// The partition these statistics belong to.
+    pub usage: Usage,
+    /// Number of tags in the partition's own sub-range.
+    pub capacity: u64,
+    /// Number of tags the partition can always obtain, regardless of borrowing by others.
+    pub guaranteed: u64,
+    /// Number of tags currently held by this partition, including borrowed ones.
+    pub in_use: u64,
+    /// Number of held tags that were borrowed from other partitions.
+    pub borrowed: u64,
+    /// Number of tags from this partition's sub-range currently held by other partitions.
+    pub lent: u64,
+    /// Number of freed tags from this partition's sub-range still waiting in quarantine.
+    pub quarantined: u64,
+    /// Highest value `in_use` has reached so far.
+    pub high_water_mark: u64,
+    /// Number of allocations that failed because neither the own sub-range nor
+    /// any other partition had a tag to spare.
+    pub allocation_failures: u64,
+}
+
+impl PartitionStats {
+    /// Ratio of tags in use to the partition's own capacity.
+    ///
+    /// May exceed `1.0` while the partition is borrowing tags from others.
+    pub fn utilisation(&self) -> f64 {
+        if self.capacity == 0 {
+            return 0.0;
+        }
+        self.in_use as f64 / self.capacity as f64
+    }
+
+    /// Number of tags from this partition's sub-range that are currently free
+    /// and not in quarantine.
+    pub fn available(&self) -> u64 {
+        self.capacity
+            .saturating_sub(self.in_use.saturating_sub(self.borrowed))
+            .saturating_sub(self.lent)
+            .saturating_sub(self.quarantined)
+    }
+}
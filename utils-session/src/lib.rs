@@ -53,6 +53,10 @@ use serde_with::serde_as;
 use tokio::net::TcpListener;
 use tracing::{debug, error, info};
 
+mod proxy;
+
+pub use proxy::create_proxy_client_binding;
+
 /// Size of the buffer for forwarding data to/from a TCP stream.
 pub const HOPR_TCP_BUFFER_SIZE: usize = 4096;
 
@@ -79,6 +83,9 @@ pub type Routing = RoutingOptions;
 #[cfg(not(feature = "explicit-path"))]
 pub type Routing = HopRouting;
 
+/// String representation of [`SessionTargetSpec::Proxy`].
+const PROXY_TARGET_SPEC: &str = "proxy://";
+
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 /// Session target specification.
@@ -86,6 +93,8 @@ pub enum SessionTargetSpec {
     Plain(String),
     Sealed(#[serde_as(as = "serde_with::base64::Base64")] Vec<u8>),
     Service(ServiceId),
+    /// The target is read from each client request of a [proxy listener](create_proxy_client_binding).
+    Proxy,
 }
 
 impl std::fmt::Display for SessionTargetSpec {
@@ -94,6 +103,7 @@ impl std::fmt::Display for SessionTargetSpec {
             SessionTargetSpec::Plain(t) => write!(f, "{t}"),
             SessionTargetSpec::Sealed(t) => write!(f, "$${}", base64::prelude::BASE64_URL_SAFE.encode(t)),
             SessionTargetSpec::Service(t) => write!(f, "#{t}"),
+            SessionTargetSpec::Proxy => write!(f, "{PROXY_TARGET_SPEC}"),
         }
     }
 }
@@ -102,7 +112,9 @@ impl FromStr for SessionTargetSpec {
     type Err = HoprLibError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(if let Some(stripped) = s.strip_prefix("$$") {
+        Ok(if s == PROXY_TARGET_SPEC {
+            Self::Proxy
+        } else if let Some(stripped) = s.strip_prefix("$$") {
             Self::Sealed(
                 base64::prelude::BASE64_URL_SAFE
                     .decode(stripped)
@@ -136,6 +148,11 @@ impl SessionTargetSpec {
                 SessionTarget::UdpStream(SealedHost::Sealed(enc.into_boxed_slice()))
             }
             (_, SessionTargetSpec::Service(id)) => SessionTarget::ExitNode(id),
+            (_, SessionTargetSpec::Proxy) => {
+                return Err(HoprLibError::GeneralError(
+                    "proxy target is given by each client request".into(),
+                ));
+            }
         })
     }
 }
@@ -953,7 +970,7 @@ mod tests {
 
     use super::*;
 
-    fn loopback_transport() -> (
+    pub(crate) fn loopback_transport() -> (
         UnboundedSender<(DestinationRouting, ApplicationDataOut)>,
         UnboundedReceiver<ApplicationDataIn>,
     ) {
@@ -1165,6 +1182,15 @@ mod tests {
         assert_eq!(SessionTargetSpec::from_str(&s).unwrap(), SessionTargetSpec::Service(42));
     }
 
+    #[test]
+    fn session_target_spec_proxy_roundtrip() {
+        let spec = SessionTargetSpec::Proxy;
+        let s = spec.to_string();
+        assert_eq!(s, "proxy://");
+        assert_eq!(SessionTargetSpec::from_str(&s).unwrap(), SessionTargetSpec::Proxy);
+        assert!(spec.into_target(IpProtocol::TCP).is_err());
+    }
+
     #[test]
     fn build_binding_address() {
         let default = "10.0.0.1:10000".parse().unwrap();
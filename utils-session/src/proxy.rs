@@ -0,0 +1,1239 @@
+//! Proxy listener reading the Session target from each client request.
+//!
+//! Unlike the listeners bound to a fixed [`SessionTargetSpec`], a proxy listener lets its clients
+//! choose the target. Each accepted TCP connection is recognized by its first byte as either:
+//!
+//! - a [SOCKS5](https://www.rfc-editor.org/rfc/rfc1928) request (without authentication), supporting the `CONNECT` and
+//!   `UDP ASSOCIATE` commands, or
+//! - an HTTP `CONNECT` request.
+//!
+//! For every client, a Session to the requested target is opened via the configured Exit node.
+//! TCP targets requested for the first time get their own [`SessionPool`], so that subsequent
+//! clients connecting to the same target can use pooled Sessions.
+//! A SOCKS5 UDP association opens a Session for each target its client sends datagrams to, up to
+//! a fixed number of targets per association.
+
+use std::{collections::HashMap, net::SocketAddr, sync::Arc, time::Duration};
+
+use dashmap::DashMap;
+use futures::{StreamExt, TryStreamExt, future::AbortHandle, stream::FuturesUnordered};
+use futures_time::future::FutureExt as TimeFutureExt;
+use hopr_lib::{
+    api::types::primitive::prelude::Address,
+    exports::transport::{HoprSession, HoprSessionConfigurator, SessionId, SessionTarget},
+};
+use hopr_utils::network_types::prelude::{IpOrHost, IpProtocol, SealedHost};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpStream, UdpSocket},
+};
+use tracing::{debug, error, info};
+
+use crate::{
+    BindError, ClientEntry, HOPR_TCP_BUFFER_SIZE, HOPR_UDP_BUFFER_SIZE, ListenerId, ListenerJoinHandles,
+    SessionFactory, SessionPool, SessionTargetSpec, StoredSessionEntry, bind_session_to_stream, tcp_listen_on,
+};
+
+/// Time within which a proxy client must send its request.
+const PROXY_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Maximum size of the HTTP `CONNECT` request head.
+const MAX_HTTP_REQUEST_HEAD_SIZE: usize = 8192;
+
+/// Maximum number of distinct targets per proxy listener that get a [`SessionPool`].
+const MAX_POOLED_PROXY_TARGETS: usize = 16;
+
+/// Maximum number of distinct targets, and therefore Sessions, of a single SOCKS5 UDP association.
+const MAX_UDP_ASSOCIATION_TARGETS: usize = 16;
+
+const SOCKS_VERSION: u8 = 0x05;
+const SOCKS_AUTH_NONE: u8 = 0x00;
+const SOCKS_AUTH_NO_ACCEPTABLE: u8 = 0xff;
+const SOCKS_CMD_CONNECT: u8 = 0x01;
+const SOCKS_CMD_UDP_ASSOCIATE: u8 = 0x03;
+const SOCKS_ATYP_IPV4: u8 = 0x01;
+const SOCKS_ATYP_DOMAIN: u8 = 0x03;
+const SOCKS_ATYP_IPV6: u8 = 0x04;
+
+/// SOCKS5 reply codes used by the proxy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+enum SocksReply {
+    Succeeded = 0x00,
+    GeneralFailure = 0x01,
+    HostUnreachable = 0x04,
+    CommandNotSupported = 0x07,
+    AddressTypeNotSupported = 0x08,
+}
+
+/// Command requested by a proxy client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProxyCommand {
+    Socks5Connect,
+    Socks5UdpAssociate,
+    HttpConnect,
+}
+
+/// Request of a proxy client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ProxyRequest {
+    command: ProxyCommand,
+    target: IpOrHost,
+    /// Data the client sent right after its request.
+    early_data: Vec<u8>,
+}
+
+fn invalid_request(msg: impl Into<String>) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, msg.into())
+}
+
+/// Reads the request of a proxy client, recognizing the protocol by the first byte.
+///
+/// Malformed or unsupported requests are answered with an error reply before returning an error.
+async fn read_proxy_request<S>(stream: &mut S) -> std::io::Result<ProxyRequest>
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    match stream.read_u8().await? {
+        SOCKS_VERSION => read_socks5_request(stream).await,
+        first => read_http_connect_request(first, stream).await,
+    }
+}
+
+async fn read_socks5_request<S>(stream: &mut S) -> std::io::Result<ProxyRequest>
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    // The version byte has been already read
+    let mut methods = vec![0u8; stream.read_u8().await? as usize];
+    stream.read_exact(&mut methods).await?;
+    if !methods.contains(&SOCKS_AUTH_NONE) {
+        stream.write_all(&[SOCKS_VERSION, SOCKS_AUTH_NO_ACCEPTABLE]).await?;
+        return Err(invalid_request("no acceptable socks5 authentication method"));
+    }
+    stream.write_all(&[SOCKS_VERSION, SOCKS_AUTH_NONE]).await?;
+
+    let mut header = [0u8; 3];
+    stream.read_exact(&mut header).await?;
+    if header[0] != SOCKS_VERSION {
+        return Err(invalid_request(format!("invalid socks version {}", header[0])));
+    }
+
+    let target = match read_socks5_address(stream).await {
+        Ok(target) => target,
+        Err(error) if error.kind() == std::io::ErrorKind::InvalidData => {
+            write_socks5_reply(stream, SocksReply::AddressTypeNotSupported, None).await?;
+            return Err(error);
+        }
+        Err(error) => return Err(error),
+    };
+
+    let command = match header[1] {
+        SOCKS_CMD_CONNECT => ProxyCommand::Socks5Connect,
+        SOCKS_CMD_UDP_ASSOCIATE => ProxyCommand::Socks5UdpAssociate,
+        cmd => {
+            write_socks5_reply(stream, SocksReply::CommandNotSupported, None).await?;
+            return Err(invalid_request(format!("unsupported socks5 command {cmd}")));
+        }
+    };
+
+    Ok(ProxyRequest {
+        command,
+        target,
+        early_data: Vec::new(),
+    })
+}
+
+async fn read_socks5_address<S: tokio::io::AsyncRead + Unpin>(stream: &mut S) -> std::io::Result<IpOrHost> {
+    let mut encoded = vec![stream.read_u8().await?];
+    let len = match encoded[0] {
+        SOCKS_ATYP_IPV4 => 4,
+        SOCKS_ATYP_IPV6 => 16,
+        SOCKS_ATYP_DOMAIN => {
+            let len = stream.read_u8().await?;
+            encoded.push(len);
+            len as usize
+        }
+        atyp => return Err(invalid_request(format!("unsupported socks5 address type {atyp}"))),
+    };
+    encoded.resize(encoded.len() + len + 2, 0);
+    let offset = encoded.len() - len - 2;
+    stream.read_exact(&mut encoded[offset..]).await?;
+
+    decode_socks5_address(&encoded)
+        .map(|(addr, _)| addr)
+        .ok_or_else(|| invalid_request("invalid socks5 address"))
+}
+
+/// Decodes a SOCKS5 address, returning it together with the number of bytes it occupied.
+fn decode_socks5_address(data: &[u8]) -> Option<(IpOrHost, usize)> {
+    let port_at = |offset: usize| {
+        data.get(offset..offset + 2)
+            .map(|port| u16::from_be_bytes([port[0], port[1]]))
+    };
+    match *data.first()? {
+        SOCKS_ATYP_IPV4 => {
+            let ip: [u8; 4] = data.get(1..5)?.try_into().ok()?;
+            Some((SocketAddr::new(ip.into(), port_at(5)?).into(), 7))
+        }
+        SOCKS_ATYP_IPV6 => {
+            let ip: [u8; 16] = data.get(1..17)?.try_into().ok()?;
+            Some((SocketAddr::new(ip.into(), port_at(17)?).into(), 19))
+        }
+        SOCKS_ATYP_DOMAIN => {
+            let len = *data.get(1)? as usize;
+            let host = std::str::from_utf8(data.get(2..2 + len)?).ok()?;
+            if host.is_empty() {
+                return None;
+            }
+            Some((IpOrHost::Dns(host.to_owned(), port_at(2 + len)?), 4 + len))
+        }
+        _ => None,
+    }
+}
+
+fn encode_socks5_address(addr: &IpOrHost, out: &mut Vec<u8>) {
+    match addr {
+        IpOrHost::Ip(SocketAddr::V4(addr)) => {
+            out.push(SOCKS_ATYP_IPV4);
+            out.extend_from_slice(&addr.ip().octets());
+        }
+        IpOrHost::Ip(SocketAddr::V6(addr)) => {
+            out.push(SOCKS_ATYP_IPV6);
+            out.extend_from_slice(&addr.ip().octets());
+        }
+        IpOrHost::Dns(host, _) => {
+            // Domain names longer than 255 bytes cannot be decoded from a SOCKS5 request
+            let host = &host.as_bytes()[..host.len().min(u8::MAX as usize)];
+            out.push(SOCKS_ATYP_DOMAIN);
+            out.push(host.len() as u8);
+            out.extend_from_slice(host);
+        }
+    }
+    out.extend_from_slice(&addr.port().to_be_bytes());
+}
+
+async fn write_socks5_reply<S: tokio::io::AsyncWrite + Unpin>(
+    stream: &mut S,
+    reply: SocksReply,
+    bound: Option<SocketAddr>,
+) -> std::io::Result<()> {
+    let mut msg = vec![SOCKS_VERSION, reply as u8, 0x00];
+    encode_socks5_address(
+        &bound.unwrap_or_else(|| SocketAddr::from(([0, 0, 0, 0], 0))).into(),
+        &mut msg,
+    );
+    stream.write_all(&msg).await?;
+    stream.flush().await
+}
+
+/// Decodes a SOCKS5 UDP datagram into its destination and payload.
+///
+/// Fragmented datagrams are not supported.
+fn decode_socks5_udp_datagram(data: &[u8]) -> Option<(IpOrHost, &[u8])> {
+    // RSV (2 bytes) | FRAG (1 byte) | address | data
+    if data.len() < 3 || data[2] != 0 {
+        return None;
+    }
+    let (addr, len) = decode_socks5_address(&data[3..])?;
+    Some((addr, &data[3 + len..]))
+}
+
+fn socks5_udp_header(addr: &IpOrHost) -> Vec<u8> {
+    let mut header = vec![0u8; 3];
+    encode_socks5_address(addr, &mut header);
+    header
+}
+
+async fn read_http_connect_request<S>(first: u8, stream: &mut S) -> std::io::Result<ProxyRequest>
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    let mut head = vec![first];
+    let mut buf = [0u8; 1024];
+    let head_end = loop {
+        if let Some(pos) = head.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        if head.len() > MAX_HTTP_REQUEST_HEAD_SIZE {
+            write_http_reply(stream, "431 Request Header Fields Too Large").await?;
+            return Err(invalid_request("http request head too large"));
+        }
+        let len = stream.read(&mut buf).await?;
+        if len == 0 {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        head.extend_from_slice(&buf[..len]);
+    };
+    let early_data = head.split_off(head_end);
+
+    let request_line = std::str::from_utf8(&head)
+        .ok()
+        .and_then(|head| head.lines().next())
+        .unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some("CONNECT"), Some(authority), Some(version)) if version.starts_with("HTTP/") => {
+            match authority.parse::<IpOrHost>() {
+                Ok(target) => Ok(ProxyRequest {
+                    command: ProxyCommand::HttpConnect,
+                    target,
+                    early_data,
+                }),
+                Err(error) => {
+                    write_http_reply(stream, "400 Bad Request").await?;
+                    Err(invalid_request(format!(
+                        "invalid http connect authority {authority}: {error}"
+                    )))
+                }
+            }
+        }
+        (Some(method), Some(_), Some(_)) => {
+            write_http_reply(stream, "405 Method Not Allowed\r\nAllow: CONNECT").await?;
+            Err(invalid_request(format!("unsupported http method {method}")))
+        }
+        _ => {
+            write_http_reply(stream, "400 Bad Request").await?;
+            Err(invalid_request("invalid http request line"))
+        }
+    }
+}
+
+async fn write_http_reply<S: tokio::io::AsyncWrite + Unpin>(stream: &mut S, status: &str) -> std::io::Result<()> {
+    stream
+        .write_all(format!("HTTP/1.1 {status}\r\n\r\n").as_bytes())
+        .await?;
+    stream.flush().await
+}
+
+/// Lets the proxy client know whether its `CONNECT` request has been fulfilled.
+async fn write_connect_reply<S: tokio::io::AsyncWrite + Unpin>(
+    stream: &mut S,
+    command: ProxyCommand,
+    succeeded: bool,
+) -> std::io::Result<()> {
+    match (command, succeeded) {
+        (ProxyCommand::HttpConnect, true) => write_http_reply(stream, "200 Connection established").await,
+        (ProxyCommand::HttpConnect, false) => write_http_reply(stream, "502 Bad Gateway").await,
+        (_, true) => write_socks5_reply(stream, SocksReply::Succeeded, None).await,
+        (_, false) => write_socks5_reply(stream, SocksReply::HostUnreachable, None).await,
+    }
+}
+
+/// Relays datagrams of a SOCKS5 UDP association with the given `client` via Sessions opened by `open_session`.
+///
+/// Starting with the `first` datagram, each distinct target of the association gets its own Session,
+/// up to [`MAX_UDP_ASSOCIATION_TARGETS`]. Datagrams to further targets are discarded, as well as
+/// datagrams from other sources or fragmented datagrams.
+/// Once the Session of a target is closed, its ID is passed to `on_session_end`, and the Session
+/// is opened again by the next datagram to the target.
+/// The relay ends only when the relay socket fails.
+async fn relay_socks5_udp<S, F, Fut, E>(
+    socket: &UdpSocket,
+    client: SocketAddr,
+    first: (IpOrHost, Vec<u8>),
+    mut open_session: F,
+    mut on_session_end: E,
+) -> std::io::Result<()>
+where
+    S: futures::AsyncRead + futures::AsyncWrite + Unpin,
+    F: FnMut(IpOrHost) -> Fut,
+    Fut: Future<Output = Option<(SessionId, S)>>,
+    E: FnMut(SessionId),
+{
+    use futures::{AsyncReadExt, AsyncWriteExt};
+
+    let mut session_writers: HashMap<IpOrHost, futures::io::WriteHalf<S>> = HashMap::new();
+    let mut downstreams = FuturesUnordered::new();
+    let mut pending = Some(first);
+    let mut buf = vec![0u8; HOPR_UDP_BUFFER_SIZE];
+
+    let res = loop {
+        let (target, data) = match pending.take() {
+            Some(first) => first,
+            None => {
+                let received = if downstreams.is_empty() {
+                    socket.recv_from(&mut buf).await
+                } else {
+                    // Receiving from a UDP socket is cancel-safe
+                    match futures::future::select(std::pin::pin!(socket.recv_from(&mut buf)), downstreams.next()).await
+                    {
+                        futures::future::Either::Left((received, _)) => received,
+                        futures::future::Either::Right((ended, _)) => {
+                            if let Some((target, session_id)) = ended {
+                                debug!(%target, %session_id, "socks5 udp session to target has ended");
+                                if let Some(mut session_tx) = session_writers.remove(&target) {
+                                    let _ = session_tx.close().await;
+                                }
+                                on_session_end(session_id);
+                            }
+                            continue;
+                        }
+                    }
+                };
+                let (len, from) = match received {
+                    Ok(received) => received,
+                    Err(error) => break Err(error),
+                };
+                if from != client {
+                    debug!(%from, %client, "discarding datagram from a foreign socks5 udp client");
+                    continue;
+                }
+                match decode_socks5_udp_datagram(&buf[..len]) {
+                    Some((target, data)) => (target, data.to_vec()),
+                    None => {
+                        debug!(%client, "discarding malformed socks5 udp datagram");
+                        continue;
+                    }
+                }
+            }
+        };
+
+        if !session_writers.contains_key(&target) {
+            if session_writers.len() >= MAX_UDP_ASSOCIATION_TARGETS {
+                debug!(%target, %client, "discarding socks5 udp datagram to a target over the association limit");
+                continue;
+            }
+            let Some((session_id, session)) = open_session(target.clone()).await else {
+                continue;
+            };
+            let (session_rx, session_tx) = session.split();
+            downstreams.push(relay_socks5_udp_downstream(
+                session_rx,
+                socket,
+                client,
+                target.clone(),
+                session_id,
+            ));
+            session_writers.insert(target.clone(), session_tx);
+        }
+
+        if let Some(session_tx) = session_writers.get_mut(&target)
+            && let Err(error) = async {
+                session_tx.write_all(&data).await?;
+                session_tx.flush().await
+            }
+            .await
+        {
+            error!(%error, %target, "failed to forward socks5 udp datagram");
+        }
+    };
+
+    for (_, mut session_tx) in session_writers {
+        let _ = session_tx.close().await;
+    }
+    res
+}
+
+/// Sends data received from the Session of the `target` back to the `client`.
+///
+/// Returns the `target` and the ID of its Session once the Session is closed.
+async fn relay_socks5_udp_downstream<R: futures::AsyncRead + Unpin>(
+    mut session_rx: R,
+    socket: &UdpSocket,
+    client: SocketAddr,
+    target: IpOrHost,
+    session_id: SessionId,
+) -> (IpOrHost, SessionId) {
+    use futures::AsyncReadExt;
+
+    let header = socks5_udp_header(&target);
+    let mut datagram = header.clone();
+    let mut buf = vec![0u8; HOPR_UDP_BUFFER_SIZE];
+    loop {
+        let len = match session_rx.read(&mut buf).await {
+            Ok(0) => break,
+            Ok(len) => len,
+            Err(error) => {
+                error!(%error, %target, "failed to read from socks5 udp session");
+                break;
+            }
+        };
+        datagram.truncate(header.len());
+        datagram.extend_from_slice(&buf[..len]);
+        if let Err(error) = socket.send_to(&datagram, client).await {
+            error!(%error, %client, "failed to send datagram to socks5 udp client");
+            break;
+        }
+    }
+    (target, session_id)
+}
+
+/// Resolves when the client closes the control connection.
+async fn control_connection_closed(stream: &mut TcpStream) {
+    let mut buf = [0u8; 64];
+    while matches!(stream.read(&mut buf).await, Ok(len) if len > 0) {}
+}
+
+/// Everything a proxy listener needs to serve its clients.
+struct ProxyClientContext<T: SessionFactory> {
+    factory: T,
+    destination: Address,
+    // The configuration is not required to be `Sync`
+    config: parking_lot::Mutex<T::Cfg>,
+    clients: Arc<DashMap<SessionId, ClientEntry>>,
+    max_clients: usize,
+    pools: Arc<DashMap<IpOrHost, SessionPool>>,
+    pool_size: usize,
+}
+
+impl<T: SessionFactory> ProxyClientContext<T> {
+    fn config(&self) -> T::Cfg {
+        self.config.lock().clone()
+    }
+
+    /// Takes a pooled Session to the `target` or creates a new one.
+    ///
+    /// The first time a TCP target is requested, a [`SessionPool`] for it is created in the background.
+    async fn tcp_session_to(&self, target: &IpOrHost) -> anyhow::Result<(HoprSession, HoprSessionConfigurator)> {
+        let session_target = SessionTarget::TcpStream(SealedHost::from(target.clone()));
+        if let Some((session, configurator)) = self.pools.get_mut(target).and_then(|mut pool| pool.pop()) {
+            debug!(session_id = %session.id(), %target, "using pooled session");
+            return Ok((session, configurator));
+        }
+
+        // Mind not to hold the entry lock while checking the total number of pooled targets
+        if self.pool_size > 0
+            && self.pools.len() < MAX_POOLED_PROXY_TARGETS
+            && let dashmap::Entry::Vacant(entry) = self.pools.entry(target.clone())
+        {
+            // Placeholder making sure each target gets pooled at most once
+            entry.insert(SessionPool { pool: None, ah: None });
+
+            let pools = self.pools.clone();
+            let pool_target = target.clone();
+            let pool_future = SessionPool::new(
+                self.pool_size,
+                self.destination,
+                session_target.clone(),
+                self.config(),
+                self.factory.clone(),
+            );
+            hopr_utils::runtime::prelude::spawn(async move {
+                match pool_future.await {
+                    Ok(pool) => {
+                        pools.insert(pool_target, pool);
+                    }
+                    Err(error) => {
+                        error!(%error, target = %pool_target, "failed to create session pool for target");
+                        // Drop the placeholder, so that it does not take up one of the pooled targets
+                        pools.remove_if(&pool_target, |_, pool| pool.pool.is_none());
+                    }
+                }
+            });
+        }
+
+        self.factory
+            .create_session(self.destination, session_target, self.config())
+            .await
+    }
+
+    /// Registers the client, so it is accounted in the listener's [`StoredSessionEntry`].
+    fn register_client(
+        &self,
+        session_id: SessionId,
+        sock_addr: SocketAddr,
+        configurator: HoprSessionConfigurator,
+    ) -> futures::future::AbortRegistration {
+        let (abort_handle, abort_reg) = AbortHandle::new_pair();
+        self.register_client_with(session_id, sock_addr, configurator, abort_handle);
+        abort_reg
+    }
+
+    /// Same as [`register_client`](Self::register_client), but with an `abort_handle` that can be
+    /// shared by multiple Sessions of the same client.
+    fn register_client_with(
+        &self,
+        session_id: SessionId,
+        sock_addr: SocketAddr,
+        configurator: HoprSessionConfigurator,
+        abort_handle: AbortHandle,
+    ) {
+        self.clients.insert(
+            session_id,
+            ClientEntry {
+                sock_addr,
+                abort_handle,
+                configurator,
+            },
+        );
+    }
+
+    async fn serve_client(self: Arc<Self>, sock_addr: SocketAddr, mut stream: TcpStream) {
+        let request = match read_proxy_request(&mut stream)
+            .timeout(futures_time::time::Duration::from(PROXY_HANDSHAKE_TIMEOUT))
+            .await
+        {
+            Ok(Ok(request)) => request,
+            Ok(Err(error)) => {
+                error!(%error, ?sock_addr, "invalid proxy request");
+                return;
+            }
+            Err(_) => {
+                error!(?sock_addr, "proxy client did not send its request in time");
+                return;
+            }
+        };
+        debug!(?sock_addr, command = ?request.command, target = %request.target, "incoming proxy request");
+
+        // Clients still in the handshake are not accounted, so check the quota once more
+        if self.clients.len() >= self.max_clients {
+            error!(?sock_addr, "no more client slots available at proxy listener");
+            if let Err(error) = write_connect_reply(&mut stream, request.command, false).await {
+                error!(%error, ?sock_addr, "failed to reply to proxy client");
+            }
+            return;
+        }
+
+        match request.command {
+            ProxyCommand::Socks5Connect | ProxyCommand::HttpConnect => {
+                self.serve_tcp_client(sock_addr, stream, request).await
+            }
+            ProxyCommand::Socks5UdpAssociate => self.serve_udp_client(sock_addr, stream).await,
+        }
+    }
+
+    async fn serve_tcp_client(&self, sock_addr: SocketAddr, mut stream: TcpStream, request: ProxyRequest) {
+        let (mut session, configurator) = match self.tcp_session_to(&request.target).await {
+            Ok(session) => session,
+            Err(error) => {
+                error!(%error, target = %request.target, "failed to establish session for proxy client");
+                if let Err(error) = write_connect_reply(&mut stream, request.command, false).await {
+                    error!(%error, ?sock_addr, "failed to reply to proxy client");
+                }
+                return;
+            }
+        };
+
+        let session_id = *session.id();
+        if let Err(error) = write_connect_reply(&mut stream, request.command, true).await {
+            error!(%error, ?sock_addr, %session_id, "failed to reply to proxy client");
+            return;
+        }
+        if !request.early_data.is_empty()
+            && let Err(error) = async {
+                session.write_all(&request.early_data).await?;
+                session.flush().await
+            }
+            .await
+        {
+            error!(%error, %session_id, "failed to forward early data of proxy client");
+            return;
+        }
+
+        debug!(?sock_addr, %session_id, target = %request.target, "new session for proxy client");
+        let abort_reg = self.register_client(session_id, sock_addr, configurator);
+
+        #[cfg(all(feature = "telemetry", not(test)))]
+        crate::METRIC_ACTIVE_CLIENTS.increment(&["tcp"], 1.0);
+
+        bind_session_to_stream(session, stream, HOPR_TCP_BUFFER_SIZE, Some(abort_reg)).await;
+        self.clients.remove(&session_id);
+        debug!(%session_id, "proxy tcp session has ended");
+
+        #[cfg(all(feature = "telemetry", not(test)))]
+        crate::METRIC_ACTIVE_CLIENTS.decrement(&["tcp"], 1.0);
+    }
+
+    async fn serve_udp_client(&self, sock_addr: SocketAddr, mut stream: TcpStream) {
+        // Bind the relay on the interface the client connected to
+        let socket = match stream.local_addr() {
+            Ok(local) => UdpSocket::bind(SocketAddr::new(local.ip(), 0)).await,
+            Err(error) => Err(error),
+        };
+        let (socket, relay_addr) = match socket.and_then(|socket| Ok((socket.local_addr()?, socket))) {
+            Ok((relay_addr, socket)) => (socket, relay_addr),
+            Err(error) => {
+                error!(%error, ?sock_addr, "failed to bind socks5 udp relay");
+                let _ = write_socks5_reply(&mut stream, SocksReply::GeneralFailure, None).await;
+                return;
+            }
+        };
+
+        if let Err(error) = write_socks5_reply(&mut stream, SocksReply::Succeeded, Some(relay_addr)).await {
+            error!(%error, ?sock_addr, "failed to reply to proxy client");
+            return;
+        }
+        debug!(?sock_addr, %relay_addr, "socks5 udp relay bound");
+
+        // The association starts with the first datagram of the client
+        let first_datagram = async {
+            let mut buf = vec![0u8; HOPR_UDP_BUFFER_SIZE];
+            loop {
+                let (len, from) = socket.recv_from(&mut buf).await?;
+                match decode_socks5_udp_datagram(&buf[..len]) {
+                    // Only the client that requested the association is served
+                    Some((target, data)) if from.ip() == sock_addr.ip() => {
+                        return Ok::<_, std::io::Error>((from, target, data.to_vec()));
+                    }
+                    _ => debug!(%from, "discarding datagram at socks5 udp relay"),
+                }
+            }
+        };
+        let (client, target, data) = match futures::future::select(
+            std::pin::pin!(first_datagram),
+            std::pin::pin!(control_connection_closed(&mut stream)),
+        )
+        .await
+        {
+            futures::future::Either::Left((Ok(first), _)) => first,
+            futures::future::Either::Left((Err(error), _)) => {
+                error!(%error, ?sock_addr, "socks5 udp relay failed");
+                return;
+            }
+            futures::future::Either::Right(_) => {
+                debug!(?sock_addr, "socks5 udp association closed before any data was sent");
+                return;
+            }
+        };
+
+        // All Sessions of the association are aborted together
+        let (abort_handle, abort_reg) = AbortHandle::new_pair();
+        let session_ids = parking_lot::Mutex::new(Vec::new());
+        let open_session = |target: IpOrHost| {
+            let abort_handle = abort_handle.clone();
+            let session_ids = &session_ids;
+            async move {
+                if self.clients.len() >= self.max_clients {
+                    error!(%client, %target, "no more client slots available for socks5 udp session");
+                    return None;
+                }
+                let (session, configurator) = match self
+                    .factory
+                    .create_session(
+                        self.destination,
+                        SessionTarget::UdpStream(SealedHost::from(target.clone())),
+                        self.config(),
+                    )
+                    .await
+                {
+                    Ok(session) => session,
+                    Err(error) => {
+                        error!(%error, %target, "failed to establish udp session for proxy client");
+                        return None;
+                    }
+                };
+
+                let session_id = *session.id();
+                debug!(%client, %session_id, %target, "new udp session for proxy client");
+                self.register_client_with(session_id, client, configurator, abort_handle);
+                session_ids.lock().push(session_id);
+
+                #[cfg(all(feature = "telemetry", not(test)))]
+                crate::METRIC_ACTIVE_CLIENTS.increment(&["udp"], 1.0);
+
+                Some((session_id, session))
+            }
+        };
+        let close_session = |session_id: SessionId| {
+            session_ids.lock().retain(|id| id != &session_id);
+            self.clients.remove(&session_id);
+
+            #[cfg(all(feature = "telemetry", not(test)))]
+            crate::METRIC_ACTIVE_CLIENTS.decrement(&["udp"], 1.0);
+        };
+
+        // The association ends when the control connection is closed
+        let relay_future = std::pin::pin!(relay_socks5_udp(
+            &socket,
+            client,
+            (target, data),
+            open_session,
+            &close_session,
+        ));
+        let control_future = std::pin::pin!(control_connection_closed(&mut stream));
+        let relay = futures::future::select(relay_future, control_future);
+        match futures::future::Abortable::new(relay, abort_reg).await {
+            Ok(futures::future::Either::Left((Err(error), _))) => {
+                error!(%error, %client, "error during socks5 udp relay");
+            }
+            Ok(_) => info!(%client, "socks5 udp association ended"),
+            Err(_) => debug!(%client, "socks5 udp association aborted"),
+        }
+
+        let remaining = std::mem::take(&mut *session_ids.lock());
+        remaining.into_iter().for_each(close_session);
+    }
+}
+
+/// Binds a SOCKS5 / HTTP CONNECT proxy listener, which opens a Session via the `destination`
+/// to the target requested by each of its clients.
+///
+/// The listener is stored in `open_listeners` with the [`SessionTargetSpec::Proxy`] target.
+#[allow(clippy::too_many_arguments)]
+pub async fn create_proxy_client_binding<T: SessionFactory>(
+    bind_host: SocketAddr,
+    port_range: Option<String>,
+    factory: T,
+    open_listeners: Arc<ListenerJoinHandles>,
+    destination: Address,
+    config: T::Cfg,
+    use_session_pool: Option<usize>,
+    max_client_sessions: Option<usize>,
+) -> Result<(SocketAddr, Option<SessionId>, usize), BindError> {
+    let (bound_host, tcp_listener) = tcp_listen_on(bind_host, port_range).await.map_err(|e| {
+        if e.kind() == std::io::ErrorKind::AddrInUse {
+            BindError::ListenHostAlreadyUsed
+        } else {
+            BindError::UnknownFailure(format!("failed to start proxy listener on {bind_host}: {e}"))
+        }
+    })?;
+    info!(%bound_host, "proxy session listener bound");
+
+    let (forward_path, return_path) = factory
+        .routing_from_cfg(&config)
+        .map_err(|e| BindError::UnknownFailure(e.to_string()))?;
+    let (max_surb_upstream, response_buffer) = factory.listener_limits(&config);
+
+    let pool_size = use_session_pool.unwrap_or(0).min(SessionPool::MAX_SESSION_POOL_SIZE);
+    let max_clients = max_client_sessions.unwrap_or(5).max(1).max(pool_size);
+    let active_sessions = Arc::new(DashMap::new());
+
+    let context = Arc::new(ProxyClientContext {
+        factory,
+        destination,
+        config: parking_lot::Mutex::new(config),
+        clients: active_sessions.clone(),
+        max_clients,
+        pools: Arc::new(DashMap::new()),
+        pool_size,
+    });
+
+    let (abort_handle, abort_reg) = AbortHandle::new_pair();
+    let active_sessions_clone = active_sessions.clone();
+    hopr_utils::runtime::prelude::spawn(async move {
+        hopr_utils::runtime::DropAbortable::new_with_registration(
+            tokio_stream::wrappers::TcpListenerStream::new(tcp_listener),
+            abort_reg,
+        )
+        .and_then(|sock| async { Ok((sock.peer_addr()?, sock)) })
+        .for_each(|accepted_client| {
+            match accepted_client {
+                Ok((sock_addr, mut stream)) => {
+                    debug!(?sock_addr, "incoming proxy connection");
+                    if context.clients.len() >= context.max_clients {
+                        error!(?bind_host, "no more client slots available at proxy listener");
+                        hopr_utils::runtime::prelude::spawn(async move {
+                            if let Err(error) = stream.shutdown().await {
+                                error!(%error, ?sock_addr, "failed to shutdown TCP connection");
+                            }
+                        });
+                    } else {
+                        // Serve each client separately, so that slow handshakes do not block the listener
+                        hopr_utils::runtime::prelude::spawn(context.clone().serve_client(sock_addr, stream));
+                    }
+                }
+                Err(error) => error!(%error, "failed to accept connection"),
+            }
+            futures::future::ready(())
+        })
+        .await;
+
+        // Once the listener is done, abort all active sessions created by the listener
+        active_sessions_clone.iter().for_each(|entry| {
+            let client = entry.value();
+            debug!(session_id = %entry.key(), sock_addr = ?client.sock_addr, "aborting proxied session after listener has been closed");
+            client.abort_handle.abort()
+        });
+    });
+
+    open_listeners.0.insert(
+        ListenerId(IpProtocol::TCP, bound_host),
+        StoredSessionEntry {
+            destination,
+            target: SessionTargetSpec::Proxy,
+            forward_path,
+            return_path,
+            clients: active_sessions,
+            max_client_sessions: max_clients,
+            max_surb_upstream,
+            response_buffer,
+            session_pool: Some(pool_size),
+            abort_handle,
+        },
+    );
+    Ok((bound_host, None, max_clients))
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Context;
+    use futures_time::future::FutureExt as TimeFutureExt;
+    use hopr_api::types::crypto::crypto_traits::Randomizable;
+    use hopr_lib::api::types::internal::{
+        prelude::HoprPseudonym,
+        routing::{DestinationRouting, RoutingOptions},
+    };
+    use hopr_transport::session::HoprSessionConfig;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn socks5_connect_request_should_be_parsed() -> anyhow::Result<()> {
+        let (mut client, mut server) = tokio::io::duplex(1024);
+
+        let jh = tokio::task::spawn(async move { read_proxy_request(&mut server).await });
+
+        client.write_all(&[SOCKS_VERSION, 2, 0x02, SOCKS_AUTH_NONE]).await?;
+        let mut method = [0u8; 2];
+        client.read_exact(&mut method).await?;
+        assert_eq!([SOCKS_VERSION, SOCKS_AUTH_NONE], method);
+
+        let mut request = vec![SOCKS_VERSION, SOCKS_CMD_CONNECT, 0x00, SOCKS_ATYP_DOMAIN, 11];
+        request.extend_from_slice(b"example.com");
+        request.extend_from_slice(&443_u16.to_be_bytes());
+        client.write_all(&request).await?;
+
+        assert_eq!(
+            ProxyRequest {
+                command: ProxyCommand::Socks5Connect,
+                target: IpOrHost::Dns("example.com".into(), 443),
+                early_data: vec![],
+            },
+            jh.await??
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn socks5_request_should_be_rejected_without_acceptable_auth_method() -> anyhow::Result<()> {
+        let (mut client, mut server) = tokio::io::duplex(1024);
+
+        let jh = tokio::task::spawn(async move { read_proxy_request(&mut server).await });
+
+        // Only username/password authentication is offered
+        client.write_all(&[SOCKS_VERSION, 1, 0x02]).await?;
+        let mut method = [0u8; 2];
+        client.read_exact(&mut method).await?;
+        assert_eq!([SOCKS_VERSION, SOCKS_AUTH_NO_ACCEPTABLE], method);
+        assert!(jh.await?.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn socks5_bind_command_should_not_be_supported() -> anyhow::Result<()> {
+        let (mut client, mut server) = tokio::io::duplex(1024);
+
+        let jh = tokio::task::spawn(async move { read_proxy_request(&mut server).await });
+
+        client.write_all(&[SOCKS_VERSION, 1, SOCKS_AUTH_NONE]).await?;
+        client
+            .write_all(&[SOCKS_VERSION, 0x02, 0x00, SOCKS_ATYP_IPV4, 127, 0, 0, 1, 0, 80])
+            .await?;
+
+        let mut reply = [0u8; 12];
+        client.read_exact(&mut reply).await?;
+        assert_eq!(SocksReply::CommandNotSupported as u8, reply[3]);
+        assert!(jh.await?.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn http_connect_request_should_be_parsed_with_early_data() -> anyhow::Result<()> {
+        let (mut client, mut server) = tokio::io::duplex(1024);
+
+        client
+            .write_all(b"CONNECT [::1]:8443 HTTP/1.1\r\nHost: [::1]:8443\r\n\r\nearly")
+            .await?;
+
+        assert_eq!(
+            ProxyRequest {
+                command: ProxyCommand::HttpConnect,
+                target: IpOrHost::Ip("[::1]:8443".parse()?),
+                early_data: b"early".to_vec(),
+            },
+            read_proxy_request(&mut server).await?
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn http_request_other_than_connect_should_be_rejected() -> anyhow::Result<()> {
+        let (mut client, mut server) = tokio::io::duplex(1024);
+
+        client.write_all(b"GET http://example.com/ HTTP/1.1\r\n\r\n").await?;
+        assert!(read_proxy_request(&mut server).await.is_err());
+
+        drop(server);
+        let mut reply = String::new();
+        client.read_to_string(&mut reply).await?;
+        assert!(reply.starts_with("HTTP/1.1 405"), "unexpected reply: {reply}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn socks5_udp_datagram_should_roundtrip() -> anyhow::Result<()> {
+        for target in [
+            IpOrHost::Ip("10.0.0.1:53".parse()?),
+            IpOrHost::Ip("[2001:db8::1]:53".parse()?),
+            IpOrHost::Dns("dns.example".into(), 53),
+        ] {
+            let mut datagram = socks5_udp_header(&target);
+            datagram.extend_from_slice(b"payload");
+
+            let (decoded, data) = decode_socks5_udp_datagram(&datagram).context("must decode")?;
+            assert_eq!(target, decoded);
+            assert_eq!(b"payload", data);
+        }
+
+        // Fragmented datagrams are not supported
+        let mut datagram = socks5_udp_header(&IpOrHost::Dns("dns.example".into(), 53));
+        datagram[2] = 1;
+        assert!(decode_socks5_udp_datagram(&datagram).is_none());
+
+        Ok(())
+    }
+
+    fn loopback_session() -> anyhow::Result<HoprSession> {
+        let peer: Address = "0x5112D584a1C72Fc250176B57aEba5fFbbB287D8F".parse()?;
+        Ok(HoprSession::new(
+            HoprPseudonym::random(),
+            DestinationRouting::forward_only(peer, RoutingOptions::IntermediatePath(Default::default())),
+            HoprSessionConfig::default(),
+            crate::tests::loopback_transport(),
+            None,
+        )?)
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn socks5_udp_relay_should_forward_datagrams_over_the_session() -> anyhow::Result<()> {
+        let relay = UdpSocket::bind("127.0.0.1:0").await?;
+        let client = UdpSocket::bind("127.0.0.1:0").await?;
+        let relay_addr = relay.local_addr()?;
+        let client_addr = client.local_addr()?;
+        let target = IpOrHost::Dns("dns.example".into(), 53);
+
+        let first = (target.clone(), b"first".to_vec());
+        let jh = tokio::task::spawn(async move {
+            relay_socks5_udp(
+                &relay,
+                client_addr,
+                first,
+                |_| futures::future::ready(loopback_session().ok().map(|session| (*session.id(), session))),
+                |_| {},
+            )
+            .await
+        });
+
+        let mut buf = vec![0u8; HOPR_UDP_BUFFER_SIZE];
+        for expected in [b"first".as_slice(), b"second".as_slice()] {
+            if expected != b"first" {
+                let mut datagram = socks5_udp_header(&target);
+                datagram.extend_from_slice(expected);
+                client.send_to(&datagram, relay_addr).await?;
+            }
+
+            let (len, from) = client
+                .recv_from(&mut buf)
+                .timeout(futures_time::time::Duration::from_secs(2))
+                .await??;
+            assert_eq!(relay_addr, from);
+
+            // The loopback transport sends the data back, wrapped with the SOCKS5 UDP header of the target
+            let (decoded, data) = decode_socks5_udp_datagram(&buf[..len]).context("must decode")?;
+            assert_eq!(target, decoded);
+            assert_eq!(expected, data);
+        }
+
+        jh.abort();
+        Ok(())
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn socks5_udp_relay_should_open_a_session_per_target() -> anyhow::Result<()> {
+        let relay = UdpSocket::bind("127.0.0.1:0").await?;
+        let client = UdpSocket::bind("127.0.0.1:0").await?;
+        let relay_addr = relay.local_addr()?;
+        let client_addr = client.local_addr()?;
+        let target_1 = IpOrHost::Dns("dns.example".into(), 53);
+        let target_2 = IpOrHost::Ip("10.0.0.1:123".parse()?);
+
+        let opened = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let opened_clone = opened.clone();
+        let first = (target_1.clone(), b"first".to_vec());
+        let jh = tokio::task::spawn(async move {
+            relay_socks5_udp(
+                &relay,
+                client_addr,
+                first,
+                |target| {
+                    opened_clone.lock().push(target);
+                    futures::future::ready(loopback_session().ok().map(|session| (*session.id(), session)))
+                },
+                |_| {},
+            )
+            .await
+        });
+
+        let mut buf = vec![0u8; HOPR_UDP_BUFFER_SIZE];
+        for (target, expected) in [
+            (&target_1, b"first".as_slice()),
+            (&target_2, b"second".as_slice()),
+            (&target_1, b"third".as_slice()),
+            (&target_2, b"fourth".as_slice()),
+        ] {
+            if expected != b"first" {
+                let mut datagram = socks5_udp_header(target);
+                datagram.extend_from_slice(expected);
+                client.send_to(&datagram, relay_addr).await?;
+            }
+
+            let (len, _) = client
+                .recv_from(&mut buf)
+                .timeout(futures_time::time::Duration::from_secs(2))
+                .await??;
+
+            // Each reply comes from the Session of the target the datagram was sent to
+            let (decoded, data) = decode_socks5_udp_datagram(&buf[..len]).context("must decode")?;
+            assert_eq!(target, &decoded);
+            assert_eq!(expected, data);
+        }
+
+        assert_eq!(vec![target_1, target_2], *opened.lock());
+
+        jh.abort();
+        Ok(())
+    }
+
+    /// Opens Sessions over the loopback transport, which echoes all data back.
+    #[derive(Clone)]
+    struct LoopbackSessionFactory;
+
+    #[async_trait::async_trait]
+    impl SessionFactory for LoopbackSessionFactory {
+        type Cfg = ();
+
+        async fn create_session(
+            &self,
+            _: Address,
+            _: SessionTarget,
+            _: Self::Cfg,
+        ) -> anyhow::Result<(HoprSession, HoprSessionConfigurator)> {
+            let session = loopback_session()?;
+            let configurator = HoprSessionConfigurator::detached(*session.id());
+            Ok((session, configurator))
+        }
+
+        fn routing_from_cfg(&self, _: &Self::Cfg) -> anyhow::Result<(crate::Routing, crate::Routing)> {
+            Ok(Default::default())
+        }
+
+        fn listener_limits(
+            &self,
+            _: &Self::Cfg,
+        ) -> (
+            Option<human_bandwidth::re::bandwidth::Bandwidth>,
+            Option<bytesize::ByteSize>,
+        ) {
+            (None, None)
+        }
+
+        fn session_idle_timeout(&self) -> Option<Duration> {
+            None
+        }
+    }
+
+    async fn bind_loopback_proxy() -> anyhow::Result<(SocketAddr, Arc<ListenerJoinHandles>)> {
+        let open_listeners = Arc::new(ListenerJoinHandles::default());
+        let (bound_addr, ..) = create_proxy_client_binding(
+            "127.0.0.1:0".parse()?,
+            None,
+            LoopbackSessionFactory,
+            open_listeners.clone(),
+            Address::default(),
+            (),
+            None,
+            None,
+        )
+        .await?;
+        Ok((bound_addr, open_listeners))
+    }
+
+    async fn assert_echoed_through(stream: &mut TcpStream, data: &[u8]) -> anyhow::Result<()> {
+        stream.write_all(data).await?;
+        let mut buf = vec![0u8; data.len()];
+        stream
+            .read_exact(&mut buf)
+            .timeout(futures_time::time::Duration::from_secs(2))
+            .await??;
+        assert_eq!(data, buf);
+        Ok(())
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn proxy_listener_should_relay_socks5_connect_over_a_session() -> anyhow::Result<()> {
+        let (bound_addr, open_listeners) = bind_loopback_proxy().await?;
+        let mut stream = TcpStream::connect(bound_addr).await?;
+
+        stream.write_all(&[SOCKS_VERSION, 1, SOCKS_AUTH_NONE]).await?;
+        let mut method = [0u8; 2];
+        stream.read_exact(&mut method).await?;
+        assert_eq!([SOCKS_VERSION, SOCKS_AUTH_NONE], method);
+
+        let mut request = vec![SOCKS_VERSION, SOCKS_CMD_CONNECT, 0x00];
+        encode_socks5_address(&IpOrHost::Dns("example.com".into(), 443), &mut request);
+        stream.write_all(&request).await?;
+
+        let mut reply = [0u8; 10];
+        stream
+            .read_exact(&mut reply)
+            .timeout(futures_time::time::Duration::from_secs(2))
+            .await??;
+        assert_eq!(SocksReply::Succeeded as u8, reply[1]);
+
+        assert_echoed_through(&mut stream, b"hello socks").await?;
+
+        let listener = open_listeners
+            .0
+            .get(&ListenerId(IpProtocol::TCP, bound_addr))
+            .context("listener must be stored")?;
+        assert_eq!(SessionTargetSpec::Proxy, listener.target);
+        assert_eq!(1, listener.clients.len());
+
+        Ok(())
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn proxy_listener_should_relay_http_connect_over_a_session() -> anyhow::Result<()> {
+        let (bound_addr, open_listeners) = bind_loopback_proxy().await?;
+        let mut stream = TcpStream::connect(bound_addr).await?;
+
+        stream
+            .write_all(b"CONNECT example.com:443 HTTP/1.1\r\nHost: example.com:443\r\n\r\nearly")
+            .await?;
+
+        let expected_reply = b"HTTP/1.1 200 Connection established\r\n\r\n";
+        let mut reply = vec![0u8; expected_reply.len()];
+        stream
+            .read_exact(&mut reply)
+            .timeout(futures_time::time::Duration::from_secs(2))
+            .await??;
+        assert_eq!(expected_reply.as_slice(), reply);
+
+        // The early data of the request is forwarded over the Session too
+        let mut early = [0u8; 5];
+        stream
+            .read_exact(&mut early)
+            .timeout(futures_time::time::Duration::from_secs(2))
+            .await??;
+        assert_eq!(b"early", &early);
+
+        assert_echoed_through(&mut stream, b"hello http").await?;
+
+        // Once the client disconnects, it is no longer accounted at the listener
+        drop(stream);
+        let listener_id = ListenerId(IpProtocol::TCP, bound_addr);
+        for _ in 0..20 {
+            if open_listeners.0.get(&listener_id).is_some_and(|l| l.clients.is_empty()) {
+                return Ok(());
+            }
+            futures_time::task::sleep(futures_time::time::Duration::from_millis(50)).await;
+        }
+        anyhow::bail!("proxy client has not been removed from the listener")
+    }
+}